@@ -2,8 +2,6 @@
 //! Simple example that shows how to get a window up and running,
 //! with some basic event callbacks
 
-// We use block_on as Renderer creation requires async, but our app isn't configured to use async.
-use futures::executor::block_on;
 use rusty_gui::{components::{Button, Label}, gui::{GUI}, layout::Layout, rendering::{Renderer, ScreenMode, Transform, WindowBuilder}};
 use winit::event::{ElementState, Event};
 
@@ -38,7 +36,7 @@ fn _from_scratch(){
 
     window.set_event_handler(Box::new(event_callback_handler));
 
-    let renderer = block_on(Renderer::new(&window.window));
+    let renderer = Renderer::new_blocking(&window.window);
 
     let mut gui = GUI::new(window, renderer, wgpu::Color::WHITE);
 
@@ -0,0 +1,15 @@
+#version 450
+layout (location = 0) in vec2 v_tex_coords;
+
+layout(location = 0) out vec4 f_color;
+
+vec3 hsv_to_rgb(vec3 c) {
+    vec3 p = abs(fract(c.xxx + vec3(1.0, 2.0 / 3.0, 1.0 / 3.0)) * 6.0 - 3.0);
+    return c.z * mix(vec3(1.0), clamp(p - 1.0, 0.0, 1.0), c.y);
+}
+
+void main() {
+    // Hue goes top to bottom, fully saturated
+    vec3 rgb = hsv_to_rgb(vec3(v_tex_coords.y, 1.0, 1.0));
+    f_color = vec4(rgb, 1.0);
+}
@@ -0,0 +1,22 @@
+#version 450
+layout (location = 0) in vec2 v_tex_coords;
+
+layout(location = 0) out vec4 f_color;
+
+// The hue is passed in the red channel of the tint
+layout(set=2, binding=0)
+uniform Tint {
+    vec4 color;
+};
+
+vec3 hsv_to_rgb(vec3 c) {
+    vec3 p = abs(fract(c.xxx + vec3(1.0, 2.0 / 3.0, 1.0 / 3.0)) * 6.0 - 3.0);
+    return c.z * mix(vec3(1.0), clamp(p - 1.0, 0.0, 1.0), c.y);
+}
+
+void main() {
+    // Saturation goes left to right, and value top to bottom
+    vec3 rgb = hsv_to_rgb(vec3(color.r, v_tex_coords.x, 1.0 - v_tex_coords.y));
+    // Drawn the same way a quad tinted with the picked colour would be
+    f_color = vec4(rgb, 1.0);
+}
@@ -0,0 +1,23 @@
+#version 450
+layout(location=0) in vec2 v_tex_coords;
+layout(location=1) in vec4 v_color;
+
+layout(location=0) out vec4 f_color;
+
+layout(set=0, binding=0)
+uniform Globals {
+    mat4 transform;
+    float gamma;
+};
+
+layout(set=0, binding=1) uniform texture2D t_glyphs;
+layout(set=0, binding=2) uniform sampler s_glyphs;
+
+void main() {
+    // How much of the pixel the glyph covers, bent by the gamma curve
+    float coverage = pow(texture(sampler2D(t_glyphs, s_glyphs), v_tex_coords).r, 1.0 / gamma);
+    if (coverage <= 0.0) {
+        discard;
+    }
+    f_color = vec4(v_color.rgb, v_color.a * coverage);
+}
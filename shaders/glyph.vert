@@ -0,0 +1,41 @@
+#version 450
+
+// One instance per glyph, drawn as a strip of 4 vertices
+layout(location=0) in vec3 left_top;
+layout(location=1) in vec2 right_bottom;
+layout(location=2) in vec2 tex_left_top;
+layout(location=3) in vec2 tex_right_bottom;
+layout(location=4) in vec4 color;
+
+layout(location=0) out vec2 v_tex_coords;
+layout(location=1) out vec4 v_color;
+
+layout(set=0, binding=0)
+uniform Globals {
+    mat4 transform;
+    float gamma;
+};
+
+void main() {
+    vec2 pos = vec2(0.0);
+    switch (gl_VertexIndex) {
+        case 0:
+            pos = vec2(left_top.x, right_bottom.y);
+            v_tex_coords = vec2(tex_left_top.x, tex_right_bottom.y);
+            break;
+        case 1:
+            pos = right_bottom;
+            v_tex_coords = tex_right_bottom;
+            break;
+        case 2:
+            pos = left_top.xy;
+            v_tex_coords = tex_left_top;
+            break;
+        default:
+            pos = vec2(right_bottom.x, left_top.y);
+            v_tex_coords = vec2(tex_right_bottom.x, tex_left_top.y);
+            break;
+    }
+    v_color = color;
+    gl_Position = transform * vec4(pos, left_top.z, 1.0);
+}
@@ -0,0 +1,25 @@
+#version 450
+layout(location=0) in vec2 v_tex_coords;
+layout(location=1) in vec4 v_color;
+
+layout(location=0) out vec4 f_color;
+
+layout(set=0, binding=0)
+uniform Globals {
+    mat4 transform;
+    float gamma;
+};
+
+layout(set=0, binding=1) uniform texture2D t_glyphs;
+layout(set=0, binding=2) uniform sampler s_glyphs;
+
+void main() {
+    // The edge of the glyph is where the distance is a half, softened over about a pixel on screen
+    float distance = texture(sampler2D(t_glyphs, s_glyphs), v_tex_coords).r;
+    float width = fwidth(distance) * 0.7;
+    float coverage = pow(smoothstep(0.5 - width, 0.5 + width, distance), 1.0 / gamma);
+    if (coverage <= 0.0) {
+        discard;
+    }
+    f_color = vec4(v_color.rgb, v_color.a * coverage);
+}
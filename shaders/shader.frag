@@ -3,6 +3,14 @@ layout (location = 0) in vec2 v_tex_coords;
 
 layout(location = 0) out vec4 f_color;
 
+layout(set=2, binding=0)
+uniform Tint {
+    vec4 color;
+};
+
+layout(set=3, binding=0) uniform texture2D t_image;
+layout(set=3, binding=1) uniform sampler s_image;
+
 void main() {
-    f_color = vec4(1,0,0,1);
+    f_color = color * texture(sampler2D(t_image, s_image), v_tex_coords);
 }
\ No newline at end of file
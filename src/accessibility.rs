@@ -0,0 +1,90 @@
+//! This module lets the application announce things to screen reader users, like "File saved" once a
+//! background save finishes. Announcements go to whichever `Announcer` is set - the speech command that comes with
+//! the platform (`SpeechCommand`), or your own, for example one forwarding to an AccessKit adapter.
+
+use std::{process::Command, sync::Mutex};
+
+/// How urgent an announcement is
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Politeness{
+    /// Wait for the screen reader to finish what it's saying
+    Polite,
+    /// Interrupt the screen reader, for errors and other things that can't wait
+    Assertive,
+}
+
+/// # Announcer
+///
+/// Something that can read announcements out. `announce` can be called from any thread, so announcers have to
+/// be `Send`.
+pub trait Announcer{
+    fn announce(&mut self, message: &str, politeness: Politeness);
+}
+
+static ANNOUNCER: Mutex<Option<Box<dyn Announcer + Send>>> = Mutex::new(None);
+
+/// Set where announcements go, or `None` to drop them. Nothing is announced until this is called.
+pub fn set_announcer(announcer: Option<Box<dyn Announcer + Send>>){
+    if let Ok(mut current) = ANNOUNCER.lock(){
+        *current = announcer;
+    }
+}
+
+/// Announce something politely, after whatever the screen reader is currently saying
+pub fn announce(message: &str){
+    announce_with(message, Politeness::Polite);
+}
+
+/// Announce something straight away, interrupting the screen reader
+pub fn announce_assertive(message: &str){
+    announce_with(message, Politeness::Assertive);
+}
+
+pub fn announce_with(message: &str, politeness: Politeness){
+    if message.is_empty(){
+        return;
+    }
+    if let Ok(mut announcer) = ANNOUNCER.lock(){
+        if let Some(announcer) = announcer.as_mut(){
+            announcer.announce(message, politeness);
+        }
+    }
+}
+
+/// # SpeechCommand
+///
+/// Reads announcements out with the speech command that comes with the platform - `spd-say` (speech dispatcher,
+/// which Orca also speaks through) on Linux, `say` on macOS, and the .NET speech synthesizer on Windows.
+/// The command runs in the background, and if it isn't installed the announcement is dropped.
+#[derive(Default)]
+pub struct SpeechCommand;
+
+impl SpeechCommand{
+    pub fn new() -> Self{
+        Self
+    }
+}
+
+impl Announcer for SpeechCommand{
+    fn announce(&mut self, message: &str, politeness: Politeness){
+        let mut command = if cfg!(target_os = "windows"){
+            let mut command = Command::new("powershell");
+            let script = format!("Add-Type -AssemblyName System.Speech; (New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak('{}')", message.replace('\'', "''"));
+            command.args(["-NoProfile", "-Command", &script]);
+            command
+        }else if cfg!(target_os = "macos"){
+            let mut command = Command::new("say");
+            command.arg(message);
+            command
+        }else{
+            let mut command = Command::new("spd-say");
+            let priority = match politeness{
+                Politeness::Polite => "message",
+                Politeness::Assertive => "important",
+            };
+            command.args(["--priority", priority, "--", message]);
+            command
+        };
+        let _ = command.spawn();
+    }
+}
@@ -0,0 +1,284 @@
+//! This module contains the `AchievementPopup` component, which slides a banner down from the top of the screen
+//! when an achievement is unlocked, showing unlocks one after the other.
+
+use std::{any::Any, collections::VecDeque, rc::Rc};
+
+use wgpu_glyph::VerticalAlign;
+
+use crate::rendering::{Quad, Renderer, TextBrush, Texture};
+
+use super::{EventGUIComponent, Label, TextGUIComponent};
+
+// The space between the popup and the top of the screen, and around its contents
+const MARGIN: f32 = 16.0;
+const PADDING: f32 = 12.0;
+// How wide the coloured stripe down the side of the popup is
+const STRIPE_WIDTH: f32 = 4.0;
+// How long sliding in and out takes, in seconds
+const SLIDE_TIME: f32 = 0.35;
+
+type SoundHook = Box<dyn Fn(&Achievement)>;
+
+/// How far a popup has slid in, from 0 (hidden above the screen) to 1 (all the way down), `age` seconds after it
+/// started sliding in. It stays down for `duration` seconds between sliding in and out over `slide_time` seconds.
+pub(crate) fn achievement_slide(age: f32, duration: f32, slide_time: f32) -> f32{
+    if slide_time <= 0.0{
+        return if age >= 0.0 && age < duration { 1.0 } else { 0.0 };
+    }
+    (age / slide_time).min((duration + slide_time * 2.0 - age) / slide_time).clamp(0.0, 1.0)
+}
+
+/// An unlocked achievement, to show with `AchievementPopup::unlock`
+#[derive(Clone)]
+pub struct Achievement{
+    pub title: String,
+    pub description: String,
+    pub icon: Option<Rc<Texture>>,
+}
+
+impl Achievement{
+    pub fn new(title: &str, description: &str) -> Self{
+        Self{
+            title: title.to_string(),
+            description: description.to_string(),
+            icon: None,
+        }
+    }
+
+    /// Show a texture (like the achievement's badge) at the left of the popup
+    pub fn with_icon(mut self, icon: Rc<Texture>) -> Self{
+        self.icon = Some(icon);
+        self
+    }
+}
+
+/// # AchievementPopup
+///
+/// A banner that slides down at the top middle of the screen when an achievement is unlocked, with the
+/// achievement's icon, title and description, then slides back up a few seconds later. Unlocks that come in while
+/// one is showing wait in a queue, and are shown in order once it's gone, so they never overlap.
+///
+/// The sound hook (see `set_sound_hook`) runs as each popup starts sliding in, for playing a jingle with whatever
+/// audio library the game uses. The popup belongs in the overlay layer (`Layout::add_overlay_component`), and never
+/// takes input.
+pub struct AchievementPopup{
+    queue: VecDeque<Achievement>,
+    current: Option<Achievement>,
+    age: f32, // seconds since the current popup started sliding in
+    duration: f32,
+    size: [f32; 2],
+    sound_hook: Option<SoundHook>,
+
+    background: Quad,
+    stripe: Quad,
+    icon: Quad,
+    title: Label,
+    description: Label,
+    enabled: bool,
+}
+
+impl AchievementPopup{
+    /// Create a popup with nothing to show, that shows each unlock for 4 seconds
+    pub fn new(renderer: &Renderer) -> Self{
+        let mut title = Label::new("", 18.0, [0.0, 0.0]);
+        title.align_vertical(VerticalAlign::Bottom);
+        let mut description = Label::new("", 14.0, [0.0, 0.0]);
+        description.align_vertical(VerticalAlign::Top);
+
+        Self{
+            queue: VecDeque::new(),
+            current: None,
+            age: 0.0,
+            duration: 4.0,
+            size: [340.0, 72.0],
+            sound_hook: None,
+
+            background: Quad::new([0.0, 0.0], [0.0, 0.0], [0.97, 0.97, 0.97, 1.0], &renderer.device),
+            stripe: Quad::new([0.0, 0.0], [0.0, 0.0], [1.0, 0.76, 0.1, 1.0], &renderer.device),
+            icon: Quad::new([0.0, 0.0], [0.0, 0.0], [1.0, 1.0, 1.0, 1.0], &renderer.device),
+            title,
+            description,
+            enabled: true,
+        }
+    }
+
+    /// Show an achievement, after any that are already showing or waiting
+    pub fn unlock(&mut self, achievement: Achievement){
+        self.queue.push_back(achievement);
+        if self.current.is_none(){
+            self.show_next();
+        }
+    }
+
+    /// The achievement being shown, if there is one
+    pub fn get_current(&self) -> Option<&Achievement>{
+        self.current.as_ref()
+    }
+
+    /// How many achievements are waiting for the one being shown to go
+    pub fn queued_len(&self) -> usize{
+        self.queue.len()
+    }
+
+    /// Hide the popup and forget the waiting achievements
+    pub fn clear(&mut self){
+        self.queue.clear();
+        self.current = None;
+    }
+
+    /// Set how many seconds each popup stays down, not counting sliding in and out
+    pub fn set_duration(&mut self, duration: f32){
+        self.duration = duration.max(0.0);
+    }
+
+    /// Set the function to run with each achievement as its popup starts sliding in, like to play a sound
+    pub fn set_sound_hook(&mut self, hook: Option<SoundHook>){
+        self.sound_hook = hook;
+    }
+
+    /// Set the size of the popup, in pixels. It's 340 by 72 by default.
+    pub fn set_size(&mut self, size: [f32; 2]){
+        self.size = size;
+    }
+
+    /// Set the colours of the popup's background and of the stripe down its left side
+    pub fn set_colors(&mut self, background: [f32; 4], stripe: [f32; 4]){
+        self.background.set_color(background);
+        self.stripe.set_color(stripe);
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+    }
+
+    fn show_next(&mut self){
+        self.current = self.queue.pop_front();
+        self.age = 0.0;
+        if let Some(achievement) = &self.current{
+            self.title.set_text(achievement.title.as_str());
+            self.description.set_text(achievement.description.as_str());
+            self.icon.set_texture(achievement.icon.clone());
+            if let Some(hook) = &self.sound_hook{
+                hook(achievement);
+            }
+        }
+    }
+
+    // How big the icon is, and how far in from the left the text starts
+    fn icon_size(&self) -> f32{
+        match self.current.as_ref().and_then(|achievement| achievement.icon.as_ref()){
+            Some(_) => self.size[1] - PADDING * 2.0,
+            None => 0.0,
+        }
+    }
+}
+
+impl EventGUIComponent for AchievementPopup{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if !self.enabled || self.current.is_none(){
+            return;
+        }
+        self.background.render(render_pass);
+        self.stripe.render(render_pass);
+        if self.icon.get_texture().is_some(){
+            self.icon.render(render_pass);
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if self.enabled && self.current.is_some(){
+            TextGUIComponent::render_text(&self.title, brush);
+            TextGUIComponent::render_text(&self.description, brush);
+        }
+    }
+
+    fn handle_event_callback(&mut self, _event: &winit::event::Event<()>, _window: &winit::window::Window){}
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.background.get_pos()
+    }
+
+    fn update(&mut self, dt: f32){
+        if self.current.is_none(){
+            return;
+        }
+        self.age += dt;
+        if self.age >= self.duration + SLIDE_TIME * 2.0{
+            self.show_next();
+        }
+    }
+
+    fn is_animating(&self) -> bool{
+        self.current.is_some()
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        if self.current.is_none(){
+            return;
+        }
+        // Ease out while sliding in, so the popup comes to a gentle stop
+        let slide = achievement_slide(self.age, self.duration, SLIDE_TIME);
+        let shown = 1.0 - (1.0 - slide).powi(3);
+        let [width, height] = self.size;
+        let y = -(screen_dim.1 as f32) / 2.0 + MARGIN + height / 2.0 - (1.0 - shown) * (height + MARGIN);
+        let left = -width / 2.0;
+
+        self.background.set_pos([0.0, y]);
+        self.background.set_size(self.size);
+        self.stripe.set_pos([left + STRIPE_WIDTH / 2.0, y]);
+        self.stripe.set_size([STRIPE_WIDTH, height]);
+        let icon_size = self.icon_size();
+        self.icon.set_pos([left + STRIPE_WIDTH + PADDING + icon_size / 2.0, y]);
+        self.icon.set_size([icon_size, icon_size]);
+        self.background.prepare(queue, screen_dim);
+        self.stripe.prepare(queue, screen_dim);
+        self.icon.prepare(queue, screen_dim);
+
+        let text_left = left + STRIPE_WIDTH + PADDING + if icon_size > 0.0 { icon_size + PADDING } else { 0.0 };
+        self.title.set_pos([text_left, y - 2.0], screen_dim);
+        self.description.set_pos([text_left, y + 2.0], screen_dim);
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Test that achievement popups slide in, stay down for the duration, and slide back out
+    #[test]
+    fn achievement_slide_test(){
+        assert_eq!(achievement_slide(0.0, 4.0, 0.5), 0.0);
+        assert_eq!(achievement_slide(0.25, 4.0, 0.5), 0.5);
+        assert_eq!(achievement_slide(0.5, 4.0, 0.5), 1.0);
+        assert_eq!(achievement_slide(4.5, 4.0, 0.5), 1.0);
+        assert_eq!(achievement_slide(4.75, 4.0, 0.5), 0.5);
+        assert_eq!(achievement_slide(5.0, 4.0, 0.5), 0.0);
+        assert_eq!(achievement_slide(6.0, 4.0, 0.5), 0.0);
+
+        // Without sliding, it just shows for the duration
+        assert_eq!(achievement_slide(1.0, 4.0, 0.0), 1.0);
+        assert_eq!(achievement_slide(4.0, 4.0, 0.0), 0.0);
+    }
+}
@@ -0,0 +1,353 @@
+//! This module contains the `AnimatedImage` component, which plays the frames of a spritesheet or an animated GIF.
+//! GIFs get packed into a spritesheet when they're loaded, so both are one texture with the quad's UVs moved along.
+
+use std::{any::Any, io::Cursor, rc::Rc};
+
+use image::{AnimationDecoder, codecs::gif::GifDecoder};
+
+use crate::rendering::{Quad, Renderer, Texture};
+
+use super::{GUIComponent, Rect};
+
+// The biggest spritesheet a GIF gets packed into, which every GPU can load as a texture
+const MAX_SHEET_SIZE: u32 = 8192;
+// GIFs with shorter delays than this get played at the usual speed of 10 frames a second, like browsers do
+const MIN_GIF_DELAY: f32 = 0.02;
+const DEFAULT_GIF_DELAY: f32 = 0.1;
+
+/// The part of a spritesheet showing a frame, as an offset and a size in texture coordinates. Frames go left to
+/// right along each row of the `(columns, rows)` grid, then down to the next row.
+pub(crate) fn sprite_uv(frame: usize, grid: (u32, u32)) -> ([f32; 2], [f32; 2]){
+    let columns = grid.0.max(1) as usize;
+    let size = [1.0 / columns as f32, 1.0 / grid.1.max(1) as f32];
+    let column = frame % columns;
+    let row = frame / columns;
+    ([column as f32 * size[0], row as f32 * size[1]], size)
+}
+
+/// Move an animation on by `dt` seconds, from `elapsed` seconds into `frame`, where `durations` is how long each
+/// frame shows for. Gives back the new frame, how far into it the animation is, and whether it stopped on the last
+/// frame (which only happens when it isn't looping).
+pub(crate) fn advance_frame(frame: usize, elapsed: f32, dt: f32, durations: &[f32], looping: bool) -> (usize, f32, bool){
+    if durations.is_empty(){
+        return (0, 0.0, true);
+    }
+    let mut frame = frame.min(durations.len() - 1);
+    let mut elapsed = elapsed + dt;
+    let total: f32 = durations.iter().sum();
+    if total <= 0.0{
+        return (frame, 0.0, false);
+    }
+    // A long pause (like the window being minimised) doesn't need every loop played through
+    if looping && elapsed > total{
+        elapsed %= total;
+    }
+    while elapsed >= durations[frame]{
+        elapsed -= durations[frame];
+        if frame + 1 < durations.len(){
+            frame += 1;
+        }else if looping{
+            frame = 0;
+        }else{
+            return (frame, 0.0, true);
+        }
+    }
+    (frame, elapsed, false)
+}
+
+/// # SpriteSheet
+///
+/// The frames of an animation packed into a grid in one RGBA8 image, ready to be played by an `AnimatedImage`.
+/// Decoding one doesn't need the GPU, so it can be done on another thread.
+pub struct SpriteSheet{
+    pub pixels: Vec<u8>,
+    pub size: (u32, u32),
+    pub grid: (u32, u32), // columns and rows
+    pub durations: Vec<f32>, // how long each frame shows for, in seconds
+}
+
+impl SpriteSheet{
+    /// Load a spritesheet from the bytes of an image file, where the frames are laid out on a `(columns, rows)`
+    /// grid. `frame_count` can be less than the size of the grid, if the last row isn't full.
+    pub fn from_image(bytes: &[u8], grid: (u32, u32), frame_count: usize, fps: f32) -> Result<Self, &'static str>{
+        if grid.0 == 0 || grid.1 == 0 || frame_count == 0 || frame_count > (grid.0 * grid.1) as usize{
+            return Err("Error, the spritesheet grid doesn't fit the frame count!");
+        }
+        let image = image::load_from_memory(bytes).map_err(|_| "Error, failed to load spritesheet!")?.into_rgba8();
+        Ok(Self{
+            size: image.dimensions(),
+            pixels: image.into_raw(),
+            grid,
+            durations: vec![frame_duration(fps); frame_count],
+        })
+    }
+
+    /// Decode an animated GIF, keeping how long it shows each frame for
+    pub fn from_gif(bytes: &[u8]) -> Result<Self, &'static str>{
+        let decoder = GifDecoder::new(Cursor::new(bytes)).map_err(|_| "Error, failed to read GIF!")?;
+        let frames = decoder.into_frames().collect_frames().map_err(|_| "Error, failed to decode GIF frames!")?;
+        let first = frames.first().ok_or("Error, GIF has no frames!")?;
+        let frame_size = first.buffer().dimensions();
+
+        // As close to square as it gets, to keep the sheet under the size limit for as long as possible
+        let columns = (frames.len() as f32).sqrt().ceil() as u32;
+        let rows = (frames.len() as u32).div_ceil(columns);
+        let size = (frame_size.0 * columns, frame_size.1 * rows);
+        if size.0 > MAX_SHEET_SIZE || size.1 > MAX_SHEET_SIZE{
+            return Err("Error, GIF has too many frames to fit in a texture!");
+        }
+
+        let mut pixels = vec![0u8; (4 * size.0 * size.1) as usize];
+        let row_bytes = (4 * frame_size.0) as usize;
+        let mut durations = Vec::with_capacity(frames.len());
+        for (i, frame) in frames.iter().enumerate(){
+            // The decoder draws every frame onto the full canvas, so they're all the same size
+            let left = (i as u32 % columns * frame_size.0) as usize;
+            let top = (i as u32 / columns * frame_size.1) as usize;
+            for (y, row) in frame.buffer().as_raw().chunks_exact(row_bytes).enumerate(){
+                let start = ((top + y) * size.0 as usize + left) * 4;
+                pixels[start..start + row_bytes].copy_from_slice(row);
+            }
+
+            let (numerator, denominator) = frame.delay().numer_denom_ms();
+            let delay = numerator as f32 / denominator.max(1) as f32 / 1000.0;
+            durations.push(if delay < MIN_GIF_DELAY { DEFAULT_GIF_DELAY } else { delay });
+        }
+
+        Ok(Self{
+            pixels,
+            size,
+            grid: (columns, rows),
+            durations,
+        })
+    }
+
+    pub fn get_frame_count(&self) -> usize{
+        self.durations.len()
+    }
+}
+
+// How long a frame shows for at some frame rate. Rates of 0 or less show each frame for a very long time.
+fn frame_duration(fps: f32) -> f32{
+    1.0 / fps.max(f32::EPSILON)
+}
+
+/// # AnimatedImage
+///
+/// Plays an animation from a `SpriteSheet`, moving on a frame as time passes in `update`. Like images, the
+/// position is the centre of the animation from the centre of the screen, and the size is in pixels.
+///
+/// Animations loop by default, and start playing straight away. Spritesheets play at the frame rate they're loaded
+/// with, and GIFs at their own speed, until `set_fps` gives every frame the same length.
+pub struct AnimatedImage{
+    quad: Quad,
+    grid: (u32, u32),
+    durations: Vec<f32>,
+    frame: usize,
+    elapsed: f32, // seconds since the current frame came up
+    playing: bool,
+    looping: bool,
+    enabled: bool,
+}
+
+impl AnimatedImage{
+    /// Create an animated image from a spritesheet
+    pub fn new(sheet: &SpriteSheet, pos: [f32; 2], size: [f32; 2], renderer: &Renderer) -> Self{
+        let texture = Texture::from_rgba(&sheet.pixels, sheet.size, "AnimatedImage", &renderer.device, &renderer.queue);
+        let mut quad = Quad::new(pos, size, [1.0, 1.0, 1.0, 1.0], &renderer.device);
+        quad.set_texture(Some(Rc::new(texture)));
+        let mut image = Self{
+            quad,
+            grid: sheet.grid,
+            durations: sheet.durations.clone(),
+            frame: 0,
+            elapsed: 0.0,
+            playing: true,
+            looping: true,
+            enabled: true,
+        };
+        image.set_frame(0);
+        image
+    }
+
+    /// Load a spritesheet from the bytes of an image file and play it (see `SpriteSheet::from_image`)
+    pub fn from_spritesheet(bytes: &[u8], grid: (u32, u32), frame_count: usize, fps: f32, pos: [f32; 2], size: [f32; 2], renderer: &Renderer) -> Result<Self, &'static str>{
+        let sheet = SpriteSheet::from_image(bytes, grid, frame_count, fps)?;
+        Ok(AnimatedImage::new(&sheet, pos, size, renderer))
+    }
+
+    /// Load an animated GIF from its bytes and play it
+    pub fn from_gif(bytes: &[u8], pos: [f32; 2], size: [f32; 2], renderer: &Renderer) -> Result<Self, &'static str>{
+        let sheet = SpriteSheet::from_gif(bytes)?;
+        Ok(AnimatedImage::new(&sheet, pos, size, renderer))
+    }
+
+    /// Play every frame for the same length of time, `fps` frames a second
+    pub fn set_fps(&mut self, fps: f32){
+        let duration = frame_duration(fps);
+        for frame_duration in self.durations.iter_mut(){
+            *frame_duration = duration;
+        }
+        self.elapsed = self.elapsed.min(duration);
+    }
+
+    /// Show a frame (kept inside the frame count), from its start
+    pub fn set_frame(&mut self, frame: usize){
+        self.frame = frame.min(self.durations.len().saturating_sub(1));
+        self.elapsed = 0.0;
+        let (offset, size) = sprite_uv(self.frame, self.grid);
+        self.quad.set_uv(offset, size);
+    }
+
+    pub fn get_frame(&self) -> usize{
+        self.frame
+    }
+
+    pub fn get_frame_count(&self) -> usize{
+        self.durations.len()
+    }
+
+    /// Carry on playing. An animation that stopped on its last frame starts again from the first.
+    pub fn play(&mut self){
+        if !self.looping && self.frame + 1 >= self.durations.len(){
+            self.set_frame(0);
+        }
+        self.playing = true;
+    }
+
+    /// Stop on the current frame
+    pub fn pause(&mut self){
+        self.playing = false;
+    }
+
+    pub fn is_playing(&self) -> bool{
+        self.playing
+    }
+
+    /// Set whether the animation goes back to the first frame after the last, or stops there
+    pub fn set_looping(&mut self, looping: bool){
+        self.looping = looping;
+    }
+
+    pub fn is_looping(&self) -> bool{
+        self.looping
+    }
+
+    pub fn set_pos(&mut self, pos: [f32; 2]){
+        self.quad.set_pos(pos);
+    }
+
+    /// Set how big the animation is drawn, in pixels
+    pub fn set_size(&mut self, size: [f32; 2]){
+        self.quad.set_size(size);
+    }
+
+    pub fn get_size(&self) -> [f32; 2]{
+        self.quad.get_size()
+    }
+
+    /// Set a colour to multiply the frames by. White leaves them as they are.
+    pub fn set_tint(&mut self, color: [f32; 4]){
+        self.quad.set_color(color);
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+    }
+}
+
+impl GUIComponent for AnimatedImage{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.enabled{
+            self.quad.render(render_pass);
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.quad.get_pos()
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(Rect::new(self.quad.get_pos(), self.quad.get_size()))
+    }
+
+    fn update(&mut self, dt: f32){
+        if !self.enabled || !self.playing || self.durations.len() < 2{
+            return;
+        }
+        let (frame, elapsed, finished) = advance_frame(self.frame, self.elapsed, dt, &self.durations, self.looping);
+        if frame != self.frame{
+            self.set_frame(frame);
+        }
+        self.elapsed = elapsed;
+        self.playing = !finished;
+    }
+
+    /// Keep the main loop ticking while there are frames to move on to
+    fn is_animating(&self) -> bool{
+        self.enabled && self.playing && self.durations.len() > 1
+    }
+
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{
+        Some(self.quad.get_size())
+    }
+
+    /// Like images, animations keep their size, centred in the rectangle
+    fn arrange(&mut self, rect: Rect){
+        self.quad.set_pos(rect.pos);
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.quad.prepare(queue, screen_dim);
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Test that frames go along the rows of the grid, then down
+    #[test]
+    fn sprite_uv_test(){
+        assert_eq!(sprite_uv(0, (4, 2)), ([0.0, 0.0], [0.25, 0.5]));
+        assert_eq!(sprite_uv(3, (4, 2)), ([0.75, 0.0], [0.25, 0.5]));
+        assert_eq!(sprite_uv(5, (4, 2)), ([0.25, 0.5], [0.25, 0.5]));
+        assert_eq!(sprite_uv(0, (1, 1)), ([0.0, 0.0], [1.0, 1.0]));
+    }
+
+    /// Test moving through frames of different lengths, looping and stopping at the end
+    #[test]
+    fn advance_frame_test(){
+        let durations = [0.5, 0.25, 0.25];
+        assert_eq!(advance_frame(0, 0.0, 0.25, &durations, true), (0, 0.25, false));
+        assert_eq!(advance_frame(0, 0.25, 0.375, &durations, true), (1, 0.125, false));
+        assert_eq!(advance_frame(0, 0.25, 0.5, &durations, true), (2, 0.0, false));
+        assert_eq!(advance_frame(2, 0.0, 0.25, &durations, true), (0, 0.0, false));
+        assert_eq!(advance_frame(2, 0.0, 0.25, &durations, false), (2, 0.0, true));
+        // A long gap only plays the part of the loop it ends in
+        assert_eq!(advance_frame(0, 0.0, 10.5, &durations, true), (1, 0.0, false));
+        assert_eq!(advance_frame(0, 0.0, 10.5, &durations, false), (2, 0.0, true));
+        assert_eq!(advance_frame(3, 0.0, 0.0, &[], true), (0, 0.0, true));
+    }
+}
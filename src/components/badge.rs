@@ -0,0 +1,255 @@
+//! This module contains the `Badge`, a small counter bubble the layout keeps at the corner of another component
+//! (see `Layout::set_badge`), for things like unread messages.
+
+use std::rc::Rc;
+
+use wgpu_glyph::{HorizontalAlign, VerticalAlign};
+
+use crate::rendering::{Quad, Renderer, TextBrush, Texture, measure_text};
+
+use super::{Rect, canvas::disc_pixels};
+
+// How many pixels across the texture for the rounded ends is
+const CAP_RESOLUTION: u32 = 32;
+// The space either side of the text, as a fraction of the text size
+const PADDING: f32 = 0.35;
+
+/// The corner of a component a badge sits on
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum BadgeCorner{
+    TopLeft,
+    #[default]
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// The text a badge shows for a count, which stops at `max` with a plus (so 150 with a max of 99 is "99+")
+pub(crate) fn badge_text(count: usize, max: usize) -> String{
+    if count > max{
+        format!("{}+", max)
+    }else{
+        count.to_string()
+    }
+}
+
+/// Where the centre of a badge goes for a component's bounds - right on the corner, moved by `offset` pixels
+pub(crate) fn badge_pos(bounds: Rect, corner: BadgeCorner, offset: [f32; 2]) -> [f32; 2]{
+    let half = [bounds.size[0] / 2.0, bounds.size[1] / 2.0];
+    let (x, y) = match corner{
+        BadgeCorner::TopLeft => (-half[0], -half[1]),
+        BadgeCorner::TopRight => (half[0], -half[1]),
+        BadgeCorner::BottomLeft => (-half[0], half[1]),
+        BadgeCorner::BottomRight => (half[0], half[1]),
+    };
+    [bounds.pos[0] + x + offset[0], bounds.pos[1] + y + offset[1]]
+}
+
+/// # Badge
+///
+/// A rounded bubble with a count in it. It isn't added to a layout like other components, but attached to one of
+/// its components with `Layout::set_badge`, and the layout moves it to the corner of that component's bounds
+/// every frame, so it follows the component around. Only components that return their bounds from `get_bounds`
+/// can have a badge.
+///
+/// A count of 0 hides the badge, unless `set_show_zero` says otherwise. The badge is hidden along with its
+/// component, too.
+pub struct Badge{
+    count: usize,
+    max: usize,
+    show_zero: bool,
+    text: String,
+    text_size: f32,
+    text_color: [f32; 4],
+    corner: BadgeCorner,
+    offset: [f32; 2],
+
+    body: Quad, // the middle of the bubble, between the rounded ends
+    caps: [Quad; 2],
+    size: [f32; 2],
+    measured: bool, // whether the size fits the text
+    screen_pos: [f32; 2], // the centre in window space, for the text
+    attached: bool, // whether the component it's on is there to be drawn on
+}
+
+impl Badge{
+    /// Create a red badge showing `count`, with text `text_size` pixels high
+    pub fn new(count: usize, text_size: f32, renderer: &Renderer) -> Self{
+        let disc = Rc::new(Texture::from_rgba(&disc_pixels(CAP_RESOLUTION), (CAP_RESOLUTION, CAP_RESOLUTION), "Badge", &renderer.device, &renderer.queue));
+        let color = [0.85, 0.15, 0.15, 1.0];
+        let cap = || {
+            let mut quad = Quad::new([0.0, 0.0], [0.0, 0.0], color, &renderer.device);
+            quad.set_texture(Some(disc.clone()));
+            quad
+        };
+        let height = text_size * (1.0 + PADDING * 2.0);
+        Self{
+            count,
+            max: 99,
+            show_zero: false,
+            text: badge_text(count, 99),
+            text_size,
+            text_color: [1.0, 1.0, 1.0, 1.0],
+            corner: BadgeCorner::default(),
+            offset: [0.0, 0.0],
+
+            body: Quad::new([0.0, 0.0], [0.0, 0.0], color, &renderer.device),
+            caps: [cap(), cap()],
+            size: [height, height],
+            measured: false,
+            screen_pos: [0.0, 0.0],
+            attached: false,
+        }
+    }
+
+    pub fn get_count(&self) -> usize{
+        self.count
+    }
+
+    pub fn set_count(&mut self, count: usize){
+        self.count = count;
+        self.update_text();
+    }
+
+    /// Set the biggest count shown in full. Counts above it show as the max with a plus, "99+" by default.
+    pub fn set_max(&mut self, max: usize){
+        self.max = max;
+        self.update_text();
+    }
+
+    /// Set whether the badge still shows with a count of 0
+    pub fn set_show_zero(&mut self, show_zero: bool){
+        self.show_zero = show_zero;
+    }
+
+    /// The text the badge is showing
+    pub fn get_text(&self) -> &str{
+        &self.text
+    }
+
+    /// Set which corner of the component the badge sits on. It's the top right by default.
+    pub fn set_corner(&mut self, corner: BadgeCorner){
+        self.corner = corner;
+    }
+
+    pub fn get_corner(&self) -> BadgeCorner{
+        self.corner
+    }
+
+    /// Move the badge away from the corner by some pixels, to tuck it into a rounded component for example
+    pub fn set_offset(&mut self, offset: [f32; 2]){
+        self.offset = offset;
+    }
+
+    pub fn set_colors(&mut self, background: [f32; 4], text: [f32; 4]){
+        self.body.set_color(background);
+        for cap in self.caps.iter_mut(){
+            cap.set_color(background);
+        }
+        self.text_color = text;
+    }
+
+    /// Whether the badge has anything to show (which doesn't mean its component is on screen)
+    pub fn is_visible(&self) -> bool{
+        self.count > 0 || self.show_zero
+    }
+
+    /// The bubble's rectangle, from the last time the layout placed it
+    pub fn get_rect(&self) -> Rect{
+        Rect::new(self.body.get_pos(), self.size)
+    }
+
+    fn update_text(&mut self){
+        let text = badge_text(self.count, self.max);
+        if text != self.text{
+            self.text = text;
+            self.measured = false;
+        }
+    }
+
+    // Move the bubble onto the corner of the component's bounds, or hide it with `None`
+    pub(crate) fn place(&mut self, bounds: Option<Rect>){
+        self.attached = bounds.is_some();
+        if let Some(bounds) = bounds{
+            let pos = badge_pos(bounds, self.corner, self.offset);
+            // The rounded ends are as wide as the bubble is tall, half of each sticking out past the body
+            let reach = (self.size[0] - self.size[1]) / 2.0;
+            self.body.set_pos(pos);
+            self.body.set_size([reach * 2.0, self.size[1]]);
+            self.caps[0].set_pos([pos[0] - reach, pos[1]]);
+            self.caps[1].set_pos([pos[0] + reach, pos[1]]);
+            for cap in self.caps.iter_mut(){
+                cap.set_size([self.size[1], self.size[1]]);
+            }
+        }
+    }
+
+    fn is_shown(&self) -> bool{
+        self.attached && self.is_visible()
+    }
+
+    /// Make the bubble wide enough for the text, and no narrower than a circle
+    pub(crate) fn layout_text(&mut self, brush: &mut TextBrush){
+        if self.measured{
+            return;
+        }
+        let width = measure_text(brush, &self.text, self.text_size, wgpu_glyph::FontId::default(), None)[0];
+        self.size[0] = self.size[1].max(width + self.text_size * PADDING * 2.0);
+        self.measured = true;
+    }
+
+    pub(crate) fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        let pos = self.body.get_pos();
+        self.screen_pos = [pos[0] + (screen_dim.0 / 2) as f32, pos[1] + (screen_dim.1 / 2) as f32];
+        self.body.prepare(queue, screen_dim);
+        for cap in self.caps.iter_mut(){
+            cap.prepare(queue, screen_dim);
+        }
+    }
+
+    /// The plain body goes first, so the default texture is still bound for it
+    pub(crate) fn render<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>){
+        if self.is_shown(){
+            self.body.render(render_pass);
+            for cap in self.caps.iter(){
+                cap.render(render_pass);
+            }
+        }
+    }
+
+    pub(crate) fn render_text(&self, brush: &mut TextBrush){
+        if self.is_shown(){
+            brush.queue(wgpu_glyph::Section {
+                screen_position: (self.screen_pos[0], self.screen_pos[1]),
+                text: vec![wgpu_glyph::Text::new(&self.text).with_color(self.text_color).with_scale(wgpu_glyph::ab_glyph::PxScale::from(self.text_size))],
+                layout: wgpu_glyph::Layout::default().v_align(VerticalAlign::Center).h_align(HorizontalAlign::Center),
+                ..wgpu_glyph::Section::default()
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Test that counts past the max are cut short with a plus
+    #[test]
+    fn badge_text_test(){
+        assert_eq!(badge_text(0, 99), "0");
+        assert_eq!(badge_text(99, 99), "99");
+        assert_eq!(badge_text(150, 99), "99+");
+        assert_eq!(badge_text(10, 9), "9+");
+    }
+
+    /// Test that badges go on the corners of a component's bounds, with y going down
+    #[test]
+    fn badge_pos_test(){
+        let bounds = Rect::new([100.0, 50.0], [40.0, 20.0]);
+        assert_eq!(badge_pos(bounds, BadgeCorner::TopRight, [0.0, 0.0]), [120.0, 40.0]);
+        assert_eq!(badge_pos(bounds, BadgeCorner::TopLeft, [0.0, 0.0]), [80.0, 40.0]);
+        assert_eq!(badge_pos(bounds, BadgeCorner::BottomLeft, [0.0, 0.0]), [80.0, 60.0]);
+        assert_eq!(badge_pos(bounds, BadgeCorner::BottomRight, [-4.0, -4.0]), [116.0, 56.0]);
+        assert_eq!(BadgeCorner::default(), BadgeCorner::TopRight);
+    }
+}
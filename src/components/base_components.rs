@@ -1,13 +1,15 @@
 //! This module defines base components provided for the user
-//! It includes labels, images and buttons.
+//! It includes labels, images, buttons and text inputs.
 //! There is also a trait provided that will allow users to define custom components,
 //! so that the crate remains as modular and user extendable as possible.
 
 use wgpu::util::DeviceExt;
 use winit::window::Window;
-use wgpu_glyph::{HorizontalAlign, VerticalAlign};
+use wgpu_glyph::{GlyphCruncher, HorizontalAlign, VerticalAlign, ab_glyph::{Font, ScaleFont}};
 
-use crate::{layout::Layout, rendering::{Renderer, Transform}};
+use crate::{input::{EventPhase, EventResponse, HoverEvent, InputCategory, InputState, Key, KeyEvent, MouseButton, MouseEvent, UiEvent}, layout::Layout, rendering::{Allocation, Quad, Renderer, TextBrush, Transform, measure_text}};
+
+use super::{Caret, Searchable, TextEffect, TextEffects, find::Highlights, password::{mask_index, mask_text}};
 
 use std::{any::Any};
 
@@ -23,6 +25,28 @@ use std::{any::Any};
 /// You use this function to add images to bind groups, or to draw text etc.
 ///
 /// Lastly, the user should define a new function to easily create a new struct.
+///
+/// Animated components can also implement `update`, which gets called once per frame with the time
+/// since the last frame (in seconds), and `prepare`, which runs right before rendering and is the place
+/// to write changed values (like a moved transform) to the GPU. While `is_animating` returns true, the GUI
+/// redraws every frame instead of waiting for events.
+///
+/// Components that own text (rather than linking a label in the layout) can queue it in `render_text`,
+/// which runs after every component has been rendered, so the text always ends up on top. If they need to know
+/// how big their text is (to place a caret, for example), they can measure it in `layout_text`, which runs
+/// right before `prepare`.
+///
+/// Layout happens in two passes. First `measure` asks the component how big it wants to be, given the space
+/// available, then `arrange` tells it the rectangle it ended up with. Containers measure their children to work
+/// out their own size, then arrange them inside the rectangle they were given. Components that return `None`
+/// from `measure` (the default) place themselves, and don't get arranged. The results are kept until the layout
+/// changes, see `Layout::invalidate_layout`.
+///
+/// Components that make other components on the fly (like a `ListView` making rows for new items) can do it in
+/// `rebuild`, which gets the GPU device before anything else happens in a frame. It returns true if the component
+/// changed size, so the layout gets arranged again.
+///
+/// Components that return their bounds from `get_bounds` can show a tooltip when hovered (see `Layout::set_tooltip`).
 pub trait GUIComponent{
     fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>) where 'a: 'b;
     fn as_any(&self) -> &dyn Any;
@@ -30,10 +54,37 @@ pub trait GUIComponent{
     fn get_text_id(&self) -> Option<usize>;
     fn is_enabled(&self) -> bool;
     fn get_pos(&self) -> [f32; 2];
+    fn update(&mut self, _dt: f32){}
+    fn is_animating(&self) -> bool{ false }
+    fn prepare(&mut self, _queue: &wgpu::Queue, _screen_dim: (u32, u32)){}
+    fn render_text<'a, 'b>(&'a self, _brush: &'b mut TextBrush) where 'a: 'b{}
+    fn layout_text(&mut self, _brush: &mut TextBrush){}
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{ None }
+    fn arrange(&mut self, _rect: Rect){}
+    fn rebuild(&mut self, _device: &wgpu::Device) -> bool{ false }
+    fn get_bounds(&self) -> Option<Rect>{ None }
 }
 
 /// Similar to the `GUIComponent`, except every event gets passed to the component. Useful for buttons
 /// and other event driven components.
+///
+/// `update`, `is_animating`, `prepare`, `render_text`, `layout_text`, `measure`, `arrange` and `rebuild` work the
+/// same as they do for a `GUIComponent`.
+///
+/// Components that return their bounds from `get_bounds` take part in hit testing. The GUI works out which
+/// component is under the cursor once per frame, and only calls `handle_hover` on the components the cursor
+/// entered or left, so they don't have to check every `CursorMoved` event themselves. Only the topmost component
+/// under the cursor is hovered.
+///
+/// The layout passes events to its components from the top down (the overlay first), and each one gets the event
+/// through `handle_event` twice in a row - `EventPhase::Capture`, then `EventPhase::Bubble` - before the next one
+/// does. Returning `EventResponse::Consumed` stops the event from reaching anything else, including the user's
+/// event handler. By default, `handle_event` calls `handle_event_callback` in the bubble phase and lets the event
+/// carry on. Components holding other components should pass the capture phase on to their children before
+/// handling it themselves, and the bubble phase after, so a container can step in before anything inside it.
+///
+/// A component returning true from `is_modal`, like an open `Dialog`, captures all input - while it's the topmost
+/// one, the layout sends input only to it and nothing under it gets hovered, including the layouts below.
 pub trait EventGUIComponent{
     fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>) where 'a: 'b;
     fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window);
@@ -42,15 +93,82 @@ pub trait EventGUIComponent{
     fn get_text_id(&self) -> Option<usize>;
     fn is_enabled(&self) -> bool;
     fn get_pos(&self) -> [f32; 2];
+    fn update(&mut self, _dt: f32){}
+    fn is_animating(&self) -> bool{ false }
+    fn prepare(&mut self, _queue: &wgpu::Queue, _screen_dim: (u32, u32)){}
+    fn render_text<'a, 'b>(&'a self, _brush: &'b mut TextBrush) where 'a: 'b{}
+    fn layout_text(&mut self, _brush: &mut TextBrush){}
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{ None }
+    fn arrange(&mut self, _rect: Rect){}
+    fn rebuild(&mut self, _device: &wgpu::Device) -> bool{ false }
+    fn get_bounds(&self) -> Option<Rect>{ None }
+    fn handle_hover(&mut self, _event: HoverEvent){}
+    fn is_modal(&self) -> bool{ false }
+    fn handle_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window, phase: EventPhase) -> EventResponse{
+        if phase == EventPhase::Bubble{
+            self.handle_event_callback(event, window);
+        }
+        EventResponse::Continue
+    }
 }
 
 
 /// Similar to a GUI component, but renders text rather than an image.
 /// Exists because labels require it.
 pub trait TextGUIComponent{
-    fn render_text<'a, 'b>(&'a self, brush: &'b mut wgpu_glyph::GlyphBrush<()>) where 'a: 'b;
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush) where 'a: 'b;
     fn as_any(&self) -> &dyn Any;
     fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn update(&mut self, _dt: f32){}
+    fn is_animating(&self) -> bool{ false }
+}
+
+/// The direction a component is laid out in, for things like scrollbars
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Orientation{
+    Horizontal,
+    Vertical,
+}
+
+/// # Rect
+///
+/// A rectangle, in the same space as a `Quad` - the position is the centre, measured in pixels
+/// from the centre of the screen, and the size is the full width and height.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Rect{
+    pub pos: [f32; 2],
+    pub size: [f32; 2],
+}
+
+impl Rect{
+    pub fn new(pos: [f32; 2], size: [f32; 2]) -> Self{
+        Self{
+            pos,
+            size,
+        }
+    }
+
+    /// Create a rect spanning two corners, in any order
+    pub fn from_corners(a: [f32; 2], b: [f32; 2]) -> Self{
+        Self{
+            pos: [(a[0] + b[0]) / 2.0, (a[1] + b[1]) / 2.0],
+            size: [(a[0] - b[0]).abs(), (a[1] - b[1]).abs()],
+        }
+    }
+
+    pub fn contains(&self, point: [f32; 2]) -> bool{
+        (point[0] - self.pos[0]).abs() <= self.size[0] / 2.0 && (point[1] - self.pos[1]).abs() <= self.size[1] / 2.0
+    }
+
+    pub fn intersects(&self, other: &Rect) -> bool{
+        (self.pos[0] - other.pos[0]).abs() * 2.0 <= self.size[0] + other.size[0]
+            && (self.pos[1] - other.pos[1]).abs() * 2.0 <= self.size[1] + other.size[1]
+    }
+}
+
+/// Convert a point in window space (like a cursor position) into the space components are positioned in
+pub fn window_to_component_space(point: [f64; 2], screen_dim: (u32, u32)) -> [f32; 2]{
+    [point[0] as f32 - (screen_dim.0 / 2) as f32, point[1] as f32 - (screen_dim.1 / 2) as f32]
 }
 
 // This part now shows some of the base components, and may help when designing your own custom components
@@ -64,23 +182,72 @@ pub struct Label{
     content: String,
     size: f32,
     pos: [f32; 2], // x and y coords
+    max_width: Option<f32>, // wrap past this width
 
     alignment: (wgpu_glyph::VerticalAlign, wgpu_glyph::HorizontalAlign),
+    orientation: Orientation,
+    rotation: f32, // radians clockwise, about the position
+    glyph_rotation: f32, // of each glyph of vertical text, about its own centre
     enabled: bool,
+    font: wgpu_glyph::FontId,
+    effects: TextEffects,
+}
+
+// The space between columns of vertical text, as a fraction of the text size
+const VERTICAL_COLUMN_GAP: f32 = 0.25;
+
+// Where a point ends up when it's turned by `angle` radians (clockwise) about `pivot`
+fn turn_about(point: [f32; 2], angle: f32, pivot: [f32; 2]) -> [f32; 2]{
+    let (sin, cos) = angle.sin_cos();
+    let (x, y) = (point[0] - pivot[0], point[1] - pivot[1]);
+    [pivot[0] + x * cos - y * sin, pivot[1] + x * sin + y * cos]
+}
+
+// The size of the box something `size` pixels big fits in once it's turned by `angle` radians
+fn rotated_size(size: [f32; 2], angle: f32) -> [f32; 2]{
+    let (sin, cos) = (angle.sin().abs(), angle.cos().abs());
+    [size[0] * cos + size[1] * sin, size[0] * sin + size[1] * cos]
 }
 
 impl Label{
     /// Create a new `Label` struct
     pub fn new<S: Into<String> + Copy>(content: S, size: f32, pos: [f32; 2]) -> Self{
+        let content: String = content.into();
+        let mut effects = TextEffects::new();
+        effects.restart(content.chars().count());
         Self{
-            content: content.into(),
+            content,
             size,
             pos,
+            max_width: None,
             alignment: (wgpu_glyph::VerticalAlign::Top, wgpu_glyph::HorizontalAlign::Left),
+            orientation: Orientation::Horizontal,
+            rotation: 0.0,
+            glyph_rotation: 0.0,
             enabled: true,
+            font: wgpu_glyph::FontId::default(),
+            effects,
+        }
+    }
+
+    /// Change the text of the label. A typewriter effect types out the new text, if it's different.
+    pub fn set_text<S: Into<String>>(&mut self, content: S){
+        let content = content.into();
+        if content != self.content{
+            self.effects.retype(content.chars().count());
+            self.content = content;
         }
     }
 
+    pub fn get_text(&self) -> &str{
+        &self.content
+    }
+
+    /// Change the font of the label, using an ID from `Renderer::add_font`
+    pub fn set_font(&mut self, font: wgpu_glyph::FontId){
+        self.font = font;
+    }
+
     /// Change the vertical alignment of the label
     pub fn align_vertical(&mut self, alignment: wgpu_glyph::VerticalAlign){
         self.alignment.0 = alignment;
@@ -91,6 +258,69 @@ impl Label{
         self.alignment.1 = alignment;
     }
 
+    /// Lay the text out left to right (the default), or top to bottom for things like CJK text and the axis labels
+    /// of charts. Vertical text keeps its glyphs upright, one under the other, and each line becomes a column,
+    /// with the columns running right to left. The alignment still applies, and the max width is ignored.
+    ///
+    /// Sideways glyphs can be turned with `set_glyph_rotation`, and the whole text with `set_rotation`.
+    pub fn set_orientation(&mut self, orientation: Orientation){
+        self.orientation = orientation;
+    }
+
+    pub fn get_orientation(&self) -> Orientation{
+        self.orientation
+    }
+
+    /// Turn the text by an angle (in radians, clockwise) about its position. Text reading from bottom to top, like
+    /// the title of a chart's y axis, is a quarter turn back (`-FRAC_PI_2`). It's measured as the box the turned
+    /// text fits in.
+    pub fn set_rotation(&mut self, rotation: f32){
+        self.rotation = rotation;
+    }
+
+    pub fn get_rotation(&self) -> f32{
+        self.rotation
+    }
+
+    /// Turn every glyph of vertical text about the middle of its square, on top of the label's rotation. A quarter
+    /// turn lays Latin letters on their side in a column of CJK text, for example. Horizontal text ignores it.
+    pub fn set_glyph_rotation(&mut self, rotation: f32){
+        self.glyph_rotation = rotation;
+    }
+
+    pub fn get_glyph_rotation(&self) -> f32{
+        self.glyph_rotation
+    }
+
+    /// Play an effect on every character, like a typewriter reveal or a wave. Effects stack, and run off the
+    /// layout's animation clock, so the label keeps the GUI redrawing while they play.
+    pub fn add_effect(&mut self, effect: TextEffect){
+        self.effects.add(effect);
+    }
+
+    pub fn clear_effects(&mut self){
+        self.effects.clear();
+    }
+
+    pub fn get_effects(&self) -> &TextEffects{
+        &self.effects
+    }
+
+    /// Start the effects again from the beginning, hiding the text again for a typewriter
+    pub fn restart_effects(&mut self){
+        self.effects.restart(self.content.chars().count());
+    }
+
+    /// Whether a typewriter effect is still revealing the text
+    pub fn is_typing(&self) -> bool{
+        self.effects.is_typing()
+    }
+
+    /// Reveal all of the text a typewriter effect is still typing
+    pub fn finish_typing(&mut self){
+        self.effects.finish_typing();
+    }
+
     pub fn enable(&mut self){
         self.enabled = true;
     }
@@ -102,21 +332,128 @@ impl Label{
     pub fn set_pos(&mut self, pos: [f32; 2], screen_dim: (u32, u32)){
         self.pos = [(pos[0] + (screen_dim.0/2) as f32), (pos[1] + (screen_dim.1/2) as f32)];
     }
+
+    /// Wrap the text onto new lines once it gets wider than a number of pixels. `None` never wraps.
+    pub fn set_max_width(&mut self, max_width: Option<f32>){
+        self.max_width = max_width;
+    }
+
+    /// Work out how much space the text takes up in pixels, wrapping included
+    pub fn measure(&self, renderer: &mut Renderer) -> [f32; 2]{
+        let size = match self.orientation{
+            Orientation::Horizontal => renderer.measure_text(&self.content, self.size, self.font, self.max_width),
+            Orientation::Vertical => self.vertical_size(),
+        };
+        rotated_size(size, self.rotation)
+    }
+
+    // Roughly how tall the text is, for when there's no renderer to measure it with
+    pub(crate) fn estimated_height(&self) -> f32{
+        match self.orientation{
+            Orientation::Horizontal => self.content.lines().count().max(1) as f32 * self.size * 1.2,
+            Orientation::Vertical => self.vertical_size()[1],
+        }
+    }
+
+    // Every glyph in vertical text gets a square as big as the text size
+    fn vertical_size(&self) -> [f32; 2]{
+        let columns = self.content.lines().count() as f32;
+        let longest = self.content.lines().map(|line| line.chars().count()).max().unwrap_or(0) as f32;
+        [
+            columns * self.size + (columns - 1.0).max(0.0) * self.size * VERTICAL_COLUMN_GAP,
+            longest * self.size,
+        ]
+    }
+
+    fn render_vertical<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        let width = self.vertical_size()[0];
+        let left = match self.alignment.1{
+            wgpu_glyph::HorizontalAlign::Left => self.pos[0],
+            wgpu_glyph::HorizontalAlign::Center => self.pos[0] - width / 2.0,
+            wgpu_glyph::HorizontalAlign::Right => self.pos[0] - width,
+        };
+        let mut index = 0; // of the character, through the whole text, for the effects
+        for (i, column) in self.content.lines().enumerate(){
+            let x = left + width - self.size / 2.0 - i as f32 * self.size * (1.0 + VERTICAL_COLUMN_GAP);
+            let height = column.chars().count() as f32 * self.size;
+            let top = match self.alignment.0{
+                wgpu_glyph::VerticalAlign::Top => self.pos[1],
+                wgpu_glyph::VerticalAlign::Center => self.pos[1] - height / 2.0,
+                wgpu_glyph::VerticalAlign::Bottom => self.pos[1] - height,
+            };
+            // Each glyph gets its own section, centred in its square. Turning the label moves the middle of the
+            // square, which the glyph turns about.
+            for (j, (start, character)) in column.char_indices().enumerate(){
+                let offset = self.effects.offset(index + j);
+                let middle = [x + offset[0], top + (j as f32 + 0.5) * self.size + offset[1]];
+                let turned = turn_about(middle, self.rotation, self.pos);
+                brush.queue_rotated(wgpu_glyph::Section {
+                    screen_position: (turned[0], turned[1] - self.size / 2.0),
+                    text: vec![wgpu_glyph::Text::new(&column[start..start + character.len_utf8()]).with_color(self.effects.color(index + j, [0.0, 0.0, 0.0, 1.0])).with_scale(wgpu_glyph::ab_glyph::PxScale::from(self.size)).with_font_id(self.font)],
+                    layout: wgpu_glyph::Layout::default_single_line().h_align(wgpu_glyph::HorizontalAlign::Center),
+                    ..wgpu_glyph::Section::default()
+                }, self.rotation + self.glyph_rotation, turned)
+            }
+            index += column.chars().count() + 1;
+        }
+    }
+
+    fn section(&self) -> wgpu_glyph::Section<'_>{
+        wgpu_glyph::Section {
+            screen_position: (self.pos[0], self.pos[1]),
+            bounds: (self.max_width.unwrap_or(f32::INFINITY), f32::INFINITY),
+            text: vec![wgpu_glyph::Text::new(&self.content).with_color([0.0, 0.0, 0.0, 1.0]).with_scale(wgpu_glyph::ab_glyph::PxScale::from(self.size)).with_font_id(self.font)],
+            layout: wgpu_glyph::Layout::default().v_align(self.alignment.0).h_align(self.alignment.1),
+            ..wgpu_glyph::Section::default()
+        }
+    }
+
+    // Text with effects gets a colour for every character. Effects that move the characters need every glyph in
+    // its own section, placed where the glyph was laid out, plus its offset.
+    fn render_effects<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        let mut section = self.section();
+        if !self.effects.moves_glyphs(){
+            section.text = self.content.char_indices().enumerate().map(|(i, (start, character))| {
+                wgpu_glyph::Text::new(&self.content[start..start + character.len_utf8()])
+                    .with_color(self.effects.color(i, [0.0, 0.0, 0.0, 1.0]))
+                    .with_scale(wgpu_glyph::ab_glyph::PxScale::from(self.size))
+                    .with_font_id(self.font)
+            }).collect();
+            brush.queue_rotated(section, self.rotation, self.pos);
+            return;
+        }
+
+        let starts: Vec<usize> = self.content.char_indices().map(|(start, _)| start).collect();
+        let ascent = brush.fonts()[self.font.0].as_scaled(self.size).ascent();
+        let placed: Vec<_> = brush.glyphs(section).map(|glyph| (glyph.byte_index, glyph.glyph.position)).collect();
+        for (start, position) in placed{
+            let index = starts.binary_search(&start).unwrap_or_else(|i| i);
+            if !self.effects.is_revealed(index){
+                continue;
+            }
+            let end = starts.get(index + 1).copied().unwrap_or(self.content.len());
+            let offset = self.effects.offset(index);
+            brush.queue_rotated(wgpu_glyph::Section {
+                screen_position: (position.x + offset[0], position.y - ascent + offset[1]),
+                text: vec![wgpu_glyph::Text::new(&self.content[start..end]).with_color(self.effects.color(index, [0.0, 0.0, 0.0, 1.0])).with_scale(wgpu_glyph::ab_glyph::PxScale::from(self.size)).with_font_id(self.font)],
+                layout: wgpu_glyph::Layout::default_single_line(),
+                ..wgpu_glyph::Section::default()
+            }, self.rotation, self.pos)
+        }
+    }
 }
 
 impl TextGUIComponent for Label{
-    fn render_text<'a, 'b>(&'a self, brush: &'b mut wgpu_glyph::GlyphBrush<()>)
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
     where 'a: 'b {
-        if self.enabled{
-            brush.queue(
-                wgpu_glyph::Section {
-                    screen_position: (self.pos[0], self.pos[1]),
-                    text: vec![wgpu_glyph::Text::new(&self.content).with_color([0.0, 0.0, 0.0, 1.0]).with_scale(wgpu_glyph::ab_glyph::PxScale::from(self.size))],
-                    layout: wgpu_glyph::Layout::default().v_align(self.alignment.0).h_align(self.alignment.1),
-                    ..wgpu_glyph::Section::default()
-                }
-                
-            )
+        if self.enabled && self.orientation == Orientation::Vertical{
+            self.render_vertical(brush);
+        }else if self.enabled && !self.effects.is_empty(){
+            self.render_effects(brush);
+        }else if self.enabled{
+            brush.queue_rotated(self.section(), self.rotation, self.pos)
         }
     }
 
@@ -127,6 +464,16 @@ impl TextGUIComponent for Label{
     fn as_any_mut(&mut self) -> &mut dyn Any {
         self
     }
+
+    fn update(&mut self, dt: f32){
+        if !self.effects.is_empty(){
+            self.effects.update(dt);
+        }
+    }
+
+    fn is_animating(&self) -> bool{
+        self.enabled && self.effects.is_animating()
+    }
 }
 
 
@@ -147,6 +494,7 @@ pub struct Button{
     vertex_buffer: wgpu::Buffer, // the vertex buffer that stores the verticies of,
     enabled: bool,
     attached_text_id: Option<usize>,
+    _memory: Allocation,
 }
 
 
@@ -170,7 +518,8 @@ impl Button{
             cursor_in_bounds: false,
             vertex_buffer: create_buffers(&renderer.device),
             enabled: true,
-            attached_text_id
+            attached_text_id,
+            _memory: Allocation::quad_buffer(),
         }
     }
 
@@ -266,6 +615,501 @@ impl EventGUIComponent for Button{
     }
 }
 
+/// What a `TextBuffer` did with a key press
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EditResult{
+    /// The key didn't do anything, either because it isn't an editing key or there was nothing to delete
+    Ignored,
+    /// The cursor moved
+    Moved,
+    /// The text changed
+    Changed,
+}
+
+/// # TextBuffer
+///
+/// The text and cursor behind a text input, without any drawing. The cursor is a byte index into the text,
+/// and always sits on a character boundary.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextBuffer{
+    text: String,
+    cursor: usize,
+}
+
+impl TextBuffer{
+    pub fn new() -> Self{
+        Self::default()
+    }
+
+    pub fn get_text(&self) -> &str{
+        &self.text
+    }
+
+    /// Replace the text, putting the cursor at the end
+    pub fn set_text<S: Into<String>>(&mut self, text: S){
+        self.text = text.into();
+        self.cursor = self.text.len();
+    }
+
+    pub fn get_cursor(&self) -> usize{
+        self.cursor
+    }
+
+    /// Move the cursor to a byte index, rounded down to the nearest character boundary
+    pub fn set_cursor(&mut self, cursor: usize){
+        let mut cursor = cursor.min(self.text.len());
+        while !self.text.is_char_boundary(cursor){
+            cursor -= 1;
+        }
+        self.cursor = cursor;
+    }
+
+    /// Type a character at the cursor
+    pub fn insert(&mut self, character: char){
+        self.text.insert(self.cursor, character);
+        self.cursor += character.len_utf8();
+    }
+
+    /// Remove the character before the cursor. Returns false if there wasn't one.
+    pub fn backspace(&mut self) -> bool{
+        match self.text[..self.cursor].chars().next_back(){
+            Some(character) => {
+                self.cursor -= character.len_utf8();
+                self.text.remove(self.cursor);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove the character after the cursor. Returns false if there wasn't one.
+    pub fn delete(&mut self) -> bool{
+        if self.cursor < self.text.len(){
+            self.text.remove(self.cursor);
+            true
+        }else{
+            false
+        }
+    }
+
+    pub fn move_left(&mut self){
+        if let Some(character) = self.text[..self.cursor].chars().next_back(){
+            self.cursor -= character.len_utf8();
+        }
+    }
+
+    pub fn move_right(&mut self){
+        if let Some(character) = self.text[self.cursor..].chars().next(){
+            self.cursor += character.len_utf8();
+        }
+    }
+
+    pub fn move_home(&mut self){
+        self.cursor = 0;
+    }
+
+    pub fn move_end(&mut self){
+        self.cursor = self.text.len();
+    }
+
+    /// Handle the editing keys - backspace, delete, the left and right arrows, home and end
+    pub fn handle_key(&mut self, event: &KeyEvent) -> EditResult{
+        if !event.pressed{
+            return EditResult::Ignored;
+        }
+        match event.key{
+            Key::Backspace => return if self.backspace() { EditResult::Changed } else { EditResult::Ignored },
+            Key::Delete => return if self.delete() { EditResult::Changed } else { EditResult::Ignored },
+            Key::Left => self.move_left(),
+            Key::Right => self.move_right(),
+            Key::Home => self.move_home(),
+            Key::End => self.move_end(),
+            _ => return EditResult::Ignored,
+        }
+        EditResult::Moved
+    }
+}
+
+/// # TextInput
+///
+/// A single line text box. Click on it to focus it, then type. Backspace, delete, the arrow keys, home and end
+/// edit the text like you'd expect, enter submits it and escape unfocuses it. While focused, it consumes
+/// keyboard events so they don't reach anything else.
+///
+/// Text wider than the box isn't clipped, so give it room (or limit the length in `set_on_change`). Text inputs can be
+/// searched, see `Searchable`.
+pub struct TextInput{
+    buffer: TextBuffer,
+    placeholder: String,
+    bounds: Rect,
+    padding: f32,
+    text_size: f32,
+    font: wgpu_glyph::FontId,
+    text_color: [f32; 4],
+    placeholder_color: [f32; 4],
+    mask: Option<char>, // shown in place of every character, for passwords
+
+    border: Quad,
+    background: Quad,
+    border_colors: ([f32; 4], [f32; 4]), // unfocused, focused
+    caret: Caret,
+    caret_offset: f32, // how far the cursor is from the start of the text, in pixels
+    click_offset: Option<f32>, // a click waiting to be turned into a cursor position, once the text is measured
+    highlights: Highlights,
+    screen_dim: (u32, u32),
+
+    input: InputState,
+    focused: bool,
+    enabled: bool,
+    on_change: Option<Box<dyn Fn(&str)>>,
+    on_submit: Option<Box<dyn Fn(&str)>>,
+}
+
+impl TextInput{
+    /// Create an empty text input filling `bounds`
+    pub fn new(bounds: Rect, text_size: f32, renderer: &Renderer) -> Self{
+        let mut caret = Caret::new([0.0, 0.0], text_size, &renderer.device);
+        caret.disable();
+
+        let mut input = Self{
+            buffer: TextBuffer::new(),
+            placeholder: String::new(),
+            bounds,
+            padding: 6.0,
+            text_size,
+            font: wgpu_glyph::FontId::default(),
+            text_color: [0.0, 0.0, 0.0, 1.0],
+            placeholder_color: [0.5, 0.5, 0.5, 1.0],
+            mask: None,
+
+            border: Quad::new(bounds.pos, bounds.size, [0.5, 0.5, 0.5, 1.0], &renderer.device),
+            background: Quad::new(bounds.pos, [bounds.size[0] - 2.0, bounds.size[1] - 2.0], [1.0, 1.0, 1.0, 1.0], &renderer.device),
+            border_colors: ([0.5, 0.5, 0.5, 1.0], [0.2, 0.4, 0.8, 1.0]),
+            caret,
+            caret_offset: 0.0,
+            click_offset: None,
+            highlights: Highlights::new(),
+            screen_dim: (renderer.sc_desc.width, renderer.sc_desc.height),
+
+            input: InputState::new(),
+            focused: false,
+            enabled: true,
+            on_change: None,
+            on_submit: None,
+        };
+        input.move_caret();
+        input
+    }
+
+    pub fn get_text(&self) -> &str{
+        self.buffer.get_text()
+    }
+
+    /// Replace the text, putting the cursor at the end. This doesn't call the change callback.
+    pub fn set_text<S: Into<String>>(&mut self, text: S){
+        self.buffer.set_text(text);
+    }
+
+    /// The text and cursor, for editing them directly
+    pub fn borrow_buffer_mut(&mut self) -> &mut TextBuffer{
+        &mut self.buffer
+    }
+
+    /// Set the grey text shown while the input is empty
+    pub fn set_placeholder<S: Into<String>>(&mut self, placeholder: S){
+        self.placeholder = placeholder.into();
+    }
+
+    /// Change the font, using an ID from `Renderer::add_font`
+    pub fn set_font(&mut self, font: wgpu_glyph::FontId){
+        self.font = font;
+    }
+
+    pub fn set_text_color(&mut self, color: [f32; 4]){
+        self.text_color = color;
+        self.caret.set_color(color);
+    }
+
+    /// Set the colours of the background, and the border when unfocused and focused
+    pub fn set_colors(&mut self, background: [f32; 4], border: [f32; 4], focused_border: [f32; 4]){
+        self.background.set_color(background);
+        self.border_colors = (border, focused_border);
+        self.border.set_color(if self.focused { focused_border } else { border });
+    }
+
+    /// Set the colours search matches are highlighted in, and the current match
+    pub fn set_highlight_colors(&mut self, matches: [f32; 4], current: [f32; 4]){
+        self.highlights.set_colors(matches, current);
+    }
+
+    pub fn set_bounds(&mut self, bounds: Rect){
+        self.bounds = bounds;
+        self.border.set_pos(bounds.pos);
+        self.border.set_size(bounds.size);
+        self.background.set_pos(bounds.pos);
+        self.background.set_size([bounds.size[0] - 2.0, bounds.size[1] - 2.0]);
+        self.move_caret();
+    }
+
+    /// Called with the new text whenever the user changes it
+    pub fn set_on_change(&mut self, callback: Option<Box<dyn Fn(&str)>>){
+        self.on_change = callback;
+    }
+
+    /// Called with the text when enter is pressed
+    pub fn set_on_submit(&mut self, callback: Option<Box<dyn Fn(&str)>>){
+        self.on_submit = callback;
+    }
+
+    /// Focus or unfocus the input. Only a focused input takes keyboard input.
+    pub fn set_focused(&mut self, focused: bool){
+        self.focused = focused;
+        self.border.set_color(if focused { self.border_colors.1 } else { self.border_colors.0 });
+        if focused{
+            self.caret.enable();
+        }else{
+            self.caret.disable();
+        }
+    }
+
+    pub fn is_focused(&self) -> bool{
+        self.focused
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+        self.set_focused(false);
+    }
+
+    // Where the text starts, in the same space as components
+    fn text_left(&self) -> f32{
+        self.bounds.pos[0] - self.bounds.size[0] / 2.0 + self.padding
+    }
+
+    fn move_caret(&mut self){
+        self.caret.move_to([self.text_left() + self.caret_offset, self.bounds.pos[1] - self.text_size / 2.0]);
+    }
+
+    fn changed(&self){
+        if let Some(callback) = &self.on_change{
+            callback(self.buffer.get_text());
+        }
+    }
+
+    /// Show every character as `mask` (or the real text again, with `None`), see `PasswordInput`
+    pub(crate) fn set_mask(&mut self, mask: Option<char>){
+        self.mask = mask;
+    }
+
+    // The text as it's drawn, which is the mask character over and over for passwords
+    fn shown_text(&self) -> std::borrow::Cow<'_, str>{
+        mask_text(self.buffer.get_text(), self.mask)
+    }
+
+    // Where a byte index in the text ends up in the text as it's drawn
+    fn shown_index(&self, index: usize) -> usize{
+        mask_index(self.buffer.get_text(), index, self.mask)
+    }
+}
+
+impl EventGUIComponent for TextInput{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.enabled{
+            self.border.render(render_pass);
+            self.background.render(render_pass);
+            self.highlights.render(render_pass);
+            self.caret.render(render_pass);
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if !self.enabled{
+            return;
+        }
+        let shown = self.shown_text();
+        let (text, color) = if shown.is_empty(){
+            (self.placeholder.as_str(), self.placeholder_color)
+        }else{
+            (shown.as_ref(), self.text_color)
+        };
+        brush.queue(wgpu_glyph::Section {
+            screen_position: (self.text_left() + (self.screen_dim.0 / 2) as f32, self.bounds.pos[1] + (self.screen_dim.1 / 2) as f32),
+            text: vec![wgpu_glyph::Text::new(text).with_color(color).with_scale(wgpu_glyph::ab_glyph::PxScale::from(self.text_size)).with_font_id(self.font)],
+            layout: wgpu_glyph::Layout::default_single_line().v_align(VerticalAlign::Center).h_align(HorizontalAlign::Left),
+            ..wgpu_glyph::Section::default()
+        });
+    }
+
+    fn layout_text(&mut self, brush: &mut TextBrush){
+        let shown = self.shown_text().into_owned();
+        let text = self.buffer.get_text();
+
+        // Put the cursor on the character boundary closest to where the text was clicked
+        if let Some(click_offset) = self.click_offset.take(){
+            let mut closest = (0, click_offset.abs());
+            for (index, character) in text.char_indices(){
+                let end = index + character.len_utf8();
+                let distance = (measure_text(brush, &shown[..self.shown_index(end)], self.text_size, self.font, None)[0] - click_offset).abs();
+                if distance < closest.1{
+                    closest = (end, distance);
+                }
+            }
+            self.buffer.set_cursor(closest.0);
+        }
+
+        let offset = measure_text(brush, &shown[..self.shown_index(self.buffer.get_cursor())], self.text_size, self.font, None)[0];
+        if offset != self.caret_offset{
+            self.caret_offset = offset;
+            self.move_caret();
+        }
+
+        // Cover the search matches, measuring up to either end of each
+        let left = self.text_left();
+        let current = self.highlights.get_current();
+        let rects: Vec<(Rect, bool)> = self.highlights.get_matches().iter().enumerate().map(|(i, range)| {
+            let start = measure_text(brush, &shown[..self.shown_index(range.start)], self.text_size, self.font, None)[0];
+            let end = measure_text(brush, &shown[..self.shown_index(range.end)], self.text_size, self.font, None)[0];
+            (Rect::new([left + (start + end) / 2.0, self.bounds.pos[1]], [end - start, self.text_size]), current == Some(i))
+        }).collect();
+        self.highlights.place(rects);
+    }
+
+    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        if !self.enabled{
+            return;
+        }
+
+        match self.input.handle_event(event, window){
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed: true, pos, .. })) => {
+                let clicked = self.bounds.contains(pos);
+                self.set_focused(clicked);
+                if clicked{
+                    self.click_offset = Some(pos[0] - self.text_left());
+                    self.caret.reset_blink();
+                }
+            }
+            Some(UiEvent::Text(character)) if self.focused => {
+                // Shortcuts like command+c on macOS still come through as text
+                let modifiers = self.input.get_modifiers();
+                if !modifiers.command() || modifiers.alt{
+                    self.buffer.insert(character);
+                    self.caret.reset_blink();
+                    self.changed();
+                }
+            }
+            Some(UiEvent::Key(key)) if self.focused && key.pressed => {
+                match key.key{
+                    Key::Enter => {
+                        if let Some(callback) = &self.on_submit{
+                            callback(self.buffer.get_text());
+                        }
+                    }
+                    Key::Escape => self.set_focused(false),
+                    _ => match self.buffer.handle_key(&key){
+                        EditResult::Changed => {
+                            self.caret.reset_blink();
+                            self.changed();
+                        }
+                        EditResult::Moved => self.caret.reset_blink(),
+                        EditResult::Ignored => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Keyboard input is consumed while focused
+    fn handle_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window, phase: EventPhase) -> EventResponse{
+        if phase != EventPhase::Bubble{
+            return EventResponse::Continue;
+        }
+        let consume = self.enabled && self.focused && InputCategory::of(event) == Some(InputCategory::Keyboard);
+        self.handle_event_callback(event, window);
+        if consume { EventResponse::Consumed } else { EventResponse::Continue }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.bounds.pos
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(self.bounds)
+    }
+
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{
+        Some(self.bounds.size)
+    }
+
+    fn arrange(&mut self, rect: Rect){
+        if rect != self.bounds{
+            self.set_bounds(rect);
+        }
+    }
+
+    fn update(&mut self, dt: f32){
+        self.caret.update(dt);
+    }
+
+    /// The caret blinks while focused
+    fn is_animating(&self) -> bool{
+        self.enabled && self.focused
+    }
+
+    fn rebuild(&mut self, device: &wgpu::Device) -> bool{
+        self.highlights.rebuild(device);
+        false
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.screen_dim = screen_dim;
+        self.border.prepare(queue, screen_dim);
+        self.background.prepare(queue, screen_dim);
+        self.highlights.prepare(queue, screen_dim);
+        self.caret.prepare(queue, screen_dim);
+    }
+}
+
+/// Replacing text keeps the cursor where it was (as far as the text goes), and counts as the user changing it
+impl Searchable for TextInput{
+    fn get_text(&self) -> &str{
+        self.buffer.get_text()
+    }
+
+    fn set_text(&mut self, text: String){
+        let cursor = self.buffer.get_cursor();
+        self.buffer.set_text(text);
+        self.buffer.set_cursor(cursor);
+        self.changed();
+    }
+
+    fn set_highlights(&mut self, matches: &[std::ops::Range<usize>], current: Option<usize>){
+        self.highlights.set(matches, current);
+    }
+}
+
 /// Helpful function to automatically create a new quad buffer for all your GUI needs.
 pub fn create_buffers(device: &wgpu::Device) -> wgpu::Buffer{
     // Create the vertex buffer (so we can draw to it)
@@ -273,9 +1117,36 @@ pub fn create_buffers(device: &wgpu::Device) -> wgpu::Buffer{
         &wgpu::util::BufferInitDescriptor {
             label: Some("Vertex Buffer"),
             contents: bytemuck::cast_slice(crate::rendering::QUAD),
-            usage: wgpu::BufferUsage::VERTEX,
+            // Written to when a quad only draws part of its texture
+            usage: wgpu::BufferUsage::VERTEX | wgpu::BufferUsage::COPY_DST,
         }
     )
 
 }
 
+
+#[cfg(test)]
+mod tests{
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    /// Test that points turn clockwise about the pivot, with y going down
+    #[test]
+    fn turn_about_test(){
+        let round = |point: [f32; 2]| point.map(|value| (value * 1000.0).round() / 1000.0);
+        assert_eq!(round(turn_about([15.0, 20.0], FRAC_PI_2, [10.0, 20.0])), [10.0, 25.0]);
+        assert_eq!(round(turn_about([15.0, 20.0], -FRAC_PI_2, [10.0, 20.0])), [10.0, 15.0]);
+        assert_eq!(turn_about([15.0, 20.0], 0.0, [10.0, 20.0]), [15.0, 20.0]);
+    }
+
+    /// Test that turned text is measured as the box it fits in
+    #[test]
+    fn rotated_size_test(){
+        let round = |size: [f32; 2]| size.map(|value| (value * 1000.0).round() / 1000.0);
+        assert_eq!(rotated_size([100.0, 20.0], 0.0), [100.0, 20.0]);
+        assert_eq!(round(rotated_size([100.0, 20.0], -FRAC_PI_2)), [20.0, 100.0]);
+        let diagonal = rotated_size([10.0, 10.0], FRAC_PI_2 / 2.0);
+        assert_eq!(round(diagonal), round([200.0f32.sqrt(), 200.0f32.sqrt()]));
+    }
+}
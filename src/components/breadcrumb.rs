@@ -0,0 +1,381 @@
+//! This module contains the `Breadcrumb` component, a row of clickable path segments (like the folders leading to the
+//! one being shown in a file manager) separated by chevrons.
+
+use std::any::Any;
+
+use wgpu_glyph::{HorizontalAlign, VerticalAlign};
+
+use crate::{input::{HoverEvent, InputState, MouseButton, MouseEvent, UiEvent}, rendering::{Quad, Renderer, TextBrush}};
+
+use super::{EventGUIComponent, Label, Rect, TextGUIComponent};
+
+// The space either side of a segment's text, in pixels
+const PADDING: f32 = 6.0;
+// What stands in for the segments that don't fit
+const ELLIPSIS: &str = "...";
+
+/// The first segment a breadcrumb shows when its segments (`widths` wide, with `separator` pixels between each) have
+/// to fit in `max_width`. Segments are dropped from the start, and replaced with an ellipsis `ellipsis` wide (and a
+/// separator), until the rest fit - but the last segment is always shown.
+pub(crate) fn first_visible_segment(widths: &[f32], separator: f32, ellipsis: f32, max_width: f32) -> usize{
+    let total: f32 = widths.iter().sum::<f32>() + separator * widths.len().saturating_sub(1) as f32;
+    if total <= max_width{
+        return 0;
+    }
+    let mut width = total + ellipsis + separator;
+    for (i, segment) in widths.iter().enumerate().take(widths.len().saturating_sub(1)){
+        width -= segment + separator;
+        if width <= max_width{
+            return i + 1;
+        }
+    }
+    widths.len().saturating_sub(1)
+}
+
+struct Segment{
+    label: Label,
+    quad: Quad,
+    width: f32,
+}
+
+/// # Breadcrumb
+///
+/// A path shown as a row of segments, like `Home > Documents > Photos`, starting at `pos` and going right. Clicking
+/// a segment runs the callback with its index, so the app can go back up to it (`truncate` drops the segments
+/// after it). The segment under the cursor is highlighted.
+///
+/// With `set_max_width`, segments are dropped from the start of the path, and replaced with an ellipsis, until the
+/// rest fit. The last segment is always shown.
+pub struct Breadcrumb{
+    pos: [f32; 2], // the middle of the left end
+    height: f32,
+    text_size: f32,
+    max_width: Option<f32>,
+    callback: Option<Box<dyn Fn(usize)>>,
+    colors: [[f32; 4]; 2], // normal, hovered
+
+    segments: Vec<Segment>,
+    separator: String,
+    separator_width: f32,
+    separators: Vec<(Label, [f32; 2])>,
+    ellipsis: Label,
+    ellipsis_pos: [f32; 2],
+    ellipsis_width: f32,
+    first: usize, // the first segment shown
+
+    input: InputState,
+    hovered: Option<usize>,
+    pressed: Option<usize>, // the segment the mouse went down on
+    enabled: bool,
+}
+
+impl Breadcrumb{
+    /// Create an empty breadcrumb, with the middle of its left end at `pos`
+    pub fn new(pos: [f32; 2], height: f32, text_size: f32, renderer: &mut Renderer) -> Self{
+        let mut ellipsis = Label::new(ELLIPSIS, text_size, [0.0, 0.0]);
+        ellipsis.align_horizontal(HorizontalAlign::Center);
+        ellipsis.align_vertical(VerticalAlign::Center);
+        let mut breadcrumb = Self{
+            pos,
+            height,
+            text_size,
+            max_width: None,
+            callback: None,
+            colors: [[0.0, 0.0, 0.0, 0.0], [0.85, 0.85, 0.85, 1.0]],
+
+            segments: Vec::new(),
+            separator: String::new(),
+            separator_width: 0.0,
+            separators: Vec::new(),
+            ellipsis,
+            ellipsis_pos: [0.0, 0.0],
+            ellipsis_width: renderer.measure_text(ELLIPSIS, text_size, wgpu_glyph::FontId::default(), None)[0] + PADDING * 2.0,
+            first: 0,
+
+            input: InputState::new(),
+            hovered: None,
+            pressed: None,
+            enabled: true,
+        };
+        breadcrumb.set_separator(">", renderer);
+        breadcrumb
+    }
+
+    /// Replace the path, starting from the root
+    pub fn set_path(&mut self, path: &[&str], renderer: &mut Renderer){
+        self.segments.clear();
+        for segment in path.iter(){
+            self.add_segment(segment, renderer);
+        }
+        self.place();
+    }
+
+    /// Add a segment to the end of the path, like when going into a folder
+    pub fn push(&mut self, segment: &str, renderer: &mut Renderer){
+        self.add_segment(segment, renderer);
+        self.place();
+    }
+
+    /// Drop the segments after the first `len`, like when going back up to a folder
+    pub fn truncate(&mut self, len: usize){
+        self.segments.truncate(len);
+        self.place();
+    }
+
+    /// The text of each segment in the path
+    pub fn get_path(&self) -> Vec<&str>{
+        self.segments.iter().map(|segment| segment.label.get_text()).collect()
+    }
+
+    pub fn len(&self) -> usize{
+        self.segments.len()
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.segments.is_empty()
+    }
+
+    /// Set the text between segments. It's a `>` by default.
+    pub fn set_separator(&mut self, separator: &str, renderer: &mut Renderer){
+        self.separator = separator.to_string();
+        self.separator_width = renderer.measure_text(separator, self.text_size, wgpu_glyph::FontId::default(), None)[0] + PADDING * 2.0;
+        self.place();
+    }
+
+    /// Set the function to run with the index of a segment when it's clicked
+    pub fn set_callback(&mut self, callback: Option<Box<dyn Fn(usize)>>){
+        self.callback = callback;
+    }
+
+    /// Set the background colours of the segments, and of the one under the cursor
+    pub fn set_colors(&mut self, normal: [f32; 4], hovered: [f32; 4]){
+        self.colors = [normal, hovered];
+        self.update_colors();
+    }
+
+    /// Drop segments from the start of the path until it fits in a width, or `None` to show all of them
+    pub fn set_max_width(&mut self, max_width: Option<f32>){
+        self.max_width = max_width;
+        self.place();
+    }
+
+    /// Move the breadcrumb, so the middle of its left end is at `pos`
+    pub fn set_pos(&mut self, pos: [f32; 2]){
+        self.pos = pos;
+        self.place();
+    }
+
+    /// The index of the first segment shown, which is more than 0 when the start of the path doesn't fit
+    pub fn get_first_visible(&self) -> usize{
+        self.first
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+        self.hovered = None;
+        self.pressed = None;
+        self.update_colors();
+    }
+
+    /// The area taken up by the shown segments
+    pub fn get_rect(&self) -> Rect{
+        let shown = &self.segments[self.first.min(self.segments.len())..];
+        let mut width: f32 = shown.iter().map(|segment| segment.width).sum::<f32>() + self.separator_width * shown.len().saturating_sub(1) as f32;
+        if self.first > 0{
+            width += self.ellipsis_width + self.separator_width;
+        }
+        Rect::new([self.pos[0] + width / 2.0, self.pos[1]], [width, self.height])
+    }
+
+    fn add_segment(&mut self, text: &str, renderer: &mut Renderer){
+        let mut label = Label::new(text, self.text_size, [0.0, 0.0]);
+        label.align_horizontal(HorizontalAlign::Center);
+        label.align_vertical(VerticalAlign::Center);
+        let width = label.measure(renderer)[0] + PADDING * 2.0;
+        self.segments.push(Segment{
+            label,
+            quad: Quad::new([0.0, 0.0], [width, self.height], self.colors[0], &renderer.device),
+            width,
+        });
+    }
+
+    // Work out which segments fit, and line them up
+    fn place(&mut self){
+        let widths: Vec<f32> = self.segments.iter().map(|segment| segment.width).collect();
+        self.first = match self.max_width{
+            Some(max_width) => first_visible_segment(&widths, self.separator_width, self.ellipsis_width, max_width),
+            None => 0,
+        };
+
+        let mut x = self.pos[0];
+        if self.first > 0{
+            self.ellipsis_pos = [x + self.ellipsis_width / 2.0, self.pos[1]];
+            x += self.ellipsis_width + self.separator_width;
+        }
+        self.separators.clear();
+        for i in self.first..self.segments.len(){
+            if i > self.first{
+                self.add_separator([x - self.separator_width / 2.0, self.pos[1]]);
+            }
+            let segment = &mut self.segments[i];
+            segment.quad.set_pos([x + segment.width / 2.0, self.pos[1]]);
+            segment.quad.set_size([segment.width, self.height]);
+            x += segment.width + self.separator_width;
+        }
+        // The ellipsis is followed by a separator too
+        if self.first > 0{
+            self.add_separator([self.pos[0] + self.ellipsis_width + self.separator_width / 2.0, self.pos[1]]);
+        }
+        self.hovered = None;
+        self.pressed = None;
+        self.update_colors();
+    }
+
+    fn add_separator(&mut self, pos: [f32; 2]){
+        let mut separator = Label::new(self.separator.as_str(), self.text_size, [0.0, 0.0]);
+        separator.align_horizontal(HorizontalAlign::Center);
+        separator.align_vertical(VerticalAlign::Center);
+        self.separators.push((separator, pos));
+    }
+
+    fn update_colors(&mut self){
+        for (i, segment) in self.segments.iter_mut().enumerate(){
+            segment.quad.set_color(self.colors[(self.hovered == Some(i)) as usize]);
+        }
+    }
+
+    fn segment_at(&self, pos: [f32; 2]) -> Option<usize>{
+        (self.first..self.segments.len()).find(|i| {
+            let quad = &self.segments[*i].quad;
+            Rect::new(quad.get_pos(), quad.get_size()).contains(pos)
+        })
+    }
+}
+
+impl EventGUIComponent for Breadcrumb{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.enabled{
+            for segment in self.segments.iter().skip(self.first){
+                segment.quad.render(render_pass);
+            }
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if !self.enabled{
+            return;
+        }
+        for segment in self.segments.iter().skip(self.first){
+            TextGUIComponent::render_text(&segment.label, brush);
+        }
+        for (separator, _) in self.separators.iter(){
+            TextGUIComponent::render_text(separator, brush);
+        }
+        if self.first > 0{
+            TextGUIComponent::render_text(&self.ellipsis, brush);
+        }
+    }
+
+    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        if !self.enabled{
+            return;
+        }
+
+        match self.input.handle_event(event, window){
+            Some(UiEvent::CursorMoved{ pos, .. }) => {
+                let hovered = self.segment_at(pos);
+                if hovered != self.hovered{
+                    self.hovered = hovered;
+                    self.update_colors();
+                }
+            }
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed, pos, .. })) => {
+                let segment = self.segment_at(pos);
+                if pressed{
+                    self.pressed = segment;
+                }else{
+                    let clicked = self.pressed.take().filter(|pressed| Some(*pressed) == segment);
+                    if let (Some(clicked), Some(callback)) = (clicked, &self.callback){
+                        callback(clicked);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.pos
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(self.get_rect())
+    }
+
+    fn handle_hover(&mut self, event: HoverEvent){
+        if event == HoverEvent::Exit{
+            self.hovered = None;
+            self.update_colors();
+        }
+    }
+
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{
+        Some(self.get_rect().size)
+    }
+
+    /// The breadcrumb goes along the left of the rectangle
+    fn arrange(&mut self, rect: Rect){
+        self.set_pos([rect.pos[0] - rect.size[0] / 2.0, rect.pos[1]]);
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        for segment in self.segments.iter_mut().skip(self.first){
+            segment.quad.prepare(queue, screen_dim);
+            segment.label.set_pos(segment.quad.get_pos(), screen_dim);
+        }
+        for (separator, pos) in self.separators.iter_mut(){
+            separator.set_pos(*pos, screen_dim);
+        }
+        self.ellipsis.set_pos(self.ellipsis_pos, screen_dim);
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Test that segments are dropped from the start of a breadcrumb until the rest fit, keeping the last one
+    #[test]
+    fn first_visible_segment_test(){
+        let widths = [50.0, 50.0, 50.0, 50.0];
+        // 230 pixels with the separators between them
+        assert_eq!(first_visible_segment(&widths, 10.0, 20.0, 300.0), 0);
+        assert_eq!(first_visible_segment(&widths, 10.0, 20.0, 230.0), 0);
+        // The ellipsis and its separator take up room too
+        assert_eq!(first_visible_segment(&widths, 10.0, 20.0, 150.0), 2);
+        assert_eq!(first_visible_segment(&widths, 10.0, 20.0, 10.0), 3);
+        assert_eq!(first_visible_segment(&[], 10.0, 20.0, 10.0), 0);
+    }
+}
@@ -0,0 +1,433 @@
+//! This module contains the `Canvas` component, which calls a paint function to draw rectangles, lines, circles
+//! and text, and the `Painter` that function draws with.
+
+use std::{any::Any, f32::consts::PI, rc::Rc};
+
+use crate::rendering::{Lines, Quad, Renderer, TextBrush, Texture};
+
+use super::{GUIComponent, Rect};
+
+// How many pixels across the disc texture circles are drawn with
+const DISC_RESOLUTION: u32 = 64;
+// Each pixel of the disc texture is sampled this many times across and down, to smooth the edge
+const DISC_SAMPLES: u32 = 4;
+
+type PaintCallback = Box<dyn FnMut(&mut Painter)>;
+// The segments painted in one colour, which are drawn together
+type LineBatch = ([f32; 4], Vec<[[f32; 2]; 2]>);
+
+/// `segments + 1` points evenly spaced around a circle, starting and ending on its right. Joined up in order, they
+/// draw the circle's outline.
+pub(crate) fn circle_points(center: [f32; 2], radius: f32, segments: usize) -> Vec<[f32; 2]>{
+    let segments = segments.max(3);
+    (0..=segments).map(|i| {
+        let angle = (i % segments) as f32 / segments as f32 * PI * 2.0;
+        [center[0] + radius * angle.cos(), center[1] + radius * angle.sin()]
+    }).collect()
+}
+
+// A white disc filling the texture, on a clear background
+pub(crate) fn disc_pixels(size: u32) -> Vec<u8>{
+    let mut pixels = Vec::with_capacity((size * size * 4) as usize);
+    for row in 0..size{
+        for column in 0..size{
+            let mut covered = 0;
+            for sample in 0..DISC_SAMPLES * DISC_SAMPLES{
+                let x = (column as f32 + ((sample % DISC_SAMPLES) as f32 + 0.5) / DISC_SAMPLES as f32) / size as f32;
+                let y = (row as f32 + ((sample / DISC_SAMPLES) as f32 + 0.5) / DISC_SAMPLES as f32) / size as f32;
+                if (x - 0.5).powi(2) + (y - 0.5).powi(2) <= 0.25{
+                    covered += 1;
+                }
+            }
+            pixels.extend_from_slice(&[255, 255, 255, (covered * 255 / (DISC_SAMPLES * DISC_SAMPLES)) as u8]);
+        }
+    }
+    pixels
+}
+
+/// Something painted on a `Canvas`, in pixels from the top left of the canvas
+#[derive(Clone, Debug, PartialEq)]
+pub enum PaintShape{
+    Rect{ pos: [f32; 2], size: [f32; 2], color: [f32; 4] },
+    Circle{ center: [f32; 2], radius: f32, color: [f32; 4] },
+    Line{ from: [f32; 2], to: [f32; 2], color: [f32; 4] },
+    Text{ text: String, pos: [f32; 2], size: f32, color: [f32; 4] },
+}
+
+/// # Painter
+///
+/// What a `Canvas`'s paint function draws with. Positions are in pixels from the top left of the canvas, with y
+/// going down, and everything painted is kept until the next repaint.
+pub struct Painter{
+    size: [f32; 2],
+    time: f32,
+    shapes: Vec<PaintShape>,
+}
+
+impl Painter{
+    /// Create a painter for a canvas `size` pixels big, `time` seconds after it was created
+    pub fn new(size: [f32; 2], time: f32) -> Self{
+        Self{
+            size,
+            time,
+            shapes: Vec::new(),
+        }
+    }
+
+    /// How big the canvas is, in pixels
+    pub fn get_size(&self) -> [f32; 2]{
+        self.size
+    }
+
+    /// How many seconds the canvas has been around for, for animating
+    pub fn get_time(&self) -> f32{
+        self.time
+    }
+
+    /// Fill a rectangle, with its top left corner at `pos`
+    pub fn fill_rect(&mut self, pos: [f32; 2], size: [f32; 2], color: [f32; 4]){
+        self.shapes.push(PaintShape::Rect{ pos, size, color });
+    }
+
+    /// Draw the outline of a rectangle, with its top left corner at `pos`, a pixel wide
+    pub fn stroke_rect(&mut self, pos: [f32; 2], size: [f32; 2], color: [f32; 4]){
+        let corners = [pos, [pos[0] + size[0], pos[1]], [pos[0] + size[0], pos[1] + size[1]], [pos[0], pos[1] + size[1]]];
+        for i in 0..corners.len(){
+            self.line(corners[i], corners[(i + 1) % corners.len()], color);
+        }
+    }
+
+    /// Draw a line a pixel wide
+    pub fn line(&mut self, from: [f32; 2], to: [f32; 2], color: [f32; 4]){
+        self.shapes.push(PaintShape::Line{ from, to, color });
+    }
+
+    /// Fill a circle
+    pub fn fill_circle(&mut self, center: [f32; 2], radius: f32, color: [f32; 4]){
+        self.shapes.push(PaintShape::Circle{ center, radius: radius.max(0.0), color });
+    }
+
+    /// Draw the outline of a circle, a pixel wide. Bigger circles get more segments, so they stay round.
+    pub fn stroke_circle(&mut self, center: [f32; 2], radius: f32, color: [f32; 4]){
+        let segments = ((radius * 0.5).ceil() as usize).clamp(12, 64);
+        let points = circle_points(center, radius, segments);
+        for pair in points.windows(2){
+            self.line(pair[0], pair[1], color);
+        }
+    }
+
+    /// Write some text, `size` pixels tall, with its top left corner at `pos`
+    pub fn text<S: Into<String>>(&mut self, text: S, pos: [f32; 2], size: f32, color: [f32; 4]){
+        self.shapes.push(PaintShape::Text{ text: text.into(), pos, size, color });
+    }
+
+    /// Everything painted so far, in order
+    pub fn get_shapes(&self) -> &[PaintShape]{
+        &self.shapes
+    }
+}
+
+/// # Canvas
+///
+/// A component for custom drawing - charts, minimaps, node graphs - without writing a `GUIComponent` of its own.
+/// Its paint function gets a `Painter` to fill rectangles and circles, draw lines and write text on, which the
+/// canvas turns into quads, `Lines` and glyph sections.
+///
+/// The paint function runs again when the canvas changes size or `request_repaint` is called, or every frame
+/// while it's animated (see `set_animated`), and what it painted is drawn until then. Rectangles and circles are
+/// drawn in the order they were painted, then the lines over them, all clipped to the canvas. Text always ends up
+/// on top of everything else in the layout, like all text does, and isn't clipped.
+///
+/// ```ignore
+/// let mut canvas = Canvas::new(Rect::new([0.0, 0.0], [200.0, 200.0]), &renderer);
+/// canvas.set_on_paint(Some(Box::new(|painter: &mut Painter| {
+///     let [width, height] = painter.get_size();
+///     painter.fill_circle([width / 2.0, height / 2.0], 40.0, [0.9, 0.3, 0.2, 1.0]);
+///     painter.line([0.0, height], [width, 0.0], [0.0, 0.0, 0.0, 1.0]);
+///     painter.text("Hello", [8.0, 8.0], 16.0, [0.0, 0.0, 0.0, 1.0]);
+/// })));
+/// canvas.set_animated(true);
+/// ```
+pub struct Canvas{
+    rect: Rect,
+    on_paint: Option<PaintCallback>,
+    animated: bool,
+    repaint: bool,
+    time: f32,
+    shapes: Vec<PaintShape>,
+    screen_dim: (u32, u32),
+
+    background: Quad,
+    quads: Vec<Quad>,
+    quad_count: usize, // how many of the quads are in use
+    lines: Vec<Lines>, // one for each colour of line
+    white: Rc<Texture>,
+    disc: Rc<Texture>,
+    enabled: bool,
+}
+
+impl Canvas{
+    /// Create a canvas filling `rect`, on a white background, with nothing painted on it
+    pub fn new(rect: Rect, renderer: &Renderer) -> Self{
+        let white = Rc::new(Texture::from_rgba(&[255, 255, 255, 255], (1, 1), "Canvas white", &renderer.device, &renderer.queue));
+        let disc = Rc::new(Texture::from_rgba(&disc_pixels(DISC_RESOLUTION), (DISC_RESOLUTION, DISC_RESOLUTION), "Canvas disc", &renderer.device, &renderer.queue));
+        let mut background = Quad::new(rect.pos, rect.size, [1.0, 1.0, 1.0, 1.0], &renderer.device);
+        background.set_texture(Some(white.clone()));
+        Self{
+            rect,
+            on_paint: None,
+            animated: false,
+            repaint: true,
+            time: 0.0,
+            shapes: Vec::new(),
+            screen_dim: (0, 0),
+
+            background,
+            quads: Vec::new(),
+            quad_count: 0,
+            lines: vec![Lines::new([0.0, 0.0, 0.0, 1.0], renderer)],
+            white,
+            disc,
+            enabled: true,
+        }
+    }
+
+    /// Set the function that paints the canvas, and repaint it
+    pub fn set_on_paint(&mut self, callback: Option<PaintCallback>){
+        self.on_paint = callback;
+        self.repaint = true;
+    }
+
+    /// Set whether the canvas is painted again every frame, for animations. It isn't by default.
+    pub fn set_animated(&mut self, animated: bool){
+        self.animated = animated;
+    }
+
+    pub fn is_animated(&self) -> bool{
+        self.animated
+    }
+
+    /// Paint the canvas again on the next frame, like when whatever it shows has changed
+    pub fn request_repaint(&mut self){
+        self.repaint = true;
+    }
+
+    /// What was painted last time, in order
+    pub fn get_shapes(&self) -> &[PaintShape]{
+        &self.shapes
+    }
+
+    pub fn set_background_color(&mut self, color: [f32; 4]){
+        self.background.set_color(color);
+    }
+
+    pub fn set_rect(&mut self, rect: Rect){
+        self.rect = rect;
+        self.background.set_pos(rect.pos);
+        self.background.set_size(rect.size);
+        self.repaint = true;
+    }
+
+    pub fn get_rect(&self) -> Rect{
+        self.rect
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+    }
+
+    // Where a point on the canvas is, measured from the centre of the screen
+    fn to_screen(&self, point: [f32; 2]) -> [f32; 2]{
+        [point[0] + self.rect.pos[0] - self.rect.size[0] / 2.0, point[1] + self.rect.pos[1] - self.rect.size[1] / 2.0]
+    }
+
+    // Run the paint function, and turn what it painted into quads and lines
+    fn paint(&mut self, device: &wgpu::Device){
+        let mut painter = Painter::new(self.rect.size, self.time);
+        if let Some(callback) = &mut self.on_paint{
+            callback(&mut painter);
+        }
+        self.shapes = painter.shapes;
+
+        let mut count = 0;
+        let mut segments: Vec<LineBatch> = Vec::new();
+        for shape in self.shapes.iter(){
+            let (pos, size, color, texture) = match shape{
+                PaintShape::Rect{ pos, size, color } => {
+                    let center = [pos[0] + size[0] / 2.0, pos[1] + size[1] / 2.0];
+                    (self.to_screen(center), [size[0].abs(), size[1].abs()], *color, &self.white)
+                }
+                PaintShape::Circle{ center, radius, color } => (self.to_screen(*center), [radius * 2.0; 2], *color, &self.disc),
+                PaintShape::Line{ from, to, color } => {
+                    let segment = [self.to_screen(*from), self.to_screen(*to)];
+                    match segments.iter_mut().find(|(line_color, _)| line_color == color){
+                        Some((_, list)) => list.push(segment),
+                        None => segments.push((*color, vec![segment])),
+                    }
+                    continue;
+                }
+                PaintShape::Text{ .. } => continue,
+            };
+            if count == self.quads.len(){
+                self.quads.push(Quad::new([0.0, 0.0], [0.0, 0.0], color, device));
+            }
+            let quad = &mut self.quads[count];
+            quad.set_pos(pos);
+            quad.set_size(size);
+            quad.set_color(color);
+            quad.set_texture(Some(texture.clone()));
+            count += 1;
+        }
+        self.quad_count = count;
+
+        while self.lines.len() < segments.len(){
+            let lines = self.lines[0].empty_like([0.0, 0.0, 0.0, 1.0], device);
+            self.lines.push(lines);
+        }
+        for (i, lines) in self.lines.iter_mut().enumerate(){
+            match segments.get(i){
+                Some((color, list)) => {
+                    lines.set_color(*color);
+                    lines.set_segments(list);
+                }
+                None => lines.clear(),
+            }
+            lines.rebuild(device);
+        }
+    }
+}
+
+impl GUIComponent for Canvas{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if !self.enabled{
+            return;
+        }
+        self.background.render(render_pass);
+        // The scissor rect is in window pixels, and has to stay inside the window
+        let (width, height) = (self.screen_dim.0 as f32, self.screen_dim.1 as f32);
+        let left = (self.rect.pos[0] - self.rect.size[0] / 2.0 + width / 2.0).clamp(0.0, width);
+        let right = (self.rect.pos[0] + self.rect.size[0] / 2.0 + width / 2.0).clamp(0.0, width);
+        let top = (self.rect.pos[1] - self.rect.size[1] / 2.0 + height / 2.0).clamp(0.0, height);
+        let bottom = (self.rect.pos[1] + self.rect.size[1] / 2.0 + height / 2.0).clamp(0.0, height);
+        if right <= left || bottom <= top{
+            return;
+        }
+        render_pass.set_scissor_rect(left as u32, top as u32, (right - left) as u32, (bottom - top) as u32);
+        for quad in self.quads.iter().take(self.quad_count){
+            quad.render(render_pass);
+        }
+        // Lines sample the bound texture too, so leave a plain one bound for them and whatever comes next
+        render_pass.set_bind_group(3, &self.white.bind_group, &[]);
+        for lines in self.lines.iter(){
+            lines.render(render_pass);
+        }
+        render_pass.set_scissor_rect(0, 0, self.screen_dim.0, self.screen_dim.1);
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if !self.enabled{
+            return;
+        }
+        let half = [self.screen_dim.0 as f32 / 2.0, self.screen_dim.1 as f32 / 2.0];
+        for shape in self.shapes.iter(){
+            if let PaintShape::Text{ text, pos, size, color } = shape{
+                let pos = self.to_screen(*pos);
+                brush.queue(wgpu_glyph::Section {
+                    screen_position: (pos[0] + half[0], pos[1] + half[1]),
+                    text: vec![wgpu_glyph::Text::new(text.as_str()).with_color(*color).with_scale(wgpu_glyph::ab_glyph::PxScale::from(*size))],
+                    ..wgpu_glyph::Section::default()
+                });
+            }
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.rect.pos
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(self.rect)
+    }
+
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{
+        Some(self.rect.size)
+    }
+
+    fn arrange(&mut self, rect: Rect){
+        if rect != self.rect{
+            self.set_rect(rect);
+        }
+    }
+
+    /// Repaint if it's due, making quads and lines for what was painted
+    fn rebuild(&mut self, device: &wgpu::Device) -> bool{
+        if self.enabled && (self.repaint || self.animated){
+            self.repaint = false;
+            self.paint(device);
+        }
+        false
+    }
+
+    fn update(&mut self, dt: f32){
+        self.time += dt;
+    }
+
+    fn is_animating(&self) -> bool{
+        self.enabled && (self.animated || self.repaint)
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.screen_dim = screen_dim;
+        self.background.prepare(queue, screen_dim);
+        for quad in self.quads.iter_mut().take(self.quad_count){
+            quad.prepare(queue, screen_dim);
+        }
+        for lines in self.lines.iter_mut(){
+            lines.prepare(queue, screen_dim);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Test that the points around a circle start and end on its right, and stay on the circle
+    #[test]
+    fn circle_points_test(){
+        let points = circle_points([10.0, 20.0], 5.0, 4);
+        assert_eq!(points.len(), 5);
+        assert_eq!(points[0], [15.0, 20.0]);
+        assert_eq!(points[0], points[4]);
+        assert!((points[1][0] - 10.0).abs() < 0.001 && (points[1][1] - 25.0).abs() < 0.001);
+        for point in points.iter(){
+            let distance = ((point[0] - 10.0).powi(2) + (point[1] - 20.0).powi(2)).sqrt();
+            assert!((distance - 5.0).abs() < 0.001);
+        }
+
+        // Fewer than 3 segments can't go round a circle
+        assert_eq!(circle_points([0.0, 0.0], 1.0, 1).len(), 4);
+    }
+}
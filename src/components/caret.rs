@@ -0,0 +1,205 @@
+//! This module contains the caret (text cursor) used by text editing components.
+//! The caret blinks using the frame clock, and can be drawn as a bar, a block or an underline.
+//! It can also glide smoothly to its new position instead of jumping there.
+
+use wgpu::Device;
+
+use crate::rendering::Quad;
+
+/// The shape the caret gets drawn as
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CaretStyle{
+    /// A thin vertical line before the character
+    Bar,
+    /// A box covering the whole character
+    Block,
+    /// A thin line underneath the character
+    Underline,
+}
+
+/// # Caret
+///
+/// The blinking text cursor. It isn't a component on its own - text editing components
+/// own a caret, and forward `update`, `prepare` and `render` to it.
+///
+/// The position of the caret is the top left corner of the character it sits on, in the same
+/// space as a `Quad` (pixels from the centre of the screen).
+pub struct Caret{
+    quad: Quad,
+    style: CaretStyle,
+    width: f32, // thickness of the bar/underline
+    line_height: f32,
+    char_width: f32, // width of the block/underline
+
+    blink_rate: f32, // seconds the caret stays on (and off) for, 0 disables blinking
+    blink_timer: f32,
+    blink_on: bool,
+    enabled: bool,
+
+    smooth: bool,
+    smooth_speed: f32,
+    pos: [f32; 2],
+    target: [f32; 2],
+}
+
+impl Caret{
+    /// Create a new caret. Defaults to a 2px black bar, blinking every 0.53 seconds (the Windows default).
+    pub fn new(pos: [f32; 2], line_height: f32, device: &Device) -> Self{
+        let mut caret = Self{
+            quad: Quad::new(pos, [0.0, 0.0], [0.0, 0.0, 0.0, 1.0], device),
+            style: CaretStyle::Bar,
+            width: 2.0,
+            line_height,
+            char_width: line_height / 2.0,
+
+            blink_rate: 0.53,
+            blink_timer: 0.0,
+            blink_on: true,
+            enabled: true,
+
+            smooth: false,
+            smooth_speed: 20.0,
+            pos,
+            target: pos,
+        };
+        caret.update_quad();
+        caret
+    }
+
+    pub fn set_style(&mut self, style: CaretStyle){
+        self.style = style;
+        self.update_quad();
+    }
+
+    pub fn get_style(&self) -> CaretStyle{
+        self.style
+    }
+
+    /// Set the thickness of the bar and underline styles, in pixels
+    pub fn set_width(&mut self, width: f32){
+        self.width = width;
+        self.update_quad();
+    }
+
+    /// Set the height of a line of text, in pixels
+    pub fn set_line_height(&mut self, line_height: f32){
+        self.line_height = line_height;
+        self.update_quad();
+    }
+
+    /// Set the width of the character under the caret, used by the block and underline styles
+    pub fn set_char_width(&mut self, char_width: f32){
+        self.char_width = char_width;
+        self.update_quad();
+    }
+
+    pub fn set_color(&mut self, color: [f32; 4]){
+        self.quad.set_color(color);
+    }
+
+    /// Set how long the caret stays visible (and hidden) for, in seconds. Use 0 to stop it blinking.
+    pub fn set_blink_rate(&mut self, blink_rate: f32){
+        self.blink_rate = blink_rate.max(0.0);
+        self.reset_blink();
+    }
+
+    /// Enable or disable smooth movement. The speed is how quickly the caret catches up,
+    /// higher is faster.
+    pub fn set_smooth(&mut self, smooth: bool, speed: f32){
+        self.smooth = smooth;
+        self.smooth_speed = speed;
+    }
+
+    /// Move the caret. If smooth movement is enabled, the caret glides there over the next few frames.
+    ///
+    /// This also makes the caret visible again, so it doesn't disappear while typing.
+    pub fn move_to(&mut self, pos: [f32; 2]){
+        self.target = pos;
+        if !self.smooth{
+            self.pos = pos;
+        }
+        self.reset_blink();
+        self.update_quad();
+    }
+
+    /// Get where the caret is heading
+    pub fn get_pos(&self) -> [f32; 2]{
+        self.target
+    }
+
+    /// Show the caret and restart the blink cycle
+    pub fn reset_blink(&mut self){
+        self.blink_timer = 0.0;
+        self.blink_on = true;
+    }
+
+    /// Show the caret, for example when the text component gains focus
+    pub fn enable(&mut self){
+        self.enabled = true;
+        self.reset_blink();
+    }
+
+    /// Hide the caret, for example when the text component loses focus
+    pub fn disable(&mut self){
+        self.enabled = false;
+    }
+
+    pub fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    /// Check if the caret is currently drawn (enabled, and in the visible part of the blink)
+    pub fn is_visible(&self) -> bool{
+        self.enabled && self.blink_on
+    }
+
+    /// Advance the blink and movement animations
+    pub fn update(&mut self, dt: f32){
+        if self.blink_rate > 0.0{
+            self.blink_timer += dt;
+            while self.blink_timer >= self.blink_rate{
+                self.blink_timer -= self.blink_rate;
+                self.blink_on = !self.blink_on;
+            }
+        }
+
+        if self.pos != self.target{
+            // Exponential smoothing, so the movement doesn't depend on the frame rate
+            let t = 1.0 - (-self.smooth_speed * dt).exp();
+            self.pos[0] += (self.target[0] - self.pos[0]) * t;
+            self.pos[1] += (self.target[1] - self.pos[1]) * t;
+
+            if (self.target[0] - self.pos[0]).abs() < 0.5 && (self.target[1] - self.pos[1]).abs() < 0.5{
+                self.pos = self.target;
+            }
+            self.update_quad();
+        }
+    }
+
+    /// True while the caret is still moving towards its target
+    pub fn is_animating(&self) -> bool{
+        self.enabled && self.pos != self.target
+    }
+
+    pub fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.quad.prepare(queue, screen_dim);
+    }
+
+    pub fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>) where 'a: 'b{
+        if self.is_visible(){
+            self.quad.render(render_pass);
+        }
+    }
+
+    // Work out the rectangle of the caret from its style
+    fn update_quad(&mut self){
+        let (offset, size) = match self.style{
+            CaretStyle::Bar => ([self.width / 2.0, self.line_height / 2.0], [self.width, self.line_height]),
+            CaretStyle::Block => ([self.char_width / 2.0, self.line_height / 2.0], [self.char_width, self.line_height]),
+            CaretStyle::Underline => ([self.char_width / 2.0, self.line_height - self.width / 2.0], [self.char_width, self.width]),
+        };
+
+        self.quad.set_pos([self.pos[0] + offset[0], self.pos[1] + offset[1]]);
+        self.quad.set_size(size);
+    }
+}
@@ -0,0 +1,644 @@
+//! This module contains the `ChangelogPanel` component, a modal popup showing a version's patch notes the first time
+//! it's run, and the simple Markdown reader it shows them with.
+
+use std::{any::Any, sync::mpsc::{self, Receiver, TryRecvError}};
+
+use wgpu_glyph::{HorizontalAlign, VerticalAlign};
+
+use crate::{input::{InputState, Key, KeyEvent, MouseButton, MouseEvent, UiEvent}, rendering::{Quad, Renderer, TextBrush, measure_text}};
+
+use super::{EventGUIComponent, KineticScroll, Label, Orientation, Rect, Scrollbar, TextGUIComponent};
+
+// The space around the notes and the close button, in pixels
+const PADDING: f32 = 16.0;
+// How much bigger each level of heading is than the rest of the text
+const HEADING_SCALES: [f32; 3] = [1.6, 1.3, 1.1];
+// How far bullet points are indented, and how big their dots are
+const BULLET_INDENT: f32 = 18.0;
+const BULLET_SIZE: f32 = 5.0;
+// The space between blocks of text
+const BLOCK_GAP: f32 = 6.0;
+// How far the arrow keys scroll the notes
+const KEY_SCROLL: f32 = 40.0;
+
+type FetchResult = Result<String, &'static str>;
+type SeenCallback = Box<dyn Fn(&str)>;
+
+/// A block of patch notes, as read from the Markdown given to `ChangelogPanel::set_notes`
+#[derive(Clone, Debug, PartialEq)]
+pub enum ChangelogBlock{
+    /// A heading, from level 1 (`# `) to 3 (`### ` and smaller)
+    Heading(usize, String),
+    Bullet(String),
+    Paragraph(String),
+}
+
+impl ChangelogBlock{
+    pub fn get_text(&self) -> &str{
+        match self{
+            ChangelogBlock::Heading(_, text) | ChangelogBlock::Bullet(text) | ChangelogBlock::Paragraph(text) => text,
+        }
+    }
+}
+
+/// Whether the patch notes for `version` should be shown, given the version they were last shown for (`None` if
+/// they never have been, like on the first run after installing)
+pub(crate) fn changelog_due(version: &str, last_seen: Option<&str>) -> bool{
+    last_seen != Some(version)
+}
+
+// Take out the Markdown for bold, italics, code and links, which are all drawn as plain text
+fn strip_inline(text: &str) -> String{
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(c) = rest.chars().next(){
+        if rest.starts_with("**") || rest.starts_with("__"){
+            rest = &rest[2..];
+        }else if c == '`'{
+            rest = &rest[1..];
+        }else if c == '['{
+            // A link keeps its text and loses the address. It's only a link if the `]` closing this bracket has the
+            // address right after it.
+            let link = rest.find(']')
+                .filter(|middle| rest[middle + 1..].starts_with('('))
+                .and_then(|middle| rest[middle..].find(')').map(|end| (middle, middle + end)));
+            match link{
+                Some((middle, end)) => {
+                    out.push_str(&rest[1..middle]);
+                    rest = &rest[end + 1..];
+                }
+                None => {
+                    out.push(c);
+                    rest = &rest[1..];
+                }
+            }
+        }else{
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    out
+}
+
+/// Read patch notes written in (a small part of) Markdown. Lines starting with `#`s are headings, lines starting
+/// with `- `, `* ` or `+ ` are bullet points, and the other lines are paragraphs, which run on until a blank line
+/// or another block. Bold, italic and code markers (`**`, `__`, `` ` ``) are taken out, links keep just their text,
+/// and rules (`---`) are skipped.
+pub(crate) fn parse_changelog(markdown: &str) -> Vec<ChangelogBlock>{
+    let mut blocks = Vec::new();
+    // Whether the block before this line can be carried on by it
+    let mut open = false;
+    for line in markdown.lines(){
+        let trimmed = line.trim();
+        if trimmed.is_empty() || (trimmed.len() >= 3 && trimmed.chars().all(|c| c == '-')){
+            open = false;
+            continue;
+        }
+
+        let hashes = trimmed.chars().take_while(|c| *c == '#').count();
+        if hashes > 0 && trimmed[hashes..].starts_with(' '){
+            blocks.push(ChangelogBlock::Heading(hashes.min(3), strip_inline(trimmed[hashes..].trim())));
+            open = false;
+        }else if let Some(item) = trimmed.strip_prefix("- ").or_else(|| trimmed.strip_prefix("* ")).or_else(|| trimmed.strip_prefix("+ ")){
+            blocks.push(ChangelogBlock::Bullet(strip_inline(item.trim())));
+            open = true;
+        }else{
+            let text = strip_inline(trimmed);
+            match blocks.last_mut(){
+                Some(ChangelogBlock::Bullet(last)) | Some(ChangelogBlock::Paragraph(last)) if open => {
+                    last.push(' ');
+                    last.push_str(&text);
+                }
+                _ => blocks.push(ChangelogBlock::Paragraph(text)),
+            }
+            open = true;
+        }
+    }
+    blocks
+}
+
+// A block of the notes, laid out down the panel
+struct NotesBlock{
+    label: Label,
+    size: f32,
+    indent: f32,
+    dot: Option<Quad>, // bullet points have a dot in front
+    offset: f32, // the top of the block, down from the top of the notes
+    height: f32,
+    shown: bool, // only blocks that fit in the view are drawn, as text can't be clipped
+}
+
+/// # ChangelogPanel
+///
+/// A modal popup showing the patch notes for a new version of a game or app, written in Markdown (headings,
+/// bullet points and paragraphs), in a scrolling view with a close button underneath. Like a `Dialog`, it darkens everything
+/// behind it and captures all input while it's open, and belongs in the overlay layer
+/// (`Layout::add_overlay_component`).
+///
+/// The notes can be given straight away with `set_notes`, or fetched with `fetch`, which runs a function (like
+/// an HTTP request) on another thread and shows the notes once it returns.
+///
+/// The panel only needs showing once per version. The app keeps the version the notes were last seen for with
+/// its other settings, passes it to `open_if_new` at startup, and stores the new one when the seen callback runs:
+///
+/// ```ignore
+/// let mut changelog = ChangelogPanel::new(env!("CARGO_PKG_VERSION"), 16.0, &renderer);
+/// changelog.fetch(|| download_patch_notes());
+/// changelog.set_on_seen(Some(Box::new(|version| settings.set("last_seen_version", version))));
+/// changelog.open_if_new(settings.get("last_seen_version"));
+/// layout.add_overlay_component(Box::new(changelog), None);
+/// ```
+///
+/// Escape, enter and the close button close it, and the wheel, the scrollbar and the arrow keys scroll the notes.
+/// Text can't be clipped, so each block of text is only drawn while all of it fits in the view.
+pub struct ChangelogPanel{
+    version: String,
+    text_size: f32,
+    size: [f32; 2],
+    notes: Vec<ChangelogBlock>,
+    blocks: Vec<NotesBlock>,
+    content_height: f32,
+    measured: bool,
+    fetching: Option<Receiver<FetchResult>>,
+    on_seen: Option<SeenCallback>,
+
+    shade: Quad,
+    border: Quad,
+    panel: Quad,
+    title: Label,
+    status: Label, // shown instead of the notes while they're loading, or if they couldn't be
+    close_button: Quad,
+    close_label: Label,
+    scroll: KineticScroll,
+    scrollbar: Scrollbar,
+
+    input: InputState,
+    pressed: bool, // whether the mouse went down on the close button
+    screen_dim: (u32, u32),
+    open: bool,
+}
+
+impl ChangelogPanel{
+    /// Create a closed panel for the patch notes of `version`, without any notes yet
+    pub fn new(version: &str, text_size: f32, renderer: &Renderer) -> Self{
+        let mut title = Label::new("", text_size * HEADING_SCALES[0], [0.0, 0.0]);
+        title.set_text(format!("What's new in {}", version));
+        title.align_vertical(VerticalAlign::Center);
+        let mut status = Label::new("", text_size, [0.0, 0.0]);
+        status.align_horizontal(HorizontalAlign::Center);
+        status.align_vertical(VerticalAlign::Center);
+        let mut close_label = Label::new("Close", text_size, [0.0, 0.0]);
+        close_label.align_horizontal(HorizontalAlign::Center);
+        close_label.align_vertical(VerticalAlign::Center);
+
+        let mut panel = Self{
+            version: version.to_string(),
+            text_size,
+            size: [520.0, 440.0],
+            notes: Vec::new(),
+            blocks: Vec::new(),
+            content_height: 0.0,
+            measured: false,
+            fetching: None,
+            on_seen: None,
+
+            shade: Quad::new([0.0, 0.0], [0.0, 0.0], [0.0, 0.0, 0.0, 0.5], &renderer.device),
+            border: Quad::new([0.0, 0.0], [0.0, 0.0], [0.3, 0.3, 0.3, 1.0], &renderer.device),
+            panel: Quad::new([0.0, 0.0], [0.0, 0.0], [1.0, 1.0, 1.0, 1.0], &renderer.device),
+            title,
+            status,
+            close_button: Quad::new([0.0, 0.0], [0.0, 0.0], [0.85, 0.85, 0.85, 1.0], &renderer.device),
+            close_label,
+            scroll: KineticScroll::new(),
+            scrollbar: Scrollbar::new([0.0, 0.0], 0.0, Orientation::Vertical, renderer),
+
+            input: InputState::new(),
+            pressed: false,
+            screen_dim: (0, 0),
+            open: false,
+        };
+        panel.place();
+        panel
+    }
+
+    /// Show patch notes written in Markdown, scrolled to the top
+    pub fn set_notes(&mut self, markdown: &str){
+        self.fetching = None;
+        self.status.set_text("");
+        self.notes = parse_changelog(markdown);
+        let text_size = self.text_size;
+        self.blocks = self.notes.iter().map(|block| {
+            let (size, indent) = match block{
+                ChangelogBlock::Heading(level, _) => (text_size * HEADING_SCALES[(*level).clamp(1, 3) - 1], 0.0),
+                ChangelogBlock::Bullet(_) => (text_size, BULLET_INDENT),
+                ChangelogBlock::Paragraph(_) => (text_size, 0.0),
+            };
+            let mut label = Label::new(block.get_text(), size, [0.0, 0.0]);
+            label.align_vertical(VerticalAlign::Top);
+            NotesBlock{
+                label,
+                size,
+                indent,
+                dot: None,
+                offset: 0.0,
+                height: 0.0,
+                shown: false,
+            }
+        }).collect();
+        self.measured = false;
+        self.scroll.set_offset(0.0);
+    }
+
+    /// Fetch the notes by running `fetcher` on another thread, showing that they're loading until it returns. If it
+    /// fails, its error is shown instead.
+    pub fn fetch<F: FnOnce() -> FetchResult + Send + 'static>(&mut self, fetcher: F){
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            // The panel may be gone by the time the notes are, which is fine
+            let _ = sender.send(fetcher());
+        });
+        self.fetching = Some(receiver);
+        self.notes.clear();
+        self.blocks.clear();
+        self.status.set_text("Loading patch notes...");
+    }
+
+    /// Whether the notes are still being fetched
+    pub fn is_fetching(&self) -> bool{
+        self.fetching.is_some()
+    }
+
+    /// The notes being shown
+    pub fn get_notes(&self) -> &[ChangelogBlock]{
+        &self.notes
+    }
+
+    pub fn get_version(&self) -> &str{
+        &self.version
+    }
+
+    /// Set the function to run with the version once the notes have been seen and closed, to store it for next time
+    pub fn set_on_seen(&mut self, callback: Option<SeenCallback>){
+        self.on_seen = callback;
+    }
+
+    /// Open the panel if the notes weren't last seen for this version (or have never been seen, with `None`),
+    /// returning whether it opened
+    pub fn open_if_new(&mut self, last_seen: Option<&str>) -> bool{
+        if changelog_due(&self.version, last_seen){
+            self.open();
+        }
+        self.open
+    }
+
+    pub fn open(&mut self){
+        self.open = true;
+        self.pressed = false;
+    }
+
+    /// Close the panel, running the seen callback
+    pub fn close(&mut self){
+        if !self.open{
+            return;
+        }
+        self.open = false;
+        if let Some(callback) = &self.on_seen{
+            callback(&self.version);
+        }
+    }
+
+    pub fn is_open(&self) -> bool{
+        self.open
+    }
+
+    /// Change the title, which is "What's new in" and the version by default
+    pub fn set_title(&mut self, title: &str){
+        self.title.set_text(title);
+    }
+
+    /// Set the size of the panel, in pixels. It's 520 by 440 by default.
+    pub fn set_size(&mut self, size: [f32; 2]){
+        self.size = size;
+        self.measured = false;
+        self.place();
+    }
+
+    pub fn get_size(&self) -> [f32; 2]{
+        self.size
+    }
+
+    /// Set the colours of the shade over the rest of the GUI, the panel, and the close button
+    pub fn set_colors(&mut self, shade: [f32; 4], panel: [f32; 4], button: [f32; 4]){
+        self.shade.set_color(shade);
+        self.panel.set_color(panel);
+        self.close_button.set_color(button);
+    }
+
+    // The part of the panel the notes scroll through
+    fn view_rect(&self) -> Rect{
+        let top = -self.size[1] / 2.0 + PADDING * 2.0 + self.title_height();
+        let bottom = self.size[1] / 2.0 - PADDING * 2.0 - self.button_size()[1];
+        Rect::new([0.0, (top + bottom) / 2.0], [self.size[0] - PADDING * 2.0, (bottom - top).max(0.0)])
+    }
+
+    fn title_height(&self) -> f32{
+        self.text_size * HEADING_SCALES[0]
+    }
+
+    fn button_size(&self) -> [f32; 2]{
+        [100.0, self.text_size + PADDING]
+    }
+
+    fn is_scrollable(&self) -> bool{
+        self.content_height > self.view_rect().size[1]
+    }
+
+    fn place(&mut self){
+        self.border.set_size([self.size[0] + 2.0, self.size[1] + 2.0]);
+        self.panel.set_size(self.size);
+        let button_size = self.button_size();
+        self.close_button.set_pos([self.size[0] / 2.0 - PADDING - button_size[0] / 2.0, self.size[1] / 2.0 - PADDING - button_size[1] / 2.0]);
+        self.close_button.set_size(button_size);
+
+        let view = self.view_rect();
+        self.scroll.set_content(self.content_height, view.size[1]);
+        self.scrollbar.set_content(self.content_height, view.size[1]);
+        self.scrollbar.set_length(view.size[1]);
+        self.scrollbar.set_pos([view.pos[0] + view.size[0] / 2.0 - self.scrollbar.get_thickness() / 2.0, view.pos[1]]);
+        self.scrollbar.set_value(self.scroll.get_offset().max(0.0));
+        self.place_blocks();
+    }
+
+    // Move the blocks to the scroll offset, working out which ones fit in the view
+    fn place_blocks(&mut self){
+        let view = self.view_rect();
+        let [left, top] = [view.pos[0] - view.size[0] / 2.0, view.pos[1] - view.size[1] / 2.0];
+        let offset = self.scroll.get_offset();
+        for block in self.blocks.iter_mut(){
+            let y = block.offset - offset;
+            block.shown = self.measured && y >= -0.5 && y + block.height <= view.size[1] + 0.5;
+            if let Some(dot) = &mut block.dot{
+                dot.set_pos([left + BULLET_INDENT / 2.0, top + y + block.size * 0.6]);
+            }
+        }
+    }
+
+    // Scroll with the wheel, the scrollbar and the arrow keys, and close with the button and keys
+    fn handle_input(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        let mut scrolled = false;
+        match self.input.handle_event(event, window){
+            Some(UiEvent::Scroll{ delta, pos, .. }) if self.is_scrollable() && self.view_rect().contains(pos) => {
+                self.scroll.scroll_by(-delta[1]);
+                scrolled = true;
+            }
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed, pos, .. })) => {
+                let over = Rect::new(self.close_button.get_pos(), self.button_size()).contains(pos);
+                if pressed{
+                    self.pressed = over;
+                }else{
+                    if self.pressed && over{
+                        self.close();
+                    }
+                    self.pressed = false;
+                }
+            }
+            Some(UiEvent::Key(KeyEvent{ key, pressed: true, .. })) => match key{
+                Key::Escape | Key::Enter => self.close(),
+                Key::Up | Key::Down if self.is_scrollable() => {
+                    self.scroll.scroll_by(if key == Key::Up { -KEY_SCROLL } else { KEY_SCROLL });
+                    scrolled = true;
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+
+        if self.open && self.is_scrollable(){
+            let value = self.scrollbar.get_value();
+            self.scrollbar.handle_event_callback(event, window);
+            if self.scrollbar.get_value() != value{
+                self.scroll.set_offset(self.scrollbar.get_value());
+                scrolled = true;
+            }
+        }
+        if scrolled{
+            self.scrollbar.set_value(self.scroll.get_offset().max(0.0));
+            self.place_blocks();
+        }
+    }
+}
+
+impl EventGUIComponent for ChangelogPanel{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if !self.open{
+            return;
+        }
+        self.shade.render(render_pass);
+        self.border.render(render_pass);
+        self.panel.render(render_pass);
+        self.close_button.render(render_pass);
+
+        // The dots are clipped to the view, so they can scroll in and out smoothly. The scissor rect is in window
+        // pixels, and has to stay inside the window.
+        let view = self.view_rect();
+        let (width, height) = (self.screen_dim.0 as f32, self.screen_dim.1 as f32);
+        let left = (view.pos[0] - view.size[0] / 2.0 + width / 2.0).clamp(0.0, width);
+        let right = (view.pos[0] + view.size[0] / 2.0 + width / 2.0).clamp(0.0, width);
+        let top = (view.pos[1] - view.size[1] / 2.0 + height / 2.0).clamp(0.0, height);
+        let bottom = (view.pos[1] + view.size[1] / 2.0 + height / 2.0).clamp(0.0, height);
+        if right > left && bottom > top{
+            render_pass.set_scissor_rect(left as u32, top as u32, (right - left) as u32, (bottom - top) as u32);
+            for dot in self.blocks.iter().filter_map(|block| block.dot.as_ref()){
+                dot.render(render_pass);
+            }
+            render_pass.set_scissor_rect(0, 0, self.screen_dim.0, self.screen_dim.1);
+        }
+        if self.is_scrollable(){
+            self.scrollbar.render(render_pass);
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if !self.open{
+            return;
+        }
+        TextGUIComponent::render_text(&self.title, brush);
+        TextGUIComponent::render_text(&self.close_label, brush);
+        if !self.status.get_text().is_empty(){
+            TextGUIComponent::render_text(&self.status, brush);
+        }
+        for block in self.blocks.iter().filter(|block| block.shown){
+            TextGUIComponent::render_text(&block.label, brush);
+        }
+    }
+
+    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        if self.open{
+            self.handle_input(event, window);
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.open
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        [0.0, 0.0]
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(Rect::new([0.0, 0.0], self.size))
+    }
+
+    fn is_modal(&self) -> bool{
+        self.open
+    }
+
+    fn update(&mut self, dt: f32){
+        let result = match &self.fetching{
+            Some(receiver) => match receiver.try_recv(){
+                Ok(result) => Some(result),
+                Err(TryRecvError::Disconnected) => Some(Err("Couldn't load the patch notes")),
+                Err(TryRecvError::Empty) => None,
+            },
+            None => None,
+        };
+        match result{
+            Some(Ok(markdown)) => self.set_notes(&markdown),
+            Some(Err(error)) => {
+                self.fetching = None;
+                self.status.set_text(error);
+            }
+            None => {}
+        }
+
+        let offset = self.scroll.get_offset();
+        self.scroll.update(dt);
+        if self.scroll.get_offset() != offset{
+            self.scrollbar.set_value(self.scroll.get_offset().max(0.0));
+            self.place_blocks();
+        }
+    }
+
+    /// Frames keep coming while the notes are being fetched, to pick them up when they arrive
+    fn is_animating(&self) -> bool{
+        self.open && (self.fetching.is_some() || self.scroll.is_animating())
+    }
+
+    /// Measure how tall each block wraps to, and stack them up
+    fn layout_text(&mut self, brush: &mut TextBrush){
+        if self.measured{
+            return;
+        }
+        let width = self.view_rect().size[0] - self.scrollbar.get_thickness() - PADDING / 2.0;
+        let mut offset = 0.0;
+        for (block, note) in self.blocks.iter_mut().zip(self.notes.iter()){
+            let max_width = (width - block.indent).max(1.0);
+            block.label.set_max_width(Some(max_width));
+            block.height = measure_text(brush, block.label.get_text(), block.size, wgpu_glyph::FontId::default(), Some(max_width))[1].max(block.size);
+            // Headings get some room above them, apart from at the very top
+            if let ChangelogBlock::Heading(..) = note{
+                if offset > 0.0{
+                    offset += block.size / 2.0;
+                }
+            }
+            block.offset = offset;
+            offset += block.height + BLOCK_GAP;
+        }
+        self.content_height = (offset - BLOCK_GAP).max(0.0);
+        self.measured = true;
+        self.place();
+    }
+
+    fn rebuild(&mut self, device: &wgpu::Device) -> bool{
+        for (block, note) in self.blocks.iter_mut().zip(self.notes.iter()){
+            if block.dot.is_none() && matches!(note, ChangelogBlock::Bullet(_)){
+                block.dot = Some(Quad::new([0.0, 0.0], [BULLET_SIZE, BULLET_SIZE], [0.2, 0.2, 0.2, 1.0], device));
+            }
+        }
+        false
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        if self.screen_dim != screen_dim{
+            self.screen_dim = screen_dim;
+            self.place_blocks();
+        }
+        // The shade covers the whole screen, whatever size it is
+        self.shade.set_size([screen_dim.0 as f32, screen_dim.1 as f32]);
+        self.shade.prepare(queue, screen_dim);
+        self.border.prepare(queue, screen_dim);
+        self.panel.prepare(queue, screen_dim);
+        self.close_button.prepare(queue, screen_dim);
+        self.scrollbar.prepare(queue, screen_dim);
+
+        let view = self.view_rect();
+        let [left, top] = [view.pos[0] - view.size[0] / 2.0, view.pos[1] - view.size[1] / 2.0];
+        self.title.set_pos([-self.size[0] / 2.0 + PADDING, -self.size[1] / 2.0 + PADDING + self.title_height() / 2.0], screen_dim);
+        self.status.set_pos(view.pos, screen_dim);
+        self.close_label.set_pos(self.close_button.get_pos(), screen_dim);
+        let offset = self.scroll.get_offset();
+        for block in self.blocks.iter_mut(){
+            block.label.set_pos([left + block.indent, top + block.offset - offset], screen_dim);
+            if let Some(dot) = &mut block.dot{
+                dot.prepare(queue, screen_dim);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Test that patch notes are split into headings, bullet points and paragraphs, with the inline Markdown taken out
+    #[test]
+    fn parse_changelog_test(){
+        let notes = "# 1.2.0\n\nThe **big** update.\nNow with `more`.\n\n## Fixes\n- Fixed [the crash](https://example.com/1)\n  on startup\n* Faster loading\n---\n#hashtag";
+        assert_eq!(parse_changelog(notes), vec![
+            ChangelogBlock::Heading(1, "1.2.0".to_string()),
+            ChangelogBlock::Paragraph("The big update. Now with more.".to_string()),
+            ChangelogBlock::Heading(2, "Fixes".to_string()),
+            ChangelogBlock::Bullet("Fixed the crash on startup".to_string()),
+            ChangelogBlock::Bullet("Faster loading".to_string()),
+            ChangelogBlock::Paragraph("#hashtag".to_string()),
+        ]);
+
+        // Deep headings are drawn like level 3 ones, and a lone bracket isn't a link
+        assert_eq!(parse_changelog("##### Small\n[not a link"), vec![
+            ChangelogBlock::Heading(3, "Small".to_string()),
+            ChangelogBlock::Paragraph("[not a link".to_string()),
+        ]);
+        assert!(parse_changelog("\n\n").is_empty());
+    }
+
+    /// Test that a bracket that isn't a link doesn't swallow a real link after it
+    #[test]
+    fn strip_inline_test(){
+        assert_eq!(strip_inline("[x] done, see [a](b)"), "[x] done, see a");
+        assert_eq!(strip_inline("[a] (b)"), "[a] (b)");
+        assert_eq!(strip_inline("[a](b"), "[a](b");
+    }
+
+    /// Test that the notes are due for any version they weren't last seen for
+    #[test]
+    fn changelog_due_test(){
+        assert!(changelog_due("1.2.0", None));
+        assert!(changelog_due("1.2.0", Some("1.1.0")));
+        assert!(!changelog_due("1.2.0", Some("1.2.0")));
+    }
+}
@@ -0,0 +1,429 @@
+//! This module contains the `Chart` component, which plots a series of points as a line or bars, with the values
+//! marked along its axes.
+
+use std::{any::Any, f32::consts::FRAC_PI_2};
+
+use wgpu_glyph::{HorizontalAlign, VerticalAlign};
+
+use crate::rendering::{Lines, Quad, Renderer, TextBrush};
+
+use super::{GUIComponent, Label, Rect, TextGUIComponent};
+
+// How big the axis text is
+const LABEL_SIZE: f32 = 14.0;
+// How many values are marked along each axis, counting both ends
+const TICK_COUNT: usize = 5;
+// How far the marks stick out from the axes
+const TICK_LENGTH: f32 = 5.0;
+// The space between the plot and the edges of the chart, and between the marks and their text
+const PADDING: f32 = 6.0;
+// How much of its share of the width a bar takes up
+const BAR_FILL: f32 = 0.8;
+
+/// How a `Chart` draws its points
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ChartKind{
+    /// A line joining the points up in order
+    Line,
+    /// A bar for each point, going up (or down) from zero
+    Bar,
+}
+
+/// The ranges of x and y a chart shows its points in, when it's left to work them out: from the smallest value to
+/// the largest on each axis. Bar charts always include zero on the y axis, and leave room for half a bar at each
+/// end of the x axis. An axis with nothing to span gets a range of 1 around its value.
+pub(crate) fn chart_range(points: &[(f32, f32)], kind: ChartKind) -> [[f32; 2]; 2]{
+    if points.is_empty(){
+        return [[0.0, 1.0], [0.0, 1.0]];
+    }
+    let mut range = [[f32::MAX, f32::MIN], [f32::MAX, f32::MIN]];
+    for (x, y) in points.iter(){
+        range[0] = [range[0][0].min(*x), range[0][1].max(*x)];
+        range[1] = [range[1][0].min(*y), range[1][1].max(*y)];
+    }
+    if kind == ChartKind::Bar{
+        let half_slot = bar_slot(points, range[0]) / 2.0;
+        range[0] = [range[0][0] - half_slot, range[0][1] + half_slot];
+        range[1] = [range[1][0].min(0.0), range[1][1].max(0.0)];
+    }
+    for axis in range.iter_mut(){
+        if axis[1] - axis[0] <= 0.0{
+            *axis = [axis[0] - 0.5, axis[1] + 0.5];
+        }
+    }
+    range
+}
+
+/// Write a value marked on an axis, with as many decimal places as the gap between the marks (`step`) needs
+pub(crate) fn format_tick(value: f32, step: f32) -> String{
+    // Enough to write the step exactly, or two significant figures of it when it doesn't come out exactly
+    let step = step.abs();
+    let decimals = (0..=4).find(|decimals| {
+        let scaled = step * 10.0f32.powi(*decimals);
+        (scaled - scaled.round()).abs() < 0.01
+    }).unwrap_or_else(|| (1.0 - step.log10().floor()).clamp(0.0, 6.0) as i32) as usize;
+    let text = format!("{:.*}", decimals, value);
+    // Don't write tiny negative values as "-0"
+    if text.starts_with('-') && text.chars().all(|c| matches!(c, '-' | '0' | '.')){
+        text[1..].to_string()
+    }else{
+        text
+    }
+}
+
+// The gap between neighbouring bars in x, as if they were spread evenly across the range
+fn bar_slot(points: &[(f32, f32)], x_range: [f32; 2]) -> f32{
+    let span = x_range[1] - x_range[0];
+    if points.len() > 1 && span > 0.0 { span / (points.len() - 1) as f32 } else { 1.0 }
+}
+
+/// # Chart
+///
+/// Plots a series of `(x, y)` points inside its rect, as a line (`ChartKind::Line`) or as bars (`ChartKind::Bar`),
+/// with the values marked along the left and bottom axes. The axes span the points (bar charts always taking in zero) unless a
+/// range is set with `set_range`, in which case anything outside it is cut off. Titles for the axes can be added
+/// with `set_axis_titles`, the y one reading up the side.
+///
+/// The lines are drawn with the renderer's line pipeline, so they're a pixel wide. Text is always black, so the
+/// background should be light.
+pub struct Chart{
+    rect: Rect,
+    kind: ChartKind,
+    points: Vec<(f32, f32)>,
+    range: Option<[[f32; 2]; 2]>, // set by the user, rather than worked out from the points
+    bar_color: [f32; 4],
+
+    background: Quad,
+    axes: Lines,
+    plot: Lines,
+    bars: Vec<Quad>,
+    ticks: Vec<Label>,
+    tick_pos: Vec<[f32; 2]>,
+    titles: [Option<Label>; 2],
+    screen_dim: (u32, u32),
+    enabled: bool,
+}
+
+impl Chart{
+    /// Create an empty chart filling `rect`
+    pub fn new(rect: Rect, kind: ChartKind, renderer: &Renderer) -> Self{
+        let mut chart = Self{
+            rect,
+            kind,
+            points: Vec::new(),
+            range: None,
+            bar_color: [0.2, 0.4, 0.8, 1.0],
+
+            background: Quad::new(rect.pos, rect.size, [0.95, 0.95, 0.95, 1.0], &renderer.device),
+            axes: Lines::new([0.2, 0.2, 0.2, 1.0], renderer),
+            plot: Lines::new([0.2, 0.4, 0.8, 1.0], renderer),
+            bars: Vec::new(),
+            ticks: Vec::new(),
+            tick_pos: Vec::new(),
+            titles: [None, None],
+            screen_dim: (0, 0),
+            enabled: true,
+        };
+        chart.place();
+        chart
+    }
+
+    /// Replace the points. Line charts join them up in the order they're given.
+    pub fn set_points(&mut self, points: &[(f32, f32)]){
+        self.points = points.to_vec();
+        self.place();
+    }
+
+    pub fn get_points(&self) -> &[(f32, f32)]{
+        &self.points
+    }
+
+    /// Add a point to the end, like the newest sample of a value being watched
+    pub fn push_point(&mut self, point: (f32, f32)){
+        self.points.push(point);
+        self.place();
+    }
+
+    pub fn set_kind(&mut self, kind: ChartKind){
+        self.kind = kind;
+        self.place();
+    }
+
+    pub fn get_kind(&self) -> ChartKind{
+        self.kind
+    }
+
+    /// Set the ranges of x and y the chart shows, or `None` to fit them to the points
+    pub fn set_range(&mut self, range: Option<[[f32; 2]; 2]>){
+        self.range = range;
+        self.place();
+    }
+
+    /// The ranges of x and y the chart is showing
+    pub fn get_range(&self) -> [[f32; 2]; 2]{
+        self.range.unwrap_or_else(|| chart_range(&self.points, self.kind))
+    }
+
+    /// Label the x axis along the bottom and the y axis down the left, or take a label away with `None`
+    pub fn set_axis_titles(&mut self, x: Option<&str>, y: Option<&str>){
+        self.titles = [x, y].map(|title| title.map(|title| {
+            let mut label = Label::new(title, LABEL_SIZE, [0.0, 0.0]);
+            label.align_horizontal(HorizontalAlign::Center);
+            label.align_vertical(VerticalAlign::Center);
+            label
+        }));
+        if let Some(title) = &mut self.titles[1]{
+            title.set_rotation(-FRAC_PI_2);
+        }
+        self.place();
+    }
+
+    /// Set the colours of the background, the axes, and the line or bars
+    pub fn set_colors(&mut self, background: [f32; 4], axes: [f32; 4], plot: [f32; 4]){
+        self.background.set_color(background);
+        self.axes.set_color(axes);
+        self.plot.set_color(plot);
+        self.bar_color = plot;
+        for bar in self.bars.iter_mut(){
+            bar.set_color(plot);
+        }
+    }
+
+    pub fn set_rect(&mut self, rect: Rect){
+        self.rect = rect;
+        self.background.set_pos(rect.pos);
+        self.background.set_size(rect.size);
+        self.place();
+    }
+
+    pub fn get_rect(&self) -> Rect{
+        self.rect
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+    }
+
+    /// The part of the chart the points are plotted in, inside the axes
+    pub fn get_plot_rect(&self) -> Rect{
+        let left = self.rect.pos[0] - self.rect.size[0] / 2.0 + PADDING + LABEL_SIZE * 3.0 + TICK_LENGTH
+            + if self.titles[1].is_some() { LABEL_SIZE + PADDING } else { 0.0 };
+        let bottom = self.rect.pos[1] + self.rect.size[1] / 2.0 - PADDING - LABEL_SIZE * 1.2 - TICK_LENGTH
+            - if self.titles[0].is_some() { LABEL_SIZE * 1.2 + PADDING } else { 0.0 };
+        let right = (self.rect.pos[0] + self.rect.size[0] / 2.0 - PADDING).max(left);
+        let top = (self.rect.pos[1] - self.rect.size[1] / 2.0 + PADDING).min(bottom);
+        Rect::from_corners([left, top], [right, bottom])
+    }
+
+    /// Where a point goes in component space
+    pub fn point_pos(&self, point: (f32, f32)) -> [f32; 2]{
+        let plot = self.get_plot_rect();
+        let range = self.get_range();
+        let across = (point.0 - range[0][0]) / (range[0][1] - range[0][0]);
+        let up = (point.1 - range[1][0]) / (range[1][1] - range[1][0]);
+        [plot.pos[0] + (across - 0.5) * plot.size[0], plot.pos[1] + (0.5 - up) * plot.size[1]]
+    }
+
+    // Work out where the axes, the plot and the text go
+    fn place(&mut self){
+        let plot = self.get_plot_rect();
+        let range = self.get_range();
+        let (left, right) = (plot.pos[0] - plot.size[0] / 2.0, plot.pos[0] + plot.size[0] / 2.0);
+        let (top, bottom) = (plot.pos[1] - plot.size[1] / 2.0, plot.pos[1] + plot.size[1] / 2.0);
+
+        // The axes, with marks along them and the values next to the marks
+        let mut axes = vec![[[left, top], [left, bottom]], [[left, bottom], [right, bottom]]];
+        self.ticks.clear();
+        self.tick_pos.clear();
+        for (axis, [low, high]) in range.iter().copied().enumerate(){
+            let step = (high - low) / (TICK_COUNT - 1) as f32;
+            for i in 0..TICK_COUNT{
+                let fraction = i as f32 / (TICK_COUNT - 1) as f32;
+                let mut label = Label::new(format_tick(low + step * i as f32, step).as_str(), LABEL_SIZE, [0.0, 0.0]);
+                if axis == 0{
+                    let x = left + fraction * plot.size[0];
+                    axes.push([[x, bottom], [x, bottom + TICK_LENGTH]]);
+                    label.align_horizontal(HorizontalAlign::Center);
+                    self.tick_pos.push([x, bottom + TICK_LENGTH + PADDING / 2.0]);
+                }else{
+                    let y = bottom - fraction * plot.size[1];
+                    axes.push([[left - TICK_LENGTH, y], [left, y]]);
+                    label.align_horizontal(HorizontalAlign::Right);
+                    label.align_vertical(VerticalAlign::Center);
+                    self.tick_pos.push([left - TICK_LENGTH - PADDING / 2.0, y]);
+                }
+                self.ticks.push(label);
+            }
+        }
+        self.axes.set_segments(&axes);
+
+        match self.kind{
+            ChartKind::Line => {
+                let points: Vec<[f32; 2]> = self.points.iter().map(|point| self.point_pos(*point)).collect();
+                self.plot.set_strip(&points);
+            }
+            ChartKind::Bar => self.plot.clear(),
+        }
+        self.place_bars();
+    }
+
+    // Move the bars over their points, if they've been made
+    fn place_bars(&mut self){
+        if self.kind != ChartKind::Bar || self.bars.len() != self.points.len(){
+            return;
+        }
+        let range = self.get_range();
+        let plot = self.get_plot_rect();
+        let data_range = chart_range(&self.points, ChartKind::Line)[0];
+        let width = bar_slot(&self.points, data_range) / (range[0][1] - range[0][0]) * plot.size[0] * BAR_FILL;
+        // Bars go up from zero, or from the bottom of the range if zero isn't in it
+        let base = self.point_pos((0.0, 0.0f32.max(range[1][0]).min(range[1][1])))[1];
+        for i in 0..self.points.len(){
+            let top = self.point_pos(self.points[i]);
+            let bar = &mut self.bars[i];
+            bar.set_pos([top[0], (top[1] + base) / 2.0]);
+            bar.set_size([width, (top[1] - base).abs()]);
+        }
+    }
+
+    // The plot in window pixels, for the scissor rect that cuts off anything outside the range
+    fn plot_scissor(&self) -> Option<(u32, u32, u32, u32)>{
+        let plot = self.get_plot_rect();
+        let (width, height) = (self.screen_dim.0 as f32, self.screen_dim.1 as f32);
+        let left = (plot.pos[0] - plot.size[0] / 2.0 + width / 2.0).clamp(0.0, width);
+        let top = (plot.pos[1] - plot.size[1] / 2.0 + height / 2.0).clamp(0.0, height);
+        // One more pixel, so lines along the edges still show
+        let right = (plot.pos[0] + plot.size[0] / 2.0 + width / 2.0 + 1.0).clamp(0.0, width);
+        let bottom = (plot.pos[1] + plot.size[1] / 2.0 + height / 2.0 + 1.0).clamp(0.0, height);
+        Some((left as u32, top as u32, (right - left) as u32, (bottom - top) as u32)).filter(|_| right > left && bottom > top)
+    }
+}
+
+impl GUIComponent for Chart{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if !self.enabled{
+            return;
+        }
+        self.background.render(render_pass);
+        self.axes.render(render_pass);
+        // The scissor rect is in window pixels, and has to stay inside the window
+        if let Some((x, y, width, height)) = self.plot_scissor(){
+            render_pass.set_scissor_rect(x, y, width, height);
+            for bar in self.bars.iter().filter(|_| self.kind == ChartKind::Bar){
+                bar.render(render_pass);
+            }
+            self.plot.render(render_pass);
+            render_pass.set_scissor_rect(0, 0, self.screen_dim.0, self.screen_dim.1);
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if self.enabled{
+            for label in self.ticks.iter().chain(self.titles.iter().flatten()){
+                TextGUIComponent::render_text(label, brush);
+            }
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.rect.pos
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(self.rect)
+    }
+
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{
+        Some(self.rect.size)
+    }
+
+    fn arrange(&mut self, rect: Rect){
+        if rect != self.rect{
+            self.set_rect(rect);
+        }
+    }
+
+    /// Make a bar for each point, and room for the lines
+    fn rebuild(&mut self, device: &wgpu::Device) -> bool{
+        self.axes.rebuild(device);
+        self.plot.rebuild(device);
+        let bars = if self.kind == ChartKind::Bar { self.points.len() } else { 0 };
+        if self.bars.len() != bars{
+            let color = self.bar_color;
+            self.bars.resize_with(bars, || Quad::new([0.0, 0.0], [0.0, 0.0], color, device));
+            self.place_bars();
+        }
+        false
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.screen_dim = screen_dim;
+        self.background.prepare(queue, screen_dim);
+        self.axes.prepare(queue, screen_dim);
+        self.plot.prepare(queue, screen_dim);
+        for bar in self.bars.iter_mut(){
+            bar.prepare(queue, screen_dim);
+        }
+        for (label, pos) in self.ticks.iter_mut().zip(self.tick_pos.iter()){
+            label.set_pos(*pos, screen_dim);
+        }
+        let plot = self.get_plot_rect();
+        let rect = self.rect;
+        if let Some(title) = &mut self.titles[0]{
+            title.set_pos([plot.pos[0], rect.pos[1] + rect.size[1] / 2.0 - PADDING - LABEL_SIZE * 0.6], screen_dim);
+        }
+        if let Some(title) = &mut self.titles[1]{
+            title.set_pos([rect.pos[0] - rect.size[0] / 2.0 + PADDING + LABEL_SIZE / 2.0, plot.pos[1]], screen_dim);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Test that charts fit their ranges to the points, with room for the bars in bar charts
+    #[test]
+    fn chart_range_test(){
+        let points = [(0.0, 2.0), (1.0, 5.0), (2.0, 3.0)];
+        assert_eq!(chart_range(&points, ChartKind::Line), [[0.0, 2.0], [2.0, 5.0]]);
+        // Bars start from zero, with half a bar either side
+        assert_eq!(chart_range(&points, ChartKind::Bar), [[-0.5, 2.5], [0.0, 5.0]]);
+        // Nothing to span
+        assert_eq!(chart_range(&[(3.0, 1.0)], ChartKind::Line), [[2.5, 3.5], [0.5, 1.5]]);
+        assert_eq!(chart_range(&[], ChartKind::Line), [[0.0, 1.0], [0.0, 1.0]]);
+    }
+
+    /// Test that axis values get as many decimal places as the gap between them needs
+    #[test]
+    fn format_tick_test(){
+        assert_eq!(format_tick(25.0, 5.0), "25");
+        assert_eq!(format_tick(0.5, 0.25), "0.50");
+        assert_eq!(format_tick(5.0, 2.5), "5.0");
+        assert_eq!(format_tick(1.0 / 3.0, 1.0 / 3.0), "0.33");
+        assert_eq!(format_tick(100.0 / 3.0, 100.0 / 3.0), "33");
+        assert_eq!(format_tick(-0.0001, 0.5), "0.0");
+    }
+}
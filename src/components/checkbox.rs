@@ -0,0 +1,252 @@
+//! This module contains the `Checkbox` component, a box that gets ticked and unticked by clicking on it
+//! (or on its label).
+
+use std::any::Any;
+
+use wgpu_glyph::{HorizontalAlign, VerticalAlign};
+
+use crate::{input::{InputState, MouseButton, MouseEvent, UiEvent}, rendering::{Quad, Renderer, TextBrush}};
+
+use super::{EventGUIComponent, Label, Rect, TextGUIComponent};
+
+// The space between the box and the label, in pixels
+const LABEL_GAP: f32 = 8.0;
+
+// The area that can be clicked for a box `size` pixels across centred on `pos`, with a label of some measured size
+// to the right of it
+fn clickable_rect(pos: [f32; 2], size: f32, label: Option<[f32; 2]>) -> Rect{
+    let left = pos[0] - size / 2.0;
+    let (width, height) = match label{
+        Some(measured) => (size + LABEL_GAP + measured[0], size.max(measured[1])),
+        None => (size, size),
+    };
+    Rect::new([left + width / 2.0, pos[1]], [width, height])
+}
+
+// Whether a press or release of the mouse button finishes a click, given whether it happened inside the checkbox.
+// `pressed` remembers whether the button went down inside, since letting go only counts if it did.
+fn finishes_click(pressed: &mut bool, down: bool, inside: bool) -> bool{
+    if down{
+        *pressed = inside;
+        false
+    }else{
+        std::mem::take(pressed) && inside
+    }
+}
+
+/// # Checkbox
+///
+/// A box with an optional label next to it. Clicking either toggles it, and the callback runs with the new state.
+///
+/// Like a `Button`, it reacts to a press and release both inside it, so dragging off it cancels the click.
+pub struct Checkbox{
+    pos: [f32; 2], // the centre of the box
+    size: f32,
+    checked: bool,
+    callback: Option<Box<dyn Fn(bool)>>,
+
+    border: Quad,
+    background: Quad,
+    check: Quad,
+    label: Option<(Label, [f32; 2])>, // and its measured size
+
+    input: InputState,
+    pressed: bool, // the mouse went down inside us
+    enabled: bool,
+}
+
+impl Checkbox{
+    /// Create an unticked checkbox, with the box centred on `pos` and the label (if any) to the right of it
+    pub fn new(pos: [f32; 2], size: f32, text: Option<&str>, text_size: f32, callback: Option<Box<dyn Fn(bool)>>, renderer: &mut Renderer) -> Self{
+        let label = text.map(|text| {
+            let mut label = Label::new(text, text_size, [0.0, 0.0]);
+            label.align_vertical(VerticalAlign::Center);
+            label.align_horizontal(HorizontalAlign::Left);
+            let measured = label.measure(renderer);
+            (label, measured)
+        });
+
+        Self{
+            pos,
+            size,
+            checked: false,
+            callback,
+
+            border: Quad::new(pos, [size, size], [0.3, 0.3, 0.3, 1.0], &renderer.device),
+            background: Quad::new(pos, [size - 4.0, size - 4.0], [1.0, 1.0, 1.0, 1.0], &renderer.device),
+            check: Quad::new(pos, [size / 2.0, size / 2.0], [0.2, 0.4, 0.8, 1.0], &renderer.device),
+            label,
+
+            input: InputState::new(),
+            pressed: false,
+            enabled: true,
+        }
+    }
+
+    pub fn is_checked(&self) -> bool{
+        self.checked
+    }
+
+    /// Tick or untick the box. This doesn't run the callback.
+    pub fn set_checked(&mut self, checked: bool){
+        self.checked = checked;
+    }
+
+    /// Flip the state, and run the callback with it
+    pub fn toggle(&mut self){
+        self.checked = !self.checked;
+        if let Some(callback) = &self.callback{
+            callback(self.checked);
+        }
+    }
+
+    /// Set the function to run when the state changes
+    pub fn set_callback(&mut self, callback: Option<Box<dyn Fn(bool)>>){
+        self.callback = callback;
+    }
+
+    pub fn set_pos(&mut self, pos: [f32; 2]){
+        self.pos = pos;
+        self.border.set_pos(pos);
+        self.background.set_pos(pos);
+        self.check.set_pos(pos);
+    }
+
+    /// Set the colours of the border, the inside of the box and the tick
+    pub fn set_colors(&mut self, border: [f32; 4], background: [f32; 4], check: [f32; 4]){
+        self.border.set_color(border);
+        self.background.set_color(background);
+        self.check.set_color(check);
+    }
+
+    /// Borrow the label, for example to change its font
+    pub fn borrow_label_mut(&mut self) -> Option<&mut Label>{
+        self.label.as_mut().map(|(label, _)| label)
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+        self.pressed = false;
+    }
+
+    /// The area that can be clicked - the box, and the label next to it
+    pub fn get_rect(&self) -> Rect{
+        clickable_rect(self.pos, self.size, self.label.as_ref().map(|(_, measured)| *measured))
+    }
+}
+
+impl EventGUIComponent for Checkbox{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.enabled{
+            self.border.render(render_pass);
+            self.background.render(render_pass);
+            if self.checked{
+                self.check.render(render_pass);
+            }
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if let (true, Some((label, _))) = (self.enabled, &self.label){
+            TextGUIComponent::render_text(label, brush);
+        }
+    }
+
+    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        if !self.enabled{
+            return;
+        }
+
+        if let Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed, pos, .. })) = self.input.handle_event(event, window){
+            let inside = self.get_rect().contains(pos);
+            if finishes_click(&mut self.pressed, pressed, inside){
+                self.toggle();
+            }
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.pos
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(self.get_rect())
+    }
+
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{
+        Some(self.get_rect().size)
+    }
+
+    /// The box goes at the left of the rectangle, with the label after it
+    fn arrange(&mut self, rect: Rect){
+        self.set_pos([rect.pos[0] - rect.size[0] / 2.0 + self.size / 2.0, rect.pos[1]]);
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.border.prepare(queue, screen_dim);
+        self.background.prepare(queue, screen_dim);
+        self.check.prepare(queue, screen_dim);
+        if let Some((label, _)) = &mut self.label{
+            label.set_pos([self.pos[0] + self.size / 2.0 + LABEL_GAP, self.pos[1]], screen_dim);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Test that only a press and release both inside the checkbox make a click
+    #[test]
+    fn click_test(){
+        let mut pressed = false;
+        assert!(!finishes_click(&mut pressed, true, true));
+        assert!(finishes_click(&mut pressed, false, true));
+
+        // Dragging off before letting go cancels the click, and dragging on doesn't make one
+        finishes_click(&mut pressed, true, true);
+        assert!(!finishes_click(&mut pressed, false, false));
+        finishes_click(&mut pressed, true, false);
+        assert!(!finishes_click(&mut pressed, false, true));
+
+        // A release only finishes the press before it, so a second release does nothing
+        finishes_click(&mut pressed, true, true);
+        assert!(finishes_click(&mut pressed, false, true));
+        assert!(!finishes_click(&mut pressed, false, true));
+    }
+
+    /// Test that the label can be clicked as well as the box, and that a tall label makes the area taller
+    #[test]
+    fn clickable_rect_test(){
+        assert_eq!(clickable_rect([10.0, 20.0], 16.0, None), Rect::new([10.0, 20.0], [16.0, 16.0]));
+
+        let rect = clickable_rect([10.0, 20.0], 16.0, Some([50.0, 24.0]));
+        assert_eq!(rect.size, [16.0 + LABEL_GAP + 50.0, 24.0]);
+        // The left edge stays where the box's is
+        assert_eq!(rect.pos[0] - rect.size[0] / 2.0, 2.0);
+        assert!(rect.contains([70.0, 20.0]));
+        assert!(!rect.contains([0.0, 20.0]));
+    }
+}
@@ -0,0 +1,410 @@
+//! This module contains the `CollapsiblePanel` component, a header that shows and hides a body under it, for
+//! accordions and "advanced settings" sections.
+
+use std::any::Any;
+
+use wgpu_glyph::{HorizontalAlign, VerticalAlign};
+
+use crate::{input::{EventPhase, EventResponse, InputState, MouseButton, MouseEvent, UiEvent}, rendering::{Quad, Renderer, TextBrush}};
+
+use super::{EventGUIComponent, Label, Rect, TextGUIComponent};
+
+// The space around the header's text, in pixels
+const PADDING: f32 = 8.0;
+// How long opening or closing takes, in seconds
+const SLIDE_TIME: f32 = 0.15;
+
+// How open the body is after sliding for `dt` seconds towards open or closed
+fn slide(openness: f32, expanded: bool, dt: f32) -> f32{
+    let step = dt / SLIDE_TIME;
+    if expanded { (openness + step).min(1.0) } else { (openness - step).max(0.0) }
+}
+
+// The header's rectangle, and the part of the body showing under it, for a panel arranged into `rect`
+fn panel_rects(rect: Rect, header_height: f32, body_height: f32, openness: f32) -> (Rect, Rect){
+    let top = rect.pos[1] - rect.size[1] / 2.0;
+    let header = Rect::new([rect.pos[0], top + header_height / 2.0], [rect.size[0], header_height]);
+    let height = body_height * openness;
+    let body = Rect::new([rect.pos[0], top + header_height + height / 2.0], [rect.size[0], height]);
+    (header, body)
+}
+
+// Whether a press or release of the mouse button toggles the panel, given whether it happened on the header.
+// `pressed` remembers whether the button went down on the header, since letting go only counts if it did.
+fn toggles(pressed: &mut bool, down: bool, on_header: bool) -> bool{
+    if down{
+        *pressed = on_header;
+        false
+    }else{
+        std::mem::take(pressed) && on_header
+    }
+}
+
+/// # CollapsiblePanel
+///
+/// A header row with a title, and a body (any component) under it that clicking the header shows and hides. The
+/// body slides open and closed, and the panel's height follows it, so in a `StackPanel` (or a `ListView`, or a
+/// `ScrollView` holding either) everything below the panel moves up and down with it. A column of these makes an
+/// accordion.
+///
+/// While the body is sliding, its quads are clipped to the part of it that's showing, and its text waits until it's
+/// all the way open, as text can't be clipped.
+pub struct CollapsiblePanel{
+    rect: Rect,
+    width: f32,
+    header_height: f32,
+    body: Box<dyn EventGUIComponent>,
+    body_size: [f32; 2],
+    expanded: bool,
+    openness: f32, // from 0 (closed) to 1 (open), sliding towards `expanded`
+    resized: bool, // the panel changed height since the last frame, so the layout needs arranging again
+    callback: Option<Box<dyn Fn(bool)>>,
+    header_colors: ([f32; 4], [f32; 4]), // normal, hovered
+
+    header: Quad,
+    background: Quad,
+    title: Label,
+    arrow: Label,
+    pressed: bool,
+    input: InputState,
+    screen_dim: (u32, u32),
+    enabled: bool,
+}
+
+impl CollapsiblePanel{
+    /// Create an open panel `width` pixels wide, with a header showing `title` above `body`
+    pub fn new(width: f32, title: &str, text_size: f32, body: Box<dyn EventGUIComponent>, renderer: &Renderer) -> Self{
+        let header_height = text_size + PADDING * 2.0;
+        let mut title = Label::new(title, text_size, [0.0, 0.0]);
+        title.align_vertical(VerticalAlign::Center);
+        let mut arrow = Label::new("v", text_size, [0.0, 0.0]);
+        arrow.align_vertical(VerticalAlign::Center);
+        arrow.align_horizontal(HorizontalAlign::Center);
+        let header_colors = ([0.85, 0.85, 0.85, 1.0], [0.78, 0.78, 0.78, 1.0]);
+
+        Self{
+            rect: Rect::new([0.0, 0.0], [width, header_height]),
+            width,
+            header_height,
+            body,
+            body_size: [0.0, 0.0],
+            expanded: true,
+            openness: 1.0,
+            resized: true,
+            callback: None,
+            header_colors,
+
+            header: Quad::new([0.0, 0.0], [width, header_height], header_colors.0, &renderer.device),
+            background: Quad::new([0.0, 0.0], [0.0, 0.0], [0.96, 0.96, 0.96, 1.0], &renderer.device),
+            title,
+            arrow,
+            pressed: false,
+            input: InputState::new(),
+            screen_dim: (0, 0),
+            enabled: true,
+        }
+    }
+
+    /// Open or close the body, sliding it there. This doesn't run the callback.
+    pub fn set_expanded(&mut self, expanded: bool){
+        self.expanded = expanded;
+        self.arrow.set_text(if expanded { "v" } else { ">" });
+    }
+
+    /// Open or close the body straight away, without sliding
+    pub fn set_expanded_immediate(&mut self, expanded: bool){
+        self.set_expanded(expanded);
+        self.openness = if expanded { 1.0 } else { 0.0 };
+        self.resized = true;
+    }
+
+    /// Open the body if it's closed, or close it if it's open, like clicking the header does
+    pub fn toggle(&mut self){
+        self.set_expanded(!self.expanded);
+        if let Some(callback) = &self.callback{
+            callback(self.expanded);
+        }
+    }
+
+    pub fn is_expanded(&self) -> bool{
+        self.expanded
+    }
+
+    /// Set the function to run when the header is clicked, with whether the body is now open
+    pub fn set_callback(&mut self, callback: Option<Box<dyn Fn(bool)>>){
+        self.callback = callback;
+    }
+
+    pub fn set_title(&mut self, title: &str){
+        self.title.set_text(title);
+    }
+
+    /// Set the colours of the header (normally and while hovered) and of the background behind the body
+    pub fn set_colors(&mut self, header: [f32; 4], hovered: [f32; 4], background: [f32; 4]){
+        self.header_colors = (header, hovered);
+        self.background.set_color(background);
+    }
+
+    pub fn set_width(&mut self, width: f32){
+        self.width = width;
+        self.resized = true;
+    }
+
+    /// Borrow the body, for example to downcast it
+    pub fn borrow_body_mut(&mut self) -> &mut Box<dyn EventGUIComponent>{
+        &mut self.body
+    }
+
+    pub fn get_rect(&self) -> Rect{
+        self.rect
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+        self.pressed = false;
+    }
+
+    fn header_rect(&self) -> Rect{
+        panel_rects(self.rect, self.header_height, self.body_size[1], self.openness).0
+    }
+
+    // The part of the body that's showing
+    fn visible_body(&self) -> Rect{
+        panel_rects(self.rect, self.header_height, self.body_size[1], self.openness).1
+    }
+
+    // Clicks (and the wheel) only reach the body where it can be seen
+    fn reaches_body(&self, event: &winit::event::Event<()>) -> bool{
+        use winit::event::{ElementState, Event, WindowEvent};
+
+        if self.openness <= 0.0{
+            return false;
+        }
+        match event{
+            Event::WindowEvent{ event: WindowEvent::MouseInput{ state: ElementState::Pressed, .. }, .. }
+            | Event::WindowEvent{ event: WindowEvent::MouseWheel{ .. }, .. } => self.visible_body().contains(self.input.get_cursor_pos()),
+            _ => true,
+        }
+    }
+
+    // Toggle on a click that starts and ends on the header, returning true if the event was a click on it
+    fn own_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window) -> bool{
+        match self.input.handle_event(event, window){
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed, pos, .. })) => {
+                let on_header = self.header_rect().contains(pos);
+                if toggles(&mut self.pressed, pressed, on_header){
+                    self.toggle();
+                }
+                on_header
+            }
+            _ => false,
+        }
+    }
+}
+
+impl EventGUIComponent for CollapsiblePanel{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if !self.enabled{
+            return;
+        }
+        self.header.render(render_pass);
+        if self.openness <= 0.0 || self.screen_dim.0 == 0 || self.screen_dim.1 == 0{
+            return;
+        }
+
+        // The scissor rect is in window pixels, and has to stay inside the window
+        let body = self.visible_body();
+        let (width, height) = (self.screen_dim.0 as f32, self.screen_dim.1 as f32);
+        let left = (body.pos[0] - body.size[0] / 2.0 + width / 2.0).clamp(0.0, width);
+        let top = (body.pos[1] - body.size[1] / 2.0 + height / 2.0).clamp(0.0, height);
+        let right = (body.pos[0] + body.size[0] / 2.0 + width / 2.0).clamp(0.0, width);
+        let bottom = (body.pos[1] + body.size[1] / 2.0 + height / 2.0).clamp(0.0, height);
+        if right > left && bottom > top{
+            render_pass.set_scissor_rect(left as u32, top as u32, (right - left) as u32, (bottom - top) as u32);
+            self.background.render(render_pass);
+            self.body.render(render_pass);
+            render_pass.set_scissor_rect(0, 0, self.screen_dim.0, self.screen_dim.1);
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if self.enabled{
+            TextGUIComponent::render_text(&self.title, brush);
+            TextGUIComponent::render_text(&self.arrow, brush);
+            if self.openness >= 1.0{
+                self.body.render_text(brush);
+            }
+        }
+    }
+
+    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        if !self.enabled{
+            return;
+        }
+        if self.reaches_body(event){
+            self.body.handle_event_callback(event, window);
+        }
+        self.own_event(event, window);
+    }
+
+    /// Clicks on the header are ours, the body gets everything else first
+    fn handle_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window, phase: EventPhase) -> EventResponse{
+        if !self.enabled{
+            return EventResponse::Continue;
+        }
+        if self.reaches_body(event) && self.body.handle_event(event, window, phase) == EventResponse::Consumed{
+            return EventResponse::Consumed;
+        }
+        if phase != EventPhase::Bubble{
+            return EventResponse::Continue;
+        }
+        if self.own_event(event, window) { EventResponse::Consumed } else { EventResponse::Continue }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.rect.pos
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(self.rect)
+    }
+
+    // Slide the body towards open or closed, asking for the layout to follow
+    fn update(&mut self, dt: f32){
+        let target = if self.expanded { 1.0 } else { 0.0 };
+        if self.openness != target{
+            self.openness = slide(self.openness, self.expanded, dt);
+            self.resized = true;
+        }
+        if self.openness > 0.0{
+            self.body.update(dt);
+        }
+    }
+
+    fn is_animating(&self) -> bool{
+        self.openness != if self.expanded { 1.0 } else { 0.0 } || (self.openness > 0.0 && self.body.is_animating())
+    }
+
+    /// The panel's height changes as it slides, which is what moves the components after it
+    fn rebuild(&mut self, device: &wgpu::Device) -> bool{
+        let body_changed = self.body.rebuild(device);
+        std::mem::take(&mut self.resized) || body_changed
+    }
+
+    fn layout_text(&mut self, brush: &mut TextBrush){
+        self.body.layout_text(brush);
+    }
+
+    /// The body is always measured as if it were open, and the panel takes as much of its height as is showing
+    fn measure(&mut self, available: [f32; 2]) -> Option<[f32; 2]>{
+        let width = self.width.min(available[0]);
+        self.body_size = self.body.measure([width, f32::INFINITY])
+            .or_else(|| self.body.get_bounds().map(|bounds| bounds.size))
+            .unwrap_or([0.0, 0.0]);
+        Some([width, self.header_height + self.body_size[1] * self.openness])
+    }
+
+    fn arrange(&mut self, rect: Rect){
+        self.rect = rect;
+        let header = self.header_rect();
+        self.header.set_pos(header.pos);
+        self.header.set_size(header.size);
+
+        // The body is arranged at its full size, under the header, and clipped when it's partly open
+        let top = header.pos[1] + self.header_height / 2.0;
+        let body = Rect::new([rect.pos[0], top + self.body_size[1] / 2.0], [rect.size[0], self.body_size[1]]);
+        self.background.set_pos(body.pos);
+        self.background.set_size(body.size);
+        self.body.arrange(body);
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.screen_dim = screen_dim;
+        let header = self.header_rect();
+        let hovered = header.contains(self.input.get_cursor_pos());
+        self.header.set_color(if hovered { self.header_colors.1 } else { self.header_colors.0 });
+        self.header.prepare(queue, screen_dim);
+        self.background.prepare(queue, screen_dim);
+
+        let left = header.pos[0] - header.size[0] / 2.0;
+        self.arrow.set_pos([left + PADDING + self.header_height / 4.0, header.pos[1]], screen_dim);
+        self.title.set_pos([left + PADDING + self.header_height / 2.0 + PADDING, header.pos[1]], screen_dim);
+        if self.openness > 0.0{
+            self.body.prepare(queue, screen_dim);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Test that the body slides open and closed over `SLIDE_TIME`, stopping at the ends
+    #[test]
+    fn slide_test(){
+        let half = SLIDE_TIME / 2.0;
+        assert_eq!(slide(0.0, true, half), 0.5);
+        assert_eq!(slide(0.5, true, SLIDE_TIME), 1.0);
+        assert_eq!(slide(1.0, true, half), 1.0);
+        assert_eq!(slide(1.0, false, half), 0.5);
+        assert_eq!(slide(0.5, false, SLIDE_TIME), 0.0);
+    }
+
+    /// Test that only a click that starts and ends on the header toggles the panel
+    #[test]
+    fn toggles_test(){
+        let mut pressed = false;
+        assert!(!toggles(&mut pressed, true, true));
+        assert!(toggles(&mut pressed, false, true));
+        // Letting go again without a press doesn't count
+        assert!(!toggles(&mut pressed, false, true));
+
+        // Pressing on the header and dragging off
+        toggles(&mut pressed, true, true);
+        assert!(!toggles(&mut pressed, false, false));
+        assert!(!pressed);
+
+        // Pressing on the body and dragging onto the header
+        toggles(&mut pressed, true, false);
+        assert!(!toggles(&mut pressed, false, true));
+    }
+
+    /// Test that the header sits at the top of the panel, with only the open part of the body under it
+    #[test]
+    fn panel_rects_test(){
+        // A 30 pixel header over a 100 pixel body that's halfway open, so the panel is 80 pixels tall
+        let rect = Rect::new([50.0, 40.0], [100.0, 80.0]);
+        let (header, body) = panel_rects(rect, 30.0, 100.0, 0.5);
+        assert_eq!(header, Rect::new([50.0, 15.0], [100.0, 30.0]));
+        assert_eq!(body, Rect::new([50.0, 55.0], [100.0, 50.0]));
+
+        // Closed, nothing of the body shows
+        let rect = Rect::new([50.0, 15.0], [100.0, 30.0]);
+        let (header, body) = panel_rects(rect, 30.0, 100.0, 0.0);
+        assert_eq!(header, rect);
+        assert_eq!(body.size[1], 0.0);
+        assert!(!body.contains([50.0, 35.0]));
+    }
+}
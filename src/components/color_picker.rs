@@ -0,0 +1,502 @@
+//! This module contains the `ColorPicker` component, a saturation/value square next to a hue strip, both drawn
+//! with small fragment shaders of their own (see `Renderer::add_pipeline`), and an eyedropper button for picking
+//! a colour off the screen.
+
+use std::{any::Any, cell::Cell, rc::Rc};
+
+use crate::{input::{EventPhase, EventResponse, InputCategory, InputState, MouseButton, MouseEvent, UiEvent}, rendering::{Pipelines, Quad, Renderer}};
+
+use super::{Eyedropper, EventGUIComponent, Rect, eyedropper::srgb_to_linear};
+
+// Where the eyedropper leaves the colour it picked, until the picker next updates
+type PickedSlot = Rc<Cell<Option<[f32; 4]>>>;
+
+// How wide the hue strip is (and how big the eyedropper button under it is), and the space between it and the
+// square, in pixels
+const HUE_WIDTH: f32 = 20.0;
+const HUE_GAP: f32 = 8.0;
+// How big the marker on the square is
+const MARKER_SIZE: f32 = 10.0;
+// The names the picker's shaders are added to the renderer under
+const SQUARE_PIPELINE: &str = "rusty_gui::color_picker_square";
+const HUE_PIPELINE: &str = "rusty_gui::color_picker_hue";
+
+// The part of the picker being dragged
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum PickerPart{
+    Square,
+    Hue,
+}
+
+/// Turn a hue, saturation and value (all from 0 to 1) into red, green and blue
+pub fn hsv_to_rgb(hsv: [f32; 3]) -> [f32; 3]{
+    let [hue, saturation, value] = hsv;
+    let channel = |offset: f32| {
+        // The same as the shaders - how far this channel is from its peak on the hue circle
+        let p = ((hue + offset).fract() * 6.0 - 3.0).abs();
+        let pure = (p - 1.0).clamp(0.0, 1.0);
+        value * (1.0 + (pure - 1.0) * saturation)
+    };
+    [channel(1.0), channel(2.0 / 3.0), channel(1.0 / 3.0)]
+}
+
+// The colour the picker is on, kept apart from the quads showing it
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct PickerColor{
+    hsv: [f32; 3],
+    alpha: f32,
+}
+
+impl PickerColor{
+    fn rgba(&self) -> [f32; 4]{
+        let [r, g, b] = hsv_to_rgb(self.hsv);
+        [r, g, b, self.alpha]
+    }
+
+    fn set_rgba(&mut self, color: [f32; 4]){
+        let hsv = rgb_to_hsv([color[0], color[1], color[2]]);
+        // Greys don't have a hue, so keep the one the strip is on
+        let hue = if hsv[1] == 0.0 { self.hsv[0] } else { hsv[0] };
+        self.alpha = color[3];
+        self.set_hsv([hue, hsv[1], hsv[2]]);
+    }
+
+    fn set_hsv(&mut self, hsv: [f32; 3]){
+        self.hsv = [hsv[0].clamp(0.0, 1.0), hsv[1].clamp(0.0, 1.0), hsv[2].clamp(0.0, 1.0)];
+    }
+
+    // Move to the colour the eyedropper left in the slot, if it picked one since we last looked. Picked colours
+    // are what's on screen (sRGB), so they're made linear first, like the colours a `Quad` takes.
+    fn take_pick(&mut self, picked: &PickedSlot) -> bool{
+        match picked.take(){
+            Some(color) => {
+                self.set_rgba([srgb_to_linear(color[0]), srgb_to_linear(color[1]), srgb_to_linear(color[2]), color[3]]);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+// The eyedropper's callback, leaving the colour in the slot for the picker to pick up
+fn pick_into(picked: &PickedSlot) -> Box<dyn Fn([f32; 4])>{
+    let picked = picked.clone();
+    Box::new(move |color| picked.set(Some(color)))
+}
+
+/// Turn red, green and blue into a hue, saturation and value (all from 0 to 1). Greys get a hue of 0.
+pub fn rgb_to_hsv(rgb: [f32; 3]) -> [f32; 3]{
+    let [r, g, b] = rgb;
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+
+    let hue = if delta == 0.0{
+        0.0
+    }else if max == r{
+        ((g - b) / delta).rem_euclid(6.0) / 6.0
+    }else if max == g{
+        ((b - r) / delta + 2.0) / 6.0
+    }else{
+        ((r - g) / delta + 4.0) / 6.0
+    };
+    let saturation = if max == 0.0 { 0.0 } else { delta / max };
+    [hue, saturation, max]
+}
+
+/// # ColorPicker
+///
+/// A square picking the saturation (left to right) and value (top to bottom), with a strip picking the hue
+/// (top to bottom) to the right of it. Pressing either jumps to that colour, and dragging carries on even if the
+/// cursor leaves the picker. The callback runs with the new colour each time it changes.
+///
+/// Under the hue strip is an eyedropper button (see `Eyedropper`). Pressing it (or calling `pick_from_screen`)
+/// picks the next colour clicked anywhere in the window, and escape cancels. Components above the picker in the
+/// layout see the click first, so keep the picker near the top if it should pick over them.
+///
+/// Colours are red, green, blue and alpha, from 0 to 1, the same as a `Quad` takes. The alpha isn't picked with
+/// the square or strip, it's whatever was set or picked off the screen last.
+pub struct ColorPicker{
+    rect: Rect,
+    color: PickerColor,
+    callback: Option<Box<dyn Fn([f32; 4])>>,
+    eyedropper: Eyedropper,
+    picked: PickedSlot,
+
+    border: Quad,
+    square: Quad, // the hue is passed to the shader in the tint
+    hue: Quad,
+    square_marker: Quad,
+    square_swatch: Quad, // the picked colour, inside the marker
+    hue_marker: Quad,
+    dropper_button: Quad,
+    dropper_swatch: Quad, // the colour under the cursor while picking, otherwise the picked colour
+
+    pipelines: Pipelines, // to pick up new pipelines when the MSAA level changes
+    square_pipeline: Rc<wgpu::RenderPipeline>,
+    hue_pipeline: Rc<wgpu::RenderPipeline>,
+    default_pipeline: Rc<wgpu::RenderPipeline>,
+
+    input: InputState,
+    dragging: Option<PickerPart>,
+    enabled: bool,
+}
+
+impl ColorPicker{
+    /// Create a picker filling `rect`, starting at a colour
+    pub fn new(rect: Rect, color: [f32; 4], callback: Option<Box<dyn Fn([f32; 4])>>, renderer: &mut Renderer) -> Self{
+        let square_pipeline = renderer.add_pipeline(SQUARE_PIPELINE, wgpu::include_spirv!("../../shaders/color_picker_sv.frag.spv"));
+        let hue_pipeline = renderer.add_pipeline(HUE_PIPELINE, wgpu::include_spirv!("../../shaders/color_picker_hue.frag.spv"));
+        let picked = Rc::new(Cell::new(None));
+        let eyedropper = Eyedropper::new(Some(pick_into(&picked)), renderer);
+        let device = &renderer.device;
+
+        let mut picker = Self{
+            rect,
+            color: PickerColor{ hsv: [0.0, 0.0, 0.0], alpha: 1.0 },
+            callback,
+            eyedropper,
+            picked,
+
+            border: Quad::new(rect.pos, rect.size, [0.3, 0.3, 0.3, 1.0], device),
+            square: Quad::new(rect.pos, rect.size, [0.0, 0.0, 0.0, 1.0], device),
+            hue: Quad::new(rect.pos, rect.size, [1.0, 1.0, 1.0, 1.0], device),
+            square_marker: Quad::new(rect.pos, [MARKER_SIZE, MARKER_SIZE], [1.0, 1.0, 1.0, 1.0], device),
+            square_swatch: Quad::new(rect.pos, [MARKER_SIZE - 4.0, MARKER_SIZE - 4.0], [0.0, 0.0, 0.0, 1.0], device),
+            hue_marker: Quad::new(rect.pos, [HUE_WIDTH + 4.0, 3.0], [0.1, 0.1, 0.1, 1.0], device),
+            dropper_button: Quad::new(rect.pos, [HUE_WIDTH, HUE_WIDTH], [0.3, 0.3, 0.3, 1.0], device),
+            dropper_swatch: Quad::new(rect.pos, [HUE_WIDTH - 6.0, HUE_WIDTH - 6.0], [0.0, 0.0, 0.0, 1.0], device),
+
+            pipelines: renderer.get_pipelines(),
+            square_pipeline,
+            hue_pipeline,
+            default_pipeline: renderer.get_default_pipeline(),
+
+            input: InputState::new(),
+            dragging: None,
+            enabled: true,
+        };
+        picker.set_rect(rect);
+        picker.set_color(color);
+        picker
+    }
+
+    pub fn get_color(&self) -> [f32; 4]{
+        self.color.rgba()
+    }
+
+    /// Pick a colour. This doesn't run the callback.
+    pub fn set_color(&mut self, color: [f32; 4]){
+        self.color.set_rgba(color);
+        self.update_markers();
+    }
+
+    /// The picked colour as a hue, saturation and value, from 0 to 1
+    pub fn get_hsv(&self) -> [f32; 3]{
+        self.color.hsv
+    }
+
+    /// Pick a colour by its hue, saturation and value. This doesn't run the callback.
+    pub fn set_hsv(&mut self, hsv: [f32; 3]){
+        self.color.set_hsv(hsv);
+        self.update_markers();
+    }
+
+    /// Start picking a colour off the screen, like pressing the eyedropper button. The next click picks the
+    /// colour under the cursor, and the callback runs with it once it's been read back from the frame.
+    pub fn pick_from_screen(&mut self){
+        if self.enabled{
+            self.eyedropper.activate();
+        }
+    }
+
+    /// True while the eyedropper is waiting for a click
+    pub fn is_picking_from_screen(&self) -> bool{
+        self.eyedropper.is_active()
+    }
+
+    /// Set the function to run when a new colour is picked
+    pub fn set_callback(&mut self, callback: Option<Box<dyn Fn([f32; 4])>>){
+        self.callback = callback;
+    }
+
+    pub fn set_rect(&mut self, rect: Rect){
+        self.rect = rect;
+        self.border.set_pos(rect.pos);
+        self.border.set_size([rect.size[0] + 2.0, rect.size[1] + 2.0]);
+        let square = self.square_rect();
+        self.square.set_pos(square.pos);
+        self.square.set_size(square.size);
+        let hue = self.hue_rect();
+        self.hue.set_pos(hue.pos);
+        self.hue.set_size(hue.size);
+        let dropper = self.dropper_rect();
+        self.dropper_button.set_pos(dropper.pos);
+        self.dropper_swatch.set_pos(dropper.pos);
+        self.update_markers();
+    }
+
+    pub fn get_rect(&self) -> Rect{
+        self.rect
+    }
+
+    pub fn is_dragging(&self) -> bool{
+        self.dragging.is_some()
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+        self.dragging = None;
+        self.eyedropper.cancel();
+    }
+
+    // The square takes up everything to the left of the hue strip
+    fn square_rect(&self) -> Rect{
+        let width = (self.rect.size[0] - HUE_WIDTH - HUE_GAP).max(0.0);
+        let left = self.rect.pos[0] - self.rect.size[0] / 2.0;
+        Rect::new([left + width / 2.0, self.rect.pos[1]], [width, self.rect.size[1]])
+    }
+
+    // The hue strip takes up the right hand side, down to the eyedropper button
+    fn hue_rect(&self) -> Rect{
+        let right = self.rect.pos[0] + self.rect.size[0] / 2.0;
+        let top = self.rect.pos[1] - self.rect.size[1] / 2.0;
+        let height = (self.rect.size[1] - HUE_WIDTH - HUE_GAP).max(0.0);
+        Rect::new([right - HUE_WIDTH / 2.0, top + height / 2.0], [HUE_WIDTH, height])
+    }
+
+    fn dropper_rect(&self) -> Rect{
+        let right = self.rect.pos[0] + self.rect.size[0] / 2.0;
+        let bottom = self.rect.pos[1] + self.rect.size[1] / 2.0;
+        Rect::new([right - HUE_WIDTH / 2.0, bottom - HUE_WIDTH / 2.0], [HUE_WIDTH, HUE_WIDTH])
+    }
+
+    // Where a point is inside a rectangle, from 0 to 1 across and down, clamped to the edges
+    fn fraction_in(rect: Rect, pos: [f32; 2]) -> [f32; 2]{
+        let fraction = |axis: usize| if rect.size[axis] > 0.0{
+            ((pos[axis] - rect.pos[axis]) / rect.size[axis] + 0.5).clamp(0.0, 1.0)
+        }else{
+            0.0
+        };
+        [fraction(0), fraction(1)]
+    }
+
+    fn drag_to(&mut self, part: PickerPart, pos: [f32; 2]){
+        let old_color = self.get_color();
+        let hsv = self.color.hsv;
+        match part{
+            PickerPart::Square => {
+                let [saturation, down] = Self::fraction_in(self.square_rect(), pos);
+                self.set_hsv([hsv[0], saturation, 1.0 - down]);
+            }
+            PickerPart::Hue => {
+                let [_, hue] = Self::fraction_in(self.hue_rect(), pos);
+                self.set_hsv([hue, hsv[1], hsv[2]]);
+            }
+        }
+        self.changed_from(old_color);
+    }
+
+    // Run the callback if the colour isn't what it was
+    fn changed_from(&self, old_color: [f32; 4]){
+        let color = self.get_color();
+        if color != old_color{
+            if let Some(callback) = &self.callback{
+                callback(color);
+            }
+        }
+    }
+
+    fn update_markers(&mut self){
+        let square = self.square_rect();
+        let left = square.pos[0] - square.size[0] / 2.0;
+        let top = square.pos[1] - square.size[1] / 2.0;
+        let hsv = self.color.hsv;
+        let marker = [left + hsv[1] * square.size[0], top + (1.0 - hsv[2]) * square.size[1]];
+        self.square_marker.set_pos(marker);
+        self.square_swatch.set_pos(marker);
+        self.square_swatch.set_color(self.get_color());
+        // A dark marker on a dark colour would disappear
+        self.square_marker.set_color(if hsv[2] > 0.5 { [0.1, 0.1, 0.1, 1.0] } else { [1.0, 1.0, 1.0, 1.0] });
+        self.square.set_color([hsv[0], 0.0, 0.0, 1.0]);
+
+        let hue = self.hue_rect();
+        self.hue_marker.set_pos([hue.pos[0], hue.pos[1] - hue.size[1] / 2.0 + hsv[0] * hue.size[1]]);
+    }
+}
+
+impl EventGUIComponent for ColorPicker{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.enabled{
+            self.border.render(render_pass);
+            render_pass.set_pipeline(&self.square_pipeline);
+            self.square.render(render_pass);
+            render_pass.set_pipeline(&self.hue_pipeline);
+            self.hue.render(render_pass);
+            // Back to plain quads for the markers, and whatever gets drawn after us
+            render_pass.set_pipeline(&self.default_pipeline);
+            self.square_marker.render(render_pass);
+            self.square_swatch.render(render_pass);
+            self.hue_marker.render(render_pass);
+            self.dropper_button.render(render_pass);
+            self.dropper_swatch.render(render_pass);
+            self.eyedropper.render(render_pass);
+        }
+    }
+
+    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        if !self.enabled{
+            return;
+        }
+
+        match self.input.handle_event(event, window){
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed: true, pos, .. })) => {
+                if self.dropper_rect().contains(pos){
+                    self.pick_from_screen();
+                    return;
+                }
+                let part = if self.square_rect().contains(pos){
+                    Some(PickerPart::Square)
+                }else if self.hue_rect().contains(pos){
+                    Some(PickerPart::Hue)
+                }else{
+                    None
+                };
+                if let Some(part) = part{
+                    self.dragging = Some(part);
+                    self.drag_to(part, pos);
+                }
+            }
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed: false, .. })) => self.dragging = None,
+            Some(UiEvent::CursorMoved{ pos, .. }) => {
+                if let Some(part) = self.dragging{
+                    self.drag_to(part, pos);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The mouse is ours while dragging, so components under the cursor don't react to the drag. While picking off
+    /// the screen, the eyedropper gets the click (and escape) instead.
+    fn handle_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window, phase: EventPhase) -> EventResponse{
+        if self.enabled && self.eyedropper.handle_event(event, window, phase) == EventResponse::Consumed{
+            return EventResponse::Consumed;
+        }
+        if phase != EventPhase::Bubble{
+            return EventResponse::Continue;
+        }
+        let was_dragging = self.is_dragging();
+        self.handle_event_callback(event, window);
+        let mouse = InputCategory::of(event) == Some(InputCategory::Mouse);
+        if mouse && (was_dragging || self.is_dragging()) { EventResponse::Consumed } else { EventResponse::Continue }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.rect.pos
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(self.rect)
+    }
+
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{
+        Some(self.rect.size)
+    }
+
+    fn arrange(&mut self, rect: Rect){
+        self.set_rect(rect);
+    }
+
+    fn update(&mut self, _dt: f32){
+        let old_color = self.get_color();
+        if self.color.take_pick(&self.picked){
+            self.update_markers();
+            self.changed_from(old_color);
+        }
+    }
+
+    /// Keep ticking while picking off the screen, until the picked colour has been read back and applied
+    fn is_animating(&self) -> bool{
+        self.eyedropper.is_animating() || self.picked.get().is_some()
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        let swatch = if self.eyedropper.is_active(){
+            let [r, g, b, a] = self.eyedropper.get_preview();
+            [srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b), a]
+        }else{
+            self.get_color()
+        };
+        self.dropper_swatch.set_color(swatch);
+        self.dropper_button.set_color(if self.eyedropper.is_active() { [0.8, 0.8, 0.8, 1.0] } else { [0.3, 0.3, 0.3, 1.0] });
+
+        if let (Some(square), Some(hue)) = (self.pipelines.get(SQUARE_PIPELINE), self.pipelines.get(HUE_PIPELINE)){
+            self.square_pipeline = square;
+            self.hue_pipeline = hue;
+        }
+        self.default_pipeline = self.pipelines.get_default();
+        self.border.prepare(queue, screen_dim);
+        self.square.prepare(queue, screen_dim);
+        self.hue.prepare(queue, screen_dim);
+        self.square_marker.prepare(queue, screen_dim);
+        self.square_swatch.prepare(queue, screen_dim);
+        self.hue_marker.prepare(queue, screen_dim);
+        self.dropper_button.prepare(queue, screen_dim);
+        self.dropper_swatch.prepare(queue, screen_dim);
+        self.eyedropper.prepare(queue, screen_dim);
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use crate::rendering::PixelPicker;
+
+    /// Test that a colour picked off the screen moves the picker to it, made linear, and only once
+    #[test]
+    fn take_pick_test(){
+        let picked: PickedSlot = Rc::new(Cell::new(None));
+        let mut color = PickerColor{ hsv: [0.5, 0.0, 0.0], alpha: 1.0 };
+        assert!(!color.take_pick(&picked));
+
+        // Answer a pick the way the renderer does, after the frame is drawn
+        let pixels = PixelPicker::new();
+        pixels.pick([1, 1], pick_into(&picked));
+        for (_, answer) in pixels.take_requests(){
+            answer([1.0, 0.0, 0.0, 0.5]);
+        }
+        assert!(color.take_pick(&picked));
+        assert_eq!(color.hsv, [0.0, 1.0, 1.0]);
+        assert_eq!(color.alpha, 0.5);
+        assert!(!color.take_pick(&picked));
+
+        // Greys keep the hue the strip was on, and come out darker once they're linear
+        picked.set(Some([0.5, 0.5, 0.5, 1.0]));
+        assert!(color.take_pick(&picked));
+        assert_eq!(color.hsv[0], 0.0);
+        assert!((color.hsv[2] - srgb_to_linear(0.5)).abs() < 1e-6);
+        assert!(color.hsv[2] < 0.25);
+    }
+}
@@ -0,0 +1,358 @@
+//! This module contains the `CreditsRoller` component, which scrolls a long block of text (like a game's credits) up
+//! through its rect, and can be paused, sped up and skipped.
+
+use std::any::Any;
+
+use wgpu_glyph::{HorizontalAlign, VerticalAlign};
+
+use crate::{input::{EventPhase, EventResponse, InputState, Key, KeyEvent, MouseButton, MouseEvent, UiEvent}, rendering::{Quad, Renderer, TextBrush}};
+
+use super::{EventGUIComponent, Label, Rect, TextGUIComponent};
+
+// How much bigger headings (`# `) and subheadings (`## `) are than the rest of the text
+const HEADING_SCALE: f32 = 1.6;
+const SUBHEADING_SCALE: f32 = 1.25;
+// The height of a line, as a multiple of its text size
+const LINE_HEIGHT: f32 = 1.2;
+
+/// Split credits up into lines, each with its text and text size. A line starting with `# ` is a heading and one
+/// starting with `## ` is a subheading, which are drawn bigger (without the marker). Blank lines are kept, as gaps.
+pub(crate) fn parse_credits(text: &str, text_size: f32) -> Vec<(String, f32)>{
+    text.lines().map(|line| {
+        let line = line.trim();
+        if let Some(heading) = line.strip_prefix("## "){
+            (heading.to_string(), text_size * SUBHEADING_SCALE)
+        }else if let Some(heading) = line.strip_prefix("# "){
+            (heading.to_string(), text_size * HEADING_SCALE)
+        }else{
+            (line.to_string(), text_size)
+        }
+    }).collect()
+}
+
+struct CreditsLine{
+    label: Label,
+    offset: f32, // the middle of the line, down from the top of the text
+    height: f32,
+}
+
+/// # CreditsRoller
+///
+/// Scrolls a block of text up through its rect at a steady speed, starting just below the bottom and finishing once
+/// the last line has gone off the top, when the finished callback runs. Every line is centred, and lines starting
+/// with `# ` and `## ` are headings, drawn bigger without the marker:
+///
+/// ```ignore
+/// let credits = CreditsRoller::new(rect, "# Programming\nAda\nGrace\n\n# Art\nFrida", 20.0, &renderer);
+/// ```
+///
+/// While it's enabled, space pauses and unpauses it, holding enter, the down arrow or the left mouse button (pressed
+/// over it) speeds it up, and escape skips to the end. Those keys are consumed.
+///
+/// The text isn't cut off at the edges of the rect, so the roller is best filling the screen.
+pub struct CreditsRoller{
+    rect: Rect,
+    text_size: f32,
+    lines: Vec<CreditsLine>,
+    content_height: f32,
+    scroll: f32, // how far the text has moved up, in pixels
+    speed: f32,
+    fast_multiplier: f32,
+    paused: bool,
+    finished: bool,
+    on_finished: Option<Box<dyn Fn()>>,
+
+    background: Quad,
+    input: InputState,
+    fast_key: bool,
+    fast_mouse: bool,
+    enabled: bool,
+}
+
+impl CreditsRoller{
+    /// Create a roller filling `rect`, starting to scroll `text` up at 40 pixels a second
+    pub fn new(rect: Rect, text: &str, text_size: f32, renderer: &Renderer) -> Self{
+        let mut roller = Self{
+            rect,
+            text_size,
+            lines: Vec::new(),
+            content_height: 0.0,
+            scroll: 0.0,
+            speed: 40.0,
+            fast_multiplier: 4.0,
+            paused: false,
+            finished: false,
+            on_finished: None,
+
+            background: Quad::new(rect.pos, rect.size, [0.95, 0.95, 0.95, 1.0], &renderer.device),
+            input: InputState::new(),
+            fast_key: false,
+            fast_mouse: false,
+            enabled: true,
+        };
+        roller.set_text(text);
+        roller
+    }
+
+    /// Replace the text, and start scrolling it from the bottom again
+    pub fn set_text(&mut self, text: &str){
+        let mut offset = 0.0;
+        self.lines = parse_credits(text, self.text_size).into_iter().map(|(text, size)| {
+            let mut label = Label::new(text.as_str(), size, [0.0, 0.0]);
+            label.align_horizontal(HorizontalAlign::Center);
+            label.align_vertical(VerticalAlign::Center);
+            let height = size * LINE_HEIGHT;
+            offset += height;
+            CreditsLine{
+                label,
+                offset: offset - height / 2.0,
+                height,
+            }
+        }).collect();
+        self.content_height = offset;
+        self.restart();
+    }
+
+    /// Set how fast the text scrolls, in pixels a second
+    pub fn set_speed(&mut self, speed: f32){
+        self.speed = speed.max(0.0);
+    }
+
+    pub fn get_speed(&self) -> f32{
+        self.speed
+    }
+
+    /// Set how many times faster the text goes while it's being sped up. 4 by default.
+    pub fn set_fast_multiplier(&mut self, multiplier: f32){
+        self.fast_multiplier = multiplier.max(1.0);
+    }
+
+    pub fn set_paused(&mut self, paused: bool){
+        self.paused = paused;
+    }
+
+    pub fn is_paused(&self) -> bool{
+        self.paused
+    }
+
+    /// True while the text is going faster, because the key or mouse button is held
+    pub fn is_fast(&self) -> bool{
+        self.fast_key || self.fast_mouse
+    }
+
+    /// Set the function to run once the last line has gone off the top (or the credits were skipped)
+    pub fn set_on_finished(&mut self, callback: Option<Box<dyn Fn()>>){
+        self.on_finished = callback;
+    }
+
+    pub fn is_finished(&self) -> bool{
+        self.finished
+    }
+
+    /// Start scrolling from the bottom again
+    pub fn restart(&mut self){
+        self.scroll = 0.0;
+        self.finished = false;
+    }
+
+    /// Jump to the end, running the finished callback
+    pub fn skip(&mut self){
+        self.scroll = self.get_length();
+        self.finish();
+    }
+
+    /// How far the text has scrolled, in pixels
+    pub fn get_scroll(&self) -> f32{
+        self.scroll
+    }
+
+    /// How far the text scrolls in total, from the first line coming in at the bottom to the last going off the top
+    pub fn get_length(&self) -> f32{
+        self.content_height + self.rect.size[1]
+    }
+
+    pub fn set_rect(&mut self, rect: Rect){
+        self.rect = rect;
+        self.background.set_pos(rect.pos);
+        self.background.set_size(rect.size);
+    }
+
+    pub fn get_rect(&self) -> Rect{
+        self.rect
+    }
+
+    pub fn set_background_color(&mut self, color: [f32; 4]){
+        self.background.set_color(color);
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+        self.fast_key = false;
+        self.fast_mouse = false;
+    }
+
+    fn finish(&mut self){
+        if !self.finished{
+            self.finished = true;
+            if let Some(callback) = &self.on_finished{
+                callback();
+            }
+        }
+    }
+
+    // Where the middle of a line is, going up from just below the bottom of the rect
+    fn line_y(&self, line: &CreditsLine) -> f32{
+        self.rect.pos[1] + self.rect.size[1] / 2.0 + line.offset - self.scroll
+    }
+
+    fn is_line_visible(&self, line: &CreditsLine) -> bool{
+        let y = self.line_y(line);
+        y + line.height / 2.0 > self.rect.pos[1] - self.rect.size[1] / 2.0 && y - line.height / 2.0 < self.rect.pos[1] + self.rect.size[1] / 2.0
+    }
+
+    // Handle an event, returning true if it was one of ours
+    fn handle_input(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window) -> bool{
+        let ui_event = self.input.handle_event(event, window);
+        if !self.enabled{
+            return false;
+        }
+        match ui_event{
+            Some(UiEvent::Key(KeyEvent{ key, pressed, repeat, .. })) => match key{
+                Key::Space => {
+                    if pressed && !repeat{
+                        self.paused = !self.paused;
+                    }
+                    true
+                }
+                Key::Enter | Key::Down => {
+                    self.fast_key = pressed;
+                    true
+                }
+                Key::Escape => {
+                    if pressed{
+                        self.skip();
+                    }
+                    true
+                }
+                _ => false,
+            },
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed, pos, .. })) => {
+                self.fast_mouse = pressed && self.rect.contains(pos);
+                self.fast_mouse
+            }
+            _ => false,
+        }
+    }
+}
+
+impl EventGUIComponent for CreditsRoller{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.enabled{
+            self.background.render(render_pass);
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if self.enabled{
+            for line in self.lines.iter().filter(|line| self.is_line_visible(line)){
+                TextGUIComponent::render_text(&line.label, brush);
+            }
+        }
+    }
+
+    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        self.handle_input(event, window);
+    }
+
+    /// The pause, speed up and skip keys are consumed, and so are clicks on the roller
+    fn handle_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window, phase: EventPhase) -> EventResponse{
+        if phase != EventPhase::Bubble{
+            return EventResponse::Continue;
+        }
+        if self.handle_input(event, window) { EventResponse::Consumed } else { EventResponse::Continue }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.rect.pos
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(self.rect)
+    }
+
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{
+        Some(self.rect.size)
+    }
+
+    fn arrange(&mut self, rect: Rect){
+        if rect != self.rect{
+            self.set_rect(rect);
+        }
+    }
+
+    fn update(&mut self, dt: f32){
+        if !self.is_animating(){
+            return;
+        }
+        let speed = if self.is_fast() { self.speed * self.fast_multiplier } else { self.speed };
+        self.scroll += speed * dt;
+        if self.scroll >= self.get_length(){
+            self.scroll = self.get_length();
+            self.finish();
+        }
+    }
+
+    fn is_animating(&self) -> bool{
+        self.enabled && !self.paused && !self.finished
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.background.prepare(queue, screen_dim);
+        for i in 0..self.lines.len(){
+            if self.is_line_visible(&self.lines[i]){
+                let pos = [self.rect.pos[0], self.line_y(&self.lines[i])];
+                self.lines[i].label.set_pos(pos, screen_dim);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Test that credits are split into lines, with the heading markers turned into bigger text
+    #[test]
+    fn parse_credits_test(){
+        let lines = parse_credits("# Programming\n  Ada\n\n## Tools\nGrace", 20.0);
+        assert_eq!(lines, vec![
+            ("Programming".to_string(), 32.0),
+            ("Ada".to_string(), 20.0),
+            (String::new(), 20.0),
+            ("Tools".to_string(), 25.0),
+            ("Grace".to_string(), 20.0),
+        ]);
+    }
+}
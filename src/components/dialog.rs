@@ -0,0 +1,297 @@
+//! This module contains the `Dialog` component, a modal popup with a message and a row of buttons. While it's open,
+//! the rest of the GUI is darkened and gets no input until one of the buttons is picked.
+
+use std::any::Any;
+
+use wgpu_glyph::{HorizontalAlign, VerticalAlign};
+
+use crate::{input::{InputState, Key, KeyEvent, MouseButton, MouseEvent, UiEvent}, rendering::{Quad, Renderer, TextBrush}};
+
+use super::{EventGUIComponent, Label, Rect, TextGUIComponent};
+
+// The space around the message and buttons, and between the buttons, in pixels
+const PADDING: f32 = 16.0;
+// How wide the message can get before it wraps
+const MESSAGE_WIDTH: f32 = 400.0;
+
+// A button along the bottom of the dialog
+struct DialogButton{
+    quad: Quad,
+    label: Label,
+}
+
+/// # Dialog
+///
+/// A modal popup showing a message, with buttons like OK and Cancel underneath. While it's open it darkens
+/// everything behind it and captures all input (see `EventGUIComponent::is_modal`), so nothing else in the
+/// layout - or the layouts under it - can be clicked or typed into.
+///
+/// Picking a button closes the dialog and runs the callback with the button's index. Enter and Escape pick the
+/// default and cancel buttons, if they're set (see `set_keys`). Dialogs belong in the overlay layer
+/// (`Layout::add_overlay_component`), and open as soon as they're created. They can be opened again with `open`.
+pub struct Dialog{
+    pos: [f32; 2],
+    button_size: [f32; 2],
+    callback: Option<Box<dyn Fn(usize)>>,
+    result: Option<usize>,
+    default_button: Option<usize>,
+    cancel_button: Option<usize>,
+
+    shade: Quad,
+    border: Quad,
+    panel: Quad,
+    message: Label,
+    message_size: [f32; 2],
+    buttons: Vec<DialogButton>,
+
+    input: InputState,
+    pressed: Option<usize>, // the button the mouse went down on
+    open: bool,
+}
+
+impl Dialog{
+    /// Create an open dialog centred on the screen, with a button for each name, left to right
+    pub fn new(message: &str, buttons: &[&str], text_size: f32, renderer: &mut Renderer) -> Self{
+        let mut label = Label::new(message, text_size, [0.0, 0.0]);
+        label.set_max_width(Some(MESSAGE_WIDTH));
+        label.align_horizontal(HorizontalAlign::Center);
+        let message_size = label.measure(renderer);
+        let button_size = [100.0, text_size + PADDING];
+
+        let buttons = buttons.iter().map(|name| {
+            let mut label = Label::new(*name, text_size, [0.0, 0.0]);
+            label.align_vertical(VerticalAlign::Center);
+            label.align_horizontal(HorizontalAlign::Center);
+            DialogButton{
+                quad: Quad::new([0.0, 0.0], button_size, [0.85, 0.85, 0.85, 1.0], &renderer.device),
+                label,
+            }
+        }).collect();
+
+        let mut dialog = Self{
+            pos: [0.0, 0.0],
+            button_size,
+            callback: None,
+            result: None,
+            default_button: None,
+            cancel_button: None,
+
+            shade: Quad::new([0.0, 0.0], [0.0, 0.0], [0.0, 0.0, 0.0, 0.5], &renderer.device),
+            border: Quad::new([0.0, 0.0], [0.0, 0.0], [0.3, 0.3, 0.3, 1.0], &renderer.device),
+            panel: Quad::new([0.0, 0.0], [0.0, 0.0], [1.0, 1.0, 1.0, 1.0], &renderer.device),
+            message: label,
+            message_size,
+            buttons,
+
+            input: InputState::new(),
+            pressed: None,
+            open: true,
+        };
+        dialog.set_pos([0.0, 0.0]);
+        dialog
+    }
+
+    /// Create a dialog with OK and Cancel buttons (0 and 1), where Enter picks OK and Escape picks Cancel
+    pub fn ok_cancel(message: &str, text_size: f32, renderer: &mut Renderer) -> Self{
+        let mut dialog = Self::new(message, &["OK", "Cancel"], text_size, renderer);
+        dialog.set_keys(Some(0), Some(1));
+        dialog
+    }
+
+    /// Set the function to run with the index of the picked button
+    pub fn set_callback(&mut self, callback: Option<Box<dyn Fn(usize)>>){
+        self.callback = callback;
+    }
+
+    /// Set the buttons Enter and Escape pick. `None` leaves the key doing nothing.
+    pub fn set_keys(&mut self, default_button: Option<usize>, cancel_button: Option<usize>){
+        self.default_button = default_button;
+        self.cancel_button = cancel_button;
+    }
+
+    /// Change the message, measuring it again so the dialog fits around it
+    pub fn set_message(&mut self, message: &str, renderer: &mut Renderer){
+        self.message.set_text(message);
+        self.message_size = self.message.measure(renderer);
+        self.set_pos(self.pos);
+    }
+
+    /// Set the colours of the shade over the rest of the GUI, the panel and the buttons
+    pub fn set_colors(&mut self, shade: [f32; 4], panel: [f32; 4], button: [f32; 4]){
+        self.shade.set_color(shade);
+        self.panel.set_color(panel);
+        for dialog_button in self.buttons.iter_mut(){
+            dialog_button.quad.set_color(button);
+        }
+    }
+
+    /// Move the dialog, centring it on `pos`
+    pub fn set_pos(&mut self, pos: [f32; 2]){
+        self.pos = pos;
+        let size = self.get_size();
+        self.panel.set_pos(pos);
+        self.panel.set_size(size);
+        self.border.set_pos(pos);
+        self.border.set_size([size[0] + 2.0, size[1] + 2.0]);
+
+        let count = self.buttons.len() as f32;
+        let row_width = count * self.button_size[0] + (count - 1.0).max(0.0) * PADDING;
+        let y = pos[1] + size[1] / 2.0 - PADDING - self.button_size[1] / 2.0;
+        for (i, button) in self.buttons.iter_mut().enumerate(){
+            let x = pos[0] - row_width / 2.0 + self.button_size[0] / 2.0 + i as f32 * (self.button_size[0] + PADDING);
+            button.quad.set_pos([x, y]);
+        }
+    }
+
+    /// The size of the panel, buttons and padding included
+    pub fn get_size(&self) -> [f32; 2]{
+        let count = self.buttons.len() as f32;
+        let row_width = count * self.button_size[0] + (count - 1.0).max(0.0) * PADDING;
+        [
+            self.message_size[0].max(row_width) + PADDING * 2.0,
+            self.message_size[1] + self.button_size[1] + PADDING * 3.0,
+        ]
+    }
+
+    /// Open the dialog again, forgetting the last result
+    pub fn open(&mut self){
+        self.open = true;
+        self.result = None;
+    }
+
+    /// Close the dialog without picking a button or running the callback
+    pub fn close(&mut self){
+        self.open = false;
+        self.pressed = None;
+    }
+
+    pub fn is_open(&self) -> bool{
+        self.open
+    }
+
+    /// Pick a button like a click on it would, closing the dialog and running the callback
+    pub fn resolve(&mut self, index: usize){
+        if !self.open || index >= self.buttons.len(){
+            return;
+        }
+        self.close();
+        self.result = Some(index);
+        if let Some(callback) = &self.callback{
+            callback(index);
+        }
+    }
+
+    /// The button picked the last time the dialog was open, if one was
+    pub fn get_result(&self) -> Option<usize>{
+        self.result
+    }
+
+    /// Borrow the label of a button, for example to change its font
+    pub fn borrow_button_label_mut(&mut self, index: usize) -> Option<&mut Label>{
+        self.buttons.get_mut(index).map(|button| &mut button.label)
+    }
+
+    fn button_at(&self, pos: [f32; 2]) -> Option<usize>{
+        self.buttons.iter().position(|button| Rect::new(button.quad.get_pos(), self.button_size).contains(pos))
+    }
+}
+
+impl EventGUIComponent for Dialog{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.open{
+            self.shade.render(render_pass);
+            self.border.render(render_pass);
+            self.panel.render(render_pass);
+            for button in self.buttons.iter(){
+                button.quad.render(render_pass);
+            }
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if self.open{
+            TextGUIComponent::render_text(&self.message, brush);
+            for button in self.buttons.iter(){
+                TextGUIComponent::render_text(&button.label, brush);
+            }
+        }
+    }
+
+    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        if !self.open{
+            return;
+        }
+
+        match self.input.handle_event(event, window){
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed, pos, .. })) => {
+                let button = self.button_at(pos);
+                if pressed{
+                    self.pressed = button;
+                }else{
+                    if let (Some(pressed), Some(button)) = (self.pressed, button){
+                        if pressed == button{
+                            self.resolve(button);
+                        }
+                    }
+                    self.pressed = None;
+                }
+            }
+            Some(UiEvent::Key(KeyEvent{ key, pressed: true, repeat: false, .. })) => {
+                let button = match key{
+                    Key::Enter => self.default_button,
+                    Key::Escape => self.cancel_button,
+                    _ => None,
+                };
+                if let Some(button) = button{
+                    self.resolve(button);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.open
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.pos
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(Rect::new(self.pos, self.get_size()))
+    }
+
+    fn is_modal(&self) -> bool{
+        self.open
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        // The shade covers the whole screen, whatever size it is
+        self.shade.set_size([screen_dim.0 as f32, screen_dim.1 as f32]);
+        self.shade.prepare(queue, screen_dim);
+        self.border.prepare(queue, screen_dim);
+        self.panel.prepare(queue, screen_dim);
+
+        let size = self.get_size();
+        self.message.set_pos([self.pos[0], self.pos[1] - size[1] / 2.0 + PADDING], screen_dim);
+        for button in self.buttons.iter_mut(){
+            button.quad.prepare(queue, screen_dim);
+            button.label.set_pos(button.quad.get_pos(), screen_dim);
+        }
+    }
+}
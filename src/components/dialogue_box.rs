@@ -0,0 +1,559 @@
+//! This module contains the `DialogueBox` component, the conversation box along the bottom of the screen in lots of
+//! games. Text types itself out a page at a time next to a portrait of whoever's talking, and can end in a choice.
+
+use std::any::Any;
+
+use wgpu_glyph::VerticalAlign;
+
+use crate::{input::{EventPhase, EventResponse, InputState, Key, KeyEvent, MouseButton, MouseEvent, UiEvent}, rendering::{Quad, Renderer, TextBrush, Texture}};
+
+use super::{EventGUIComponent, Label, Rect, TextEffect, TextGUIComponent};
+
+// The space around the portrait, text and choices
+const PADDING: f32 = 16.0;
+// The size of the marker showing there's more to read, and how far it bobs
+const INDICATOR_SIZE: f32 = 10.0;
+const INDICATOR_BOB: f32 = 3.0;
+
+// The pages of a conversation, typed out a page at a time into `text`, and how far through them it is. `choices`
+// is how many choices are offered after the last page.
+struct Conversation{
+    pages: Vec<String>,
+    page: usize,
+    text: Label,
+    choices: usize,
+    result: Option<usize>,
+    open: bool,
+}
+
+impl Conversation{
+    fn new(text: Label) -> Self{
+        Self{
+            pages: Vec::new(),
+            page: 0,
+            text,
+            choices: 0,
+            result: None,
+            open: false,
+        }
+    }
+
+    // Start on the first of some pages, opening if there are any
+    fn say(&mut self, pages: &[&str]){
+        self.pages = pages.iter().map(|page| page.to_string()).collect();
+        self.page = 0;
+        self.result = None;
+        self.open = !self.pages.is_empty();
+        self.show_page();
+    }
+
+    // Finish typing the page, or go on to the next one, or end the conversation if there's nothing left to read
+    // and no choice to make. Returns whether it ended.
+    fn advance(&mut self) -> bool{
+        if !self.open || self.showing_choices(){
+            return false;
+        }
+        if self.text.is_typing(){
+            self.text.finish_typing();
+        }else if self.page + 1 < self.pages.len(){
+            self.page += 1;
+            self.show_page();
+        }else if self.choices == 0{
+            self.finish(None);
+            return true;
+        }
+        false
+    }
+
+    // End the conversation with a choice, if the choices are showing. Returns whether it ended.
+    fn choose(&mut self, index: usize) -> bool{
+        if !self.showing_choices() || index >= self.choices{
+            return false;
+        }
+        self.finish(Some(index));
+        true
+    }
+
+    fn is_typing(&self) -> bool{
+        self.open && self.text.is_typing()
+    }
+
+    fn showing_choices(&self) -> bool{
+        self.open && self.choices > 0 && self.page + 1 >= self.pages.len() && !self.text.is_typing()
+    }
+
+    fn show_page(&mut self){
+        self.text.set_text(self.pages.get(self.page).map(String::as_str).unwrap_or(""));
+        self.text.restart_effects();
+    }
+
+    fn finish(&mut self, choice: Option<usize>){
+        self.open = false;
+        self.result = choice;
+    }
+}
+
+// An option in the choice list
+struct Choice{
+    quad: Quad,
+    label: Label,
+}
+
+/// # DialogueBox
+///
+/// A box of dialogue, with a portrait slot on the left, the speaker's name and their lines typed out beside it.
+/// Give it the lines a page at a time with `say` - each page types out at the typing speed, and a click (or Enter
+/// or Space) either finishes typing the page or moves on to the next one. A marker bobs in the corner while a page
+/// is waiting to be moved on from.
+///
+/// Choices set with `set_choices` show up in a list above the box once the last page is typed out, and can be
+/// picked with the mouse or the arrow keys and Enter. When the conversation is over the box closes and the callback
+/// gets the picked choice, or `None` if there weren't any.
+///
+/// Dialogue boxes belong in the overlay layer (`Layout::add_overlay_component`). They take clicks on themselves
+/// and the keys they use, but aren't modal.
+pub struct DialogueBox{
+    rect: Rect,
+    text_size: f32,
+    conversation: Conversation,
+    callback: Option<Box<dyn Fn(Option<usize>)>>,
+
+    border: Quad,
+    panel: Quad,
+    portrait: Option<(Quad, Texture)>,
+    speaker: Label,
+    indicator: Quad,
+    choices: Vec<Choice>,
+    choice_size: [f32; 2],
+    highlighted: Option<usize>,
+    choice_color: [f32; 4],
+    highlight_color: [f32; 4],
+
+    input: InputState,
+    time: f32,
+}
+
+impl DialogueBox{
+    /// Create a closed dialogue box, centred on `pos`. Open it by giving it something to `say`.
+    pub fn new(pos: [f32; 2], size: [f32; 2], text_size: f32, renderer: &Renderer) -> Self{
+        let speaker = Label::new("", text_size, [0.0, 0.0]);
+        let mut text = Label::new("", text_size, [0.0, 0.0]);
+        text.add_effect(TextEffect::Typewriter{ chars_per_second: 40.0 });
+
+        let mut dialogue_box = Self{
+            rect: Rect::new(pos, size),
+            text_size,
+            conversation: Conversation::new(text),
+            callback: None,
+
+            border: Quad::new([0.0, 0.0], [0.0, 0.0], [0.3, 0.3, 0.3, 1.0], &renderer.device),
+            panel: Quad::new([0.0, 0.0], [0.0, 0.0], [0.97, 0.97, 0.97, 1.0], &renderer.device),
+            portrait: None,
+            speaker,
+            indicator: Quad::new([0.0, 0.0], [INDICATOR_SIZE, INDICATOR_SIZE], [0.3, 0.3, 0.3, 1.0], &renderer.device),
+            choices: Vec::new(),
+            choice_size: [0.0, 0.0],
+            highlighted: None,
+            choice_color: [0.97, 0.97, 0.97, 1.0],
+            highlight_color: [0.75, 0.85, 1.0, 1.0],
+
+            input: InputState::new(),
+            time: 0.0,
+        };
+        dialogue_box.set_rect(dialogue_box.rect);
+        dialogue_box
+    }
+
+    /// Set the function to run when the conversation ends, with the picked choice (if there were choices)
+    pub fn set_callback(&mut self, callback: Option<Box<dyn Fn(Option<usize>)>>){
+        self.callback = callback;
+    }
+
+    /// Set how many characters a second the text types out at. This replaces any effects on the text.
+    pub fn set_typing_speed(&mut self, chars_per_second: f32){
+        self.conversation.text.clear_effects();
+        self.conversation.text.add_effect(TextEffect::Typewriter{ chars_per_second });
+    }
+
+    /// Set the picture of whoever's talking, or `None` to leave the slot empty. The text moves over to fill the
+    /// space when there's no portrait.
+    pub fn set_portrait(&mut self, portrait: Option<Texture>, renderer: &Renderer){
+        self.portrait = portrait.map(|texture| (Quad::new([0.0, 0.0], [0.0, 0.0], [1.0, 1.0, 1.0, 1.0], &renderer.device), texture));
+        self.set_rect(self.rect);
+    }
+
+    /// Set the name shown above the text, or `None` for no name
+    pub fn set_speaker(&mut self, name: Option<&str>){
+        self.speaker.set_text(name.unwrap_or(""));
+    }
+
+    /// Open the box and start typing out some pages of dialogue, forgetting the last result
+    pub fn say(&mut self, pages: &[&str]){
+        self.conversation.say(pages);
+        self.highlighted = None;
+    }
+
+    /// Set the choices offered after the last page, replacing any already set
+    pub fn set_choices(&mut self, choices: &[&str], renderer: &mut Renderer){
+        let mut labels: Vec<Label> = choices.iter().map(|choice| {
+            let mut label = Label::new(*choice, self.text_size, [0.0, 0.0]);
+            label.align_vertical(VerticalAlign::Center);
+            label
+        }).collect();
+        let widest = labels.iter_mut().map(|label| label.measure(renderer)[0]).fold(0.0, f32::max);
+        self.choice_size = [widest + PADDING * 2.0, self.text_size + PADDING];
+        self.conversation.choices = labels.len();
+        self.choices = labels.into_iter().map(|label| Choice{
+            quad: Quad::new([0.0, 0.0], self.choice_size, self.choice_color, &renderer.device),
+            label,
+        }).collect();
+        self.highlighted = None;
+        self.set_rect(self.rect);
+    }
+
+    /// Set the colours of the panel, the choices and the highlighted choice
+    pub fn set_colors(&mut self, panel: [f32; 4], choice: [f32; 4], highlight: [f32; 4]){
+        self.panel.set_color(panel);
+        self.choice_color = choice;
+        self.highlight_color = highlight;
+        self.recolor_choices();
+    }
+
+    /// Move and resize the box
+    pub fn set_rect(&mut self, rect: Rect){
+        self.rect = rect;
+        self.panel.set_pos(rect.pos);
+        self.panel.set_size(rect.size);
+        self.border.set_pos(rect.pos);
+        self.border.set_size([rect.size[0] + 2.0, rect.size[1] + 2.0]);
+
+        let (left, right, top) = (rect.pos[0] - rect.size[0] / 2.0, rect.pos[0] + rect.size[0] / 2.0, rect.pos[1] - rect.size[1] / 2.0);
+        let side = (rect.size[1] - PADDING * 2.0).max(0.0);
+        if let Some((quad, _)) = &mut self.portrait{
+            quad.set_pos([left + PADDING + side / 2.0, rect.pos[1]]);
+            quad.set_size([side, side]);
+        }
+        self.conversation.text.set_max_width(Some((right - self.text_left() - PADDING).max(0.0)));
+
+        // The choices stack up from the top right corner of the box, the first furthest up
+        let count = self.choices.len();
+        for (i, choice) in self.choices.iter_mut().enumerate(){
+            let y = top - PADDING / 2.0 - (count - i) as f32 * self.choice_size[1] + self.choice_size[1] / 2.0;
+            choice.quad.set_pos([right - self.choice_size[0] / 2.0, y]);
+        }
+    }
+
+    pub fn get_rect(&self) -> Rect{
+        self.rect
+    }
+
+    /// Move the conversation on like a click would - finish typing the page, go to the next page, or (with no
+    /// choices to make) end the conversation
+    pub fn advance(&mut self){
+        if self.conversation.advance(){
+            self.finished();
+        }
+    }
+
+    /// Pick a choice like a click on it would, ending the conversation. Does nothing until the choices are showing.
+    pub fn choose(&mut self, index: usize){
+        if self.conversation.choose(index){
+            self.finished();
+        }
+    }
+
+    /// Close the box without ending the conversation or running the callback
+    pub fn close(&mut self){
+        self.conversation.open = false;
+    }
+
+    pub fn is_open(&self) -> bool{
+        self.conversation.open
+    }
+
+    /// Which page is showing, counting from 0
+    pub fn get_page(&self) -> usize{
+        self.conversation.page
+    }
+
+    pub fn get_page_count(&self) -> usize{
+        self.conversation.pages.len()
+    }
+
+    /// Whether the page is still typing out
+    pub fn is_typing(&self) -> bool{
+        self.conversation.is_typing()
+    }
+
+    /// Whether the choices are showing, waiting for one to be picked
+    pub fn showing_choices(&self) -> bool{
+        self.conversation.showing_choices()
+    }
+
+    /// The choice highlighted by the mouse or the arrow keys
+    pub fn get_highlighted(&self) -> Option<usize>{
+        self.highlighted
+    }
+
+    /// The choice picked the last time the conversation ended, if there was one
+    pub fn get_result(&self) -> Option<usize>{
+        self.conversation.result
+    }
+
+    /// Borrow the label the dialogue is typed into, for example to add effects or change the font
+    pub fn borrow_text_mut(&mut self) -> &mut Label{
+        &mut self.conversation.text
+    }
+
+    // Tell the callback how the conversation ended
+    fn finished(&mut self){
+        if let Some(callback) = &self.callback{
+            callback(self.conversation.result);
+        }
+    }
+
+    // The text sits to the right of the portrait, if there is one
+    fn text_left(&self) -> f32{
+        let left = self.rect.pos[0] - self.rect.size[0] / 2.0;
+        match self.portrait{
+            Some(_) => left + self.rect.size[1],
+            None => left + PADDING,
+        }
+    }
+
+    fn choice_at(&self, pos: [f32; 2]) -> Option<usize>{
+        self.choices.iter().position(|choice| Rect::new(choice.quad.get_pos(), self.choice_size).contains(pos))
+    }
+
+    fn highlight(&mut self, choice: Option<usize>){
+        if self.highlighted != choice{
+            self.highlighted = choice;
+            self.recolor_choices();
+        }
+    }
+
+    fn recolor_choices(&mut self){
+        for (i, choice) in self.choices.iter_mut().enumerate(){
+            choice.quad.set_color(if self.highlighted == Some(i) { self.highlight_color } else { self.choice_color });
+        }
+    }
+
+    // Whether the box has anything for the player to do, so it shouldn't let input through
+    fn takes(&self, pos: [f32; 2]) -> bool{
+        self.rect.contains(pos) || (self.showing_choices() && self.choice_at(pos).is_some())
+    }
+}
+
+impl EventGUIComponent for DialogueBox{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if !self.conversation.open{
+            return;
+        }
+        self.border.render(render_pass);
+        self.panel.render(render_pass);
+        if !self.conversation.text.is_typing(){
+            self.indicator.render(render_pass);
+        }
+        if self.showing_choices(){
+            for choice in self.choices.iter(){
+                choice.quad.render(render_pass);
+            }
+        }
+        // Last, as it swaps the texture out for the portrait's
+        if let Some((quad, texture)) = &self.portrait{
+            render_pass.set_bind_group(3, &texture.bind_group, &[]);
+            quad.render(render_pass);
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if !self.conversation.open{
+            return;
+        }
+        TextGUIComponent::render_text(&self.speaker, brush);
+        TextGUIComponent::render_text(&self.conversation.text, brush);
+        if self.showing_choices(){
+            for choice in self.choices.iter(){
+                TextGUIComponent::render_text(&choice.label, brush);
+            }
+        }
+    }
+
+    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        if !self.conversation.open{
+            return;
+        }
+
+        match self.input.handle_event(event, window){
+            Some(UiEvent::CursorMoved{ pos, .. }) if self.showing_choices() => {
+                if let Some(choice) = self.choice_at(pos){
+                    self.highlight(Some(choice));
+                }
+            }
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed: false, pos, .. })) => {
+                if self.showing_choices(){
+                    if let Some(choice) = self.choice_at(pos){
+                        self.choose(choice);
+                    }
+                }else if self.rect.contains(pos){
+                    self.advance();
+                }
+            }
+            Some(UiEvent::Key(KeyEvent{ key, pressed: true, .. })) if self.showing_choices() => {
+                let last = self.choices.len() - 1;
+                match (key, self.highlighted){
+                    (Key::Up, Some(i)) => self.highlight(Some(i.saturating_sub(1))),
+                    (Key::Down, Some(i)) => self.highlight(Some((i + 1).min(last))),
+                    (Key::Up, None) => self.highlight(Some(last)),
+                    (Key::Down, None) => self.highlight(Some(0)),
+                    (Key::Enter, Some(i)) | (Key::Space, Some(i)) => self.choose(i),
+                    _ => {}
+                }
+            }
+            Some(UiEvent::Key(KeyEvent{ key: Key::Enter, pressed: true, repeat: false, .. }))
+            | Some(UiEvent::Key(KeyEvent{ key: Key::Space, pressed: true, repeat: false, .. })) => self.advance(),
+            _ => {}
+        }
+    }
+
+    /// Clicks on the box and its choices are ours, so nothing behind them reacts too
+    fn handle_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window, phase: EventPhase) -> EventResponse{
+        if phase != EventPhase::Bubble{
+            return EventResponse::Continue;
+        }
+        let taken = self.conversation.open && matches!(event, winit::event::Event::WindowEvent{ event: winit::event::WindowEvent::MouseInput{ .. }, .. }) && self.takes(self.input.get_cursor_pos());
+        self.handle_event_callback(event, window);
+        if taken { EventResponse::Consumed } else { EventResponse::Continue }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.conversation.open
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.rect.pos
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(self.rect)
+    }
+
+    fn update(&mut self, dt: f32){
+        if self.conversation.open{
+            self.time += dt;
+            TextGUIComponent::update(&mut self.conversation.text, dt);
+        }
+    }
+
+    /// Open boxes keep animating, for the typing and the bobbing marker
+    fn is_animating(&self) -> bool{
+        self.conversation.open
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        let text_left = self.text_left();
+        let mut top = self.rect.pos[1] - self.rect.size[1] / 2.0 + PADDING;
+        self.speaker.set_pos([text_left, top], screen_dim);
+        if !self.speaker.get_text().is_empty(){
+            top += self.text_size * 1.5;
+        }
+        self.conversation.text.set_pos([text_left, top], screen_dim);
+
+        self.border.prepare(queue, screen_dim);
+        self.panel.prepare(queue, screen_dim);
+        let bob = (self.time * std::f32::consts::TAU).sin() * INDICATOR_BOB;
+        let corner = [self.rect.pos[0] + self.rect.size[0] / 2.0, self.rect.pos[1] + self.rect.size[1] / 2.0];
+        self.indicator.set_pos([corner[0] - PADDING, corner[1] - PADDING - INDICATOR_SIZE / 2.0 + bob]);
+        self.indicator.prepare(queue, screen_dim);
+        if let Some((quad, _)) = &mut self.portrait{
+            quad.prepare(queue, screen_dim);
+        }
+        for choice in self.choices.iter_mut(){
+            choice.quad.prepare(queue, screen_dim);
+            let pos = choice.quad.get_pos();
+            choice.label.set_pos([pos[0] - self.choice_size[0] / 2.0 + PADDING, pos[1]], screen_dim);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    // A conversation typing out ten characters a second
+    fn conversation() -> Conversation{
+        let mut text = Label::new("", 20.0, [0.0, 0.0]);
+        text.add_effect(TextEffect::Typewriter{ chars_per_second: 10.0 });
+        Conversation::new(text)
+    }
+
+    /// Test that advancing finishes typing a page, then moves to the next, then ends the conversation
+    #[test]
+    fn advance_test(){
+        let mut conversation = conversation();
+        conversation.say(&["Hello there", "Bye"]);
+        assert!(conversation.open);
+        assert!(conversation.is_typing());
+
+        assert!(!conversation.advance());
+        assert!(!conversation.is_typing());
+        assert_eq!(conversation.text.get_text(), "Hello there");
+        assert!(!conversation.advance());
+        assert_eq!(conversation.page, 1);
+        assert!(conversation.is_typing());
+
+        // Once the last page has typed out on its own, the next advance ends it
+        TextGUIComponent::update(&mut conversation.text, 1.0);
+        assert!(!conversation.is_typing());
+        assert!(conversation.advance());
+        assert!(!conversation.open);
+        assert_eq!(conversation.result, None);
+        assert!(!conversation.advance());
+
+        // Nothing to say doesn't open it at all
+        conversation.say(&[]);
+        assert!(!conversation.open);
+    }
+
+    /// Test that choices only show after the last page has typed out, and that picking one ends the conversation
+    #[test]
+    fn choices_test(){
+        let mut conversation = conversation();
+        conversation.choices = 2;
+        conversation.say(&["First", "Which one?"]);
+        assert!(!conversation.showing_choices());
+        assert!(!conversation.choose(0));
+
+        conversation.advance();
+        conversation.advance();
+        assert!(!conversation.showing_choices());
+        conversation.advance();
+        assert!(conversation.showing_choices());
+
+        // Advancing can't skip the choice, and only real choices can be picked
+        assert!(!conversation.advance());
+        assert!(conversation.open);
+        assert!(!conversation.choose(2));
+        assert!(conversation.choose(1));
+        assert!(!conversation.open);
+        assert_eq!(conversation.result, Some(1));
+
+        // Saying something new forgets the last result
+        conversation.say(&["Again"]);
+        assert_eq!(conversation.result, None);
+    }
+}
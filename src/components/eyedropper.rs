@@ -0,0 +1,234 @@
+//! This module contains the `Eyedropper` component, which picks a colour from anywhere in the window.
+//! It reads the pixels back from the rendered frame, so it sees exactly what's on screen (text included).
+//! Only the app's own window can be sampled, not the rest of the desktop.
+
+use std::{any::Any, cell::{Cell, RefCell}, rc::Rc};
+
+use winit::event::{ElementState, Event, KeyboardInput, MouseButton, VirtualKeyCode, WindowEvent};
+
+use crate::{input::{EventPhase, EventResponse}, rendering::{PixelPicker, Quad, Renderer}};
+
+use super::{EventGUIComponent, base_components::window_to_component_space};
+
+type ColorCallback = Rc<RefCell<Option<Box<dyn Fn([f32; 4])>>>>;
+
+/// # Eyedropper
+///
+/// Once activated, a swatch follows the cursor showing the colour under it. Clicking picks that colour
+/// and runs the callback with it (RGBA, 0 to 1), and pressing escape cancels.
+///
+/// While picking, the click and escape press are consumed, so they don't also reach whatever is under the cursor.
+/// Events go to the top of the layout first, so add it to the overlay layer to pick over everything else.
+pub struct Eyedropper{
+    picker: PixelPicker,
+    callback: ColorCallback,
+    preview: Rc<Cell<[f32; 4]>>, // the colour under the cursor, filled in by the renderer
+
+    border: Quad,
+    swatch: Quad,
+    swatch_size: f32,
+    cursor_pos: [f64; 2],
+    screen_dim: (u32, u32),
+    active: bool,
+    enabled: bool,
+}
+
+impl Eyedropper{
+    /// Create a new eyedropper. It starts off inactive, call `activate` to start picking.
+    pub fn new(callback: Option<Box<dyn Fn([f32; 4])>>, renderer: &Renderer) -> Self{
+        Self{
+            picker: renderer.get_pixel_picker(),
+            callback: Rc::new(RefCell::new(callback)),
+            preview: Rc::new(Cell::new([0.0, 0.0, 0.0, 1.0])),
+
+            border: Quad::new([0.0, 0.0], [0.0, 0.0], [0.0, 0.0, 0.0, 1.0], &renderer.device),
+            swatch: Quad::new([0.0, 0.0], [0.0, 0.0], [0.0, 0.0, 0.0, 1.0], &renderer.device),
+            swatch_size: 24.0,
+            cursor_pos: [0.0, 0.0],
+            screen_dim: (0, 0),
+            active: false,
+            enabled: true,
+        }
+    }
+
+    /// Set the function to run with the picked colour
+    pub fn set_callback(&mut self, callback: Option<Box<dyn Fn([f32; 4])>>){
+        *self.callback.borrow_mut() = callback;
+    }
+
+    /// Start picking. The next click picks a colour.
+    pub fn activate(&mut self){
+        self.active = true;
+        self.preview_cursor();
+    }
+
+    /// Stop picking without picking a colour
+    pub fn cancel(&mut self){
+        self.active = false;
+    }
+
+    pub fn is_active(&self) -> bool{
+        self.active
+    }
+
+    /// The colour that was under the cursor last time we looked
+    pub fn get_preview(&self) -> [f32; 4]{
+        self.preview.get()
+    }
+
+    /// Set the size of the swatch following the cursor
+    pub fn set_swatch_size(&mut self, swatch_size: f32){
+        self.swatch_size = swatch_size;
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+        self.active = false;
+    }
+
+    // The pixel under the cursor, if it's inside the window
+    fn cursor_pixel(&self) -> Option<[u32; 2]>{
+        if self.cursor_pos[0] < 0.0 || self.cursor_pos[1] < 0.0{
+            return None;
+        }
+        Some([self.cursor_pos[0] as u32, self.cursor_pos[1] as u32])
+    }
+
+    // Ask for the colour under the cursor, for the swatch. Only one at a time, so we don't pile up reads.
+    fn preview_cursor(&self){
+        if let Some(pixel) = self.cursor_pixel(){
+            if !self.picker.is_pending(){
+                let preview = self.preview.clone();
+                self.picker.pick(pixel, move |color| preview.set(color));
+            }
+        }
+    }
+
+    fn pick(&mut self){
+        if let Some(pixel) = self.cursor_pixel(){
+            let preview = self.preview.clone();
+            let callback = self.callback.clone();
+            self.picker.pick(pixel, move |color| {
+                preview.set(color);
+                if let Some(callback) = &*callback.borrow(){
+                    callback(color);
+                }
+            });
+        }
+        self.active = false;
+    }
+}
+
+impl EventGUIComponent for Eyedropper{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.enabled && self.active{
+            self.border.render(render_pass);
+            self.swatch.render(render_pass);
+        }
+    }
+
+    fn handle_event_callback(&mut self, event: &Event<()>, window: &winit::window::Window){
+        if !self.enabled{
+            return;
+        }
+
+        match event{
+            Event::WindowEvent{
+                ref event,
+                window_id,
+            } if window_id == &window.id() => {
+                match event{
+                    WindowEvent::CursorMoved{ position, .. } => {
+                        self.cursor_pos = [position.x, position.y];
+                        if self.active{
+                            self.preview_cursor();
+                        }
+                    }
+                    WindowEvent::MouseInput{ button: MouseButton::Left, state: ElementState::Pressed, .. } if self.active => {
+                        self.pick();
+                    }
+                    WindowEvent::KeyboardInput{ input: KeyboardInput{ state: ElementState::Pressed, virtual_keycode: Some(VirtualKeyCode::Escape), .. }, .. } if self.active => {
+                        self.cancel();
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_event(&mut self, event: &Event<()>, window: &winit::window::Window, phase: EventPhase) -> EventResponse{
+        // Grab the click as early as possible - inside a container, before the other children see it
+        let picking_input = matches!(event,
+            Event::WindowEvent{ event: WindowEvent::MouseInput{ button: MouseButton::Left, state: ElementState::Pressed, .. }, .. }
+            | Event::WindowEvent{ event: WindowEvent::KeyboardInput{ input: KeyboardInput{ state: ElementState::Pressed, virtual_keycode: Some(VirtualKeyCode::Escape), .. }, .. }, .. });
+        match phase{
+            EventPhase::Capture if self.enabled && self.active && picking_input => {
+                self.handle_event_callback(event, window);
+                EventResponse::Consumed
+            }
+            EventPhase::Capture => EventResponse::Continue,
+            EventPhase::Bubble => {
+                self.handle_event_callback(event, window);
+                EventResponse::Continue
+            }
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        window_to_component_space(self.cursor_pos, self.screen_dim)
+    }
+
+    /// Keep ticking while picking, so the swatch keeps up with the cursor
+    fn is_animating(&self) -> bool{
+        self.enabled && (self.active || self.picker.is_pending())
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.screen_dim = screen_dim;
+
+        // Sit the swatch off to the bottom right of the cursor, so it doesn't cover the pixel being picked
+        let cursor = window_to_component_space(self.cursor_pos, screen_dim);
+        let pos = [cursor[0] + self.swatch_size, cursor[1] + self.swatch_size];
+        self.border.set_pos(pos);
+        self.border.set_size([self.swatch_size + 4.0, self.swatch_size + 4.0]);
+        self.swatch.set_pos(pos);
+        self.swatch.set_size([self.swatch_size, self.swatch_size]);
+        // Picked colours are what's on screen (sRGB), but quad colours get written out as linear
+        let preview = self.preview.get();
+        self.swatch.set_color([srgb_to_linear(preview[0]), srgb_to_linear(preview[1]), srgb_to_linear(preview[2]), preview[3]]);
+
+        self.border.prepare(queue, screen_dim);
+        self.swatch.prepare(queue, screen_dim);
+    }
+}
+
+/// Turn a colour channel as it's shown on screen (sRGB) into the linear value a quad takes
+pub(crate) fn srgb_to_linear(value: f32) -> f32{
+    if value <= 0.04045{
+        value / 12.92
+    }else{
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
@@ -0,0 +1,325 @@
+//! This module contains the search logic behind the find-and-replace bar.
+//! It works on plain strings, so any text component can be searched by
+//! implementing the `Searchable` trait. The search keeps track of every match,
+//! which one is currently selected, and can replace one or all of them.
+//!
+//! `TextArea` and `TextInput` are both searchable, and draw the matches highlighted.
+
+use std::ops::Range;
+
+use winit::event::{ElementState, Event, WindowEvent};
+
+use crate::{input::{Key, Modifiers}, rendering::Quad};
+
+use super::Rect;
+
+/// # Searchable
+///
+/// Implement this trait on text components so they can be attached to a `TextSearch`.
+///
+/// The search reads the text through `get_text`, writes replacements back with `set_text`
+/// and tells the component what to highlight with `set_highlights`.
+pub trait Searchable{
+    fn get_text(&self) -> &str;
+    fn set_text(&mut self, text: String);
+    fn set_highlights(&mut self, matches: &[Range<usize>], current: Option<usize>);
+}
+
+/// The quads a text component draws behind the matches a search tells it about, in one colour for the matches and
+/// another for the current one. The component works out where the matches are once its text is laid out, and
+/// hands the rectangles to `place`.
+pub(crate) struct Highlights{
+    matches: Vec<Range<usize>>,
+    current: Option<usize>,
+    colors: [[f32; 4]; 2], // matches, the current match
+
+    quads: Vec<Quad>,
+    shown: usize, // how many of the quads are in use
+    wanted: usize, // how many quads the last `place` needed
+}
+
+impl Highlights{
+    pub(crate) fn new() -> Self{
+        Self{
+            matches: Vec::new(),
+            current: None,
+            colors: [[1.0, 0.92, 0.45, 1.0], [1.0, 0.65, 0.25, 1.0]],
+
+            quads: Vec::new(),
+            shown: 0,
+            wanted: 0,
+        }
+    }
+
+    /// Take the matches from `Searchable::set_highlights`, returning true if the current match is a different one
+    pub(crate) fn set(&mut self, matches: &[Range<usize>], current: Option<usize>) -> bool{
+        let moved = current.is_some() && current != self.current;
+        self.matches = matches.to_vec();
+        self.current = current;
+        moved
+    }
+
+    pub(crate) fn get_matches(&self) -> &[Range<usize>]{
+        &self.matches
+    }
+
+    pub(crate) fn get_current(&self) -> Option<usize>{
+        self.current
+    }
+
+    pub(crate) fn set_colors(&mut self, matches: [f32; 4], current: [f32; 4]){
+        self.colors = [matches, current];
+    }
+
+    /// Make a quad for every match, or as many as the last `place` needed if that was more (when matches wrap
+    /// across lines)
+    pub(crate) fn rebuild(&mut self, device: &wgpu::Device){
+        let count = self.matches.len().max(self.wanted);
+        if self.quads.len() < count{
+            self.quads.resize_with(count, || Quad::new([0.0, 0.0], [0.0, 0.0], [0.0, 0.0, 0.0, 0.0], device));
+        }
+    }
+
+    /// Cover some rectangles, each with whether it's part of the current match. Any that there aren't quads for yet
+    /// wait for the next `rebuild`.
+    pub(crate) fn place<I: IntoIterator<Item = (Rect, bool)>>(&mut self, rects: I){
+        let mut count = 0;
+        for (rect, current) in rects{
+            if let Some(quad) = self.quads.get_mut(count){
+                quad.set_pos(rect.pos);
+                quad.set_size(rect.size);
+                quad.set_color(self.colors[current as usize]);
+            }
+            count += 1;
+        }
+        self.wanted = count;
+        self.shown = count.min(self.quads.len());
+    }
+
+    /// Whether some matches couldn't be drawn for want of quads, so another frame is needed
+    pub(crate) fn is_short(&self) -> bool{
+        self.wanted > self.quads.len()
+    }
+
+    pub(crate) fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        for quad in self.quads.iter_mut().take(self.shown){
+            quad.prepare(queue, screen_dim);
+        }
+    }
+
+    pub(crate) fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>) where 'a: 'b{
+        for quad in self.quads.iter().take(self.shown){
+            quad.render(render_pass);
+        }
+    }
+}
+
+/// # TextSearch
+///
+/// Holds the query and the matches found in the attached text. Matches are stored
+/// as byte ranges into the text, and never overlap.
+///
+/// Enter cycles forward through the matches, and Shift+Enter cycles backwards
+/// (see `handle_event`).
+pub struct TextSearch{
+    query: String,
+    case_sensitive: bool,
+    matches: Vec<Range<usize>>,
+    current: Option<usize>,
+    modifiers: Modifiers,
+}
+
+impl TextSearch{
+    /// Create a new, empty search
+    pub fn new() -> Self{
+        Self{
+            query: String::new(),
+            case_sensitive: false,
+            matches: Vec::new(),
+            current: None,
+            modifiers: Modifiers::NONE,
+        }
+    }
+
+    /// Change what we're searching for, and search the target again
+    pub fn set_query<S: Into<String>, T: Searchable + ?Sized>(&mut self, query: S, target: &mut T){
+        self.query = query.into();
+        self.current = None;
+        self.update(target);
+    }
+
+    /// Get the current query
+    pub fn get_query(&self) -> &str{
+        &self.query
+    }
+
+    /// Enable or disable case sensitive matching, and search the target again
+    pub fn set_case_sensitive<T: Searchable + ?Sized>(&mut self, case_sensitive: bool, target: &mut T){
+        self.case_sensitive = case_sensitive;
+        self.update(target);
+    }
+
+    /// Search the target again. Call this when the text of the target has changed.
+    ///
+    /// The current match is kept if it still exists, otherwise it is clamped to the last match.
+    pub fn update<T: Searchable + ?Sized>(&mut self, target: &mut T){
+        self.matches = find_matches(target.get_text(), &self.query, self.case_sensitive);
+
+        self.current = match self.current{
+            _ if self.matches.is_empty() => None,
+            Some(i) => Some(i.min(self.matches.len() - 1)),
+            None => Some(0),
+        };
+
+        target.set_highlights(&self.matches, self.current);
+    }
+
+    /// All the matches found, as byte ranges into the text
+    pub fn get_matches(&self) -> &[Range<usize>]{
+        &self.matches
+    }
+
+    /// The currently selected match, if there are any matches
+    pub fn get_current_match(&self) -> Option<Range<usize>>{
+        self.current.map(|i| self.matches[i].clone())
+    }
+
+    /// The index of the selected match, if there are any matches
+    pub fn get_current_index(&self) -> Option<usize>{
+        self.current
+    }
+
+    /// Select the next match, wrapping around to the first
+    pub fn next<T: Searchable + ?Sized>(&mut self, target: &mut T) -> Option<Range<usize>>{
+        if let Some(i) = self.current{
+            self.current = Some((i + 1) % self.matches.len());
+            target.set_highlights(&self.matches, self.current);
+        }
+        self.get_current_match()
+    }
+
+    /// Select the previous match, wrapping around to the last
+    pub fn previous<T: Searchable + ?Sized>(&mut self, target: &mut T) -> Option<Range<usize>>{
+        if let Some(i) = self.current{
+            self.current = Some((i + self.matches.len() - 1) % self.matches.len());
+            target.set_highlights(&self.matches, self.current);
+        }
+        self.get_current_match()
+    }
+
+    /// Replace the selected match and move on to the next one. Returns true if something was replaced.
+    pub fn replace<T: Searchable + ?Sized>(&mut self, replacement: &str, target: &mut T) -> bool{
+        let range = match self.get_current_match(){
+            Some(range) => range,
+            None => return false,
+        };
+
+        let mut text = target.get_text().to_string();
+        text.replace_range(range, replacement);
+        target.set_text(text);
+
+        // The match we replaced is gone, so the same index now points to the next match
+        self.update(target);
+        true
+    }
+
+    /// Replace every match. Returns the amount of matches replaced.
+    pub fn replace_all<T: Searchable + ?Sized>(&mut self, replacement: &str, target: &mut T) -> usize{
+        let count = self.matches.len();
+        if count == 0{
+            return 0;
+        }
+
+        let old_text = target.get_text();
+        let mut text = String::with_capacity(old_text.len());
+        let mut last = 0;
+        for range in self.matches.iter(){
+            text.push_str(&old_text[last..range.start]);
+            text.push_str(replacement);
+            last = range.end;
+        }
+        text.push_str(&old_text[last..]);
+        target.set_text(text);
+
+        self.current = None;
+        self.update(target);
+        count
+    }
+
+    /// Cycle through the matches with Enter (next) and Shift+Enter (previous).
+    ///
+    /// Returns true if the event was used by the search.
+    pub fn handle_event<T: Searchable + ?Sized>(&mut self, event: &Event<()>, target: &mut T) -> bool{
+        if let Event::WindowEvent{ event, .. } = event{
+            match event{
+                WindowEvent::ModifiersChanged(modifiers) => {
+                    self.modifiers = Modifiers::from(*modifiers);
+                }
+                WindowEvent::KeyboardInput{ input, .. } if input.state == ElementState::Pressed && Key::from_input(input) == Key::Enter => {
+                    if self.modifiers.shift{
+                        self.previous(target);
+                    }else{
+                        self.next(target);
+                    }
+                    return true;
+                }
+                _ => {}
+            }
+        }
+        false
+    }
+}
+
+/// Find every non-overlapping occurence of `query` in `text`, returning the byte ranges.
+///
+/// Ignoring case compares the text and query lowercased a character at a time, so it works past ASCII ("ÉCOLE"
+/// finds "école", and final sigmas match other sigmas). Characters that only fold to several others don't match
+/// them though, so "ß" doesn't find "ss".
+pub fn find_matches(text: &str, query: &str, case_sensitive: bool) -> Vec<Range<usize>>{
+    if query.is_empty(){
+        return Vec::new();
+    }
+    if case_sensitive{
+        return search(text, query, Some);
+    }
+
+    // Matches in the folded text only count if they start and end where characters of the original text do
+    let (folded, starts) = fold_case(text);
+    let original = |index: usize| starts.binary_search_by_key(&index, |&(folded, _)| folded).ok().map(|i| starts[i].1);
+    search(&folded, &fold_case(query).0, |range| Some(original(range.start)?..original(range.end)?))
+}
+
+// Lowercase text, along with where each character starts in the lowercased text and in the original (plus where they
+// both end). A character can lowercase to a different number of bytes, or even to several characters.
+fn fold_case(text: &str) -> (String, Vec<(usize, usize)>){
+    let mut folded = String::with_capacity(text.len());
+    let mut starts = Vec::with_capacity(text.len() + 1);
+    for (i, character) in text.char_indices(){
+        starts.push((folded.len(), i));
+        folded.extend(character.to_lowercase().map(|c| if c == '\u{3c2}' { '\u{3c3}' } else { c }));
+    }
+    starts.push((folded.len(), text.len()));
+    (folded, starts)
+}
+
+// Find every non-overlapping occurence of `query` in `text`, keeping the ones `accept` turns into a match
+fn search(text: &str, query: &str, accept: impl Fn(Range<usize>) -> Option<Range<usize>>) -> Vec<Range<usize>>{
+    let mut matches = Vec::new();
+    let bytes = text.as_bytes();
+    let query_bytes = query.as_bytes();
+    let mut i = 0;
+    while i + query_bytes.len() <= bytes.len(){
+        let end = i + query_bytes.len();
+        // Only accept matches that start and end on a character boundary
+        let found = &bytes[i..end] == query_bytes && text.is_char_boundary(i) && text.is_char_boundary(end);
+        match accept(i..end).filter(|_| found){
+            Some(range) => {
+                matches.push(range);
+                i = end;
+            }
+            None => i += 1,
+        }
+    }
+
+    matches
+}
@@ -0,0 +1,597 @@
+//! This module contains the `FindBar` component, a find-and-replace bar over a `TextArea`.
+
+use std::any::Any;
+
+use wgpu_glyph::{HorizontalAlign, VerticalAlign};
+
+use crate::{input::{EventPhase, EventResponse, InputCategory, InputState, Key, MouseButton, MouseEvent, UiEvent}, rendering::{Quad, Renderer, TextBrush}};
+
+use super::{EventGUIComponent, Label, Rect, TextArea, TextGUIComponent, TextInput, TextSearch};
+
+// The space around the bar's fields and buttons, in pixels
+const PADDING: f32 = 4.0;
+// How wide the replace and replace all buttons and the match count are, in multiples of the text size
+const WIDTHS: [f32; 3] = [4.5, 3.0, 5.0];
+
+// What a key does to the bar
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Action{
+    Open,
+    Next,
+    Previous,
+    Replace,
+    Close,
+}
+
+// Which part of a find bar has the keyboard
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Focus{
+    Nothing,
+    Area,
+    Query,
+    Replacement,
+}
+
+// What a key press does, given what has the keyboard. Command+F opens the bar from anywhere in it, enter in the query
+// goes to the next match (or the previous with shift), enter in the replacement replaces the current match, and
+// escape closes the bar.
+fn key_action(event: &UiEvent, focus: Focus) -> Option<Action>{
+    let key = match event{
+        UiEvent::Key(key) if key.pressed => key,
+        _ => return None,
+    };
+    match (key.key, focus){
+        (_, Focus::Nothing) => None,
+        (Key::Character(character), _) if key.modifiers.command() && character.eq_ignore_ascii_case(&'f') => Some(Action::Open),
+        (Key::Enter, Focus::Query) => Some(if key.modifiers.shift { Action::Previous } else { Action::Next }),
+        (Key::Enter, Focus::Replacement) => Some(Action::Replace),
+        (Key::Escape, Focus::Query | Focus::Replacement) => Some(Action::Close),
+        _ => None,
+    }
+}
+
+// The match count shown at the end of the bar
+fn count_text(current: Option<usize>, count: usize, query: &str) -> String{
+    match current{
+        _ if query.is_empty() => String::new(),
+        Some(current) => format!("{} of {}", current + 1, count),
+        None => String::from("No matches"),
+    }
+}
+
+// Where the parts of a bar `height` pixels tall go along the top of `rect`, from left to right: the query and
+// replacement fields (sharing the space the rest leave), the replace and replace all buttons, and the match count
+fn bar_rects(rect: Rect, height: f32, text_size: f32) -> [Rect; 5]{
+    let y = rect.pos[1] - rect.size[1] / 2.0 + height / 2.0;
+    let fixed: f32 = WIDTHS.iter().map(|width| width * text_size).sum::<f32>() + PADDING * 6.0;
+    let field = ((rect.size[0] - fixed) / 2.0).max(0.0);
+    let widths = [field, field, WIDTHS[0] * text_size, WIDTHS[1] * text_size, WIDTHS[2] * text_size];
+
+    let mut x = rect.pos[0] - rect.size[0] / 2.0 + PADDING;
+    let mut rects = [Rect::new([0.0, 0.0], [0.0, 0.0]); 5];
+    for (rect, width) in rects.iter_mut().zip(widths.iter()){
+        *rect = Rect::new([x + width / 2.0, y], [*width, height - PADDING * 2.0]);
+        x += width + PADDING;
+    }
+    rects
+}
+
+// The part of `rect` the text area gets, which is everything under the bar while it's open
+fn area_rect(rect: Rect, height: f32, open: bool) -> Rect{
+    if !open{
+        return rect;
+    }
+    let height = height.min(rect.size[1]);
+    Rect::new([rect.pos[0], rect.pos[1] + height / 2.0], [rect.size[0], rect.size[1] - height])
+}
+
+// A button on the bar
+struct BarButton{
+    quad: Quad,
+    label: Label,
+}
+
+/// # FindBar
+///
+/// A `TextArea` with a find-and-replace bar that opens along its top. Pressing command+F (ctrl+F outside macOS) in
+/// the text area opens the bar and focuses the query field, and every match of the query in the text is highlighted
+/// as it's typed, the current one in its own colour. Enter goes to the next match and shift+enter to the previous
+/// one, scrolling the text area to show it. Enter in the replacement field (or the "Replace" button) replaces the
+/// current match and moves on, and "All" replaces every match. Escape closes the bar, clearing the highlights.
+///
+/// The search ignores case by default, see `set_case_sensitive`. The search runs again as the text area is typed
+/// into, but if the text is changed through `borrow_area_mut` it catches up on the next frame.
+pub struct FindBar{
+    rect: Rect,
+    text_size: f32,
+    bar_height: f32,
+    area: TextArea,
+    search: TextSearch,
+    open: bool,
+    stale: bool, // the text may have changed without the search knowing
+    colors: [[f32; 4]; 2], // button, hovered button
+
+    background: Quad,
+    query: TextInput,
+    replacement: TextInput,
+    buttons: [BarButton; 2], // replace, replace all
+    count: Label,
+
+    input: InputState,
+    hovered: Option<usize>,
+    pressed: Option<usize>, // the button the mouse went down on
+    enabled: bool,
+}
+
+impl FindBar{
+    /// Create a closed find bar over `area`, filling `bounds` with the two of them
+    pub fn new(bounds: Rect, text_size: f32, area: TextArea, renderer: &Renderer) -> Self{
+        // Tall enough for fields with 6 pixels above and below their text
+        let bar_height = text_size + 12.0 + PADDING * 2.0;
+        let field = |placeholder: &str| {
+            let mut field = TextInput::new(Rect::new([0.0, 0.0], [0.0, 0.0]), text_size, renderer);
+            field.set_placeholder(placeholder);
+            field
+        };
+        let label = |text: &str| {
+            let mut label = Label::new(text, text_size, [0.0, 0.0]);
+            label.align_horizontal(HorizontalAlign::Center);
+            label.align_vertical(VerticalAlign::Center);
+            label
+        };
+        let colors = [[0.85, 0.85, 0.85, 1.0], [0.78, 0.78, 0.78, 1.0]];
+        let button = |text: &str| BarButton{
+            quad: Quad::new([0.0, 0.0], [0.0, 0.0], colors[0], &renderer.device),
+            label: label(text),
+        };
+
+        let mut bar = Self{
+            rect: bounds,
+            text_size,
+            bar_height,
+            area,
+            search: TextSearch::new(),
+            open: false,
+            stale: false,
+            colors,
+
+            background: Quad::new([0.0, 0.0], [0.0, 0.0], [0.93, 0.93, 0.93, 1.0], &renderer.device),
+            query: field("Find"),
+            replacement: field("Replace with"),
+            buttons: [button("Replace"), button("All")],
+            count: label(""),
+
+            input: InputState::new(),
+            hovered: None,
+            pressed: None,
+            enabled: true,
+        };
+        bar.place();
+        bar
+    }
+
+    /// Open the bar and focus the query field, searching for whatever's in it
+    pub fn open(&mut self){
+        self.open = true;
+        self.area.set_focused(false);
+        self.replacement.set_focused(false);
+        self.query.set_focused(true);
+        self.search.set_query(self.query.get_text(), &mut self.area);
+        self.place();
+    }
+
+    /// Close the bar, clearing the highlights and giving the keyboard back to the text area. The query is kept for
+    /// next time.
+    pub fn close(&mut self){
+        self.open = false;
+        self.search.set_query("", &mut self.area);
+        self.query.set_focused(false);
+        self.replacement.set_focused(false);
+        self.area.set_focused(true);
+        self.pressed = None;
+        self.place();
+    }
+
+    pub fn is_open(&self) -> bool{
+        self.open
+    }
+
+    /// Search for something, as if it had been typed into the query field
+    pub fn set_query(&mut self, query: &str){
+        self.query.set_text(query);
+        if self.open{
+            self.search.set_query(query, &mut self.area);
+            self.update_count();
+        }
+    }
+
+    /// Match case when searching, or ignore it (the default)
+    pub fn set_case_sensitive(&mut self, case_sensitive: bool){
+        self.search.set_case_sensitive(case_sensitive, &mut self.area);
+        self.update_count();
+    }
+
+    /// Go to the next match, wrapping around to the first
+    pub fn find_next(&mut self){
+        self.search.next(&mut self.area);
+        self.update_count();
+    }
+
+    /// Go to the previous match, wrapping around to the last
+    pub fn find_previous(&mut self){
+        self.search.previous(&mut self.area);
+        self.update_count();
+    }
+
+    /// Replace the current match with what's in the replacement field, and move on to the next one
+    pub fn replace(&mut self) -> bool{
+        let replaced = self.search.replace(self.replacement.get_text(), &mut self.area);
+        self.update_count();
+        replaced
+    }
+
+    /// Replace every match with what's in the replacement field, returning how many were replaced
+    pub fn replace_all(&mut self) -> usize{
+        let count = self.search.replace_all(self.replacement.get_text(), &mut self.area);
+        self.update_count();
+        count
+    }
+
+    /// The search, for its matches
+    pub fn get_search(&self) -> &TextSearch{
+        &self.search
+    }
+
+    pub fn get_area(&self) -> &TextArea{
+        &self.area
+    }
+
+    /// Borrow the text area, for example to change its text
+    pub fn borrow_area_mut(&mut self) -> &mut TextArea{
+        self.stale = true;
+        &mut self.area
+    }
+
+    /// Set the colours of the bar, and its buttons normally and while hovered
+    pub fn set_colors(&mut self, bar: [f32; 4], button: [f32; 4], hovered: [f32; 4]){
+        self.background.set_color(bar);
+        self.colors = [button, hovered];
+        self.update_colors();
+    }
+
+    pub fn get_rect(&self) -> Rect{
+        self.rect
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+        self.area.enable();
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+        self.pressed = None;
+        self.area.disable();
+        self.query.set_focused(false);
+        self.replacement.set_focused(false);
+    }
+
+    fn focus(&self) -> Focus{
+        if self.query.is_focused(){
+            Focus::Query
+        }else if self.replacement.is_focused(){
+            Focus::Replacement
+        }else if self.area.is_focused(){
+            Focus::Area
+        }else{
+            Focus::Nothing
+        }
+    }
+
+    fn act(&mut self, action: Action){
+        match action{
+            Action::Open => self.open(),
+            Action::Next => self.find_next(),
+            Action::Previous => self.find_previous(),
+            Action::Replace => { self.replace(); }
+            Action::Close => self.close(),
+        }
+    }
+
+    // Move everything into place for the bar being open or closed
+    fn place(&mut self){
+        self.area.set_bounds(area_rect(self.rect, self.bar_height, self.open));
+        let rects = bar_rects(self.rect, self.bar_height, self.text_size);
+        let top = self.rect.pos[1] - self.rect.size[1] / 2.0;
+        self.background.set_pos([self.rect.pos[0], top + self.bar_height / 2.0]);
+        self.background.set_size([self.rect.size[0], self.bar_height]);
+        self.query.set_bounds(rects[0]);
+        self.replacement.set_bounds(rects[1]);
+        for (button, rect) in self.buttons.iter_mut().zip(rects[2..4].iter()){
+            button.quad.set_pos(rect.pos);
+            button.quad.set_size(rect.size);
+        }
+        self.update_count();
+    }
+
+    fn update_count(&mut self){
+        let count = count_text(self.search.get_current_index(), self.search.get_matches().len(), self.search.get_query());
+        if count != self.count.get_text(){
+            self.count.set_text(count);
+        }
+    }
+
+    fn update_colors(&mut self){
+        for (i, button) in self.buttons.iter_mut().enumerate(){
+            button.quad.set_color(self.colors[(self.hovered == Some(i)) as usize]);
+        }
+    }
+
+    fn button_at(&self, pos: [f32; 2]) -> Option<usize>{
+        if !self.open{
+            return None;
+        }
+        self.buttons.iter().position(|button| Rect::new(button.quad.get_pos(), button.quad.get_size()).contains(pos))
+    }
+
+    // Handle the bar's own keys and buttons, returning true if the event was one of them
+    fn own_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window) -> bool{
+        let event = match self.input.handle_event(event, window){
+            Some(event) => event,
+            None => return false,
+        };
+        if let Some(action) = key_action(&event, self.focus()){
+            if self.open || action == Action::Open{
+                self.act(action);
+                return true;
+            }
+        }
+
+        match event{
+            UiEvent::CursorMoved{ pos, .. } => {
+                let hovered = self.button_at(pos);
+                if hovered != self.hovered{
+                    self.hovered = hovered;
+                    self.update_colors();
+                }
+                false
+            }
+            UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed, pos, .. }) => {
+                let button = self.button_at(pos);
+                if pressed{
+                    self.pressed = button;
+                }else{
+                    match self.pressed.take().filter(|pressed| Some(*pressed) == button){
+                        Some(0) => { self.replace(); }
+                        Some(_) => { self.replace_all(); }
+                        None => {}
+                    }
+                }
+                button.is_some()
+            }
+            _ => false,
+        }
+    }
+
+    // Search again if the query was typed into, or the text area was
+    fn after_event(&mut self, event: &winit::event::Event<()>, area_focused: bool){
+        if !self.open{
+            return;
+        }
+        if self.query.get_text() != self.search.get_query(){
+            self.search.set_query(self.query.get_text(), &mut self.area);
+            self.update_count();
+        }else if area_focused && InputCategory::of(event) == Some(InputCategory::Keyboard){
+            self.search.update(&mut self.area);
+            self.update_count();
+        }
+    }
+}
+
+impl EventGUIComponent for FindBar{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if !self.enabled{
+            return;
+        }
+        self.area.render(render_pass);
+        if self.open{
+            self.background.render(render_pass);
+            self.query.render(render_pass);
+            self.replacement.render(render_pass);
+            for button in self.buttons.iter(){
+                button.quad.render(render_pass);
+            }
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if !self.enabled{
+            return;
+        }
+        self.area.render_text(brush);
+        if self.open{
+            self.query.render_text(brush);
+            self.replacement.render_text(brush);
+            for button in self.buttons.iter(){
+                TextGUIComponent::render_text(&button.label, brush);
+            }
+            TextGUIComponent::render_text(&self.count, brush);
+        }
+    }
+
+    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        if !self.enabled || self.own_event(event, window){
+            return;
+        }
+        let area_focused = self.area.is_focused();
+        self.area.handle_event_callback(event, window);
+        if self.open{
+            self.query.handle_event_callback(event, window);
+            self.replacement.handle_event_callback(event, window);
+        }
+        self.after_event(event, area_focused);
+    }
+
+    /// The bar's keys and button clicks are ours, and the text area and fields get everything else
+    fn handle_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window, phase: EventPhase) -> EventResponse{
+        if !self.enabled || phase != EventPhase::Bubble{
+            return EventResponse::Continue;
+        }
+        if self.own_event(event, window){
+            return EventResponse::Consumed;
+        }
+        let area_focused = self.area.is_focused();
+        let mut response = self.area.handle_event(event, window, phase);
+        if self.open{
+            for field in [&mut self.query, &mut self.replacement]{
+                if field.handle_event(event, window, phase) == EventResponse::Consumed{
+                    response = EventResponse::Consumed;
+                }
+            }
+        }
+        self.after_event(event, area_focused);
+        response
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.rect.pos
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(self.rect)
+    }
+
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{
+        Some(self.rect.size)
+    }
+
+    fn arrange(&mut self, rect: Rect){
+        if rect != self.rect{
+            self.rect = rect;
+            self.place();
+        }
+    }
+
+    fn update(&mut self, dt: f32){
+        self.area.update(dt);
+        if self.open{
+            self.query.update(dt);
+            self.replacement.update(dt);
+        }
+    }
+
+    fn is_animating(&self) -> bool{
+        self.area.is_animating() || (self.open && (self.query.is_animating() || self.replacement.is_animating()))
+    }
+
+    /// Catch the search up with any changes made through `borrow_area_mut`, before the highlights are made
+    fn rebuild(&mut self, device: &wgpu::Device) -> bool{
+        if std::mem::take(&mut self.stale) && self.open{
+            self.search.update(&mut self.area);
+            self.update_count();
+        }
+        self.query.rebuild(device);
+        self.replacement.rebuild(device);
+        self.area.rebuild(device)
+    }
+
+    fn layout_text(&mut self, brush: &mut TextBrush){
+        self.area.layout_text(brush);
+        if self.open{
+            self.query.layout_text(brush);
+            self.replacement.layout_text(brush);
+        }
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.area.prepare(queue, screen_dim);
+        if !self.open{
+            return;
+        }
+        self.background.prepare(queue, screen_dim);
+        self.query.prepare(queue, screen_dim);
+        self.replacement.prepare(queue, screen_dim);
+        for button in self.buttons.iter_mut(){
+            button.quad.prepare(queue, screen_dim);
+            button.label.set_pos(button.quad.get_pos(), screen_dim);
+        }
+        let count = bar_rects(self.rect, self.bar_height, self.text_size)[4];
+        self.count.set_pos(count.pos, screen_dim);
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use crate::input::{KeyEvent, Modifiers};
+
+    fn key(key: Key, modifiers: Modifiers) -> UiEvent{
+        UiEvent::Key(KeyEvent{ key, physical: key, scancode: 0, pressed: true, repeat: false, modifiers })
+    }
+
+    // The shortcut modifier on this platform
+    fn command() -> Modifiers{
+        Modifiers{ ctrl: !cfg!(target_os = "macos"), logo: cfg!(target_os = "macos"), ..Modifiers::NONE }
+    }
+
+    /// Test that command+F opens the bar, enter and shift+enter cycle and replace, and escape closes it
+    #[test]
+    fn key_action_test(){
+        let shift = Modifiers{ shift: true, ..Modifiers::NONE };
+        assert_eq!(key_action(&key(Key::Character('f'), command()), Focus::Area), Some(Action::Open));
+        assert_eq!(key_action(&key(Key::Character('F'), command()), Focus::Query), Some(Action::Open));
+        assert_eq!(key_action(&key(Key::Character('f'), command()), Focus::Nothing), None);
+        assert_eq!(key_action(&key(Key::Character('f'), Modifiers::NONE), Focus::Area), None);
+
+        assert_eq!(key_action(&key(Key::Enter, Modifiers::NONE), Focus::Query), Some(Action::Next));
+        assert_eq!(key_action(&key(Key::Enter, shift), Focus::Query), Some(Action::Previous));
+        assert_eq!(key_action(&key(Key::Enter, Modifiers::NONE), Focus::Replacement), Some(Action::Replace));
+        // Enter in the text area is a new line, not the next match
+        assert_eq!(key_action(&key(Key::Enter, Modifiers::NONE), Focus::Area), None);
+
+        assert_eq!(key_action(&key(Key::Escape, Modifiers::NONE), Focus::Query), Some(Action::Close));
+        assert_eq!(key_action(&key(Key::Escape, Modifiers::NONE), Focus::Area), None);
+    }
+
+    /// Test that the count says which match is current, or that there aren't any
+    #[test]
+    fn count_text_test(){
+        assert_eq!(count_text(Some(1), 5, "fish"), "2 of 5");
+        assert_eq!(count_text(None, 0, "fish"), "No matches");
+        assert_eq!(count_text(None, 0, ""), "");
+    }
+
+    /// Test that the fields share the space the buttons leave, and the text area goes under the bar while it's open
+    #[test]
+    fn layout_test(){
+        let rect = Rect::new([0.0, 0.0], [500.0, 300.0]);
+        let rects = bar_rects(rect, 40.0, 10.0);
+        let fixed = (WIDTHS[0] + WIDTHS[1] + WIDTHS[2]) * 10.0 + PADDING * 6.0;
+        assert_eq!(rects[0].size, [(500.0 - fixed) / 2.0, 32.0]);
+        assert_eq!(rects[1].size, rects[0].size);
+        assert_eq!(rects[0].pos[1], -130.0);
+        assert_eq!(rects[0].pos[0] - rects[0].size[0] / 2.0, -250.0 + PADDING);
+        assert_eq!(rects[4].pos[0] + rects[4].size[0] / 2.0, 250.0 - PADDING);
+
+        assert_eq!(area_rect(rect, 40.0, false), rect);
+        assert_eq!(area_rect(rect, 40.0, true), Rect::new([0.0, 20.0], [500.0, 260.0]));
+    }
+}
@@ -0,0 +1,280 @@
+//! This module contains the `GroupBox` component, a titled frame drawn around a set of components, which can
+//! enable and disable them together.
+
+use std::any::Any;
+
+use wgpu_glyph::VerticalAlign;
+
+use crate::{layout::{ComponentHandle, LayoutCommands}, rendering::{Quad, Renderer, TextBrush, measure_text}};
+
+use super::{GUIComponent, Label, Rect, TextGUIComponent};
+
+// How far in from the left the title starts, and the space either side of it where the top edge is broken
+const TITLE_INSET: f32 = 12.0;
+const TITLE_GAP: f32 = 4.0;
+
+/// The pieces of a group box's border around `rect`, `thickness` pixels thick: the left, right and bottom edges,
+/// then the top edge either side of the gap for the title. `gap` is where the gap starts and ends, measured from
+/// the left of the rect. Without a gap (`gap[1] <= gap[0]`) the second piece of the top edge is empty.
+pub(crate) fn group_box_border(rect: Rect, thickness: f32, gap: [f32; 2]) -> [Rect; 5]{
+    let [left, right] = [rect.pos[0] - rect.size[0] / 2.0, rect.pos[0] + rect.size[0] / 2.0];
+    let [top, bottom] = [rect.pos[1] - rect.size[1] / 2.0, rect.pos[1] + rect.size[1] / 2.0];
+    let top_y = top + thickness / 2.0;
+    // The top edge, from one x to another
+    let top_edge = |from: f32, to: f32| {
+        let (from, to) = (from.clamp(left, right), to.clamp(left, right));
+        Rect::new([(from + to) / 2.0, top_y], [(to - from).max(0.0), thickness])
+    };
+    let (first, second) = if gap[1] > gap[0]{
+        (top_edge(left, left + gap[0]), top_edge(left + gap[1], right))
+    }else{
+        (top_edge(left, right), top_edge(right, right))
+    };
+    [
+        Rect::new([left + thickness / 2.0, rect.pos[1]], [thickness, rect.size[1]]),
+        Rect::new([right - thickness / 2.0, rect.pos[1]], [thickness, rect.size[1]]),
+        Rect::new([rect.pos[0], bottom - thickness / 2.0], [rect.size[0], thickness]),
+        first,
+        second,
+    ]
+}
+
+/// # GroupBox
+///
+/// A frame drawn around some components to show they belong together, like the settings for one part of a game,
+/// with a title breaking the top edge. The components stay in the `Layout` as they are - the group box only knows
+/// their handles - so it should be added before them to be drawn underneath.
+///
+/// The whole group can be disabled at once with `set_group_enabled`, which disables every component in it through
+/// `Layout::set_disabled` (so they're still drawn, but get no input) and greys out the frame. Like `TabView`, the
+/// change is queued through the layout's `LayoutCommands`, and the handles aren't updated when components are
+/// removed from the layout.
+pub struct GroupBox{
+    rect: Rect,
+    thickness: f32,
+    children: Vec<ComponentHandle>,
+    commands: LayoutCommands,
+    group_enabled: bool,
+    colors: [[f32; 4]; 2], // enabled, disabled
+
+    edges: Vec<Quad>,
+    title: Label,
+    title_size: f32,
+    title_width: Option<f32>, // None until the title has been measured
+    enabled: bool,
+}
+
+impl GroupBox{
+    /// Create a group box framing `rect`, without any components in it. `commands` should come from the layout
+    /// holding the components (see `Layout::get_commands`).
+    pub fn new(rect: Rect, title: &str, text_size: f32, commands: LayoutCommands, renderer: &Renderer) -> Self{
+        let colors = [[0.6, 0.6, 0.6, 1.0], [0.82, 0.82, 0.82, 1.0]];
+        let mut label = Label::new(title, text_size, [0.0, 0.0]);
+        label.align_vertical(VerticalAlign::Center);
+        let mut group = Self{
+            rect,
+            thickness: 1.0,
+            children: Vec::new(),
+            commands,
+            group_enabled: true,
+            colors,
+
+            edges: (0..5).map(|_| Quad::new([0.0, 0.0], [0.0, 0.0], colors[0], &renderer.device)).collect(),
+            title: label,
+            title_size: text_size,
+            title_width: None,
+            enabled: true,
+        };
+        group.update_edges();
+        group
+    }
+
+    /// Set the components in the group, which get enabled or disabled to match it
+    pub fn set_children(&mut self, children: Vec<ComponentHandle>){
+        self.children = children;
+        self.queue_disabled();
+    }
+
+    /// Add a component to the group
+    pub fn add_child(&mut self, handle: ComponentHandle){
+        if !self.children.contains(&handle){
+            self.children.push(handle);
+            self.queue_disabled();
+        }
+    }
+
+    pub fn get_children(&self) -> &[ComponentHandle]{
+        &self.children
+    }
+
+    /// Enable or disable every component in the group
+    pub fn set_group_enabled(&mut self, enabled: bool){
+        self.group_enabled = enabled;
+        for edge in self.edges.iter_mut(){
+            edge.set_color(self.colors[(!enabled) as usize]);
+        }
+        self.queue_disabled();
+    }
+
+    pub fn is_group_enabled(&self) -> bool{
+        self.group_enabled
+    }
+
+    pub fn set_title(&mut self, title: &str){
+        if title != self.title.get_text(){
+            self.title.set_text(title);
+            self.title_width = None;
+        }
+    }
+
+    pub fn get_title(&self) -> &str{
+        self.title.get_text()
+    }
+
+    /// Set the colours of the frame while the group is enabled and while it's disabled
+    pub fn set_colors(&mut self, enabled: [f32; 4], disabled: [f32; 4]){
+        self.colors = [enabled, disabled];
+        self.set_group_enabled(self.group_enabled);
+    }
+
+    /// Set how thick the frame is, in pixels. It's 1 pixel by default.
+    pub fn set_thickness(&mut self, thickness: f32){
+        self.thickness = thickness.max(0.0);
+        self.update_edges();
+    }
+
+    pub fn set_rect(&mut self, rect: Rect){
+        self.rect = rect;
+        self.update_edges();
+    }
+
+    pub fn get_rect(&self) -> Rect{
+        self.rect
+    }
+
+    /// Borrow the title, for example to change its font
+    pub fn borrow_title_mut(&mut self) -> &mut Label{
+        self.title_width = None;
+        &mut self.title
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+    }
+
+    fn update_edges(&mut self){
+        // The gap only opens once the title's been measured, and there's no gap without a title
+        let gap = match self.title_width{
+            Some(width) if width > 0.0 => [TITLE_INSET - TITLE_GAP, TITLE_INSET + width + TITLE_GAP],
+            _ => [0.0, 0.0],
+        };
+        for (quad, rect) in self.edges.iter_mut().zip(group_box_border(self.rect, self.thickness, gap).iter()){
+            quad.set_pos(rect.pos);
+            quad.set_size(rect.size);
+        }
+    }
+
+    fn queue_disabled(&self){
+        let disabled = !self.group_enabled;
+        let children = self.children.clone();
+        self.commands.run(move |layout| {
+            for handle in children.iter(){
+                layout.set_disabled(*handle, disabled);
+            }
+        });
+    }
+}
+
+impl GUIComponent for GroupBox{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.enabled{
+            for edge in self.edges.iter(){
+                edge.render(render_pass);
+            }
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if self.enabled && self.title_width.is_some(){
+            TextGUIComponent::render_text(&self.title, brush);
+        }
+    }
+
+    fn layout_text(&mut self, brush: &mut TextBrush){
+        if self.title_width.is_none(){
+            self.title_width = Some(measure_text(brush, self.title.get_text(), self.title_size, wgpu_glyph::FontId::default(), None)[0]);
+            self.update_edges();
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.rect.pos
+    }
+
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{
+        Some(self.rect.size)
+    }
+
+    fn arrange(&mut self, rect: Rect){
+        if rect != self.rect{
+            self.set_rect(rect);
+        }
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        for edge in self.edges.iter_mut(){
+            edge.prepare(queue, screen_dim);
+        }
+        let left = self.rect.pos[0] - self.rect.size[0] / 2.0;
+        let top = self.rect.pos[1] - self.rect.size[1] / 2.0;
+        self.title.set_pos([left + TITLE_INSET, top + self.thickness / 2.0], screen_dim);
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Test that a group box's border goes round its rect, with the top edge broken for the title
+    #[test]
+    fn group_box_border_test(){
+        let rect = Rect::new([0.0, 0.0], [200.0, 100.0]);
+        let [left, right, bottom, first, second] = group_box_border(rect, 2.0, [10.0, 60.0]);
+        assert_eq!(left, Rect::new([-99.0, 0.0], [2.0, 100.0]));
+        assert_eq!(right, Rect::new([99.0, 0.0], [2.0, 100.0]));
+        assert_eq!(bottom, Rect::new([0.0, 49.0], [200.0, 2.0]));
+        assert_eq!(first, Rect::new([-95.0, -49.0], [10.0, 2.0]));
+        assert_eq!(second, Rect::new([30.0, -49.0], [140.0, 2.0]));
+
+        // Without a gap the top edge goes all the way across
+        let [_, _, _, first, second] = group_box_border(rect, 2.0, [0.0, 0.0]);
+        assert_eq!(first, Rect::new([0.0, -49.0], [200.0, 2.0]));
+        assert_eq!(second.size, [0.0, 2.0]);
+
+        // A title wider than the box doesn't turn the top edge inside out
+        let [_, _, _, _, second] = group_box_border(rect, 2.0, [10.0, 500.0]);
+        assert_eq!(second.size, [0.0, 2.0]);
+    }
+}
@@ -0,0 +1,256 @@
+//! This module adds support for icon fonts, like Font Awesome or Material Icons.
+//! Icons are just glyphs in a font, so they stay crisp at any size and can be coloured
+//! like text, without needing any textures.
+//!
+//! Load the font with `Renderer::add_font`, then either create icons straight from their
+//! codepoint, look them up by name in an `IconSet`, or generate an enum of every icon
+//! from the font's metadata file in your build script with `generate_icon_enum`.
+
+use std::{any::Any, collections::HashMap};
+
+use wgpu_glyph::{FontId, HorizontalAlign, VerticalAlign};
+
+use crate::rendering::TextBrush;
+
+use super::TextGUIComponent;
+
+/// Implemented by icon enums generated with `generate_icon_enum`
+pub trait NamedIcon{
+    fn codepoint(&self) -> char;
+    fn name(&self) -> &'static str;
+}
+
+/// # Icon
+///
+/// A single glyph from an icon font. It gets drawn centred on its position, so it lines up
+/// with buttons and other components.
+pub struct Icon{
+    codepoint: char,
+    font: FontId,
+    size: f32,
+    pos: [f32; 2],
+    color: [f32; 4],
+    enabled: bool,
+}
+
+impl Icon{
+    /// Create an icon from a codepoint in an icon font. Returns `None` if the codepoint isn't a valid character.
+    pub fn from_codepoint(codepoint: u32, font: FontId, size: f32, pos: [f32; 2]) -> Option<Self>{
+        let codepoint = std::char::from_u32(codepoint)?;
+        Some(Self{
+            codepoint,
+            font,
+            size,
+            pos,
+            color: [0.0, 0.0, 0.0, 1.0],
+            enabled: true,
+        })
+    }
+
+    /// Create an icon from a generated icon enum
+    pub fn from_named<I: NamedIcon>(icon: I, font: FontId, size: f32, pos: [f32; 2]) -> Self{
+        Self{
+            codepoint: icon.codepoint(),
+            font,
+            size,
+            pos,
+            color: [0.0, 0.0, 0.0, 1.0],
+            enabled: true,
+        }
+    }
+
+    pub fn get_codepoint(&self) -> char{
+        self.codepoint
+    }
+
+    pub fn set_codepoint(&mut self, codepoint: char){
+        self.codepoint = codepoint;
+    }
+
+    /// How big the icon is, in pixels. Icons are as wide as they are tall.
+    pub fn get_size(&self) -> f32{
+        self.size
+    }
+
+    pub fn set_size(&mut self, size: f32){
+        self.size = size;
+    }
+
+    pub fn get_color(&self) -> [f32; 4]{
+        self.color
+    }
+
+    pub fn set_color(&mut self, color: [f32; 4]){
+        self.color = color;
+    }
+
+    pub fn get_font(&self) -> FontId{
+        self.font
+    }
+
+    /// Switch to another icon font, for example a filled version of the same icons
+    pub fn set_font(&mut self, font: FontId){
+        self.font = font;
+    }
+
+    pub fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+    }
+
+    /// Where the icon is drawn, in window pixels from the top left
+    pub fn get_pos(&self) -> [f32; 2]{
+        self.pos
+    }
+
+    /// Set the position, from the centre of the screen (like buttons)
+    pub fn set_pos(&mut self, pos: [f32; 2], screen_dim: (u32, u32)){
+        self.pos = [(pos[0] + (screen_dim.0/2) as f32), (pos[1] + (screen_dim.1/2) as f32)];
+    }
+}
+
+impl TextGUIComponent for Icon{
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if self.enabled{
+            let mut buffer = [0u8; 4];
+            let text = self.codepoint.encode_utf8(&mut buffer);
+            brush.queue(wgpu_glyph::Section {
+                screen_position: (self.pos[0], self.pos[1]),
+                text: vec![wgpu_glyph::Text::new(text).with_color(self.color).with_scale(wgpu_glyph::ab_glyph::PxScale::from(self.size)).with_font_id(self.font)],
+                layout: wgpu_glyph::Layout::default().v_align(VerticalAlign::Center).h_align(HorizontalAlign::Center),
+                ..wgpu_glyph::Section::default()
+            })
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// # IconSet
+///
+/// An icon font, along with the names of its icons. Use this to look icons up by name at runtime.
+pub struct IconSet{
+    font: FontId,
+    names: HashMap<String, char>,
+}
+
+impl IconSet{
+    /// Create an icon set from a font and its metadata. See `parse_icon_metadata` for the format.
+    pub fn new(font: FontId, metadata: &str) -> Self{
+        Self{
+            font,
+            names: parse_icon_metadata(metadata).into_iter().collect(),
+        }
+    }
+
+    pub fn get_font(&self) -> FontId{
+        self.font
+    }
+
+    /// Get the codepoint of an icon by name
+    pub fn get(&self, name: &str) -> Option<char>{
+        self.names.get(name).copied()
+    }
+
+    /// Create an icon by name
+    pub fn icon(&self, name: &str, size: f32, pos: [f32; 2]) -> Option<Icon>{
+        Icon::from_codepoint(self.get(name)? as u32, self.font, size, pos)
+    }
+}
+
+/// Parse icon metadata into names and codepoints.
+///
+/// Each line holds a name and a hex codepoint, separated by whitespace (the `codepoints` file
+/// Material Icons ships with), for example `home e88a`. Empty lines, lines starting with `#`
+/// and lines that don't parse are skipped.
+pub fn parse_icon_metadata(metadata: &str) -> Vec<(String, char)>{
+    metadata.lines().filter_map(|line| {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#'){
+            return None;
+        }
+        let mut parts = line.split_whitespace();
+        let name = parts.next()?;
+        let codepoint = parts.next()?.trim_start_matches("0x").trim_start_matches("U+");
+        let codepoint = std::char::from_u32(u32::from_str_radix(codepoint, 16).ok()?)?;
+        Some((name.to_string(), codepoint))
+    }).collect()
+}
+
+/// Generate the source code of an enum holding every icon in the metadata (see `parse_icon_metadata`).
+///
+/// This is meant to be called from a build script, writing the result into `OUT_DIR` and pulling it in
+/// with `include!`. The icon names get converted into CamelCase variants (`arrow_back` becomes `ArrowBack`),
+/// and the enum implements `NamedIcon`.
+///
+/// ```ignore
+/// // build.rs
+/// let metadata = std::fs::read_to_string("fonts/MaterialIcons-Regular.codepoints").unwrap();
+/// let source = rusty_gui::components::icon::generate_icon_enum("MaterialIcon", &metadata);
+/// std::fs::write(std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("material_icon.rs"), source).unwrap();
+///
+/// // main.rs
+/// include!(concat!(env!("OUT_DIR"), "/material_icon.rs"));
+/// ```
+pub fn generate_icon_enum(enum_name: &str, metadata: &str) -> String{
+    let mut variants = Vec::<(String, String, char)>::new();
+    for (name, codepoint) in parse_icon_metadata(metadata){
+        let variant = to_variant_name(&name);
+        if !variants.iter().any(|(existing, _, _)| existing == &variant){
+            variants.push((variant, name, codepoint));
+        }
+    }
+
+    let mut source = String::new();
+    source.push_str("#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]\n");
+    source.push_str(&format!("pub enum {}{{\n", enum_name));
+    for (variant, _, _) in variants.iter(){
+        source.push_str(&format!("    {},\n", variant));
+    }
+    source.push_str("}\n\n");
+
+    source.push_str(&format!("impl rusty_gui::components::NamedIcon for {}{{\n", enum_name));
+    source.push_str("    fn codepoint(&self) -> char{\n        match self{\n");
+    for (variant, _, codepoint) in variants.iter(){
+        source.push_str(&format!("            {}::{} => '\\u{{{:x}}}',\n", enum_name, variant, *codepoint as u32));
+    }
+    source.push_str("        }\n    }\n\n");
+    source.push_str("    fn name(&self) -> &'static str{\n        match self{\n");
+    for (variant, name, _) in variants.iter(){
+        source.push_str(&format!("            {}::{} => {:?},\n", enum_name, variant, name));
+    }
+    source.push_str("        }\n    }\n}\n");
+
+    source
+}
+
+// Turn an icon name like `arrow_back` or `3d-rotation` into a valid CamelCase variant name
+fn to_variant_name(name: &str) -> String{
+    let mut variant = String::new();
+    for word in name.split(|c: char| !c.is_ascii_alphanumeric()).filter(|word| !word.is_empty()){
+        let mut chars = word.chars();
+        if let Some(first) = chars.next(){
+            variant.push(first.to_ascii_uppercase());
+            variant.extend(chars);
+        }
+    }
+
+    if variant.is_empty() || variant.starts_with(|c: char| c.is_ascii_digit()){
+        variant.insert_str(0, "Icon");
+    }
+    variant
+}
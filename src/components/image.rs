@@ -0,0 +1,245 @@
+//! This module contains the `Image` component, which draws a texture. Its pixels can be replaced
+//! every frame, which is useful for webcam feeds, software rendered content and live previews.
+//!
+//! New pixels go into a small ring of staging buffers, and only the newest frame gets uploaded when
+//! the frame is prepared. Frames can also be pushed from another thread through a `PixelFeed`.
+
+use std::{any::Any, sync::{Arc, Mutex}};
+
+use crate::rendering::{Quad, Renderer, Texture};
+
+use super::{GUIComponent, Rect};
+
+// How many frames can be waiting for upload before the oldest gets overwritten
+const STAGING_FRAMES: usize = 3;
+
+// The staging buffers. Their allocations get reused, so pushing a frame doesn't allocate once the ring is warm.
+struct FrameRing{
+    slots: Vec<Vec<u8>>,
+    next: usize,
+    latest: Option<usize>,
+    size: (u32, u32),
+    pushed: bool, // since the last update, so the image knows it's live
+}
+
+impl FrameRing{
+    fn new(size: (u32, u32)) -> Self{
+        Self{
+            slots: vec![Vec::new(); STAGING_FRAMES],
+            next: 0,
+            latest: None,
+            size,
+            pushed: false,
+        }
+    }
+
+    fn push(&mut self, pixels: &[u8], size: (u32, u32)) -> Result<(), &'static str>{
+        if size != self.size{
+            return Err("Error, pixel size doesn't match the image, resize it first!");
+        }
+        if pixels.len() != (4 * size.0 * size.1) as usize{
+            return Err("Error, expected 4 bytes (RGBA) per pixel!");
+        }
+
+        let slot = &mut self.slots[self.next];
+        slot.clear();
+        slot.extend_from_slice(pixels);
+        self.latest = Some(self.next);
+        self.next = (self.next + 1) % STAGING_FRAMES;
+        self.pushed = true;
+        Ok(())
+    }
+}
+
+/// # PixelFeed
+///
+/// A handle for sending new pixels to an `Image` from any thread (for example a camera capture thread).
+/// Get one with `Image::get_feed`.
+#[derive(Clone)]
+pub struct PixelFeed{
+    ring: Arc<Mutex<FrameRing>>,
+}
+
+impl PixelFeed{
+    /// Queue a new frame of RGBA8 pixels. The size has to match the image.
+    pub fn push(&self, pixels: &[u8], size: (u32, u32)) -> Result<(), &'static str>{
+        self.ring.lock().map_err(|_| "Error, pixel feed was poisoned!")?.push(pixels, size)
+    }
+}
+
+/// # Image
+///
+/// Draws a texture, stretched over a rectangle. Like quads, the position is the centre of the image
+/// from the centre of the screen, and the size is in pixels.
+pub struct Image{
+    quad: Quad,
+    texture: Texture,
+    ring: Arc<Mutex<FrameRing>>,
+    idle_time: f32, // seconds since new pixels last came in
+    enabled: bool,
+}
+
+impl Image{
+    /// Create an image from a texture
+    pub fn new(pos: [f32; 2], size: [f32; 2], texture: Texture, renderer: &Renderer) -> Self{
+        Self{
+            quad: Quad::new(pos, size, [1.0, 1.0, 1.0, 1.0], &renderer.device),
+            ring: Arc::new(Mutex::new(FrameRing::new(texture.size))),
+            texture,
+            idle_time: f32::INFINITY,
+            enabled: true,
+        }
+    }
+
+    /// Create an image from the bytes of an image file
+    pub fn from_bytes(bytes: &[u8], pos: [f32; 2], size: [f32; 2], renderer: &Renderer) -> Result<Self, &'static str>{
+        let texture = Texture::from_bytes(bytes, "Image", &renderer.device, &renderer.queue)?;
+        Ok(Image::new(pos, size, texture, renderer))
+    }
+
+    /// Load an image file (PNG, JPEG and the other formats the `image` crate supports)
+    pub fn from_path(path: &str, pos: [f32; 2], size: [f32; 2], renderer: &Renderer) -> Result<Self, &'static str>{
+        let texture = Texture::from_path(path, renderer)?;
+        Ok(Image::new(pos, size, texture, renderer))
+    }
+
+    /// Create a transparent image, with a given size in pixels, ready to be filled in with `update_pixels`
+    pub fn blank(pixel_size: (u32, u32), pos: [f32; 2], size: [f32; 2], renderer: &Renderer) -> Self{
+        let pixels = vec![0u8; (4 * pixel_size.0 * pixel_size.1) as usize];
+        let texture = Texture::from_rgba(&pixels, pixel_size, "Image", &renderer.device, &renderer.queue);
+        Image::new(pos, size, texture, renderer)
+    }
+
+    /// Replace the pixels of the image with new RGBA8 data. This is cheap enough to do every frame,
+    /// only the newest pixels get uploaded when the frame is prepared.
+    ///
+    /// The size has to match the image's current size, use `resize` to change it.
+    pub fn update_pixels(&mut self, pixels: &[u8], size: (u32, u32)) -> Result<(), &'static str>{
+        self.ring.lock().map_err(|_| "Error, pixel feed was poisoned!")?.push(pixels, size)
+    }
+
+    /// Get a handle to push pixels from another thread
+    pub fn get_feed(&self) -> PixelFeed{
+        PixelFeed{
+            ring: self.ring.clone(),
+        }
+    }
+
+    /// Change the size of the image in pixels. This creates a new (transparent) texture, and drops
+    /// any frames waiting to be uploaded.
+    pub fn resize(&mut self, pixel_size: (u32, u32), renderer: &Renderer){
+        if pixel_size == self.texture.size{
+            return;
+        }
+        let pixels = vec![0u8; (4 * pixel_size.0 * pixel_size.1) as usize];
+        self.texture = Texture::from_rgba(&pixels, pixel_size, "Image", &renderer.device, &renderer.queue);
+        if let Ok(mut ring) = self.ring.lock(){
+            *ring = FrameRing::new(pixel_size);
+        }
+    }
+
+    /// Swap the texture for another one
+    pub fn set_texture(&mut self, texture: Texture){
+        if let Ok(mut ring) = self.ring.lock(){
+            *ring = FrameRing::new(texture.size);
+        }
+        self.texture = texture;
+    }
+
+    /// The size of the texture in pixels
+    pub fn get_pixel_size(&self) -> (u32, u32){
+        self.texture.size
+    }
+
+    pub fn set_pos(&mut self, pos: [f32; 2]){
+        self.quad.set_pos(pos);
+    }
+
+    /// Set how big the image is drawn, in pixels
+    pub fn set_size(&mut self, size: [f32; 2]){
+        self.quad.set_size(size);
+    }
+
+    pub fn get_size(&self) -> [f32; 2]{
+        self.quad.get_size()
+    }
+
+    /// Set a colour to multiply the image by. White leaves it as it is.
+    pub fn set_tint(&mut self, color: [f32; 4]){
+        self.quad.set_color(color);
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+    }
+}
+
+impl GUIComponent for Image{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.enabled{
+            render_pass.set_bind_group(3, &self.texture.bind_group, &[]);
+            self.quad.render(render_pass);
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.quad.get_pos()
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(Rect::new(self.quad.get_pos(), self.quad.get_size()))
+    }
+
+    fn update(&mut self, dt: f32){
+        let pushed = match self.ring.lock(){
+            Ok(mut ring) => std::mem::replace(&mut ring.pushed, false),
+            Err(_) => false,
+        };
+        self.idle_time = if pushed { 0.0 } else { self.idle_time + dt };
+    }
+
+    /// While frames are coming in, keep the main loop ticking so they show up straight away
+    fn is_animating(&self) -> bool{
+        self.enabled && self.idle_time < 0.5
+    }
+
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{
+        Some(self.quad.get_size())
+    }
+
+    /// Images keep their size (so they don't get stretched out of shape), centred in the rectangle
+    fn arrange(&mut self, rect: Rect){
+        self.quad.set_pos(rect.pos);
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        if let Ok(mut ring) = self.ring.lock(){
+            if let Some(latest) = ring.latest.take(){
+                // The ring was checked against the texture size when the pixels were pushed
+                let _ = self.texture.write_pixels(&ring.slots[latest], queue);
+            }
+        }
+        self.quad.prepare(queue, screen_dim);
+    }
+}
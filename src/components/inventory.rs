@@ -0,0 +1,584 @@
+//! This module contains the `InventoryGrid` component, a grid of item slots for games, and the `Inventory` behind
+//! it. Items get dragged between slots (and between grids sharing an `InventoryHand`), and stacks can be split.
+
+use std::{any::Any, cell::RefCell, rc::Rc, sync::atomic::{AtomicUsize, Ordering}};
+
+use wgpu_glyph::{FontId, HorizontalAlign, VerticalAlign};
+
+use crate::{input::{EventPhase, EventResponse, InputState, MouseButton, MouseEvent, UiEvent}, layout::ComponentHandle, rendering::{Quad, Renderer, TextBrush}};
+
+use super::{EventGUIComponent, Icon, Label, Rect, TextGUIComponent, tooltip::HoverTooltip};
+
+// The space between slots, and around the edge of the grid, in pixels
+const SPACING: f32 = 4.0;
+// How big item icons are, as a fraction of the slot
+const ICON_SCALE: f32 = 0.6;
+
+// Every grid gets its own number, so the hand knows which grid an item came from
+static NEXT_GRID: AtomicUsize = AtomicUsize::new(0);
+
+/// # InventoryItem
+///
+/// A stack of items in an inventory slot. Items with the same `id` stack together, up to `max_stack`. The icon is a
+/// glyph from the icon font of the grid showing it (see `Icon`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct InventoryItem{
+    pub id: String,
+    pub name: String,
+    pub icon: char,
+    pub count: u32,
+    pub max_stack: u32,
+}
+
+impl InventoryItem{
+    pub fn new(id: &str, name: &str, icon: char, count: u32, max_stack: u32) -> Self{
+        Self{
+            id: id.to_string(),
+            name: name.to_string(),
+            icon,
+            count,
+            max_stack: max_stack.max(1),
+        }
+    }
+
+    /// Whether the two can go in the same slot
+    pub fn stacks_with(&self, other: &InventoryItem) -> bool{
+        self.id == other.id
+    }
+}
+
+/// # Inventory
+///
+/// A fixed number of slots, each empty or holding a stack of items. This is everything an `InventoryGrid` shows,
+/// and can be used on its own for the game's side of things (picking items up, using them up...).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inventory{
+    slots: Vec<Option<InventoryItem>>,
+}
+
+impl Inventory{
+    /// Create an inventory with some number of empty slots
+    pub fn new(slots: usize) -> Self{
+        Self{
+            slots: vec![None; slots],
+        }
+    }
+
+    pub fn get_slot_count(&self) -> usize{
+        self.slots.len()
+    }
+
+    pub fn get(&self, slot: usize) -> Option<&InventoryItem>{
+        self.slots.get(slot).and_then(|item| item.as_ref())
+    }
+
+    /// Put something in a slot (or empty it), returning what was there
+    pub fn set(&mut self, slot: usize, item: Option<InventoryItem>) -> Option<InventoryItem>{
+        std::mem::replace(self.slots.get_mut(slot)?, item)
+    }
+
+    /// Empty a slot, returning what was in it
+    pub fn take(&mut self, slot: usize) -> Option<InventoryItem>{
+        self.slots.get_mut(slot)?.take()
+    }
+
+    /// Take up to `count` items off the stack in a slot, leaving the rest. Taking the whole stack empties the slot.
+    pub fn split(&mut self, slot: usize, count: u32) -> Option<InventoryItem>{
+        let stack = self.slots.get_mut(slot)?;
+        let item = stack.as_mut()?;
+        let count = count.min(item.count);
+        if count == 0{
+            return None;
+        }
+        if count == item.count{
+            return stack.take();
+        }
+        item.count -= count;
+        let mut taken = item.clone();
+        taken.count = count;
+        Some(taken)
+    }
+
+    /// Put items into a slot, stacking them with what's there if they stack. Returns whatever didn't fit - the
+    /// extra once the stack is full, or everything if the slot holds something else.
+    pub fn put(&mut self, slot: usize, mut item: InventoryItem) -> Option<InventoryItem>{
+        let stack = match self.slots.get_mut(slot){
+            Some(stack) => stack,
+            None => return Some(item),
+        };
+        match stack{
+            None => {
+                let extra = item.count.saturating_sub(item.max_stack);
+                item.count -= extra;
+                *stack = Some(item.clone());
+                Some(InventoryItem{ count: extra, ..item }).filter(|item| item.count > 0)
+            }
+            Some(existing) if existing.stacks_with(&item) => {
+                let moved = item.count.min(existing.max_stack.saturating_sub(existing.count));
+                existing.count += moved;
+                item.count -= moved;
+                Some(item).filter(|item| item.count > 0)
+            }
+            Some(_) => Some(item),
+        }
+    }
+
+    /// Swap the contents of two slots
+    pub fn swap(&mut self, a: usize, b: usize){
+        if a < self.slots.len() && b < self.slots.len(){
+            self.slots.swap(a, b);
+        }
+    }
+
+    /// Add items wherever they fit, topping up stacks of the same item first and then filling empty slots in
+    /// order. Returns whatever didn't fit.
+    pub fn add(&mut self, item: InventoryItem) -> Option<InventoryItem>{
+        let mut left = Some(item);
+        let stacks: Vec<usize> = (0..self.slots.len())
+            .filter(|slot| matches!(self.get(*slot), Some(existing) if left.as_ref().map(|item| existing.stacks_with(item)) == Some(true)))
+            .collect();
+        let empty: Vec<usize> = (0..self.slots.len()).filter(|slot| self.get(*slot).is_none()).collect();
+        for slot in stacks.into_iter().chain(empty){
+            left = match left{
+                Some(item) => self.put(slot, item),
+                None => break,
+            };
+        }
+        left
+    }
+
+    /// How many of an item there are, across every slot
+    pub fn count(&self, id: &str) -> u32{
+        self.slots.iter().flatten().filter(|item| item.id == id).map(|item| item.count).sum()
+    }
+
+    /// Take up to `count` of an item out of the inventory, from the last slots first. Returns how many were taken.
+    pub fn remove(&mut self, id: &str, count: u32) -> u32{
+        let mut removed = 0;
+        for slot in (0..self.slots.len()).rev(){
+            if removed == count{
+                break;
+            }
+            if matches!(self.get(slot), Some(item) if item.id == id){
+                removed += self.split(slot, count - removed).map_or(0, |item| item.count);
+            }
+        }
+        removed
+    }
+}
+
+// An item being dragged, and where it came from
+struct Held{
+    item: InventoryItem,
+    grid: usize,
+    slot: usize,
+    whole: bool, // the whole stack was picked up, leaving the slot empty
+    dropped: bool, // the mouse was let go, so whatever's left goes back where it came from
+}
+
+/// # InventoryHand
+///
+/// The item being dragged. Each `InventoryGrid` has its own, and grids given the same hand (with `set_hand`) can
+/// drag items between each other - like a chest and the player's backpack. It's cheap to clone, and every clone
+/// shares the same item.
+#[derive(Clone, Default)]
+pub struct InventoryHand{
+    held: Rc<RefCell<Option<Held>>>,
+}
+
+impl InventoryHand{
+    pub fn new() -> Self{
+        Self::default()
+    }
+
+    /// The item being dragged, if there is one
+    pub fn get_item(&self) -> Option<InventoryItem>{
+        self.held.borrow().as_ref().map(|held| held.item.clone())
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.held.borrow().is_none()
+    }
+}
+
+/// # InventoryGrid
+///
+/// A grid of item slots showing an `Inventory`. Each item shows its icon, and its count when there's more than one.
+///
+/// Dragging a slot picks up its whole stack, and letting go over another slot drops it there - stacking it with the
+/// same item, or swapping places with a different one. Holding shift while picking up takes half the stack, and
+/// ctrl takes just one. Anything that doesn't fit (or is dropped somewhere that isn't a slot) goes back where it
+/// came from. Hovering an item shows its name in a tooltip.
+///
+/// The item being dragged and the tooltips draw over the grid, so grids belong in the overlay layer
+/// (`Layout::add_overlay_component`), like other popups.
+pub struct InventoryGrid{
+    inventory: Inventory,
+    id: usize,
+    hand: InventoryHand,
+    columns: usize,
+    slot_size: f32,
+    rect: Rect,
+    icon_font: FontId,
+    callback: Option<Box<dyn Fn(usize)>>,
+    slot_colors: ([f32; 4], [f32; 4]), // normal, hovered
+
+    background: Quad,
+    slots: Vec<Quad>,
+    icons: Vec<Option<Icon>>,
+    counts: Vec<Label>,
+    held_icon: Option<Icon>,
+    held_count: Label,
+    tooltip: HoverTooltip,
+    hovered: Option<usize>,
+    input: InputState,
+    enabled: bool,
+}
+
+impl InventoryGrid{
+    /// Create a grid of empty slots, `slot_size` pixels square, centred on `pos`. Item icons come from `icon_font`.
+    pub fn new(pos: [f32; 2], columns: usize, rows: usize, slot_size: f32, icon_font: FontId, renderer: &Renderer) -> Self{
+        let columns = columns.max(1);
+        let count_label = || {
+            let mut label = Label::new("", slot_size * 0.3, [0.0, 0.0]);
+            label.align_horizontal(HorizontalAlign::Right);
+            label.align_vertical(VerticalAlign::Bottom);
+            label
+        };
+        let slot_colors = ([0.35, 0.35, 0.35, 1.0], [0.5, 0.5, 0.5, 1.0]);
+
+        let mut grid = Self{
+            inventory: Inventory::new(columns * rows),
+            id: NEXT_GRID.fetch_add(1, Ordering::Relaxed),
+            hand: InventoryHand::new(),
+            columns,
+            slot_size,
+            rect: Rect::new(pos, [0.0, 0.0]),
+            icon_font,
+            callback: None,
+            slot_colors,
+
+            background: Quad::new(pos, [0.0, 0.0], [0.2, 0.2, 0.2, 0.95], &renderer.device),
+            slots: (0..columns * rows).map(|_| Quad::new(pos, [slot_size, slot_size], slot_colors.0, &renderer.device)).collect(),
+            icons: (0..columns * rows).map(|_| None).collect(),
+            counts: (0..columns * rows).map(|_| count_label()).collect(),
+            held_icon: None,
+            held_count: count_label(),
+            tooltip: HoverTooltip::new(),
+            hovered: None,
+            input: InputState::new(),
+            enabled: true,
+        };
+        grid.set_pos(pos);
+        grid
+    }
+
+    pub fn borrow_inventory(&self) -> &Inventory{
+        &self.inventory
+    }
+
+    /// Borrow the inventory to change the items. The grid shows the changes in the next frame.
+    pub fn borrow_inventory_mut(&mut self) -> &mut Inventory{
+        &mut self.inventory
+    }
+
+    /// Get the hand this grid drags items with, to share it with other grids
+    pub fn get_hand(&self) -> InventoryHand{
+        self.hand.clone()
+    }
+
+    /// Drag items with another grid's hand, so items can go between the two
+    pub fn set_hand(&mut self, hand: InventoryHand){
+        self.hand = hand;
+    }
+
+    /// Set the function to run with a slot's index whenever dragging changes what's in it
+    pub fn set_callback(&mut self, callback: Option<Box<dyn Fn(usize)>>){
+        self.callback = callback;
+    }
+
+    /// Set the colours behind the grid and of the slots, normally and while hovered
+    pub fn set_colors(&mut self, background: [f32; 4], slot: [f32; 4], hovered: [f32; 4]){
+        self.background.set_color(background);
+        self.slot_colors = (slot, hovered);
+    }
+
+    pub fn set_pos(&mut self, pos: [f32; 2]){
+        let rows = self.slots.len().div_ceil(self.columns);
+        let size = [self.grid_length(self.columns), self.grid_length(rows)];
+        self.rect = Rect::new(pos, size);
+        self.background.set_pos(pos);
+        self.background.set_size(size);
+        let left = pos[0] - size[0] / 2.0 + SPACING + self.slot_size / 2.0;
+        let top = pos[1] - size[1] / 2.0 + SPACING + self.slot_size / 2.0;
+        for (i, slot) in self.slots.iter_mut().enumerate(){
+            let step = self.slot_size + SPACING;
+            slot.set_pos([left + (i % self.columns) as f32 * step, top + (i / self.columns) as f32 * step]);
+        }
+    }
+
+    pub fn get_rect(&self) -> Rect{
+        self.rect
+    }
+
+    /// The slot under a point, if there is one
+    pub fn slot_at(&self, pos: [f32; 2]) -> Option<usize>{
+        if !self.rect.contains(pos){
+            return None;
+        }
+        self.slots.iter().position(|slot| Rect::new(slot.get_pos(), slot.get_size()).contains(pos))
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+        self.hovered = None;
+        self.tooltip.set_target(None, [0.0, 0.0]);
+    }
+
+    // How long a row or column of slots is, with the spacing around them
+    fn grid_length(&self, slots: usize) -> f32{
+        slots as f32 * (self.slot_size + SPACING) + SPACING
+    }
+
+    fn changed(&self, slot: usize){
+        if let Some(callback) = &self.callback{
+            callback(slot);
+        }
+    }
+
+    // Pick up some or all of the stack in a slot
+    fn pick_up(&mut self, slot: usize, modifiers: crate::input::Modifiers){
+        let count = match self.inventory.get(slot){
+            Some(_) if modifiers.ctrl => 1,
+            Some(item) if modifiers.shift => item.count.div_ceil(2),
+            Some(item) => item.count,
+            None => return,
+        };
+        if let Some(item) = self.inventory.split(slot, count){
+            let whole = self.inventory.get(slot).is_none();
+            *self.hand.held.borrow_mut() = Some(Held{ item, grid: self.id, slot, whole, dropped: false });
+            self.changed(slot);
+        }
+    }
+
+    // Drop what's being dragged into a slot. Whatever doesn't fit stays in the hand, to go back where it came from.
+    fn drop_on(&mut self, slot: usize){
+        let mut hand = self.hand.held.borrow_mut();
+        let held = match hand.as_mut(){
+            Some(held) => held,
+            None => return,
+        };
+        held.dropped = true;
+        if held.grid == self.id && held.slot == slot{
+            return;
+        }
+
+        let different = matches!(self.inventory.get(slot), Some(existing) if !existing.stacks_with(&held.item));
+        if different{
+            // Swap places, if the slot it came from was left empty for the other item to go into
+            if held.whole{
+                let displaced = self.inventory.set(slot, Some(held.item.clone()));
+                if let Some(displaced) = displaced{
+                    held.item = displaced;
+                }
+                drop(hand);
+                self.changed(slot);
+            }
+            return;
+        }
+        match self.inventory.put(slot, held.item.clone()){
+            Some(left) => held.item = left,
+            None => *hand = None,
+        }
+        drop(hand);
+        self.changed(slot);
+    }
+
+    // Put back whatever was dragged out of this grid and let go of, keeping it in the hand if it won't fit anywhere
+    fn return_held(&mut self){
+        let mut hand = self.hand.held.borrow_mut();
+        let (item, slot) = match hand.as_ref(){
+            Some(held) if held.grid == self.id && held.dropped => (held.item.clone(), held.slot),
+            _ => return,
+        };
+        let inventory = &mut self.inventory;
+        let left = inventory.put(slot, item).and_then(|item| inventory.add(item));
+        match (hand.as_mut(), left){
+            (Some(held), Some(item)) => {
+                held.item = item;
+                held.dropped = false;
+            }
+            _ => *hand = None,
+        }
+        drop(hand);
+        self.changed(slot);
+    }
+
+    fn is_holding(&self) -> bool{
+        matches!(self.hand.held.borrow().as_ref(), Some(held) if held.grid == self.id)
+    }
+
+    fn make_icon(&self, item: &InventoryItem, pos: [f32; 2], screen_dim: (u32, u32)) -> Option<Icon>{
+        let mut icon = Icon::from_codepoint(item.icon as u32, self.icon_font, self.slot_size * ICON_SCALE, [0.0, 0.0])?;
+        icon.set_color([1.0, 1.0, 1.0, 1.0]);
+        icon.set_pos(pos, screen_dim);
+        Some(icon)
+    }
+}
+
+impl EventGUIComponent for InventoryGrid{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.enabled{
+            self.background.render(render_pass);
+            for slot in self.slots.iter(){
+                slot.render(render_pass);
+            }
+            self.tooltip.render(render_pass);
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if !self.enabled{
+            return;
+        }
+        for (icon, count) in self.icons.iter().zip(self.counts.iter()){
+            if let Some(icon) = icon{
+                TextGUIComponent::render_text(icon, brush);
+                TextGUIComponent::render_text(count, brush);
+            }
+        }
+        self.tooltip.render_text(brush);
+        if let Some(icon) = &self.held_icon{
+            TextGUIComponent::render_text(icon, brush);
+            TextGUIComponent::render_text(&self.held_count, brush);
+        }
+    }
+
+    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        if !self.enabled{
+            return;
+        }
+
+        match self.input.handle_event(event, window){
+            Some(UiEvent::CursorMoved{ pos, .. }) => {
+                self.hovered = self.slot_at(pos);
+                let name = self.hovered.and_then(|slot| self.inventory.get(slot)).filter(|_| self.hand.is_empty()).map(|item| item.name.clone());
+                // The slot stands in for a component, so moving to another slot starts the delay again
+                self.tooltip.set_target(self.hovered.zip(name.as_deref()).map(|(slot, name)| (ComponentHandle::Component(slot), name)), pos);
+            }
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed: true, pos, modifiers })) => {
+                self.tooltip.dismiss();
+                if let (Some(slot), true) = (self.slot_at(pos), self.hand.is_empty()){
+                    self.pick_up(slot, modifiers);
+                }
+            }
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed: false, pos, .. })) => {
+                match self.slot_at(pos){
+                    Some(slot) => self.drop_on(slot),
+                    None => {
+                        if let Some(held) = self.hand.held.borrow_mut().as_mut().filter(|held| held.grid == self.id){
+                            held.dropped = true;
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Clicks on the grid are ours, and so is every mouse event while dragging something out of it
+    fn handle_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window, phase: EventPhase) -> EventResponse{
+        if phase != EventPhase::Bubble{
+            return EventResponse::Continue;
+        }
+        let click = matches!(event, winit::event::Event::WindowEvent{ event: winit::event::WindowEvent::MouseInput{ .. }, .. });
+        let taken = self.enabled && click && (self.rect.contains(self.input.get_cursor_pos()) || self.is_holding());
+        self.handle_event_callback(event, window);
+        if taken { EventResponse::Consumed } else { EventResponse::Continue }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.rect.pos
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(self.rect)
+    }
+
+    fn update(&mut self, dt: f32){
+        self.return_held();
+        self.tooltip.update(dt);
+    }
+
+    /// Items let go of somewhere else get put back in the next update, and tooltips wait out their delay
+    fn is_animating(&self) -> bool{
+        let returning = matches!(self.hand.held.borrow().as_ref(), Some(held) if held.grid == self.id && held.dropped);
+        returning || (self.tooltip.get_target().is_some() && !self.tooltip.is_visible())
+    }
+
+    fn rebuild(&mut self, device: &wgpu::Device) -> bool{
+        self.tooltip.rebuild(device);
+        false
+    }
+
+    fn layout_text(&mut self, brush: &mut TextBrush){
+        self.tooltip.layout_text(brush);
+    }
+
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{
+        Some(self.rect.size)
+    }
+
+    fn arrange(&mut self, rect: Rect){
+        if rect.pos != self.rect.pos{
+            self.set_pos(rect.pos);
+        }
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.background.prepare(queue, screen_dim);
+        let corner = self.slot_size / 2.0 - SPACING;
+        for i in 0..self.slots.len(){
+            let pos = self.slots[i].get_pos();
+            self.slots[i].set_color(if self.hovered == Some(i) { self.slot_colors.1 } else { self.slot_colors.0 });
+            self.slots[i].prepare(queue, screen_dim);
+
+            self.icons[i] = self.inventory.get(i).and_then(|item| self.make_icon(item, pos, screen_dim));
+            let count = self.inventory.get(i).map_or(0, |item| item.count);
+            self.counts[i].set_text(if count > 1 { count.to_string() } else { String::new() });
+            self.counts[i].set_pos([pos[0] + corner, pos[1] + corner], screen_dim);
+        }
+
+        // The item being dragged follows the cursor
+        let cursor = self.input.get_cursor_pos();
+        let held = self.hand.held.borrow().as_ref().filter(|held| held.grid == self.id && !held.dropped).map(|held| held.item.clone());
+        self.held_icon = held.as_ref().and_then(|item| self.make_icon(item, cursor, screen_dim));
+        let count = held.map_or(0, |item| item.count);
+        self.held_count.set_text(if count > 1 { count.to_string() } else { String::new() });
+        self.held_count.set_pos([cursor[0] + corner, cursor[1] + corner], screen_dim);
+
+        self.tooltip.prepare(queue, screen_dim);
+    }
+}
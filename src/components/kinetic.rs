@@ -0,0 +1,292 @@
+//! This module contains the physics behind kinetic (inertial) scrolling.
+//! Scrollable containers feed it drags and wheel movement, step it with the frame clock,
+//! and read the offset back out. Letting go of a drag keeps the content moving with a
+//! decaying velocity, and going past the edges either bounces back or shows a glow.
+//!
+//! It also handles programmatic scrolling, like jumping to an offset or bringing an item into view,
+//! either straight away or animated.
+
+/// What happens when the content is scrolled past one of its edges
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum OverscrollEffect{
+    /// Stop dead at the edge
+    Clamp,
+    /// Let the content go past the edge (with some resistance), then spring back.
+    /// Higher stiffness springs back faster.
+    Bounce{ stiffness: f32 },
+    /// Stop at the edge, but report a glow amount (see `get_glow`) the container can draw
+    Glow,
+}
+
+/// # KineticScroll
+///
+/// Tracks the scroll offset of a container along one axis. The offset goes from 0 to `max_offset`,
+/// with overscroll pushing it (temporarily) outside that range when bouncing.
+pub struct KineticScroll{
+    offset: f32,
+    max_offset: f32,
+    viewport_size: f32,
+    velocity: f32, // pixels per second
+    target: Option<f32>, // where an animated scroll is heading
+    scroll_speed: f32,
+
+    friction: f32, // how quickly the velocity decays, per second
+    effect: OverscrollEffect,
+    glow: f32,
+
+    dragging: bool,
+    drag_pos: f32,
+    drag_delta: f32, // movement since the last update, used to work out the release velocity
+}
+
+impl KineticScroll{
+    /// Create a new kinetic scroll, with a bouncy overscroll
+    pub fn new() -> Self{
+        Self{
+            offset: 0.0,
+            max_offset: 0.0,
+            viewport_size: 0.0,
+            velocity: 0.0,
+            target: None,
+            scroll_speed: 12.0,
+
+            friction: 4.0,
+            effect: OverscrollEffect::Bounce{ stiffness: 120.0 },
+            glow: 0.0,
+
+            dragging: false,
+            drag_pos: 0.0,
+            drag_delta: 0.0,
+        }
+    }
+
+    /// Set how far the content can be scrolled (content size - viewport size)
+    pub fn set_max_offset(&mut self, max_offset: f32){
+        self.max_offset = max_offset.max(0.0);
+        // A bouncing scroll springs back on its own, everything else snaps straight to the new edge
+        if !matches!(self.effect, OverscrollEffect::Bounce{ .. }){
+            self.offset = self.clamp(self.offset);
+        }
+    }
+
+    pub fn get_max_offset(&self) -> f32{
+        self.max_offset
+    }
+
+    /// Set the size of the content and of the viewport showing it. This also sets the max offset.
+    pub fn set_content(&mut self, content_size: f32, viewport_size: f32){
+        self.viewport_size = viewport_size.max(0.0);
+        self.set_max_offset(content_size - viewport_size);
+    }
+
+    pub fn get_viewport_size(&self) -> f32{
+        self.viewport_size
+    }
+
+    /// Set how quickly animated scrolls reach their target. Higher is faster.
+    pub fn set_scroll_speed(&mut self, scroll_speed: f32){
+        self.scroll_speed = scroll_speed.max(0.0);
+    }
+
+    /// Set how quickly the content slows down after letting go. Higher values stop sooner.
+    pub fn set_friction(&mut self, friction: f32){
+        self.friction = friction.max(0.0);
+    }
+
+    pub fn set_overscroll_effect(&mut self, effect: OverscrollEffect){
+        self.effect = effect;
+    }
+
+    /// The current offset. While bouncing, this can be outside of 0 to `max_offset`.
+    pub fn get_offset(&self) -> f32{
+        self.offset
+    }
+
+    /// How far past the edge we are. Negative past the start, positive past the end.
+    pub fn get_overscroll(&self) -> f32{
+        if self.offset < 0.0{
+            self.offset
+        }else if self.offset > self.max_offset{
+            self.offset - self.max_offset
+        }else{
+            0.0
+        }
+    }
+
+    /// How strong the edge glow should be (0 to 1), when using `OverscrollEffect::Glow`
+    pub fn get_glow(&self) -> f32{
+        self.glow
+    }
+
+    pub fn get_velocity(&self) -> f32{
+        self.velocity
+    }
+
+    /// Jump to an offset, stopping any movement
+    pub fn set_offset(&mut self, offset: f32){
+        self.offset = self.clamp(offset);
+        self.velocity = 0.0;
+        self.target = None;
+    }
+
+    /// Scroll to an offset. If animated, the content glides there over the next few frames.
+    pub fn scroll_to(&mut self, offset: f32, animated: bool){
+        if animated{
+            self.velocity = 0.0;
+            self.target = Some(self.clamp(offset));
+        }else{
+            self.set_offset(offset);
+        }
+    }
+
+    /// Scroll the least amount needed to show the span from `start` to `start + size` (in content space).
+    /// If the span is bigger than the viewport, its start is shown.
+    pub fn ensure_visible(&mut self, start: f32, size: f32, animated: bool){
+        let offset = self.target.unwrap_or(self.offset);
+        if start < offset || size > self.viewport_size{
+            self.scroll_to(start, animated);
+        }else if start + size > offset + self.viewport_size{
+            self.scroll_to(start + size - self.viewport_size, animated);
+        }
+    }
+
+    /// Check if the span from `start` to `start + size` is at least partly in view
+    pub fn is_visible(&self, start: f32, size: f32) -> bool{
+        start + size > self.offset && start < self.offset + self.viewport_size
+    }
+
+    /// Where the content is heading, if an animated scroll is running
+    pub fn get_target(&self) -> Option<f32>{
+        self.target
+    }
+
+    /// Scroll by an amount straight away, like a mouse wheel does
+    pub fn scroll_by(&mut self, delta: f32){
+        self.velocity = 0.0;
+        self.target = None;
+        let offset = self.offset + delta;
+        if self.effect == OverscrollEffect::Glow && (offset < 0.0 || offset > self.max_offset){
+            self.glow = 1.0;
+        }
+        self.offset = self.clamp(offset);
+    }
+
+    /// Start dragging the content. The position is the cursor position along the scroll axis.
+    pub fn begin_drag(&mut self, pos: f32){
+        self.dragging = true;
+        self.drag_pos = pos;
+        self.drag_delta = 0.0;
+        self.velocity = 0.0;
+        self.target = None;
+    }
+
+    /// Move the drag. Dragging down (or right) scrolls back towards the start.
+    pub fn drag_to(&mut self, pos: f32){
+        if !self.dragging{
+            return;
+        }
+        let mut delta = self.drag_pos - pos;
+        self.drag_pos = pos;
+        self.drag_delta += delta;
+
+        match self.effect{
+            OverscrollEffect::Bounce{ .. } => {
+                // Resist the drag past the edges, so it feels like pulling on a rubber band
+                if self.offset + delta < 0.0 || self.offset + delta > self.max_offset{
+                    delta *= 0.5;
+                }
+                self.offset += delta;
+            }
+            OverscrollEffect::Glow => {
+                let offset = self.offset + delta;
+                if offset < 0.0 || offset > self.max_offset{
+                    self.glow = (self.glow + delta.abs() / 100.0).min(1.0);
+                }
+                self.offset = self.clamp(offset);
+            }
+            OverscrollEffect::Clamp => {
+                self.offset = self.clamp(self.offset + delta);
+            }
+        }
+    }
+
+    /// Let go of the content. It keeps moving with the speed it was dragged at.
+    pub fn end_drag(&mut self){
+        self.dragging = false;
+    }
+
+    pub fn is_dragging(&self) -> bool{
+        self.dragging
+    }
+
+    /// Step the simulation. Call this from the component's `update`.
+    pub fn update(&mut self, dt: f32){
+        if dt <= 0.0{
+            return;
+        }
+
+        if self.dragging{
+            // Smooth the release velocity a bit, so one jittery frame doesn't throw the content
+            self.velocity = self.velocity * 0.5 + (self.drag_delta / dt) * 0.5;
+            self.drag_delta = 0.0;
+            return;
+        }
+
+        if let Some(target) = self.target{
+            // Exponential smoothing towards the target, like the caret uses
+            self.offset += (target - self.offset) * (1.0 - (-self.scroll_speed * dt).exp());
+            if (target - self.offset).abs() < 0.5{
+                self.offset = target;
+                self.target = None;
+            }
+            return;
+        }
+
+        self.offset += self.velocity * dt;
+        self.velocity *= (-self.friction * dt).exp();
+
+        let overscroll = self.get_overscroll();
+        match self.effect{
+            OverscrollEffect::Bounce{ stiffness } => {
+                if overscroll != 0.0{
+                    // Critically damped spring, pulling us back to the edge
+                    let damping = 2.0 * stiffness.sqrt();
+                    self.velocity += (-stiffness * overscroll - damping * self.velocity) * dt;
+
+                    // Snap to the edge once we're close enough, rather than creeping towards it forever
+                    if overscroll.abs() < 0.5 && self.velocity.abs() < 10.0{
+                        self.offset = self.clamp(self.offset);
+                        self.velocity = 0.0;
+                    }
+                }
+            }
+            OverscrollEffect::Glow => {
+                if overscroll != 0.0{
+                    self.glow = (self.glow + self.velocity.abs() / 2000.0).min(1.0);
+                    self.offset = self.clamp(self.offset);
+                    self.velocity = 0.0;
+                }
+                self.glow = (self.glow - dt * 2.0).max(0.0);
+            }
+            OverscrollEffect::Clamp => {
+                if overscroll != 0.0{
+                    self.offset = self.clamp(self.offset);
+                    self.velocity = 0.0;
+                }
+            }
+        }
+
+        if self.velocity.abs() < 1.0{
+            self.velocity = 0.0;
+        }
+    }
+
+    /// True while the content is still moving on its own
+    pub fn is_animating(&self) -> bool{
+        !self.dragging && (self.target.is_some() || self.velocity != 0.0 || self.get_overscroll() != 0.0 || self.glow > 0.0)
+    }
+
+    fn clamp(&self, offset: f32) -> f32{
+        offset.max(0.0).min(self.max_offset)
+    }
+}
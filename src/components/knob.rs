@@ -0,0 +1,291 @@
+//! This module contains the `Knob` component, a dial turned by dragging up and down to pick a number from a range,
+//! like the ones on synths and mixing desks.
+
+use std::{any::Any, f32::consts::PI, rc::Rc};
+
+use crate::{input::{EventPhase, EventResponse, InputCategory, InputState, MouseButton, MouseEvent, UiEvent}, rendering::{Quad, Renderer, Texture}};
+
+use super::{EventGUIComponent, Rect, canvas::disc_pixels, slider::snap_value};
+
+// How many pixels across the texture for the dial is
+const DIAL_RESOLUTION: u32 = 64;
+// How much slower the value changes while shift is held
+const FINE_FACTOR: f32 = 10.0;
+
+/// How far a knob is turned for a value `fraction` of the way along its range (0 to 1), in radians clockwise from
+/// pointing straight up. The knob turns through `sweep` radians in total, evenly either side of the top.
+pub(crate) fn knob_angle(fraction: f32, sweep: f32) -> f32{
+    (fraction.clamp(0.0, 1.0) - 0.5) * sweep
+}
+
+/// The value a knob is dragged to, from `value` when the drag started and `distance` pixels up since then (down is
+/// negative). Dragging `travel` pixels goes from one end of the range to the other, and the value stays inside it.
+pub(crate) fn knob_drag(value: f32, distance: f32, min: f32, max: f32, travel: f32) -> f32{
+    let (low, high) = if min <= max { (min, max) } else { (max, min) };
+    (value + distance / travel.max(1.0) * (max - min)).clamp(low, high)
+}
+
+/// # Knob
+///
+/// A round dial with a line showing its value, centred on its position. Pressing on it and dragging up turns it up,
+/// and dragging down turns it down, running the callback each time the value changes. Holding shift turns it more
+/// slowly, for fine adjustments. Like a slider, the drag carries on even if the cursor leaves the knob.
+///
+/// By default it turns through 270 degrees, and a drag of 200 pixels goes across the whole range.
+pub struct Knob{
+    pos: [f32; 2],
+    radius: f32,
+    min: f32,
+    max: f32,
+    step: Option<f32>,
+    value: f32,
+    sweep: f32, // radians
+    travel: f32, // pixels to drag across the whole range
+    callback: Option<Box<dyn Fn(f32)>>,
+
+    dial: Quad,
+    indicator: Quad,
+
+    input: InputState,
+    drag: Option<(f32, f32, bool)>, // where the drag was anchored (y and value), and whether it was fine
+    enabled: bool,
+}
+
+impl Knob{
+    /// Create a knob reaching `radius` pixels out from its centre, going from `min` to `max` and starting at `value`
+    pub fn new(pos: [f32; 2], radius: f32, min: f32, max: f32, value: f32, callback: Option<Box<dyn Fn(f32)>>, renderer: &Renderer) -> Self{
+        let disc = Rc::new(Texture::from_rgba(&disc_pixels(DIAL_RESOLUTION), (DIAL_RESOLUTION, DIAL_RESOLUTION), "Knob", &renderer.device, &renderer.queue));
+        let mut dial = Quad::new(pos, [radius * 2.0, radius * 2.0], [0.3, 0.3, 0.3, 1.0], &renderer.device);
+        dial.set_texture(Some(disc));
+        // The indicator is drawn over the dial, so it needs a texture of its own to cover up the dial's
+        let mut indicator = Quad::new(pos, [0.0, 0.0], [0.95, 0.95, 0.95, 1.0], &renderer.device);
+        indicator.set_texture(Some(Rc::new(Texture::from_rgba(&[255, 255, 255, 255], (1, 1), "Knob indicator", &renderer.device, &renderer.queue))));
+        let mut knob = Self{
+            pos,
+            radius,
+            min,
+            max,
+            step: None,
+            value: min,
+            sweep: PI * 1.5,
+            travel: 200.0,
+            callback,
+
+            dial,
+            indicator,
+
+            input: InputState::new(),
+            drag: None,
+            enabled: true,
+        };
+        knob.set_value(value);
+        knob
+    }
+
+    pub fn get_value(&self) -> f32{
+        self.value
+    }
+
+    /// Turn the knob to a value (snapped and clamped to the range). This doesn't run the callback.
+    pub fn set_value(&mut self, value: f32){
+        self.value = snap_value(value, self.min, self.max, self.step);
+        self.update_quads();
+    }
+
+    /// Change the range, keeping the value inside it
+    pub fn set_range(&mut self, min: f32, max: f32){
+        self.min = min;
+        self.max = max;
+        self.set_value(self.value);
+    }
+
+    /// Only allow values that are a multiple of `step` away from the minimum, or any value with `None`
+    pub fn set_step(&mut self, step: Option<f32>){
+        self.step = step.filter(|step| *step > 0.0);
+        self.set_value(self.value);
+    }
+
+    /// Set how far the knob turns from one end of its range to the other, in radians
+    pub fn set_sweep(&mut self, sweep: f32){
+        self.sweep = sweep;
+        self.update_quads();
+    }
+
+    /// Set how many pixels of dragging go from one end of the range to the other
+    pub fn set_travel(&mut self, travel: f32){
+        self.travel = travel.max(1.0);
+    }
+
+    /// Set the function to run when the value is dragged to something new
+    pub fn set_callback(&mut self, callback: Option<Box<dyn Fn(f32)>>){
+        self.callback = callback;
+    }
+
+    pub fn set_pos(&mut self, pos: [f32; 2]){
+        self.pos = pos;
+        self.update_quads();
+    }
+
+    pub fn set_radius(&mut self, radius: f32){
+        self.radius = radius;
+        self.update_quads();
+    }
+
+    /// Set the colours of the dial and the line on it
+    pub fn set_colors(&mut self, dial: [f32; 4], indicator: [f32; 4]){
+        self.dial.set_color(dial);
+        self.indicator.set_color(indicator);
+    }
+
+    pub fn is_dragging(&self) -> bool{
+        self.drag.is_some()
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+        self.drag = None;
+    }
+
+    fn contains(&self, pos: [f32; 2]) -> bool{
+        (pos[0] - self.pos[0]).powi(2) + (pos[1] - self.pos[1]).powi(2) <= self.radius * self.radius
+    }
+
+    // Work the value out from how far the cursor is from where the drag was anchored. Pressing or letting go of
+    // shift anchors it again where the cursor is, so the knob doesn't jump.
+    fn drag_to(&mut self, y: f32, fine: bool){
+        let (anchor_y, anchor_value, anchor_fine) = match self.drag{
+            Some(drag) => drag,
+            None => return,
+        };
+        if fine != anchor_fine{
+            self.drag = Some((y, self.value, fine));
+            return;
+        }
+        let travel = if fine { self.travel * FINE_FACTOR } else { self.travel };
+        let old_value = self.value;
+        self.set_value(knob_drag(anchor_value, anchor_y - y, self.min, self.max, travel));
+        if self.value != old_value{
+            if let Some(callback) = &self.callback{
+                callback(self.value);
+            }
+        }
+    }
+
+    fn update_quads(&mut self){
+        let fraction = if self.max == self.min { 0.0 } else { (self.value - self.min) / (self.max - self.min) };
+        let angle = knob_angle(fraction, self.sweep);
+        // The line runs from part way out to near the edge, turned to point along the angle
+        let length = self.radius * 0.45;
+        let middle = self.radius * 0.55;
+        self.dial.set_pos(self.pos);
+        self.dial.set_size([self.radius * 2.0, self.radius * 2.0]);
+        self.indicator.set_pos([self.pos[0] + angle.sin() * middle, self.pos[1] - angle.cos() * middle]);
+        self.indicator.set_size([(self.radius * 0.12).max(2.0), length]);
+        self.indicator.set_rotation(angle);
+    }
+}
+
+impl EventGUIComponent for Knob{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.enabled{
+            self.dial.render(render_pass);
+            self.indicator.render(render_pass);
+        }
+    }
+
+    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        if !self.enabled{
+            return;
+        }
+
+        match self.input.handle_event(event, window){
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed: true, pos, modifiers })) if self.contains(pos) => {
+                self.drag = Some((pos[1], self.value, modifiers.shift));
+            }
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed: false, .. })) => self.drag = None,
+            Some(UiEvent::CursorMoved{ pos, modifiers }) if self.drag.is_some() => self.drag_to(pos[1], modifiers.shift),
+            _ => {}
+        }
+    }
+
+    /// The mouse is ours while dragging, so components under the cursor don't react to the drag
+    fn handle_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window, phase: EventPhase) -> EventResponse{
+        if phase != EventPhase::Bubble{
+            return EventResponse::Continue;
+        }
+        let was_dragging = self.is_dragging();
+        self.handle_event_callback(event, window);
+        let mouse = InputCategory::of(event) == Some(InputCategory::Mouse);
+        if mouse && (was_dragging || self.is_dragging()) { EventResponse::Consumed } else { EventResponse::Continue }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.pos
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(Rect::new(self.pos, [self.radius * 2.0, self.radius * 2.0]))
+    }
+
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{
+        Some([self.radius * 2.0, self.radius * 2.0])
+    }
+
+    /// Knobs keep their size, centred in the rectangle
+    fn arrange(&mut self, rect: Rect){
+        if rect.pos != self.pos{
+            self.set_pos(rect.pos);
+        }
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.dial.prepare(queue, screen_dim);
+        self.indicator.prepare(queue, screen_dim);
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Test that knobs turn evenly either side of straight up, and stop at the ends of their sweep
+    #[test]
+    fn knob_angle_test(){
+        let sweep = PI * 1.5;
+        assert_eq!(knob_angle(0.5, sweep), 0.0);
+        assert_eq!(knob_angle(0.0, sweep), -PI * 0.75);
+        assert_eq!(knob_angle(1.0, sweep), PI * 0.75);
+        assert_eq!(knob_angle(2.0, sweep), PI * 0.75);
+    }
+
+    /// Test that dragging up turns a knob up by the share of the travel dragged, kept inside the range
+    #[test]
+    fn knob_drag_test(){
+        assert_eq!(knob_drag(0.5, 50.0, 0.0, 1.0, 200.0), 0.75);
+        assert_eq!(knob_drag(0.5, -100.0, 0.0, 1.0, 200.0), 0.0);
+        assert_eq!(knob_drag(0.5, 400.0, 0.0, 1.0, 200.0), 1.0);
+        // Reversed ranges turn the other way, but still stay inside
+        assert_eq!(knob_drag(5.0, 100.0, 10.0, 0.0, 200.0), 0.0);
+    }
+}
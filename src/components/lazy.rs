@@ -0,0 +1,93 @@
+//! This module contains the hook used for infinite scrolling. When the user scrolls close
+//! to the end of a list, the loader runs a callback to fetch more items, and stays in a
+//! loading state (so a spinner row can be shown) until the fetch reports it is done.
+
+use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
+
+/// # LoadHandle
+///
+/// Given to the load callback. Call `finish` once the new items have been added - this can
+/// be done from any thread, so fetching can happen in the background.
+#[derive(Clone)]
+pub struct LoadHandle{
+    done: Arc<AtomicBool>,
+}
+
+impl LoadHandle{
+    pub(crate) fn new(done: Arc<AtomicBool>) -> Self{
+        Self{
+            done,
+        }
+    }
+
+    /// Mark the fetch as done, so the loader can fire again
+    pub fn finish(&self){
+        self.done.store(true, Ordering::SeqCst);
+    }
+}
+
+/// # LazyLoader
+///
+/// Watches the scroll offset of a list, and runs the callback when the end of the content
+/// is closer than the threshold (in pixels). Only one fetch runs at a time.
+pub struct LazyLoader{
+    threshold: f32,
+    callback: Option<Box<dyn Fn(LoadHandle)>>,
+    pending: Option<Arc<AtomicBool>>,
+    exhausted: bool,
+}
+
+impl LazyLoader{
+    /// Create a new loader, firing when the end is within `threshold` pixels
+    pub fn new(threshold: f32, callback: Option<Box<dyn Fn(LoadHandle)>>) -> Self{
+        Self{
+            threshold,
+            callback,
+            pending: None,
+            exhausted: false,
+        }
+    }
+
+    pub fn set_callback(&mut self, callback: Option<Box<dyn Fn(LoadHandle)>>){
+        self.callback = callback;
+    }
+
+    pub fn set_threshold(&mut self, threshold: f32){
+        self.threshold = threshold;
+    }
+
+    /// Stop (or restart) loading, for when there is nothing more to fetch
+    pub fn set_exhausted(&mut self, exhausted: bool){
+        self.exhausted = exhausted;
+    }
+
+    pub fn is_exhausted(&self) -> bool{
+        self.exhausted
+    }
+
+    /// True while a fetch is running. Lists show their loading row while this is true.
+    pub fn is_loading(&mut self) -> bool{
+        if let Some(done) = &self.pending{
+            if done.load(Ordering::SeqCst){
+                self.pending = None;
+            }
+        }
+        self.pending.is_some()
+    }
+
+    /// Check the scroll position, and run the callback if we're close enough to the end.
+    /// Returns true if a fetch was started.
+    pub fn check(&mut self, offset: f32, max_offset: f32) -> bool{
+        if self.exhausted || self.is_loading() || max_offset - offset > self.threshold{
+            return false;
+        }
+
+        if let Some(callback) = &self.callback{
+            let done = Arc::new(AtomicBool::new(false));
+            self.pending = Some(done.clone());
+            callback(LoadHandle::new(done));
+            return true;
+        }
+        false
+    }
+}
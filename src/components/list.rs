@@ -0,0 +1,318 @@
+//! This module contains the data binding side of lists. A `ListData` holds the items and remembers what changed,
+//! a `ListAdapter` turns items into row components, and a `ListView` keeps its rows in step with the data,
+//! rebuilding only the rows that changed.
+
+use std::{any::Any, cell::RefCell, rc::Rc};
+
+use crate::{input::{EventPhase, EventResponse}, rendering::TextBrush};
+
+use super::{EventGUIComponent, Orientation, Rect, StackPanel};
+
+/// A change made to a `ListData`, by the index of the item
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ListChange{
+    Inserted(usize),
+    Removed(usize),
+    Updated(usize),
+    /// Everything changed, so every row needs making again
+    Reset,
+}
+
+struct ListState<T>{
+    items: Vec<T>,
+    changes: Vec<ListChange>,
+}
+
+/// # ListData
+///
+/// The items shown by a `ListView`. Changing the items through it records what changed, so the view only has to
+/// touch the rows that did. It's cheap to clone, and every clone shares the same items, so one can be kept
+/// outside the layout (in a callback, for example) to change the list later.
+///
+/// The changes are taken by the view that shows the data, so each `ListData` should only be shown by one view.
+pub struct ListData<T>{
+    state: Rc<RefCell<ListState<T>>>,
+}
+
+impl<T> Clone for ListData<T>{
+    fn clone(&self) -> Self{
+        Self{
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<T> ListData<T>{
+    pub fn new() -> Self{
+        Self::from_vec(Vec::new())
+    }
+
+    /// Start off with some items
+    pub fn from_vec(items: Vec<T>) -> Self{
+        Self{
+            state: Rc::new(RefCell::new(ListState{
+                items,
+                changes: vec![ListChange::Reset],
+            })),
+        }
+    }
+
+    pub fn len(&self) -> usize{
+        self.state.borrow().items.len()
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.state.borrow().items.is_empty()
+    }
+
+    pub fn push(&self, item: T){
+        let mut state = self.state.borrow_mut();
+        state.items.push(item);
+        let index = state.items.len() - 1;
+        state.changes.push(ListChange::Inserted(index));
+    }
+
+    pub fn insert(&self, index: usize, item: T){
+        let mut state = self.state.borrow_mut();
+        state.items.insert(index, item);
+        state.changes.push(ListChange::Inserted(index));
+    }
+
+    pub fn remove(&self, index: usize) -> T{
+        let mut state = self.state.borrow_mut();
+        let item = state.items.remove(index);
+        state.changes.push(ListChange::Removed(index));
+        item
+    }
+
+    /// Replace an item, returning the old one
+    pub fn set(&self, index: usize, item: T) -> T{
+        let mut state = self.state.borrow_mut();
+        let old = std::mem::replace(&mut state.items[index], item);
+        state.changes.push(ListChange::Updated(index));
+        old
+    }
+
+    /// Change an item in place
+    pub fn update<F: FnOnce(&mut T)>(&self, index: usize, function: F){
+        let mut state = self.state.borrow_mut();
+        function(&mut state.items[index]);
+        state.changes.push(ListChange::Updated(index));
+    }
+
+    /// Replace all the items at once
+    pub fn replace_all(&self, items: Vec<T>){
+        let mut state = self.state.borrow_mut();
+        state.items = items;
+        state.changes.clear();
+        state.changes.push(ListChange::Reset);
+    }
+
+    pub fn clear(&self){
+        self.replace_all(Vec::new());
+    }
+
+    /// Look at an item
+    pub fn with<R, F: FnOnce(&T) -> R>(&self, index: usize, function: F) -> Option<R>{
+        self.state.borrow().items.get(index).map(function)
+    }
+
+    /// Take the changes made since the last time this was called, in the order they were made
+    pub fn take_changes(&self) -> Vec<ListChange>{
+        self.state.borrow_mut().changes.drain(..).collect()
+    }
+}
+
+/// # ListAdapter
+///
+/// Turns the items of a list into row components. `create_row` makes the row for an item. When an item changes,
+/// `update_row` gets the chance to change its row in place - if it returns false (the default), the row gets
+/// made again instead.
+pub trait ListAdapter<T>{
+    fn create_row(&self, item: &T, device: &wgpu::Device) -> Box<dyn EventGUIComponent>;
+    fn update_row(&self, _item: &T, _row: &mut dyn EventGUIComponent) -> bool{ false }
+}
+
+/// # ListView
+///
+/// A column of rows made from a `ListData` by a `ListAdapter`. Changes to the data show up before the next
+/// frame - inserted and removed items add and remove just their rows, and updated items update just theirs.
+///
+/// The rows are laid out like a vertical `StackPanel`, so they should report their size with `measure`. Every
+/// item gets a row, so for long lists, a `VirtualListView` is better.
+pub struct ListView<T>{
+    data: ListData<T>,
+    adapter: Box<dyn ListAdapter<T>>,
+    rows: StackPanel,
+}
+
+impl<T: 'static> ListView<T>{
+    /// Create a list centred on `pos`, with `spacing` pixels between the rows. The rows get made before the
+    /// first frame.
+    pub fn new(pos: [f32; 2], data: ListData<T>, adapter: Box<dyn ListAdapter<T>>, spacing: f32) -> Self{
+        Self{
+            data,
+            adapter,
+            rows: StackPanel::new(pos, Orientation::Vertical, spacing),
+        }
+    }
+
+    /// Get a handle to the data, to change the items
+    pub fn get_data(&self) -> ListData<T>{
+        self.data.clone()
+    }
+
+    /// Borrow the row for an item, for example to downcast it
+    pub fn borrow_row_mut(&mut self, index: usize) -> Option<&mut Box<dyn EventGUIComponent>>{
+        self.rows.borrow_child_mut(index)
+    }
+
+    /// Borrow the panel holding the rows, to change the padding and spacing
+    pub fn borrow_panel_mut(&mut self) -> &mut StackPanel{
+        &mut self.rows
+    }
+
+    pub fn enable(&mut self){
+        self.rows.enable();
+    }
+
+    pub fn disable(&mut self){
+        self.rows.disable();
+    }
+
+    // Bring the rows up to date with the data, returning true if anything changed
+    fn sync(&mut self, device: &wgpu::Device) -> bool{
+        let changes = self.data.take_changes();
+        if changes.is_empty(){
+            return false;
+        }
+        let state = self.data.state.borrow();
+        let items = &state.items;
+
+        // Anything before the last reset gets rebuilt by it anyway
+        let start = changes.iter().rposition(|change| *change == ListChange::Reset);
+        if start.is_some(){
+            self.rows.clear();
+            for item in items.iter(){
+                self.rows.push(self.adapter.create_row(item, device));
+            }
+        }
+
+        // Later changes can move items around, so the item for each change is only known once they've all
+        // been applied. Work out where each row ends up first, then make the rows that need it.
+        let mut stale = vec![false; self.rows.len()];
+        for change in changes[start.map_or(0, |start| start + 1)..].iter(){
+            match *change{
+                ListChange::Inserted(index) => {
+                    self.rows.insert(index, Box::new(Placeholder));
+                    stale.insert(index, true);
+                }
+                ListChange::Removed(index) => {
+                    self.rows.remove(index);
+                    stale.remove(index);
+                }
+                ListChange::Updated(index) => stale[index] = true,
+                ListChange::Reset => {}
+            }
+        }
+        for (index, item) in items.iter().enumerate(){
+            if !stale[index]{
+                continue;
+            }
+            let updated = match self.rows.borrow_child_mut(index){
+                Some(row) if !row.as_any().is::<Placeholder>() => self.adapter.update_row(item, row.as_mut()),
+                _ => false,
+            };
+            if !updated{
+                self.rows.replace(index, self.adapter.create_row(item, device));
+            }
+        }
+        true
+    }
+}
+
+// Holds the place of a row that's about to be made
+struct Placeholder;
+
+impl EventGUIComponent for Placeholder{
+    fn render<'a, 'b>(&'a self, _render_pass: &'b mut wgpu::RenderPass<'a>) where 'a: 'b {}
+    fn handle_event_callback(&mut self, _event: &winit::event::Event<()>, _window: &winit::window::Window){}
+    fn as_any(&self) -> &dyn Any{ self }
+    fn as_any_mut(&mut self) -> &mut dyn Any{ self }
+    fn get_text_id(&self) -> Option<usize>{ None }
+    fn is_enabled(&self) -> bool{ false }
+    fn get_pos(&self) -> [f32; 2]{ [0.0, 0.0] }
+}
+
+impl<T: 'static> EventGUIComponent for ListView<T>{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        self.rows.render(render_pass);
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        self.rows.render_text(brush);
+    }
+
+    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        self.rows.handle_event_callback(event, window);
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window, phase: EventPhase) -> EventResponse{
+        self.rows.handle_event(event, window, phase)
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.rows.is_enabled()
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.rows.get_pos()
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        self.rows.get_bounds()
+    }
+
+    fn update(&mut self, dt: f32){
+        self.rows.update(dt);
+    }
+
+    fn is_animating(&self) -> bool{
+        self.rows.is_animating()
+    }
+
+    fn rebuild(&mut self, device: &wgpu::Device) -> bool{
+        let synced = self.sync(device);
+        self.rows.rebuild(device) || synced
+    }
+
+    fn layout_text(&mut self, brush: &mut TextBrush){
+        self.rows.layout_text(brush);
+    }
+
+    fn measure(&mut self, available: [f32; 2]) -> Option<[f32; 2]>{
+        self.rows.measure(available)
+    }
+
+    fn arrange(&mut self, rect: Rect){
+        self.rows.arrange(rect);
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.rows.prepare(queue, screen_dim);
+    }
+}
@@ -0,0 +1,310 @@
+//! This module contains the `LoadingScreen` component, which shows how far along a background load is, and the
+//! `LoadingProgress` handle the loading thread reports that through.
+
+use std::{any::Any, sync::{Arc, Mutex, MutexGuard}};
+
+use wgpu_glyph::{HorizontalAlign, VerticalAlign};
+
+use crate::rendering::{Quad, Renderer, TextBrush};
+
+use super::{GUIComponent, Label, ProgressBar, Rect, Spinner, TextGUIComponent};
+
+// The widest the progress bar gets, in pixels
+const BAR_WIDTH: f32 = 480.0;
+const BAR_HEIGHT: f32 = 12.0;
+// How far the tip is up from the bottom of the screen
+const TIP_MARGIN: f32 = 48.0;
+
+struct ProgressState{
+    progress: f32,
+    message: String,
+    finished: bool,
+}
+
+/// # LoadingProgress
+///
+/// A handle for reporting how far along a load is from any thread, while a `LoadingScreen` shows it on the UI
+/// thread. Clones share the same progress, so one can be handed to each loading thread.
+///
+/// ```ignore
+/// let progress = LoadingProgress::new();
+/// let reporter = progress.clone();
+/// std::thread::spawn(move || {
+///     reporter.set_message("Loading textures");
+///     // ...
+///     reporter.set_progress(0.5);
+///     // ...
+///     reporter.finish();
+/// });
+/// renderer.push_layout(Layout::loading_screen(progress, &["Tip: press F to pay respects"], &renderer));
+/// ```
+#[derive(Clone)]
+pub struct LoadingProgress{
+    state: Arc<Mutex<ProgressState>>,
+}
+
+impl LoadingProgress{
+    /// Create a new handle, at no progress and with no message
+    pub fn new() -> Self{
+        Self{
+            state: Arc::new(Mutex::new(ProgressState{
+                progress: 0.0,
+                message: String::new(),
+                finished: false,
+            })),
+        }
+    }
+
+    // A thread that panicked while holding the lock can't have left the state half changed, so carry on with it
+    fn lock(&self) -> MutexGuard<'_, ProgressState>{
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Set how far along the load is, from 0 to 1
+    pub fn set_progress(&self, progress: f32){
+        self.lock().progress = progress.clamp(0.0, 1.0);
+    }
+
+    pub fn get_progress(&self) -> f32{
+        self.lock().progress
+    }
+
+    /// Set the message saying what's being loaded, like "Loading textures"
+    pub fn set_message<S: Into<String>>(&self, message: S){
+        self.lock().message = message.into();
+    }
+
+    pub fn get_message(&self) -> String{
+        self.lock().message.clone()
+    }
+
+    /// Mark the load as done, filling the progress. Layouts can be switched again once it's finished.
+    pub fn finish(&self){
+        let mut state = self.lock();
+        state.progress = 1.0;
+        state.finished = true;
+    }
+
+    pub fn is_finished(&self) -> bool{
+        self.lock().finished
+    }
+}
+
+impl Default for LoadingProgress{
+    fn default() -> Self{
+        Self::new()
+    }
+}
+
+/// # LoadingScreen
+///
+/// Fills the screen while something loads in the background, with a spinner, a progress bar and the loader's
+/// message in the middle, and a tip along the bottom that changes every few seconds. The progress comes from a
+/// `LoadingProgress`, which is read every frame.
+///
+/// It's normally used through `Layout::loading_screen`, which also stops the layouts from being switched with
+/// `LayoutCommands` until the load is finished.
+pub struct LoadingScreen{
+    rect: Rect,
+    progress: LoadingProgress,
+    tips: Vec<String>,
+    tip: usize,
+    tip_interval: f32,
+    tip_time: f32, // seconds the current tip has been shown for
+    finished: bool,
+    on_finished: Option<Box<dyn Fn()>>,
+
+    background: Quad,
+    bar: ProgressBar,
+    spinner: Spinner,
+    message: Label,
+    tip_label: Label,
+    enabled: bool,
+}
+
+impl LoadingScreen{
+    /// Create a loading screen filling `rect`, showing `progress` and going through `tips` every 5 seconds
+    pub fn new(rect: Rect, progress: LoadingProgress, tips: &[&str], renderer: &Renderer) -> Self{
+        let mut message = Label::new("", 18.0, [0.0, 0.0]);
+        message.align_horizontal(HorizontalAlign::Center);
+        message.align_vertical(VerticalAlign::Center);
+        let mut tip_label = Label::new("", 16.0, [0.0, 0.0]);
+        tip_label.align_horizontal(HorizontalAlign::Center);
+        tip_label.align_vertical(VerticalAlign::Center);
+
+        let mut screen = Self{
+            rect,
+            progress,
+            tips: tips.iter().map(|tip| tip.to_string()).collect(),
+            tip: 0,
+            tip_interval: 5.0,
+            tip_time: 0.0,
+            finished: false,
+            on_finished: None,
+
+            background: Quad::new(rect.pos, rect.size, [0.95, 0.95, 0.95, 1.0], &renderer.device),
+            bar: ProgressBar::new(Rect::new(rect.pos, [BAR_WIDTH, BAR_HEIGHT]), renderer),
+            spinner: Spinner::new(rect.pos, 16.0, renderer),
+            message,
+            tip_label,
+            enabled: true,
+        };
+        screen.set_rect(rect);
+        screen.read_progress();
+        screen.update_tip();
+        screen
+    }
+
+    /// Replace the tips, starting again from the first
+    pub fn set_tips(&mut self, tips: &[&str]){
+        self.tips = tips.iter().map(|tip| tip.to_string()).collect();
+        self.tip = 0;
+        self.tip_time = 0.0;
+        self.update_tip();
+    }
+
+    /// Set how many seconds each tip is shown for
+    pub fn set_tip_interval(&mut self, interval: f32){
+        self.tip_interval = interval.max(0.1);
+    }
+
+    /// The tip being shown, if there are any
+    pub fn get_tip(&self) -> Option<&str>{
+        self.tips.get(self.tip).map(|tip| tip.as_str())
+    }
+
+    /// Set the function to run on the UI thread once the load has finished, for example to pop the loading
+    /// screen's layout
+    pub fn set_on_finished(&mut self, callback: Option<Box<dyn Fn()>>){
+        self.on_finished = callback;
+    }
+
+    /// Get another handle to the progress being shown
+    pub fn get_progress(&self) -> LoadingProgress{
+        self.progress.clone()
+    }
+
+    pub fn is_finished(&self) -> bool{
+        self.finished
+    }
+
+    pub fn set_rect(&mut self, rect: Rect){
+        self.rect = rect;
+        self.background.set_pos(rect.pos);
+        self.background.set_size(rect.size);
+        self.bar.set_rect(Rect::new(rect.pos, [BAR_WIDTH.min(rect.size[0] * 0.6), BAR_HEIGHT]));
+        self.spinner.set_pos([rect.pos[0], rect.pos[1] - 48.0]);
+    }
+
+    pub fn get_rect(&self) -> Rect{
+        self.rect
+    }
+
+    pub fn set_background_color(&mut self, color: [f32; 4]){
+        self.background.set_color(color);
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+    }
+
+    fn read_progress(&mut self){
+        self.bar.set_progress(self.progress.get_progress());
+        self.message.set_text(self.progress.get_message());
+    }
+
+    fn update_tip(&mut self){
+        let tip = self.get_tip().unwrap_or("").to_string();
+        self.tip_label.set_text(tip);
+    }
+}
+
+impl GUIComponent for LoadingScreen{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.enabled{
+            self.background.render(render_pass);
+            self.bar.render(render_pass);
+            self.spinner.render(render_pass);
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if self.enabled{
+            TextGUIComponent::render_text(&self.message, brush);
+            TextGUIComponent::render_text(&self.tip_label, brush);
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.rect.pos
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(self.rect)
+    }
+
+    /// The loading screen takes up all the space it's given
+    fn measure(&mut self, available: [f32; 2]) -> Option<[f32; 2]>{
+        Some(available)
+    }
+
+    fn arrange(&mut self, rect: Rect){
+        if rect != self.rect{
+            self.set_rect(rect);
+        }
+    }
+
+    fn update(&mut self, dt: f32){
+        self.spinner.update(dt);
+        self.read_progress();
+        if self.tips.len() > 1{
+            self.tip_time += dt;
+            if self.tip_time >= self.tip_interval{
+                self.tip_time = 0.0;
+                self.tip = (self.tip + 1) % self.tips.len();
+                self.update_tip();
+            }
+        }
+        if !self.finished && self.progress.is_finished(){
+            self.finished = true;
+            if let Some(callback) = &self.on_finished{
+                callback();
+            }
+        }
+    }
+
+    /// Always true while it's enabled, so frames keep coming to pick up progress from the loading thread
+    fn is_animating(&self) -> bool{
+        self.enabled
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.background.prepare(queue, screen_dim);
+        self.bar.prepare(queue, screen_dim);
+        self.spinner.prepare(queue, screen_dim);
+        self.message.set_pos([self.rect.pos[0], self.rect.pos[1] + 32.0], screen_dim);
+        self.tip_label.set_pos([self.rect.pos[0], self.rect.pos[1] + self.rect.size[1] / 2.0 - TIP_MARGIN], screen_dim);
+    }
+}
@@ -0,0 +1,192 @@
+//! This module contains the `Marquee` component, which adds rectangle selection to a container.
+//! Dragging on empty space draws a selection rectangle, and every item it touches gets selected.
+//! Holding shift (or ctrl, command on macOS) adds to the current selection instead of replacing it.
+
+use std::any::Any;
+
+use winit::event::Event;
+
+use crate::{input::{InputState, MouseButton, MouseEvent, UiEvent}, rendering::{Quad, Renderer}};
+
+use super::{EventGUIComponent, Rect};
+
+/// # Marquee
+///
+/// Rectangle selection over a set of items. The items are registered with an ID (usually the ID of the
+/// component in the layout) and their bounds. Only drags starting inside the marquee bounds, and not on
+/// top of an item, draw a selection.
+///
+/// The callback gets the selected IDs every time the selection changes.
+pub struct Marquee{
+    bounds: Rect,
+    items: Vec<(usize, Rect)>,
+    selected: Vec<usize>,
+    kept: Vec<usize>, // the selection from before an additive drag
+    callback: Option<Box<dyn Fn(&[usize])>>,
+
+    fill: Quad,
+    start: Option<[f32; 2]>,
+    cursor_pos: [f32; 2],
+    input: InputState,
+    enabled: bool,
+}
+
+impl Marquee{
+    /// Create a new marquee, covering the bounds of a container
+    pub fn new(bounds: Rect, callback: Option<Box<dyn Fn(&[usize])>>, renderer: &Renderer) -> Self{
+        Self{
+            bounds,
+            items: Vec::new(),
+            selected: Vec::new(),
+            kept: Vec::new(),
+            callback,
+
+            fill: Quad::new(bounds.pos, [0.0, 0.0], [0.2, 0.4, 0.9, 0.3], &renderer.device),
+            start: None,
+            cursor_pos: [0.0, 0.0],
+            input: InputState::new(),
+            enabled: true,
+        }
+    }
+
+    pub fn set_callback(&mut self, callback: Option<Box<dyn Fn(&[usize])>>){
+        self.callback = callback;
+    }
+
+    pub fn set_bounds(&mut self, bounds: Rect){
+        self.bounds = bounds;
+    }
+
+    /// Set the colour of the selection rectangle
+    pub fn set_color(&mut self, color: [f32; 4]){
+        self.fill.set_color(color);
+    }
+
+    /// Add an item that can be selected, or update its bounds if it already exists
+    pub fn set_item(&mut self, id: usize, bounds: Rect){
+        match self.items.iter_mut().find(|(item_id, _)| *item_id == id){
+            Some(item) => item.1 = bounds,
+            None => self.items.push((id, bounds)),
+        }
+    }
+
+    pub fn remove_item(&mut self, id: usize){
+        self.items.retain(|(item_id, _)| *item_id != id);
+        if self.selected.contains(&id){
+            self.selected.retain(|selected| *selected != id);
+            self.notify();
+        }
+    }
+
+    pub fn clear_items(&mut self){
+        self.items.clear();
+        self.set_selection(Vec::new());
+    }
+
+    pub fn get_selection(&self) -> &[usize]{
+        &self.selected
+    }
+
+    /// Replace the selection, running the callback if it changed
+    pub fn set_selection(&mut self, selected: Vec<usize>){
+        if selected != self.selected{
+            self.selected = selected;
+            self.notify();
+        }
+    }
+
+    /// True while a selection rectangle is being dragged
+    pub fn is_selecting(&self) -> bool{
+        self.start.is_some()
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+        self.start = None;
+    }
+
+    fn notify(&self){
+        if let Some(callback) = &self.callback{
+            callback(&self.selected);
+        }
+    }
+
+    // Select everything touching the rectangle from the start of the drag to the cursor
+    fn update_selection(&mut self, start: [f32; 2]){
+        let rect = Rect::from_corners(start, self.cursor_pos);
+        self.fill.set_pos(rect.pos);
+        self.fill.set_size(rect.size);
+
+        let mut selected = self.kept.clone();
+        for (id, bounds) in self.items.iter(){
+            if bounds.intersects(&rect) && !selected.contains(id){
+                selected.push(*id);
+            }
+        }
+        self.set_selection(selected);
+    }
+}
+
+impl EventGUIComponent for Marquee{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.enabled && self.start.is_some(){
+            self.fill.render(render_pass);
+        }
+    }
+
+    fn handle_event_callback(&mut self, event: &Event<()>, window: &winit::window::Window){
+        if !self.enabled{
+            return;
+        }
+
+        match self.input.handle_event(event, window){
+            Some(UiEvent::CursorMoved{ pos, .. }) => {
+                self.cursor_pos = pos;
+                if let Some(start) = self.start{
+                    self.update_selection(start);
+                }
+            }
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed: true, modifiers, .. })) => {
+                let on_item = self.items.iter().any(|(_, bounds)| bounds.contains(self.cursor_pos));
+                if self.bounds.contains(self.cursor_pos) && !on_item{
+                    self.kept = if modifiers.shift || modifiers.command() { self.selected.clone() } else { Vec::new() };
+                    self.start = Some(self.cursor_pos);
+                    self.update_selection(self.cursor_pos);
+                }
+            }
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed: false, .. })) => {
+                self.start = None;
+            }
+            _ => {}
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.bounds.pos
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.fill.prepare(queue, screen_dim);
+    }
+}
@@ -0,0 +1,935 @@
+//! This module contains the `MenuBar` component, a bar along the top of the window with drop-down menus, and
+//! `Menu`, the list of items, separators and submenus that goes in each drop-down. `ContextMenu` shows a `Menu`
+//! at the cursor instead, see `Layout::set_context_menu`.
+
+use std::any::Any;
+
+use wgpu_glyph::{HorizontalAlign, VerticalAlign};
+
+use crate::{input::{EventPhase, EventResponse, InputCategory, InputState, Key, KeyEvent, MouseButton, MouseEvent, UiEvent}, rendering::{Quad, Renderer, TextBrush}};
+
+use super::{EventGUIComponent, Label, Rect, TextGUIComponent};
+
+// The space either side of text in the bar and the drop-downs, in pixels
+const PADDING: f32 = 10.0;
+// How tall a separator is, the line goes across the middle
+const SEPARATOR_HEIGHT: f32 = 9.0;
+// The room left on the right of an item for the arrow marking a submenu
+const ARROW_WIDTH: f32 = 20.0;
+
+enum MenuEntry{
+    Item{ label: String, callback: Option<Box<dyn Fn()>> },
+    Separator,
+    Submenu{ label: String, menu: Menu },
+}
+
+/// # Menu
+///
+/// The items in one of a `MenuBar`'s drop-downs. Built up in order, like:
+///
+/// ```ignore
+/// let file = Menu::new()
+///     .with_item("Open", Some(Box::new(|| open())))
+///     .with_submenu("Recent", Menu::new().with_item("notes.txt", None))
+///     .with_separator()
+///     .with_item("Quit", Some(Box::new(|| quit())));
+/// ```
+#[derive(Default)]
+pub struct Menu{
+    entries: Vec<MenuEntry>,
+}
+
+impl Menu{
+    pub fn new() -> Self{
+        Self::default()
+    }
+
+    /// Add an item that runs the callback when picked
+    pub fn with_item(mut self, label: &str, callback: Option<Box<dyn Fn()>>) -> Self{
+        self.entries.push(MenuEntry::Item{ label: label.to_string(), callback });
+        self
+    }
+
+    /// Add a line between items
+    pub fn with_separator(mut self) -> Self{
+        self.entries.push(MenuEntry::Separator);
+        self
+    }
+
+    /// Add an item that opens another menu to the side when hovered
+    pub fn with_submenu(mut self, label: &str, menu: Menu) -> Self{
+        self.entries.push(MenuEntry::Submenu{ label: label.to_string(), menu });
+        self
+    }
+
+    pub fn len(&self) -> usize{
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.entries.is_empty()
+    }
+}
+
+enum RowKind{
+    Item(Option<Box<dyn Fn()>>),
+    Separator,
+    Submenu(Box<Panel>),
+}
+
+// A row of an open drop-down, the quad is the highlight (or the line, for separators)
+struct Row{
+    kind: RowKind,
+    rect: Rect,
+    quad: Quad,
+    label: Option<Label>,
+    arrow: Option<Label>,
+}
+
+// A drop-down, built from a `Menu`
+struct Panel{
+    rows: Vec<Row>,
+    rect: Rect,
+    border: Quad,
+    background: Quad,
+}
+
+impl Panel{
+    fn build(menu: Menu, text_size: f32, colors: &MenuColors, renderer: &mut Renderer) -> Self{
+        let has_submenu = menu.entries.iter().any(|entry| matches!(entry, MenuEntry::Submenu{ .. }));
+        let mut width: f32 = 0.0;
+        let mut rows = Vec::with_capacity(menu.entries.len());
+        for entry in menu.entries{
+            let (kind, text) = match entry{
+                MenuEntry::Item{ label, callback } => (RowKind::Item(callback), Some(label)),
+                MenuEntry::Separator => (RowKind::Separator, None),
+                MenuEntry::Submenu{ label, menu } => (RowKind::Submenu(Box::new(Panel::build(menu, text_size, colors, renderer))), Some(label)),
+            };
+            let label = text.map(|text| {
+                let mut label = Label::new(text.as_str(), text_size, [0.0, 0.0]);
+                label.align_vertical(VerticalAlign::Center);
+                width = width.max(label.measure(renderer)[0]);
+                label
+            });
+            let arrow = match kind{
+                RowKind::Submenu(_) => {
+                    let mut arrow = Label::new(">", text_size, [0.0, 0.0]);
+                    arrow.align_vertical(VerticalAlign::Center);
+                    arrow.align_horizontal(HorizontalAlign::Right);
+                    Some(arrow)
+                }
+                _ => None,
+            };
+            let color = if let RowKind::Separator = kind { colors.separator } else { [0.0; 4] };
+            rows.push(Row{
+                kind,
+                rect: Rect::new([0.0, 0.0], [0.0, 0.0]),
+                quad: Quad::new([0.0, 0.0], [0.0, 0.0], color, &renderer.device),
+                label,
+                arrow,
+            });
+        }
+
+        let height = rows.iter().map(|row| row_height(&row.kind, text_size)).sum();
+        let width = width + PADDING * 2.0 + if has_submenu { ARROW_WIDTH } else { 0.0 };
+        Self{
+            rows,
+            rect: Rect::new([0.0, 0.0], [width, height]),
+            border: Quad::new([0.0, 0.0], [0.0, 0.0], colors.border, &renderer.device),
+            background: Quad::new([0.0, 0.0], [0.0, 0.0], colors.background, &renderer.device),
+        }
+    }
+
+    // Move the drop-down so its top left corner is at `corner`, and its submenus beside their rows. Submenus that
+    // would go past `right` open on the left instead.
+    fn place(&mut self, corner: [f32; 2], text_size: f32, right: f32){
+        let size = self.rect.size;
+        self.rect.pos = [corner[0] + size[0] / 2.0, corner[1] + size[1] / 2.0];
+        self.background.set_pos(self.rect.pos);
+        self.background.set_size(size);
+        self.border.set_pos(self.rect.pos);
+        self.border.set_size([size[0] + 2.0, size[1] + 2.0]);
+
+        let mut top = corner[1];
+        for row in self.rows.iter_mut(){
+            let height = row_height(&row.kind, text_size);
+            row.rect = Rect::new([self.rect.pos[0], top + height / 2.0], [size[0], height]);
+            row.quad.set_pos(row.rect.pos);
+            if let RowKind::Separator = row.kind{
+                row.quad.set_size([size[0] - PADDING, 1.0]);
+            }else{
+                row.quad.set_size(row.rect.size);
+            }
+            if let RowKind::Submenu(panel) = &mut row.kind{
+                let left = submenu_left(corner[0], size[0], panel.rect.size[0], right);
+                panel.place([left, top], text_size, right);
+            }
+            top += height;
+        }
+    }
+
+    fn row_at(&self, pos: [f32; 2]) -> Option<usize>{
+        if !self.rect.contains(pos){
+            return None;
+        }
+        self.rows.iter().position(|row| row.rect.contains(pos))
+    }
+
+    fn submenu(&self, index: usize) -> Option<&Panel>{
+        match self.rows.get(index).map(|row| &row.kind){
+            Some(RowKind::Submenu(panel)) => Some(panel),
+            _ => None,
+        }
+    }
+
+    fn submenu_mut(&mut self, index: usize) -> Option<&mut Panel>{
+        match self.rows.get_mut(index).map(|row| &mut row.kind){
+            Some(RowKind::Submenu(panel)) => Some(panel),
+            _ => None,
+        }
+    }
+
+    // Follow a row in each submenu down from this drop-down
+    fn descend(&self, rows: &[usize]) -> Option<&Panel>{
+        rows.iter().try_fold(self, |panel, row| panel.submenu(*row))
+    }
+
+    fn descend_mut(&mut self, rows: &[usize]) -> Option<&mut Panel>{
+        rows.iter().try_fold(self, |panel, row| panel.submenu_mut(*row))
+    }
+
+    // Highlight the hovered row and the row with an open submenu, and move the text to the rows
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32), lit: [Option<usize>; 2], highlight: [f32; 4]){
+        self.border.prepare(queue, screen_dim);
+        self.background.prepare(queue, screen_dim);
+        for (i, row) in self.rows.iter_mut().enumerate(){
+            if !matches!(row.kind, RowKind::Separator){
+                row.quad.set_color(if lit.contains(&Some(i)) { highlight } else { [0.0; 4] });
+            }
+            row.quad.prepare(queue, screen_dim);
+
+            let left = row.rect.pos[0] - row.rect.size[0] / 2.0;
+            if let Some(label) = &mut row.label{
+                label.set_pos([left + PADDING, row.rect.pos[1]], screen_dim);
+            }
+            if let Some(arrow) = &mut row.arrow{
+                arrow.set_pos([left + row.rect.size[0] - PADDING, row.rect.pos[1]], screen_dim);
+            }
+        }
+    }
+
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        self.border.render(render_pass);
+        self.background.render(render_pass);
+        for row in self.rows.iter(){
+            row.quad.render(render_pass);
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        for row in self.rows.iter(){
+            for label in row.label.iter().chain(row.arrow.iter()){
+                TextGUIComponent::render_text(label, brush);
+            }
+        }
+    }
+
+    fn recolor(&mut self, colors: &MenuColors){
+        self.background.set_color(colors.background);
+        self.border.set_color(colors.border);
+        for row in self.rows.iter_mut(){
+            if let RowKind::Submenu(submenu) = &mut row.kind{
+                submenu.recolor(colors);
+            }
+        }
+    }
+}
+
+// Where the left edge of a submenu `submenu_width` wide goes, beside a drop-down with its left edge at `left`. It
+// opens on the right, unless that would go past `right`.
+fn submenu_left(left: f32, width: f32, submenu_width: f32, right: f32) -> f32{
+    if left + width + submenu_width > right { left - submenu_width } else { left + width }
+}
+
+// Hovering `row` in the drop-down `depth` levels down closes the submenus deeper than it, and opens its own if it
+// has one
+fn hover_row(open: &mut Vec<usize>, depth: usize, row: usize, has_submenu: bool){
+    open.truncate(depth + 1);
+    if has_submenu{
+        open.push(row);
+    }
+}
+
+fn row_height(kind: &RowKind, text_size: f32) -> f32{
+    match kind{
+        RowKind::Separator => SEPARATOR_HEIGHT,
+        _ => text_size + PADDING,
+    }
+}
+
+struct MenuColors{
+    bar: [f32; 4],
+    background: [f32; 4],
+    border: [f32; 4],
+    highlight: [f32; 4],
+    separator: [f32; 4],
+}
+
+impl Default for MenuColors{
+    fn default() -> Self{
+        Self{
+            bar: [0.92, 0.92, 0.92, 1.0],
+            background: [1.0, 1.0, 1.0, 1.0],
+            border: [0.6, 0.6, 0.6, 1.0],
+            highlight: [0.75, 0.85, 1.0, 1.0],
+            separator: [0.8, 0.8, 0.8, 1.0],
+        }
+    }
+}
+
+// A menu in the bar, and its drop-down
+struct BarMenu{
+    title: Label,
+    width: f32,
+    rect: Rect,
+    quad: Quad, // the highlight behind the title
+    panel: Panel,
+}
+
+/// # MenuBar
+///
+/// A bar across the top of the window, with a title for each of its menus. Clicking a title opens its drop-down,
+/// and while one is open, hovering the other titles switches to them. Hovering an item with a submenu opens it to
+/// the side, and picking an item closes everything and runs its callback. Clicking anywhere outside the open
+/// menus, or pressing Escape enough times, closes them without picking anything.
+///
+/// The drop-downs have to draw over the rest of the GUI, so menu bars belong in the overlay layer
+/// (`Layout::add_overlay_component`). While a menu is open, the bar takes all mouse input, so clicking outside
+/// to close it doesn't also click whatever was under the cursor.
+pub struct MenuBar{
+    height: f32,
+    text_size: f32,
+    colors: MenuColors,
+    menus: Vec<BarMenu>,
+    screen_dim: (u32, u32),
+
+    bar: Quad,
+    // The open menu, then the row of each open submenu, deepest last
+    open: Vec<usize>,
+    hovered: Option<(usize, usize)>, // depth and row
+    pressed: Option<(usize, usize)>,
+
+    input: InputState,
+    enabled: bool,
+}
+
+impl MenuBar{
+    /// Create an empty menu bar, `height` pixels tall
+    pub fn new(height: f32, text_size: f32, renderer: &Renderer) -> Self{
+        let colors = MenuColors::default();
+        Self{
+            height,
+            text_size,
+            bar: Quad::new([0.0, 0.0], [0.0, height], colors.bar, &renderer.device),
+            colors,
+            menus: Vec::new(),
+            screen_dim: (0, 0),
+
+            open: Vec::new(),
+            hovered: None,
+            pressed: None,
+
+            input: InputState::new(),
+            enabled: true,
+        }
+    }
+
+    /// Add a menu to the right of the others
+    pub fn add_menu(&mut self, title: &str, menu: Menu, renderer: &mut Renderer){
+        let mut label = Label::new(title, self.text_size, [0.0, 0.0]);
+        label.align_vertical(VerticalAlign::Center);
+        label.align_horizontal(HorizontalAlign::Center);
+        let width = label.measure(renderer)[0] + PADDING * 2.0;
+        self.menus.push(BarMenu{
+            title: label,
+            width,
+            rect: Rect::new([0.0, 0.0], [width, self.height]),
+            quad: Quad::new([0.0, 0.0], [width, self.height], [0.0; 4], &renderer.device),
+            panel: Panel::build(menu, self.text_size, &self.colors, renderer),
+        });
+        self.arrange();
+    }
+
+    /// Set the colours of the bar, the drop-downs and their borders, and the highlight behind hovered items.
+    /// Menus added after this use the new colours too.
+    pub fn set_colors(&mut self, bar: [f32; 4], background: [f32; 4], border: [f32; 4], highlight: [f32; 4]){
+        self.bar.set_color(bar);
+        self.colors.bar = bar;
+        self.colors.background = background;
+        self.colors.border = border;
+        self.colors.highlight = highlight;
+        for menu in self.menus.iter_mut(){
+            menu.panel.recolor(&self.colors);
+        }
+    }
+
+    pub fn get_height(&self) -> f32{
+        self.height
+    }
+
+    /// Open the menu at `index` in the bar, closing any other
+    pub fn open_menu(&mut self, index: usize){
+        if index < self.menus.len(){
+            self.open = vec![index];
+            self.hovered = None;
+        }
+    }
+
+    /// Close every open menu without picking anything
+    pub fn close(&mut self){
+        self.open.clear();
+        self.hovered = None;
+        self.pressed = None;
+    }
+
+    /// The menu open in the bar, if there is one
+    pub fn get_open_menu(&self) -> Option<usize>{
+        self.open.first().copied()
+    }
+
+    /// How many menus are open, the one from the bar and its open submenus
+    pub fn get_open_depth(&self) -> usize{
+        self.open.len()
+    }
+
+    pub fn is_open(&self) -> bool{
+        !self.open.is_empty()
+    }
+
+    /// Pick the item at `path` like a click on it would: the menu in the bar, then the row in each menu down to
+    /// the item. Picking a submenu opens it, picking an item closes everything and runs its callback. Returns
+    /// whether there was anything at the path to pick.
+    pub fn activate(&mut self, path: &[usize]) -> bool{
+        if path.len() == 1{
+            self.open_menu(path[0]);
+            return path[0] < self.menus.len();
+        }
+        let is_item = match self.panel_at_path(path).and_then(|(panel, row)| panel.rows.get(row)).map(|row| &row.kind){
+            Some(RowKind::Item(_)) => true,
+            Some(RowKind::Submenu(_)) => false,
+            _ => return false,
+        };
+        if !is_item{
+            self.open = path.to_vec();
+            return true;
+        }
+
+        self.close();
+        if let Some((panel, row)) = self.panel_at_path(path){
+            if let RowKind::Item(Some(callback)) = &panel.rows[row].kind{
+                callback();
+            }
+        }
+        true
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+        self.close();
+    }
+
+    // The drop-down holding the last row of `path`, and that row
+    fn panel_at_path(&self, path: &[usize]) -> Option<(&Panel, usize)>{
+        let (&first, rows) = path.split_first()?;
+        let (&last, rows) = rows.split_last()?;
+        Some((self.menus.get(first)?.panel.descend(rows)?, last))
+    }
+
+    // The open drop-down `depth` levels down, 0 being the one opened from the bar
+    fn open_panel(&self, depth: usize) -> Option<&Panel>{
+        self.menus.get(*self.open.first()?)?.panel.descend(self.open.get(1..=depth)?)
+    }
+
+    fn bar_rect(&self) -> Rect{
+        let width = self.screen_dim.0 as f32;
+        Rect::new([0.0, (self.height - self.screen_dim.1 as f32) / 2.0], [width, self.height])
+    }
+
+    fn title_at(&self, pos: [f32; 2]) -> Option<usize>{
+        self.menus.iter().position(|menu| menu.rect.contains(pos))
+    }
+
+    // The deepest open drop-down row under `pos`, as its depth and row
+    fn row_at(&self, pos: [f32; 2]) -> Option<(usize, usize)>{
+        (0..self.open.len()).rev().find_map(|depth| {
+            self.open_panel(depth).and_then(|panel| panel.row_at(pos)).map(|row| (depth, row))
+        })
+    }
+
+    fn over_open_panel(&self, pos: [f32; 2]) -> bool{
+        (0..self.open.len()).any(|depth| matches!(self.open_panel(depth), Some(panel) if panel.rect.contains(pos)))
+    }
+
+    // Lay out the titles along the bar and every drop-down under its title
+    fn arrange(&mut self){
+        let bar = self.bar_rect();
+        self.bar.set_pos(bar.pos);
+        self.bar.set_size(bar.size);
+
+        let top = bar.pos[1] + self.height / 2.0;
+        let right = self.screen_dim.0 as f32 / 2.0;
+        let mut left = -right;
+        for menu in self.menus.iter_mut(){
+            menu.rect = Rect::new([left + menu.width / 2.0, bar.pos[1]], [menu.width, self.height]);
+            menu.quad.set_pos(menu.rect.pos);
+            menu.panel.place([left, top], self.text_size, right);
+            left += menu.width;
+        }
+    }
+
+    fn hover(&mut self, pos: [f32; 2]){
+        if !self.is_open(){
+            return;
+        }
+        // Sliding along the bar switches between menus
+        if let Some(title) = self.title_at(pos){
+            if self.open[0] != title{
+                self.open_menu(title);
+            }
+            self.hovered = None;
+            return;
+        }
+
+        self.hovered = self.row_at(pos);
+        if let Some((depth, row)) = self.hovered{
+            let has_submenu = self.open_panel(depth).and_then(|panel| panel.submenu(row)).is_some();
+            hover_row(&mut self.open, depth, row, has_submenu);
+        }
+    }
+
+    fn click(&mut self, pos: [f32; 2], pressed: bool){
+        if pressed{
+            self.pressed = None;
+            if let Some(title) = self.title_at(pos){
+                if self.get_open_menu() == Some(title) { self.close() } else { self.open_menu(title) }
+            }else if let Some(row) = self.row_at(pos){
+                self.pressed = Some(row);
+            }else if !self.over_open_panel(pos){
+                // Clicking outside the menus closes them
+                self.close();
+            }
+            return;
+        }
+
+        let pressed = self.pressed.take();
+        if let (Some((depth, row)), Some(released)) = (pressed, self.row_at(pos)){
+            if released == (depth, row){
+                let mut path = self.open[..=depth].to_vec();
+                path.push(row);
+                self.activate(&path);
+            }
+        }
+    }
+}
+
+impl EventGUIComponent for MenuBar{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if !self.enabled{
+            return;
+        }
+        self.bar.render(render_pass);
+        if let Some(&open) = self.open.first(){
+            self.menus[open].quad.render(render_pass);
+        }
+        for depth in 0..self.open.len(){
+            if let Some(panel) = self.open_panel(depth){
+                panel.render(render_pass);
+            }
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if !self.enabled{
+            return;
+        }
+        for menu in self.menus.iter(){
+            TextGUIComponent::render_text(&menu.title, brush);
+        }
+        for depth in 0..self.open.len(){
+            if let Some(panel) = self.open_panel(depth){
+                panel.render_text(brush);
+            }
+        }
+    }
+
+    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        if !self.enabled{
+            return;
+        }
+
+        match self.input.handle_event(event, window){
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed, pos, .. })) => self.click(pos, pressed),
+            Some(UiEvent::MouseInput(MouseEvent{ pressed: true, pos, .. })) if self.is_open() && !self.over_open_panel(pos) => self.close(),
+            Some(UiEvent::CursorMoved{ pos, .. }) => self.hover(pos),
+            Some(UiEvent::CursorLeft) => self.hovered = None,
+            Some(UiEvent::Key(KeyEvent{ key: Key::Escape, pressed: true, .. })) => {
+                // Escape closes the deepest menu first
+                self.open.pop();
+                self.hovered = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Mouse input over the bar is ours, and while a menu is open, all of it is, so a click outside the menus
+    /// only closes them
+    fn handle_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window, phase: EventPhase) -> EventResponse{
+        if phase != EventPhase::Bubble{
+            return EventResponse::Continue;
+        }
+        let was_open = self.is_open();
+        let over_bar = self.bar_rect().contains(self.input.get_cursor_pos());
+        self.handle_event_callback(event, window);
+        let mouse = InputCategory::of(event) == Some(InputCategory::Mouse);
+        if self.enabled && mouse && (was_open || over_bar) { EventResponse::Consumed } else { EventResponse::Continue }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.bar_rect().pos
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(self.bar_rect())
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        if screen_dim != self.screen_dim{
+            self.screen_dim = screen_dim;
+            self.arrange();
+        }
+
+        self.bar.prepare(queue, screen_dim);
+        let open = self.get_open_menu();
+        for (i, menu) in self.menus.iter_mut().enumerate(){
+            menu.title.set_pos(menu.rect.pos, screen_dim);
+            if open == Some(i){
+                menu.quad.set_color(self.colors.highlight);
+                menu.quad.prepare(queue, screen_dim);
+            }
+        }
+
+        let highlight = self.colors.highlight;
+        if let Some((&first, rows)) = self.open.split_first(){
+            let panel = &mut self.menus[first].panel;
+            for depth in 0..=rows.len(){
+                // The hovered row and the rows with open submenus stay highlighted
+                let hovered = self.hovered.filter(|hovered| hovered.0 == depth).map(|hovered| hovered.1);
+                if let Some(panel) = panel.descend_mut(&rows[..depth]){
+                    panel.prepare(queue, screen_dim, [hovered, rows.get(depth).copied()], highlight);
+                }
+            }
+        }
+    }
+}
+
+/// # ContextMenu
+///
+/// A `Menu` that pops up at the cursor, usually on right-click. Register one for a component, or for the whole
+/// layout, with `Layout::set_context_menu`, and the layout opens it when that component (or anywhere, for the
+/// layout's menu) gets right-clicked. It can also be opened from code with `Layout::open_context_menu`.
+///
+/// Submenus, separators and picking items work like the drop-downs of a `MenuBar`. While it's open the menu gets
+/// all input first: clicking outside it or pressing Escape closes it without picking anything, and the click
+/// doesn't reach what was under the cursor. A right-click outside closes it and opens whichever menu belongs there.
+/// The menu stays on screen, opening up or to the left of the cursor near the edges.
+pub struct ContextMenu{
+    panel: Panel,
+    text_size: f32,
+    colors: MenuColors,
+    corner: [f32; 2], // where it was opened, before keeping it on screen
+    screen_dim: (u32, u32),
+
+    // The row of each open submenu, deepest last
+    open: Vec<usize>,
+    hovered: Option<(usize, usize)>, // depth and row
+    pressed: Option<(usize, usize)>,
+    visible: bool,
+
+    input: InputState,
+}
+
+impl ContextMenu{
+    /// Build a closed context menu from the items in `menu`
+    pub fn new(menu: Menu, text_size: f32, renderer: &mut Renderer) -> Self{
+        let colors = MenuColors::default();
+        Self{
+            panel: Panel::build(menu, text_size, &colors, renderer),
+            text_size,
+            colors,
+            corner: [0.0, 0.0],
+            screen_dim: (0, 0),
+
+            open: Vec::new(),
+            hovered: None,
+            pressed: None,
+            visible: false,
+
+            input: InputState::new(),
+        }
+    }
+
+    /// Set the colours of the menu and its border, and the highlight behind hovered items
+    pub fn set_colors(&mut self, background: [f32; 4], border: [f32; 4], highlight: [f32; 4]){
+        self.colors.background = background;
+        self.colors.border = border;
+        self.colors.highlight = highlight;
+        self.panel.recolor(&self.colors);
+    }
+
+    /// Open the menu with its top left corner at `pos`, or as close as it can get while staying on screen
+    pub fn open_at(&mut self, pos: [f32; 2]){
+        self.corner = pos;
+        self.visible = true;
+        self.open.clear();
+        self.hovered = None;
+        self.pressed = None;
+        self.place();
+    }
+
+    /// Close the menu without picking anything
+    pub fn close(&mut self){
+        self.visible = false;
+        self.open.clear();
+        self.hovered = None;
+        self.pressed = None;
+    }
+
+    pub fn is_open(&self) -> bool{
+        self.visible
+    }
+
+    /// The space the menu takes up while it's open, not counting its submenus
+    pub fn get_bounds(&self) -> Option<Rect>{
+        Some(self.panel.rect).filter(|_| self.visible)
+    }
+
+    /// Pick the item at `path` like a click on it would: the row in each menu down to the item. Picking a submenu
+    /// opens it, picking an item closes the menu and runs its callback. Returns whether there was anything at the
+    /// path to pick.
+    pub fn activate(&mut self, path: &[usize]) -> bool{
+        let (&last, rows) = match path.split_last(){
+            Some(split) => split,
+            None => return false,
+        };
+        let is_item = match self.panel.descend(rows).and_then(|panel| panel.rows.get(last)).map(|row| &row.kind){
+            Some(RowKind::Item(_)) => true,
+            Some(RowKind::Submenu(_)) => false,
+            _ => return false,
+        };
+        if !is_item{
+            self.open = path.to_vec();
+            return true;
+        }
+
+        self.close();
+        if let Some(RowKind::Item(Some(callback))) = self.panel.descend(rows).map(|panel| &panel.rows[last].kind){
+            callback();
+        }
+        true
+    }
+
+    // Keep the menu on screen, once there's a screen to keep it on
+    fn place(&mut self){
+        let size = self.panel.rect.size;
+        let mut corner = self.corner;
+        let mut right = f32::INFINITY;
+        if self.screen_dim != (0, 0){
+            let half = [self.screen_dim.0 as f32 / 2.0, self.screen_dim.1 as f32 / 2.0];
+            corner = [corner[0].min(half[0] - size[0]).max(-half[0]), corner[1].min(half[1] - size[1]).max(-half[1])];
+            right = half[0];
+        }
+        self.panel.place(corner, self.text_size, right);
+    }
+
+    // The deepest open menu row under `pos`, as its depth and row
+    fn row_at(&self, pos: [f32; 2]) -> Option<(usize, usize)>{
+        (0..=self.open.len()).rev().find_map(|depth| {
+            self.panel.descend(&self.open[..depth]).and_then(|panel| panel.row_at(pos)).map(|row| (depth, row))
+        })
+    }
+
+    fn over_menu(&self, pos: [f32; 2]) -> bool{
+        (0..=self.open.len()).any(|depth| matches!(self.panel.descend(&self.open[..depth]), Some(panel) if panel.rect.contains(pos)))
+    }
+
+    fn hover(&mut self, pos: [f32; 2]){
+        self.hovered = self.row_at(pos);
+        if let Some((depth, row)) = self.hovered{
+            // Hovering a row closes submenus deeper than it, and opens its own
+            self.open.truncate(depth);
+            if self.panel.descend(&self.open).and_then(|panel| panel.submenu(row)).is_some(){
+                self.open.push(row);
+            }
+        }
+    }
+
+    fn click(&mut self, pos: [f32; 2], pressed: bool){
+        if pressed{
+            self.pressed = self.row_at(pos);
+            if self.pressed.is_none() && !self.over_menu(pos){
+                self.close();
+            }
+            return;
+        }
+
+        if let (Some((depth, row)), Some(released)) = (self.pressed.take(), self.row_at(pos)){
+            if released == (depth, row){
+                let mut path = self.open[..depth].to_vec();
+                path.push(row);
+                self.activate(&path);
+            }
+        }
+    }
+
+    // Take an event while the menu is open, returning true if nothing else should get it
+    pub(crate) fn handle_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window) -> bool{
+        if !self.visible{
+            return false;
+        }
+
+        match self.input.handle_event(event, window){
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed, pos, .. })) => self.click(pos, pressed),
+            Some(UiEvent::MouseInput(MouseEvent{ button, pressed: true, pos, .. })) if !self.over_menu(pos) => {
+                self.close();
+                // Let a right-click open the menu that belongs where it landed
+                return button != MouseButton::Right;
+            }
+            Some(UiEvent::CursorMoved{ pos, .. }) => self.hover(pos),
+            Some(UiEvent::Key(KeyEvent{ key: Key::Escape, pressed: true, .. })) => {
+                // Escape closes the deepest submenu first
+                if self.open.pop().is_none(){
+                    self.close();
+                }
+                self.hovered = None;
+            }
+            _ => {}
+        }
+        InputCategory::of(event).is_some()
+    }
+
+    pub(crate) fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        if screen_dim != self.screen_dim{
+            self.screen_dim = screen_dim;
+            self.place();
+        }
+        if !self.visible{
+            return;
+        }
+
+        let highlight = self.colors.highlight;
+        for depth in 0..=self.open.len(){
+            let hovered = self.hovered.filter(|hovered| hovered.0 == depth).map(|hovered| hovered.1);
+            let active = self.open.get(depth).copied();
+            if let Some(panel) = self.panel.descend_mut(&self.open[..depth]){
+                panel.prepare(queue, screen_dim, [hovered, active], highlight);
+            }
+        }
+    }
+
+    pub(crate) fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.visible{
+            for depth in 0..=self.open.len(){
+                if let Some(panel) = self.panel.descend(&self.open[..depth]){
+                    panel.render(render_pass);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if self.visible{
+            for depth in 0..=self.open.len(){
+                if let Some(panel) = self.panel.descend(&self.open[..depth]){
+                    panel.render_text(brush);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Test that menus keep their items, separators and submenus in the order they were added
+    #[test]
+    fn menu_builder_test(){
+        assert!(Menu::new().is_empty());
+        let menu = Menu::new()
+            .with_item("Open", None)
+            .with_submenu("Recent", Menu::new().with_item("notes.txt", None).with_item("todo.txt", None))
+            .with_separator()
+            .with_item("Quit", Some(Box::new(|| {})));
+        assert_eq!(menu.len(), 4);
+        assert!(matches!(&menu.entries[0], MenuEntry::Item{ label, callback: None } if label == "Open"));
+        assert!(matches!(&menu.entries[1], MenuEntry::Submenu{ label, menu } if label == "Recent" && menu.len() == 2));
+        assert!(matches!(menu.entries[2], MenuEntry::Separator));
+        assert!(matches!(&menu.entries[3], MenuEntry::Item{ callback: Some(_), .. }));
+    }
+
+    /// Test that submenus open on the right, unless they'd go off the edge of the screen
+    #[test]
+    fn submenu_left_test(){
+        assert_eq!(submenu_left(-100.0, 80.0, 60.0, 200.0), -20.0);
+        assert_eq!(submenu_left(100.0, 80.0, 60.0, 200.0), 40.0);
+        // Just fitting is fine
+        assert_eq!(submenu_left(60.0, 80.0, 60.0, 200.0), 140.0);
+    }
+
+    /// Test that hovering a row closes the submenus below it, and opens its own
+    #[test]
+    fn hover_row_test(){
+        let mut open = vec![0, 2, 1];
+        hover_row(&mut open, 1, 1, true);
+        assert_eq!(open, vec![0, 2, 1]);
+        hover_row(&mut open, 1, 4, false);
+        assert_eq!(open, vec![0, 2]);
+        hover_row(&mut open, 0, 3, true);
+        assert_eq!(open, vec![0, 3]);
+        hover_row(&mut open, 0, 0, false);
+        assert_eq!(open, vec![0]);
+    }
+
+    /// Test that separators are thinner than the rows with text
+    #[test]
+    fn row_height_test(){
+        assert_eq!(row_height(&RowKind::Separator, 16.0), SEPARATOR_HEIGHT);
+        assert_eq!(row_height(&RowKind::Item(None), 16.0), 16.0 + PADDING);
+    }
+}
@@ -1,3 +1,117 @@
 pub mod base_components;
+pub mod find;
+pub mod find_bar;
+pub mod caret;
+pub mod scrollbar;
+pub mod kinetic;
+pub mod sticky;
+pub mod spinner;
+pub mod lazy;
+pub mod refresh;
+pub mod marquee;
+pub mod icon;
+pub mod image;
+pub mod eyedropper;
+pub mod tooltip;
+pub mod checkbox;
+pub mod slider;
+pub mod stack;
+pub mod radio;
+pub mod progress;
+pub mod list;
+pub mod scroll_view;
+pub mod tabs;
+pub mod streamed_image;
+pub mod dialog;
+pub mod color_picker;
+pub mod menu;
+pub mod path_text;
+pub mod rich_text;
+pub mod toast;
+pub mod text_effect;
+pub mod dialogue_box;
+pub mod text_area;
+pub mod number_input;
+pub mod stat_bar;
+pub mod radial_menu;
+pub mod virtual_list;
+pub mod collapsible;
+pub mod inventory;
+pub mod password;
+pub mod chart;
+pub mod reticle;
+pub mod breadcrumb;
+pub mod credits;
+pub mod rating;
+pub mod loading;
+pub mod wizard;
+pub mod splash;
+pub mod achievement;
+pub mod group_box;
+pub mod canvas;
+pub mod changelog;
+pub mod search_box;
+pub mod pagination;
+pub mod animated_image;
+pub mod separator;
+pub mod badge;
+pub mod knob;
 
-pub use base_components::{GUIComponent, TextGUIComponent, EventGUIComponent, Label, Button};
\ No newline at end of file
+pub use base_components::{GUIComponent, TextGUIComponent, EventGUIComponent, Label, Button, TextInput, TextBuffer, EditResult, Orientation, Rect};
+pub use find::{TextSearch, Searchable};
+pub use find_bar::FindBar;
+pub use caret::{Caret, CaretStyle};
+pub use scrollbar::Scrollbar;
+pub use kinetic::{KineticScroll, OverscrollEffect};
+pub use sticky::{StickyHeaders, StuckHeader};
+pub use spinner::Spinner;
+pub use lazy::{LazyLoader, LoadHandle};
+pub use refresh::PullToRefresh;
+pub use marquee::Marquee;
+pub use icon::{Icon, IconSet, NamedIcon};
+pub use image::{Image, PixelFeed};
+pub use eyedropper::Eyedropper;
+pub use tooltip::{Tooltip, TooltipContent, TooltipText, TooltipStack};
+pub use checkbox::Checkbox;
+pub use slider::Slider;
+pub use stack::StackPanel;
+pub use radio::RadioGroup;
+pub use progress::ProgressBar;
+pub use list::{ListAdapter, ListChange, ListData, ListView};
+pub use scroll_view::ScrollView;
+pub use tabs::TabView;
+pub use streamed_image::StreamedImage;
+pub use dialog::Dialog;
+pub use color_picker::{ColorPicker, hsv_to_rgb, rgb_to_hsv};
+pub use menu::{ContextMenu, Menu, MenuBar};
+pub use path_text::{BezierPath, PathGlyph, PathText};
+pub use rich_text::{GlyphHit, InlineImages, RichText};
+pub use toast::{Notifications, ToastCorner, ToastLevel};
+pub use text_effect::{TextEffect, TextEffects};
+pub use dialogue_box::DialogueBox;
+pub use text_area::TextArea;
+pub use number_input::NumberInput;
+pub use stat_bar::StatBar;
+pub use radial_menu::RadialMenu;
+pub use virtual_list::VirtualListView;
+pub use collapsible::CollapsiblePanel;
+pub use inventory::{Inventory, InventoryGrid, InventoryHand, InventoryItem};
+pub use password::PasswordInput;
+pub use chart::{Chart, ChartKind};
+pub use reticle::{Reticle, ReticleStyle};
+pub use breadcrumb::Breadcrumb;
+pub use credits::CreditsRoller;
+pub use rating::Rating;
+pub use loading::{LoadingProgress, LoadingScreen};
+pub use wizard::Wizard;
+pub use splash::SplashScreen;
+pub use achievement::{Achievement, AchievementPopup};
+pub use group_box::GroupBox;
+pub use canvas::{Canvas, PaintShape, Painter};
+pub use changelog::{ChangelogBlock, ChangelogPanel};
+pub use search_box::{SearchBox, filter_suggestions};
+pub use pagination::Pagination;
+pub use animated_image::{AnimatedImage, SpriteSheet};
+pub use separator::{Separator, Spacer};
+pub use badge::{Badge, BadgeCorner};
+pub use knob::Knob;
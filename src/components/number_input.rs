@@ -0,0 +1,436 @@
+//! This module contains the `NumberInput` component, a text box for numbers with buttons to step the value up and
+//! down, kept inside a range.
+
+use std::any::Any;
+
+use wgpu_glyph::{HorizontalAlign, VerticalAlign};
+
+use crate::{input::{EventPhase, EventResponse, InputCategory, InputState, Key, KeyEvent, MouseButton, MouseEvent, UiEvent}, rendering::{Quad, Renderer, TextBrush}};
+
+use super::{EventGUIComponent, Label, Rect, TextGUIComponent, TextInput};
+
+// How wide the step buttons are
+const BUTTON_WIDTH: f32 = 24.0;
+// How long a step button has to be held before it starts repeating, and how often it repeats after that, in seconds
+const REPEAT_DELAY: f32 = 0.4;
+const REPEAT_RATE: f32 = 0.05;
+
+// The value behind a number input and the range, step and rounding it's kept to
+struct NumberValue{
+    value: f32,
+    min: f32,
+    max: f32,
+    step: f32,
+    decimals: usize,
+}
+
+impl NumberValue{
+    // Keep a value inside the range, rounded to the decimal places shown
+    fn clamp(&self, value: f32) -> f32{
+        let scale = 10f32.powi(self.decimals as i32);
+        ((value * scale).round() / scale).max(self.min).min(self.max)
+    }
+
+    // Change the value (clamped), returning whether it actually changed
+    fn change_to(&mut self, value: f32) -> bool{
+        let old_value = self.value;
+        self.value = self.clamp(value);
+        self.value != old_value
+    }
+
+    // Set the range, whichever way round it's given
+    fn set_range(&mut self, min: f32, max: f32){
+        self.min = min.min(max);
+        self.max = max.max(min);
+        self.value = self.clamp(self.value);
+    }
+
+    // Read what was typed, keeping the value if it isn't a number. Returns whether the value changed.
+    fn commit(&mut self, text: &str) -> bool{
+        let typed = text.trim().parse::<f32>().ok().filter(|value| value.is_finite());
+        self.change_to(typed.unwrap_or(self.value))
+    }
+
+    // Read what was typed, then step up (or down, with a negative direction) from it
+    fn step_by(&mut self, text: &str, direction: f32) -> bool{
+        let committed = self.commit(text);
+        self.change_to(self.value + direction * self.step) || committed
+    }
+
+    fn text(&self) -> String{
+        format!("{:.*}", self.decimals, self.value)
+    }
+}
+
+/// # NumberInput
+///
+/// A text box that only takes numbers, with + and - buttons down its right side. The buttons (and the up and down
+/// arrow keys, while the box is focused) step the value by the step size, and holding a button keeps stepping.
+/// Typed values are read when enter is pressed or the box loses focus, and clamped to the range - anything that
+/// isn't a number puts the last value back.
+///
+/// The callback gets the value every time it changes, however it was changed.
+pub struct NumberInput{
+    field: TextInput,
+    bounds: Rect,
+    number: NumberValue,
+    callback: Option<Box<dyn Fn(f32)>>,
+
+    up: Quad,
+    down: Quad,
+    up_label: Label,
+    down_label: Label,
+    held: Option<f32>, // the direction of the button being held down
+    repeat_timer: f32,
+
+    input: InputState,
+    enabled: bool,
+}
+
+impl NumberInput{
+    /// Create a number input filling `bounds`, starting at 0 with no limits and a step of 1
+    pub fn new(bounds: Rect, text_size: f32, renderer: &Renderer) -> Self{
+        let button = |text: &str| {
+            let mut label = Label::new(text, text_size, [0.0, 0.0]);
+            label.align_horizontal(HorizontalAlign::Center);
+            label.align_vertical(VerticalAlign::Center);
+            label
+        };
+
+        let mut number_input = Self{
+            field: TextInput::new(bounds, text_size, renderer),
+            bounds,
+            number: NumberValue{
+                value: 0.0,
+                min: f32::NEG_INFINITY,
+                max: f32::INFINITY,
+                step: 1.0,
+                decimals: 0,
+            },
+            callback: None,
+
+            up: Quad::new([0.0, 0.0], [0.0, 0.0], [0.85, 0.85, 0.85, 1.0], &renderer.device),
+            down: Quad::new([0.0, 0.0], [0.0, 0.0], [0.85, 0.85, 0.85, 1.0], &renderer.device),
+            up_label: button("+"),
+            down_label: button("-"),
+            held: None,
+            repeat_timer: 0.0,
+
+            input: InputState::new(),
+            enabled: true,
+        };
+        number_input.set_bounds(bounds);
+        number_input.set_value(0.0);
+        number_input
+    }
+
+    pub fn get_value(&self) -> f32{
+        self.number.value
+    }
+
+    /// Set the value (clamped to the range). This doesn't run the callback.
+    pub fn set_value(&mut self, value: f32){
+        self.number.change_to(value);
+        self.field.set_text(self.number.text());
+    }
+
+    /// Change the range, keeping the value inside it
+    pub fn set_range(&mut self, min: f32, max: f32){
+        self.number.set_range(min, max);
+        self.field.set_text(self.number.text());
+    }
+
+    /// Set how much the buttons and arrow keys change the value by
+    pub fn set_step(&mut self, step: f32){
+        self.number.step = step.abs();
+    }
+
+    /// Set how many decimal places the value is shown (and rounded) to. Whole numbers by default.
+    pub fn set_decimals(&mut self, decimals: usize){
+        self.number.decimals = decimals;
+        self.set_value(self.number.value);
+    }
+
+    /// Set the function to run with the new value whenever it changes
+    pub fn set_callback(&mut self, callback: Option<Box<dyn Fn(f32)>>){
+        self.callback = callback;
+    }
+
+    pub fn set_bounds(&mut self, bounds: Rect){
+        self.bounds = bounds;
+        let right = bounds.pos[0] + bounds.size[0] / 2.0;
+        let button_size = [BUTTON_WIDTH, bounds.size[1] / 2.0];
+        self.field.set_bounds(Rect::new([bounds.pos[0] - BUTTON_WIDTH / 2.0, bounds.pos[1]], [bounds.size[0] - BUTTON_WIDTH, bounds.size[1]]));
+        self.up.set_pos([right - BUTTON_WIDTH / 2.0, bounds.pos[1] - button_size[1] / 2.0]);
+        self.down.set_pos([right - BUTTON_WIDTH / 2.0, bounds.pos[1] + button_size[1] / 2.0]);
+        // A pixel smaller, so there's a line between the buttons
+        self.up.set_size([button_size[0], button_size[1] - 1.0]);
+        self.down.set_size([button_size[0], button_size[1] - 1.0]);
+    }
+
+    /// Set the colours of the box's background and border (unfocused and focused), and of the buttons
+    pub fn set_colors(&mut self, background: [f32; 4], border: [f32; 4], focused_border: [f32; 4], button: [f32; 4]){
+        self.field.set_colors(background, border, focused_border);
+        self.up.set_color(button);
+        self.down.set_color(button);
+    }
+
+    /// Borrow the text box, for example to change its font or placeholder
+    pub fn borrow_field_mut(&mut self) -> &mut TextInput{
+        &mut self.field
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+        self.field.enable();
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+        self.held = None;
+        self.field.disable();
+    }
+
+    // Put the value back in the text box, and run the callback if it changed. The text is put back either way, so
+    // a typed value that got clamped (or wasn't a number) shows what the value really is.
+    fn changed(&mut self, changed: bool){
+        self.field.set_text(self.number.text());
+        if changed{
+            if let Some(callback) = &self.callback{
+                callback(self.number.value);
+            }
+        }
+    }
+
+    // Read what was typed, going back to the last value if it isn't a number
+    fn commit(&mut self){
+        let changed = self.number.commit(self.field.get_text());
+        self.changed(changed);
+    }
+
+    fn step_by(&mut self, direction: f32){
+        let changed = self.number.step_by(self.field.get_text(), direction);
+        self.changed(changed);
+    }
+
+    fn button_at(&self, pos: [f32; 2]) -> Option<f32>{
+        if Rect::new(self.up.get_pos(), self.up.get_size()).contains(pos){
+            Some(1.0)
+        }else if Rect::new(self.down.get_pos(), self.down.get_size()).contains(pos){
+            Some(-1.0)
+        }else{
+            None
+        }
+    }
+}
+
+impl EventGUIComponent for NumberInput{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.enabled{
+            self.field.render(render_pass);
+            self.up.render(render_pass);
+            self.down.render(render_pass);
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if self.enabled{
+            EventGUIComponent::render_text(&self.field, brush);
+            TextGUIComponent::render_text(&self.up_label, brush);
+            TextGUIComponent::render_text(&self.down_label, brush);
+        }
+    }
+
+    fn layout_text(&mut self, brush: &mut TextBrush){
+        self.field.layout_text(brush);
+    }
+
+    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        if !self.enabled{
+            return;
+        }
+
+        // Anything typed that can't be part of a number never reaches the text box
+        if let winit::event::Event::WindowEvent{ event: winit::event::WindowEvent::ReceivedCharacter(character), .. } = event{
+            if !(character.is_ascii_digit() || matches!(character, '-' | '.' | '+') || character.is_control()){
+                return;
+            }
+        }
+
+        let was_focused = self.field.is_focused();
+        match self.input.handle_event(event, window){
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed: true, pos, .. })) => {
+                if let Some(direction) = self.button_at(pos){
+                    self.step_by(direction);
+                    self.held = Some(direction);
+                    self.repeat_timer = -REPEAT_DELAY;
+                }
+            }
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed: false, .. })) => self.held = None,
+            Some(UiEvent::Key(KeyEvent{ key, pressed: true, .. })) if was_focused => {
+                match key{
+                    Key::Up => self.step_by(1.0),
+                    Key::Down => self.step_by(-1.0),
+                    Key::Enter => self.commit(),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+
+        self.field.handle_event_callback(event, window);
+        if was_focused && !self.field.is_focused(){
+            self.commit();
+        }
+    }
+
+    /// Keyboard input is consumed while focused, and clicks on the buttons
+    fn handle_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window, phase: EventPhase) -> EventResponse{
+        if phase != EventPhase::Bubble{
+            return EventResponse::Continue;
+        }
+        let keyboard = self.enabled && self.field.is_focused() && InputCategory::of(event) == Some(InputCategory::Keyboard);
+        let click = matches!(event, winit::event::Event::WindowEvent{ event: winit::event::WindowEvent::MouseInput{ .. }, .. });
+        let on_button = self.enabled && click && self.button_at(self.input.get_cursor_pos()).is_some();
+        self.handle_event_callback(event, window);
+        if keyboard || on_button { EventResponse::Consumed } else { EventResponse::Continue }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.bounds.pos
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(self.bounds)
+    }
+
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{
+        Some(self.bounds.size)
+    }
+
+    fn arrange(&mut self, rect: Rect){
+        if rect != self.bounds{
+            self.set_bounds(rect);
+        }
+    }
+
+    // Holding a button steps again and again, once it's been held for a moment
+    fn update(&mut self, dt: f32){
+        self.field.update(dt);
+        if let Some(direction) = self.held{
+            self.repeat_timer += dt;
+            while self.repeat_timer >= REPEAT_RATE{
+                self.repeat_timer -= REPEAT_RATE;
+                let changed = self.number.change_to(self.number.value + direction * self.number.step);
+                self.changed(changed);
+            }
+        }
+    }
+
+    fn is_animating(&self) -> bool{
+        self.held.is_some() || self.field.is_animating()
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.field.prepare(queue, screen_dim);
+        self.up.prepare(queue, screen_dim);
+        self.down.prepare(queue, screen_dim);
+        self.up_label.set_pos(self.up.get_pos(), screen_dim);
+        self.down_label.set_pos(self.down.get_pos(), screen_dim);
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    // A number from 0 to 10 going up in halves, shown to a decimal place
+    fn number() -> NumberValue{
+        NumberValue{
+            value: 1.0,
+            min: 0.0,
+            max: 10.0,
+            step: 0.5,
+            decimals: 1,
+        }
+    }
+
+    /// Test that values are rounded to the decimal places shown, and kept inside the range
+    #[test]
+    fn clamp_test(){
+        let mut number = number();
+        assert_eq!(number.clamp(6.66666), 6.7);
+        assert_eq!(number.clamp(-4.0), 0.0);
+        assert_eq!(number.clamp(12.0), 10.0);
+        number.decimals = 0;
+        assert_eq!(number.clamp(2.5), 3.0);
+        assert_eq!(number.clamp(2.4), 2.0);
+
+        assert!(number.change_to(7.2));
+        assert_eq!(number.value, 7.0);
+        assert!(!number.change_to(6.9));
+        assert_eq!(number.text(), "7");
+    }
+
+    /// Test that ranges given the wrong way round are swapped, and the value moved inside
+    #[test]
+    fn range_test(){
+        let mut number = number();
+        number.value = 8.0;
+        number.set_range(5.0, -5.0);
+        assert_eq!((number.min, number.max), (-5.0, 5.0));
+        assert_eq!(number.value, 5.0);
+    }
+
+    /// Test that typed values are read and clamped, and that anything that isn't a finite number keeps the value
+    #[test]
+    fn commit_test(){
+        let mut number = number();
+        assert!(number.commit(" 4.25 "));
+        assert_eq!(number.value, 4.3);
+        assert!(number.commit("50"));
+        assert_eq!(number.value, 10.0);
+        for text in ["", "abc", "1.2.3", "inf", "-inf", "NaN"].iter(){
+            assert!(!number.commit(text));
+            assert_eq!(number.value, 10.0);
+        }
+    }
+
+    /// Test that stepping starts from what was typed, and stops at the ends of the range
+    #[test]
+    fn step_by_test(){
+        let mut number = number();
+        assert!(number.step_by("1.0", 1.0));
+        assert_eq!(number.value, 1.5);
+        assert!(number.step_by("4", -1.0));
+        assert_eq!(number.value, 3.5);
+        // Something that isn't a number steps from the last value
+        assert!(number.step_by("oops", 1.0));
+        assert_eq!(number.value, 4.0);
+
+        assert!(number.step_by("0", -1.0));
+        assert_eq!(number.value, 0.0);
+        assert!(!number.step_by("0", -1.0));
+        assert!(number.step_by("10", 1.0));
+        assert_eq!(number.value, 10.0);
+        assert!(!number.step_by("10", 1.0));
+    }
+}
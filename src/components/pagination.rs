@@ -0,0 +1,388 @@
+//! This module contains the `Pagination` component, a row of page number buttons between previous and next arrows,
+//! for moving through results split across pages.
+
+use std::any::Any;
+
+use wgpu_glyph::{HorizontalAlign, VerticalAlign};
+
+use crate::{input::{HoverEvent, InputState, MouseButton, MouseEvent, UiEvent}, rendering::{Quad, Renderer, TextBrush, measure_text}};
+
+use super::{EventGUIComponent, Label, Rect, TextGUIComponent};
+
+// The space between buttons, and either side of the widest page number
+const GAP: f32 = 4.0;
+const PADDING: f32 = 6.0;
+// What stands in for the pages that are skipped
+const ELLIPSIS: &str = "...";
+
+/// The pages a pagination control shows for `current` out of `count` pages, with `None` for an ellipsis standing in
+/// for the pages skipped. The first and last pages are always shown, along with `siblings` pages either side of the
+/// current one, and the number of items stays the same (`siblings * 2 + 5`) wherever the current page is, so the
+/// buttons don't jump around. An ellipsis only ever replaces two or more pages.
+pub(crate) fn pagination_items(current: usize, count: usize, siblings: usize) -> Vec<Option<usize>>{
+    let slots = siblings * 2 + 5;
+    if count <= slots{
+        return (0..count).map(Some).collect();
+    }
+    let current = current.min(count - 1);
+    let left = current.saturating_sub(siblings).max(1);
+    let right = (current + siblings).min(count - 2);
+    // The pages at either end, when only one side gets an ellipsis
+    let end_run = slots - 2;
+    match (left > 2, right < count - 3){
+        (false, _) => (0..end_run).map(Some).chain([None, Some(count - 1)]).collect(),
+        (true, false) => [Some(0), None].iter().copied().chain((count - end_run..count).map(Some)).collect(),
+        (true, true) => [Some(0), None].iter().copied().chain((left..=right).map(Some)).chain([None, Some(count - 1)]).collect(),
+    }
+}
+
+// A button in the row: an arrow, a page, or an ellipsis
+struct PageButton{
+    quad: Quad,
+    label: Label,
+    target: Option<usize>, // the page clicking it goes to
+    ellipsis: bool,
+}
+
+/// # Pagination
+///
+/// A row of buttons for picking a page, centred on its position: a previous arrow, the page numbers, and a next
+/// arrow. With more pages than fit, some are skipped and shown as an ellipsis, though the first and last pages
+/// and the ones next to the current page are always there. The current page is highlighted, as is the button under
+/// the cursor, and the arrows are greyed out at either end.
+///
+/// Picking a page (with a button or an arrow) runs the callback with its index. Pages count from 0, but are shown
+/// counting from 1.
+pub struct Pagination{
+    pos: [f32; 2],
+    height: f32,
+    text_size: f32,
+    page: usize,
+    page_count: usize,
+    siblings: usize,
+    callback: Option<Box<dyn Fn(usize)>>,
+    colors: [[f32; 4]; 4], // button, current page, hovered, disabled arrow
+
+    buttons: Vec<PageButton>,
+    shown: usize, // how many of the buttons are in use
+    button_width: f32,
+    measured: bool, // whether the button width fits the widest page number
+
+    input: InputState,
+    hovered: Option<usize>,
+    pressed: Option<usize>, // the button the mouse went down on
+    enabled: bool,
+}
+
+impl Pagination{
+    /// Create a pagination control centred on `pos`, with buttons `height` pixels tall, on the first of `page_count`
+    /// pages
+    pub fn new(pos: [f32; 2], page_count: usize, height: f32, text_size: f32, renderer: &Renderer) -> Self{
+        let mut pagination = Self{
+            pos,
+            height,
+            text_size,
+            page: 0,
+            page_count,
+            siblings: 1,
+            callback: None,
+            colors: [[0.9, 0.9, 0.9, 1.0], [0.55, 0.7, 0.95, 1.0], [0.8, 0.8, 0.8, 1.0], [0.96, 0.96, 0.96, 1.0]],
+
+            buttons: Vec::new(),
+            shown: 0,
+            button_width: height,
+            measured: false,
+
+            input: InputState::new(),
+            hovered: None,
+            pressed: None,
+            enabled: true,
+        };
+        pagination.add_buttons(&renderer.device);
+        pagination.place();
+        pagination
+    }
+
+    pub fn get_page(&self) -> usize{
+        self.page
+    }
+
+    /// Go to a page (kept inside the page count). This doesn't run the callback.
+    pub fn set_page(&mut self, page: usize){
+        self.page = page.min(self.page_count.saturating_sub(1));
+        self.place();
+    }
+
+    pub fn get_page_count(&self) -> usize{
+        self.page_count
+    }
+
+    /// Change how many pages there are, keeping the current page inside them
+    pub fn set_page_count(&mut self, page_count: usize){
+        self.page_count = page_count;
+        self.measured = false;
+        self.set_page(self.page);
+    }
+
+    /// Set how many pages are shown either side of the current one before skipping to an ellipsis. It's 1 by default.
+    pub fn set_siblings(&mut self, siblings: usize){
+        self.siblings = siblings;
+        self.place();
+    }
+
+    /// Set the function to run with the index of a page when it's picked
+    pub fn set_callback(&mut self, callback: Option<Box<dyn Fn(usize)>>){
+        self.callback = callback;
+    }
+
+    /// Set the colours of the buttons, the current page's button, the one under the cursor, and the arrows when
+    /// there's nowhere for them to go
+    pub fn set_colors(&mut self, button: [f32; 4], current: [f32; 4], hovered: [f32; 4], disabled: [f32; 4]){
+        self.colors = [button, current, hovered, disabled];
+        self.update_colors();
+    }
+
+    /// Move the row, so it's centred on `pos`
+    pub fn set_pos(&mut self, pos: [f32; 2]){
+        self.pos = pos;
+        self.place();
+    }
+
+    /// The area taken up by the buttons
+    pub fn get_rect(&self) -> Rect{
+        let width = (self.button_width + GAP) * self.shown as f32 - GAP;
+        Rect::new(self.pos, [width.max(0.0), self.height])
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+        self.hovered = None;
+        self.pressed = None;
+        self.update_colors();
+    }
+
+    // Make sure there's a button for every item, plus the arrows
+    fn add_buttons(&mut self, device: &wgpu::Device){
+        let needed = self.siblings * 2 + 7;
+        let text_size = self.text_size;
+        let height = self.height;
+        self.buttons.resize_with(self.buttons.len().max(needed), || {
+            let mut label = Label::new("", text_size, [0.0, 0.0]);
+            label.align_horizontal(HorizontalAlign::Center);
+            label.align_vertical(VerticalAlign::Center);
+            PageButton{
+                quad: Quad::new([0.0, 0.0], [height, height], [0.9, 0.9, 0.9, 1.0], device),
+                label,
+                target: None,
+                ellipsis: false,
+            }
+        });
+    }
+
+    // Work out the items, and line the buttons up
+    fn place(&mut self){
+        let items = pagination_items(self.page, self.page_count, self.siblings);
+        let previous = if self.page > 0 { Some(self.page - 1) } else { None };
+        let next = Some(self.page + 1).filter(|next| *next < self.page_count);
+
+        let mut kinds: Vec<(String, Option<usize>, bool)> = Vec::with_capacity(items.len() + 2);
+        kinds.push(("<".to_string(), previous, false));
+        kinds.extend(items.iter().map(|item| match item{
+            Some(page) => ((page + 1).to_string(), Some(*page), false),
+            None => (ELLIPSIS.to_string(), None, true),
+        }));
+        kinds.push((">".to_string(), next, false));
+
+        // Until rebuild makes more buttons, only the ones there are get shown
+        self.shown = kinds.len().min(self.buttons.len());
+        let width = self.get_rect().size[0];
+        let mut x = self.pos[0] - width / 2.0 + self.button_width / 2.0;
+        for (button, (text, target, ellipsis)) in self.buttons.iter_mut().zip(kinds){
+            button.label.set_text(text);
+            button.target = target;
+            button.ellipsis = ellipsis;
+            button.quad.set_pos([x, self.pos[1]]);
+            button.quad.set_size([self.button_width, self.height]);
+            x += self.button_width + GAP;
+        }
+        self.hovered = None;
+        self.pressed = None;
+        self.update_colors();
+    }
+
+    fn update_colors(&mut self){
+        let last = self.shown.saturating_sub(1);
+        for (i, button) in self.buttons.iter_mut().enumerate().take(self.shown){
+            let arrow = i == 0 || i == last;
+            let color = if arrow && button.target.is_none(){
+                3
+            }else if !arrow && button.target == Some(self.page){
+                1
+            }else if self.hovered == Some(i){
+                2
+            }else{
+                0
+            };
+            button.quad.set_color(self.colors[color]);
+        }
+    }
+
+    // The button under a point, if it goes anywhere
+    fn button_at(&self, pos: [f32; 2]) -> Option<usize>{
+        (0..self.shown).find(|i| {
+            let button = &self.buttons[*i];
+            button.target.is_some() && Rect::new(button.quad.get_pos(), button.quad.get_size()).contains(pos)
+        })
+    }
+}
+
+impl EventGUIComponent for Pagination{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.enabled{
+            for button in self.buttons.iter().take(self.shown).filter(|button| !button.ellipsis){
+                button.quad.render(render_pass);
+            }
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if self.enabled{
+            for button in self.buttons.iter().take(self.shown){
+                TextGUIComponent::render_text(&button.label, brush);
+            }
+        }
+    }
+
+    /// Make the buttons wide enough for the biggest page number
+    fn layout_text(&mut self, brush: &mut TextBrush){
+        if self.measured{
+            return;
+        }
+        let widest = measure_text(brush, &self.page_count.max(1).to_string(), self.text_size, wgpu_glyph::FontId::default(), None)[0];
+        self.button_width = self.height.max(widest + PADDING * 2.0);
+        self.measured = true;
+        self.place();
+    }
+
+    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        if !self.enabled{
+            return;
+        }
+
+        match self.input.handle_event(event, window){
+            Some(UiEvent::CursorMoved{ pos, .. }) => {
+                let hovered = self.button_at(pos);
+                if hovered != self.hovered{
+                    self.hovered = hovered;
+                    self.update_colors();
+                }
+            }
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed, pos, .. })) => {
+                let button = self.button_at(pos);
+                if pressed{
+                    self.pressed = button;
+                }else{
+                    let clicked = self.pressed.take().filter(|pressed| Some(*pressed) == button);
+                    if let Some(page) = clicked.and_then(|clicked| self.buttons[clicked].target){
+                        if page != self.page{
+                            self.set_page(page);
+                            if let Some(callback) = &self.callback{
+                                callback(self.page);
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.pos
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(self.get_rect())
+    }
+
+    fn handle_hover(&mut self, event: HoverEvent){
+        if event == HoverEvent::Exit && self.hovered.is_some(){
+            self.hovered = None;
+            self.update_colors();
+        }
+    }
+
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{
+        Some(self.get_rect().size)
+    }
+
+    fn arrange(&mut self, rect: Rect){
+        if rect.pos != self.pos{
+            self.set_pos(rect.pos);
+        }
+    }
+
+    /// Make more buttons when there are more siblings to show
+    fn rebuild(&mut self, device: &wgpu::Device) -> bool{
+        let buttons = self.buttons.len();
+        self.add_buttons(device);
+        if self.buttons.len() != buttons{
+            self.place();
+            return true;
+        }
+        false
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        for button in self.buttons.iter_mut().take(self.shown){
+            button.quad.prepare(queue, screen_dim);
+            button.label.set_pos(button.quad.get_pos(), screen_dim);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Test that every page is shown when they all fit, and that there's nothing to show with no pages
+    #[test]
+    fn pagination_items_few_pages_test(){
+        assert!(pagination_items(0, 0, 1).is_empty());
+        assert_eq!(pagination_items(3, 7, 1), (0..7).map(Some).collect::<Vec<_>>());
+    }
+
+    /// Test where the ellipses go as the current page moves, and that the number of items stays the same
+    #[test]
+    fn pagination_items_ellipsis_test(){
+        assert_eq!(pagination_items(0, 10, 1), vec![Some(0), Some(1), Some(2), Some(3), Some(4), None, Some(9)]);
+        assert_eq!(pagination_items(3, 10, 1), vec![Some(0), Some(1), Some(2), Some(3), Some(4), None, Some(9)]);
+        assert_eq!(pagination_items(4, 10, 1), vec![Some(0), None, Some(3), Some(4), Some(5), None, Some(9)]);
+        assert_eq!(pagination_items(6, 10, 1), vec![Some(0), None, Some(5), Some(6), Some(7), Some(8), Some(9)]);
+        // A page past the end is treated as the last
+        assert_eq!(pagination_items(50, 10, 1), vec![Some(0), None, Some(5), Some(6), Some(7), Some(8), Some(9)]);
+        assert_eq!(pagination_items(50, 100, 2), vec![Some(0), None, Some(48), Some(49), Some(50), Some(51), Some(52), None, Some(99)]);
+    }
+}
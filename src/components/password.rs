@@ -0,0 +1,272 @@
+//! This module contains the `PasswordInput` component, a text box that hides what's typed into it.
+
+use std::{any::Any, borrow::Cow};
+
+use crate::{input::{EventPhase, EventResponse, InputState, Key, KeyEvent, UiEvent}, rendering::{Renderer, TextBrush}};
+
+use super::{EventGUIComponent, Rect, TextInput};
+
+// What's drawn in place of each character
+const BULLET: char = '\u{2022}';
+
+/// Text as a masked `TextInput` draws it, with every character swapped for `mask` (or as it is, with `None`)
+pub(crate) fn mask_text(text: &str, mask: Option<char>) -> Cow<'_, str>{
+    match mask{
+        Some(mask) => text.chars().map(|_| mask).collect::<String>().into(),
+        None => text.into(),
+    }
+}
+
+/// Where a byte index in `text` ends up in the masked text
+pub(crate) fn mask_index(text: &str, index: usize, mask: Option<char>) -> usize{
+    match mask{
+        Some(mask) => text[..index].chars().count() * mask.len_utf8(),
+        None => index,
+    }
+}
+
+// Whether an event is the copy or cut shortcut, pressed while the input is focused
+fn is_copy_shortcut(event: &UiEvent, focused: bool) -> bool{
+    match event{
+        UiEvent::Key(KeyEvent{ key: Key::Character(character), pressed: true, modifiers, .. }) => {
+            focused && modifiers.command() && matches!(character.to_ascii_lowercase(), 'c' | 'x')
+        }
+        _ => false,
+    }
+}
+
+/// # PasswordInput
+///
+/// A single line text box like `TextInput`, except that it draws a bullet in place of every character typed, so the
+/// password can't be read over someone's shoulder (unless `set_revealed` is used for a "show password" toggle).
+///
+/// The password itself only comes out of `get_password` - the callbacks get told how long it is instead, and the
+/// copy and cut shortcuts are swallowed while it's focused, so they never reach the app's own shortcut handling.
+pub struct PasswordInput{
+    field: TextInput,
+    revealed: bool,
+    input: InputState,
+}
+
+impl PasswordInput{
+    /// Create an empty password input filling `bounds`
+    pub fn new(bounds: Rect, text_size: f32, renderer: &Renderer) -> Self{
+        let mut field = TextInput::new(bounds, text_size, renderer);
+        field.set_mask(Some(BULLET));
+        Self{
+            field,
+            revealed: false,
+            input: InputState::new(),
+        }
+    }
+
+    /// The password that's been typed
+    pub fn get_password(&self) -> &str{
+        self.field.get_text()
+    }
+
+    /// Empty the input, like after a failed login
+    pub fn clear(&mut self){
+        self.field.set_text("");
+    }
+
+    /// Show the password as it was typed (or hide it again), for a "show password" toggle
+    pub fn set_revealed(&mut self, revealed: bool){
+        self.revealed = revealed;
+        self.field.set_mask(if revealed { None } else { Some(BULLET) });
+    }
+
+    pub fn is_revealed(&self) -> bool{
+        self.revealed
+    }
+
+    /// Set the grey text shown while the input is empty
+    pub fn set_placeholder<S: Into<String>>(&mut self, placeholder: S){
+        self.field.set_placeholder(placeholder);
+    }
+
+    /// Change the font, using an ID from `Renderer::add_font`. The font needs a bullet character.
+    pub fn set_font(&mut self, font: wgpu_glyph::FontId){
+        self.field.set_font(font);
+    }
+
+    pub fn set_text_color(&mut self, color: [f32; 4]){
+        self.field.set_text_color(color);
+    }
+
+    /// Set the colours of the background, and the border when unfocused and focused
+    pub fn set_colors(&mut self, background: [f32; 4], border: [f32; 4], focused_border: [f32; 4]){
+        self.field.set_colors(background, border, focused_border);
+    }
+
+    pub fn set_bounds(&mut self, bounds: Rect){
+        self.field.set_bounds(bounds);
+    }
+
+    /// Called with the number of characters in the password whenever the user changes it
+    pub fn set_on_change(&mut self, callback: Option<Box<dyn Fn(usize)>>){
+        self.field.set_on_change(callback.map(|callback| -> Box<dyn Fn(&str)> {
+            Box::new(move |text: &str| callback(text.chars().count()))
+        }));
+    }
+
+    /// Called when enter is pressed. Read the password with `get_password`.
+    pub fn set_on_submit(&mut self, callback: Option<Box<dyn Fn()>>){
+        self.field.set_on_submit(callback.map(|callback| -> Box<dyn Fn(&str)> {
+            Box::new(move |_: &str| callback())
+        }));
+    }
+
+    /// Focus or unfocus the input. Only a focused input takes keyboard input.
+    pub fn set_focused(&mut self, focused: bool){
+        self.field.set_focused(focused);
+    }
+
+    pub fn is_focused(&self) -> bool{
+        self.field.is_focused()
+    }
+
+    pub fn enable(&mut self){
+        self.field.enable();
+    }
+
+    pub fn disable(&mut self){
+        self.field.disable();
+    }
+
+    // Whether an event is the copy or cut shortcut, pressed while focused
+    fn is_copy(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window) -> bool{
+        let focused = self.field.is_focused();
+        matches!(self.input.handle_event(event, window), Some(event) if is_copy_shortcut(&event, focused))
+    }
+}
+
+impl EventGUIComponent for PasswordInput{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        self.field.render(render_pass);
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        self.field.render_text(brush);
+    }
+
+    fn layout_text(&mut self, brush: &mut TextBrush){
+        self.field.layout_text(brush);
+    }
+
+    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        if !self.is_copy(event, window){
+            self.field.handle_event_callback(event, window);
+        }
+    }
+
+    /// Keyboard input is consumed while focused, copy and cut included
+    fn handle_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window, phase: EventPhase) -> EventResponse{
+        if phase != EventPhase::Bubble{
+            return EventResponse::Continue;
+        }
+        if self.is_copy(event, window){
+            return EventResponse::Consumed;
+        }
+        self.field.handle_event(event, window, phase)
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.field.is_enabled()
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.field.get_pos()
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        self.field.get_bounds()
+    }
+
+    fn measure(&mut self, available: [f32; 2]) -> Option<[f32; 2]>{
+        self.field.measure(available)
+    }
+
+    fn arrange(&mut self, rect: Rect){
+        self.field.arrange(rect);
+    }
+
+    fn update(&mut self, dt: f32){
+        self.field.update(dt);
+    }
+
+    fn is_animating(&self) -> bool{
+        self.field.is_animating()
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.field.prepare(queue, screen_dim);
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use crate::{components::TextBuffer, input::Modifiers};
+
+    fn key(character: char, pressed: bool, modifiers: Modifiers) -> UiEvent{
+        let key = Key::Character(character);
+        UiEvent::Key(KeyEvent{ key, physical: key, scancode: 0, pressed, repeat: false, modifiers })
+    }
+
+    // The shortcut modifier on this platform
+    fn command() -> Modifiers{
+        Modifiers{ ctrl: !cfg!(target_os = "macos"), logo: cfg!(target_os = "macos"), ..Modifiers::NONE }
+    }
+
+    /// Test that every character is drawn as the mask, while the password underneath stays as it was typed
+    #[test]
+    fn mask_text_test(){
+        let mut buffer = TextBuffer::new();
+        for character in "pa\u{df}w\u{f6}rd".chars(){
+            buffer.insert(character);
+        }
+        assert_eq!(mask_text(buffer.get_text(), Some(BULLET)), "\u{2022}".repeat(7));
+        assert_eq!(buffer.get_text(), "pa\u{df}w\u{f6}rd");
+        assert_eq!(mask_text(buffer.get_text(), None), buffer.get_text());
+        assert_eq!(mask_text("", Some(BULLET)), "");
+    }
+
+    /// Test that indices into the password map onto the masked text by character, not byte
+    #[test]
+    fn mask_index_test(){
+        // The sharp s is two bytes, and each bullet is three
+        let text = "a\u{df}c";
+        assert_eq!(mask_index(text, 0, Some(BULLET)), 0);
+        assert_eq!(mask_index(text, 1, Some(BULLET)), 3);
+        assert_eq!(mask_index(text, 3, Some(BULLET)), 6);
+        assert_eq!(mask_index(text, 4, Some(BULLET)), 9);
+        assert_eq!(mask_index(text, 3, None), 3);
+    }
+
+    /// Test that copy and cut are caught while focused, in either case, and nothing else is
+    #[test]
+    fn copy_shortcut_test(){
+        assert!(is_copy_shortcut(&key('c', true, command()), true));
+        assert!(is_copy_shortcut(&key('X', true, command()), true));
+        assert!(!is_copy_shortcut(&key('c', true, command()), false));
+        assert!(!is_copy_shortcut(&key('c', false, command()), true));
+        assert!(!is_copy_shortcut(&key('c', true, Modifiers::NONE), true));
+        assert!(!is_copy_shortcut(&key('v', true, command()), true));
+        assert!(!is_copy_shortcut(&key('a', true, command()), true));
+    }
+}
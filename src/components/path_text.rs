@@ -0,0 +1,325 @@
+//! This module contains `PathText`, a line of text laid along a curve for things like circular badges and
+//! decorative headers, and `BezierPath`, the curve it follows.
+
+use std::{any::Any, f32::consts::PI};
+
+use wgpu_glyph::{GlyphCruncher, HorizontalAlign, VerticalAlign, ab_glyph::{Font, ScaleFont}};
+
+use crate::rendering::TextBrush;
+
+use super::{GUIComponent, Rect};
+
+// How many straight pieces each curve gets split into when measuring it
+const CURVE_STEPS: usize = 32;
+
+/// # BezierPath
+///
+/// A path made of cubic Bézier curves (and the lines and quadratic curves that can be turned into them), in the
+/// same space components are positioned in. Built up from a starting point, like:
+///
+/// ```ignore
+/// let wave = BezierPath::new([-200.0, 0.0])
+///     .cubic_to([-100.0, -80.0], [0.0, 80.0], [100.0, 0.0])
+///     .line_to([200.0, 0.0]);
+/// ```
+///
+/// The path gets split into short straight pieces as it's built, so finding points along it with `sample` is cheap.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BezierPath{
+    points: Vec<[f32; 2]>,
+    distances: Vec<f32>, // how far along the path each point is
+}
+
+impl BezierPath{
+    /// Start an empty path at a point
+    pub fn new(start: [f32; 2]) -> Self{
+        Self{
+            points: vec![start],
+            distances: vec![0.0],
+        }
+    }
+
+    /// A circular arc around `centre`, between two angles in radians. Angles go clockwise on screen from the right
+    /// of the centre, so an arc from `PI` to `2.0 * PI` runs over the top, left to right - where a badge's text goes.
+    pub fn arc(centre: [f32; 2], radius: f32, start_angle: f32, end_angle: f32) -> Self{
+        let point = |angle: f32| [centre[0] + radius * angle.cos(), centre[1] + radius * angle.sin()];
+        let mut path = Self::new(point(start_angle));
+        // Each curve covers at most a quarter turn, where it stays close to the circle
+        let pieces = ((end_angle - start_angle).abs() / (PI / 2.0)).ceil().max(1.0) as usize;
+        let step = (end_angle - start_angle) / pieces as f32;
+        let handle = 4.0 / 3.0 * (step / 4.0).tan() * radius;
+        for i in 0..pieces{
+            let (a, b) = (start_angle + step * i as f32, start_angle + step * (i + 1) as f32);
+            let (start, end) = (point(a), point(b));
+            path = path.cubic_to(
+                [start[0] - handle * a.sin(), start[1] + handle * a.cos()],
+                [end[0] + handle * b.sin(), end[1] - handle * b.cos()],
+                end,
+            );
+        }
+        path
+    }
+
+    /// A whole circle, starting and ending on its left and going clockwise over the top
+    pub fn circle(centre: [f32; 2], radius: f32) -> Self{
+        Self::arc(centre, radius, PI, 3.0 * PI)
+    }
+
+    /// Add a straight line from the end of the path
+    pub fn line_to(mut self, end: [f32; 2]) -> Self{
+        self.push(end);
+        self
+    }
+
+    /// Add a quadratic curve from the end of the path
+    pub fn quad_to(self, control: [f32; 2], end: [f32; 2]) -> Self{
+        let start = self.get_end();
+        let lerp = |a: [f32; 2], b: [f32; 2]| [a[0] + (b[0] - a[0]) * 2.0 / 3.0, a[1] + (b[1] - a[1]) * 2.0 / 3.0];
+        self.cubic_to(lerp(start, control), lerp(end, control), end)
+    }
+
+    /// Add a cubic curve from the end of the path
+    pub fn cubic_to(mut self, control_a: [f32; 2], control_b: [f32; 2], end: [f32; 2]) -> Self{
+        let start = self.get_end();
+        for step in 1..=CURVE_STEPS{
+            let t = step as f32 / CURVE_STEPS as f32;
+            let u = 1.0 - t;
+            let weights = [u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t];
+            let along = |axis: usize| weights[0] * start[axis] + weights[1] * control_a[axis] + weights[2] * control_b[axis] + weights[3] * end[axis];
+            self.push([along(0), along(1)]);
+        }
+        self
+    }
+
+    pub fn get_start(&self) -> [f32; 2]{
+        self.points[0]
+    }
+
+    pub fn get_end(&self) -> [f32; 2]{
+        self.points[self.points.len() - 1]
+    }
+
+    /// How long the path is, in pixels
+    pub fn length(&self) -> f32{
+        self.distances[self.distances.len() - 1]
+    }
+
+    /// The point `distance` pixels along the path, and the direction the path is heading there (in radians,
+    /// clockwise on screen from pointing right). `None` if the distance is off either end of the path.
+    pub fn sample(&self, distance: f32) -> Option<([f32; 2], f32)>{
+        if self.points.len() < 2 || distance < 0.0 || distance > self.length(){
+            return None;
+        }
+        // The piece the distance lands on
+        let end = self.distances.partition_point(|along| *along < distance).clamp(1, self.points.len() - 1);
+        let (a, b) = (self.points[end - 1], self.points[end]);
+        let piece = self.distances[end] - self.distances[end - 1];
+        let t = if piece > 0.0 { (distance - self.distances[end - 1]) / piece } else { 0.0 };
+        Some(([a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t], (b[1] - a[1]).atan2(b[0] - a[0])))
+    }
+
+    fn push(&mut self, point: [f32; 2]){
+        let last = self.get_end();
+        let distance = self.length() + ((point[0] - last[0]).powi(2) + (point[1] - last[1]).powi(2)).sqrt();
+        self.points.push(point);
+        self.distances.push(distance);
+    }
+}
+
+/// Where a glyph of a `PathText` ended up
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PathGlyph{
+    /// Where the glyph's character starts in the text, in bytes
+    pub byte_index: usize,
+    /// The centre of the glyph
+    pub pos: [f32; 2],
+    /// The direction of the path under the glyph, in radians, clockwise on screen from pointing right
+    pub angle: f32,
+}
+
+/// # PathText
+///
+/// A line of text following a `BezierPath`, with each glyph centred on the path. The text starts at the beginning
+/// of the path by default, but can be aligned to its middle or end with `align`, and pushed along with `set_offset`.
+/// Glyphs that would land off the end of the path aren't drawn.
+///
+/// Where each glyph went, and the angle of the path under it, can be read with `get_glyphs` once the text has been
+/// laid out (at the start of each frame, after the text or path changed). Each glyph is turned to the angle of the
+/// path under it, which takes a draw call per glyph, so it suits short text like titles and badges.
+pub struct PathText{
+    text: String,
+    size: f32,
+    font: wgpu_glyph::FontId,
+    color: [f32; 4],
+    path: BezierPath,
+    offset: f32,
+    alignment: HorizontalAlign,
+
+    glyphs: Vec<PathGlyph>,
+    dirty: bool, // the glyphs need laying out again
+    screen_offset: [f32; 2],
+    enabled: bool,
+}
+
+impl PathText{
+    pub fn new(text: &str, size: f32, path: BezierPath) -> Self{
+        Self{
+            text: text.to_string(),
+            size,
+            font: wgpu_glyph::FontId::default(),
+            color: [0.0, 0.0, 0.0, 1.0],
+            path,
+            offset: 0.0,
+            alignment: HorizontalAlign::Left,
+
+            glyphs: Vec::new(),
+            dirty: true,
+            screen_offset: [0.0, 0.0],
+            enabled: true,
+        }
+    }
+
+    pub fn set_text(&mut self, text: &str){
+        self.text = text.to_string();
+        self.dirty = true;
+    }
+
+    pub fn get_text(&self) -> &str{
+        &self.text
+    }
+
+    pub fn set_path(&mut self, path: BezierPath){
+        self.path = path;
+        self.dirty = true;
+    }
+
+    pub fn get_path(&self) -> &BezierPath{
+        &self.path
+    }
+
+    /// Change the font, using an ID from `Renderer::add_font`
+    pub fn set_font(&mut self, font: wgpu_glyph::FontId){
+        self.font = font;
+        self.dirty = true;
+    }
+
+    pub fn set_color(&mut self, color: [f32; 4]){
+        self.color = color;
+    }
+
+    /// Line the text up with the start, middle or end of the path
+    pub fn align(&mut self, alignment: HorizontalAlign){
+        self.alignment = alignment;
+        self.dirty = true;
+    }
+
+    /// Push the text along the path by a number of pixels (back towards the start, if negative)
+    pub fn set_offset(&mut self, offset: f32){
+        self.offset = offset;
+        self.dirty = true;
+    }
+
+    /// Where each glyph ended up the last time the text was laid out
+    pub fn get_glyphs(&self) -> &[PathGlyph]{
+        &self.glyphs
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+    }
+}
+
+impl GUIComponent for PathText{
+    fn render<'a, 'b>(&'a self, _render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {}
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if !self.enabled{
+            return;
+        }
+        for glyph in self.glyphs.iter(){
+            let length = self.text[glyph.byte_index..].chars().next().map_or(0, char::len_utf8);
+            let pos = [glyph.pos[0] + self.screen_offset[0], glyph.pos[1] + self.screen_offset[1]];
+            brush.queue_rotated(wgpu_glyph::Section {
+                screen_position: (pos[0], pos[1]),
+                text: vec![wgpu_glyph::Text::new(&self.text[glyph.byte_index..glyph.byte_index + length]).with_color(self.color).with_scale(wgpu_glyph::ab_glyph::PxScale::from(self.size)).with_font_id(self.font)],
+                layout: wgpu_glyph::Layout::default_single_line().h_align(HorizontalAlign::Center).v_align(VerticalAlign::Center),
+                ..wgpu_glyph::Section::default()
+            }, glyph.angle, pos);
+        }
+    }
+
+    // Lay the text out in a straight line, then bend it along the path
+    fn layout_text(&mut self, brush: &mut TextBrush){
+        if !self.dirty{
+            return;
+        }
+        self.dirty = false;
+
+        let section = wgpu_glyph::Section {
+            text: vec![wgpu_glyph::Text::new(&self.text).with_scale(wgpu_glyph::ab_glyph::PxScale::from(self.size)).with_font_id(self.font)],
+            layout: wgpu_glyph::Layout::default_single_line(),
+            ..wgpu_glyph::Section::default()
+        };
+        let laid_out: Vec<_> = brush.glyphs(section).map(|glyph| (glyph.byte_index, glyph.glyph.position.x, glyph.glyph.id, glyph.font_id)).collect();
+        let fonts = brush.fonts();
+        let placed: Vec<_> = laid_out.into_iter().map(|(byte_index, x, id, font)| {
+            (byte_index, x, fonts[font.0].as_scaled(self.size).h_advance(id))
+        }).collect();
+
+        let width = placed.last().map_or(0.0, |(_, x, advance)| x + advance);
+        let start = self.offset + match self.alignment{
+            HorizontalAlign::Left => 0.0,
+            HorizontalAlign::Center => (self.path.length() - width) / 2.0,
+            HorizontalAlign::Right => self.path.length() - width,
+        };
+        let path = &self.path;
+        self.glyphs = placed.into_iter().filter_map(|(byte_index, x, advance)| {
+            path.sample(start + x + advance / 2.0).map(|(pos, angle)| PathGlyph{ byte_index, pos, angle })
+        }).collect();
+    }
+
+    fn prepare(&mut self, _queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.screen_offset = [(screen_dim.0 / 2) as f32, (screen_dim.1 / 2) as f32];
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.path.get_start()
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        if self.glyphs.is_empty(){
+            return None;
+        }
+        let half = self.size / 2.0;
+        let (mut min, mut max) = ([f32::INFINITY; 2], [f32::NEG_INFINITY; 2]);
+        for glyph in self.glyphs.iter(){
+            for axis in 0..2{
+                min[axis] = min[axis].min(glyph.pos[axis] - half);
+                max[axis] = max[axis].max(glyph.pos[axis] + half);
+            }
+        }
+        Some(Rect::from_corners(min, max))
+    }
+}
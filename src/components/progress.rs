@@ -0,0 +1,207 @@
+//! This module contains the `ProgressBar` component, a bar that fills up from the left as something
+//! gets closer to being done.
+
+use std::any::Any;
+
+use crate::rendering::{Quad, Renderer};
+
+use super::{GUIComponent, Rect};
+
+// How much of the bar the sliding block takes up in indeterminate mode
+const BLOCK_WIDTH: f32 = 0.3;
+
+// Where the fill starts and ends, as fractions of the bar. Indeterminate bars show a block `phase` of the way
+// through sliding across, which starts off to the left and slides out to the right, cut off at the ends.
+fn fill_span(progress: f32, indeterminate: bool, phase: f32) -> (f32, f32){
+    if indeterminate{
+        let start = phase * (1.0 + BLOCK_WIDTH) - BLOCK_WIDTH;
+        (start.max(0.0), (start + BLOCK_WIDTH).min(1.0))
+    }else{
+        (0.0, progress)
+    }
+}
+
+/// # ProgressBar
+///
+/// A background with a fill in front of it, as wide as the progress (from 0 to 1) says.
+///
+/// When there's no way of knowing how far along something is, the bar can be made indeterminate instead
+/// (see `set_indeterminate`). A block then slides across it, going all the way across once every `period` seconds.
+pub struct ProgressBar{
+    rect: Rect,
+    progress: f32,
+    indeterminate: bool,
+    period: f32,
+    phase: f32, // 0 to 1, how far across the block is
+
+    background: Quad,
+    fill: Quad,
+    enabled: bool,
+}
+
+impl ProgressBar{
+    /// Create an empty progress bar filling `rect`
+    pub fn new(rect: Rect, renderer: &Renderer) -> Self{
+        let mut bar = Self{
+            rect,
+            progress: 0.0,
+            indeterminate: false,
+            period: 1.5,
+            phase: 0.0,
+
+            background: Quad::new(rect.pos, rect.size, [0.8, 0.8, 0.8, 1.0], &renderer.device),
+            fill: Quad::new(rect.pos, [0.0, rect.size[1]], [0.2, 0.4, 0.8, 1.0], &renderer.device),
+            enabled: true,
+        };
+        bar.update_fill();
+        bar
+    }
+
+    pub fn get_progress(&self) -> f32{
+        self.progress
+    }
+
+    /// Set how far along the bar is, from 0 (empty) to 1 (full)
+    pub fn set_progress(&mut self, progress: f32){
+        self.progress = progress.clamp(0.0, 1.0);
+        self.update_fill();
+    }
+
+    pub fn is_indeterminate(&self) -> bool{
+        self.indeterminate
+    }
+
+    /// Switch between showing the progress, and a block sliding across to show something is happening
+    pub fn set_indeterminate(&mut self, indeterminate: bool){
+        self.indeterminate = indeterminate;
+        self.phase = 0.0;
+        self.update_fill();
+    }
+
+    /// Set how many seconds it takes the block to go across the bar in indeterminate mode
+    pub fn set_period(&mut self, period: f32){
+        self.period = period.max(0.01);
+    }
+
+    pub fn set_rect(&mut self, rect: Rect){
+        self.rect = rect;
+        self.background.set_pos(rect.pos);
+        self.background.set_size(rect.size);
+        self.update_fill();
+    }
+
+    pub fn get_rect(&self) -> Rect{
+        self.rect
+    }
+
+    /// Set the colours of the background and the fill
+    pub fn set_colors(&mut self, background: [f32; 4], fill: [f32; 4]){
+        self.background.set_color(background);
+        self.fill.set_color(fill);
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+    }
+
+    fn update_fill(&mut self){
+        let width = self.rect.size[0];
+        let (start, end) = fill_span(self.progress, self.indeterminate, self.phase);
+        let left = self.rect.pos[0] - width / 2.0;
+        self.fill.set_pos([left + (start + end) / 2.0 * width, self.rect.pos[1]]);
+        self.fill.set_size([(end - start) * width, self.rect.size[1]]);
+    }
+}
+
+impl GUIComponent for ProgressBar{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.enabled{
+            self.background.render(render_pass);
+            self.fill.render(render_pass);
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.rect.pos
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(self.rect)
+    }
+
+    fn update(&mut self, dt: f32){
+        if self.enabled && self.indeterminate{
+            self.phase = (self.phase + dt / self.period).rem_euclid(1.0);
+            self.update_fill();
+        }
+    }
+
+    fn is_animating(&self) -> bool{
+        self.enabled && self.indeterminate
+    }
+
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{
+        Some(self.rect.size)
+    }
+
+    fn arrange(&mut self, rect: Rect){
+        if rect != self.rect{
+            self.set_rect(rect);
+        }
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.background.prepare(queue, screen_dim);
+        self.fill.prepare(queue, screen_dim);
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Test that the fill covers the bar from the left up to the progress
+    #[test]
+    fn fill_span_test(){
+        assert_eq!(fill_span(0.0, false, 0.0), (0.0, 0.0));
+        assert_eq!(fill_span(0.4, false, 0.0), (0.0, 0.4));
+        assert_eq!(fill_span(1.0, false, 0.7), (0.0, 1.0));
+    }
+
+    /// Test that the indeterminate block slides in from the left and out to the right, cut off at the ends
+    #[test]
+    fn indeterminate_test(){
+        // It starts just off the bar, and finishes just off the other end, so the loop back to the start is hidden
+        assert_eq!(fill_span(0.5, true, 0.0), (0.0, 0.0));
+        let (start, end) = fill_span(0.5, true, 1.0);
+        assert_eq!(end, 1.0);
+        assert!((start - 1.0).abs() < 1e-6);
+
+        let (start, end) = fill_span(0.5, true, 0.1);
+        assert_eq!(start, 0.0);
+        assert!((end - 0.13).abs() < 1e-6);
+        let (start, end) = fill_span(0.5, true, 0.5);
+        assert!((start - 0.35).abs() < 1e-6 && (end - 0.65).abs() < 1e-6);
+    }
+}
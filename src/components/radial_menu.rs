@@ -0,0 +1,352 @@
+//! This module contains the `RadialMenu` component, a ring of options around a point (like a weapon wheel), picked by
+//! pointing at them with the mouse or a gamepad stick.
+
+use std::{any::Any, f32::consts::PI};
+
+use wgpu_glyph::{HorizontalAlign, VerticalAlign};
+
+use crate::{input::{EventPhase, EventResponse, InputCategory, InputState, Key, KeyEvent, MouseButton, MouseEvent, UiEvent}, rendering::{Quad, Renderer, TextBrush}};
+
+use super::{EventGUIComponent, Label, Rect, TextGUIComponent};
+
+// The space either side of an option's text, in pixels
+const PADDING: f32 = 10.0;
+// How big the marker in the middle of the ring is
+const HUB_SIZE: f32 = 12.0;
+// How far the cursor has to be from the middle, as a fraction of the radius, before it points at an option
+const MOUSE_DEADZONE: f32 = 0.3;
+// How far a stick has to be pushed (from 0 to 1) before it points at an option
+const STICK_DEADZONE: f32 = 0.5;
+
+/// Which of `count` equal wedges around a point an offset from it points into. Wedge 0 is centred straight up and
+/// the rest go clockwise, with y going down like in component space. There's no wedge for an offset of zero.
+pub(crate) fn wedge_at(offset: [f32; 2], count: usize) -> Option<usize>{
+    if count == 0 || (offset[0] == 0.0 && offset[1] == 0.0){
+        return None;
+    }
+    let wedge = 2.0 * PI / count as f32;
+    // Clockwise from straight up, from 0 to 2 pi
+    let angle = offset[0].atan2(-offset[1]).rem_euclid(2.0 * PI);
+    Some(((angle + wedge / 2.0) / wedge) as usize % count)
+}
+
+struct RadialOption{
+    label: Label,
+    quad: Quad,
+    size: [f32; 2],
+}
+
+/// # RadialMenu
+///
+/// A ring of options that opens around a point - the cursor with `open_at_cursor`, or anywhere with `open_at` (like
+/// the middle of the screen, or above the player, for gamepads). The option in the direction being pointed is
+/// highlighted: with the mouse, that's the wedge of the ring the cursor is in, and with a gamepad, the stick's
+/// direction passed in with `set_stick`. Letting go of the stick keeps the highlight, so it can be confirmed.
+///
+/// Clicking, enter or `confirm` picks the highlighted option, and right-clicking, escape or `cancel` closes the
+/// menu without picking anything. Either way the callback gets the result, `None` for a cancel. For hold-to-open
+/// menus, call `confirm` when the button is let go.
+///
+/// The menu draws over everything else, so it belongs in the overlay layer (`Layout::add_overlay_component`).
+/// While it's open it takes all mouse and keyboard input.
+pub struct RadialMenu{
+    options: Vec<RadialOption>,
+    center: [f32; 2],
+    radius: f32,
+    text_size: f32,
+    highlighted: Option<usize>,
+    callback: Option<Box<dyn Fn(Option<usize>)>>,
+    option_color: [f32; 4],
+    highlight_color: [f32; 4],
+    screen_dim: (u32, u32),
+
+    hub: Quad,
+    open: bool,
+    input: InputState,
+}
+
+impl RadialMenu{
+    /// Create a closed menu with no options, which puts the middle of its options `radius` pixels from its centre
+    pub fn new(radius: f32, text_size: f32, renderer: &Renderer) -> Self{
+        Self{
+            options: Vec::new(),
+            center: [0.0, 0.0],
+            radius,
+            text_size,
+            highlighted: None,
+            callback: None,
+            option_color: [0.95, 0.95, 0.95, 0.9],
+            highlight_color: [0.7, 0.82, 1.0, 0.95],
+            screen_dim: (0, 0),
+
+            hub: Quad::new([0.0, 0.0], [HUB_SIZE, HUB_SIZE], [0.3, 0.3, 0.3, 0.9], &renderer.device),
+            open: false,
+            input: InputState::new(),
+        }
+    }
+
+    /// Replace the options, the first going at the top and the rest clockwise around the ring
+    pub fn set_options(&mut self, options: &[&str], renderer: &mut Renderer){
+        self.options = options.iter().map(|text| {
+            let mut label = Label::new(*text, self.text_size, [0.0, 0.0]);
+            label.align_horizontal(HorizontalAlign::Center);
+            label.align_vertical(VerticalAlign::Center);
+            let size = [label.measure(renderer)[0] + PADDING * 2.0, self.text_size + PADDING];
+            RadialOption{
+                label,
+                quad: Quad::new([0.0, 0.0], size, self.option_color, &renderer.device),
+                size,
+            }
+        }).collect();
+        self.highlighted = None;
+        self.place();
+    }
+
+    pub fn get_option_count(&self) -> usize{
+        self.options.len()
+    }
+
+    /// Set the function to run with the picked option when the menu closes, or `None` if it was cancelled
+    pub fn set_callback(&mut self, callback: Option<Box<dyn Fn(Option<usize>)>>){
+        self.callback = callback;
+    }
+
+    /// Set the colours of the options, the highlighted option and the marker in the middle
+    pub fn set_colors(&mut self, option: [f32; 4], highlight: [f32; 4], hub: [f32; 4]){
+        self.option_color = option;
+        self.highlight_color = highlight;
+        self.hub.set_color(hub);
+    }
+
+    pub fn set_radius(&mut self, radius: f32){
+        self.radius = radius;
+        self.place();
+    }
+
+    /// Open the menu centred on `pos`, or as close as it can get while staying on screen
+    pub fn open_at(&mut self, pos: [f32; 2]){
+        self.center = pos;
+        self.open = true;
+        self.highlighted = None;
+        self.place();
+    }
+
+    /// Open the menu centred on the cursor
+    pub fn open_at_cursor(&mut self){
+        self.open_at(self.input.get_cursor_pos());
+    }
+
+    pub fn is_open(&self) -> bool{
+        self.open
+    }
+
+    /// Where the menu is centred, after keeping it on screen
+    pub fn get_center(&self) -> [f32; 2]{
+        self.center
+    }
+
+    pub fn get_highlighted(&self) -> Option<usize>{
+        self.highlighted
+    }
+
+    /// Highlight an option (or none), like pointing at it would
+    pub fn set_highlighted(&mut self, highlighted: Option<usize>){
+        self.highlighted = highlighted.filter(|i| *i < self.options.len());
+    }
+
+    /// Point with a gamepad stick, with each axis going from -1 to 1 and y going up, like gamepad libraries give
+    /// them. Small pushes are ignored, so the highlight stays put when the stick springs back to the middle.
+    pub fn set_stick(&mut self, stick: [f32; 2]){
+        if self.open && (stick[0] * stick[0] + stick[1] * stick[1]).sqrt() >= STICK_DEADZONE{
+            self.highlighted = wedge_at([stick[0], -stick[1]], self.options.len());
+        }
+    }
+
+    /// Close the menu, picking the highlighted option. With nothing highlighted, this is the same as `cancel`.
+    pub fn confirm(&mut self){
+        if self.open{
+            self.finish(self.highlighted);
+        }
+    }
+
+    /// Close the menu without picking anything
+    pub fn cancel(&mut self){
+        if self.open{
+            self.finish(None);
+        }
+    }
+
+    fn finish(&mut self, result: Option<usize>){
+        self.open = false;
+        self.highlighted = None;
+        if let Some(callback) = &self.callback{
+            callback(result);
+        }
+    }
+
+    // Where the middle of an option goes
+    fn option_pos(&self, index: usize) -> [f32; 2]{
+        let angle = 2.0 * PI * index as f32 / self.options.len() as f32;
+        [self.center[0] + self.radius * angle.sin(), self.center[1] - self.radius * angle.cos()]
+    }
+
+    // Keep the whole ring on screen, once there's a screen to keep it on, and move the options around the centre
+    fn place(&mut self){
+        if self.screen_dim != (0, 0){
+            let half = [self.screen_dim.0 as f32 / 2.0, self.screen_dim.1 as f32 / 2.0];
+            let largest = self.options.iter().fold([0.0f32, 0.0f32], |largest, option| [largest[0].max(option.size[0]), largest[1].max(option.size[1])]);
+            for axis in 0..2{
+                let extent = (self.radius + largest[axis] / 2.0).min(half[axis]);
+                self.center[axis] = self.center[axis].max(-half[axis] + extent).min(half[axis] - extent);
+            }
+        }
+        self.hub.set_pos(self.center);
+        for i in 0..self.options.len(){
+            let pos = self.option_pos(i);
+            self.options[i].quad.set_pos(pos);
+        }
+    }
+
+    fn point_at(&mut self, pos: [f32; 2]){
+        let offset = [pos[0] - self.center[0], pos[1] - self.center[1]];
+        let on_option = self.options.iter().position(|option| Rect::new(option.quad.get_pos(), option.size).contains(pos));
+        self.highlighted = if (offset[0] * offset[0] + offset[1] * offset[1]).sqrt() < self.radius * MOUSE_DEADZONE{
+            None
+        }else{
+            on_option.or_else(|| wedge_at(offset, self.options.len()))
+        };
+    }
+
+    // Step the highlight around the ring with the arrow keys
+    fn step(&mut self, direction: isize){
+        let count = self.options.len() as isize;
+        if count == 0{
+            return;
+        }
+        let next = match self.highlighted{
+            Some(i) => (i as isize + direction).rem_euclid(count),
+            None if direction > 0 => 0,
+            None => count - 1,
+        };
+        self.highlighted = Some(next as usize);
+    }
+}
+
+impl EventGUIComponent for RadialMenu{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.open{
+            self.hub.render(render_pass);
+            for option in self.options.iter(){
+                option.quad.render(render_pass);
+            }
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if self.open{
+            for option in self.options.iter(){
+                TextGUIComponent::render_text(&option.label, brush);
+            }
+        }
+    }
+
+    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        let ui_event = self.input.handle_event(event, window);
+        if !self.open{
+            return;
+        }
+
+        match ui_event{
+            Some(UiEvent::CursorMoved{ pos, .. }) => self.point_at(pos),
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed: true, pos, .. })) => {
+                self.point_at(pos);
+                self.confirm();
+            }
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Right, pressed: true, .. })) => self.cancel(),
+            Some(UiEvent::Key(KeyEvent{ key, pressed: true, .. })) => {
+                match key{
+                    Key::Right | Key::Down => self.step(1),
+                    Key::Left | Key::Up => self.step(-1),
+                    Key::Enter | Key::Space => self.confirm(),
+                    Key::Escape => self.cancel(),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// While the menu is open, all mouse and keyboard input is ours
+    fn handle_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window, phase: EventPhase) -> EventResponse{
+        if phase != EventPhase::Bubble{
+            return EventResponse::Continue;
+        }
+        let taken = self.open && InputCategory::of(event).is_some();
+        self.handle_event_callback(event, window);
+        if taken { EventResponse::Consumed } else { EventResponse::Continue }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.open
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.center
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        let size = 2.0 * self.radius;
+        Some(Rect::new(self.center, [size, size])).filter(|_| self.open)
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        if screen_dim != self.screen_dim{
+            self.screen_dim = screen_dim;
+            self.place();
+        }
+        if !self.open{
+            return;
+        }
+        self.hub.prepare(queue, screen_dim);
+        for i in 0..self.options.len(){
+            let color = if self.highlighted == Some(i) { self.highlight_color } else { self.option_color };
+            let option = &mut self.options[i];
+            option.quad.set_color(color);
+            option.quad.prepare(queue, screen_dim);
+            option.label.set_pos(option.quad.get_pos(), screen_dim);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Test that offsets fall into the wedges clockwise from straight up, with y going down
+    #[test]
+    fn wedge_test(){
+        assert_eq!(wedge_at([0.0, -10.0], 4), Some(0));
+        assert_eq!(wedge_at([10.0, 0.0], 4), Some(1));
+        assert_eq!(wedge_at([0.0, 10.0], 4), Some(2));
+        assert_eq!(wedge_at([-10.0, 0.0], 4), Some(3));
+        // Wedge 0 is centred on straight up, so just left of up is still in it
+        assert_eq!(wedge_at([-1.0, -10.0], 4), Some(0));
+        assert_eq!(wedge_at([10.0, -9.0], 8), Some(1));
+        assert_eq!(wedge_at([0.0, 0.0], 4), None);
+        assert_eq!(wedge_at([1.0, 1.0], 0), None);
+    }
+}
@@ -0,0 +1,351 @@
+//! This module contains the `RadioGroup` component, a column of options where picking one unpicks the rest.
+
+use std::any::Any;
+
+use wgpu_glyph::{HorizontalAlign, VerticalAlign};
+
+use crate::{input::{InputState, MouseButton, MouseEvent, UiEvent}, rendering::{Quad, Renderer, TextBrush}};
+
+use super::{EventGUIComponent, Label, Rect, TextGUIComponent};
+
+// The space between a button and its label, in pixels
+const LABEL_GAP: f32 = 8.0;
+
+// Which of `len` options is selected, and which one the mouse went down on. Only one can ever be selected.
+struct Selection{
+    selected: Option<usize>,
+    pressed: Option<usize>,
+    len: usize,
+}
+
+impl Selection{
+    fn new(len: usize) -> Self{
+        Self{
+            selected: None,
+            pressed: None,
+            len,
+        }
+    }
+
+    // Select an option, or nothing with `None` (which is also what an option that doesn't exist does)
+    fn set(&mut self, selected: Option<usize>){
+        self.selected = selected.filter(|index| *index < self.len);
+    }
+
+    // Select an option like a click would, returning whether the selection changed
+    fn select(&mut self, index: usize) -> bool{
+        if index >= self.len || self.selected == Some(index){
+            return false;
+        }
+        self.selected = Some(index);
+        true
+    }
+
+    // Handle the mouse button going down or up over an option (or between them, with `None`), returning the option
+    // clicked if it went down and up on the same one
+    fn click(&mut self, down: bool, option: Option<usize>) -> Option<usize>{
+        if down{
+            self.pressed = option;
+            None
+        }else{
+            self.pressed.take().filter(|pressed| Some(*pressed) == option)
+        }
+    }
+}
+
+// A button, with its label and the label's measured size
+struct RadioOption{
+    border: Quad,
+    background: Quad,
+    label: Label,
+    measured: [f32; 2],
+}
+
+/// # RadioGroup
+///
+/// A column of labelled buttons, where only one can be selected at a time. Clicking an option (or its label)
+/// selects it and unselects the one before, then the callback runs with the index of the new selection.
+///
+/// Like a `Checkbox`, an option reacts to a press and release both inside it.
+pub struct RadioGroup{
+    pos: [f32; 2], // the centre of the first button
+    size: f32,
+    spacing: f32, // between the rows
+    selection: Selection,
+    callback: Option<Box<dyn Fn(usize)>>,
+
+    options: Vec<RadioOption>,
+    dot: Quad,
+
+    input: InputState,
+    enabled: bool,
+}
+
+impl RadioGroup{
+    /// Create a group with nothing selected. The first button is centred on `pos`, and the rest go below it,
+    /// `spacing` pixels apart.
+    pub fn new(pos: [f32; 2], size: f32, options: &[&str], text_size: f32, spacing: f32, callback: Option<Box<dyn Fn(usize)>>, renderer: &mut Renderer) -> Self{
+        let count = options.len();
+        let options = options.iter().map(|text| {
+            let mut label = Label::new(*text, text_size, [0.0, 0.0]);
+            label.align_vertical(VerticalAlign::Center);
+            label.align_horizontal(HorizontalAlign::Left);
+            let measured = label.measure(renderer);
+            RadioOption{
+                border: Quad::new(pos, [size, size], [0.3, 0.3, 0.3, 1.0], &renderer.device),
+                background: Quad::new(pos, [size - 4.0, size - 4.0], [1.0, 1.0, 1.0, 1.0], &renderer.device),
+                label,
+                measured,
+            }
+        }).collect();
+
+        let mut group = Self{
+            pos,
+            size,
+            spacing,
+            selection: Selection::new(count),
+            callback,
+
+            options,
+            dot: Quad::new(pos, [size / 2.0, size / 2.0], [0.2, 0.4, 0.8, 1.0], &renderer.device),
+
+            input: InputState::new(),
+            enabled: true,
+        };
+        group.set_pos(pos);
+        group
+    }
+
+    /// The index of the selected option, if there is one
+    pub fn get_selected(&self) -> Option<usize>{
+        self.selection.selected
+    }
+
+    /// Select an option, or clear the selection with `None`. This doesn't run the callback.
+    pub fn set_selected(&mut self, selected: Option<usize>){
+        self.selection.set(selected);
+        self.place_dot();
+    }
+
+    /// Select an option like a click would, running the callback if the selection changed
+    pub fn select(&mut self, index: usize){
+        if !self.selection.select(index){
+            return;
+        }
+        self.place_dot();
+        if let Some(callback) = &self.callback{
+            callback(index);
+        }
+    }
+
+    /// Set the function to run when the selection changes
+    pub fn set_callback(&mut self, callback: Option<Box<dyn Fn(usize)>>){
+        self.callback = callback;
+    }
+
+    /// Move the group, so the first button is centred on `pos`
+    pub fn set_pos(&mut self, pos: [f32; 2]){
+        self.pos = pos;
+        for i in 0..self.options.len(){
+            let centre = self.button_centre(i);
+            self.options[i].border.set_pos(centre);
+            self.options[i].background.set_pos(centre);
+        }
+        self.place_dot();
+    }
+
+    /// Set the colours of the border, the inside of the buttons and the dot on the selected one
+    pub fn set_colors(&mut self, border: [f32; 4], background: [f32; 4], dot: [f32; 4]){
+        for option in self.options.iter_mut(){
+            option.border.set_color(border);
+            option.background.set_color(background);
+        }
+        self.dot.set_color(dot);
+    }
+
+    /// Borrow the label of an option, for example to change its font
+    pub fn borrow_label_mut(&mut self, index: usize) -> Option<&mut Label>{
+        self.options.get_mut(index).map(|option| &mut option.label)
+    }
+
+    pub fn len(&self) -> usize{
+        self.options.len()
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.options.is_empty()
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+        self.selection.pressed = None;
+    }
+
+    /// The area that can be clicked to select an option - its button, and the label next to it
+    pub fn get_option_rect(&self, index: usize) -> Option<Rect>{
+        let option = self.options.get(index)?;
+        let centre = self.button_centre(index);
+        let width = self.size + LABEL_GAP + option.measured[0];
+        Some(Rect::new([centre[0] - self.size / 2.0 + width / 2.0, centre[1]], [width, self.row_height()]))
+    }
+
+    /// The area taken up by every option
+    pub fn get_rect(&self) -> Rect{
+        let left = self.pos[0] - self.size / 2.0;
+        let top = self.pos[1] - self.row_height() / 2.0;
+        let width = self.options.iter().map(|option| self.size + LABEL_GAP + option.measured[0]).fold(self.size, f32::max);
+        let rows = self.options.len().max(1) as f32;
+        let height = rows * self.row_height() + (rows - 1.0) * self.spacing;
+        Rect::new([left + width / 2.0, top + height / 2.0], [width, height])
+    }
+
+    fn row_height(&self) -> f32{
+        self.options.iter().map(|option| option.measured[1]).fold(self.size, f32::max)
+    }
+
+    fn button_centre(&self, index: usize) -> [f32; 2]{
+        [self.pos[0], self.pos[1] + index as f32 * (self.row_height() + self.spacing)]
+    }
+
+    fn place_dot(&mut self){
+        if let Some(selected) = self.selection.selected{
+            self.dot.set_pos(self.button_centre(selected));
+        }
+    }
+
+    fn option_at(&self, pos: [f32; 2]) -> Option<usize>{
+        (0..self.options.len()).find(|i| matches!(self.get_option_rect(*i), Some(rect) if rect.contains(pos)))
+    }
+}
+
+impl EventGUIComponent for RadioGroup{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.enabled{
+            for option in self.options.iter(){
+                option.border.render(render_pass);
+                option.background.render(render_pass);
+            }
+            if self.selection.selected.is_some(){
+                self.dot.render(render_pass);
+            }
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if self.enabled{
+            for option in self.options.iter(){
+                TextGUIComponent::render_text(&option.label, brush);
+            }
+        }
+    }
+
+    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        if !self.enabled{
+            return;
+        }
+
+        if let Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed, pos, .. })) = self.input.handle_event(event, window){
+            let option = self.option_at(pos);
+            if let Some(clicked) = self.selection.click(pressed, option){
+                self.select(clicked);
+            }
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.pos
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(self.get_rect())
+    }
+
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{
+        Some(self.get_rect().size)
+    }
+
+    /// The buttons go down the left of the rectangle, starting at the top
+    fn arrange(&mut self, rect: Rect){
+        let top = rect.pos[1] - rect.size[1] / 2.0;
+        self.set_pos([rect.pos[0] - rect.size[0] / 2.0 + self.size / 2.0, top + self.row_height() / 2.0]);
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        for i in 0..self.options.len(){
+            let centre = self.button_centre(i);
+            let option = &mut self.options[i];
+            option.border.prepare(queue, screen_dim);
+            option.background.prepare(queue, screen_dim);
+            option.label.set_pos([centre[0] + self.size / 2.0 + LABEL_GAP, centre[1]], screen_dim);
+        }
+        self.dot.prepare(queue, screen_dim);
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Test that selecting an option unselects the one before, and that only real options can be selected
+    #[test]
+    fn selection_test(){
+        let mut selection = Selection::new(3);
+        assert_eq!(selection.selected, None);
+        assert!(selection.select(0));
+        assert!(selection.select(2));
+        assert_eq!(selection.selected, Some(2));
+
+        // Picking the selected option again isn't a change, so the callback doesn't run
+        assert!(!selection.select(2));
+        assert!(!selection.select(3));
+        assert_eq!(selection.selected, Some(2));
+
+        selection.set(Some(1));
+        assert_eq!(selection.selected, Some(1));
+        selection.set(Some(5));
+        assert_eq!(selection.selected, None);
+    }
+
+    /// Test that an option is only clicked by pressing and releasing on it
+    #[test]
+    fn click_test(){
+        let mut selection = Selection::new(3);
+        assert_eq!(selection.click(true, Some(1)), None);
+        assert_eq!(selection.click(false, Some(1)), Some(1));
+
+        // Pressing on one option and letting go on another (or between them) clicks neither
+        selection.click(true, Some(0));
+        assert_eq!(selection.click(false, Some(2)), None);
+        selection.click(true, Some(0));
+        assert_eq!(selection.click(false, None), None);
+        selection.click(true, None);
+        assert_eq!(selection.click(false, Some(0)), None);
+
+        // The press is used up by the release
+        selection.click(true, Some(2));
+        assert_eq!(selection.click(false, Some(2)), Some(2));
+        assert_eq!(selection.click(false, Some(2)), None);
+    }
+}
@@ -0,0 +1,365 @@
+//! This module contains the `Rating` component, a row of stars that shows a rating and lets one be picked, and the
+//! star texture it draws them with.
+
+use std::{any::Any, f32::consts::PI, rc::Rc};
+
+use crate::{input::{HoverEvent, InputState, MouseButton, MouseEvent, UiEvent}, rendering::{Quad, Renderer, Texture}};
+
+use super::{EventGUIComponent, Rect};
+
+// How many pixels across the star texture is
+const STAR_RESOLUTION: u32 = 64;
+// How far in the points of the star go, as a fraction of how far out they go
+const STAR_INNER_RADIUS: f32 = 0.4;
+// Each pixel of the texture is sampled this many times across and down, to smooth the edges
+const STAR_SAMPLES: u32 = 4;
+
+/// The rating a point along a row of stars picks, with `x` measured in pixels from the left end of the row. Each
+/// star is `star_size` wide with `spacing` pixels between them, and pointing anywhere on a star (or the gap after
+/// it) picks that many stars. In half star mode, the left half of a star picks half a star less. Points left of
+/// the row pick 0.
+pub(crate) fn rating_at(x: f32, star_size: f32, spacing: f32, count: usize, half_stars: bool) -> f32{
+    if x < 0.0 || count == 0{
+        return 0.0;
+    }
+    let star = (x / (star_size + spacing)).floor();
+    if star >= count as f32{
+        return count as f32;
+    }
+    let across = (x - star * (star_size + spacing)) / star_size;
+    if half_stars && across < 0.5 { star + 0.5 } else { star + 1.0 }
+}
+
+// A white five pointed star, pointing up, on a clear background
+fn star_pixels(size: u32) -> Vec<u8>{
+    let corners: Vec<[f32; 2]> = (0..10).map(|i| {
+        let angle = -PI / 2.0 + i as f32 * PI / 5.0;
+        let radius = if i % 2 == 0 { 0.5 } else { 0.5 * STAR_INNER_RADIUS };
+        [0.5 + radius * angle.cos(), 0.5 + radius * angle.sin()]
+    }).collect();
+    // Count the edges a ray going right from the point crosses - an odd number means it's inside
+    let inside = |x: f32, y: f32| {
+        let mut crossings = 0;
+        for i in 0..corners.len(){
+            let (a, b) = (corners[i], corners[(i + 1) % corners.len()]);
+            if (a[1] > y) != (b[1] > y) && x < a[0] + (y - a[1]) / (b[1] - a[1]) * (b[0] - a[0]){
+                crossings += 1;
+            }
+        }
+        crossings % 2 == 1
+    };
+
+    let mut pixels = Vec::with_capacity((size * size * 4) as usize);
+    for row in 0..size{
+        for column in 0..size{
+            let mut covered = 0;
+            for sample in 0..STAR_SAMPLES * STAR_SAMPLES{
+                let x = (column as f32 + ((sample % STAR_SAMPLES) as f32 + 0.5) / STAR_SAMPLES as f32) / size as f32;
+                let y = (row as f32 + ((sample / STAR_SAMPLES) as f32 + 0.5) / STAR_SAMPLES as f32) / size as f32;
+                if inside(x, y){
+                    covered += 1;
+                }
+            }
+            pixels.extend_from_slice(&[255, 255, 255, (covered * 255 / (STAR_SAMPLES * STAR_SAMPLES)) as u8]);
+        }
+    }
+    pixels
+}
+
+struct Star{
+    empty: Quad,
+    fill: Quad,
+}
+
+/// # Rating
+///
+/// A row of stars centred on a point, with as many of them filled in as the rating. Hovering over the stars
+/// previews the rating they'd pick in a lighter colour, and clicking picks it, running the callback. In half star
+/// mode (`set_half_stars`) ratings go up in halves, and the left half of a star picks half a star less.
+///
+/// A read only rating (`set_read_only`) just shows its value, for things like an average of other people's ratings,
+/// and can show any fraction of a star.
+pub struct Rating{
+    pos: [f32; 2],
+    star_size: f32,
+    spacing: f32,
+    value: f32,
+    preview: Option<f32>,
+    half_stars: bool,
+    read_only: bool,
+    callback: Option<Box<dyn Fn(f32)>>,
+    colors: [[f32; 4]; 3], // empty, filled, preview
+
+    stars: Vec<Star>,
+    screen_dim: (u32, u32),
+    input: InputState,
+    enabled: bool,
+}
+
+impl Rating{
+    /// Create a row of `count` empty stars centred on `pos`
+    pub fn new(pos: [f32; 2], count: usize, star_size: f32, renderer: &Renderer) -> Self{
+        let texture = Rc::new(Texture::from_rgba(&star_pixels(STAR_RESOLUTION), (STAR_RESOLUTION, STAR_RESOLUTION), "Star", &renderer.device, &renderer.queue));
+        let colors = [[0.75, 0.75, 0.75, 1.0], [1.0, 0.76, 0.1, 1.0], [1.0, 0.87, 0.5, 1.0]];
+        let stars = (0..count).map(|_| {
+            let mut empty = Quad::new([0.0, 0.0], [star_size, star_size], colors[0], &renderer.device);
+            let mut fill = Quad::new([0.0, 0.0], [star_size, star_size], colors[1], &renderer.device);
+            empty.set_texture(Some(texture.clone()));
+            fill.set_texture(Some(texture.clone()));
+            Star{ empty, fill }
+        }).collect();
+        let mut rating = Self{
+            pos,
+            star_size,
+            spacing: star_size * 0.2,
+            value: 0.0,
+            preview: None,
+            half_stars: false,
+            read_only: false,
+            callback: None,
+            colors,
+
+            stars,
+            screen_dim: (0, 0),
+            input: InputState::new(),
+            enabled: true,
+        };
+        rating.place();
+        rating
+    }
+
+    /// The picked rating, from 0 to the number of stars
+    pub fn get_value(&self) -> f32{
+        self.value
+    }
+
+    /// Set the rating without running the callback. Unless the rating is read only, it's rounded to a whole star
+    /// (or half a star, in half star mode).
+    pub fn set_value(&mut self, value: f32){
+        let value = value.clamp(0.0, self.stars.len() as f32);
+        self.value = match (self.read_only, self.half_stars){
+            (true, _) => value,
+            (false, true) => (value * 2.0).round() / 2.0,
+            (false, false) => value.round(),
+        };
+        self.update_colors();
+    }
+
+    /// The rating being previewed under the cursor, if it's over the stars
+    pub fn get_preview(&self) -> Option<f32>{
+        self.preview
+    }
+
+    /// Pick ratings in half stars, rather than whole ones
+    pub fn set_half_stars(&mut self, half_stars: bool){
+        self.half_stars = half_stars;
+        self.set_value(self.value);
+    }
+
+    /// Stop the rating from being picked, so it only shows its value
+    pub fn set_read_only(&mut self, read_only: bool){
+        self.read_only = read_only;
+        self.preview = None;
+        self.update_colors();
+    }
+
+    pub fn get_star_count(&self) -> usize{
+        self.stars.len()
+    }
+
+    /// Set the function to run with the new rating when one is picked
+    pub fn set_callback(&mut self, callback: Option<Box<dyn Fn(f32)>>){
+        self.callback = callback;
+    }
+
+    /// Set the colours of empty stars, filled stars, and the stars previewing a rating
+    pub fn set_colors(&mut self, empty: [f32; 4], filled: [f32; 4], preview: [f32; 4]){
+        self.colors = [empty, filled, preview];
+        for star in self.stars.iter_mut(){
+            star.empty.set_color(empty);
+        }
+        self.update_colors();
+    }
+
+    /// Set the space between the stars, in pixels. It's a fifth of a star by default.
+    pub fn set_spacing(&mut self, spacing: f32){
+        self.spacing = spacing;
+        self.place();
+    }
+
+    /// Move the row of stars, so it's centred on `pos`
+    pub fn set_pos(&mut self, pos: [f32; 2]){
+        self.pos = pos;
+        self.place();
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+        self.preview = None;
+        self.update_colors();
+    }
+
+    /// The area taken up by the stars
+    pub fn get_rect(&self) -> Rect{
+        let count = self.stars.len() as f32;
+        let width = count * self.star_size + (count - 1.0).max(0.0) * self.spacing;
+        Rect::new(self.pos, [width, self.star_size])
+    }
+
+    fn place(&mut self){
+        let rect = self.get_rect();
+        let left = rect.pos[0] - rect.size[0] / 2.0;
+        for (i, star) in self.stars.iter_mut().enumerate(){
+            let pos = [left + i as f32 * (self.star_size + self.spacing) + self.star_size / 2.0, self.pos[1]];
+            star.empty.set_pos(pos);
+            star.fill.set_pos(pos);
+        }
+    }
+
+    // What's drawn - the preview if there is one, and the picked rating otherwise
+    fn shown(&self) -> f32{
+        self.preview.unwrap_or(self.value)
+    }
+
+    fn update_colors(&mut self){
+        let color = self.colors[if self.preview.is_some() { 2 } else { 1 }];
+        for star in self.stars.iter_mut(){
+            star.fill.set_color(color);
+        }
+    }
+
+    fn rating_under(&self, pos: [f32; 2]) -> Option<f32>{
+        let rect = self.get_rect();
+        if !rect.contains(pos){
+            return None;
+        }
+        let x = pos[0] - (rect.pos[0] - rect.size[0] / 2.0);
+        Some(rating_at(x, self.star_size, self.spacing, self.stars.len(), self.half_stars))
+    }
+}
+
+impl EventGUIComponent for Rating{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if !self.enabled{
+            return;
+        }
+        let shown = self.shown();
+        for (i, star) in self.stars.iter().enumerate(){
+            star.empty.render(render_pass);
+            let filled = (shown - i as f32).clamp(0.0, 1.0);
+            if filled >= 1.0{
+                star.fill.render(render_pass);
+            }else if filled > 0.0{
+                // Only the left part of a partly filled star gets drawn. The scissor rect is in window pixels,
+                // and has to stay inside the window.
+                let (width, height) = (self.screen_dim.0 as f32, self.screen_dim.1 as f32);
+                let pos = star.fill.get_pos();
+                let left = (pos[0] - self.star_size / 2.0 + width / 2.0).clamp(0.0, width);
+                let right = (pos[0] - self.star_size / 2.0 + filled * self.star_size + width / 2.0).clamp(0.0, width);
+                let top = (pos[1] - self.star_size / 2.0 + height / 2.0).clamp(0.0, height);
+                let bottom = (pos[1] + self.star_size / 2.0 + height / 2.0).clamp(0.0, height);
+                if right > left && bottom > top{
+                    render_pass.set_scissor_rect(left as u32, top as u32, (right - left).ceil() as u32, (bottom - top) as u32);
+                    star.fill.render(render_pass);
+                    render_pass.set_scissor_rect(0, 0, self.screen_dim.0, self.screen_dim.1);
+                }
+            }
+        }
+    }
+
+    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        let ui_event = self.input.handle_event(event, window);
+        if !self.enabled || self.read_only{
+            return;
+        }
+
+        match ui_event{
+            Some(UiEvent::CursorMoved{ pos, .. }) => {
+                self.preview = self.rating_under(pos);
+                self.update_colors();
+            }
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed: true, pos, .. })) => {
+                if let Some(value) = self.rating_under(pos){
+                    self.value = value;
+                    if let Some(callback) = &self.callback{
+                        callback(value);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.pos
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(self.get_rect())
+    }
+
+    fn handle_hover(&mut self, event: HoverEvent){
+        if event == HoverEvent::Exit{
+            self.preview = None;
+            self.update_colors();
+        }
+    }
+
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{
+        Some(self.get_rect().size)
+    }
+
+    fn arrange(&mut self, rect: Rect){
+        self.set_pos(rect.pos);
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.screen_dim = screen_dim;
+        for star in self.stars.iter_mut(){
+            star.empty.prepare(queue, screen_dim);
+            star.fill.prepare(queue, screen_dim);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Test that pointing along a row of stars picks the star under it, or half of it in half star mode
+    #[test]
+    fn rating_at_test(){
+        // Five 20 pixel stars with 5 pixels between them
+        assert_eq!(rating_at(-1.0, 20.0, 5.0, 5, false), 0.0);
+        assert_eq!(rating_at(2.0, 20.0, 5.0, 5, false), 1.0);
+        assert_eq!(rating_at(30.0, 20.0, 5.0, 5, false), 2.0);
+        // The gap after a star counts as that star
+        assert_eq!(rating_at(47.0, 20.0, 5.0, 5, false), 2.0);
+        assert_eq!(rating_at(500.0, 20.0, 5.0, 5, false), 5.0);
+
+        assert_eq!(rating_at(2.0, 20.0, 5.0, 5, true), 0.5);
+        assert_eq!(rating_at(38.0, 20.0, 5.0, 5, true), 2.0);
+        assert_eq!(rating_at(33.0, 20.0, 5.0, 5, true), 1.5);
+    }
+}
@@ -0,0 +1,114 @@
+//! This module contains the pull-to-refresh gesture. Dragging a scroll container past its top
+//! reveals a spinner, and letting go far enough runs a refresh callback. The content stays pulled
+//! down (keeping the spinner visible) until the refresh reports it is done.
+
+use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
+
+use super::{KineticScroll, LoadHandle};
+
+/// # PullToRefresh
+///
+/// Watches a `KineticScroll` (which needs a bouncing overscroll, so it can be pulled past the top).
+/// Call `update` every frame after updating the scroll, and draw the content at `get_offset` instead
+/// of the scroll's own offset.
+pub struct PullToRefresh{
+    threshold: f32, // how far to pull before letting go triggers a refresh
+    hold: f32, // how much room is kept for the spinner while refreshing
+    callback: Option<Box<dyn Fn(LoadHandle)>>,
+    pending: Option<Arc<AtomicBool>>,
+    pull: f32,
+    was_dragging: bool,
+    offset: f32,
+}
+
+impl PullToRefresh{
+    /// Create a new pull-to-refresh, triggering once pulled `threshold` pixels past the top
+    pub fn new(threshold: f32, callback: Option<Box<dyn Fn(LoadHandle)>>) -> Self{
+        Self{
+            threshold,
+            hold: threshold * 0.75,
+            callback,
+            pending: None,
+            pull: 0.0,
+            was_dragging: false,
+            offset: 0.0,
+        }
+    }
+
+    pub fn set_callback(&mut self, callback: Option<Box<dyn Fn(LoadHandle)>>){
+        self.callback = callback;
+    }
+
+    pub fn set_threshold(&mut self, threshold: f32){
+        self.threshold = threshold;
+    }
+
+    /// Set how much room is kept at the top for the spinner while refreshing
+    pub fn set_hold(&mut self, hold: f32){
+        self.hold = hold;
+    }
+
+    /// True while the refresh callback hasn't finished yet
+    pub fn is_refreshing(&mut self) -> bool{
+        if let Some(done) = &self.pending{
+            if done.load(Ordering::SeqCst){
+                self.pending = None;
+            }
+        }
+        self.pending.is_some()
+    }
+
+    /// How far the pull has got towards triggering a refresh, from 0 to 1.
+    /// Useful for fading in or growing the spinner while pulling.
+    pub fn get_progress(&self) -> f32{
+        if self.pending.is_some(){
+            return 1.0;
+        }
+        (self.pull / self.threshold).clamp(0.0, 1.0)
+    }
+
+    /// How much space is revealed above the content, where the spinner goes
+    pub fn get_reveal(&self) -> f32{
+        (-self.offset).max(0.0)
+    }
+
+    /// The offset to draw the content at. While refreshing, this keeps room for the spinner.
+    pub fn get_offset(&self) -> f32{
+        self.offset
+    }
+
+    /// Check the scroll for a finished pull. Returns true if a refresh was started.
+    pub fn update(&mut self, scroll: &KineticScroll) -> bool{
+        let refreshing = self.is_refreshing();
+        let dragging = scroll.is_dragging();
+        let mut started = false;
+
+        if dragging{
+            self.pull = (-scroll.get_overscroll()).max(0.0);
+        }else if self.was_dragging{
+            // We just let go, so check if we pulled far enough
+            if !refreshing && self.pull >= self.threshold{
+                if let Some(callback) = &self.callback{
+                    let done = Arc::new(AtomicBool::new(false));
+                    self.pending = Some(done.clone());
+                    callback(LoadHandle::new(done));
+                    started = true;
+                }
+            }
+            self.pull = 0.0;
+        }
+        self.was_dragging = dragging;
+
+        self.offset = if self.pending.is_some(){
+            scroll.get_offset().min(-self.hold)
+        }else{
+            scroll.get_offset()
+        };
+        started
+    }
+
+    /// True while the content is still held open by a refresh
+    pub fn is_animating(&self) -> bool{
+        self.pending.is_some()
+    }
+}
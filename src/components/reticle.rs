@@ -0,0 +1,245 @@
+//! This module contains the `Reticle` component, a crosshair whose lines spread apart and change colour with a value
+//! like a weapon's inaccuracy.
+
+use std::any::Any;
+
+use crate::rendering::{Quad, Renderer};
+
+use super::{EventGUIComponent, Rect};
+
+// How quickly the shown spread catches up with the one that was set - the fraction of the way left covered each
+// second, roughly
+const SPREAD_RESPONSE: f32 = 20.0;
+
+/// What a `Reticle`'s four arms look like
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ReticleStyle{
+    /// Lines pointing in at the middle
+    Lines,
+    /// A square dot for each arm
+    Dots,
+}
+
+/// Where a reticle's four arms go around its middle (up, right, down and left), `gap` pixels out from it.
+/// Dots are as long as they are thick.
+pub(crate) fn reticle_arms(gap: f32, length: f32, thickness: f32, style: ReticleStyle) -> [Rect; 4]{
+    let length = if style == ReticleStyle::Dots { thickness } else { length };
+    let out = gap + length / 2.0;
+    [
+        Rect::new([0.0, -out], [thickness, length]),
+        Rect::new([out, 0.0], [length, thickness]),
+        Rect::new([0.0, out], [thickness, length]),
+        Rect::new([-out, 0.0], [length, thickness]),
+    ]
+}
+
+/// # Reticle
+///
+/// A crosshair made of four arms around a point (the middle of the screen by default), with an optional dot in the
+/// middle. The spread, from 0 to 1, is set every frame from something like a weapon's inaccuracy or the player's
+/// speed - the arms move out from `min_gap` to `max_gap` pixels as it goes up, and their colour fades from the calm
+/// colour to the spread one. The arms ease over to a new spread rather than jumping (unless `set_spread_immediate`
+/// is used), so a spread that's set in steps still moves smoothly.
+///
+/// The reticle is meant to go over everything else, so it belongs in the overlay layer
+/// (`Layout::add_overlay_component`) of the topmost layout, like a HUD. It never takes input or gets hovered.
+pub struct Reticle{
+    pos: [f32; 2],
+    spread: f32,
+    shown: f32,
+    gap: [f32; 2], // at no spread and full spread
+    length: f32,
+    thickness: f32,
+    style: ReticleStyle,
+    colors: [[f32; 4]; 2], // at no spread and full spread
+
+    arms: Vec<Quad>,
+    dot: Quad,
+    show_dot: bool,
+    enabled: bool,
+}
+
+impl Reticle{
+    /// Create a reticle in the middle of the screen, with lines `length` pixels long
+    pub fn new(length: f32, thickness: f32, renderer: &Renderer) -> Self{
+        let colors = [[1.0, 1.0, 1.0, 0.9], [1.0, 0.35, 0.3, 0.9]];
+        let mut reticle = Self{
+            pos: [0.0, 0.0],
+            spread: 0.0,
+            shown: 0.0,
+            gap: [4.0, 24.0],
+            length,
+            thickness,
+            style: ReticleStyle::Lines,
+            colors,
+
+            arms: (0..4).map(|_| Quad::new([0.0, 0.0], [0.0, 0.0], colors[0], &renderer.device)).collect(),
+            dot: Quad::new([0.0, 0.0], [thickness, thickness], colors[0], &renderer.device),
+            show_dot: false,
+            enabled: true,
+        };
+        reticle.update_quads();
+        reticle
+    }
+
+    /// Set how spread out the reticle is, from 0 (tight, and the calm colour) to 1 (the widest, and the spread
+    /// colour). The arms ease over to it.
+    pub fn set_spread(&mut self, spread: f32){
+        self.spread = spread.clamp(0.0, 1.0);
+    }
+
+    /// Set the spread without easing over to it
+    pub fn set_spread_immediate(&mut self, spread: f32){
+        self.set_spread(spread);
+        self.shown = self.spread;
+        self.update_quads();
+    }
+
+    pub fn get_spread(&self) -> f32{
+        self.spread
+    }
+
+    /// The spread the arms are showing, which lags behind `get_spread` while it eases over
+    pub fn get_shown_spread(&self) -> f32{
+        self.shown
+    }
+
+    /// Set how far the arms are from the middle at no spread and at full spread, in pixels
+    pub fn set_gap(&mut self, min_gap: f32, max_gap: f32){
+        self.gap = [min_gap, max_gap];
+        self.update_quads();
+    }
+
+    /// Set how long and how thick the arms are
+    pub fn set_line_size(&mut self, length: f32, thickness: f32){
+        self.length = length;
+        self.thickness = thickness;
+        self.update_quads();
+    }
+
+    pub fn set_style(&mut self, style: ReticleStyle){
+        self.style = style;
+        self.update_quads();
+    }
+
+    pub fn get_style(&self) -> ReticleStyle{
+        self.style
+    }
+
+    /// Show or hide a dot in the middle, as big as the arms are thick
+    pub fn set_center_dot(&mut self, show: bool){
+        self.show_dot = show;
+    }
+
+    /// Set the colour at no spread and at full spread. Spreads in between get a mix of the two.
+    pub fn set_colors(&mut self, calm: [f32; 4], spread: [f32; 4]){
+        self.colors = [calm, spread];
+        self.update_quads();
+    }
+
+    /// Move the reticle, like onto where a gun is actually pointing. It's in the middle of the screen by default.
+    pub fn set_pos(&mut self, pos: [f32; 2]){
+        self.pos = pos;
+        self.update_quads();
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+    }
+
+    fn update_quads(&mut self){
+        let gap = self.gap[0] + (self.gap[1] - self.gap[0]) * self.shown;
+        let mut color = [0.0; 4];
+        for (channel, value) in color.iter_mut().enumerate(){
+            *value = self.colors[0][channel] + (self.colors[1][channel] - self.colors[0][channel]) * self.shown;
+        }
+        for (quad, arm) in self.arms.iter_mut().zip(reticle_arms(gap, self.length, self.thickness, self.style).iter()){
+            quad.set_pos([self.pos[0] + arm.pos[0], self.pos[1] + arm.pos[1]]);
+            quad.set_size(arm.size);
+            quad.set_color(color);
+        }
+        self.dot.set_pos(self.pos);
+        self.dot.set_size([self.thickness, self.thickness]);
+        self.dot.set_color(color);
+    }
+}
+
+impl EventGUIComponent for Reticle{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.enabled{
+            for arm in self.arms.iter(){
+                arm.render(render_pass);
+            }
+            if self.show_dot{
+                self.dot.render(render_pass);
+            }
+        }
+    }
+
+    fn handle_event_callback(&mut self, _event: &winit::event::Event<()>, _window: &winit::window::Window){}
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.pos
+    }
+
+    fn update(&mut self, dt: f32){
+        if self.shown == self.spread{
+            return;
+        }
+        let step = (self.spread - self.shown) * (SPREAD_RESPONSE * dt).min(1.0);
+        // Snap the last little bit, so the reticle stops animating
+        self.shown = if (self.spread - self.shown - step).abs() < 0.001 { self.spread } else { self.shown + step };
+        self.update_quads();
+    }
+
+    fn is_animating(&self) -> bool{
+        self.enabled && self.shown != self.spread
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        for arm in self.arms.iter_mut(){
+            arm.prepare(queue, screen_dim);
+        }
+        self.dot.prepare(queue, screen_dim);
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Test that a reticle's arms go out from the middle in each direction, starting from the gap
+    #[test]
+    fn reticle_arms_test(){
+        let arms = reticle_arms(4.0, 10.0, 2.0, ReticleStyle::Lines);
+        assert_eq!(arms[0], Rect::new([0.0, -9.0], [2.0, 10.0]));
+        assert_eq!(arms[1], Rect::new([9.0, 0.0], [10.0, 2.0]));
+        assert_eq!(arms[2], Rect::new([0.0, 9.0], [2.0, 10.0]));
+        assert_eq!(arms[3], Rect::new([-9.0, 0.0], [10.0, 2.0]));
+
+        // Dots are square, whatever the length
+        let dots = reticle_arms(4.0, 10.0, 2.0, ReticleStyle::Dots);
+        assert_eq!(dots[1], Rect::new([5.0, 0.0], [2.0, 2.0]));
+    }
+}
@@ -0,0 +1,547 @@
+//! This module contains `RichText`, text made of spans in different colours, sizes and fonts, with icons, images
+//! and clickable links inline between them, and `InlineImages`, a set of images that `:shortcodes:` in text turn into.
+
+use std::{any::Any, collections::HashMap, rc::Rc};
+
+use wgpu_glyph::{GlyphCruncher, ab_glyph::{Font, PxScale, ScaleFont}};
+
+use crate::{input::{EventPhase, EventResponse, InputState, MouseButton, MouseEvent, UiEvent}, rendering::{Quad, TextBrush, Texture}};
+
+use super::{EventGUIComponent, GUIComponent, Rect};
+
+// The character that holds an image's place in the text, stretched sideways to the image's width and never drawn
+const PLACEHOLDER: &str = "0";
+
+enum RichSpan{
+    Text{ text: String, color: [f32; 4], scale: f32, font: Option<wgpu_glyph::FontId>, link: Option<Box<dyn Fn()>> },
+    Image{ texture: Rc<Texture>, scale: f32 },
+}
+
+// An image laid out in the text
+struct InlineImage{
+    span: usize,
+    quad: Quad,
+    offset: [f32; 2], // the centre, from the top left of the text
+    size: [f32; 2],
+}
+
+/// # GlyphHit
+///
+/// A glyph of some `RichText`, as found by `RichText::glyph_at`. The rect is the glyph's advance across and the
+/// font's ascent to descent down, in the same space as the rich text's position, so the glyphs of a line sit
+/// edge to edge without gaps.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GlyphHit{
+    /// The span the glyph is in, counting from 0 in the order they were added
+    pub span: usize,
+    /// Where the glyph's character starts in the span's text, in bytes
+    pub byte_index: usize,
+    pub rect: Rect,
+}
+
+/// # InlineImages
+///
+/// Images to put in text in place of shortcodes, like emoji (`:smile:`) or item icons (`:gold_coin:`). Fill one
+/// up once and build `RichText` from it with `RichText::from_shortcodes`. The textures are shared, so the same
+/// image can show up in lots of text without being loaded again.
+#[derive(Default)]
+pub struct InlineImages{
+    images: HashMap<String, Rc<Texture>>,
+}
+
+impl InlineImages{
+    pub fn new() -> Self{
+        Self::default()
+    }
+
+    /// Add an image for a shortcode, given without the colons
+    pub fn add(&mut self, name: &str, texture: Texture){
+        self.images.insert(name.to_string(), Rc::new(texture));
+    }
+
+    pub fn remove(&mut self, name: &str){
+        self.images.remove(name);
+    }
+
+    pub fn contains(&self, name: &str) -> bool{
+        self.images.contains_key(name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<Rc<Texture>>{
+        self.images.get(name).cloned()
+    }
+}
+
+/// Find everything that looks like a shortcode in some text - a name made of letters, digits, `_`, `-` and `+`
+/// between two colons - as byte ranges, colons included
+pub(crate) fn find_shortcodes(text: &str) -> Vec<(usize, usize)>{
+    let is_name = |c: char| c.is_alphanumeric() || c == '_' || c == '-' || c == '+';
+    let mut found = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices(){
+        match (c, start){
+            (':', Some(open)) if i > open + 1 => {
+                found.push((open, i + 1));
+                start = None;
+            }
+            (':', _) => start = Some(i),
+            (c, Some(_)) if !is_name(c) => start = None,
+            _ => {}
+        }
+    }
+    found
+}
+
+/// # RichText
+///
+/// Text made of spans, each with its own colour, size and font, that wraps as one paragraph. Built up in order:
+///
+/// ```ignore
+/// let text = RichText::new(20.0, [-200.0, 0.0])
+///     .with_text("Picked up ")
+///     .with_image(sword_texture, 1.0)
+///     .with_styled_text(" Iron Sword", [0.3, 0.5, 1.0, 1.0], 1.0, None);
+/// ```
+///
+/// Images (and icons from an icon font, see `with_icon`) sit in the line like any other character, and wrap with
+/// the text around them. They're sized relative to the text - at a scale of 1.0 an image is as tall as the text
+/// size, keeping its aspect ratio - and an image taller than the text makes its line taller. Like labels, the
+/// position is the top left of the text.
+///
+/// Every glyph's bounds are kept once the text is laid out, so spans can be hit-tested (`glyph_at`, `span_at`)
+/// and outlined (`span_rects`) even when they wrap over several lines. Links (`with_link`) use this to take
+/// clicks - add the rich text with `add_event_component` for them to work, rather than `add_component`.
+pub struct RichText{
+    spans: Vec<RichSpan>,
+    size: f32,
+    pos: [f32; 2],
+    max_width: Option<f32>,
+
+    images: Vec<InlineImage>,
+    placeholder_scales: Vec<PxScale>, // the scale of each image's placeholder, in the same order as `images`
+    glyphs: Vec<GlyphHit>, // from the top left of the text, in layout order
+    text_size: [f32; 2],
+    link_hover_color: [f32; 4],
+    hovered_link: Option<usize>,
+    pressed_link: Option<usize>,
+    input: InputState,
+    dirty: bool,
+    screen_offset: [f32; 2],
+    enabled: bool,
+}
+
+impl RichText{
+    /// Create empty rich text, with the size the spans are relative to
+    pub fn new(size: f32, pos: [f32; 2]) -> Self{
+        Self{
+            spans: Vec::new(),
+            size,
+            pos,
+            max_width: None,
+
+            images: Vec::new(),
+            placeholder_scales: Vec::new(),
+            glyphs: Vec::new(),
+            text_size: [0.0, 0.0],
+            link_hover_color: [0.1, 0.2, 0.6, 1.0],
+            hovered_link: None,
+            pressed_link: None,
+            input: InputState::new(),
+            dirty: true,
+            screen_offset: [0.0, 0.0],
+            enabled: true,
+        }
+    }
+
+    /// Build rich text from a string, swapping any `:shortcodes:` found in `images` for their images. Shortcodes
+    /// that aren't in `images` are left as they are.
+    pub fn from_shortcodes(text: &str, images: &InlineImages, size: f32, pos: [f32; 2]) -> Self{
+        let mut rich_text = Self::new(size, pos);
+        let mut last = 0;
+        for (start, end) in find_shortcodes(text){
+            if let Some(texture) = images.get(&text[start + 1..end - 1]){
+                if start > last{
+                    rich_text = rich_text.with_text(&text[last..start]);
+                }
+                rich_text.spans.push(RichSpan::Image{ texture, scale: 1.0 });
+                last = end;
+            }
+        }
+        if last < text.len(){
+            rich_text = rich_text.with_text(&text[last..]);
+        }
+        rich_text
+    }
+
+    /// Add black text at the full size, in the default font
+    pub fn with_text(self, text: &str) -> Self{
+        self.with_styled_text(text, [0.0, 0.0, 0.0, 1.0], 1.0, None)
+    }
+
+    /// Add text with a colour, a size relative to the rich text's, and a font (`None` for the default)
+    pub fn with_styled_text(mut self, text: &str, color: [f32; 4], scale: f32, font: Option<wgpu_glyph::FontId>) -> Self{
+        self.spans.push(RichSpan::Text{ text: text.to_string(), color, scale, font, link: None });
+        self.dirty = true;
+        self
+    }
+
+    /// Add a link, which changes to the link hover colour under the cursor and runs its callback when clicked
+    pub fn with_link(mut self, text: &str, color: [f32; 4], callback: Box<dyn Fn()>) -> Self{
+        self.spans.push(RichSpan::Text{ text: text.to_string(), color, scale: 1.0, font: None, link: Some(callback) });
+        self.dirty = true;
+        self
+    }
+
+    /// Add an icon from an icon font (see `IconSet`)
+    pub fn with_icon(self, codepoint: char, font: wgpu_glyph::FontId, color: [f32; 4]) -> Self{
+        self.with_styled_text(&codepoint.to_string(), color, 1.0, Some(font))
+    }
+
+    /// Add an image, `scale` times as tall as the text
+    pub fn with_image(mut self, texture: Rc<Texture>, scale: f32) -> Self{
+        self.spans.push(RichSpan::Image{ texture, scale });
+        self.dirty = true;
+        self
+    }
+
+    /// Remove every span
+    pub fn clear(&mut self){
+        self.spans.clear();
+        self.images.clear();
+        self.glyphs.clear();
+        self.hovered_link = None;
+        self.pressed_link = None;
+        self.dirty = true;
+    }
+
+    pub fn set_pos(&mut self, pos: [f32; 2]){
+        self.pos = pos;
+    }
+
+    /// Wrap the text onto new lines once it gets wider than a number of pixels. `None` never wraps.
+    pub fn set_max_width(&mut self, max_width: Option<f32>){
+        self.max_width = max_width;
+        self.dirty = true;
+    }
+
+    /// How much space the text took up the last time it was laid out
+    pub fn get_size(&self) -> [f32; 2]{
+        self.text_size
+    }
+
+    /// Set the colour links change to under the cursor
+    pub fn set_link_hover_color(&mut self, color: [f32; 4]){
+        self.link_hover_color = color;
+    }
+
+    /// The glyph under a point, if there is one. Only known once the text has been laid out.
+    pub fn glyph_at(&self, pos: [f32; 2]) -> Option<GlyphHit>{
+        self.get_glyphs().into_iter().find(|glyph| glyph.rect.contains(pos))
+    }
+
+    /// The span under a point, if there is one
+    pub fn span_at(&self, pos: [f32; 2]) -> Option<usize>{
+        self.glyph_at(pos).map(|glyph| glyph.span)
+    }
+
+    /// Every glyph that was laid out, in order, placed where the text is now
+    pub fn get_glyphs(&self) -> Vec<GlyphHit>{
+        self.glyphs.iter().map(|glyph| GlyphHit{
+            rect: Rect::new([self.pos[0] + glyph.rect.pos[0], self.pos[1] + glyph.rect.pos[1]], glyph.rect.size),
+            ..*glyph
+        }).collect()
+    }
+
+    /// The bounds of a span, one rect for each line it's on
+    pub fn span_rects(&self, span: usize) -> Vec<Rect>{
+        // A glyph further left than the one before it has wrapped onto the next line
+        let mut lines: Vec<([f32; 2], [f32; 2])> = Vec::new(); // the top left and bottom right of each line
+        let mut last_left = f32::INFINITY;
+        for glyph in self.get_glyphs().into_iter().filter(|glyph| glyph.span == span){
+            let rect = glyph.rect;
+            let (min, max) = ([rect.pos[0] - rect.size[0] / 2.0, rect.pos[1] - rect.size[1] / 2.0], [rect.pos[0] + rect.size[0] / 2.0, rect.pos[1] + rect.size[1] / 2.0]);
+            match lines.last_mut(){
+                Some(line) if min[0] >= last_left => *line = ([line.0[0].min(min[0]), line.0[1].min(min[1])], [line.1[0].max(max[0]), line.1[1].max(max[1])]),
+                _ => lines.push((min, max)),
+            }
+            last_left = min[0];
+        }
+        lines.into_iter().map(|(min, max)| Rect::new([(min[0] + max[0]) / 2.0, (min[1] + max[1]) / 2.0], [max[0] - min[0], max[1] - min[1]])).collect()
+    }
+
+    /// Whether a span is a link
+    pub fn is_link(&self, span: usize) -> bool{
+        matches!(self.spans.get(span), Some(RichSpan::Text{ link: Some(_), .. }))
+    }
+
+    /// The link span under the cursor, if there is one
+    pub fn get_hovered_link(&self) -> Option<usize>{
+        self.hovered_link
+    }
+
+    fn link_at(&self, pos: [f32; 2]) -> Option<usize>{
+        self.span_at(pos).filter(|&span| self.is_link(span))
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+        self.hovered_link = None;
+        self.pressed_link = None;
+    }
+
+    // The spans as a section of text, images swapped for their (invisible) placeholders
+    fn section(&self, screen_position: (f32, f32)) -> wgpu_glyph::Section<'_>{
+        let mut images = self.placeholder_scales.iter();
+        let text = self.spans.iter().enumerate().map(|(i, span)| match span{
+            RichSpan::Text{ text, color, scale, font, .. } => wgpu_glyph::Text::new(text)
+                .with_color(if self.hovered_link == Some(i) { self.link_hover_color } else { *color })
+                .with_scale(PxScale::from(self.size * scale))
+                .with_font_id(font.unwrap_or_default()),
+            RichSpan::Image{ .. } => wgpu_glyph::Text::new(PLACEHOLDER)
+                .with_color([0.0, 0.0, 0.0, 0.0])
+                .with_scale(images.next().copied().unwrap_or_else(|| PxScale::from(self.size))),
+        }).collect();
+        wgpu_glyph::Section {
+            screen_position,
+            bounds: (self.max_width.unwrap_or(f32::INFINITY), f32::INFINITY),
+            text,
+            ..wgpu_glyph::Section::default()
+        }
+    }
+}
+
+impl GUIComponent for RichText{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if !self.enabled{
+            return;
+        }
+        for image in self.images.iter(){
+            if let RichSpan::Image{ texture, .. } = &self.spans[image.span]{
+                render_pass.set_bind_group(3, &texture.bind_group, &[]);
+                image.quad.render(render_pass);
+            }
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if self.enabled{
+            let position = (self.pos[0] + self.screen_offset[0], self.pos[1] + self.screen_offset[1]);
+            brush.queue(self.section(position));
+        }
+    }
+
+    // Images need a quad each, which needs the device
+    fn rebuild(&mut self, device: &wgpu::Device) -> bool{
+        let image_spans: Vec<usize> = self.spans.iter().enumerate()
+            .filter(|(_, span)| matches!(span, RichSpan::Image{ .. }))
+            .map(|(i, _)| i)
+            .collect();
+        if !self.images.iter().map(|image| image.span).eq(image_spans.iter().copied()){
+            self.images = image_spans.into_iter().map(|span| InlineImage{
+                span,
+                quad: Quad::new([0.0, 0.0], [0.0, 0.0], [1.0, 1.0, 1.0, 1.0], device),
+                offset: [0.0, 0.0],
+                size: [0.0, 0.0],
+            }).collect();
+            self.dirty = true;
+        }
+        false
+    }
+
+    // Stretch each image's placeholder to the image's width, then see where the placeholders ended up
+    fn layout_text(&mut self, brush: &mut TextBrush){
+        if !self.dirty{
+            return;
+        }
+        self.dirty = false;
+
+        let font = &brush.fonts()[0];
+        let placeholder_id = font.glyph_id(char::from(PLACEHOLDER.as_bytes()[0]));
+        let unit_advance = font.h_advance_unscaled(placeholder_id) / font.height_unscaled();
+        let descent = font.as_scaled(self.size).descent();
+        let size = self.size;
+        for image in self.images.iter_mut(){
+            if let RichSpan::Image{ texture, scale } = &self.spans[image.span]{
+                let height = size * scale;
+                image.size = [height * texture.size.0 as f32 / texture.size.1.max(1) as f32, height];
+            }
+        }
+        self.placeholder_scales = self.images.iter().map(|image| PxScale{
+            x: image.size[0] / unit_advance.max(f32::EPSILON),
+            y: image.size[1].max(size),
+        }).collect();
+
+        // Every glyph spans its advance across and the font's ascent to descent down, so there are no gaps to miss
+        let section = self.section((0.0, 0.0));
+        let fonts = brush.fonts().to_vec();
+        let glyphs = brush.glyphs(section.clone()).map(|glyph| {
+            let position = glyph.glyph.position;
+            let font = fonts[glyph.font_id.0].as_scaled(glyph.glyph.scale);
+            let (width, height) = (font.h_advance(glyph.glyph.id), font.ascent() - font.descent());
+            let top = position.y - font.ascent();
+            GlyphHit{
+                span: glyph.section_index,
+                byte_index: glyph.byte_index,
+                rect: Rect::new([position.x + width / 2.0, top + height / 2.0], [width, height]),
+            }
+        }).collect();
+        let placed: Vec<_> = brush.glyphs(section).map(|glyph| (glyph.section_index, glyph.glyph.position)).collect();
+        for image in self.images.iter_mut(){
+            // Images sit on the baseline, reaching down as far as the text does
+            if let Some((_, position)) = placed.iter().find(|(span, _)| *span == image.span){
+                let bottom = position.y - descent;
+                image.offset = [position.x + image.size[0] / 2.0, bottom - image.size[1] / 2.0];
+            }
+        }
+        self.glyphs = glyphs;
+
+        let section = self.section((0.0, 0.0));
+        self.text_size = match brush.glyph_bounds(section){
+            Some(bounds) => [bounds.max.x.max(0.0), bounds.max.y.max(0.0)],
+            None => [0.0, 0.0],
+        };
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.screen_offset = [(screen_dim.0 / 2) as f32, (screen_dim.1 / 2) as f32];
+        for image in self.images.iter_mut(){
+            image.quad.set_pos([self.pos[0] + image.offset[0], self.pos[1] + image.offset[1]]);
+            image.quad.set_size(image.size);
+            image.quad.prepare(queue, screen_dim);
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.pos
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(Rect::new([self.pos[0] + self.text_size[0] / 2.0, self.pos[1] + self.text_size[1] / 2.0], self.text_size))
+    }
+}
+
+// The same rich text, with its links working
+impl EventGUIComponent for RichText{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        GUIComponent::render(self, render_pass);
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        GUIComponent::render_text(self, brush);
+    }
+
+    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        if !self.enabled{
+            return;
+        }
+
+        match self.input.handle_event(event, window){
+            Some(UiEvent::CursorMoved{ pos, .. }) => self.hovered_link = self.link_at(pos),
+            Some(UiEvent::CursorLeft) => self.hovered_link = None,
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed: true, pos, .. })) => self.pressed_link = self.link_at(pos),
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed: false, pos, .. })) => {
+                // Only a click that starts and ends on the same link counts
+                let pressed = self.pressed_link.take();
+                if pressed.is_some() && pressed == self.link_at(pos){
+                    if let Some(RichSpan::Text{ link: Some(callback), .. }) = pressed.and_then(|span| self.spans.get(span)){
+                        callback();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Clicks on links are ours, so whatever is under the text doesn't get them too
+    fn handle_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window, phase: EventPhase) -> EventResponse{
+        if phase != EventPhase::Bubble{
+            return EventResponse::Continue;
+        }
+        let was_pressed = self.pressed_link.is_some();
+        self.handle_event_callback(event, window);
+        let click = matches!(event, winit::event::Event::WindowEvent{ event: winit::event::WindowEvent::MouseInput{ .. }, .. });
+        if click && (was_pressed || self.pressed_link.is_some()){
+            EventResponse::Consumed
+        }else{
+            EventResponse::Continue
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.pos
+    }
+
+    fn rebuild(&mut self, device: &wgpu::Device) -> bool{
+        GUIComponent::rebuild(self, device)
+    }
+
+    fn layout_text(&mut self, brush: &mut TextBrush){
+        GUIComponent::layout_text(self, brush);
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        GUIComponent::prepare(self, queue, screen_dim);
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        GUIComponent::get_bounds(self)
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Test which parts of some text get picked out as shortcodes
+    #[test]
+    fn shortcode_test(){
+        assert_eq!(find_shortcodes("hi :smile: there"), vec![(3, 10)]);
+        assert_eq!(find_shortcodes(":gold_coin::+1:"), vec![(0, 11), (11, 15)]);
+        // Empty names, spaces and unclosed colons aren't shortcodes
+        assert!(find_shortcodes("a :: b").is_empty());
+        assert!(find_shortcodes("note: two words: here").is_empty());
+        assert!(find_shortcodes(":open").is_empty());
+        // A stray colon before a shortcode doesn't swallow it
+        assert_eq!(find_shortcodes("Loot: :gem:"), vec![(6, 11)]);
+    }
+}
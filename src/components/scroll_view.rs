@@ -0,0 +1,281 @@
+//! This module contains the `ScrollView`, a container that shows part of a child that's taller than it,
+//! scrolled with the mouse wheel or a scrollbar, and clipped to the container's bounds.
+
+use std::any::Any;
+
+use crate::{input::{EventPhase, EventResponse, InputCategory, InputState, MouseEvent, UiEvent}, rendering::{Renderer, TextBrush}};
+
+use super::{EventGUIComponent, KineticScroll, Orientation, Rect, Scrollbar};
+
+/// # ScrollView
+///
+/// Holds one child (usually a `StackPanel` or a `ListView`) and scrolls it up and down inside its rectangle.
+/// The child is measured with as much height as it wants, and a scrollbar appears down the right hand side
+/// when it doesn't fit.
+///
+/// Quads are clipped to the view with a scissor rect. Text is drawn by the glyph brush after everything else,
+/// so it isn't clipped. The clipping is done in window pixels, so it doesn't follow a layout's content scale.
+///
+/// Mouse presses and wheel movement outside the view don't reach the child, as the part of it under the cursor
+/// is hidden.
+pub struct ScrollView{
+    rect: Rect,
+    content: Box<dyn EventGUIComponent>,
+    content_size: [f32; 2],
+    scroll: KineticScroll,
+    scrollbar: Scrollbar,
+
+    input: InputState,
+    screen_dim: (u32, u32),
+    enabled: bool,
+}
+
+impl ScrollView{
+    /// Create a view filling `rect`, scrolled to the top of its child
+    pub fn new(rect: Rect, content: Box<dyn EventGUIComponent>, renderer: &Renderer) -> Self{
+        let mut view = Self{
+            rect,
+            content,
+            content_size: [0.0, 0.0],
+            scroll: KineticScroll::new(),
+            scrollbar: Scrollbar::new(rect.pos, rect.size[1], Orientation::Vertical, renderer),
+
+            input: InputState::new(),
+            screen_dim: (0, 0),
+            enabled: true,
+        };
+        view.layout_content();
+        view
+    }
+
+    /// Borrow the child, for example to downcast it
+    pub fn borrow_content_mut(&mut self) -> &mut Box<dyn EventGUIComponent>{
+        &mut self.content
+    }
+
+    /// Borrow the scrolling physics, to change the overscroll effect or scroll somewhere from code
+    pub fn borrow_scroll_mut(&mut self) -> &mut KineticScroll{
+        &mut self.scroll
+    }
+
+    /// Borrow the scrollbar, to change its colours
+    pub fn borrow_scrollbar_mut(&mut self) -> &mut Scrollbar{
+        &mut self.scrollbar
+    }
+
+    /// How far down the child is scrolled, in pixels
+    pub fn get_offset(&self) -> f32{
+        self.scroll.get_offset()
+    }
+
+    pub fn set_rect(&mut self, rect: Rect){
+        self.rect = rect;
+        self.layout_content();
+    }
+
+    pub fn get_rect(&self) -> Rect{
+        self.rect
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+    }
+
+    // The child only needs a scrollbar if it's taller than the view
+    fn is_scrollable(&self) -> bool{
+        self.content_size[1] > self.rect.size[1]
+    }
+
+    // Place the scrollbar, and move the child to the scroll offset
+    fn layout_content(&mut self){
+        let bar = if self.is_scrollable() { self.scrollbar.get_thickness() } else { 0.0 };
+        let left = self.rect.pos[0] - self.rect.size[0] / 2.0;
+        let top = self.rect.pos[1] - self.rect.size[1] / 2.0;
+
+        self.scroll.set_content(self.content_size[1], self.rect.size[1]);
+        self.scrollbar.set_content(self.content_size[1], self.rect.size[1]);
+        self.scrollbar.set_value(self.scroll.get_offset().max(0.0));
+        self.scrollbar.set_length(self.rect.size[1]);
+        self.scrollbar.set_pos([left + self.rect.size[0] - bar / 2.0, self.rect.pos[1]]);
+
+        let width = self.content_size[0].max(self.rect.size[0] - bar);
+        let height = self.content_size[1];
+        self.content.arrange(Rect::new([left + width / 2.0, top + height / 2.0 - self.scroll.get_offset()], [width, height]));
+    }
+
+    // Scroll with the wheel and the scrollbar, returning true if the event scrolled the view
+    fn own_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window) -> bool{
+        let mut scrolled = false;
+        match self.input.handle_event(event, window){
+            Some(UiEvent::Scroll{ delta, pos, .. }) if self.rect.contains(pos) && self.is_scrollable() => {
+                self.scroll.scroll_by(-delta[1]);
+                scrolled = true;
+            }
+            Some(UiEvent::MouseInput(MouseEvent{ pressed: true, pos, .. })) if self.rect.contains(pos) => {
+                // A click on the view stops it gliding
+                self.scroll.set_offset(self.scroll.get_offset());
+            }
+            _ => {}
+        }
+
+        if self.is_scrollable(){
+            let value = self.scrollbar.get_value();
+            self.scrollbar.handle_event_callback(event, window);
+            if self.scrollbar.get_value() != value{
+                self.scroll.set_offset(self.scrollbar.get_value());
+                scrolled = true;
+            }
+        }
+        if scrolled{
+            self.layout_content();
+        }
+        scrolled
+    }
+
+    // Presses and the wheel only go to the child when the cursor is over the part that can be seen
+    fn reaches_content(&self, event: &winit::event::Event<()>) -> bool{
+        use winit::event::{ElementState, Event, WindowEvent};
+
+        match event{
+            Event::WindowEvent{ event: WindowEvent::MouseInput{ state: ElementState::Pressed, .. }, .. }
+            | Event::WindowEvent{ event: WindowEvent::MouseWheel{ .. }, .. } => self.rect.contains(self.input.get_cursor_pos()),
+            _ => true,
+        }
+    }
+}
+
+impl EventGUIComponent for ScrollView{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if !self.enabled || self.screen_dim.0 == 0 || self.screen_dim.1 == 0{
+            return;
+        }
+
+        // The scissor rect is in window pixels, and has to stay inside the window
+        let (width, height) = (self.screen_dim.0 as f32, self.screen_dim.1 as f32);
+        let left = (self.rect.pos[0] - self.rect.size[0] / 2.0 + width / 2.0).clamp(0.0, width);
+        let top = (self.rect.pos[1] - self.rect.size[1] / 2.0 + height / 2.0).clamp(0.0, height);
+        let right = (self.rect.pos[0] + self.rect.size[0] / 2.0 + width / 2.0).clamp(0.0, width);
+        let bottom = (self.rect.pos[1] + self.rect.size[1] / 2.0 + height / 2.0).clamp(0.0, height);
+        if right > left && bottom > top{
+            render_pass.set_scissor_rect(left as u32, top as u32, (right - left) as u32, (bottom - top) as u32);
+            self.content.render(render_pass);
+            render_pass.set_scissor_rect(0, 0, self.screen_dim.0, self.screen_dim.1);
+        }
+
+        if self.is_scrollable(){
+            self.scrollbar.render(render_pass);
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if self.enabled{
+            self.content.render_text(brush);
+        }
+    }
+
+    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        if !self.enabled{
+            return;
+        }
+
+        if self.reaches_content(event){
+            self.content.handle_event_callback(event, window);
+        }
+        self.own_event(event, window);
+    }
+
+    /// The child gets events first, so scrollable things inside it get to use the wheel before we do
+    fn handle_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window, phase: EventPhase) -> EventResponse{
+        if !self.enabled{
+            return EventResponse::Continue;
+        }
+        if self.reaches_content(event) && self.content.handle_event(event, window, phase) == EventResponse::Consumed{
+            return EventResponse::Consumed;
+        }
+        if phase != EventPhase::Bubble{
+            return EventResponse::Continue;
+        }
+
+        let dragging = self.scrollbar.is_dragging();
+        if self.own_event(event, window) || (dragging && InputCategory::of(event) == Some(InputCategory::Mouse)){
+            EventResponse::Consumed
+        }else{
+            EventResponse::Continue
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.rect.pos
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(self.rect)
+    }
+
+    fn update(&mut self, dt: f32){
+        let offset = self.scroll.get_offset();
+        self.scroll.update(dt);
+        if self.scroll.get_offset() != offset{
+            self.layout_content();
+        }
+        self.content.update(dt);
+    }
+
+    fn is_animating(&self) -> bool{
+        self.scroll.is_animating() || self.content.is_animating()
+    }
+
+    fn rebuild(&mut self, device: &wgpu::Device) -> bool{
+        self.content.rebuild(device)
+    }
+
+    fn layout_text(&mut self, brush: &mut TextBrush){
+        self.content.layout_text(brush);
+    }
+
+    /// The view is always the size it was given, the child gets measured with unlimited height
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{
+        let size = self.content.measure([self.rect.size[0], f32::INFINITY])
+            .or_else(|| self.content.get_bounds().map(|bounds| bounds.size))
+            .unwrap_or([0.0, 0.0]);
+        // Leave room for the scrollbar if it's going to be needed
+        self.content_size = if size[1] > self.rect.size[1]{
+            self.content.measure([self.rect.size[0] - self.scrollbar.get_thickness(), f32::INFINITY]).unwrap_or(size)
+        }else{
+            size
+        };
+        Some(self.rect.size)
+    }
+
+    fn arrange(&mut self, rect: Rect){
+        self.set_rect(rect);
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.screen_dim = screen_dim;
+        self.content.prepare(queue, screen_dim);
+        self.scrollbar.prepare(queue, screen_dim);
+    }
+}
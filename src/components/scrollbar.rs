@@ -0,0 +1,307 @@
+//! This module contains the `Scrollbar` component. It can be used on its own, or as part of a
+//! scrollable container. The scrollbar only keeps track of a value (how far we've scrolled),
+//! it's up to the owner to move its content when the value changes.
+
+use std::any::Any;
+
+use winit::event::{ElementState, Event, MouseButton, WindowEvent};
+
+use crate::rendering::{Quad, Renderer};
+
+use super::{EventGUIComponent, Orientation};
+
+/// # Scrollbar
+///
+/// A track with a draggable thumb. The size of the thumb shows how much of the content is visible,
+/// and its position shows how far the content is scrolled.
+///
+/// Dragging the thumb scrolls the content, and clicking on the track moves a page (the size of the viewport)
+/// towards the cursor. Every time the value is changed by the user, the callback is run with the new value.
+pub struct Scrollbar{
+    orientation: Orientation,
+    pos: [f32; 2], // centre of the track
+    length: f32,
+    thickness: f32,
+    min_thumb_length: f32,
+
+    track: Quad,
+    thumb: Quad,
+    thumb_colors: [[f32; 4]; 3], // normal, hovered, dragged
+
+    content_size: f32,
+    viewport_size: f32,
+    value: f32,
+
+    cursor_pos: [f64; 2],
+    thumb_hovered: bool,
+    drag_offset: Option<f32>, // where on the thumb we grabbed it, while dragging
+    callback: Option<Box<dyn Fn(f32)>>,
+    enabled: bool,
+}
+
+impl Scrollbar{
+    /// Create a new scrollbar. The position is the centre of the track, and the length is the length of the track (both in pixels).
+    pub fn new(pos: [f32; 2], length: f32, orientation: Orientation, renderer: &Renderer) -> Self{
+        let thickness = 12.0;
+        let thumb_colors = [[0.6, 0.6, 0.6, 1.0], [0.5, 0.5, 0.5, 1.0], [0.4, 0.4, 0.4, 1.0]];
+        let mut scrollbar = Self{
+            orientation,
+            pos,
+            length,
+            thickness,
+            min_thumb_length: 16.0,
+
+            track: Quad::new(pos, [0.0, 0.0], [0.9, 0.9, 0.9, 1.0], &renderer.device),
+            thumb: Quad::new(pos, [0.0, 0.0], thumb_colors[0], &renderer.device),
+            thumb_colors,
+
+            content_size: 1.0,
+            viewport_size: 1.0,
+            value: 0.0,
+
+            cursor_pos: [0.0, 0.0],
+            thumb_hovered: false,
+            drag_offset: None,
+            callback: None,
+            enabled: true,
+        };
+        scrollbar.update_quads();
+        scrollbar
+    }
+
+    /// Set the function to run when the user changes the value
+    pub fn set_callback(&mut self, callback: Option<Box<dyn Fn(f32)>>){
+        self.callback = callback;
+    }
+
+    /// Set the size of the content being scrolled, and how much of it fits in the view at once
+    pub fn set_content(&mut self, content_size: f32, viewport_size: f32){
+        self.content_size = content_size.max(0.0);
+        self.viewport_size = viewport_size.max(0.0);
+        self.value = self.value.min(self.get_max_value());
+        self.update_quads();
+    }
+
+    /// Set the value without running the callback. It gets clamped between 0 and `get_max_value`
+    pub fn set_value(&mut self, value: f32){
+        self.value = value.max(0.0).min(self.get_max_value());
+        self.update_quads();
+    }
+
+    pub fn get_value(&self) -> f32{
+        self.value
+    }
+
+    /// The furthest the content can be scrolled
+    pub fn get_max_value(&self) -> f32{
+        (self.content_size - self.viewport_size).max(0.0)
+    }
+
+    pub fn get_viewport_size(&self) -> f32{
+        self.viewport_size
+    }
+
+    /// True while the thumb is held by the mouse
+    pub fn is_dragging(&self) -> bool{
+        self.drag_offset.is_some()
+    }
+
+    pub fn set_pos(&mut self, pos: [f32; 2]){
+        self.pos = pos;
+        self.update_quads();
+    }
+
+    pub fn set_length(&mut self, length: f32){
+        self.length = length;
+        self.update_quads();
+    }
+
+    pub fn get_length(&self) -> f32{
+        self.length
+    }
+
+    pub fn set_thickness(&mut self, thickness: f32){
+        self.thickness = thickness;
+        self.update_quads();
+    }
+
+    pub fn get_thickness(&self) -> f32{
+        self.thickness
+    }
+
+    /// Set the smallest the thumb can get, so it stays grabbable with very long content
+    pub fn set_min_thumb_length(&mut self, min_thumb_length: f32){
+        self.min_thumb_length = min_thumb_length;
+        self.update_quads();
+    }
+
+    pub fn set_track_color(&mut self, color: [f32; 4]){
+        self.track.set_color(color);
+    }
+
+    /// Set the colour of the thumb when idle, hovered and dragged
+    pub fn set_thumb_colors(&mut self, normal: [f32; 4], hovered: [f32; 4], dragged: [f32; 4]){
+        self.thumb_colors = [normal, hovered, dragged];
+        self.update_thumb_color();
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+        self.drag_offset = None;
+    }
+
+    // Length of the thumb along the track
+    fn thumb_length(&self) -> f32{
+        if self.content_size <= self.viewport_size || self.content_size <= 0.0{
+            return self.length;
+        }
+        (self.length * self.viewport_size / self.content_size).max(self.min_thumb_length).min(self.length)
+    }
+
+    // Position of a point along the track, from the start of the track
+    fn along_track(&self, point: [f64; 2], screen_dim: (u32, u32)) -> f32{
+        match self.orientation{
+            Orientation::Horizontal => point[0] as f32 - (screen_dim.0 / 2) as f32 - (self.pos[0] - self.length / 2.0),
+            Orientation::Vertical => point[1] as f32 - (screen_dim.1 / 2) as f32 - (self.pos[1] - self.length / 2.0),
+        }
+    }
+
+    // Where the thumb starts along the track
+    fn thumb_start(&self) -> f32{
+        let max_value = self.get_max_value();
+        if max_value <= 0.0{
+            return 0.0;
+        }
+        (self.length - self.thumb_length()) * self.value / max_value
+    }
+
+    // Change the value from user input, running the callback
+    fn change_value(&mut self, value: f32){
+        let old_value = self.value;
+        self.set_value(value);
+        if self.value != old_value{
+            if let Some(callback) = &self.callback{
+                callback(self.value);
+            }
+        }
+    }
+
+    fn update_thumb_color(&mut self){
+        let color = if self.is_dragging(){
+            self.thumb_colors[2]
+        }else if self.thumb_hovered{
+            self.thumb_colors[1]
+        }else{
+            self.thumb_colors[0]
+        };
+        self.thumb.set_color(color);
+    }
+
+    fn update_quads(&mut self){
+        let thumb_length = self.thumb_length();
+        let thumb_centre = -self.length / 2.0 + self.thumb_start() + thumb_length / 2.0;
+
+        match self.orientation{
+            Orientation::Horizontal => {
+                self.track.set_size([self.length, self.thickness]);
+                self.thumb.set_size([thumb_length, self.thickness]);
+                self.thumb.set_pos([self.pos[0] + thumb_centre, self.pos[1]]);
+            }
+            Orientation::Vertical => {
+                self.track.set_size([self.thickness, self.length]);
+                self.thumb.set_size([self.thickness, thumb_length]);
+                self.thumb.set_pos([self.pos[0], self.pos[1] + thumb_centre]);
+            }
+        }
+        self.track.set_pos(self.pos);
+    }
+}
+
+impl EventGUIComponent for Scrollbar{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.enabled{
+            self.track.render(render_pass);
+            self.thumb.render(render_pass);
+        }
+    }
+
+    fn handle_event_callback(&mut self, event: &Event<()>, window: &winit::window::Window){
+        if !self.enabled{
+            return;
+        }
+        let screen_dim = (window.inner_size().width, window.inner_size().height);
+
+        match event{
+            Event::WindowEvent{
+                ref event,
+                window_id,
+            } if window_id == &window.id() => {
+                match event{
+                    WindowEvent::CursorMoved{ position, .. } => {
+                        self.cursor_pos = [position.x, position.y];
+                        self.thumb_hovered = self.thumb.contains(self.cursor_pos, screen_dim);
+
+                        if let Some(offset) = self.drag_offset{
+                            let travel = self.length - self.thumb_length();
+                            if travel > 0.0{
+                                let start = self.along_track(self.cursor_pos, screen_dim) - offset;
+                                self.change_value(start / travel * self.get_max_value());
+                            }
+                        }
+                        self.update_thumb_color();
+                    }
+                    WindowEvent::MouseInput{ button: MouseButton::Left, state: ElementState::Pressed, .. } => {
+                        if self.thumb.contains(self.cursor_pos, screen_dim){
+                            // Remember where we grabbed the thumb, so it doesn't jump to the cursor
+                            self.drag_offset = Some(self.along_track(self.cursor_pos, screen_dim) - self.thumb_start());
+                        }else if self.track.contains(self.cursor_pos, screen_dim){
+                            // Move a page towards the cursor
+                            if self.along_track(self.cursor_pos, screen_dim) < self.thumb_start(){
+                                self.change_value(self.value - self.viewport_size);
+                            }else{
+                                self.change_value(self.value + self.viewport_size);
+                            }
+                        }
+                        self.update_thumb_color();
+                    }
+                    WindowEvent::MouseInput{ button: MouseButton::Left, state: ElementState::Released, .. } => {
+                        self.drag_offset = None;
+                        self.update_thumb_color();
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.pos
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.track.prepare(queue, screen_dim);
+        self.thumb.prepare(queue, screen_dim);
+    }
+}
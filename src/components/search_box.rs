@@ -0,0 +1,414 @@
+//! This module contains the `SearchBox` component, a text box with a dropdown of suggestions for what's been typed
+//! so far.
+
+use std::any::Any;
+
+use wgpu_glyph::VerticalAlign;
+
+use crate::{input::{EventPhase, EventResponse, InputCategory, InputState, Key, KeyEvent, MouseButton, MouseEvent, UiEvent}, rendering::{Quad, Renderer, TextBrush}};
+
+use super::{EventGUIComponent, Label, Rect, TextGUIComponent, TextInput};
+
+// How far the suggestions' text is in from the left of the dropdown
+const ROW_PADDING: f32 = 6.0;
+
+type SuggestFn = Box<dyn Fn(&str) -> Vec<String>>;
+type SelectCallback = Box<dyn Fn(&str)>;
+
+/// The items matching a query, ignoring case, for the usual kind of suggestion list. Items starting with the query
+/// come first, then the ones containing it elsewhere, each in their original order. An empty query matches nothing.
+pub fn filter_suggestions(items: &[&str], query: &str) -> Vec<String>{
+    let query = query.trim().to_lowercase();
+    if query.is_empty(){
+        return Vec::new();
+    }
+    let lowered: Vec<String> = items.iter().map(|item| item.to_lowercase()).collect();
+    let starting = items.iter().zip(lowered.iter()).filter(|(_, lower)| lower.starts_with(&query));
+    let containing = items.iter().zip(lowered.iter()).filter(|(_, lower)| !lower.starts_with(&query) && lower.contains(&query));
+    starting.chain(containing).map(|(item, _)| item.to_string()).collect()
+}
+
+// A row of the dropdown
+struct SuggestionRow{
+    quad: Quad,
+    label: Label,
+}
+
+/// # SearchBox
+///
+/// A `TextInput` that shows suggestions in a dropdown underneath while it's focused. Whenever the text changes, the
+/// suggest function is called with it and returns the suggestions to show (`filter_suggestions` covers the common
+/// case of filtering a fixed list), of which the first few are shown.
+///
+/// Clicking a suggestion, or picking one with the up and down arrow keys and pressing enter, puts it in the box,
+/// closes the dropdown and runs the select callback. Escape closes the dropdown (and unfocuses the box).
+///
+/// ```ignore
+/// let countries = ["France", "Germany", "Finland", "Greece"];
+/// let mut search = SearchBox::new(Rect::new([0.0, -100.0], [240.0, 32.0]), 16.0, &renderer);
+/// search.set_suggest(Some(Box::new(move |query| filter_suggestions(&countries, query))));
+/// search.set_on_select(Some(Box::new(|country| println!("Picked {}", country))));
+/// ```
+///
+/// The dropdown is drawn as part of the search box, so it covers the components added before it, but not the ones
+/// added after. Adding the search box last (or to the overlay layer) keeps it on top.
+pub struct SearchBox{
+    field: TextInput,
+    bounds: Rect,
+    suggest: Option<SuggestFn>,
+    on_select: Option<SelectCallback>,
+    suggestions: Vec<String>,
+    max_suggestions: usize,
+    highlighted: Option<usize>,
+    dismissed: bool, // closed with escape or by picking a suggestion, until the text changes again
+    colors: [[f32; 4]; 2], // row, highlighted row
+
+    border: Quad,
+    rows: Vec<SuggestionRow>,
+    text_size: f32,
+
+    input: InputState,
+    enabled: bool,
+}
+
+impl SearchBox{
+    /// Create an empty search box filling `bounds`, showing up to 6 suggestions
+    pub fn new(bounds: Rect, text_size: f32, renderer: &Renderer) -> Self{
+        let mut search = Self{
+            field: TextInput::new(bounds, text_size, renderer),
+            bounds,
+            suggest: None,
+            on_select: None,
+            suggestions: Vec::new(),
+            max_suggestions: 6,
+            highlighted: None,
+            dismissed: false,
+            colors: [[1.0, 1.0, 1.0, 1.0], [0.85, 0.9, 1.0, 1.0]],
+
+            border: Quad::new([0.0, 0.0], [0.0, 0.0], [0.5, 0.5, 0.5, 1.0], &renderer.device),
+            rows: Vec::new(),
+            text_size,
+
+            input: InputState::new(),
+            enabled: true,
+        };
+        search.set_bounds(bounds);
+        search
+    }
+
+    /// Set the function that turns what's been typed into suggestions, and run it on the current text
+    pub fn set_suggest(&mut self, suggest: Option<SuggestFn>){
+        self.suggest = suggest;
+        self.update_suggestions();
+    }
+
+    /// Set the function to run with a suggestion when it's picked
+    pub fn set_on_select(&mut self, callback: Option<SelectCallback>){
+        self.on_select = callback;
+    }
+
+    /// Set how many suggestions the dropdown shows at most
+    pub fn set_max_suggestions(&mut self, max_suggestions: usize){
+        self.max_suggestions = max_suggestions;
+        self.update_suggestions();
+    }
+
+    /// The suggestions for the current text, including any that don't fit in the dropdown
+    pub fn get_suggestions(&self) -> &[String]{
+        &self.suggestions
+    }
+
+    /// The suggestion picked with the arrow keys or hovered, if there is one
+    pub fn get_highlighted(&self) -> Option<usize>{
+        self.highlighted
+    }
+
+    /// Whether the dropdown is showing
+    pub fn is_open(&self) -> bool{
+        self.enabled && self.field.is_focused() && !self.dismissed && self.shown_count() > 0
+    }
+
+    pub fn get_text(&self) -> &str{
+        self.field.get_text()
+    }
+
+    /// Replace the text, updating the suggestions. This doesn't run the select callback.
+    pub fn set_text<S: Into<String>>(&mut self, text: S){
+        self.field.set_text(text);
+        self.update_suggestions();
+    }
+
+    /// Put a suggestion in the box, close the dropdown and run the select callback
+    pub fn select(&mut self, index: usize){
+        let suggestion = match self.suggestions.get(index){
+            Some(suggestion) => suggestion.clone(),
+            None => return,
+        };
+        self.field.set_text(suggestion.as_str());
+        self.dismissed = true;
+        self.highlighted = None;
+        if let Some(callback) = &self.on_select{
+            callback(&suggestion);
+        }
+    }
+
+    pub fn set_bounds(&mut self, bounds: Rect){
+        self.bounds = bounds;
+        self.field.set_bounds(bounds);
+        self.place_rows();
+    }
+
+    /// Set the colours of the dropdown's rows, normally and when highlighted, and of its border
+    pub fn set_colors(&mut self, row: [f32; 4], highlighted: [f32; 4], border: [f32; 4]){
+        self.colors = [row, highlighted];
+        self.border.set_color(border);
+        self.update_rows();
+    }
+
+    /// Borrow the text box, for example to change its placeholder or colours
+    pub fn borrow_field_mut(&mut self) -> &mut TextInput{
+        &mut self.field
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+        self.field.enable();
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+        self.field.disable();
+    }
+
+    // How many suggestions fit in the dropdown
+    fn shown_count(&self) -> usize{
+        self.suggestions.len().min(self.max_suggestions)
+    }
+
+    fn update_suggestions(&mut self){
+        self.suggestions = match &self.suggest{
+            Some(suggest) => suggest(self.field.get_text()),
+            None => Vec::new(),
+        };
+        self.highlighted = None;
+        self.dismissed = false;
+        self.update_rows();
+    }
+
+    // Put the suggestions' text on the rows, and colour in the highlighted one
+    fn update_rows(&mut self){
+        for (i, row) in self.rows.iter_mut().enumerate(){
+            row.label.set_text(self.suggestions.get(i).map(|text| text.as_str()).unwrap_or(""));
+            row.quad.set_color(self.colors[(self.highlighted == Some(i)) as usize]);
+        }
+    }
+
+    // Each row is as tall as the text box, with the border round all of them
+    fn place_rows(&mut self){
+        let [width, height] = self.bounds.size;
+        let top = self.bounds.pos[1] + height / 2.0;
+        for (i, row) in self.rows.iter_mut().enumerate(){
+            row.quad.set_pos([self.bounds.pos[0], top + height * (i as f32 + 0.5)]);
+            row.quad.set_size([width - 2.0, height]);
+        }
+        let rows = self.shown_count().min(self.rows.len()) as f32;
+        self.border.set_pos([self.bounds.pos[0], top + height * rows / 2.0]);
+        self.border.set_size([width, height * rows + 1.0]);
+    }
+
+    fn row_at(&self, pos: [f32; 2]) -> Option<usize>{
+        if !self.is_open(){
+            return None;
+        }
+        (0..self.shown_count().min(self.rows.len())).find(|&i| {
+            let quad = &self.rows[i].quad;
+            Rect::new(quad.get_pos(), quad.get_size()).contains(pos)
+        })
+    }
+
+    fn set_highlighted(&mut self, highlighted: Option<usize>){
+        if highlighted != self.highlighted{
+            self.highlighted = highlighted;
+            self.update_rows();
+        }
+    }
+}
+
+impl EventGUIComponent for SearchBox{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if !self.enabled{
+            return;
+        }
+        self.field.render(render_pass);
+        if self.is_open(){
+            self.border.render(render_pass);
+            for row in self.rows.iter().take(self.shown_count()){
+                row.quad.render(render_pass);
+            }
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if !self.enabled{
+            return;
+        }
+        EventGUIComponent::render_text(&self.field, brush);
+        if self.is_open(){
+            for row in self.rows.iter().take(self.shown_count()){
+                TextGUIComponent::render_text(&row.label, brush);
+            }
+        }
+    }
+
+    fn layout_text(&mut self, brush: &mut TextBrush){
+        self.field.layout_text(brush);
+    }
+
+    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        if !self.enabled{
+            return;
+        }
+
+        let open = self.is_open();
+        match self.input.handle_event(event, window){
+            Some(UiEvent::CursorMoved{ pos, .. }) if open => {
+                if let Some(row) = self.row_at(pos){
+                    self.set_highlighted(Some(row));
+                }
+            }
+            // A click on a suggestion picks it, and doesn't reach the text box (which would lose focus)
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed, pos, .. })) if self.row_at(pos).is_some() => {
+                if pressed{
+                    if let Some(row) = self.row_at(pos){
+                        self.select(row);
+                    }
+                }
+                return;
+            }
+            Some(UiEvent::Key(KeyEvent{ key, pressed: true, .. })) if open => {
+                let count = self.shown_count();
+                match key{
+                    Key::Down => {
+                        self.set_highlighted(Some(self.highlighted.map_or(0, |i| (i + 1) % count)));
+                        return;
+                    }
+                    Key::Up => {
+                        self.set_highlighted(Some(self.highlighted.map_or(count - 1, |i| (i + count - 1) % count)));
+                        return;
+                    }
+                    Key::Enter if self.highlighted.is_some() => {
+                        if let Some(row) = self.highlighted{
+                            self.select(row);
+                        }
+                        return;
+                    }
+                    Key::Escape => {
+                        self.dismissed = true;
+                        self.highlighted = None;
+                        self.update_rows();
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+
+        let text = self.field.get_text().to_string();
+        self.field.handle_event_callback(event, window);
+        if self.field.get_text() != text{
+            self.update_suggestions();
+        }
+    }
+
+    /// Keyboard input is consumed while focused, and clicks on the dropdown
+    fn handle_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window, phase: EventPhase) -> EventResponse{
+        if phase != EventPhase::Bubble{
+            return EventResponse::Continue;
+        }
+        let keyboard = self.enabled && self.field.is_focused() && InputCategory::of(event) == Some(InputCategory::Keyboard);
+        let click = matches!(event, winit::event::Event::WindowEvent{ event: winit::event::WindowEvent::MouseInput{ .. }, .. });
+        let on_row = self.enabled && click && self.row_at(self.input.get_cursor_pos()).is_some();
+        self.handle_event_callback(event, window);
+        if keyboard || on_row { EventResponse::Consumed } else { EventResponse::Continue }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.bounds.pos
+    }
+
+    /// The bounds take in the dropdown while it's open
+    fn get_bounds(&self) -> Option<Rect>{
+        if !self.is_open(){
+            return Some(self.bounds);
+        }
+        let rows = self.shown_count() as f32;
+        let height = self.bounds.size[1] * (rows + 1.0);
+        let top = self.bounds.pos[1] - self.bounds.size[1] / 2.0;
+        Some(Rect::new([self.bounds.pos[0], top + height / 2.0], [self.bounds.size[0], height]))
+    }
+
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{
+        Some(self.bounds.size)
+    }
+
+    fn arrange(&mut self, rect: Rect){
+        if rect != self.bounds{
+            self.set_bounds(rect);
+        }
+    }
+
+    /// Make rows for the most suggestions the dropdown can show
+    fn rebuild(&mut self, device: &wgpu::Device) -> bool{
+        let rows = self.max_suggestions.min(self.suggestions.len().max(self.rows.len()));
+        if self.rows.len() < rows{
+            let text_size = self.text_size;
+            self.rows.resize_with(rows, || {
+                let mut label = Label::new("", text_size, [0.0, 0.0]);
+                label.align_vertical(VerticalAlign::Center);
+                SuggestionRow{
+                    quad: Quad::new([0.0, 0.0], [0.0, 0.0], [1.0, 1.0, 1.0, 1.0], device),
+                    label,
+                }
+            });
+            self.update_rows();
+        }
+        self.place_rows();
+        false
+    }
+
+    fn update(&mut self, dt: f32){
+        self.field.update(dt);
+    }
+
+    fn is_animating(&self) -> bool{
+        self.field.is_animating()
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.field.prepare(queue, screen_dim);
+        self.border.prepare(queue, screen_dim);
+        let left = self.bounds.pos[0] - self.bounds.size[0] / 2.0;
+        for row in self.rows.iter_mut(){
+            row.quad.prepare(queue, screen_dim);
+            row.label.set_pos([left + ROW_PADDING, row.quad.get_pos()[1]], screen_dim);
+        }
+    }
+}
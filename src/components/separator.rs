@@ -0,0 +1,222 @@
+//! This module contains the `Separator`, a thin line between components, and the `Spacer`, an empty gap. Both take
+//! part in layout, so they're mostly for adding structure to a `StackPanel`.
+
+use std::any::Any;
+
+use crate::rendering::{Quad, Renderer};
+
+use super::{EventGUIComponent, Orientation, Rect};
+
+/// Where a separator's line goes inside the rectangle it was arranged into: centred, `thickness` pixels thick, and
+/// `length` pixels long, or as long as the rectangle is with `None`.
+pub(crate) fn separator_line(rect: Rect, orientation: Orientation, thickness: f32, length: Option<f32>) -> Rect{
+    match orientation{
+        Orientation::Horizontal => Rect::new(rect.pos, [length.unwrap_or(rect.size[0]), thickness]),
+        Orientation::Vertical => Rect::new(rect.pos, [thickness, length.unwrap_or(rect.size[1])]),
+    }
+}
+
+/// # Separator
+///
+/// A thin line, going across (`Orientation::Horizontal`) or down (`Orientation::Vertical`), centred on its position.
+/// In a stack it takes up its thickness plus a margin either side, and stretches across the stack unless it's been
+/// given a length. Horizontal separators go in vertical stacks, and vertical ones in horizontal stacks.
+pub struct Separator{
+    quad: Quad,
+    orientation: Orientation,
+    thickness: f32,
+    length: Option<f32>, // None stretches the line across the rectangle it's arranged into
+    margin: f32, // the space either side of the line
+    enabled: bool,
+}
+
+impl Separator{
+    /// Create a separator 1 pixel thick, centred on `pos`. Until it's arranged (or given a length) it has no length.
+    pub fn new(pos: [f32; 2], orientation: Orientation, renderer: &Renderer) -> Self{
+        let mut separator = Self{
+            quad: Quad::new(pos, [0.0, 0.0], [0.6, 0.6, 0.6, 1.0], &renderer.device),
+            orientation,
+            thickness: 1.0,
+            length: None,
+            margin: 4.0,
+            enabled: true,
+        };
+        separator.place(Rect::new(pos, [0.0, 0.0]));
+        separator
+    }
+
+    /// Set how many pixels thick the line is
+    pub fn set_thickness(&mut self, thickness: f32){
+        self.thickness = thickness;
+        self.place(Rect::new(self.quad.get_pos(), self.quad.get_size()));
+    }
+
+    /// Set how long the line is, or stretch it across the space it's given with `None` (the default)
+    pub fn set_length(&mut self, length: Option<f32>){
+        self.length = length;
+        self.place(Rect::new(self.quad.get_pos(), self.quad.get_size()));
+    }
+
+    /// Set the space left either side of the line in a stack. It's 4 pixels by default.
+    pub fn set_margin(&mut self, margin: f32){
+        self.margin = margin;
+    }
+
+    pub fn set_color(&mut self, color: [f32; 4]){
+        self.quad.set_color(color);
+    }
+
+    pub fn set_pos(&mut self, pos: [f32; 2]){
+        self.quad.set_pos(pos);
+    }
+
+    pub fn get_orientation(&self) -> Orientation{
+        self.orientation
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+    }
+
+    // Fit the line into a rectangle
+    fn place(&mut self, rect: Rect){
+        let line = separator_line(rect, self.orientation, self.thickness, self.length);
+        self.quad.set_pos(line.pos);
+        self.quad.set_size(line.size);
+    }
+}
+
+impl EventGUIComponent for Separator{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.enabled{
+            self.quad.render(render_pass);
+        }
+    }
+
+    fn handle_event_callback(&mut self, _event: &winit::event::Event<()>, _window: &winit::window::Window){}
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.quad.get_pos()
+    }
+
+    /// The thickness and margins along the stack, and the length (if there is one) across it
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{
+        let across = self.thickness + self.margin * 2.0;
+        let along = self.length.unwrap_or(0.0);
+        Some(match self.orientation{
+            Orientation::Horizontal => [along, across],
+            Orientation::Vertical => [across, along],
+        })
+    }
+
+    fn arrange(&mut self, rect: Rect){
+        self.place(rect);
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.quad.prepare(queue, screen_dim);
+    }
+}
+
+/// # Spacer
+///
+/// An empty gap of a fixed size, which draws nothing and ignores input. In a stack only its size along the stack
+/// matters, so `Spacer::new([0.0, 20.0])` is a 20 pixel gap in a vertical stack.
+pub struct Spacer{
+    rect: Rect,
+}
+
+impl Spacer{
+    /// Create a gap `size` pixels big
+    pub fn new(size: [f32; 2]) -> Self{
+        Self{
+            rect: Rect::new([0.0, 0.0], size),
+        }
+    }
+
+    pub fn set_size(&mut self, size: [f32; 2]){
+        self.rect.size = size;
+    }
+
+    pub fn get_size(&self) -> [f32; 2]{
+        self.rect.size
+    }
+
+    /// The spacer's rectangle from the last arrange pass
+    pub fn get_rect(&self) -> Rect{
+        self.rect
+    }
+}
+
+impl EventGUIComponent for Spacer{
+    fn render<'a, 'b>(&'a self, _render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {}
+
+    fn handle_event_callback(&mut self, _event: &winit::event::Event<()>, _window: &winit::window::Window){}
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        true
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.rect.pos
+    }
+
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{
+        Some(self.rect.size)
+    }
+
+    /// The spacer keeps its own size, so it doesn't grow when a stack stretches it across
+    fn arrange(&mut self, rect: Rect){
+        self.rect.pos = rect.pos;
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Test that a separator's line stretches across the rectangle it's given, unless it has a length
+    #[test]
+    fn separator_line_test(){
+        let rect = Rect::new([10.0, 20.0], [200.0, 9.0]);
+        assert_eq!(separator_line(rect, Orientation::Horizontal, 1.0, None), Rect::new([10.0, 20.0], [200.0, 1.0]));
+        assert_eq!(separator_line(rect, Orientation::Horizontal, 2.0, Some(50.0)), Rect::new([10.0, 20.0], [50.0, 2.0]));
+
+        let rect = Rect::new([0.0, 0.0], [9.0, 40.0]);
+        assert_eq!(separator_line(rect, Orientation::Vertical, 1.0, None), Rect::new([0.0, 0.0], [1.0, 40.0]));
+    }
+}
@@ -0,0 +1,331 @@
+//! This module contains the `Slider` component, a handle dragged along a track to pick a number from a range.
+
+use std::any::Any;
+
+use wgpu_glyph::{HorizontalAlign, VerticalAlign};
+
+use crate::{input::{EventPhase, EventResponse, InputCategory, InputState, MouseButton, MouseEvent, UiEvent}, rendering::{Quad, Renderer, TextBrush}};
+
+use super::{EventGUIComponent, Label, Rect, TextGUIComponent};
+
+// The space between the track and the value label, in pixels
+const LABEL_GAP: f32 = 12.0;
+
+/// Keep a value inside the range from `min` to `max` (either way round), snapped to a whole number of `step`s from
+/// `min` if there's a step. When the range isn't a whole number of steps long, a value near the far end snaps to the
+/// last step inside the range, rather than past it.
+pub(crate) fn snap_value(value: f32, min: f32, max: f32, step: Option<f32>) -> f32{
+    let (low, high) = if min <= max { (min, max) } else { (max, min) };
+    let value = value.max(low).min(high);
+    match step{
+        Some(step) => {
+            let steps = (value - min) / step;
+            let snapped = min + steps.round() * step;
+            if snapped < low || snapped > high { min + steps.trunc() * step } else { snapped }
+        }
+        None => value,
+    }
+}
+
+// The value for a cursor at `x`, along a track starting at `start` and `length` pixels long
+fn value_at(x: f32, start: f32, length: f32, min: f32, max: f32) -> f32{
+    let fraction = if length > 0.0 { ((x - start) / length).clamp(0.0, 1.0) } else { 0.0 };
+    min + fraction * (max - min)
+}
+
+/// # Slider
+///
+/// A horizontal slider. Pressing anywhere on it jumps the handle there, and dragging moves it, running the
+/// callback with the new value each time it changes. The drag carries on even if the cursor leaves the slider.
+///
+/// The current value can be shown in a label to the right of the track, see `show_value`.
+pub struct Slider{
+    rect: Rect, // the whole slider, the handle is as tall as this
+    min: f32,
+    max: f32,
+    step: Option<f32>,
+    value: f32,
+    callback: Option<Box<dyn Fn(f32)>>,
+
+    track: Quad,
+    fill: Quad, // the part of the track before the handle
+    handle: Quad,
+    label: Option<Label>,
+    decimals: usize,
+
+    input: InputState,
+    dragging: bool,
+    enabled: bool,
+}
+
+impl Slider{
+    /// Create a slider filling `rect`, going from `min` to `max` and starting at `value`
+    pub fn new(rect: Rect, min: f32, max: f32, value: f32, callback: Option<Box<dyn Fn(f32)>>, renderer: &Renderer) -> Self{
+        let mut slider = Self{
+            rect,
+            min,
+            max,
+            step: None,
+            value: min,
+            callback,
+
+            track: Quad::new(rect.pos, [rect.size[0], 4.0], [0.7, 0.7, 0.7, 1.0], &renderer.device),
+            fill: Quad::new(rect.pos, [0.0, 4.0], [0.2, 0.4, 0.8, 1.0], &renderer.device),
+            handle: Quad::new(rect.pos, [rect.size[1] / 2.0, rect.size[1]], [0.3, 0.3, 0.3, 1.0], &renderer.device),
+            label: None,
+            decimals: 0,
+
+            input: InputState::new(),
+            dragging: false,
+            enabled: true,
+        };
+        slider.set_value(value);
+        slider
+    }
+
+    /// Show the value in a label to the right of the track, with some number of decimal places
+    pub fn show_value(&mut self, text_size: f32, decimals: usize){
+        let mut label = Label::new("", text_size, [0.0, 0.0]);
+        label.align_vertical(VerticalAlign::Center);
+        label.align_horizontal(HorizontalAlign::Left);
+        self.label = Some(label);
+        self.decimals = decimals;
+        self.update_label();
+    }
+
+    /// Stop showing the value
+    pub fn hide_value(&mut self){
+        self.label = None;
+    }
+
+    pub fn get_value(&self) -> f32{
+        self.value
+    }
+
+    /// Move the handle to a value (snapped and clamped to the range). This doesn't run the callback.
+    pub fn set_value(&mut self, value: f32){
+        self.value = snap_value(value, self.min, self.max, self.step);
+        self.update_quads();
+        self.update_label();
+    }
+
+    /// Change the range, keeping the value inside it
+    pub fn set_range(&mut self, min: f32, max: f32){
+        self.min = min;
+        self.max = max;
+        self.set_value(self.value);
+    }
+
+    /// Only allow values that are a multiple of `step` away from the minimum, or any value with `None`
+    pub fn set_step(&mut self, step: Option<f32>){
+        self.step = step.filter(|step| *step > 0.0);
+        self.set_value(self.value);
+    }
+
+    /// Set the function to run when the value is dragged to something new
+    pub fn set_callback(&mut self, callback: Option<Box<dyn Fn(f32)>>){
+        self.callback = callback;
+    }
+
+    pub fn set_rect(&mut self, rect: Rect){
+        self.rect = rect;
+        self.track.set_pos(rect.pos);
+        self.track.set_size([rect.size[0], 4.0]);
+        self.handle.set_size([rect.size[1] / 2.0, rect.size[1]]);
+        self.update_quads();
+    }
+
+    /// Set the colours of the track, the filled part of the track and the handle
+    pub fn set_colors(&mut self, track: [f32; 4], fill: [f32; 4], handle: [f32; 4]){
+        self.track.set_color(track);
+        self.fill.set_color(fill);
+        self.handle.set_color(handle);
+    }
+
+    /// Borrow the value label, if it's shown, for example to change its font
+    pub fn borrow_label_mut(&mut self) -> Option<&mut Label>{
+        self.label.as_mut()
+    }
+
+    pub fn is_dragging(&self) -> bool{
+        self.dragging
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+        self.dragging = false;
+    }
+
+    // How far along the track the handle is, from 0 to 1
+    fn fraction(&self) -> f32{
+        if self.max == self.min{
+            0.0
+        }else{
+            (self.value - self.min) / (self.max - self.min)
+        }
+    }
+
+    // The handle stays inside the slider, so its centre only travels along part of the width
+    fn travel(&self) -> (f32, f32){
+        let handle_width = self.handle.get_size()[0];
+        (self.rect.pos[0] - self.rect.size[0] / 2.0 + handle_width / 2.0, (self.rect.size[0] - handle_width).max(0.0))
+    }
+
+    fn drag_to(&mut self, x: f32){
+        let (start, length) = self.travel();
+        let old_value = self.value;
+        self.set_value(value_at(x, start, length, self.min, self.max));
+        if self.value != old_value{
+            if let Some(callback) = &self.callback{
+                callback(self.value);
+            }
+        }
+    }
+
+    fn update_quads(&mut self){
+        let (start, length) = self.travel();
+        let x = start + self.fraction() * length;
+        let left = self.rect.pos[0] - self.rect.size[0] / 2.0;
+        self.handle.set_pos([x, self.rect.pos[1]]);
+        self.fill.set_pos([(left + x) / 2.0, self.rect.pos[1]]);
+        self.fill.set_size([x - left, 4.0]);
+    }
+
+    fn update_label(&mut self){
+        let text = format!("{:.*}", self.decimals, self.value);
+        if let Some(label) = &mut self.label{
+            label.set_text(text);
+        }
+    }
+}
+
+impl EventGUIComponent for Slider{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.enabled{
+            self.track.render(render_pass);
+            self.fill.render(render_pass);
+            self.handle.render(render_pass);
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if let (true, Some(label)) = (self.enabled, &self.label){
+            TextGUIComponent::render_text(label, brush);
+        }
+    }
+
+    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        if !self.enabled{
+            return;
+        }
+
+        match self.input.handle_event(event, window){
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed: true, pos, .. })) if self.rect.contains(pos) => {
+                self.dragging = true;
+                self.drag_to(pos[0]);
+            }
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed: false, .. })) => self.dragging = false,
+            Some(UiEvent::CursorMoved{ pos, .. }) if self.dragging => self.drag_to(pos[0]),
+            _ => {}
+        }
+    }
+
+    /// The mouse is ours while dragging, so components under the cursor don't react to the drag
+    fn handle_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window, phase: EventPhase) -> EventResponse{
+        if phase != EventPhase::Bubble{
+            return EventResponse::Continue;
+        }
+        let was_dragging = self.dragging;
+        self.handle_event_callback(event, window);
+        let mouse = InputCategory::of(event) == Some(InputCategory::Mouse);
+        if mouse && (was_dragging || self.dragging) { EventResponse::Consumed } else { EventResponse::Continue }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.rect.pos
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(self.rect)
+    }
+
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{
+        Some(self.rect.size)
+    }
+
+    fn arrange(&mut self, rect: Rect){
+        if rect != self.rect{
+            self.set_rect(rect);
+        }
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.track.prepare(queue, screen_dim);
+        self.fill.prepare(queue, screen_dim);
+        self.handle.prepare(queue, screen_dim);
+        if let Some(label) = &mut self.label{
+            label.set_pos([self.rect.pos[0] + self.rect.size[0] / 2.0 + LABEL_GAP, self.rect.pos[1]], screen_dim);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Test that values snap to the nearest step from the minimum, and stay inside the range
+    #[test]
+    fn snap_value_test(){
+        assert_eq!(snap_value(0.37, 0.0, 1.0, None), 0.37);
+        assert_eq!(snap_value(4.2, 1.0, 9.0, Some(2.0)), 5.0);
+        assert_eq!(snap_value(3.9, 1.0, 9.0, Some(2.0)), 3.0);
+        assert_eq!(snap_value(-5.0, 0.0, 10.0, Some(1.0)), 0.0);
+        assert_eq!(snap_value(50.0, 0.0, 10.0, None), 10.0);
+        // Not a number ends up at the bottom of the range
+        assert_eq!(snap_value(f32::NAN, 0.0, 10.0, Some(1.0)), 0.0);
+    }
+
+    /// Test that snapping never goes past the end of a range that isn't a whole number of steps long
+    #[test]
+    fn uneven_step_test(){
+        assert_eq!(snap_value(10.0, 0.0, 10.0, Some(3.0)), 9.0);
+        assert_eq!(snap_value(11.0, 0.0, 10.0, Some(3.0)), 9.0);
+        assert_eq!(snap_value(7.4, 0.0, 10.0, Some(3.0)), 6.0);
+        // Reversed ranges count their steps down from the minimum
+        assert_eq!(snap_value(-5.0, 10.0, 0.0, Some(3.0)), 1.0);
+        assert_eq!(snap_value(8.0, 10.0, 0.0, Some(3.0)), 7.0);
+    }
+
+    /// Test that the cursor's place along the track picks the value, stopping at the ends
+    #[test]
+    fn value_at_test(){
+        assert_eq!(value_at(50.0, 0.0, 100.0, 0.0, 10.0), 5.0);
+        assert_eq!(value_at(-20.0, 0.0, 100.0, 0.0, 10.0), 0.0);
+        assert_eq!(value_at(150.0, 0.0, 100.0, 0.0, 10.0), 10.0);
+        assert_eq!(value_at(25.0, 0.0, 100.0, 10.0, 0.0), 7.5);
+        // A track with no room to move along stays at the minimum
+        assert_eq!(value_at(25.0, 0.0, 0.0, 2.0, 10.0), 2.0);
+    }
+}
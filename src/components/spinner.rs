@@ -0,0 +1,133 @@
+//! This module contains the `Spinner` component, a ring of dots that fade one after the other
+//! to show that something is loading.
+
+use std::any::Any;
+
+use crate::rendering::{Quad, Renderer};
+
+use super::GUIComponent;
+
+/// # Spinner
+///
+/// A loading indicator. The dots are placed in a circle around the position, and the brightest dot
+/// goes round the circle once every `period` seconds.
+pub struct Spinner{
+    pos: [f32; 2],
+    radius: f32,
+    dot_size: f32,
+    color: [f32; 4],
+    period: f32,
+    phase: f32, // 0 to 1, how far round the circle we are
+    dots: Vec<Quad>,
+    enabled: bool,
+}
+
+impl Spinner{
+    /// Create a new spinner, with 8 dots going round once a second
+    pub fn new(pos: [f32; 2], radius: f32, renderer: &Renderer) -> Self{
+        let mut spinner = Self{
+            pos,
+            radius,
+            dot_size: radius / 3.0,
+            color: [0.3, 0.3, 0.3, 1.0],
+            period: 1.0,
+            phase: 0.0,
+            dots: (0..8).map(|_| Quad::new(pos, [0.0, 0.0], [0.0, 0.0, 0.0, 0.0], &renderer.device)).collect(),
+            enabled: true,
+        };
+        spinner.update_dots();
+        spinner
+    }
+
+    pub fn set_pos(&mut self, pos: [f32; 2]){
+        self.pos = pos;
+        self.update_dots();
+    }
+
+    pub fn set_radius(&mut self, radius: f32){
+        self.radius = radius;
+        self.dot_size = radius / 3.0;
+        self.update_dots();
+    }
+
+    pub fn set_color(&mut self, color: [f32; 4]){
+        self.color = color;
+        self.update_dots();
+    }
+
+    /// Set how many seconds it takes to go round once
+    pub fn set_period(&mut self, period: f32){
+        self.period = period.max(0.01);
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+    }
+
+    fn update_dots(&mut self){
+        let count = self.dots.len() as f32;
+        for (i, dot) in self.dots.iter_mut().enumerate(){
+            let angle = i as f32 / count * std::f32::consts::PI * 2.0;
+            dot.set_pos([self.pos[0] + angle.sin() * self.radius, self.pos[1] - angle.cos() * self.radius]);
+            dot.set_size([self.dot_size, self.dot_size]);
+
+            // How far behind the brightest dot this one is (0 is the brightest)
+            let behind = (self.phase - i as f32 / count).rem_euclid(1.0);
+            let mut color = self.color;
+            color[3] *= 1.0 - behind * 0.85;
+            dot.set_color(color);
+        }
+    }
+}
+
+impl GUIComponent for Spinner{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.enabled{
+            for dot in self.dots.iter(){
+                dot.render(render_pass);
+            }
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.pos
+    }
+
+    fn update(&mut self, dt: f32){
+        if self.enabled{
+            self.phase = (self.phase + dt / self.period).rem_euclid(1.0);
+            self.update_dots();
+        }
+    }
+
+    fn is_animating(&self) -> bool{
+        self.enabled
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        for dot in self.dots.iter_mut(){
+            dot.prepare(queue, screen_dim);
+        }
+    }
+}
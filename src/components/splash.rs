@@ -0,0 +1,334 @@
+//! This module contains the `SplashScreen` component, which shows logos one after the other, fading each one in
+//! and out, before the game or app proper starts.
+
+use std::{any::Any, rc::Rc};
+
+use crate::{input::{EventPhase, EventResponse, InputState, KeyEvent, MouseEvent, UiEvent}, layout::{Layout, LayoutCommands}, rendering::{Quad, Renderer, Texture}};
+
+use super::{EventGUIComponent, Rect};
+
+/// How opaque a splash image is `time` seconds into being shown for `duration` seconds, fading in over the first
+/// `fade` seconds and out over the last. Images too short for both fades peak partway, rather than jumping.
+pub(crate) fn splash_alpha(time: f32, duration: f32, fade: f32) -> f32{
+    if fade <= 0.0{
+        return if time >= 0.0 && time < duration { 1.0 } else { 0.0 };
+    }
+    (time / fade).min((duration - time) / fade).clamp(0.0, 1.0)
+}
+
+/// How big an image of `image_size` pixels is drawn to fit inside `area`, keeping its shape. Images are only ever
+/// shrunk, so small logos stay sharp.
+pub(crate) fn splash_fit(image_size: (u32, u32), area: [f32; 2]) -> [f32; 2]{
+    let size = [image_size.0 as f32, image_size.1 as f32];
+    if size[0] <= 0.0 || size[1] <= 0.0{
+        return [0.0, 0.0];
+    }
+    let scale = (area[0] / size[0]).min(area[1] / size[1]).clamp(0.0, 1.0);
+    [size[0] * scale, size[1] * scale]
+}
+
+// An image, and how long it's shown for (fades included)
+struct SplashImage{
+    quad: Quad,
+    size: (u32, u32),
+    duration: f32,
+}
+
+/// # SplashScreen
+///
+/// Fills the screen with a background and shows images (like the studio's logo, then the engine's) in the middle
+/// of it, one after the other. Each one fades in, stays for a while and fades out, and any key press, click or tap
+/// skips to the next. Once the last one has gone the finished callback runs.
+///
+/// `Layout::splash_screen` puts one in a layout of its own to push over everything else, which swaps itself out for
+/// the first real layout when the splash screen is finished:
+///
+/// ```ignore
+/// let mut splash = SplashScreen::new(&renderer);
+/// splash.add_image(studio_logo, 3.0, &renderer);
+/// splash.add_image(engine_logo, 2.0, &renderer);
+/// renderer.push_layout(Layout::splash_screen(splash, Some(main_menu)));
+/// ```
+pub struct SplashScreen{
+    rect: Rect,
+    images: Vec<SplashImage>,
+    current: usize,
+    time: f32, // seconds the current image has been shown for
+    fade: f32,
+    skippable: bool,
+    finished: bool,
+    on_finished: Option<Box<dyn Fn()>>,
+    // Where to hand over to once finished - the layout the splash screen is in, and the one to replace it with
+    hand_over: Option<(LayoutCommands, Option<Layout>)>,
+
+    background: Quad,
+    input: InputState,
+    enabled: bool,
+}
+
+impl SplashScreen{
+    /// Create a splash screen without any images, on a black background. Images fade in and out over half a second.
+    pub fn new(renderer: &Renderer) -> Self{
+        Self{
+            rect: Rect::new([0.0, 0.0], [0.0, 0.0]),
+            images: Vec::new(),
+            current: 0,
+            time: 0.0,
+            fade: 0.5,
+            skippable: true,
+            finished: false,
+            on_finished: None,
+            hand_over: None,
+
+            background: Quad::new([0.0, 0.0], [0.0, 0.0], [0.0, 0.0, 0.0, 1.0], &renderer.device),
+            input: InputState::new(),
+            enabled: true,
+        }
+    }
+
+    /// Add an image to show after the others, for `duration` seconds including its fades. It's drawn in the middle
+    /// of the screen, shrunk to fit if it's too big.
+    pub fn add_image(&mut self, texture: Texture, duration: f32, renderer: &Renderer){
+        let size = texture.size;
+        let mut quad = Quad::new([0.0, 0.0], [0.0, 0.0], [1.0, 1.0, 1.0, 0.0], &renderer.device);
+        quad.set_texture(Some(Rc::new(texture)));
+        self.images.push(SplashImage{
+            quad,
+            size,
+            duration: duration.max(0.0),
+        });
+        self.place();
+    }
+
+    /// Set how long images take to fade in and out, in seconds
+    pub fn set_fade_time(&mut self, fade: f32){
+        self.fade = fade.max(0.0);
+    }
+
+    /// Set whether input skips to the next image. It does by default.
+    pub fn set_skippable(&mut self, skippable: bool){
+        self.skippable = skippable;
+    }
+
+    /// Set the function to run once the last image has gone
+    pub fn set_on_finished(&mut self, callback: Option<Box<dyn Fn()>>){
+        self.on_finished = callback;
+    }
+
+    pub fn set_background_color(&mut self, color: [f32; 4]){
+        self.background.set_color(color);
+    }
+
+    /// The index of the image being shown
+    pub fn get_current(&self) -> usize{
+        self.current
+    }
+
+    pub fn len(&self) -> usize{
+        self.images.len()
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.images.is_empty()
+    }
+
+    pub fn is_finished(&self) -> bool{
+        self.finished
+    }
+
+    /// Go on to the next image, like input does, finishing after the last one
+    pub fn skip(&mut self){
+        self.show(self.current + 1);
+    }
+
+    /// Skip all of the images that are left, finishing straight away
+    pub fn skip_all(&mut self){
+        self.show(self.images.len());
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+    }
+
+    /// Swap the layout the splash screen is in (through its `commands`) for `next` once it's finished, or just pop
+    /// it if there isn't one
+    pub(crate) fn hand_over(&mut self, commands: LayoutCommands, next: Option<Layout>){
+        self.hand_over = Some((commands, next));
+    }
+
+    fn show(&mut self, index: usize){
+        if self.finished{
+            return;
+        }
+        self.current = index;
+        self.time = 0.0;
+        if self.current >= self.images.len(){
+            self.finish();
+        }else{
+            self.update_alpha();
+        }
+    }
+
+    fn finish(&mut self){
+        self.finished = true;
+        if let Some((commands, next)) = self.hand_over.take(){
+            commands.pop_layout();
+            if let Some(next) = next{
+                commands.push_layout(next);
+            }
+        }
+        if let Some(callback) = &self.on_finished{
+            callback();
+        }
+    }
+
+    fn update_alpha(&mut self){
+        let fade = self.fade;
+        if let Some(image) = self.images.get_mut(self.current){
+            image.quad.set_color([1.0, 1.0, 1.0, splash_alpha(self.time, image.duration, fade)]);
+        }
+    }
+
+    fn place(&mut self){
+        self.background.set_pos(self.rect.pos);
+        self.background.set_size(self.rect.size);
+        for image in self.images.iter_mut(){
+            image.quad.set_pos(self.rect.pos);
+            image.quad.set_size(splash_fit(image.size, self.rect.size));
+        }
+    }
+
+    // Skip on any key press, click or tap, returning true if the event was one
+    fn handle_input(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window) -> bool{
+        use winit::event::{Event, Touch, TouchPhase, WindowEvent};
+
+        let ui_event = self.input.handle_event(event, window);
+        if !self.enabled || self.finished{
+            return false;
+        }
+        let skip = matches!(ui_event,
+            Some(UiEvent::Key(KeyEvent{ pressed: true, repeat: false, .. }))
+            | Some(UiEvent::MouseInput(MouseEvent{ pressed: true, .. }))
+        ) || matches!(event, Event::WindowEvent{ event: WindowEvent::Touch(Touch{ phase: TouchPhase::Started, .. }), .. });
+        if skip && self.skippable{
+            self.skip();
+        }
+        skip
+    }
+}
+
+impl EventGUIComponent for SplashScreen{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.enabled{
+            self.background.render(render_pass);
+            if let Some(image) = self.images.get(self.current){
+                image.quad.render(render_pass);
+            }
+        }
+    }
+
+    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        self.handle_input(event, window);
+    }
+
+    /// Key presses, clicks and taps are consumed, even when they can't skip
+    fn handle_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window, phase: EventPhase) -> EventResponse{
+        if phase != EventPhase::Bubble{
+            return EventResponse::Continue;
+        }
+        if self.handle_input(event, window) { EventResponse::Consumed } else { EventResponse::Continue }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.rect.pos
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(self.rect)
+    }
+
+    /// The splash screen takes up all the space it's given
+    fn measure(&mut self, available: [f32; 2]) -> Option<[f32; 2]>{
+        Some(available)
+    }
+
+    fn arrange(&mut self, rect: Rect){
+        if rect != self.rect{
+            self.rect = rect;
+            self.place();
+        }
+    }
+
+    fn update(&mut self, dt: f32){
+        if !self.is_animating(){
+            return;
+        }
+        self.time += dt;
+        match self.images.get(self.current){
+            Some(image) if self.time < image.duration => self.update_alpha(),
+            _ => self.skip(),
+        }
+    }
+
+    fn is_animating(&self) -> bool{
+        self.enabled && !self.finished
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.background.prepare(queue, screen_dim);
+        if let Some(image) = self.images.get_mut(self.current){
+            image.quad.prepare(queue, screen_dim);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Test that splash images fade in, stay, and fade out
+    #[test]
+    fn splash_alpha_test(){
+        assert_eq!(splash_alpha(0.0, 3.0, 0.5), 0.0);
+        assert_eq!(splash_alpha(0.25, 3.0, 0.5), 0.5);
+        assert_eq!(splash_alpha(1.5, 3.0, 0.5), 1.0);
+        assert_eq!(splash_alpha(2.75, 3.0, 0.5), 0.5);
+        assert_eq!(splash_alpha(3.0, 3.0, 0.5), 0.0);
+
+        // Too short for both fades, so it peaks in the middle
+        assert_eq!(splash_alpha(0.4, 0.8, 1.0), 0.4);
+        // No fade just shows the image while it's up
+        assert_eq!(splash_alpha(0.0, 1.0, 0.0), 1.0);
+        assert_eq!(splash_alpha(1.0, 1.0, 0.0), 0.0);
+    }
+
+    /// Test that big images shrink to fit, keeping their shape, and small ones are left alone
+    #[test]
+    fn splash_fit_test(){
+        assert_eq!(splash_fit((2000, 1000), [800.0, 600.0]), [800.0, 400.0]);
+        assert_eq!(splash_fit((1000, 2000), [800.0, 600.0]), [300.0, 600.0]);
+        assert_eq!(splash_fit((256, 128), [800.0, 600.0]), [256.0, 128.0]);
+        assert_eq!(splash_fit((0, 128), [800.0, 600.0]), [0.0, 0.0]);
+    }
+}
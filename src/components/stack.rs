@@ -0,0 +1,244 @@
+//! This module contains the `StackPanel`, a container that lines its children up in a row or a column,
+//! sizing itself to fit them using the measure and arrange passes.
+
+use std::any::Any;
+
+use crate::{input::{EventPhase, EventResponse}, rendering::TextBrush};
+
+use super::{EventGUIComponent, Orientation, Rect};
+
+/// # StackPanel
+///
+/// Lines its children up one after another, from the top (or the left) of the panel, with some spacing between
+/// them. The panel is as big as its children need, plus the padding, and stays centred on its position.
+///
+/// Children that return `None` from `measure` aren't part of layout, so they keep their own position and
+/// don't take up any space in the stack. Events go to the children in the same order the layout sends them.
+pub struct StackPanel{
+    rect: Rect,
+    orientation: Orientation,
+    spacing: f32,
+    padding: [f32; 2], // horizontal, vertical
+    children: Vec<Box<dyn EventGUIComponent>>,
+    sizes: Vec<Option<[f32; 2]>>, // what the children asked for in the last measure pass
+    enabled: bool,
+}
+
+impl StackPanel{
+    /// Create an empty stack centred on `pos`
+    pub fn new(pos: [f32; 2], orientation: Orientation, spacing: f32) -> Self{
+        Self{
+            rect: Rect::new(pos, [0.0, 0.0]),
+            orientation,
+            spacing,
+            padding: [0.0, 0.0],
+            children: Vec::new(),
+            sizes: Vec::new(),
+            enabled: true,
+        }
+    }
+
+    /// Set the space between the edge of the panel and its children, horizontally and vertically
+    pub fn set_padding(&mut self, padding: [f32; 2]){
+        self.padding = padding;
+    }
+
+    pub fn set_spacing(&mut self, spacing: f32){
+        self.spacing = spacing;
+    }
+
+    /// Add a child to the end of the stack, returning its index
+    pub fn push(&mut self, child: Box<dyn EventGUIComponent>) -> usize{
+        self.children.push(child);
+        self.sizes.push(None);
+        self.children.len() - 1
+    }
+
+    /// Add a child at an index, moving the children after it down one
+    pub fn insert(&mut self, index: usize, child: Box<dyn EventGUIComponent>){
+        self.children.insert(index, child);
+        self.sizes.insert(index, None);
+    }
+
+    /// Replace the child at an index, returning the old one
+    pub fn replace(&mut self, index: usize, child: Box<dyn EventGUIComponent>) -> Box<dyn EventGUIComponent>{
+        std::mem::replace(&mut self.children[index], child)
+    }
+
+    /// Remove every child
+    pub fn clear(&mut self){
+        self.children.clear();
+        self.sizes.clear();
+    }
+
+    /// Remove a child by its index. The children after it move up one.
+    pub fn remove(&mut self, index: usize) -> Box<dyn EventGUIComponent>{
+        self.sizes.remove(index);
+        self.children.remove(index)
+    }
+
+    pub fn borrow_child_mut(&mut self, index: usize) -> Option<&mut Box<dyn EventGUIComponent>>{
+        self.children.get_mut(index)
+    }
+
+    pub fn len(&self) -> usize{
+        self.children.len()
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.children.is_empty()
+    }
+
+    /// The panel's rectangle from the last arrange pass
+    pub fn get_rect(&self) -> Rect{
+        self.rect
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+    }
+
+    // Which axis the children are stacked along
+    fn main_axis(&self) -> usize{
+        match self.orientation{
+            Orientation::Horizontal => 0,
+            Orientation::Vertical => 1,
+        }
+    }
+}
+
+impl EventGUIComponent for StackPanel{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.enabled{
+            for child in self.children.iter(){
+                child.render(render_pass);
+            }
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if self.enabled{
+            for child in self.children.iter(){
+                child.render_text(brush);
+            }
+        }
+    }
+
+    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        if self.enabled{
+            for child in self.children.iter_mut(){
+                child.handle_event_callback(event, window);
+            }
+        }
+    }
+
+    fn handle_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window, phase: EventPhase) -> EventResponse{
+        if !self.enabled{
+            return EventResponse::Continue;
+        }
+        let consumed = |child: &mut Box<dyn EventGUIComponent>| child.handle_event(event, window, phase) == EventResponse::Consumed;
+        let consumed = match phase{
+            EventPhase::Capture => self.children.iter_mut().any(consumed),
+            EventPhase::Bubble => self.children.iter_mut().rev().any(consumed),
+        };
+        if consumed { EventResponse::Consumed } else { EventResponse::Continue }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.rect.pos
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(self.rect)
+    }
+
+    fn update(&mut self, dt: f32){
+        for child in self.children.iter_mut(){
+            child.update(dt);
+        }
+    }
+
+    fn is_animating(&self) -> bool{
+        self.children.iter().any(|child| child.is_animating())
+    }
+
+    fn layout_text(&mut self, brush: &mut TextBrush){
+        for child in self.children.iter_mut(){
+            child.layout_text(brush);
+        }
+    }
+
+    fn rebuild(&mut self, device: &wgpu::Device) -> bool{
+        self.children.iter_mut().fold(false, |changed, child| child.rebuild(device) | changed)
+    }
+
+    fn measure(&mut self, available: [f32; 2]) -> Option<[f32; 2]>{
+        let main = self.main_axis();
+        let cross = 1 - main;
+        let mut inner = [(available[0] - self.padding[0] * 2.0).max(0.0), (available[1] - self.padding[1] * 2.0).max(0.0)];
+
+        let mut size = [0.0, 0.0];
+        let mut placed = 0;
+        for (child, measured) in self.children.iter_mut().zip(self.sizes.iter_mut()){
+            *measured = child.measure(inner);
+            if let Some(child_size) = *measured{
+                let gap = if placed > 0 { self.spacing } else { 0.0 };
+                size[main] += gap + child_size[main];
+                size[cross] = f32::max(size[cross], child_size[cross]);
+                inner[main] = (inner[main] - gap - child_size[main]).max(0.0);
+                placed += 1;
+            }
+        }
+        Some([size[0] + self.padding[0] * 2.0, size[1] + self.padding[1] * 2.0])
+    }
+
+    /// Children are placed from the start of the panel, and get the full width (or height) across it
+    fn arrange(&mut self, rect: Rect){
+        self.rect = rect;
+        let main = self.main_axis();
+        let cross = 1 - main;
+
+        let mut cursor = rect.pos[main] - rect.size[main] / 2.0 + self.padding[main];
+        let cross_size = (rect.size[cross] - self.padding[cross] * 2.0).max(0.0);
+        for (child, measured) in self.children.iter_mut().zip(self.sizes.iter()){
+            if let Some(child_size) = measured{
+                let mut pos = [0.0, 0.0];
+                pos[main] = cursor + child_size[main] / 2.0;
+                pos[cross] = rect.pos[cross];
+                let mut size = [0.0, 0.0];
+                size[main] = child_size[main];
+                size[cross] = cross_size;
+                child.arrange(Rect::new(pos, size));
+                cursor += child_size[main] + self.spacing;
+            }
+        }
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        for child in self.children.iter_mut(){
+            child.prepare(queue, screen_dim);
+        }
+    }
+}
@@ -0,0 +1,403 @@
+//! This module contains the `StatBar` component, a health (or mana, stamina...) bar for game HUDs. The fill eases to
+//! new values, and damage leaves a ghost of the old value behind that drains away after a moment.
+
+use std::any::Any;
+
+use wgpu_glyph::{HorizontalAlign, VerticalAlign};
+
+use crate::rendering::{Quad, Renderer, TextBrush};
+
+use super::{GUIComponent, Label, Rect, TextGUIComponent};
+
+// How fast the fill and the ghost move, as fractions of the bar per second
+const FILL_SPEED: f32 = 2.0;
+const GHOST_SPEED: f32 = 0.6;
+// How long the ghost waits before draining, and how long the fill flashes for, in seconds
+const GHOST_DELAY: f32 = 0.5;
+const FLASH_TIME: f32 = 0.15;
+
+// The value behind a stat bar, with where the fill and the ghost are as they catch up with it
+struct Meter{
+    value: f32,
+    max: f32,
+    shown: f32, // the fraction the fill is at, easing towards the value
+    ghost: f32, // the fraction the ghost is at
+    ghost_timer: f32,
+    flash_timer: f32,
+}
+
+impl Meter{
+    // A full meter
+    fn new(max: f32) -> Self{
+        Self{
+            value: max,
+            max,
+            shown: 1.0,
+            ghost: 1.0,
+            ghost_timer: 0.0,
+            flash_timer: 0.0,
+        }
+    }
+
+    fn fraction(&self) -> f32{
+        if self.max > 0.0 { self.value / self.max } else { 0.0 }
+    }
+
+    fn set_value(&mut self, value: f32){
+        let value = value.clamp(0.0, self.max.max(0.0));
+        if value < self.value{
+            self.flash_timer = FLASH_TIME;
+            // Damage taken while the ghost is still waiting keeps it waiting, so big combos show all at once
+            self.ghost = self.ghost.max(self.shown);
+            self.ghost_timer = GHOST_DELAY;
+        }
+        self.value = value;
+    }
+
+    fn set_value_immediate(&mut self, value: f32){
+        self.value = value.clamp(0.0, self.max.max(0.0));
+        self.shown = self.fraction();
+        self.ghost = self.shown;
+        self.ghost_timer = 0.0;
+        self.flash_timer = 0.0;
+    }
+
+    fn set_max(&mut self, max: f32){
+        self.max = max;
+        self.value = self.value.min(max.max(0.0));
+    }
+
+    fn is_settled(&self) -> bool{
+        let fraction = self.fraction();
+        self.shown == fraction && self.ghost <= fraction && self.flash_timer <= 0.0
+    }
+
+    // Ease the fill towards the value, and once the delay is up, drain the ghost down to the fill
+    fn update(&mut self, dt: f32){
+        let fraction = self.fraction();
+        let step = FILL_SPEED * dt;
+        self.shown = if self.shown < fraction { (self.shown + step).min(fraction) } else { (self.shown - step).max(fraction) };
+        self.flash_timer = (self.flash_timer - dt).max(0.0);
+        if self.ghost_timer > 0.0{
+            self.ghost_timer -= dt;
+        }else{
+            self.ghost = (self.ghost - GHOST_SPEED * dt).max(self.shown);
+        }
+    }
+}
+
+// The colour of the lowest threshold at or above a fraction, if there is one. The thresholds are sorted lowest first.
+fn threshold_color(thresholds: &[(f32, [f32; 4])], fraction: f32) -> Option<[f32; 4]>{
+    thresholds.iter().find(|(threshold, _)| fraction <= *threshold).map(|(_, color)| *color)
+}
+
+/// # StatBar
+///
+/// A bar showing a value out of a maximum, like health. Setting a new value eases the fill over to it rather than
+/// jumping. When the value drops, the fill flashes, and a ghost bar (in a lighter colour) is left where the value
+/// used to be, draining down to the new value after a short delay - so players can see how much they just lost.
+///
+/// The fill can change colour as the bar empties with `set_thresholds`, like going yellow at half health and red
+/// at a quarter. `show_value` puts the numbers ("75 / 100") over the bar.
+pub struct StatBar{
+    rect: Rect,
+    meter: Meter,
+    fill_color: [f32; 4],
+    thresholds: Vec<(f32, [f32; 4])>, // sorted, lowest first
+
+    background: Quad,
+    ghost_quad: Quad,
+    fill: Quad,
+    label: Option<Label>,
+    enabled: bool,
+}
+
+impl StatBar{
+    /// Create a full bar filling `rect`
+    pub fn new(rect: Rect, max: f32, renderer: &Renderer) -> Self{
+        let mut bar = Self{
+            rect,
+            meter: Meter::new(max),
+            fill_color: [0.2, 0.75, 0.3, 1.0],
+            thresholds: Vec::new(),
+
+            background: Quad::new(rect.pos, rect.size, [0.15, 0.15, 0.15, 1.0], &renderer.device),
+            ghost_quad: Quad::new(rect.pos, [0.0, rect.size[1]], [0.95, 0.85, 0.6, 1.0], &renderer.device),
+            fill: Quad::new(rect.pos, [0.0, rect.size[1]], [0.2, 0.75, 0.3, 1.0], &renderer.device),
+            label: None,
+            enabled: true,
+        };
+        bar.update_quads();
+        bar
+    }
+
+    pub fn get_value(&self) -> f32{
+        self.meter.value
+    }
+
+    /// Set the value (clamped between 0 and the maximum), easing the fill over to it. A drop flashes the fill
+    /// and leaves a ghost behind.
+    pub fn set_value(&mut self, value: f32){
+        self.meter.set_value(value);
+        self.update_label();
+    }
+
+    /// Set the value straight away, without easing, flashing or a ghost
+    pub fn set_value_immediate(&mut self, value: f32){
+        self.meter.set_value_immediate(value);
+        self.update_quads();
+        self.update_label();
+    }
+
+    pub fn get_max(&self) -> f32{
+        self.meter.max
+    }
+
+    /// Change the maximum, keeping the value inside it
+    pub fn set_max(&mut self, max: f32){
+        self.meter.set_max(max);
+        self.update_label();
+    }
+
+    /// How full the bar is, from 0 to 1
+    pub fn get_fraction(&self) -> f32{
+        self.meter.fraction()
+    }
+
+    /// Set the colours of the background, the fill and the ghost left behind by damage
+    pub fn set_colors(&mut self, background: [f32; 4], fill: [f32; 4], ghost: [f32; 4]){
+        self.background.set_color(background);
+        self.fill_color = fill;
+        self.ghost_quad.set_color(ghost);
+        self.update_quads();
+    }
+
+    /// Change the colour of the fill once the bar is at or below some fractions, like `[(0.5, yellow), (0.25, red)]`.
+    /// Above every threshold the fill is the colour from `set_colors`.
+    pub fn set_thresholds(&mut self, thresholds: &[(f32, [f32; 4])]){
+        self.thresholds = thresholds.to_vec();
+        self.thresholds.sort_by(|a, b| a.0.total_cmp(&b.0));
+        self.update_quads();
+    }
+
+    /// Show the value and maximum over the bar
+    pub fn show_value(&mut self, text_size: f32){
+        let mut label = Label::new("", text_size, [0.0, 0.0]);
+        label.align_horizontal(HorizontalAlign::Center);
+        label.align_vertical(VerticalAlign::Center);
+        self.label = Some(label);
+        self.update_label();
+    }
+
+    pub fn hide_value(&mut self){
+        self.label = None;
+    }
+
+    /// Borrow the value label, if it's shown, for example to change its font
+    pub fn borrow_label_mut(&mut self) -> Option<&mut Label>{
+        self.label.as_mut()
+    }
+
+    pub fn set_rect(&mut self, rect: Rect){
+        self.rect = rect;
+        self.background.set_pos(rect.pos);
+        self.background.set_size(rect.size);
+        self.update_quads();
+    }
+
+    pub fn get_rect(&self) -> Rect{
+        self.rect
+    }
+
+    /// Whether the fill has caught up with the value, the ghost has drained and the flash is over
+    pub fn is_settled(&self) -> bool{
+        self.meter.is_settled()
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+    }
+
+    // The colour of the fill at the fraction it's showing, flashing towards white after damage
+    fn current_fill_color(&self) -> [f32; 4]{
+        let color = threshold_color(&self.thresholds, self.meter.shown).unwrap_or(self.fill_color);
+        let flash = (self.meter.flash_timer / FLASH_TIME).clamp(0.0, 1.0);
+        [
+            color[0] + (1.0 - color[0]) * flash,
+            color[1] + (1.0 - color[1]) * flash,
+            color[2] + (1.0 - color[2]) * flash,
+            color[3],
+        ]
+    }
+
+    fn update_quads(&mut self){
+        let left = self.rect.pos[0] - self.rect.size[0] / 2.0;
+        let width = self.rect.size[0];
+        let (shown, ghost) = (self.meter.shown, self.meter.ghost.max(self.meter.shown));
+        self.fill.set_pos([left + shown * width / 2.0, self.rect.pos[1]]);
+        self.fill.set_size([shown * width, self.rect.size[1]]);
+        self.ghost_quad.set_pos([left + ghost * width / 2.0, self.rect.pos[1]]);
+        self.ghost_quad.set_size([ghost * width, self.rect.size[1]]);
+        self.fill.set_color(self.current_fill_color());
+    }
+
+    fn update_label(&mut self){
+        let text = format!("{} / {}", self.meter.value.round(), self.meter.max.round());
+        if let Some(label) = &mut self.label{
+            label.set_text(text);
+        }
+    }
+}
+
+impl GUIComponent for StatBar{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.enabled{
+            self.background.render(render_pass);
+            self.ghost_quad.render(render_pass);
+            self.fill.render(render_pass);
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if let (true, Some(label)) = (self.enabled, &self.label){
+            TextGUIComponent::render_text(label, brush);
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.rect.pos
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(self.rect)
+    }
+
+    fn update(&mut self, dt: f32){
+        if self.is_settled(){
+            return;
+        }
+        self.meter.update(dt);
+        self.update_quads();
+    }
+
+    fn is_animating(&self) -> bool{
+        self.enabled && !self.is_settled()
+    }
+
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{
+        Some(self.rect.size)
+    }
+
+    fn arrange(&mut self, rect: Rect){
+        if rect != self.rect{
+            self.set_rect(rect);
+        }
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.background.prepare(queue, screen_dim);
+        self.ghost_quad.prepare(queue, screen_dim);
+        self.fill.prepare(queue, screen_dim);
+        if let Some(label) = &mut self.label{
+            label.set_pos(self.rect.pos, screen_dim);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Test that values are kept between 0 and the maximum, and that healing eases the fill up without a ghost
+    #[test]
+    fn value_test(){
+        let mut meter = Meter::new(100.0);
+        meter.set_value(150.0);
+        assert_eq!(meter.value, 100.0);
+        meter.set_value_immediate(-10.0);
+        assert_eq!(meter.value, 0.0);
+        assert!(meter.is_settled());
+
+        meter.set_value(50.0);
+        assert_eq!(meter.flash_timer, 0.0);
+        meter.update(0.1);
+        assert!((meter.shown - FILL_SPEED * 0.1).abs() < 1e-6);
+        meter.update(1.0);
+        assert_eq!(meter.shown, 0.5);
+        assert!(meter.is_settled());
+
+        // Lowering the maximum takes the value down with it
+        meter.set_max(40.0);
+        assert_eq!(meter.value, 40.0);
+        assert_eq!(meter.fraction(), 1.0);
+    }
+
+    /// Test that damage flashes and leaves a ghost, which waits before draining down to the fill
+    #[test]
+    fn ghost_test(){
+        let mut meter = Meter::new(100.0);
+        meter.set_value(60.0);
+        assert_eq!(meter.flash_timer, FLASH_TIME);
+        assert_eq!(meter.ghost, 1.0);
+
+        // The fill drops straight away, but the ghost stays put until the delay is up
+        meter.update(0.25);
+        assert_eq!(meter.shown, 0.6);
+        assert_eq!(meter.flash_timer, 0.0);
+        assert_eq!(meter.ghost, 1.0);
+        meter.update(0.25);
+        assert_eq!(meter.ghost, 1.0);
+        assert!(!meter.is_settled());
+        meter.update(0.5);
+        assert!((meter.ghost - (1.0 - GHOST_SPEED * 0.5)).abs() < 1e-6);
+        meter.update(1.0);
+        assert_eq!(meter.ghost, 0.6);
+        assert!(meter.is_settled());
+    }
+
+    /// Test that more damage while the ghost is waiting keeps it at the value from before the first hit
+    #[test]
+    fn combo_test(){
+        let mut meter = Meter::new(100.0);
+        meter.set_value(80.0);
+        meter.update(0.3);
+        meter.set_value(50.0);
+        assert_eq!(meter.ghost, 1.0);
+        assert_eq!(meter.ghost_timer, GHOST_DELAY);
+    }
+
+    /// Test that the fill takes the colour of the lowest threshold it's at or under
+    #[test]
+    fn threshold_color_test(){
+        let yellow = [1.0, 1.0, 0.0, 1.0];
+        let red = [1.0, 0.0, 0.0, 1.0];
+        let thresholds = [(0.25, red), (0.5, yellow)];
+        assert_eq!(threshold_color(&thresholds, 0.8), None);
+        assert_eq!(threshold_color(&thresholds, 0.5), Some(yellow));
+        assert_eq!(threshold_color(&thresholds, 0.3), Some(yellow));
+        assert_eq!(threshold_color(&thresholds, 0.1), Some(red));
+        assert_eq!(threshold_color(&[], 0.1), None);
+    }
+}
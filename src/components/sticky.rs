@@ -0,0 +1,64 @@
+//! This module works out where sticky section headers go in a scrolling list.
+//! A header sticks to the top of the viewport while its section is scrolled through,
+//! and gets pushed up and out by the header of the next section as it arrives.
+
+/// A header that is currently stuck to the top of the viewport
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct StuckHeader{
+    /// The index of the section the header belongs to
+    pub index: usize,
+    /// Where the top of the header goes, relative to the top of the viewport. This is 0 while
+    /// stuck, and goes negative while the next header pushes it out.
+    pub y: f32,
+}
+
+/// # StickyHeaders
+///
+/// Stores where each section of a list starts (in content space) and how tall its header is.
+/// Sections must be added in order, from the top of the content down.
+pub struct StickyHeaders{
+    sections: Vec<(f32, f32)>, // start of the section, height of its header
+}
+
+impl StickyHeaders{
+    pub fn new() -> Self{
+        Self{
+            sections: Vec::new(),
+        }
+    }
+
+    /// Add a section, returning its index
+    pub fn add_section(&mut self, start: f32, header_height: f32) -> usize{
+        self.sections.push((start, header_height));
+        self.sections.len() - 1
+    }
+
+    /// Remove every section, for example when the list content changes
+    pub fn clear(&mut self){
+        self.sections.clear();
+    }
+
+    pub fn len(&self) -> usize{
+        self.sections.len()
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.sections.is_empty()
+    }
+
+    /// Work out which header is stuck at a scroll offset. Returns `None` if no section has been
+    /// scrolled past the top of the viewport yet (every header is in its normal place).
+    pub fn stuck_header(&self, offset: f32) -> Option<StuckHeader>{
+        // The last section that starts above the top of the viewport
+        let index = self.sections.iter().rposition(|(start, _)| *start <= offset)?;
+        let (_, header_height) = self.sections[index];
+
+        // The next header pushes this one up once it reaches the bottom of it
+        let y = match self.sections.get(index + 1){
+            Some((next_start, _)) => (next_start - offset - header_height).min(0.0),
+            None => 0.0,
+        };
+
+        Some(StuckHeader{ index, y })
+    }
+}
@@ -0,0 +1,128 @@
+//! This module contains the `StreamedImage` component, an image whose texture is only loaded while it's on
+//! screen, for galleries and other UIs with more images than fit in memory.
+
+use std::{any::Any, path::Path, rc::Rc};
+
+use crate::rendering::{AssetState, Quad, Renderer, StreamHandle, Texture, TextureStreamer};
+
+use super::{GUIComponent, Rect};
+
+/// # StreamedImage
+///
+/// Draws a texture streamed in by the renderer's `TextureStreamer`. The file isn't read until the image is
+/// first prepared inside the window, and the placeholder is drawn until it's ready. If the texture gets
+/// unloaded to stay under the streamer's budget, it's loaded again the next time the image is on screen.
+///
+/// Only the window is checked, so an image scrolled out of a `ScrollView` but still inside the window
+/// counts as on screen.
+pub struct StreamedImage{
+    quad: Quad,
+    streamer: TextureStreamer,
+    handle: StreamHandle,
+    texture: Option<Rc<Texture>>,
+    placeholder: Rc<Texture>,
+    enabled: bool,
+}
+
+impl StreamedImage{
+    /// Create an image of a file (read through the assets' source), drawn `size` pixels big
+    pub fn new<P: AsRef<Path>>(path: P, pos: [f32; 2], size: [f32; 2], renderer: &Renderer) -> Self{
+        Self{
+            quad: Quad::new(pos, size, [1.0, 1.0, 1.0, 1.0], &renderer.device),
+            handle: renderer.streamer.request(path),
+            streamer: renderer.streamer.clone(),
+            texture: None,
+            placeholder: renderer.streamer.get_placeholder(),
+            enabled: true,
+        }
+    }
+
+    /// Where the texture is in its loading
+    pub fn get_state(&self) -> AssetState{
+        self.streamer.get_state(self.handle)
+    }
+
+    pub fn get_handle(&self) -> StreamHandle{
+        self.handle
+    }
+
+    pub fn set_pos(&mut self, pos: [f32; 2]){
+        self.quad.set_pos(pos);
+    }
+
+    /// Set how big the image is drawn, in pixels
+    pub fn set_size(&mut self, size: [f32; 2]){
+        self.quad.set_size(size);
+    }
+
+    pub fn get_size(&self) -> [f32; 2]{
+        self.quad.get_size()
+    }
+
+    /// Set a colour to multiply the image by. White leaves it as it is.
+    pub fn set_tint(&mut self, color: [f32; 4]){
+        self.quad.set_color(color);
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+    }
+}
+
+impl GUIComponent for StreamedImage{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.enabled{
+            let texture = self.texture.as_ref().unwrap_or(&self.placeholder);
+            render_pass.set_bind_group(3, &texture.bind_group, &[]);
+            self.quad.render(render_pass);
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.quad.get_pos()
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(Rect::new(self.quad.get_pos(), self.quad.get_size()))
+    }
+
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{
+        Some(self.quad.get_size())
+    }
+
+    /// Like `Image`, the size is kept and the image is centred in the rectangle
+    fn arrange(&mut self, rect: Rect){
+        self.quad.set_pos(rect.pos);
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        let screen = Rect::new([0.0, 0.0], [screen_dim.0 as f32, screen_dim.1 as f32]);
+        if self.enabled && screen.intersects(&Rect::new(self.quad.get_pos(), self.quad.get_size())){
+            self.streamer.mark_visible(self.handle);
+        }
+        // Picks up the texture once it's loaded, and lets go of it once it's unloaded
+        self.texture = self.streamer.get_texture(self.handle);
+        self.quad.prepare(queue, screen_dim);
+    }
+}
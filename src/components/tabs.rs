@@ -0,0 +1,280 @@
+//! This module contains the `TabView`, a row of tab headers that switches between pages of components,
+//! showing the components on the selected page and hiding the rest.
+
+use std::any::Any;
+
+use wgpu_glyph::{HorizontalAlign, VerticalAlign};
+
+use crate::{input::{InputState, MouseButton, MouseEvent, UiEvent}, layout::{ComponentHandle, LayoutCommands}, rendering::{Quad, Renderer, TextBrush}};
+
+use super::{EventGUIComponent, Label, Rect, TextGUIComponent};
+
+// A page, with the header that selects it
+struct TabPage{
+    header: Quad,
+    label: Label,
+    components: Vec<ComponentHandle>,
+}
+
+/// # TabView
+///
+/// A row of tab headers, each one selecting a page. A page is a set of components already in the `Layout`,
+/// found by their handles. Only the components on the selected page are shown - the ones on every other page
+/// are hidden with `Layout::set_hidden`, so they aren't drawn and don't get events.
+///
+/// The components are shown and hidden through the layout's `LayoutCommands`, so a change made while handling
+/// an event is applied once the event has been dispatched. The handles aren't updated when components are
+/// removed from the layout, so pages should be set up again if that happens.
+pub struct TabView{
+    pos: [f32; 2], // the centre of the first header
+    tab_size: [f32; 2],
+    text_size: f32,
+    selected: Option<usize>,
+    callback: Option<Box<dyn Fn(usize)>>,
+    commands: LayoutCommands,
+
+    pages: Vec<TabPage>,
+    colors: [[f32; 4]; 2], // unselected, selected
+
+    input: InputState,
+    pressed: Option<usize>, // the header the mouse went down on
+    enabled: bool,
+}
+
+impl TabView{
+    /// Create a view without any pages. The first header is centred on `pos`, and the rest go to the right of it.
+    /// `commands` should come from the layout holding the pages' components (see `Layout::get_commands`).
+    pub fn new(pos: [f32; 2], tab_size: [f32; 2], text_size: f32, commands: LayoutCommands) -> Self{
+        Self{
+            pos,
+            tab_size,
+            text_size,
+            selected: None,
+            callback: None,
+            commands,
+
+            pages: Vec::new(),
+            colors: [[0.8, 0.8, 0.8, 1.0], [1.0, 1.0, 1.0, 1.0]],
+
+            input: InputState::new(),
+            pressed: None,
+            enabled: true,
+        }
+    }
+
+    /// Add a page, returning its index. The first page added gets selected, and the components on any others
+    /// are hidden straight away.
+    pub fn add_page(&mut self, name: &str, components: Vec<ComponentHandle>, renderer: &Renderer) -> usize{
+        let index = self.pages.len();
+        let mut label = Label::new(name, self.text_size, [0.0, 0.0]);
+        label.align_vertical(VerticalAlign::Center);
+        label.align_horizontal(HorizontalAlign::Center);
+        self.pages.push(TabPage{
+            header: Quad::new(self.header_centre(index), self.tab_size, self.colors[0], &renderer.device),
+            label,
+            components,
+        });
+
+        if self.selected.is_none(){
+            self.set_selected(index);
+        }else{
+            self.queue_visibility();
+        }
+        index
+    }
+
+    /// The index of the selected page, if there are any pages
+    pub fn get_selected(&self) -> Option<usize>{
+        self.selected
+    }
+
+    /// Select a page without running the callback
+    pub fn set_selected(&mut self, index: usize){
+        if index >= self.pages.len(){
+            return;
+        }
+        self.selected = Some(index);
+        for (i, page) in self.pages.iter_mut().enumerate(){
+            page.header.set_color(self.colors[(i == index) as usize]);
+        }
+        self.queue_visibility();
+    }
+
+    /// Select a page like a click on its header would, running the callback if the selection changed
+    pub fn select(&mut self, index: usize){
+        if index >= self.pages.len() || self.selected == Some(index){
+            return;
+        }
+        self.set_selected(index);
+        if let Some(callback) = &self.callback{
+            callback(index);
+        }
+    }
+
+    /// Set the function to run with the index of the page when the selection changes
+    pub fn set_callback(&mut self, callback: Option<Box<dyn Fn(usize)>>){
+        self.callback = callback;
+    }
+
+    /// Set the colours of the headers of the other pages, and of the selected page
+    pub fn set_colors(&mut self, unselected: [f32; 4], selected: [f32; 4]){
+        self.colors = [unselected, selected];
+        for (i, page) in self.pages.iter_mut().enumerate(){
+            page.header.set_color(self.colors[(Some(i) == self.selected) as usize]);
+        }
+    }
+
+    /// Move the view, so the first header is centred on `pos`
+    pub fn set_pos(&mut self, pos: [f32; 2]){
+        self.pos = pos;
+        for i in 0..self.pages.len(){
+            let centre = self.header_centre(i);
+            self.pages[i].header.set_pos(centre);
+        }
+    }
+
+    /// Borrow the label of a page's header, for example to change its font
+    pub fn borrow_label_mut(&mut self, index: usize) -> Option<&mut Label>{
+        self.pages.get_mut(index).map(|page| &mut page.label)
+    }
+
+    /// The components on a page
+    pub fn get_page(&self, index: usize) -> Option<&[ComponentHandle]>{
+        self.pages.get(index).map(|page| page.components.as_slice())
+    }
+
+    pub fn len(&self) -> usize{
+        self.pages.len()
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.pages.is_empty()
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+        self.pressed = None;
+    }
+
+    /// The area taken up by the headers
+    pub fn get_rect(&self) -> Rect{
+        let width = self.tab_size[0] * self.pages.len() as f32;
+        let left = self.pos[0] - self.tab_size[0] / 2.0;
+        Rect::new([left + width / 2.0, self.pos[1]], [width, self.tab_size[1]])
+    }
+
+    fn header_centre(&self, index: usize) -> [f32; 2]{
+        [self.pos[0] + index as f32 * self.tab_size[0], self.pos[1]]
+    }
+
+    fn header_at(&self, pos: [f32; 2]) -> Option<usize>{
+        (0..self.pages.len()).find(|i| Rect::new(self.header_centre(*i), self.tab_size).contains(pos))
+    }
+
+    // Show the components on the selected page and hide everything else
+    fn queue_visibility(&self){
+        let selected = self.selected;
+        let pages: Vec<Vec<ComponentHandle>> = self.pages.iter().map(|page| page.components.clone()).collect();
+        self.commands.run(move |layout| {
+            // Hide first, so a component on more than one page stays shown if the selected page has it
+            for (i, components) in pages.iter().enumerate(){
+                if Some(i) != selected{
+                    for handle in components.iter(){
+                        layout.set_hidden(*handle, true);
+                    }
+                }
+            }
+            if let Some(components) = selected.and_then(|selected| pages.get(selected)){
+                for handle in components.iter(){
+                    layout.set_hidden(*handle, false);
+                }
+            }
+        });
+    }
+}
+
+impl EventGUIComponent for TabView{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.enabled{
+            for page in self.pages.iter(){
+                page.header.render(render_pass);
+            }
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if self.enabled{
+            for page in self.pages.iter(){
+                TextGUIComponent::render_text(&page.label, brush);
+            }
+        }
+    }
+
+    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        if !self.enabled{
+            return;
+        }
+
+        if let Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed, pos, .. })) = self.input.handle_event(event, window){
+            let header = self.header_at(pos);
+            if pressed{
+                self.pressed = header;
+            }else{
+                if let (Some(pressed), Some(header)) = (self.pressed, header){
+                    if pressed == header{
+                        self.select(header);
+                    }
+                }
+                self.pressed = None;
+            }
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.pos
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(self.get_rect())
+    }
+
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{
+        Some(self.get_rect().size)
+    }
+
+    /// The headers go along the top left of the rectangle. The pages' components are laid out by the layout.
+    fn arrange(&mut self, rect: Rect){
+        let left = rect.pos[0] - rect.size[0] / 2.0;
+        let top = rect.pos[1] - rect.size[1] / 2.0;
+        self.set_pos([left + self.tab_size[0] / 2.0, top + self.tab_size[1] / 2.0]);
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        for page in self.pages.iter_mut(){
+            page.header.prepare(queue, screen_dim);
+            page.label.set_pos(page.header.get_pos(), screen_dim);
+        }
+    }
+}
@@ -0,0 +1,628 @@
+//! This module contains the `TextArea` component, a multi-line text box that wraps its text to its width and
+//! scrolls through it a line at a time, and `wrap_text`, the word wrapping it uses.
+
+use std::{any::Any, ops::Range};
+
+use wgpu_glyph::ab_glyph::{Font, ScaleFont};
+
+use crate::{input::{EventPhase, EventResponse, InputCategory, InputState, Key, MouseButton, MouseEvent, UiEvent}, rendering::{Quad, Renderer, TextBrush}};
+
+use super::{Caret, EditResult, EventGUIComponent, Rect, Searchable, TextBuffer, find::Highlights};
+
+/// Break text into lines no wider than `max_width`, as byte ranges of the text. Lines break at newlines (which
+/// aren't part of either line) and between words, or inside a word too long to fit on a line of its own. Spaces
+/// at the end of a line are allowed to hang past the edge.
+///
+/// `advance` gives how far across a character moves the next one, given the character before it on the line
+/// (for kerning).
+pub(crate) fn wrap_text(text: &str, max_width: f32, advance: impl Fn(Option<char>, char) -> f32) -> Vec<(usize, usize)>{
+    let width_of = |from: usize, to: usize| {
+        let mut prev = None;
+        text[from..to].chars().map(|c| {
+            let width = advance(prev, c);
+            prev = Some(c);
+            width
+        }).sum::<f32>()
+    };
+
+    let mut lines = Vec::new();
+    let mut start = 0; // of the paragraph
+    for paragraph in text.split('\n'){
+        let mut line_start = start;
+        let mut last_break = None; // just after the last space on the line
+        let mut width = 0.0;
+        let mut prev = None;
+        for (i, c) in paragraph.char_indices(){
+            let i = start + i;
+            let char_width = advance(prev, c);
+            if width + char_width > max_width && i > line_start && !c.is_whitespace(){
+                // Break after the last space if there was one, otherwise right here
+                let end = last_break.filter(|&end| end > line_start).unwrap_or(i);
+                lines.push((line_start, end));
+                line_start = end;
+                last_break = None;
+                width = width_of(line_start, i) + advance(None, c);
+            }else{
+                width += char_width;
+            }
+            if c.is_whitespace(){
+                last_break = Some(i + c.len_utf8());
+            }
+            prev = Some(c);
+        }
+        lines.push((line_start, start + paragraph.len()));
+        start += paragraph.len() + 1;
+    }
+    lines
+}
+
+// A line of wrapped text, with where the caret can go along it
+struct Line{
+    start: usize,
+    end: usize,
+    stops: Vec<(usize, f32)>, // every character boundary, as a byte index and how far across it is
+}
+
+impl Line{
+    // How far across the line a character boundary on it is
+    fn x_of(&self, index: usize) -> f32{
+        self.stops.iter().find(|(stop, _)| *stop == index).map(|(_, x)| *x).unwrap_or(0.0)
+    }
+}
+
+// The parts of a range of the text on each of the lines it's on, as the index of the line and the range on it
+fn line_spans(lines: &[Line], range: &Range<usize>) -> Vec<(usize, Range<usize>)>{
+    lines.iter().enumerate().filter_map(|(i, line)| {
+        let span = range.start.max(line.start)..range.end.min(line.end);
+        if span.start < span.end { Some((i, span)) } else { None }
+    }).collect()
+}
+
+/// # TextArea
+///
+/// A multi-line text box. Like `TextInput`, click on it to focus it and type, but enter starts a new line. The text
+/// wraps at word boundaries to the width of the box, and the box scrolls (with the mouse wheel, or by moving the
+/// cursor off the top or bottom) when there are more lines than fit.
+///
+/// Up and down move the cursor between lines, keeping as close to the same spot across as they can, and home and
+/// end go to the start and end of the line. Page up and page down move a box's height of lines at a time.
+///
+/// Text areas can be searched (see `Searchable` and `FindBar`), and scroll to show the current match.
+pub struct TextArea{
+    buffer: TextBuffer,
+    bounds: Rect,
+    padding: f32,
+    text_size: f32,
+    font: wgpu_glyph::FontId,
+    text_color: [f32; 4],
+
+    border: Quad,
+    background: Quad,
+    border_colors: ([f32; 4], [f32; 4]), // unfocused, focused
+    caret: Caret,
+    lines: Vec<Line>,
+    line_height: f32,
+    first_line: usize, // the line at the top of the box
+    goal_x: Option<f32>, // where across the cursor is trying to stay, while moving up and down
+    click: Option<[f32; 2]>, // a click waiting to be turned into a cursor position, once the text is laid out
+    follow_cursor: bool, // scroll to the cursor after the next layout
+    reveal: Option<usize>, // a byte index to scroll to after the next layout, for the current search match
+    highlights: Highlights,
+    dirty: bool,
+    screen_dim: (u32, u32),
+
+    input: InputState,
+    focused: bool,
+    enabled: bool,
+    on_change: Option<Box<dyn Fn(&str)>>,
+}
+
+impl TextArea{
+    /// Create an empty text area filling `bounds`
+    pub fn new(bounds: Rect, text_size: f32, renderer: &Renderer) -> Self{
+        let mut caret = Caret::new([0.0, 0.0], text_size, &renderer.device);
+        caret.disable();
+
+        let mut area = Self{
+            buffer: TextBuffer::new(),
+            bounds,
+            padding: 6.0,
+            text_size,
+            font: wgpu_glyph::FontId::default(),
+            text_color: [0.0, 0.0, 0.0, 1.0],
+
+            border: Quad::new(bounds.pos, bounds.size, [0.5, 0.5, 0.5, 1.0], &renderer.device),
+            background: Quad::new(bounds.pos, [bounds.size[0] - 2.0, bounds.size[1] - 2.0], [1.0, 1.0, 1.0, 1.0], &renderer.device),
+            border_colors: ([0.5, 0.5, 0.5, 1.0], [0.2, 0.4, 0.8, 1.0]),
+            caret,
+            lines: Vec::new(),
+            line_height: text_size,
+            first_line: 0,
+            goal_x: None,
+            click: None,
+            follow_cursor: false,
+            reveal: None,
+            highlights: Highlights::new(),
+            dirty: true,
+            screen_dim: (renderer.sc_desc.width, renderer.sc_desc.height),
+
+            input: InputState::new(),
+            focused: false,
+            enabled: true,
+            on_change: None,
+        };
+        area.set_bounds(bounds);
+        area
+    }
+
+    pub fn get_text(&self) -> &str{
+        self.buffer.get_text()
+    }
+
+    /// Replace the text, putting the cursor at the end. This doesn't call the change callback.
+    pub fn set_text<S: Into<String>>(&mut self, text: S){
+        self.buffer.set_text(text);
+        self.dirty = true;
+        self.follow_cursor = true;
+    }
+
+    /// The text and cursor, for editing them directly
+    pub fn borrow_buffer_mut(&mut self) -> &mut TextBuffer{
+        self.dirty = true;
+        &mut self.buffer
+    }
+
+    /// Change the font, using an ID from `Renderer::add_font`
+    pub fn set_font(&mut self, font: wgpu_glyph::FontId){
+        self.font = font;
+        self.dirty = true;
+    }
+
+    pub fn set_text_color(&mut self, color: [f32; 4]){
+        self.text_color = color;
+        self.caret.set_color(color);
+    }
+
+    /// Set the colours of the background, and the border when unfocused and focused
+    pub fn set_colors(&mut self, background: [f32; 4], border: [f32; 4], focused_border: [f32; 4]){
+        self.background.set_color(background);
+        self.border_colors = (border, focused_border);
+        self.border.set_color(if self.focused { focused_border } else { border });
+    }
+
+    /// Set the colours search matches are highlighted in, and the current match
+    pub fn set_highlight_colors(&mut self, matches: [f32; 4], current: [f32; 4]){
+        self.highlights.set_colors(matches, current);
+    }
+
+    pub fn set_bounds(&mut self, bounds: Rect){
+        if bounds.size[0] != self.bounds.size[0]{
+            self.dirty = true;
+        }
+        self.bounds = bounds;
+        self.border.set_pos(bounds.pos);
+        self.border.set_size(bounds.size);
+        self.background.set_pos(bounds.pos);
+        self.background.set_size([bounds.size[0] - 2.0, bounds.size[1] - 2.0]);
+        self.move_caret();
+    }
+
+    /// Called with the new text whenever the user changes it
+    pub fn set_on_change(&mut self, callback: Option<Box<dyn Fn(&str)>>){
+        self.on_change = callback;
+    }
+
+    /// Focus or unfocus the text area. Only a focused text area takes keyboard input.
+    pub fn set_focused(&mut self, focused: bool){
+        self.focused = focused;
+        self.border.set_color(if focused { self.border_colors.1 } else { self.border_colors.0 });
+        self.move_caret();
+    }
+
+    pub fn is_focused(&self) -> bool{
+        self.focused
+    }
+
+    /// How many lines the text wraps onto, as of the last time it was laid out
+    pub fn get_line_count(&self) -> usize{
+        self.lines.len()
+    }
+
+    /// The line the cursor is on, counting wrapped lines
+    pub fn get_cursor_line(&self) -> usize{
+        self.line_of(self.buffer.get_cursor())
+    }
+
+    /// The line showing at the top of the box
+    pub fn get_first_line(&self) -> usize{
+        self.first_line
+    }
+
+    /// Scroll so a line is at the top of the box, as far as the text goes
+    pub fn scroll_to_line(&mut self, line: usize){
+        self.first_line = line.min(self.lines.len().saturating_sub(self.visible_lines()));
+        self.move_caret();
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+        self.set_focused(false);
+    }
+
+    fn text_left(&self) -> f32{
+        self.bounds.pos[0] - self.bounds.size[0] / 2.0 + self.padding
+    }
+
+    fn text_top(&self) -> f32{
+        self.bounds.pos[1] - self.bounds.size[1] / 2.0 + self.padding
+    }
+
+    // How many whole lines fit in the box
+    fn visible_lines(&self) -> usize{
+        (((self.bounds.size[1] - self.padding * 2.0) / self.line_height).floor() as usize).max(1)
+    }
+
+    // The wrapped line a byte index is on. An index where a line wrapped goes at the start of the next line.
+    fn line_of(&self, index: usize) -> usize{
+        self.lines.iter().rposition(|line| line.start <= index).unwrap_or(0)
+    }
+
+    // How far across a byte index is on its line
+    fn x_of(&self, index: usize) -> f32{
+        self.lines.get(self.line_of(index)).map(|line| line.x_of(index)).unwrap_or(0.0)
+    }
+
+    // Scroll as little as possible to get a line in the box
+    fn show_line(&mut self, line: usize){
+        if line < self.first_line{
+            self.first_line = line;
+        }else if line >= self.first_line + self.visible_lines(){
+            self.first_line = line + 1 - self.visible_lines();
+        }
+    }
+
+    // Cover the parts of the search matches on the lines showing in the box
+    fn place_highlights(&mut self){
+        let first = self.first_line.min(self.lines.len());
+        let lines = &self.lines[first..(first + self.visible_lines()).min(self.lines.len())];
+        let (left, top, line_height) = (self.text_left(), self.text_top(), self.line_height);
+        let current = self.highlights.get_current();
+        let rects: Vec<(Rect, bool)> = self.highlights.get_matches().iter().enumerate().flat_map(|(i, range)| {
+            line_spans(lines, range).into_iter().map(move |(row, span)| {
+                let (start, end) = (lines[row].x_of(span.start), lines[row].x_of(span.end));
+                let y = top + row as f32 * line_height + line_height / 2.0;
+                (Rect::new([left + (start + end) / 2.0, y], [end - start, line_height]), current == Some(i))
+            })
+        }).collect();
+        self.highlights.place(rects);
+    }
+
+    // The character boundary on a line closest to a distance across it
+    fn closest_stop(&self, line: usize, x: f32) -> Option<usize>{
+        let line = self.lines.get(line)?;
+        line.stops.iter().min_by(|a, b| (a.1 - x).abs().total_cmp(&(b.1 - x).abs())).map(|(stop, _)| *stop)
+    }
+
+    fn move_caret(&mut self){
+        let cursor = self.buffer.get_cursor();
+        let line = self.line_of(cursor);
+        let shown = line >= self.first_line && line < self.first_line + self.visible_lines();
+        let y = self.text_top() + (line as f32 - self.first_line as f32) * self.line_height;
+        self.caret.move_to([self.text_left() + self.x_of(cursor), y]);
+        if self.focused && shown && self.enabled{
+            self.caret.enable();
+        }else{
+            self.caret.disable();
+        }
+    }
+
+    // Move the cursor up or down some lines, keeping as close to the same spot across as it can
+    fn move_lines(&mut self, by: isize){
+        let cursor = self.buffer.get_cursor();
+        let goal_x = *self.goal_x.get_or_insert(self.x_of(cursor));
+        let line = self.line_of(cursor) as isize + by;
+        let target = if line < 0{
+            Some(0)
+        }else if line as usize >= self.lines.len(){
+            Some(self.buffer.get_text().len())
+        }else{
+            self.closest_stop(line as usize, goal_x)
+        };
+        if let Some(target) = target{
+            self.buffer.set_cursor(target);
+        }
+    }
+
+    fn changed(&mut self){
+        self.dirty = true;
+        if let Some(callback) = &self.on_change{
+            callback(self.buffer.get_text());
+        }
+    }
+}
+
+impl EventGUIComponent for TextArea{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.enabled{
+            self.border.render(render_pass);
+            self.background.render(render_pass);
+            self.highlights.render(render_pass);
+            self.caret.render(render_pass);
+        }
+    }
+
+    // Only the lines that fit in the box get drawn
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if !self.enabled{
+            return;
+        }
+        let text = self.buffer.get_text();
+        let left = self.text_left() + (self.screen_dim.0 / 2) as f32;
+        let top = self.text_top() + (self.screen_dim.1 / 2) as f32;
+        for (i, line) in self.lines.iter().skip(self.first_line).take(self.visible_lines()).enumerate(){
+            brush.queue(wgpu_glyph::Section {
+                screen_position: (left, top + i as f32 * self.line_height),
+                text: vec![wgpu_glyph::Text::new(&text[line.start..line.end]).with_color(self.text_color).with_scale(wgpu_glyph::ab_glyph::PxScale::from(self.text_size)).with_font_id(self.font)],
+                layout: wgpu_glyph::Layout::default_single_line(),
+                ..wgpu_glyph::Section::default()
+            });
+        }
+    }
+
+    // Wrap the text with the font's real advances, so the caret stops line up with the drawn glyphs
+    fn layout_text(&mut self, brush: &mut TextBrush){
+        if self.dirty{
+            self.dirty = false;
+            let font = brush.fonts()[self.font.0].as_scaled(self.text_size);
+            let advance = |prev: Option<char>, c: char| {
+                let id = font.glyph_id(c);
+                font.h_advance(id) + prev.map(|prev| font.kern(font.glyph_id(prev), id)).unwrap_or(0.0)
+            };
+            self.line_height = font.height() + font.line_gap();
+
+            let text = self.buffer.get_text();
+            let width = (self.bounds.size[0] - self.padding * 2.0).max(0.0);
+            self.lines = wrap_text(text, width, advance).into_iter().map(|(start, end)| {
+                let mut stops = vec![(start, 0.0)];
+                let (mut x, mut prev) = (0.0, None);
+                for (i, c) in text[start..end].char_indices(){
+                    x += advance(prev, c);
+                    stops.push((start + i + c.len_utf8(), x));
+                    prev = Some(c);
+                }
+                Line{ start, end, stops }
+            }).collect();
+            self.first_line = self.first_line.min(self.lines.len().saturating_sub(self.visible_lines()));
+        }
+
+        if let Some(click) = self.click.take(){
+            let line = ((click[1] - self.text_top()) / self.line_height).floor().max(0.0) as usize + self.first_line;
+            let target = match self.closest_stop(line, click[0] - self.text_left()){
+                Some(target) => target,
+                None => self.buffer.get_text().len(), // below the last line
+            };
+            self.buffer.set_cursor(target);
+        }
+
+        if std::mem::replace(&mut self.follow_cursor, false){
+            self.show_line(self.get_cursor_line());
+        }
+        if let Some(index) = self.reveal.take(){
+            self.show_line(self.line_of(index));
+        }
+        self.move_caret();
+        self.place_highlights();
+    }
+
+    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        if !self.enabled{
+            return;
+        }
+
+        match self.input.handle_event(event, window){
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed: true, pos, .. })) => {
+                let clicked = self.bounds.contains(pos);
+                self.set_focused(clicked);
+                if clicked{
+                    self.click = Some(pos);
+                    self.goal_x = None;
+                    self.caret.reset_blink();
+                }
+            }
+            Some(UiEvent::Scroll{ delta, pos, .. }) if self.bounds.contains(pos) => {
+                // A line for every line's worth of scrolling, and at least one
+                let lines = ((delta[1].abs() / self.line_height).round() as usize).max(1);
+                let line = if delta[1] > 0.0 { self.first_line.saturating_sub(lines) } else { self.first_line + lines };
+                self.scroll_to_line(line);
+            }
+            Some(UiEvent::Text(character)) if self.focused => {
+                // Shortcuts like command+c on macOS still come through as text
+                let modifiers = self.input.get_modifiers();
+                if !modifiers.command() || modifiers.alt{
+                    self.buffer.insert(character);
+                    self.goal_x = None;
+                    self.follow_cursor = true;
+                    self.caret.reset_blink();
+                    self.changed();
+                }
+            }
+            Some(UiEvent::Key(key)) if self.focused && key.pressed => {
+                let line = self.get_cursor_line();
+                match key.key{
+                    Key::Enter => {
+                        self.buffer.insert('\n');
+                        self.goal_x = None;
+                        self.changed();
+                    }
+                    Key::Escape => self.set_focused(false),
+                    Key::Up => self.move_lines(-1),
+                    Key::Down => self.move_lines(1),
+                    Key::PageUp => self.move_lines(-(self.visible_lines() as isize)),
+                    Key::PageDown => self.move_lines(self.visible_lines() as isize),
+                    Key::Home => {
+                        if let Some(line) = self.lines.get(line){
+                            self.buffer.set_cursor(line.start);
+                        }
+                        self.goal_x = None;
+                    }
+                    Key::End => {
+                        // The end of a wrapped line is the start of the next one, so stop before its last space
+                        let wrapped = self.lines.get(line + 1).map(|next| next.start) == self.lines.get(line).map(|line| line.end);
+                        if let Some(line) = self.lines.get(line){
+                            let end = if wrapped && line.stops.len() > 1 { line.stops[line.stops.len() - 2].0 } else { line.end };
+                            self.buffer.set_cursor(end);
+                        }
+                        self.goal_x = None;
+                    }
+                    _ => {
+                        self.goal_x = None;
+                        if self.buffer.handle_key(&key) == EditResult::Changed{
+                            self.changed();
+                        }
+                    }
+                }
+                self.follow_cursor = true;
+                self.caret.reset_blink();
+                self.move_caret();
+            }
+            _ => {}
+        }
+    }
+
+    /// Keyboard input is consumed while focused, and scrolling while there's somewhere to scroll to
+    fn handle_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window, phase: EventPhase) -> EventResponse{
+        if phase != EventPhase::Bubble{
+            return EventResponse::Continue;
+        }
+        let first_line = self.first_line;
+        let keyboard = self.enabled && self.focused && InputCategory::of(event) == Some(InputCategory::Keyboard);
+        self.handle_event_callback(event, window);
+        if keyboard || first_line != self.first_line { EventResponse::Consumed } else { EventResponse::Continue }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.bounds.pos
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(self.bounds)
+    }
+
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{
+        Some(self.bounds.size)
+    }
+
+    fn arrange(&mut self, rect: Rect){
+        if rect != self.bounds{
+            self.set_bounds(rect);
+        }
+    }
+
+    fn update(&mut self, dt: f32){
+        self.caret.update(dt);
+    }
+
+    /// The caret blinks while focused, and another frame is needed if there weren't enough quads to highlight
+    /// every match
+    fn is_animating(&self) -> bool{
+        (self.enabled && self.focused) || self.highlights.is_short()
+    }
+
+    fn rebuild(&mut self, device: &wgpu::Device) -> bool{
+        self.highlights.rebuild(device);
+        false
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.screen_dim = screen_dim;
+        self.border.prepare(queue, screen_dim);
+        self.background.prepare(queue, screen_dim);
+        self.highlights.prepare(queue, screen_dim);
+        self.caret.prepare(queue, screen_dim);
+    }
+}
+
+/// Replacing text keeps the cursor where it was (as far as the text goes), and counts as the user changing it
+impl Searchable for TextArea{
+    fn get_text(&self) -> &str{
+        self.buffer.get_text()
+    }
+
+    fn set_text(&mut self, text: String){
+        let cursor = self.buffer.get_cursor();
+        self.buffer.set_text(text);
+        self.buffer.set_cursor(cursor);
+        self.goal_x = None;
+        self.changed();
+    }
+
+    /// Changing the current match scrolls to it
+    fn set_highlights(&mut self, matches: &[Range<usize>], current: Option<usize>){
+        if self.highlights.set(matches, current){
+            self.reveal = current.map(|i| matches[i].start);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    // Every character is 10 pixels wide
+    fn lines(text: &str, max_width: f32) -> Vec<&str>{
+        wrap_text(text, max_width, |_, _| 10.0).into_iter().map(|(start, end)| &text[start..end]).collect()
+    }
+
+    /// Test that text wraps between words, keeping the spaces on the line they end
+    #[test]
+    fn word_wrap_test(){
+        assert_eq!(lines("the quick brown fox", 100.0), vec!["the quick ", "brown fox"]);
+        assert_eq!(lines("short", 100.0), vec!["short"]);
+        // Trailing spaces hang past the edge rather than starting a line
+        assert_eq!(lines("abcde     fgh", 50.0), vec!["abcde     ", "fgh"]);
+    }
+
+    /// Test that words too long for a line get split, and newlines always break
+    #[test]
+    fn hard_break_test(){
+        assert_eq!(lines("abcdefghij", 40.0), vec!["abcd", "efgh", "ij"]);
+        assert_eq!(lines("one\ntwo\n\nthree", 1000.0), vec!["one", "two", "", "three"]);
+        assert_eq!(lines("", 100.0), vec![""]);
+        // The byte ranges skip the newline itself
+        assert_eq!(wrap_text("ab\ncd", 100.0, |_, _| 10.0), vec![(0, 2), (3, 5)]);
+    }
+
+    /// Test that search matches are split across the lines they wrap onto, leaving out the newlines
+    #[test]
+    fn line_spans_test(){
+        let lines: Vec<Line> = wrap_text("the quick brown\nfox", 100.0, |_, _| 10.0).into_iter()
+            .map(|(start, end)| Line{ start, end, stops: Vec::new() })
+            .collect();
+        assert_eq!(line_spans(&lines, &(4..9)), vec![(0, 4..9)]);
+        assert_eq!(line_spans(&lines, &(6..13)), vec![(0, 6..10), (1, 10..13)]);
+        assert_eq!(line_spans(&lines, &(14..18)), vec![(1, 14..15), (2, 16..18)]);
+        assert!(line_spans(&lines, &(15..16)).is_empty());
+    }
+}
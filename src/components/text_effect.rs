@@ -0,0 +1,164 @@
+//! This module contains `TextEffect`, the per-character animations labels can play (typewriter reveals, waves,
+//! shaking and rainbows), and `TextEffects`, the clock and state that drives them.
+
+use std::f32::consts::TAU;
+
+use super::hsv_to_rgb;
+
+// How many times a second shaking glyphs jump somewhere new
+const SHAKE_RATE: f32 = 20.0;
+// How far along a wave (in radians) each character is from the one before it
+const WAVE_STEP: f32 = 0.5;
+// How far around the colour wheel each character is from the one before it
+const RAINBOW_STEP: f32 = 0.08;
+
+/// An animation played on every character of some text
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TextEffect{
+    /// Reveal the characters one at a time
+    Typewriter{ chars_per_second: f32 },
+    /// Bob the characters up and down, `amplitude` pixels each way, `speed` times a second
+    Wave{ amplitude: f32, speed: f32 },
+    /// Jitter the characters up to `intensity` pixels in any direction
+    Shake{ intensity: f32 },
+    /// Cycle the characters through the colours of the rainbow, `speed` times round the wheel a second
+    Rainbow{ speed: f32 },
+}
+
+/// # TextEffects
+///
+/// The effects on some text, and how far through them it is. Labels keep one of these (see `Label::add_effect`),
+/// and components drawing text themselves can too - call `update` every frame, and style each character with
+/// `offset`, `color` and `is_revealed`.
+///
+/// Effects stack, so a wave and a rainbow can play together. A typewriter stops animating once everything is
+/// revealed.
+#[derive(Debug, Clone, Default)]
+pub struct TextEffects{
+    effects: Vec<TextEffect>,
+    time: f32,
+    typed: f32, // how many characters the typewriter has revealed, as a fraction so slow typing still gets there
+    length: usize, // how many characters there are to reveal
+}
+
+impl TextEffects{
+    pub fn new() -> Self{
+        Self::default()
+    }
+
+    pub fn add(&mut self, effect: TextEffect){
+        self.effects.push(effect);
+    }
+
+    pub fn clear(&mut self){
+        self.effects.clear();
+    }
+
+    pub fn get(&self) -> &[TextEffect]{
+        &self.effects
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.effects.is_empty()
+    }
+
+    /// Whether any effect moves the characters, rather than just colouring or hiding them
+    pub fn moves_glyphs(&self) -> bool{
+        self.effects.iter().any(|effect| matches!(effect, TextEffect::Wave{ .. } | TextEffect::Shake{ .. }))
+    }
+
+    /// Start the effects again from the beginning, for text with `length` characters
+    pub fn restart(&mut self, length: usize){
+        self.time = 0.0;
+        self.typed = 0.0;
+        self.length = length;
+    }
+
+    /// Type the text out again (if there's a typewriter), now that it has `length` characters, leaving the other
+    /// effects where they are
+    pub fn retype(&mut self, length: usize){
+        self.typed = 0.0;
+        self.length = length;
+    }
+
+    /// Move the effects on by `dt` seconds
+    pub fn update(&mut self, dt: f32){
+        self.time += dt;
+        if let Some(speed) = self.typewriter_speed(){
+            self.typed = (self.typed + dt * speed).min(self.length as f32);
+        }
+    }
+
+    pub fn is_animating(&self) -> bool{
+        self.is_typing() || self.effects.iter().any(|effect| !matches!(effect, TextEffect::Typewriter{ .. }))
+    }
+
+    /// Whether a typewriter is still revealing characters
+    pub fn is_typing(&self) -> bool{
+        self.typewriter_speed().is_some() && self.typed < self.length as f32
+    }
+
+    /// Reveal the rest of the text straight away, like when a player skips ahead
+    pub fn finish_typing(&mut self){
+        self.typed = self.length as f32;
+    }
+
+    /// How many characters are showing. Without a typewriter, that's all of them.
+    pub fn get_revealed(&self) -> usize{
+        match self.typewriter_speed(){
+            Some(_) => self.typed as usize,
+            None => self.length,
+        }
+    }
+
+    pub fn is_revealed(&self, index: usize) -> bool{
+        self.typewriter_speed().is_none() || index < self.typed as usize
+    }
+
+    /// How far (in pixels) the character at `index` is moved from where it was laid out
+    pub fn offset(&self, index: usize) -> [f32; 2]{
+        let mut offset = [0.0, 0.0];
+        for effect in self.effects.iter(){
+            match *effect{
+                TextEffect::Wave{ amplitude, speed } => {
+                    offset[1] -= amplitude * (self.time * speed * TAU - index as f32 * WAVE_STEP).sin();
+                }
+                TextEffect::Shake{ intensity } => {
+                    let step = (self.time * SHAKE_RATE).floor();
+                    offset[0] += intensity * (noise(index as f32, step) * 2.0 - 1.0);
+                    offset[1] += intensity * (noise(index as f32 + 0.5, step) * 2.0 - 1.0);
+                }
+                _ => {}
+            }
+        }
+        offset
+    }
+
+    /// The colour of the character at `index`, given the colour of the text
+    pub fn color(&self, index: usize, base: [f32; 4]) -> [f32; 4]{
+        let mut color = base;
+        for effect in self.effects.iter(){
+            if let TextEffect::Rainbow{ speed } = *effect{
+                let hue = (self.time * speed + index as f32 * RAINBOW_STEP).rem_euclid(1.0);
+                let [r, g, b] = hsv_to_rgb([hue, 1.0, 1.0]);
+                color = [r, g, b, color[3]];
+            }
+        }
+        if !self.is_revealed(index){
+            color[3] = 0.0;
+        }
+        color
+    }
+
+    fn typewriter_speed(&self) -> Option<f32>{
+        self.effects.iter().find_map(|effect| match effect{
+            TextEffect::Typewriter{ chars_per_second } => Some(*chars_per_second),
+            _ => None,
+        })
+    }
+}
+
+// A cheap, repeatable random number between 0 and 1
+fn noise(x: f32, y: f32) -> f32{
+    ((x * 12.9898 + y * 78.233).sin() * 43758.547).rem_euclid(1.0)
+}
@@ -0,0 +1,254 @@
+//! This module contains `Notifications`, the toast messages every layout can show (see `Layout::notify`). Toasts
+//! slide in from the side, stack up in a corner of the screen and go away on their own after a few seconds.
+
+use super::{Label, TextGUIComponent};
+use crate::rendering::{Quad, TextBrush, measure_text};
+
+// The space between the toasts and the edge of the screen, and between each other
+const MARGIN: f32 = 16.0;
+const SPACING: f32 = 8.0;
+// The space around a toast's text
+const PADDING: f32 = 10.0;
+// How wide the coloured stripe down the side of a toast is
+const STRIPE_WIDTH: f32 = 4.0;
+// How long sliding in and out takes, in seconds
+const SLIDE_TIME: f32 = 0.25;
+
+/// How important a toast is, which sets the colour of its stripe
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum ToastLevel{
+    #[default]
+    Info,
+    Warning,
+    Error,
+}
+
+impl ToastLevel{
+    fn color(self) -> [f32; 4]{
+        match self{
+            ToastLevel::Info => [0.2, 0.5, 0.9, 1.0],
+            ToastLevel::Warning => [0.95, 0.7, 0.1, 1.0],
+            ToastLevel::Error => [0.85, 0.2, 0.2, 1.0],
+        }
+    }
+}
+
+/// The corner of the screen toasts stack up in
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum ToastCorner{
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+struct Toast{
+    label: Label,
+    level: ToastLevel,
+    size: [f32; 2], // the size of the text, once it's been measured
+    measured: bool,
+    background: Option<Quad>, // made once there's a device to make them with
+    stripe: Option<Quad>,
+
+    age: f32,
+    slide: f32, // 0 is off the side of the screen, 1 is all the way in
+    offset: f32, // how far along the stack it is from the corner, easing towards its place
+    leaving: bool,
+}
+
+impl Toast{
+    fn height(&self) -> f32{
+        self.size[1] + PADDING * 2.0
+    }
+}
+
+/// # Notifications
+///
+/// The toasts of a layout. Show one with `Layout::notify` (or `LayoutCommands::notify`, from a callback), and
+/// it slides in at the corner, pushing older toasts along the stack. Each toast stays for the timeout (4 seconds
+/// by default) and then slides back out. Once there are more than `max_visible` toasts, the oldest leave early.
+///
+/// Toasts are drawn over the rest of the layout, overlay included, and keep the GUI updating while they're on
+/// screen so they can time out.
+pub struct Notifications{
+    toasts: Vec<Toast>, // oldest first
+    corner: ToastCorner,
+    timeout: f32,
+    max_visible: usize,
+    width: f32,
+    text_size: f32,
+}
+
+impl Notifications{
+    pub(crate) fn new() -> Self{
+        Self{
+            toasts: Vec::new(),
+            corner: ToastCorner::default(),
+            timeout: 4.0,
+            max_visible: 5,
+            width: 300.0,
+            text_size: 16.0,
+        }
+    }
+
+    /// Show a toast
+    pub fn show(&mut self, text: &str, level: ToastLevel){
+        let mut label = Label::new(text, self.text_size, [0.0, 0.0]);
+        label.set_max_width(Some(self.text_width()));
+        self.toasts.push(Toast{
+            label,
+            level,
+            size: [0.0, 0.0],
+            measured: false,
+            background: None,
+            stripe: None,
+
+            age: 0.0,
+            slide: 0.0,
+            offset: 0.0,
+            leaving: false,
+        });
+
+        let staying = self.len();
+        if staying > self.max_visible{
+            self.toasts.iter_mut().filter(|toast| !toast.leaving).take(staying - self.max_visible).for_each(|toast| toast.leaving = true);
+        }
+    }
+
+    /// Slide every toast out
+    pub fn dismiss_all(&mut self){
+        for toast in self.toasts.iter_mut(){
+            toast.leaving = true;
+        }
+    }
+
+    /// Remove every toast straight away
+    pub fn clear(&mut self){
+        self.toasts.clear();
+    }
+
+    /// How many toasts are showing, not counting the ones on their way out
+    pub fn len(&self) -> usize{
+        self.toasts.iter().filter(|toast| !toast.leaving).count()
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.len() == 0
+    }
+
+    pub fn set_corner(&mut self, corner: ToastCorner){
+        self.corner = corner;
+    }
+
+    pub fn get_corner(&self) -> ToastCorner{
+        self.corner
+    }
+
+    /// Set how long (in seconds) toasts stay before leaving
+    pub fn set_timeout(&mut self, timeout: f32){
+        self.timeout = timeout.max(0.0);
+    }
+
+    pub fn get_timeout(&self) -> f32{
+        self.timeout
+    }
+
+    /// Set how many toasts can show at once. The oldest leave to make room for new ones.
+    pub fn set_max_visible(&mut self, max_visible: usize){
+        self.max_visible = max_visible.max(1);
+    }
+
+    /// Set how wide toasts are and how big their text is. Toasts already showing keep their size.
+    pub fn set_size(&mut self, width: f32, text_size: f32){
+        self.width = width;
+        self.text_size = text_size;
+    }
+
+    fn text_width(&self) -> f32{
+        self.width - STRIPE_WIDTH - PADDING * 2.0
+    }
+
+    /// Age the toasts, slide them in and out, and move them along the stack
+    pub(crate) fn update(&mut self, dt: f32){
+        let timeout = self.timeout;
+        for toast in self.toasts.iter_mut(){
+            toast.age += dt;
+            if toast.age >= timeout{
+                toast.leaving = true;
+            }
+            let target = if toast.leaving { 0.0 } else { 1.0 };
+            toast.slide = if target > toast.slide { (toast.slide + dt / SLIDE_TIME).min(target) } else { (toast.slide - dt / SLIDE_TIME).max(target) };
+        }
+        self.toasts.retain(|toast| !(toast.leaving && toast.slide <= 0.0));
+
+        // The newest toast goes in the corner, the others ease along to make room
+        let mut place = 0.0;
+        let ease = (dt * 12.0).min(1.0);
+        for toast in self.toasts.iter_mut().rev(){
+            toast.offset += (place - toast.offset) * ease;
+            place += toast.height() + SPACING;
+        }
+    }
+
+    pub(crate) fn is_animating(&self) -> bool{
+        !self.toasts.is_empty()
+    }
+
+    pub(crate) fn rebuild(&mut self, device: &wgpu::Device){
+        for toast in self.toasts.iter_mut().filter(|toast| toast.background.is_none()){
+            toast.background = Some(Quad::new([0.0, 0.0], [0.0, 0.0], [0.97, 0.97, 0.97, 1.0], device));
+            toast.stripe = Some(Quad::new([0.0, 0.0], [0.0, 0.0], toast.level.color(), device));
+        }
+    }
+
+    pub(crate) fn layout_text(&mut self, brush: &mut TextBrush){
+        let (text_width, text_size) = (self.text_width(), self.text_size);
+        for toast in self.toasts.iter_mut().filter(|toast| !toast.measured){
+            toast.size = measure_text(brush, toast.label.get_text(), text_size, wgpu_glyph::FontId::default(), Some(text_width));
+            toast.measured = true;
+        }
+    }
+
+    pub(crate) fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        let half = [screen_dim.0 as f32 / 2.0, screen_dim.1 as f32 / 2.0];
+        let right = matches!(self.corner, ToastCorner::TopRight | ToastCorner::BottomRight);
+        let top = matches!(self.corner, ToastCorner::TopLeft | ToastCorner::TopRight);
+        let width = self.width;
+        for toast in self.toasts.iter_mut(){
+            let height = toast.height();
+            let (background, stripe) = match (&mut toast.background, &mut toast.stripe){
+                (Some(background), Some(stripe)) => (background, stripe),
+                _ => continue,
+            };
+            // Ease out while sliding in, so toasts come to a gentle stop
+            let shown = 1.0 - (1.0 - toast.slide).powi(3);
+            let hidden = (1.0 - shown) * (width + MARGIN);
+            let x = if right { half[0] - MARGIN - width / 2.0 + hidden } else { -half[0] + MARGIN + width / 2.0 - hidden };
+            let y = if top { -half[1] + MARGIN + toast.offset + height / 2.0 } else { half[1] - MARGIN - toast.offset - height / 2.0 };
+
+            background.set_pos([x, y]);
+            background.set_size([width, height]);
+            stripe.set_pos([x - width / 2.0 + STRIPE_WIDTH / 2.0, y]);
+            stripe.set_size([STRIPE_WIDTH, height]);
+            background.prepare(queue, screen_dim);
+            stripe.prepare(queue, screen_dim);
+            toast.label.set_pos([x - width / 2.0 + STRIPE_WIDTH + PADDING, y - toast.size[1] / 2.0], screen_dim);
+        }
+    }
+
+    pub(crate) fn render<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>){
+        for toast in self.toasts.iter(){
+            if let (Some(background), Some(stripe)) = (&toast.background, &toast.stripe){
+                background.render(render_pass);
+                stripe.render(render_pass);
+            }
+        }
+    }
+
+    pub(crate) fn render_text(&self, brush: &mut TextBrush){
+        for toast in self.toasts.iter().filter(|toast| toast.measured && toast.background.is_some()){
+            TextGUIComponent::render_text(&toast.label, brush);
+        }
+    }
+}
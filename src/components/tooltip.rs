@@ -0,0 +1,675 @@
+//! This module contains the `Tooltip` component, a popup that shows up after hovering over an area for a moment.
+//! The content can be any component that implements `TooltipContent` - text, images, or a stack of them
+//! (like an image next to a title and a description). The popup is placed so it never runs off the screen.
+
+use std::any::Any;
+
+use winit::event::{ElementState, Event, WindowEvent};
+
+use crate::{layout::ComponentHandle, rendering::{Quad, Renderer, TextBrush, measure_text}};
+
+use super::{GUIComponent, EventGUIComponent, Image, Label, Orientation, Rect, TextGUIComponent, base_components::window_to_component_space};
+
+/// # TooltipContent
+///
+/// A component that can be shown inside a tooltip. On top of being a normal `GUIComponent`, it has to
+/// know its own size, so the tooltip can be fitted around it, and be able to move, so it can follow the tooltip.
+pub trait TooltipContent: GUIComponent{
+    /// The size of the content in pixels
+    fn get_size(&self) -> [f32; 2];
+    /// Move the content so its centre is at a position (in the same space as a `Quad`)
+    fn place(&mut self, pos: [f32; 2], screen_dim: (u32, u32));
+}
+
+impl TooltipContent for Image{
+    fn get_size(&self) -> [f32; 2]{
+        Image::get_size(self)
+    }
+
+    fn place(&mut self, pos: [f32; 2], _screen_dim: (u32, u32)){
+        self.set_pos(pos);
+    }
+}
+
+/// # TooltipText
+///
+/// A block of text for tooltips. It gets measured when it's created, so the text can't be changed afterwards -
+/// make a new one instead.
+pub struct TooltipText{
+    label: Label,
+    size: [f32; 2],
+    pos: [f32; 2],
+}
+
+impl TooltipText{
+    /// Create some tooltip text, wrapping it onto new lines past `max_width` pixels
+    pub fn new<S: Into<String> + Copy>(content: S, text_size: f32, max_width: Option<f32>, renderer: &mut Renderer) -> Self{
+        Self::with_font(content, text_size, max_width, wgpu_glyph::FontId::default(), renderer)
+    }
+
+    /// Create some tooltip text using a font from `Renderer::add_font`
+    pub fn with_font<S: Into<String> + Copy>(content: S, text_size: f32, max_width: Option<f32>, font: wgpu_glyph::FontId, renderer: &mut Renderer) -> Self{
+        let mut label = Label::new(content, text_size, [0.0, 0.0]);
+        label.set_font(font);
+        label.set_max_width(max_width);
+        let size = label.measure(renderer);
+
+        Self{
+            label,
+            size,
+            pos: [0.0, 0.0],
+        }
+    }
+}
+
+impl GUIComponent for TooltipText{
+    fn render<'a, 'b>(&'a self, _render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {}
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        TextGUIComponent::render_text(&self.label, brush);
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        true
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.pos
+    }
+}
+
+impl TooltipContent for TooltipText{
+    fn get_size(&self) -> [f32; 2]{
+        self.size
+    }
+
+    fn place(&mut self, pos: [f32; 2], screen_dim: (u32, u32)){
+        self.pos = pos;
+        // Labels are positioned from their top left corner
+        self.label.set_pos([pos[0] - self.size[0] / 2.0, pos[1] - self.size[1] / 2.0], screen_dim);
+    }
+}
+
+/// # TooltipStack
+///
+/// Lays out several pieces of tooltip content in a row or a column, with some spacing between them.
+/// Stacks can hold other stacks, so any tree of content can be built up.
+///
+/// Items are lined up with the top (for a row) or the left (for a column) of the stack.
+pub struct TooltipStack{
+    items: Vec<Box<dyn TooltipContent>>,
+    orientation: Orientation,
+    spacing: f32,
+    pos: [f32; 2],
+}
+
+impl TooltipStack{
+    pub fn new(orientation: Orientation, spacing: f32) -> Self{
+        Self{
+            items: Vec::new(),
+            orientation,
+            spacing,
+            pos: [0.0, 0.0],
+        }
+    }
+
+    /// Add an item to the end of the stack
+    pub fn push(&mut self, item: Box<dyn TooltipContent>){
+        self.items.push(item);
+    }
+
+    /// Add an item, for building a stack in one expression
+    pub fn with(mut self, item: Box<dyn TooltipContent>) -> Self{
+        self.push(item);
+        self
+    }
+
+    /// The usual rich tooltip - an optional image on the left, with a title and a description next to it
+    pub fn card(image: Option<Image>, title: &str, description: Option<&str>, renderer: &mut Renderer) -> Self{
+        let mut text = TooltipStack::new(Orientation::Vertical, 4.0)
+            .with(Box::new(TooltipText::new(title, 20.0, Some(240.0), renderer)));
+        if let Some(description) = description{
+            text.push(Box::new(TooltipText::new(description, 14.0, Some(240.0), renderer)));
+        }
+
+        match image{
+            Some(image) => TooltipStack::new(Orientation::Horizontal, 8.0).with(Box::new(image)).with(Box::new(text)),
+            None => text,
+        }
+    }
+
+    pub fn len(&self) -> usize{
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.items.is_empty()
+    }
+}
+
+impl GUIComponent for TooltipStack{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        for item in self.items.iter(){
+            item.render(render_pass);
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        for item in self.items.iter(){
+            item.render_text(brush);
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        true
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.pos
+    }
+
+    fn update(&mut self, dt: f32){
+        for item in self.items.iter_mut(){
+            item.update(dt);
+        }
+    }
+
+    fn is_animating(&self) -> bool{
+        self.items.iter().any(|item| item.is_animating())
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        for item in self.items.iter_mut(){
+            item.prepare(queue, screen_dim);
+        }
+    }
+}
+
+impl TooltipContent for TooltipStack{
+    fn get_size(&self) -> [f32; 2]{
+        let gaps = self.spacing * self.items.len().saturating_sub(1) as f32;
+        let sizes = self.items.iter().map(|item| item.get_size());
+        match self.orientation{
+            Orientation::Horizontal => sizes.fold([gaps, 0.0], |total, size| [total[0] + size[0], total[1].max(size[1])]),
+            Orientation::Vertical => sizes.fold([0.0, gaps], |total, size| [total[0].max(size[0]), total[1] + size[1]]),
+        }
+    }
+
+    fn place(&mut self, pos: [f32; 2], screen_dim: (u32, u32)){
+        self.pos = pos;
+        let size = self.get_size();
+        let mut cursor = [pos[0] - size[0] / 2.0, pos[1] - size[1] / 2.0]; // top left of the next item
+
+        for item in self.items.iter_mut(){
+            let item_size = item.get_size();
+            item.place([cursor[0] + item_size[0] / 2.0, cursor[1] + item_size[1] / 2.0], screen_dim);
+            match self.orientation{
+                Orientation::Horizontal => cursor[0] += item_size[0] + self.spacing,
+                Orientation::Vertical => cursor[1] += item_size[1] + self.spacing,
+            }
+        }
+    }
+}
+
+/// Work out where to put a tooltip of a given size, shown for a point (like the cursor), returning its centre.
+///
+/// The tooltip goes below and to the right of the point, flips to the other side if that would run off the
+/// right or bottom of the screen, and is then pushed back inside the screen if it still doesn't fit.
+pub(crate) fn place_tooltip(point: [f32; 2], size: [f32; 2], offset: f32, screen_dim: (u32, u32)) -> [f32; 2]{
+    let half_screen = [screen_dim.0 as f32 / 2.0, screen_dim.1 as f32 / 2.0];
+    let mut top_left = [0.0; 2];
+
+    for axis in 0..2{
+        top_left[axis] = point[axis] + offset;
+        if top_left[axis] + size[axis] > half_screen[axis]{
+            top_left[axis] = point[axis] - offset - size[axis];
+        }
+        // Keep it on screen, favouring the top left edge if it's too big to fit at all
+        top_left[axis] = top_left[axis].min(half_screen[axis] - size[axis]).max(-half_screen[axis]);
+    }
+
+    [top_left[0] + size[0] / 2.0, top_left[1] + size[1] / 2.0]
+}
+
+/// # Tooltip
+///
+/// Shows some content in a popup after the cursor has hovered over a target area for a moment (`delay`,
+/// in seconds). It hides again once the cursor leaves the target or a mouse button is pressed.
+///
+/// Tooltips belong in the overlay layer (`Layout::add_overlay_component`), so they get drawn over everything else.
+pub struct Tooltip{
+    target: Rect,
+    content: Box<dyn TooltipContent>,
+
+    background: Quad,
+    border: Quad,
+    padding: f32,
+    offset: f32, // gap between the cursor and the tooltip
+    delay: f32,
+
+    hover_time: f32,
+    hovering: bool,
+    visible: bool,
+    anchor: [f64; 2], // where the cursor was when the tooltip showed up
+    cursor_pos: [f64; 2],
+    enabled: bool,
+}
+
+impl Tooltip{
+    /// Create a tooltip showing some content while hovering over a target area
+    pub fn new(target: Rect, content: Box<dyn TooltipContent>, renderer: &Renderer) -> Self{
+        Self{
+            target,
+            content,
+
+            background: Quad::new([0.0, 0.0], [0.0, 0.0], [1.0, 1.0, 0.95, 1.0], &renderer.device),
+            border: Quad::new([0.0, 0.0], [0.0, 0.0], [0.3, 0.3, 0.3, 1.0], &renderer.device),
+            padding: 8.0,
+            offset: 16.0,
+            delay: 0.5,
+
+            hover_time: 0.0,
+            hovering: false,
+            visible: false,
+            anchor: [0.0, 0.0],
+            cursor_pos: [0.0, 0.0],
+            enabled: true,
+        }
+    }
+
+    /// Create a plain text tooltip
+    pub fn text(target: Rect, text: &str, renderer: &mut Renderer) -> Self{
+        let content = TooltipText::new(text, 16.0, Some(320.0), renderer);
+        Tooltip::new(target, Box::new(content), renderer)
+    }
+
+    /// Swap the content for something else
+    pub fn set_content(&mut self, content: Box<dyn TooltipContent>){
+        self.content = content;
+    }
+
+    pub fn borrow_content(&self) -> &dyn TooltipContent{
+        self.content.as_ref()
+    }
+
+    pub fn borrow_content_mut(&mut self) -> &mut dyn TooltipContent{
+        self.content.as_mut()
+    }
+
+    /// Change the area that has to be hovered over
+    pub fn set_target(&mut self, target: Rect){
+        self.target = target;
+    }
+
+    /// Set how long (in seconds) the cursor has to hover before the tooltip shows up
+    pub fn set_delay(&mut self, delay: f32){
+        self.delay = delay.max(0.0);
+    }
+
+    /// Set the space between the content and the edge of the tooltip
+    pub fn set_padding(&mut self, padding: f32){
+        self.padding = padding;
+    }
+
+    pub fn set_colors(&mut self, background: [f32; 4], border: [f32; 4]){
+        self.background.set_color(background);
+        self.border.set_color(border);
+    }
+
+    pub fn is_visible(&self) -> bool{
+        self.visible
+    }
+
+    pub fn hide(&mut self){
+        self.visible = false;
+        self.hover_time = 0.0;
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+        self.hide();
+    }
+
+    /// The size of the tooltip, content and padding included
+    pub fn get_size(&self) -> [f32; 2]{
+        let size = self.content.get_size();
+        [size[0] + self.padding * 2.0, size[1] + self.padding * 2.0]
+    }
+}
+
+impl EventGUIComponent for Tooltip{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if self.enabled && self.visible{
+            // The content goes last, as images leave their texture bound
+            self.border.render(render_pass);
+            self.background.render(render_pass);
+            self.content.render(render_pass);
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if self.enabled && self.visible{
+            self.content.render_text(brush);
+        }
+    }
+
+    fn handle_event_callback(&mut self, event: &Event<()>, window: &winit::window::Window){
+        if !self.enabled{
+            return;
+        }
+
+        match event{
+            Event::WindowEvent{
+                ref event,
+                window_id,
+            } if window_id == &window.id() => {
+                match event{
+                    WindowEvent::CursorMoved{ position, .. } => {
+                        self.cursor_pos = [position.x, position.y];
+                        let size = window.inner_size();
+                        self.hovering = self.target.contains(window_to_component_space(self.cursor_pos, (size.width, size.height)));
+                        if !self.hovering{
+                            self.hide();
+                        }
+                    }
+                    WindowEvent::CursorLeft{ .. } => {
+                        self.hovering = false;
+                        self.hide();
+                    }
+                    WindowEvent::MouseInput{ state: ElementState::Pressed, .. } => {
+                        self.hide();
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.background.get_pos()
+    }
+
+    fn update(&mut self, dt: f32){
+        if self.hovering && !self.visible{
+            self.hover_time += dt;
+            if self.hover_time >= self.delay{
+                self.visible = true;
+                self.anchor = self.cursor_pos;
+            }
+        }
+        if self.visible{
+            self.content.update(dt);
+        }
+    }
+
+    /// Tick while waiting to show up, and while the content is animating
+    fn is_animating(&self) -> bool{
+        self.enabled && ((self.hovering && !self.visible) || (self.visible && self.content.is_animating()))
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        if !self.visible{
+            return;
+        }
+
+        let size = self.get_size();
+        let anchor = window_to_component_space(self.anchor, screen_dim);
+        let pos = place_tooltip(anchor, size, self.offset, screen_dim);
+
+        self.border.set_pos(pos);
+        self.border.set_size([size[0] + 2.0, size[1] + 2.0]);
+        self.background.set_pos(pos);
+        self.background.set_size(size);
+        self.content.place(pos, screen_dim);
+
+        self.border.prepare(queue, screen_dim);
+        self.background.prepare(queue, screen_dim);
+        self.content.prepare(queue, screen_dim);
+    }
+}
+
+const HOVER_TEXT_SIZE: f32 = 16.0;
+const HOVER_MAX_WIDTH: f32 = 320.0;
+
+// The tooltip a layout shows for the component under the cursor (see `Layout::set_tooltip`). The layout tells it
+// what's hovered, and it waits out the delay, measures the text and draws itself on top of the layout.
+pub(crate) struct HoverTooltip{
+    target: Option<ComponentHandle>,
+    label: Label,
+    size: [f32; 2], // the size of the text, once it's been measured
+    measured: bool,
+
+    background: Option<Quad>, // made once there's a device to make them with
+    border: Option<Quad>,
+    padding: f32,
+    offset: f32,
+    delay: f32,
+
+    hover_time: f32,
+    visible: bool,
+    dismissed: bool, // a mouse button was pressed, so stay hidden until something else is hovered
+    cursor: [f32; 2],
+    anchor: [f32; 2], // where the cursor was when the tooltip showed up
+}
+
+impl HoverTooltip{
+    pub(crate) fn new() -> Self{
+        let mut label = Label::new("", HOVER_TEXT_SIZE, [0.0, 0.0]);
+        label.set_max_width(Some(HOVER_MAX_WIDTH));
+        Self{
+            target: None,
+            label,
+            size: [0.0, 0.0],
+            measured: false,
+
+            background: None,
+            border: None,
+            padding: 6.0,
+            offset: 16.0,
+            delay: 0.5,
+
+            hover_time: 0.0,
+            visible: false,
+            dismissed: false,
+            cursor: [0.0, 0.0],
+            anchor: [0.0, 0.0],
+        }
+    }
+
+    pub(crate) fn get_target(&self) -> Option<ComponentHandle>{
+        self.target
+    }
+
+    /// Start waiting to show a tooltip for a component, or hide it if nothing with a tooltip is hovered.
+    /// The cursor is in component space.
+    pub(crate) fn set_target(&mut self, target: Option<(ComponentHandle, &str)>, cursor: [f32; 2]){
+        self.cursor = cursor;
+        match target{
+            Some((handle, text)) if self.target == Some(handle) => {
+                if self.label.get_text() != text{
+                    self.set_text(text);
+                }
+            }
+            Some((handle, text)) => {
+                self.clear();
+                self.target = Some(handle);
+                self.set_text(text);
+            }
+            None => self.clear(),
+        }
+    }
+
+    /// Change the text of the tooltip being shown (or waited on)
+    pub(crate) fn set_text(&mut self, text: &str){
+        self.label.set_text(text);
+        self.measured = false;
+    }
+
+    /// Move the target along with the component, or forget it if `moved` returns `None`
+    pub(crate) fn move_target<F: FnOnce(ComponentHandle) -> Option<ComponentHandle>>(&mut self, moved: F){
+        match self.target.and_then(moved){
+            Some(handle) => self.target = Some(handle),
+            None => self.clear(),
+        }
+    }
+
+    /// Hide the tooltip until the cursor moves on to something else
+    pub(crate) fn dismiss(&mut self){
+        self.visible = false;
+        self.dismissed = true;
+    }
+
+    fn clear(&mut self){
+        self.target = None;
+        self.hover_time = 0.0;
+        self.visible = false;
+        self.dismissed = false;
+    }
+
+    pub(crate) fn set_delay(&mut self, delay: f32){
+        self.delay = delay.max(0.0);
+    }
+
+    pub(crate) fn get_delay(&self) -> f32{
+        self.delay
+    }
+
+    pub(crate) fn is_visible(&self) -> bool{
+        self.visible
+    }
+
+    /// Wait out the delay, returning true while still waiting
+    pub(crate) fn update(&mut self, dt: f32) -> bool{
+        if self.target.is_none() || self.visible || self.dismissed{
+            return false;
+        }
+        self.hover_time += dt;
+        if self.hover_time >= self.delay{
+            self.visible = true;
+            self.anchor = self.cursor;
+        }
+        true
+    }
+
+    pub(crate) fn rebuild(&mut self, device: &wgpu::Device){
+        if self.background.is_none(){
+            self.background = Some(Quad::new([0.0, 0.0], [0.0, 0.0], [1.0, 1.0, 0.95, 1.0], device));
+            self.border = Some(Quad::new([0.0, 0.0], [0.0, 0.0], [0.3, 0.3, 0.3, 1.0], device));
+        }
+    }
+
+    pub(crate) fn layout_text(&mut self, brush: &mut TextBrush){
+        if !self.measured{
+            self.size = measure_text(brush, self.label.get_text(), HOVER_TEXT_SIZE, wgpu_glyph::FontId::default(), Some(HOVER_MAX_WIDTH));
+            self.measured = true;
+        }
+    }
+
+    pub(crate) fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        let (background, border) = match (&mut self.background, &mut self.border){
+            (Some(background), Some(border)) if self.visible => (background, border),
+            _ => return,
+        };
+        let size = [self.size[0] + self.padding * 2.0, self.size[1] + self.padding * 2.0];
+        let pos = place_tooltip(self.anchor, size, self.offset, screen_dim);
+
+        border.set_pos(pos);
+        border.set_size([size[0] + 2.0, size[1] + 2.0]);
+        background.set_pos(pos);
+        background.set_size(size);
+        // Labels are positioned from their top left corner
+        self.label.set_pos([pos[0] - self.size[0] / 2.0, pos[1] - self.size[1] / 2.0], screen_dim);
+
+        border.prepare(queue, screen_dim);
+        background.prepare(queue, screen_dim);
+    }
+
+    pub(crate) fn render<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>){
+        if let (Some(background), Some(border)) = (&self.background, &self.border){
+            if self.visible{
+                border.render(render_pass);
+                background.render(render_pass);
+            }
+        }
+    }
+
+    pub(crate) fn render_text(&self, brush: &mut TextBrush){
+        if self.visible && self.measured{
+            TextGUIComponent::render_text(&self.label, brush);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Test that tooltips flip away from the edges they would run off, and stay on screen
+    #[test]
+    fn place_tooltip_test(){
+        let screen_dim = (800, 600);
+
+        // Plenty of room, so it goes below and to the right of the cursor
+        assert_eq!(place_tooltip([0.0, 0.0], [100.0, 50.0], 10.0, screen_dim), [60.0, 35.0]);
+
+        // Near the bottom right corner, so it flips to above and to the left
+        assert_eq!(place_tooltip([390.0, 290.0], [100.0, 50.0], 10.0, screen_dim), [330.0, 255.0]);
+
+        // Too close to both sides to fit either way, so it gets pushed back on screen
+        assert_eq!(place_tooltip([-300.0, 0.0], [780.0, 50.0], 10.0, screen_dim), [-10.0, 35.0]);
+
+        // Bigger than the screen, it lines up with the top left
+        assert_eq!(place_tooltip([0.0, 0.0], [1000.0, 50.0], 10.0, screen_dim), [100.0, 35.0]);
+    }
+}
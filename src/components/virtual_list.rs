@@ -0,0 +1,425 @@
+//! This module contains the `VirtualListView`, a scrolling list that only has rows for the items that can be seen,
+//! so lists with huge numbers of items stay cheap.
+
+use std::{any::Any, ops::Range};
+
+use crate::{input::{EventPhase, EventResponse, InputCategory, InputState, MouseEvent, UiEvent}, rendering::{Renderer, TextBrush}};
+
+use super::{EventGUIComponent, KineticScroll, ListAdapter, ListChange, ListData, Orientation, Rect, Scrollbar};
+
+/// The items that can be seen (even partly) in a viewport `viewport` pixels tall, scrolled `offset` pixels down a
+/// list of `count` rows that are each `row_height` pixels tall
+pub(crate) fn visible_rows(offset: f32, viewport: f32, row_height: f32, count: usize) -> Range<usize>{
+    if row_height <= 0.0 || count == 0{
+        return 0..0;
+    }
+    let first = (offset.max(0.0) / row_height).floor() as usize;
+    let last = ((offset + viewport).max(0.0) / row_height).ceil() as usize;
+    first.min(count)..last.min(count)
+}
+
+/// # VirtualListView
+///
+/// A scrolling list of rows made from a `ListData` by a `ListAdapter`, like a `ListView` in a `ScrollView`, except
+/// that rows only exist for the items in view. Rows scrolled out of view are kept and reused for the items
+/// scrolled into it, with `ListAdapter::update_row` - so an adapter that can update rows in place means scrolling
+/// never makes new ones. A list of a hundred thousand items only ever has a screenful of rows.
+///
+/// Every row is the same height, which is what lets the list know where any item is without making its row.
+///
+/// Like a `ScrollView`, the rows' quads are clipped to the list, but their text isn't.
+pub struct VirtualListView<T>{
+    data: ListData<T>,
+    adapter: Box<dyn ListAdapter<T>>,
+    rect: Rect,
+    row_height: f32,
+    count: usize,
+    rows: Vec<(usize, Box<dyn EventGUIComponent>)>, // the item each row shows, and the row, in order
+    spare: Vec<Box<dyn EventGUIComponent>>, // rows scrolled out of view, waiting to be reused
+    stale: bool, // every row needs its item again
+    scroll: KineticScroll,
+    scrollbar: Scrollbar,
+
+    input: InputState,
+    screen_dim: (u32, u32),
+    enabled: bool,
+}
+
+impl<T: 'static> VirtualListView<T>{
+    /// Create a list filling `rect`, with rows `row_height` pixels tall. The rows get made before the first frame.
+    pub fn new(rect: Rect, row_height: f32, data: ListData<T>, adapter: Box<dyn ListAdapter<T>>, renderer: &Renderer) -> Self{
+        let mut list = Self{
+            data,
+            adapter,
+            rect,
+            row_height,
+            count: 0,
+            rows: Vec::new(),
+            spare: Vec::new(),
+            stale: false,
+            scroll: KineticScroll::new(),
+            scrollbar: Scrollbar::new(rect.pos, rect.size[1], Orientation::Vertical, renderer),
+
+            input: InputState::new(),
+            screen_dim: (0, 0),
+            enabled: true,
+        };
+        list.layout_rows();
+        list
+    }
+
+    /// Get a handle to the data, to change the items
+    pub fn get_data(&self) -> ListData<T>{
+        self.data.clone()
+    }
+
+    /// Borrow the row showing an item, if it's in view, for example to downcast it
+    pub fn borrow_row_mut(&mut self, index: usize) -> Option<&mut Box<dyn EventGUIComponent>>{
+        self.rows.iter_mut().find(|(item, _)| *item == index).map(|(_, row)| row)
+    }
+
+    /// How many rows have been made, in view or waiting to be reused
+    pub fn get_row_count(&self) -> usize{
+        self.rows.len() + self.spare.len()
+    }
+
+    /// The items in view, as of the last frame
+    pub fn get_visible_range(&self) -> Range<usize>{
+        visible_rows(self.scroll.get_offset(), self.rect.size[1], self.row_height, self.count)
+    }
+
+    /// Scroll just far enough to bring an item into view
+    pub fn scroll_to_item(&mut self, index: usize, animated: bool){
+        self.scroll.ensure_visible(index as f32 * self.row_height, self.row_height, animated);
+        self.layout_rows();
+    }
+
+    /// How far down the list is scrolled, in pixels
+    pub fn get_offset(&self) -> f32{
+        self.scroll.get_offset()
+    }
+
+    /// Borrow the scrolling physics, to change the overscroll effect or scroll somewhere from code
+    pub fn borrow_scroll_mut(&mut self) -> &mut KineticScroll{
+        &mut self.scroll
+    }
+
+    /// Borrow the scrollbar, to change its colours
+    pub fn borrow_scrollbar_mut(&mut self) -> &mut Scrollbar{
+        &mut self.scrollbar
+    }
+
+    pub fn set_row_height(&mut self, row_height: f32){
+        self.row_height = row_height;
+        self.layout_rows();
+    }
+
+    pub fn get_row_height(&self) -> f32{
+        self.row_height
+    }
+
+    pub fn set_rect(&mut self, rect: Rect){
+        self.rect = rect;
+        self.layout_rows();
+    }
+
+    pub fn get_rect(&self) -> Rect{
+        self.rect
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+    }
+
+    fn is_scrollable(&self) -> bool{
+        self.count as f32 * self.row_height > self.rect.size[1]
+    }
+
+    // Place the scrollbar, and move the rows to where their items are
+    fn layout_rows(&mut self){
+        let bar = if self.is_scrollable() { self.scrollbar.get_thickness() } else { 0.0 };
+        let left = self.rect.pos[0] - self.rect.size[0] / 2.0;
+        let top = self.rect.pos[1] - self.rect.size[1] / 2.0;
+
+        let content = self.count as f32 * self.row_height;
+        self.scroll.set_content(content, self.rect.size[1]);
+        self.scrollbar.set_content(content, self.rect.size[1]);
+        self.scrollbar.set_value(self.scroll.get_offset().max(0.0));
+        self.scrollbar.set_length(self.rect.size[1]);
+        self.scrollbar.set_pos([left + self.rect.size[0] - bar / 2.0, self.rect.pos[1]]);
+
+        let width = self.rect.size[0] - bar;
+        let offset = self.scroll.get_offset();
+        for (index, row) in self.rows.iter_mut(){
+            let y = top + (*index as f32 + 0.5) * self.row_height - offset;
+            row.measure([width, self.row_height]);
+            row.arrange(Rect::new([left + width / 2.0, y], [width, self.row_height]));
+        }
+    }
+
+    // Give the rows for the items in view their items, reusing rows that went out of view
+    fn sync(&mut self, device: &wgpu::Device){
+        let mut updated = Vec::new();
+        for change in self.data.take_changes(){
+            match change{
+                ListChange::Updated(index) => updated.push(index),
+                // Anything else moves items around, so every row might be showing the wrong one now
+                _ => self.stale = true,
+            }
+        }
+        self.count = self.data.len();
+        let range = visible_rows(self.scroll.get_offset(), self.rect.size[1], self.row_height, self.count);
+
+        let mut kept = Vec::with_capacity(range.len());
+        for (index, row) in self.rows.drain(..){
+            if range.contains(&index){
+                kept.push((index, row));
+            }else{
+                self.spare.push(row);
+            }
+        }
+        for (index, row) in kept.iter_mut(){
+            if self.stale || updated.contains(index){
+                let old = std::mem::replace(row, Box::new(Empty));
+                *row = bind(&self.data, self.adapter.as_ref(), *index, Some(old), device);
+            }
+        }
+        for index in range{
+            if !kept.iter().any(|(item, _)| *item == index){
+                kept.push((index, bind(&self.data, self.adapter.as_ref(), index, self.spare.pop(), device)));
+            }
+        }
+        kept.sort_by_key(|(index, _)| *index);
+        self.rows = kept;
+        self.stale = false;
+    }
+
+    // Presses and the wheel only go to the rows when the cursor is over the list
+    fn reaches_rows(&self, event: &winit::event::Event<()>) -> bool{
+        use winit::event::{ElementState, Event, WindowEvent};
+
+        match event{
+            Event::WindowEvent{ event: WindowEvent::MouseInput{ state: ElementState::Pressed, .. }, .. }
+            | Event::WindowEvent{ event: WindowEvent::MouseWheel{ .. }, .. } => self.rect.contains(self.input.get_cursor_pos()),
+            _ => true,
+        }
+    }
+
+    // Scroll with the wheel and the scrollbar, returning true if the event scrolled the list
+    fn own_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window) -> bool{
+        let mut scrolled = false;
+        match self.input.handle_event(event, window){
+            Some(UiEvent::Scroll{ delta, pos, .. }) if self.rect.contains(pos) && self.is_scrollable() => {
+                self.scroll.scroll_by(-delta[1]);
+                scrolled = true;
+            }
+            Some(UiEvent::MouseInput(MouseEvent{ pressed: true, pos, .. })) if self.rect.contains(pos) => {
+                self.scroll.set_offset(self.scroll.get_offset());
+            }
+            _ => {}
+        }
+
+        if self.is_scrollable(){
+            let value = self.scrollbar.get_value();
+            self.scrollbar.handle_event_callback(event, window);
+            if self.scrollbar.get_value() != value{
+                self.scroll.set_offset(self.scrollbar.get_value());
+                scrolled = true;
+            }
+        }
+        if scrolled{
+            self.layout_rows();
+        }
+        scrolled
+    }
+}
+
+// Update a reused row to show an item, or make a new one if there's no row to reuse or it can't be updated
+fn bind<T>(data: &ListData<T>, adapter: &dyn ListAdapter<T>, index: usize, row: Option<Box<dyn EventGUIComponent>>, device: &wgpu::Device) -> Box<dyn EventGUIComponent>{
+    data.with(index, |item| match row{
+        Some(mut row) => {
+            if adapter.update_row(item, row.as_mut()) { row } else { adapter.create_row(item, device) }
+        }
+        None => adapter.create_row(item, device),
+    }).unwrap_or_else(|| Box::new(Empty))
+}
+
+// Stands in for a row while it's being swapped out
+struct Empty;
+
+impl EventGUIComponent for Empty{
+    fn render<'a, 'b>(&'a self, _render_pass: &'b mut wgpu::RenderPass<'a>) where 'a: 'b {}
+    fn handle_event_callback(&mut self, _event: &winit::event::Event<()>, _window: &winit::window::Window){}
+    fn as_any(&self) -> &dyn Any{ self }
+    fn as_any_mut(&mut self) -> &mut dyn Any{ self }
+    fn get_text_id(&self) -> Option<usize>{ None }
+    fn is_enabled(&self) -> bool{ false }
+    fn get_pos(&self) -> [f32; 2]{ [0.0, 0.0] }
+}
+
+impl<T: 'static> EventGUIComponent for VirtualListView<T>{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if !self.enabled || self.screen_dim.0 == 0 || self.screen_dim.1 == 0{
+            return;
+        }
+
+        // The scissor rect is in window pixels, and has to stay inside the window
+        let (width, height) = (self.screen_dim.0 as f32, self.screen_dim.1 as f32);
+        let left = (self.rect.pos[0] - self.rect.size[0] / 2.0 + width / 2.0).clamp(0.0, width);
+        let top = (self.rect.pos[1] - self.rect.size[1] / 2.0 + height / 2.0).clamp(0.0, height);
+        let right = (self.rect.pos[0] + self.rect.size[0] / 2.0 + width / 2.0).clamp(0.0, width);
+        let bottom = (self.rect.pos[1] + self.rect.size[1] / 2.0 + height / 2.0).clamp(0.0, height);
+        if right > left && bottom > top{
+            render_pass.set_scissor_rect(left as u32, top as u32, (right - left) as u32, (bottom - top) as u32);
+            for (_, row) in self.rows.iter(){
+                row.render(render_pass);
+            }
+            render_pass.set_scissor_rect(0, 0, self.screen_dim.0, self.screen_dim.1);
+        }
+
+        if self.is_scrollable(){
+            self.scrollbar.render(render_pass);
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if self.enabled{
+            for (_, row) in self.rows.iter(){
+                row.render_text(brush);
+            }
+        }
+    }
+
+    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        if !self.enabled{
+            return;
+        }
+
+        if self.reaches_rows(event){
+            for (_, row) in self.rows.iter_mut(){
+                row.handle_event_callback(event, window);
+            }
+        }
+        self.own_event(event, window);
+    }
+
+    /// The rows get events first, so scrollable things inside them get to use the wheel before we do
+    fn handle_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window, phase: EventPhase) -> EventResponse{
+        if !self.enabled{
+            return EventResponse::Continue;
+        }
+        if self.reaches_rows(event){
+            for (_, row) in self.rows.iter_mut(){
+                if row.handle_event(event, window, phase) == EventResponse::Consumed{
+                    return EventResponse::Consumed;
+                }
+            }
+        }
+        if phase != EventPhase::Bubble{
+            return EventResponse::Continue;
+        }
+
+        let dragging = self.scrollbar.is_dragging();
+        if self.own_event(event, window) || (dragging && InputCategory::of(event) == Some(InputCategory::Mouse)){
+            EventResponse::Consumed
+        }else{
+            EventResponse::Continue
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.rect.pos
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(self.rect)
+    }
+
+    fn update(&mut self, dt: f32){
+        let offset = self.scroll.get_offset();
+        self.scroll.update(dt);
+        if self.scroll.get_offset() != offset{
+            self.layout_rows();
+        }
+        for (_, row) in self.rows.iter_mut(){
+            row.update(dt);
+        }
+    }
+
+    fn is_animating(&self) -> bool{
+        self.scroll.is_animating() || self.rows.iter().any(|(_, row)| row.is_animating())
+    }
+
+    /// Rows are only made and reused here, as it's the only place with a device. The list arranges its own rows,
+    /// so it never needs the rest of the layout arranging again.
+    fn rebuild(&mut self, device: &wgpu::Device) -> bool{
+        self.sync(device);
+        for (_, row) in self.rows.iter_mut(){
+            row.rebuild(device);
+        }
+        self.layout_rows();
+        false
+    }
+
+    fn layout_text(&mut self, brush: &mut TextBrush){
+        for (_, row) in self.rows.iter_mut(){
+            row.layout_text(brush);
+        }
+    }
+
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{
+        Some(self.rect.size)
+    }
+
+    fn arrange(&mut self, rect: Rect){
+        self.set_rect(rect);
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.screen_dim = screen_dim;
+        for (_, row) in self.rows.iter_mut(){
+            row.prepare(queue, screen_dim);
+        }
+        self.scrollbar.prepare(queue, screen_dim);
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Test that a virtual list only wants rows for the items in its viewport, counting ones that are partly in view
+    #[test]
+    fn visible_rows_test(){
+        assert_eq!(visible_rows(0.0, 100.0, 20.0, 100_000), 0..5);
+        assert_eq!(visible_rows(30.0, 100.0, 20.0, 100_000), 1..7);
+        // Near the end, and with fewer items than fit
+        assert_eq!(visible_rows(1990.0, 100.0, 20.0, 100), 99..100);
+        assert_eq!(visible_rows(0.0, 100.0, 20.0, 3), 0..3);
+        // Overscrolling past the top still starts at the first item
+        assert_eq!(visible_rows(-30.0, 100.0, 20.0, 100), 0..4);
+        assert_eq!(visible_rows(0.0, 100.0, 20.0, 0), 0..0);
+    }
+}
@@ -0,0 +1,521 @@
+//! This module contains the `Wizard` component, which walks through a series of pages with Back and Next buttons,
+//! checking each page before moving on from it.
+
+use std::any::Any;
+
+use wgpu_glyph::{HorizontalAlign, VerticalAlign};
+
+use crate::{input::{EventPhase, EventResponse, InputState, MouseButton, MouseEvent, UiEvent}, rendering::{Quad, Renderer, TextBrush}};
+
+use super::{EventGUIComponent, Label, Rect, TextGUIComponent};
+
+// The space around the steps, page and buttons, in pixels
+const PADDING: f32 = 12.0;
+// The gap between the steps along the top, and how thick their bars are
+const STEP_GAP: f32 = 4.0;
+const STEP_HEIGHT: f32 = 6.0;
+const BUTTON_WIDTH: f32 = 100.0;
+
+type PageValidator = Box<dyn Fn(&dyn EventGUIComponent) -> Result<(), &'static str>>;
+
+/// Where each step of a wizard's progress indicator goes along a strip `width` pixels wide, as the left and right
+/// edge of each one, measured from the left of the strip. The steps are all as wide as each other, with `gap`
+/// pixels between them.
+pub(crate) fn wizard_steps(width: f32, count: usize, gap: f32) -> Vec<[f32; 2]>{
+    if count == 0{
+        return Vec::new();
+    }
+    let step = ((width - gap * (count - 1) as f32) / count as f32).max(0.0);
+    (0..count).map(|i| {
+        let left = i as f32 * (step + gap);
+        [left, left + step]
+    }).collect()
+}
+
+// A page, with its step along the top
+struct WizardPage{
+    body: Box<dyn EventGUIComponent>,
+    validator: Option<PageValidator>,
+    step: Quad,
+    title: Label,
+}
+
+// A button along the bottom
+struct WizardButton{
+    quad: Quad,
+    label: Label,
+    rect: Rect,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum WizardAction{
+    Back,
+    Next,
+}
+
+/// # Wizard
+///
+/// Walks through pages in order, like an installer or a character creator. Each page is a component (often a
+/// `StackPanel` of inputs) with a title, and only the current one is shown. A row of steps across the top shows how
+/// far along the wizard is, with the steps that are done or current filled in.
+///
+/// The Back button goes to the previous page, and Next goes on to the next one, turning into Finish on the last
+/// page. Before leaving a page with Next, its validator (see `set_validator`) is run, and if it returns an error the
+/// wizard stays put and shows the message next to the buttons. Going back doesn't check anything.
+///
+/// ```ignore
+/// let mut wizard = Wizard::new(rect, 18.0, &renderer);
+/// let name = wizard.add_page("Name", Box::new(name_input), &renderer);
+/// wizard.set_validator(name, Some(Box::new(|page| {
+///     let input = page.as_any().downcast_ref::<TextInput>().unwrap();
+///     if input.get_text().is_empty() { Err("Enter a name") } else { Ok(()) }
+/// })));
+/// wizard.add_page("Class", Box::new(class_list), &renderer);
+/// ```
+pub struct Wizard{
+    rect: Rect,
+    text_size: f32,
+    pages: Vec<WizardPage>,
+    current: usize,
+    error: Option<&'static str>,
+    finished: bool,
+    on_page_changed: Option<Box<dyn Fn(usize)>>,
+    on_finished: Option<Box<dyn Fn()>>,
+    colors: [[f32; 4]; 4], // steps to go, steps done, buttons, hovered buttons
+
+    background: Quad,
+    back: WizardButton,
+    next: WizardButton,
+    error_label: Label,
+    input: InputState,
+    pressed: Option<WizardAction>, // the button the mouse went down on
+    enabled: bool,
+}
+
+impl Wizard{
+    /// Create a wizard filling `rect`, without any pages
+    pub fn new(rect: Rect, text_size: f32, renderer: &Renderer) -> Self{
+        let colors = [[0.8, 0.8, 0.8, 1.0], [0.2, 0.4, 0.8, 1.0], [0.85, 0.85, 0.85, 1.0], [0.78, 0.78, 0.78, 1.0]];
+        let button = |text: &str| {
+            let mut label = Label::new(text, text_size, [0.0, 0.0]);
+            label.align_horizontal(HorizontalAlign::Center);
+            label.align_vertical(VerticalAlign::Center);
+            WizardButton{
+                quad: Quad::new([0.0, 0.0], [0.0, 0.0], colors[2], &renderer.device),
+                label,
+                rect: Rect::new([0.0, 0.0], [0.0, 0.0]),
+            }
+        };
+        let mut error_label = Label::new("", text_size, [0.0, 0.0]);
+        error_label.align_horizontal(HorizontalAlign::Right);
+        error_label.align_vertical(VerticalAlign::Center);
+
+        let mut wizard = Self{
+            rect,
+            text_size,
+            pages: Vec::new(),
+            current: 0,
+            error: None,
+            finished: false,
+            on_page_changed: None,
+            on_finished: None,
+            colors,
+
+            background: Quad::new(rect.pos, rect.size, [0.96, 0.96, 0.96, 1.0], &renderer.device),
+            back: button("Back"),
+            next: button("Next"),
+            error_label,
+            input: InputState::new(),
+            pressed: None,
+            enabled: true,
+        };
+        wizard.set_rect(rect);
+        wizard
+    }
+
+    /// Add a page after the others, returning its index. The title goes under its step along the top.
+    pub fn add_page(&mut self, title: &str, body: Box<dyn EventGUIComponent>, renderer: &Renderer) -> usize{
+        let mut label = Label::new(title, self.text_size * 0.8, [0.0, 0.0]);
+        label.align_horizontal(HorizontalAlign::Center);
+        self.pages.push(WizardPage{
+            body,
+            validator: None,
+            step: Quad::new([0.0, 0.0], [0.0, 0.0], self.colors[0], &renderer.device),
+            title: label,
+        });
+        self.set_rect(self.rect);
+        self.update_steps();
+        self.pages.len() - 1
+    }
+
+    /// Set the function that checks a page before Next leaves it. It gets the page's component (to downcast), and
+    /// returns the message to show if the page isn't ready yet.
+    pub fn set_validator(&mut self, index: usize, validator: Option<PageValidator>){
+        if let Some(page) = self.pages.get_mut(index){
+            page.validator = validator;
+        }
+    }
+
+    /// The index of the page being shown
+    pub fn get_current(&self) -> usize{
+        self.current
+    }
+
+    /// Go straight to a page, without checking the one being left or running the callback
+    pub fn set_current(&mut self, index: usize){
+        if index < self.pages.len(){
+            self.current = index;
+            self.error = None;
+            self.update_steps();
+        }
+    }
+
+    /// Check the current page and go on to the next one, like Next does. On the last page, a page that passes
+    /// finishes the wizard instead. Returns the error if the page didn't pass.
+    pub fn advance(&mut self) -> Result<(), &'static str>{
+        let page = match self.pages.get(self.current){
+            Some(page) => page,
+            None => return Ok(()),
+        };
+        if let Some(validator) = &page.validator{
+            if let Err(error) = validator(page.body.as_ref()){
+                self.error = Some(error);
+                self.error_label.set_text(error);
+                return Err(error);
+            }
+        }
+        self.error = None;
+        if self.current + 1 < self.pages.len(){
+            self.change_page(self.current + 1);
+        }else if !self.finished{
+            self.finished = true;
+            if let Some(callback) = &self.on_finished{
+                callback();
+            }
+        }
+        Ok(())
+    }
+
+    /// Go back to the previous page, like Back does
+    pub fn back(&mut self){
+        if self.current > 0{
+            self.error = None;
+            self.change_page(self.current - 1);
+        }
+    }
+
+    /// The message from the last validator that stopped Next, while it's showing
+    pub fn get_error(&self) -> Option<&'static str>{
+        self.error
+    }
+
+    /// True once Finish has been pressed on the last page and it passed
+    pub fn is_finished(&self) -> bool{
+        self.finished
+    }
+
+    /// Start again from the first page, so the wizard can be finished again
+    pub fn restart(&mut self){
+        self.finished = false;
+        self.set_current(0);
+    }
+
+    /// Set the function to run with the new page's index when Next or Back changes the page
+    pub fn set_on_page_changed(&mut self, callback: Option<Box<dyn Fn(usize)>>){
+        self.on_page_changed = callback;
+    }
+
+    /// Set the function to run when Finish is pressed on the last page and it passes
+    pub fn set_on_finished(&mut self, callback: Option<Box<dyn Fn()>>){
+        self.on_finished = callback;
+    }
+
+    /// Borrow a page's component, for example to downcast it
+    pub fn borrow_page_mut(&mut self, index: usize) -> Option<&mut Box<dyn EventGUIComponent>>{
+        self.pages.get_mut(index).map(|page| &mut page.body)
+    }
+
+    pub fn len(&self) -> usize{
+        self.pages.len()
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.pages.is_empty()
+    }
+
+    /// Set the colours of the steps still to go, the steps done (and the current one), and the buttons, normally
+    /// and while hovered
+    pub fn set_colors(&mut self, steps: [f32; 4], done: [f32; 4], button: [f32; 4], hovered: [f32; 4]){
+        self.colors = [steps, done, button, hovered];
+        self.update_steps();
+    }
+
+    pub fn set_background_color(&mut self, color: [f32; 4]){
+        self.background.set_color(color);
+    }
+
+    pub fn set_rect(&mut self, rect: Rect){
+        self.rect = rect;
+        self.background.set_pos(rect.pos);
+        self.background.set_size(rect.size);
+
+        let left = rect.pos[0] - rect.size[0] / 2.0 + PADDING;
+        let top = rect.pos[1] - rect.size[1] / 2.0 + PADDING;
+        let steps = wizard_steps(rect.size[0] - PADDING * 2.0, self.pages.len(), STEP_GAP);
+        for (page, [start, end]) in self.pages.iter_mut().zip(steps){
+            page.step.set_pos([left + (start + end) / 2.0, top + STEP_HEIGHT / 2.0]);
+            page.step.set_size([end - start, STEP_HEIGHT]);
+        }
+
+        let button_size = [BUTTON_WIDTH, self.text_size + PADDING];
+        let y = rect.pos[1] + rect.size[1] / 2.0 - PADDING - button_size[1] / 2.0;
+        self.back.rect = Rect::new([left + button_size[0] / 2.0, y], button_size);
+        self.next.rect = Rect::new([rect.pos[0] + rect.size[0] / 2.0 - PADDING - button_size[0] / 2.0, y], button_size);
+        for button in [&mut self.back, &mut self.next]{
+            button.quad.set_pos(button.rect.pos);
+            button.quad.set_size(button.rect.size);
+        }
+
+        let page_rect = self.page_rect();
+        for page in self.pages.iter_mut(){
+            page.body.arrange(page_rect);
+        }
+    }
+
+    pub fn get_rect(&self) -> Rect{
+        self.rect
+    }
+
+    /// The area between the steps and the buttons, where the pages go
+    pub fn page_rect(&self) -> Rect{
+        let top = self.rect.pos[1] - self.rect.size[1] / 2.0 + PADDING + STEP_HEIGHT + self.text_size + PADDING * 2.0;
+        let bottom = self.rect.pos[1] + self.rect.size[1] / 2.0 - PADDING * 2.0 - (self.text_size + PADDING);
+        let height = (bottom - top).max(0.0);
+        Rect::new([self.rect.pos[0], top + height / 2.0], [(self.rect.size[0] - PADDING * 2.0).max(0.0), height])
+    }
+
+    pub fn enable(&mut self){
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self){
+        self.enabled = false;
+        self.pressed = None;
+    }
+
+    fn change_page(&mut self, index: usize){
+        self.current = index;
+        self.update_steps();
+        if let Some(callback) = &self.on_page_changed{
+            callback(index);
+        }
+    }
+
+    fn update_steps(&mut self){
+        for (i, page) in self.pages.iter_mut().enumerate(){
+            page.step.set_color(self.colors[(i <= self.current) as usize]);
+        }
+        let last = self.current + 1 >= self.pages.len();
+        self.next.label.set_text(if last { "Finish" } else { "Next" });
+    }
+
+    fn button_at(&self, pos: [f32; 2]) -> Option<WizardAction>{
+        if self.current > 0 && self.back.rect.contains(pos){
+            Some(WizardAction::Back)
+        }else if !self.pages.is_empty() && self.next.rect.contains(pos){
+            Some(WizardAction::Next)
+        }else{
+            None
+        }
+    }
+
+    // Pick a button on a click that starts and ends on it, returning true if the event was a click on one
+    fn own_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window) -> bool{
+        match self.input.handle_event(event, window){
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed: true, pos, .. })) => {
+                self.pressed = self.button_at(pos);
+                self.pressed.is_some()
+            }
+            Some(UiEvent::MouseInput(MouseEvent{ button: MouseButton::Left, pressed: false, pos, .. })) => {
+                let button = self.button_at(pos);
+                if button.is_some() && self.pressed.take() == button{
+                    match button{
+                        Some(WizardAction::Back) => self.back(),
+                        Some(WizardAction::Next) => { let _ = self.advance(); }
+                        None => {}
+                    }
+                }
+                self.pressed = None;
+                button.is_some()
+            }
+            _ => false,
+        }
+    }
+}
+
+impl EventGUIComponent for Wizard{
+    fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>)
+    where 'a: 'b {
+        if !self.enabled{
+            return;
+        }
+        self.background.render(render_pass);
+        for page in self.pages.iter(){
+            page.step.render(render_pass);
+        }
+        if let Some(page) = self.pages.get(self.current){
+            page.body.render(render_pass);
+        }
+        if self.current > 0{
+            self.back.quad.render(render_pass);
+        }
+        if !self.pages.is_empty(){
+            self.next.quad.render(render_pass);
+        }
+    }
+
+    fn render_text<'a, 'b>(&'a self, brush: &'b mut TextBrush)
+    where 'a: 'b {
+        if !self.enabled{
+            return;
+        }
+        for page in self.pages.iter(){
+            TextGUIComponent::render_text(&page.title, brush);
+        }
+        if let Some(page) = self.pages.get(self.current){
+            page.body.render_text(brush);
+        }
+        if self.current > 0{
+            TextGUIComponent::render_text(&self.back.label, brush);
+        }
+        if !self.pages.is_empty(){
+            TextGUIComponent::render_text(&self.next.label, brush);
+        }
+        if self.error.is_some(){
+            TextGUIComponent::render_text(&self.error_label, brush);
+        }
+    }
+
+    fn handle_event_callback(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window){
+        if !self.enabled{
+            return;
+        }
+        if let Some(page) = self.pages.get_mut(self.current){
+            page.body.handle_event_callback(event, window);
+        }
+        self.own_event(event, window);
+    }
+
+    /// The current page gets events first, and clicks on the buttons are ours
+    fn handle_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window, phase: EventPhase) -> EventResponse{
+        if !self.enabled{
+            return EventResponse::Continue;
+        }
+        if let Some(page) = self.pages.get_mut(self.current){
+            if page.body.handle_event(event, window, phase) == EventResponse::Consumed{
+                return EventResponse::Consumed;
+            }
+        }
+        if phase != EventPhase::Bubble{
+            return EventResponse::Continue;
+        }
+        if self.own_event(event, window) { EventResponse::Consumed } else { EventResponse::Continue }
+    }
+
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any{
+        self
+    }
+
+    fn get_text_id(&self) -> Option<usize>{
+        None
+    }
+
+    fn is_enabled(&self) -> bool{
+        self.enabled
+    }
+
+    fn get_pos(&self) -> [f32; 2]{
+        self.rect.pos
+    }
+
+    fn get_bounds(&self) -> Option<Rect>{
+        Some(self.rect)
+    }
+
+    fn update(&mut self, dt: f32){
+        if let Some(page) = self.pages.get_mut(self.current){
+            page.body.update(dt);
+        }
+    }
+
+    fn is_animating(&self) -> bool{
+        self.pages.get(self.current).is_some_and(|page| page.body.is_animating())
+    }
+
+    fn rebuild(&mut self, device: &wgpu::Device) -> bool{
+        let mut changed = false;
+        for page in self.pages.iter_mut(){
+            changed |= page.body.rebuild(device);
+        }
+        changed
+    }
+
+    fn layout_text(&mut self, brush: &mut TextBrush){
+        for page in self.pages.iter_mut(){
+            page.body.layout_text(brush);
+        }
+    }
+
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{
+        Some(self.rect.size)
+    }
+
+    fn arrange(&mut self, rect: Rect){
+        self.set_rect(rect);
+    }
+
+    fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        self.background.prepare(queue, screen_dim);
+        let cursor = self.input.get_cursor_pos();
+        for button in [&mut self.back, &mut self.next]{
+            button.quad.set_color(self.colors[if button.rect.contains(cursor) { 3 } else { 2 }]);
+            button.quad.prepare(queue, screen_dim);
+            button.label.set_pos(button.rect.pos, screen_dim);
+        }
+        let error_pos = [self.next.rect.pos[0] - self.next.rect.size[0] / 2.0 - PADDING, self.next.rect.pos[1]];
+        self.error_label.set_pos(error_pos, screen_dim);
+
+        for page in self.pages.iter_mut(){
+            page.step.prepare(queue, screen_dim);
+            let step = page.step.get_pos();
+            page.title.set_pos([step[0], step[1] + STEP_HEIGHT / 2.0 + STEP_GAP], screen_dim);
+        }
+        if let Some(page) = self.pages.get_mut(self.current){
+            page.body.prepare(queue, screen_dim);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Test that a wizard's steps share the strip evenly, with the gaps between them
+    #[test]
+    fn wizard_steps_test(){
+        assert!(wizard_steps(100.0, 0, 4.0).is_empty());
+        assert_eq!(wizard_steps(100.0, 1, 4.0), vec![[0.0, 100.0]]);
+
+        let steps = wizard_steps(100.0, 3, 5.0);
+        assert_eq!(steps, vec![[0.0, 30.0], [35.0, 65.0], [70.0, 100.0]]);
+
+        // Too many steps for the strip squash them down to nothing, rather than going backwards
+        for [left, right] in wizard_steps(10.0, 5, 4.0){
+            assert_eq!(left, right);
+        }
+    }
+}
@@ -0,0 +1,273 @@
+//! This module contains `show_fatal_error`, which shows an error in a small window of its own, so errors that stop
+//! the app from starting (like the GPU not being usable) don't only end up in a console nobody sees, and
+//! `install_panic_hook`, which does the same for panics.
+
+use std::{any::Any, backtrace::Backtrace, cell::RefCell, panic::{self, AssertUnwindSafe}, process, thread};
+
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+use crate::{components::Dialog, rendering::{Renderer, RendererConfig}};
+
+// How big the error window is, in logical pixels, and the space left around a dialog that doesn't fit in it
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+const WINDOW_SIZE: (u32, u32) = (480, 240);
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+const WINDOW_MARGIN: u32 = 40;
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+const TEXT_SIZE: f32 = 18.0;
+// How many lines of the backtrace the panic window shows. The copied details have all of it.
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+const BACKTRACE_LINES: usize = 12;
+// The exit code after a panic, the same as a panic that isn't caught
+const PANIC_EXIT_CODE: i32 = 101;
+
+/// Show an error to the user in a window of its own, with an OK button, blocking until it's closed. The window
+/// gets its own event loop and renderer, so this works even when the app's renderer couldn't be created - it tries
+/// every backend the platform has, in case the one the app asked for is what failed.
+///
+/// The error is always printed to stderr too. If the window can't be shown at all (there's no display, or no GPU
+/// backend works either), that's all that happens.
+///
+/// ```ignore
+/// let renderer = match Renderer::with_config_blocking(&window.window, &config){
+///     Ok(renderer) => renderer,
+///     Err(error) => {
+///         rusty_gui::show_fatal_error("Couldn't start the game", error);
+///         std::process::exit(1);
+///     }
+/// };
+/// ```
+pub fn show_fatal_error(title: &str, body: &str){
+    eprintln!("{}: {}", title, body);
+    // Whatever goes wrong with the window, the error has been printed, and the caller is already handling one
+    if let Ok(Err(error)) = panic::catch_unwind(AssertUnwindSafe(|| show_window(title, body))){
+        eprintln!("{}", error);
+    }
+}
+
+thread_local!{
+    // The app's name and the details of the last panic on the UI thread, kept by the hook until the panic unwinds
+    // out to `catch_fatal_panic` (or is caught before it gets there, and replaced by the next one)
+    static LAST_PANIC: RefCell<Option<(String, String)>> = const { RefCell::new(None) };
+}
+
+/// Replace the panic hook with one that shows panics in a window before exiting, instead of the app's window just
+/// disappearing. Call it from the thread running the event loop - panics on other threads are left to the hook that
+/// was there before, which also still runs first for this one (so the panic is printed as usual).
+///
+/// The window has the panic's message, where it happened and the start of the backtrace, with a button to copy all
+/// of it to the clipboard (with the `arboard` feature). Closing it exits the process with the code an uncaught panic
+/// would. The app's own window can't be drawn to while it's panicking, so this opens a new one, like
+/// `show_fatal_error` - if that fails (or panics itself), the panic has still been printed.
+///
+/// The hook only notes the panic down. The window is shown once the panic unwinds all the way out to
+/// `catch_fatal_panic`, which `GUI::main_loop` runs every event through, so panics caught with
+/// `std::panic::catch_unwind` along the way carry on as normal. Apps running their own event loop should handle
+/// their events inside `catch_fatal_panic` too. With `panic = "abort"` nothing unwinds, so the window is shown
+/// straight from the hook.
+///
+/// ```ignore
+/// let event_loop = EventLoop::new();
+/// rusty_gui::install_panic_hook("My App");
+/// ```
+pub fn install_panic_hook(app_name: &str){
+    let app_name = app_name.to_string();
+    let ui_thread = thread::current().id();
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        previous(info);
+        if thread::current().id() != ui_thread{
+            return;
+        }
+        let details = panic_details(info, &Backtrace::force_capture());
+        if cfg!(panic = "abort"){
+            exit_with_panic_window(&app_name, &details);
+        }
+        LAST_PANIC.with(|last| *last.borrow_mut() = Some((app_name.clone(), details)));
+    }));
+}
+
+/// Run some of the app's code, and if a panic unwinds out of it on the thread `install_panic_hook` was called from,
+/// show the panic window and exit. Without the hook, the panic carries on unwinding as if this wasn't here.
+///
+/// ```ignore
+/// event_loop.run(move |event, _, control_flow| rusty_gui::catch_fatal_panic(|| {
+///     // handle the event
+/// }));
+/// ```
+pub fn catch_fatal_panic<R, F: FnOnce() -> R>(function: F) -> R{
+    match panic::catch_unwind(AssertUnwindSafe(function)){
+        Ok(value) => value,
+        Err(payload) => match LAST_PANIC.with(|last| last.borrow_mut().take()){
+            Some((app_name, details)) => exit_with_panic_window(&app_name, &details),
+            None => panic::resume_unwind(payload),
+        },
+    }
+}
+
+fn exit_with_panic_window(app_name: &str, details: &str) -> !{
+    if let Err(error) = show_panic_window(app_name, details){
+        eprintln!("{}", error);
+    }
+    process::exit(PANIC_EXIT_CODE);
+}
+
+/// The message a panic was started with, from its payload (like the one `std::panic::catch_unwind` gives back).
+/// Panics with something other than a string as their payload don't have one.
+pub fn panic_message(payload: &(dyn Any + Send)) -> Option<&str>{
+    payload.downcast_ref::<&str>().copied().or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+}
+
+// Everything about a panic that's worth copying into a bug report. Newer toolchains call `PanicInfo` a
+// `PanicHookInfo`, but the old name still works everywhere.
+#[allow(deprecated)]
+fn panic_details(info: &panic::PanicInfo, backtrace: &Backtrace) -> String{
+    let message = panic_message(info.payload()).unwrap_or("Box<dyn Any>");
+    let location = info.location().map(|location| format!("{}:{}:{}", location.file(), location.line(), location.column()));
+    format!("panicked at {}:\n{}\n\nstack backtrace:\n{}", location.as_deref().unwrap_or("an unknown location"), message, backtrace)
+}
+
+// The details shortened to fit in the window
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+fn short_details(details: &str) -> String{
+    let mut lines = details.lines();
+    let mut short: Vec<&str> = lines.by_ref().take_while(|line| *line != "stack backtrace:").collect();
+    short.push("stack backtrace:");
+    short.extend(lines.by_ref().take(BACKTRACE_LINES));
+    let mut short = short.join("\n");
+    let rest = lines.count();
+    if rest > 0{
+        short.push_str(&format!("\n... and {} more lines", rest));
+    }
+    short
+}
+
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+fn show_panic_window(app_name: &str, details: &str) -> Result<(), &'static str>{
+    let title = format!("{} has crashed", app_name);
+    let message = format!("{}\n\n{}", title, short_details(details));
+    if cfg!(feature = "arboard"){
+        // The clipboard is kept until the window closes, as some platforms lose what was copied along with it
+        let mut clipboard = None;
+        run_dialog(&title, &message, &["Copy", "Close"], |index, dialog| {
+            if index == 0{
+                clipboard = copy_to_clipboard(details);
+                if let Some(label) = dialog.borrow_button_label_mut(0){
+                    label.set_text(if clipboard.is_some() { "Copied" } else { "Failed" });
+                }
+                return false;
+            }
+            true
+        })
+    }else{
+        run_dialog(&title, &message, &["Close"], |_, _| true)
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn show_panic_window(_app_name: &str, _details: &str) -> Result<(), &'static str>{
+    Err("Error, the panic window isn't available on this platform!")
+}
+
+#[cfg(feature = "arboard")]
+fn copy_to_clipboard(text: &str) -> Option<arboard::Clipboard>{
+    let mut clipboard = arboard::Clipboard::new().ok()?;
+    clipboard.set_text(text.to_string()).ok()?;
+    Some(clipboard)
+}
+
+#[cfg(not(feature = "arboard"))]
+fn copy_to_clipboard(_text: &str) -> Option<()>{
+    None
+}
+
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+fn show_window(title: &str, body: &str) -> Result<(), &'static str>{
+    run_dialog(title, &format!("{}\n\n{}", title, body), &["OK"], |_, _| true)
+}
+
+// Show a dialog in a window of its own, until the window is closed or `on_pick` says the button picked should close
+// it. Otherwise the dialog opens again.
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+fn run_dialog<F>(title: &str, message: &str, buttons: &[&str], mut on_pick: F) -> Result<(), &'static str>
+where F: FnMut(usize, &mut Dialog) -> bool {
+    use std::{cell::Cell, rc::Rc};
+    use winit::{dpi::{LogicalSize, PhysicalSize}, event::{Event, WindowEvent}, event_loop::{ControlFlow, EventLoop}, platform::run_return::EventLoopExtRunReturn, window::WindowBuilder};
+
+    let mut event_loop = EventLoop::new();
+    let window = WindowBuilder::new()
+        .with_title(title)
+        .with_inner_size(LogicalSize::new(WINDOW_SIZE.0, WINDOW_SIZE.1))
+        .with_resizable(false)
+        .build(&event_loop)
+        .map_err(|_| "Error, failed to build the error window!")?;
+    let mut renderer = create_renderer(&window)?;
+
+    // The dialog is the whole window, so it doesn't need to shade anything
+    let mut dialog = Dialog::new(message, buttons, TEXT_SIZE, &mut renderer);
+    let last = buttons.len().checked_sub(1);
+    dialog.set_keys(last, last);
+    dialog.set_colors([0.0, 0.0, 0.0, 0.0], [1.0, 1.0, 1.0, 1.0], [0.85, 0.85, 0.85, 1.0]);
+    let picked = Rc::new(Cell::new(None));
+    let on_picked = picked.clone();
+    dialog.set_callback(Some(Box::new(move |index| on_picked.set(Some(index)))));
+
+    // Grow the window if the message doesn't fit
+    let size = dialog.get_size();
+    let inner_size = window.inner_size();
+    if size[0] > inner_size.width as f32 || size[1] > inner_size.height as f32{
+        let width = inner_size.width.max(size[0].ceil() as u32 + WINDOW_MARGIN);
+        let height = inner_size.height.max(size[1].ceil() as u32 + WINDOW_MARGIN);
+        window.set_inner_size(PhysicalSize::new(width, height));
+    }
+    let dialog_id = renderer.layout.add_overlay_component(Box::new(dialog));
+
+    event_loop.run_return(|event, _, control_flow| {
+        *control_flow = ControlFlow::Wait;
+        renderer.dispatch_event(&event, &window);
+        renderer.apply_commands();
+
+        match &event{
+            Event::WindowEvent{ event: WindowEvent::CloseRequested, .. } => *control_flow = ControlFlow::Exit,
+            Event::WindowEvent{ event: WindowEvent::Resized(size), .. } => renderer.resize(*size),
+            Event::WindowEvent{ event: WindowEvent::ScaleFactorChanged{ new_inner_size, .. }, .. } => renderer.resize(**new_inner_size),
+            Event::RedrawRequested(_) => {
+                renderer.prepass();
+                if renderer.render(wgpu::Color::WHITE).is_err(){
+                    *control_flow = ControlFlow::Exit;
+                }
+            }
+            _ => {}
+        }
+        // Input might have changed how the dialog looks
+        if let Event::WindowEvent{ .. } = &event{
+            window.request_redraw();
+        }
+        if let Some(index) = picked.take(){
+            match renderer.layout.borrow_overlay_component_as_type_mut::<Dialog>(dialog_id){
+                Ok(dialog) => if on_pick(index, dialog){
+                    *control_flow = ControlFlow::Exit;
+                }else{
+                    dialog.open();
+                },
+                Err(_) => *control_flow = ControlFlow::Exit,
+            }
+        }
+    });
+    Ok(())
+}
+
+// There's no way to run an event loop and get control back on other platforms
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn show_window(_title: &str, _body: &str) -> Result<(), &'static str>{
+    Err("Error, the error window isn't available on this platform!")
+}
+
+// Try the platform's usual backends first, then any it has
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+fn create_renderer(window: &winit::window::Window) -> Result<Renderer, &'static str>{
+    Renderer::with_config_blocking(window, &RendererConfig::default()).or_else(|_| {
+        let mut config = RendererConfig::new();
+        config.set_backends(wgpu::BackendBit::all());
+        Renderer::with_config_blocking(window, &config)
+    })
+}
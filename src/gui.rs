@@ -5,43 +5,143 @@
 
 use std::time::{Duration, Instant};
 
-use crate::{layout::Layout, rendering::{Window, WindowBuilder, Renderer}};
-use futures::executor::block_on;
+use crate::{components::base_components::window_to_component_space, layout::Layout, rendering::{Window, WindowBuilder, Renderer}};
 
 use winit::event_loop::ControlFlow;
 use winit::event::{WindowEvent, Event};
 
+// Fills the layouts again after the device is lost (see `GUI::set_rebuild_handler`)
+type RebuildHandler = Box<dyn Fn(&mut Renderer)>;
+
+/// How often the GUI updates and redraws
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum FrameMode{
+    /// Redraw after every event, plus every 250ms while idle. While something is animating, redraw
+    /// about 60 times a second (or at the max FPS, if one is set). This is the default.
+    Balanced,
+    /// Only redraw in response to input, or while something is animating. Uses the least power.
+    EventDriven,
+    /// Redraw as often as possible, limited by vsync and the max FPS. Useful for games and tools
+    /// showing live content.
+    Continuous,
+}
+
+/// # FrameLimiter
+///
+/// Keeps frames from coming faster than a target frame rate. It sleeps for most of the wait,
+/// then spins for the last moment, as sleeping alone tends to overshoot.
+pub struct FrameLimiter{
+    frame_time: Option<Duration>,
+    next_frame: Instant,
+}
+
+impl FrameLimiter{
+    /// Create a limiter for a target frame rate. `None` doesn't limit the frame rate.
+    pub fn new(max_fps: Option<f32>) -> Self{
+        let mut limiter = Self{
+            frame_time: None,
+            next_frame: Instant::now(),
+        };
+        limiter.set_max_fps(max_fps);
+        limiter
+    }
+
+    pub fn set_max_fps(&mut self, max_fps: Option<f32>){
+        self.frame_time = max_fps.filter(|fps| *fps > 0.0).map(|fps| Duration::from_secs_f32(1.0 / fps));
+    }
+
+    pub fn get_max_fps(&self) -> Option<f32>{
+        self.frame_time.map(|frame_time| 1.0 / frame_time.as_secs_f32())
+    }
+
+    /// How long a frame should take, if the frame rate is limited
+    pub fn get_frame_time(&self) -> Option<Duration>{
+        self.frame_time
+    }
+
+    /// Block until it's time for the next frame
+    pub fn wait(&mut self){
+        let frame_time = match self.frame_time{
+            Some(frame_time) => frame_time,
+            None => return,
+        };
+
+        // Sleep until just before the deadline, then spin the rest of the way
+        let spin_time = Duration::from_millis(1);
+        let now = Instant::now();
+        if self.next_frame > now + spin_time{
+            std::thread::sleep(self.next_frame - now - spin_time);
+        }
+        while Instant::now() < self.next_frame{
+            std::hint::spin_loop();
+        }
+
+        // If we fell behind (like after a stall), start counting from now instead of rushing to catch up
+        let now = Instant::now();
+        self.next_frame = if now > self.next_frame + frame_time { now + frame_time } else { self.next_frame + frame_time };
+    }
+}
+
 pub struct GUI{
     pub window: Window,
     pub renderer: Renderer,
     pub clear_color: wgpu::Color,
+    pub frame_mode: FrameMode,
+    pub max_fps: Option<f32>,
+    rebuild_handler: Option<RebuildHandler>,
 }
 
 impl Default for GUI{
     fn default() -> GUI{
         let window = WindowBuilder::new().set_resolution((800, 600)).set_title("Rusty GUI app").build().expect("Error building window");
-        let renderer = block_on(Renderer::new(&window.window));
+        let mut renderer = Renderer::new_blocking(&window.window);
+        renderer.set_vsync(window.vsync);
         let clear_color = wgpu::Color::WHITE;
         GUI{
             window: window,
             renderer: renderer,
             clear_color: clear_color,
+            frame_mode: FrameMode::Balanced,
+            max_fps: None,
+            rebuild_handler: None,
         }
     }
 }
 
 impl GUI{
+    /// Like `default`, but awaits the renderer instead of blocking the thread while the GPU is set up.
+    /// Useful for applications running their own async runtime.
+    pub async fn default_async() -> GUI{
+        let window = WindowBuilder::new().set_resolution((800, 600)).set_title("Rusty GUI app").build().expect("Error building window");
+        let renderer = Renderer::new(&window.window).await;
+        GUI::new(window, renderer, wgpu::Color::WHITE)
+    }
 
     /// This function takes the data required by a GUI struct and wraps it into itself
     /// 
     /// You can alternatively call default to generate a default renderer and window.
-    pub fn new(window: Window, renderer: Renderer, clear_color: wgpu::Color,) -> Self{
+    pub fn new(window: Window, mut renderer: Renderer, clear_color: wgpu::Color,) -> Self{
+        renderer.set_vsync(window.vsync);
         Self{
             window,
             renderer,
-            clear_color
+            clear_color,
+            frame_mode: FrameMode::Balanced,
+            max_fps: None,
+            rebuild_handler: None,
         }
     }
+
+    /// Set how often the GUI updates and redraws
+    pub fn set_frame_mode(&mut self, frame_mode: FrameMode){
+        self.frame_mode = frame_mode;
+    }
+
+    /// Cap the frame rate. Mostly useful with vsync off, so the GUI doesn't render thousands of frames a second -
+    /// with vsync off it applies to every redraw, whatever the frame mode.
+    pub fn set_max_fps(&mut self, max_fps: Option<f32>){
+        self.max_fps = max_fps;
+    }
 }
 
 // This part just has some helpful functions to simplify adding components
@@ -64,6 +164,16 @@ impl GUI{
         self.window.set_event_handler(event_handler)
     }
 
+    /// Sets the function that fills the layouts again after the graphics device is lost (when the GPU is reset, or
+    /// its driver is updated). The renderer moves to a new device by itself, but the components in the layouts were
+    /// made on the old one, so they're cleared out (see `Renderer::recover_device`) and this makes them again - it's
+    /// usually the same code that set the layouts up to start with.
+    ///
+    /// Without one, losing the device can't be recovered from, and the GUI shows an error and exits.
+    pub fn set_rebuild_handler(&mut self, rebuild_handler: RebuildHandler){
+        self.rebuild_handler = Some(rebuild_handler);
+    }
+
     /// Gets a reference to the winit window. Used to make wgpu surfaces
     pub fn get_window_ref(&self) -> &winit::window::Window{
         &self.window.window
@@ -79,6 +189,16 @@ impl GUI{
         &mut self.renderer.layout
     }
 
+    /// Stack a layout on top of the current one, like a pause menu over a HUD (see `Renderer::push_layout`)
+    pub fn push_layout(&mut self, layout: Layout){
+        self.renderer.push_layout(layout);
+    }
+
+    /// Remove the topmost stacked layout, returning it
+    pub fn pop_layout(&mut self) -> Option<Layout>{
+        self.renderer.pop_layout()
+    }
+
     /// Borrow the render device (Used for things like creating buffers, and creating certain components)
     pub fn borrow_render_device(&self) -> &wgpu::Device{
         &self.renderer.device
@@ -103,27 +223,55 @@ impl GUI{
 /// This workaround was also required as I had a lot of issues with references
 fn main_loop(gui: GUI){
     let mut renderer = gui.renderer;
+    // The cursor gets given back while we don't have focus, or a modal layout is open
+    let cursor = gui.window.get_cursor_control();
     let mut window = gui.window.window;
     let mut event_loop = gui.window.event_loop;
     let clear_color = gui.clear_color;
+    let frame_mode = gui.frame_mode;
+    let mut limiter = FrameLimiter::new(gui.max_fps);
+    // Set by input, so event driven mode knows to redraw
+    let mut needs_redraw = true;
     let event_loop_handler = gui.window.event_callback_handler;
+    let rebuild_handler = gui.rebuild_handler;
+    // While minimized (or suspended, on mobile) there's nothing to show, so we stop updating and drawing
     let mut minimized = false;
+    let mut suspended = false;
+    // The frame clock, used to tell components how much time has passed since the last frame
+    let mut last_frame = Instant::now();
+    let mut animating = false;
+    // Hover is worked out once per frame, from the last cursor position
+    let mut cursor_pos: Option<[f64; 2]> = None;
+    let mut cursor_moved = false;
+    let mut focused = true;
 
-    event_loop.take().unwrap().run(move |event, _, control_flow| {
+    // Panics unwind to here, so the panic window from `install_panic_hook` can be shown (if it's installed)
+    event_loop.take().unwrap().run(move |event, _, control_flow| crate::fatal::catch_fatal_panic(|| {
         // ControlFlow::WaitUntil pauses the event loop if no events are available to process.
-        // If no events are called, it will update every 10ms to make sure everything stays up to date
+        // If no events are called, it will update every 250ms to make sure everything stays up to date
         // This is ideal for non-game applications that only update in response to user
         // input, and uses significantly less power/CPU time than ControlFlow::Poll.
-        *control_flow = ControlFlow::WaitUntil(Instant::now().checked_add(Duration::from_millis(250)).unwrap());
+        //
+        // While a component is animating, we update roughly 60 times a second instead.
+        // While paused, we sleep until an event (like the window being restored) wakes us up.
+        //
+        // Event driven mode skips the idle updates, and continuous mode never waits (the limiter
+        // takes care of the frame rate).
+        let animation_wait = limiter.get_frame_time().unwrap_or_else(|| Duration::from_millis(16));
+        *control_flow = match frame_mode{
+            _ if minimized || suspended => ControlFlow::Wait,
+            FrameMode::Continuous => ControlFlow::Poll,
+            _ if animating => ControlFlow::WaitUntil(Instant::now().checked_add(animation_wait).unwrap()),
+            FrameMode::EventDriven => ControlFlow::Wait,
+            FrameMode::Balanced => ControlFlow::WaitUntil(Instant::now().checked_add(Duration::from_millis(250)).unwrap()),
+        };
 
-        if !minimized{
-            // Run event components - things like buttons and so on
-            for event_comp in renderer.layout.event_components.iter_mut(){
-                event_comp.handle_event_callback(&event, &mut window);
-            }
-        }
+        // Run event components - things like buttons and so on. If one of them consumes the event,
+        // the user's handler doesn't see it either.
+        let consumed = !minimized && renderer.dispatch_event(&event, &window);
 
         match &event_loop_handler{
+            Some(_) if consumed => {}
             Some(v) => {
                 // We have a callback handler, so run it below (with our required parameters)
                 v(&event, &mut window, &mut renderer);
@@ -132,6 +280,8 @@ fn main_loop(gui: GUI){
                 // No callback handler set, so do nothing
             }
         }
+        // Changes to the layouts queued while handling the event
+        renderer.apply_commands();
 
         match event {
             // This part checks for a window event, then checks if its either an exit or resize
@@ -140,41 +290,83 @@ fn main_loop(gui: GUI){
                     ref event,
                     window_id,
                 } if window_id == window.id() =>  {
+                    needs_redraw = true;
                     match event{
                     WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
                     WindowEvent::Resized(physical_size) => {
                         renderer.resize(*physical_size);
-                        if renderer.size.width == 0 && renderer.size.height == 0{
-                            minimized = true;
-                        }else{
-                            minimized = false;
-                        }
+                        set_minimized(&mut minimized, &renderer, &window, &mut last_frame);
                     }
                     WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
                         // new_inner_size is &&mut so we have to dereference it twice
                         renderer.resize(**new_inner_size);
-                        if renderer.size.width == 0 && renderer.size.height == 0{
-                            minimized = true;
-                        }else{
-                            minimized = false;
-                        }
-                    },              
+                        set_minimized(&mut minimized, &renderer, &window, &mut last_frame);
+                    },
+                    WindowEvent::CursorMoved{ position, .. } => {
+                        cursor_pos = Some([position.x, position.y]);
+                        cursor_moved = true;
+                    }
+                    WindowEvent::CursorLeft{ .. } => {
+                        cursor_pos = None;
+                        cursor_moved = true;
+                    }
+                    WindowEvent::Focused(now_focused) => focused = *now_focused,              
                     
                     _ => {}
                 }
             }
 
+            Event::Suspended => suspended = true,
+            Event::Resumed if suspended => {
+                suspended = false;
+                last_frame = Instant::now();
+                needs_redraw = true;
+            }
+
+            Event::MainEventsCleared if minimized || suspended => {
+                // Paused, so don't advance the frame clock or animations.
+                animating = false;
+            }
             Event::MainEventsCleared => {
                 // Application update code.
-                if !minimized{
-                    // Queue a RedrawRequested event.
-                    //
-                    // You only need to call this if you've determined that you need to redraw, in
-                    // applications which do not always need to. Applications that redraw continuously
-                    // can just render here instead.
+                let now = Instant::now();
+                let dt = now.duration_since(last_frame).as_secs_f32();
+                last_frame = now;
+
+                cursor.set_released(!focused || renderer.is_modal_open());
+                let _ = cursor.apply(&window);
+
+                if cursor_moved{
+                    let size = window.inner_size();
+                    renderer.update_hover(cursor_pos.map(|pos| window_to_component_space(pos, (size.width, size.height))));
+                    cursor_moved = false;
+                }
+
+                // Keep ticking quickly while assets load, so they show up as soon as they're ready
+                renderer.update_assets();
+                animating = renderer.assets.is_loading() || renderer.streamer.is_loading();
+                for layout in renderer.layouts_mut(){
+                    animating |= layout.update(dt);
+                }
+                renderer.apply_commands();
+
+                // Queue a RedrawRequested event.
+                //
+                // You only need to call this if you've determined that you need to redraw, in
+                // applications which do not always need to. Applications that redraw continuously
+                // can just render here instead.
+                if frame_mode != FrameMode::EventDriven || needs_redraw || animating{
+                    // Without vsync nothing else holds frames back (a burst of input can redraw as fast as
+                    // events come in, in any mode), so the limiter paces every redraw
+                    if frame_mode == FrameMode::Continuous || !renderer.get_vsync(){
+                        limiter.wait();
+                    }
                     window.request_redraw();
+                    needs_redraw = false;
                 }
             }
+            // The OS can still ask for a redraw while we're minimized, but there's nothing to draw to
+            Event::RedrawRequested(_) if minimized || suspended => {}
             Event::RedrawRequested(_) => {
                 // Redraw the application.
                 //
@@ -182,9 +374,44 @@ fn main_loop(gui: GUI){
                 // this event rather than in MainEventsCleared, since rendering in here allows
                 // the program to gracefully handle redraws requested by the OS.
                 renderer.prepass(); // Update the layout and stuff
-                renderer.render(clear_color); // Render a single frame.
+                // Render a single frame. Lost swapchains are handled by the renderer, so an error here
+                // means we're out of memory or the device is gone.
+                match renderer.render(clear_color){
+                    Ok(()) => {}
+                    // Move to a new device, and have the app make its layouts again on it
+                    Err(wgpu::SwapChainError::Lost) => {
+                        let recovered = match &rebuild_handler{
+                            Some(rebuild) => renderer.recover_device(&window).map(|()| rebuild(&mut renderer)),
+                            None => Err("Error, there's no rebuild handler (see `GUI::set_rebuild_handler`) to make the layouts again!"),
+                        };
+                        match recovered{
+                            Ok(()) => window.request_redraw(),
+                            Err(error) => {
+                                crate::show_fatal_error("The graphics device was lost", error);
+                                *control_flow = ControlFlow::Exit;
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("Error rendering frame: {}", e);
+                        *control_flow = ControlFlow::Exit;
+                    }
+                }
+                crate::profiling::finish_frame();
             }
             _ => {}
         }
-    });
+    }));
+}
+
+// Check if the window was minimized (or restored) after a resize. Some platforms (like Windows) shrink
+// the window to nothing when it's minimized, which we can't make a swapchain for.
+fn set_minimized(minimized: &mut bool, renderer: &Renderer, window: &winit::window::Window, last_frame: &mut Instant){
+    let was_minimized = *minimized;
+    *minimized = renderer.size.width == 0 || renderer.size.height == 0;
+    if was_minimized && !*minimized{
+        // Don't count the time spent minimized as one long frame
+        *last_frame = Instant::now();
+        window.request_redraw();
+    }
 }
\ No newline at end of file
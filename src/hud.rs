@@ -0,0 +1,176 @@
+//! This module contains the pieces behind HUD layouts (see `Layout::hud`) - the `Anchor` slots around the edge of the
+//! screen components can be pinned to, the `SafeArea` keeping them clear of the edges, and `WorldAnchor`s in the game's scene.
+
+use cgmath::{Matrix4, Vector4};
+
+use crate::components::Rect;
+
+/// A slot on the screen a component can be anchored to (see `Layout::set_anchor`)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Anchor{
+    TopLeft,
+    TopCenter,
+    TopRight,
+    CenterLeft,
+    Center,
+    CenterRight,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+}
+
+impl Anchor{
+    /// How the slot lines things up across the screen and down it: -1 for the left or top, 0 for the centre and
+    /// 1 for the right or bottom
+    pub fn alignment(self) -> [i8; 2]{
+        match self{
+            Anchor::TopLeft => [-1, -1],
+            Anchor::TopCenter => [0, -1],
+            Anchor::TopRight => [1, -1],
+            Anchor::CenterLeft => [-1, 0],
+            Anchor::Center => [0, 0],
+            Anchor::CenterRight => [1, 0],
+            Anchor::BottomLeft => [-1, 1],
+            Anchor::BottomCenter => [0, 1],
+            Anchor::BottomRight => [1, 1],
+        }
+    }
+}
+
+/// # SafeArea
+///
+/// How far (in pixels) anchored components stay from each edge of the screen, to keep them clear of TV overscan,
+/// rounded screen corners and notches.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct SafeArea{
+    pub top: f32,
+    pub bottom: f32,
+    pub left: f32,
+    pub right: f32,
+}
+
+impl SafeArea{
+    /// The same margin on every edge
+    pub fn uniform(margin: f32) -> Self{
+        Self{
+            top: margin,
+            bottom: margin,
+            left: margin,
+            right: margin,
+        }
+    }
+
+    /// A margin as a fraction of the screen size on every edge, like the 5% "title safe" area for TVs
+    pub fn fraction(fraction: f32, screen_dim: (u32, u32)) -> Self{
+        let (x, y) = (screen_dim.0 as f32 * fraction, screen_dim.1 as f32 * fraction);
+        Self{
+            top: y,
+            bottom: y,
+            left: x,
+            right: x,
+        }
+    }
+}
+
+/// Where a component of some size goes in a slot, in component space. `offset` pushes it away from the edge of the
+/// screen - down for the top and middle slots, up for the bottom ones - so things in the same slot can stack.
+pub fn anchor_rect(anchor: Anchor, size: [f32; 2], screen_dim: (u32, u32), safe_area: &SafeArea, offset: f32) -> Rect{
+    let half = [screen_dim.0 as f32 / 2.0, screen_dim.1 as f32 / 2.0];
+    let [across, down] = anchor.alignment();
+    let x = match across{
+        -1 => -half[0] + safe_area.left + size[0] / 2.0,
+        1 => half[0] - safe_area.right - size[0] / 2.0,
+        _ => (safe_area.left - safe_area.right) / 2.0,
+    };
+    let y = match down{
+        -1 => -half[1] + safe_area.top + offset + size[1] / 2.0,
+        1 => half[1] - safe_area.bottom - offset - size[1] / 2.0,
+        _ => (safe_area.top - safe_area.bottom) / 2.0 + offset,
+    };
+    Rect::new([x, y], size)
+}
+
+/// # WorldAnchor
+///
+/// A point in the game's 3D scene that a component follows around the screen (see `Layout::set_world_anchor`), for
+/// nameplates over characters and markers on objectives. `offset` moves the component from the projected point in
+/// pixels, like up above a character's head.
+///
+/// Normally the component is hidden while its point is behind the camera. A clamped anchor (see `clamped`) stays
+/// on screen instead, pinned to the edge of the safe area in the direction of the point, like an objective marker.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct WorldAnchor{
+    pub point: [f32; 3],
+    pub offset: [f32; 2],
+    pub clamp_to_screen: bool,
+}
+
+impl WorldAnchor{
+    pub fn new(point: [f32; 3]) -> Self{
+        Self{
+            point,
+            offset: [0.0, 0.0],
+            clamp_to_screen: false,
+        }
+    }
+
+    pub fn with_offset(mut self, offset: [f32; 2]) -> Self{
+        self.offset = offset;
+        self
+    }
+
+    /// Keep the component on screen when the point goes off it
+    pub fn clamped(mut self) -> Self{
+        self.clamp_to_screen = true;
+        self
+    }
+}
+
+/// Where a point in the scene ends up on screen in component space, using the camera's view-projection matrix (the
+/// one the game renders its scene with), or `None` if it's behind the camera. Points off the sides of the screen
+/// still get a position, outside of it.
+pub fn project_point(view_projection: Matrix4<f32>, point: [f32; 3], screen_dim: (u32, u32)) -> Option<[f32; 2]>{
+    let clip = view_projection * Vector4::new(point[0], point[1], point[2], 1.0);
+    if clip.w <= f32::EPSILON{
+        return None;
+    }
+    Some(clip_to_component(clip, screen_dim))
+}
+
+/// Where a world anchored component goes in component space, or `None` if it should be hidden
+pub fn world_anchor_pos(anchor: &WorldAnchor, view_projection: Matrix4<f32>, screen_dim: (u32, u32), safe_area: &SafeArea) -> Option<[f32; 2]>{
+    let clip = view_projection * Vector4::new(anchor.point[0], anchor.point[1], anchor.point[2], 1.0);
+    let behind = clip.w <= f32::EPSILON;
+    if behind && !anchor.clamp_to_screen{
+        return None;
+    }
+    // Behind the camera, the sign of w would flip the point to the wrong side of the screen
+    let projected = clip_to_component(Vector4::new(clip.x, clip.y, clip.z, clip.w.abs().max(f32::EPSILON)), screen_dim);
+    let pos = [projected[0] + anchor.offset[0], projected[1] + anchor.offset[1]];
+    if !anchor.clamp_to_screen{
+        return Some(pos);
+    }
+
+    // Pull the point in towards the middle of the safe area until it's on the edge
+    let half = [screen_dim.0 as f32 / 2.0, screen_dim.1 as f32 / 2.0];
+    let center = [(safe_area.left - safe_area.right) / 2.0, (safe_area.top - safe_area.bottom) / 2.0];
+    let extent = [
+        (half[0] - (safe_area.left + safe_area.right) / 2.0).max(0.0),
+        (half[1] - (safe_area.top + safe_area.bottom) / 2.0).max(0.0),
+    ];
+    let offset = [pos[0] - center[0], pos[1] - center[1]];
+    let reach = (offset[0].abs() / extent[0]).max(offset[1].abs() / extent[1]);
+    if !behind && reach <= 1.0{
+        Some(pos)
+    }else if reach > 0.0 && reach.is_finite(){
+        Some([center[0] + offset[0] / reach, center[1] + offset[1] / reach])
+    }else{
+        // Straight behind the camera, so there's no direction to it
+        Some([center[0], center[1] + extent[1]])
+    }
+}
+
+// Divide by w and go from normalized device coordinates (y up) to component space
+fn clip_to_component(clip: Vector4<f32>, screen_dim: (u32, u32)) -> [f32; 2]{
+    [clip.x / clip.w * screen_dim.0 as f32 / 2.0, -clip.y / clip.w * screen_dim.1 as f32 / 2.0]
+}
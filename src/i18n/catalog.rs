@@ -0,0 +1,88 @@
+//! This module contains the `Catalog`, the translated strings for one locale.
+
+use std::collections::HashMap;
+
+use super::{Locale, PluralCategory};
+
+enum Message{
+    Text(String),
+    Plural(Vec<(PluralCategory, String)>),
+}
+
+/// # Catalog
+///
+/// Translated strings for a locale, looked up by key. Plural messages have a string for each plural form the
+/// language uses, with `{}` where the count goes:
+///
+/// ```ignore
+/// let mut catalog = Catalog::new(Locale::from_tag("ru-RU")?);
+/// catalog.add_plural("items", &[(PluralCategory::One, "{} предмет"), (PluralCategory::Few, "{} предмета"), (PluralCategory::Many, "{} предметов")]);
+/// label.set_text(catalog.get_plural("items", 3)); // "3 предмета"
+/// ```
+///
+/// Keys that haven't been added come back as the key itself, so missing translations show up on screen.
+pub struct Catalog{
+    locale: Locale,
+    messages: HashMap<String, Message>,
+}
+
+impl Catalog{
+    pub fn new(locale: Locale) -> Self{
+        Self{
+            locale,
+            messages: HashMap::new(),
+        }
+    }
+
+    pub fn get_locale(&self) -> &Locale{
+        &self.locale
+    }
+
+    /// Change the locale, for example after loading the strings for another language
+    pub fn set_locale(&mut self, locale: Locale){
+        self.locale = locale;
+    }
+
+    /// Add (or replace) a plain string
+    pub fn add<S: Into<String>, T: Into<String>>(&mut self, key: S, text: T){
+        self.messages.insert(key.into(), Message::Text(text.into()));
+    }
+
+    /// Add (or replace) a plural message, with a string for each plural form. `PluralCategory::Other` is used
+    /// for any form that's missing.
+    pub fn add_plural<S: Into<String>>(&mut self, key: S, forms: &[(PluralCategory, &str)]){
+        let forms = forms.iter().map(|(category, text)| (*category, text.to_string())).collect();
+        self.messages.insert(key.into(), Message::Plural(forms));
+    }
+
+    /// Check if a key has been added
+    pub fn contains(&self, key: &str) -> bool{
+        self.messages.contains_key(key)
+    }
+
+    /// Get a plain string. Plural messages give their "other" form.
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str{
+        match self.messages.get(key){
+            Some(Message::Text(text)) => text,
+            Some(Message::Plural(forms)) => Self::find_form(forms, PluralCategory::Other).unwrap_or(key),
+            None => key,
+        }
+    }
+
+    /// Get the right plural form for a count, with the count put in (formatted for the locale)
+    pub fn get_plural(&self, key: &str, count: i64) -> String{
+        let form = match self.messages.get(key){
+            Some(Message::Text(text)) => Some(text.as_str()),
+            Some(Message::Plural(forms)) => {
+                let category = self.locale.plural_rule.select(count);
+                Self::find_form(forms, category).or_else(|| Self::find_form(forms, PluralCategory::Other))
+            }
+            None => None,
+        };
+        form.unwrap_or(key).replace("{}", &self.locale.format_integer(count))
+    }
+
+    fn find_form(forms: &[(PluralCategory, String)], category: PluralCategory) -> Option<&str>{
+        forms.iter().find(|(form, _)| *form == category).map(|(_, text)| text.as_str())
+    }
+}
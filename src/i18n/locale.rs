@@ -0,0 +1,150 @@
+//! This module contains the `Locale`, which formats numbers and dates the way a language and region
+//! expect them - "1,234.5" in the US is "1.234,5" in Germany and "1 234,5" in France.
+
+use super::PluralRule;
+
+/// The order the day, month and year are written in
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DateOrder{
+    DayMonthYear,
+    MonthDayYear,
+    YearMonthDay,
+}
+
+/// A calendar date, for `Locale::format_date`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Date{
+    pub year: i32,
+    /// From 1 to 12
+    pub month: u8,
+    /// From 1 to 31
+    pub day: u8,
+}
+
+impl Date{
+    pub fn new(year: i32, month: u8, day: u8) -> Self{
+        Self{ year, month, day }
+    }
+}
+
+/// # Locale
+///
+/// The formatting conventions of a language and region. Get one from a language tag with `Locale::from_tag`,
+/// or build your own by setting the fields. The default is US English.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Locale{
+    /// The language tag, like "en-US"
+    pub tag: String,
+    pub decimal_separator: char,
+    /// What goes between each group of three digits, if anything
+    pub group_separator: Option<char>,
+    pub date_order: DateOrder,
+    pub date_separator: char,
+    pub plural_rule: PluralRule,
+}
+
+impl Default for Locale{
+    fn default() -> Self{
+        Self{
+            tag: String::from("en-US"),
+            decimal_separator: '.',
+            group_separator: Some(','),
+            date_order: DateOrder::MonthDayYear,
+            date_separator: '/',
+            plural_rule: PluralRule::OneOther,
+        }
+    }
+}
+
+impl Locale{
+    /// Look up a locale from a language tag, like "de-DE" or "fr". Tags are matched on the language, with the
+    /// region used where it changes the formatting (like "en-US" and "en-GB"). Both "-" and "_" are accepted.
+    pub fn from_tag(tag: &str) -> Result<Locale, &'static str>{
+        let normalized = tag.replace('_', "-").to_lowercase();
+        let mut parts = normalized.split('-');
+        let language = parts.next().unwrap_or("");
+        let region = parts.next().unwrap_or("");
+
+        let (decimal_separator, group_separator, date_order, date_separator, plural_rule) = match (language, region){
+            ("en", "us") | ("en", "") => ('.', Some(','), DateOrder::MonthDayYear, '/', PluralRule::OneOther),
+            ("en", _) => ('.', Some(','), DateOrder::DayMonthYear, '/', PluralRule::OneOther),
+            ("de", _) => (',', Some('.'), DateOrder::DayMonthYear, '.', PluralRule::OneOther),
+            ("nl", _) => (',', Some('.'), DateOrder::DayMonthYear, '-', PluralRule::OneOther),
+            ("es", _) | ("it", _) => (',', Some('.'), DateOrder::DayMonthYear, '/', PluralRule::OneOther),
+            ("pt", "br") => (',', Some('.'), DateOrder::DayMonthYear, '/', PluralRule::ZeroOneOther),
+            ("pt", _) => (',', Some('\u{A0}'), DateOrder::DayMonthYear, '/', PluralRule::OneOther),
+            ("fr", _) => (',', Some('\u{202F}'), DateOrder::DayMonthYear, '/', PluralRule::ZeroOneOther),
+            ("sv", _) => (',', Some('\u{A0}'), DateOrder::YearMonthDay, '-', PluralRule::OneOther),
+            ("ru", _) | ("uk", _) => (',', Some('\u{A0}'), DateOrder::DayMonthYear, '.', PluralRule::EastSlavic),
+            ("pl", _) => (',', Some('\u{A0}'), DateOrder::DayMonthYear, '.', PluralRule::Polish),
+            ("ja", _) | ("zh", _) | ("ko", _) => ('.', Some(','), DateOrder::YearMonthDay, '/', PluralRule::None),
+            _ => return Err("Error, unknown locale!"),
+        };
+
+        Ok(Self{
+            tag: tag.to_string(),
+            decimal_separator,
+            group_separator,
+            date_order,
+            date_separator,
+            plural_rule,
+        })
+    }
+
+    /// Format a whole number with the digits grouped, like "1,234,567"
+    pub fn format_integer(&self, value: i64) -> String{
+        let digits = self.group_digits(&value.unsigned_abs().to_string());
+        if value < 0 { format!("-{}", digits) } else { digits }
+    }
+
+    /// Format a number with a fixed number of decimal places, like "1,234.50"
+    pub fn format_decimal(&self, value: f64, decimals: usize) -> String{
+        if !value.is_finite(){
+            return value.to_string();
+        }
+        let formatted = format!("{:.*}", decimals, value.abs());
+        let (whole, fraction) = match formatted.find('.'){
+            Some(index) => (&formatted[..index], Some(&formatted[index + 1..])),
+            None => (formatted.as_str(), None),
+        };
+
+        let mut result = String::new();
+        // Don't show "-0.00" for tiny negative numbers that round to zero
+        if value < 0.0 && formatted.chars().any(|c| c.is_ascii_digit() && c != '0'){
+            result.push('-');
+        }
+        result.push_str(&self.group_digits(whole));
+        if let Some(fraction) = fraction{
+            result.push(self.decimal_separator);
+            result.push_str(fraction);
+        }
+        result
+    }
+
+    /// Format a date with numbers, like "10/16/2026" or "16.10.2026"
+    pub fn format_date(&self, date: Date) -> String{
+        let separator = self.date_separator;
+        match self.date_order{
+            DateOrder::DayMonthYear => format!("{:02}{}{:02}{}{}", date.day, separator, date.month, separator, date.year),
+            DateOrder::MonthDayYear => format!("{:02}{}{:02}{}{}", date.month, separator, date.day, separator, date.year),
+            DateOrder::YearMonthDay => format!("{}{}{:02}{}{:02}", date.year, separator, date.month, separator, date.day),
+        }
+    }
+
+    // Put the group separator between every three digits, counting from the right
+    fn group_digits(&self, digits: &str) -> String{
+        let separator = match self.group_separator{
+            Some(separator) => separator,
+            None => return digits.to_string(),
+        };
+
+        let mut grouped = String::new();
+        for (i, digit) in digits.chars().enumerate(){
+            if i > 0 && i % 3 == digits.len() % 3{
+                grouped.push(separator);
+            }
+            grouped.push(digit);
+        }
+        grouped
+    }
+}
@@ -0,0 +1,11 @@
+//! This module contains the localization layer. A `Locale` knows how numbers and dates are written and which
+//! plural rule a language uses, and a `Catalog` holds the translated strings for one locale, picking the right
+//! plural form so "3 items" comes out right in every language.
+
+mod locale;
+mod plural;
+mod catalog;
+
+pub use locale::{Locale, DateOrder, Date};
+pub use plural::{PluralCategory, PluralRule};
+pub use catalog::Catalog;
@@ -0,0 +1,48 @@
+//! This module contains the plural rules. Languages split counts into different groups - English only has
+//! "one" and "other", but Russian and Polish have separate forms for a few and for many.
+
+/// Which plural form a count takes. The names follow the Unicode CLDR categories.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PluralCategory{
+    Zero,
+    One,
+    Two,
+    Few,
+    Many,
+    Other,
+}
+
+/// How a language picks the plural form of a whole number
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PluralRule{
+    /// One for 1, other for everything else (English, German, Spanish, ...)
+    OneOther,
+    /// One for 0 and 1, other for everything else (French, Portuguese in Brazil)
+    ZeroOneOther,
+    /// One for 1, 21, 31..., few for 2-4, 22-24..., many for everything else (Russian, Ukrainian)
+    EastSlavic,
+    /// One for 1 only, few for 2-4, 22-24..., many for everything else
+    Polish,
+    /// Always other, for languages without plural forms (Japanese, Chinese, Korean)
+    None,
+}
+
+impl PluralRule{
+    /// Work out which form a count takes
+    pub fn select(&self, count: i64) -> PluralCategory{
+        let n = count.unsigned_abs();
+        let few = (2..=4).contains(&(n % 10)) && !(12..=14).contains(&(n % 100));
+
+        match self{
+            PluralRule::OneOther if n == 1 => PluralCategory::One,
+            PluralRule::ZeroOneOther if n <= 1 => PluralCategory::One,
+            PluralRule::EastSlavic if n % 10 == 1 && n % 100 != 11 => PluralCategory::One,
+            PluralRule::EastSlavic if few => PluralCategory::Few,
+            PluralRule::EastSlavic => PluralCategory::Many,
+            PluralRule::Polish if n == 1 => PluralCategory::One,
+            PluralRule::Polish if few => PluralCategory::Few,
+            PluralRule::Polish => PluralCategory::Many,
+            _ => PluralCategory::Other,
+        }
+    }
+}
@@ -0,0 +1,86 @@
+//! This module contains the types used to control how events travel through the layout.
+//! Every event goes to the components from the top of the layout down, and any component can consume it so
+//! nothing after it sees the event. Containers get it twice, before their children (the capture phase) and after.
+
+/// Which way an event is travelling through the layout, see `EventGUIComponent::handle_event`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EventPhase{
+    /// On the way into a container, before any of its children see the event. Lets containers step in first,
+    /// for example to block input to everything inside them. Components without children can ignore it.
+    Capture,
+    /// On the way back out, after the children (if there are any). This is where most components handle events.
+    Bubble,
+}
+
+/// What a component did with an event
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EventResponse{
+    /// Let the event carry on to the next component
+    Continue,
+    /// Stop the event here. No other component, and not the user's event handler, will see it.
+    Consumed,
+}
+
+/// The kinds of input that can be routed separately between layouts
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum InputCategory{
+    Keyboard,
+    Mouse,
+}
+
+impl InputCategory{
+    /// Work out what kind of input an event is. Events that aren't input (like resizes), and modifier changes
+    /// (which every layout needs to keep track of), return `None` and always reach every layout.
+    pub fn of(event: &winit::event::Event<()>) -> Option<InputCategory>{
+        use winit::event::WindowEvent;
+
+        match event{
+            winit::event::Event::WindowEvent{ event, .. } => match event{
+                WindowEvent::KeyboardInput{ .. } | WindowEvent::ReceivedCharacter(_) => Some(InputCategory::Keyboard),
+                WindowEvent::CursorMoved{ .. } | WindowEvent::CursorEntered{ .. } | WindowEvent::CursorLeft{ .. }
+                | WindowEvent::MouseInput{ .. } | WindowEvent::MouseWheel{ .. } | WindowEvent::Touch(_) => Some(InputCategory::Mouse),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Whether a layout lets a kind of input through to the layouts under it
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum InputRouting{
+    PassThrough,
+    Block,
+}
+
+/// # InputPriority
+///
+/// How a layout stacked on top of others (see `Renderer::push_layout`) shares input with the layouts under it,
+/// per kind of input. A pause menu would block everything, while a HUD might take the mouse but let the keyboard
+/// through to the game underneath.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct InputPriority{
+    pub keyboard: InputRouting,
+    pub mouse: InputRouting,
+}
+
+impl InputPriority{
+    /// Let everything through. This is the default.
+    pub const PASS_THROUGH: InputPriority = InputPriority{ keyboard: InputRouting::PassThrough, mouse: InputRouting::PassThrough };
+    /// Block everything, for modal layouts like a pause menu
+    pub const MODAL: InputPriority = InputPriority{ keyboard: InputRouting::Block, mouse: InputRouting::Block };
+
+    /// Check if a kind of input gets blocked
+    pub fn blocks(&self, category: InputCategory) -> bool{
+        match category{
+            InputCategory::Keyboard => self.keyboard == InputRouting::Block,
+            InputCategory::Mouse => self.mouse == InputRouting::Block,
+        }
+    }
+}
+
+impl Default for InputPriority{
+    fn default() -> InputPriority{
+        InputPriority::PASS_THROUGH
+    }
+}
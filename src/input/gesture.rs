@@ -0,0 +1,279 @@
+//! This module contains the `GestureRecognizer`, which turns touches (and the mouse, acting as a single finger)
+//! into gestures - taps, long presses, pans, swipes and pinches. It's what carousels, zoomable viewports and
+//! swipe navigation build on, instead of each of them tracking touches on their own.
+
+use winit::event::{ElementState, Event, MouseButton, TouchPhase, WindowEvent};
+
+use crate::components::base_components::window_to_component_space;
+
+// How far (in pixels) a finger can wander before a press becomes a pan
+const TOUCH_SLOP: f32 = 10.0;
+// How long (in seconds) a finger has to be held still for a long press
+const LONG_PRESS_TIME: f32 = 0.5;
+// How fast (in pixels per second) a pan has to be going when released to count as a swipe
+const SWIPE_SPEED: f32 = 500.0;
+// The mouse gets a touch ID no real touch will have
+const MOUSE_ID: u64 = u64::MAX;
+
+/// Which way a swipe went
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SwipeDirection{
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// A recognized gesture. Positions are in the same space as components (pixels from the centre of the screen).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Gesture{
+    /// A quick press and release without moving
+    Tap{ pos: [f32; 2] },
+    /// A finger held still for a moment. Moving it afterwards sends `LongPressDrag`.
+    LongPress{ pos: [f32; 2] },
+    /// A finger moving after a long press, for things like dragging items to reorder them
+    LongPressDrag{ pos: [f32; 2], delta: [f32; 2] },
+    /// A finger moving, by `delta` since the last pan
+    Pan{ pos: [f32; 2], delta: [f32; 2] },
+    /// A pan or long press drag ended, with the speed the finger was moving at (in pixels per second)
+    PanEnd{ pos: [f32; 2], velocity: [f32; 2] },
+    /// A pan released quickly, sent along with `PanEnd`
+    Swipe{ direction: SwipeDirection, velocity: [f32; 2] },
+    /// Two fingers moving - `scale` is the distance between them relative to when they went down
+    Pinch{ centre: [f32; 2], scale: f32 },
+    PinchEnd{ scale: f32 },
+}
+
+struct Pointer{
+    id: u64,
+    start: [f32; 2],
+    pos: [f32; 2],
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum State{
+    Idle,
+    Pressed{ held_time: f32 },
+    Panning,
+    LongPressed,
+    Pinching{ start_distance: f32, scale: f32 },
+    // A pinch lost a finger, so wait for the rest to lift before starting anything new
+    Finishing,
+}
+
+/// # GestureRecognizer
+///
+/// Feed it every event with `handle_event`, and call `update` once a frame (long presses and swipe speeds are
+/// timed with the frame clock). Both return the gestures they recognized.
+///
+/// Positions can also be passed in directly with `pointer_down`, `pointer_move` and `pointer_up`, for input coming
+/// from somewhere other than winit.
+pub struct GestureRecognizer{
+    pointers: Vec<Pointer>,
+    state: State,
+    velocity: [f32; 2],
+    last_pos: [f32; 2], // where the first pointer was at the last update, for the velocity
+    mouse_down: bool,
+    cursor_pos: [f32; 2],
+}
+
+impl GestureRecognizer{
+    pub fn new() -> Self{
+        Self{
+            pointers: Vec::new(),
+            state: State::Idle,
+            velocity: [0.0, 0.0],
+            last_pos: [0.0, 0.0],
+            mouse_down: false,
+            cursor_pos: [0.0, 0.0],
+        }
+    }
+
+    /// Pass in a winit event. The left mouse button acts as a finger.
+    pub fn handle_event(&mut self, event: &Event<()>, window: &winit::window::Window) -> Vec<Gesture>{
+        let event = match event{
+            Event::WindowEvent{
+                ref event,
+                window_id,
+            } if window_id == &window.id() => event,
+            _ => return Vec::new(),
+        };
+        let size = window.inner_size();
+        let screen_dim = (size.width, size.height);
+
+        match event{
+            WindowEvent::Touch(touch) => {
+                let pos = window_to_component_space([touch.location.x, touch.location.y], screen_dim);
+                match touch.phase{
+                    TouchPhase::Started => self.pointer_down(touch.id, pos),
+                    TouchPhase::Moved => self.pointer_move(touch.id, pos),
+                    TouchPhase::Ended => self.pointer_up(touch.id),
+                    TouchPhase::Cancelled => self.cancel(touch.id),
+                }
+            }
+            WindowEvent::CursorMoved{ position, .. } => {
+                self.cursor_pos = window_to_component_space([position.x, position.y], screen_dim);
+                if self.mouse_down{
+                    self.pointer_move(MOUSE_ID, self.cursor_pos)
+                }else{
+                    Vec::new()
+                }
+            }
+            WindowEvent::MouseInput{ button: MouseButton::Left, state, .. } => {
+                self.mouse_down = state == &ElementState::Pressed;
+                if self.mouse_down{
+                    self.pointer_down(MOUSE_ID, self.cursor_pos)
+                }else{
+                    self.pointer_up(MOUSE_ID)
+                }
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Advance the clock, returning a long press if a finger has now been held long enough
+    pub fn update(&mut self, dt: f32) -> Vec<Gesture>{
+        if dt <= 0.0{
+            return Vec::new();
+        }
+
+        // Smooth the velocity out a little, as touch positions are noisy
+        if let Some(pointer) = self.pointers.first(){
+            let velocity = [(pointer.pos[0] - self.last_pos[0]) / dt, (pointer.pos[1] - self.last_pos[1]) / dt];
+            self.velocity = [(self.velocity[0] + velocity[0]) / 2.0, (self.velocity[1] + velocity[1]) / 2.0];
+            self.last_pos = pointer.pos;
+        }
+
+        if let State::Pressed{ held_time } = self.state{
+            let held_time = held_time + dt;
+            if held_time >= LONG_PRESS_TIME{
+                self.state = State::LongPressed;
+                return vec![Gesture::LongPress{ pos: self.pointers[0].pos }];
+            }
+            self.state = State::Pressed{ held_time };
+        }
+        Vec::new()
+    }
+
+    /// True while a finger is down, waiting for a long press. The GUI should keep updating every frame until then.
+    pub fn is_animating(&self) -> bool{
+        matches!(self.state, State::Pressed{ .. })
+    }
+
+    pub fn pointer_down(&mut self, id: u64, pos: [f32; 2]) -> Vec<Gesture>{
+        let mut gestures = Vec::new();
+        self.pointers.retain(|pointer| pointer.id != id);
+        self.pointers.push(Pointer{ id, start: pos, pos });
+
+        match self.state{
+            State::Idle => {
+                self.state = State::Pressed{ held_time: 0.0 };
+                self.velocity = [0.0, 0.0];
+                self.last_pos = pos;
+            }
+            // A second finger turns whatever the first one was doing into a pinch
+            State::Pressed{ .. } | State::Panning | State::LongPressed if self.pointers.len() == 2 => {
+                if !matches!(self.state, State::Pressed{ .. }){
+                    gestures.push(Gesture::PanEnd{ pos: self.pointers[0].pos, velocity: [0.0, 0.0] });
+                }
+                self.state = State::Pinching{ start_distance: self.pinch_distance().max(1.0), scale: 1.0 };
+            }
+            _ => {}
+        }
+        gestures
+    }
+
+    pub fn pointer_move(&mut self, id: u64, pos: [f32; 2]) -> Vec<Gesture>{
+        let index = match self.pointers.iter().position(|pointer| pointer.id == id){
+            Some(index) => index,
+            None => return Vec::new(),
+        };
+        let old_pos = self.pointers[index].pos;
+        self.pointers[index].pos = pos;
+        let delta = [pos[0] - old_pos[0], pos[1] - old_pos[1]];
+
+        match self.state{
+            State::Pressed{ .. } => {
+                let start = self.pointers[index].start;
+                if distance(start, pos) > TOUCH_SLOP{
+                    self.state = State::Panning;
+                    // Include the movement inside the slop, so the content doesn't jump
+                    return vec![Gesture::Pan{ pos, delta: [pos[0] - start[0], pos[1] - start[1]] }];
+                }
+                Vec::new()
+            }
+            State::Panning => vec![Gesture::Pan{ pos, delta }],
+            State::LongPressed => vec![Gesture::LongPressDrag{ pos, delta }],
+            State::Pinching{ start_distance, .. } if self.pointers.len() >= 2 => {
+                let scale = self.pinch_distance() / start_distance;
+                self.state = State::Pinching{ start_distance, scale };
+                vec![Gesture::Pinch{ centre: self.pinch_centre(), scale }]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn pointer_up(&mut self, id: u64) -> Vec<Gesture>{
+        let pointer = match self.pointers.iter().position(|pointer| pointer.id == id){
+            Some(index) => self.pointers.remove(index),
+            None => return Vec::new(),
+        };
+
+        let gestures = match self.state{
+            State::Pressed{ .. } => vec![Gesture::Tap{ pos: pointer.pos }],
+            State::Panning => {
+                let mut gestures = vec![Gesture::PanEnd{ pos: pointer.pos, velocity: self.velocity }];
+                if let Some(direction) = swipe_direction(self.velocity){
+                    gestures.push(Gesture::Swipe{ direction, velocity: self.velocity });
+                }
+                gestures
+            }
+            State::LongPressed => vec![Gesture::PanEnd{ pos: pointer.pos, velocity: self.velocity }],
+            State::Pinching{ scale, .. } => {
+                self.state = State::Finishing;
+                vec![Gesture::PinchEnd{ scale }]
+            }
+            _ => Vec::new(),
+        };
+
+        if self.pointers.is_empty(){
+            self.state = State::Idle;
+        }else if self.state != State::Finishing{
+            self.state = State::Finishing;
+        }
+        gestures
+    }
+
+    /// Forget a finger without it counting as released (the OS took the touch away)
+    pub fn cancel(&mut self, id: u64) -> Vec<Gesture>{
+        self.pointers.retain(|pointer| pointer.id != id);
+        self.state = if self.pointers.is_empty() { State::Idle } else { State::Finishing };
+        Vec::new()
+    }
+
+    fn pinch_distance(&self) -> f32{
+        distance(self.pointers[0].pos, self.pointers[1].pos)
+    }
+
+    fn pinch_centre(&self) -> [f32; 2]{
+        [(self.pointers[0].pos[0] + self.pointers[1].pos[0]) / 2.0, (self.pointers[0].pos[1] + self.pointers[1].pos[1]) / 2.0]
+    }
+}
+
+fn distance(a: [f32; 2], b: [f32; 2]) -> f32{
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)).sqrt()
+}
+
+// The direction of a release fast enough to be a swipe
+fn swipe_direction(velocity: [f32; 2]) -> Option<SwipeDirection>{
+    if velocity[0].abs().max(velocity[1].abs()) < SWIPE_SPEED{
+        return None;
+    }
+    Some(if velocity[0].abs() > velocity[1].abs(){
+        if velocity[0] > 0.0 { SwipeDirection::Right } else { SwipeDirection::Left }
+    }else if velocity[1] > 0.0{
+        SwipeDirection::Down
+    }else{
+        SwipeDirection::Up
+    })
+}
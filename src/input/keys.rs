@@ -0,0 +1,239 @@
+//! This module contains the keyboard side of the input layer. winit reports keys as an optional virtual keycode
+//! (which follows the keyboard layout) and a scancode (which follows the physical key), and both differ between
+//! platforms. `Key` flattens them into one enum, falling back on the scancode when there's no keycode.
+
+use winit::event::{KeyboardInput, VirtualKeyCode};
+
+use super::Modifiers;
+
+/// # Key
+///
+/// A key on the keyboard. Keys that type something are stored as the character they type without shift held
+/// (so `Character('a')`, `Character('1')`, `Character('-')`), which also covers the numpad. Everything else
+/// has its own variant, and keys we don't know get their scancode.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Key{
+    Character(char),
+    Space,
+    Enter,
+    Tab,
+    Backspace,
+    Delete,
+    Insert,
+    Escape,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Left,
+    Right,
+    Up,
+    Down,
+    /// A function key, from F1 to F24
+    F(u8),
+    Shift,
+    Ctrl,
+    Alt,
+    Logo,
+    CapsLock,
+    NumLock,
+    ScrollLock,
+    PrintScreen,
+    Pause,
+    Menu,
+    Unknown(u32),
+}
+
+impl Key{
+    /// Work out which key a winit keyboard input is for, using the virtual keycode if there is one
+    pub fn from_input(input: &KeyboardInput) -> Key{
+        match input.virtual_keycode.and_then(Key::from_virtual_keycode){
+            Some(key) => key,
+            None => Key::from_scancode(input.scancode),
+        }
+    }
+
+    /// Map a winit virtual keycode. These follow the keyboard layout. Returns `None` for keys we don't have a variant for
+    pub fn from_virtual_keycode(keycode: VirtualKeyCode) -> Option<Key>{
+        use VirtualKeyCode as V;
+
+        let key = match keycode{
+            V::Key1 | V::Numpad1 => Key::Character('1'),
+            V::Key2 | V::Numpad2 => Key::Character('2'),
+            V::Key3 | V::Numpad3 => Key::Character('3'),
+            V::Key4 | V::Numpad4 => Key::Character('4'),
+            V::Key5 | V::Numpad5 => Key::Character('5'),
+            V::Key6 | V::Numpad6 => Key::Character('6'),
+            V::Key7 | V::Numpad7 => Key::Character('7'),
+            V::Key8 | V::Numpad8 => Key::Character('8'),
+            V::Key9 | V::Numpad9 => Key::Character('9'),
+            V::Key0 | V::Numpad0 => Key::Character('0'),
+            V::A => Key::Character('a'),
+            V::B => Key::Character('b'),
+            V::C => Key::Character('c'),
+            V::D => Key::Character('d'),
+            V::E => Key::Character('e'),
+            V::F => Key::Character('f'),
+            V::G => Key::Character('g'),
+            V::H => Key::Character('h'),
+            V::I => Key::Character('i'),
+            V::J => Key::Character('j'),
+            V::K => Key::Character('k'),
+            V::L => Key::Character('l'),
+            V::M => Key::Character('m'),
+            V::N => Key::Character('n'),
+            V::O => Key::Character('o'),
+            V::P => Key::Character('p'),
+            V::Q => Key::Character('q'),
+            V::R => Key::Character('r'),
+            V::S => Key::Character('s'),
+            V::T => Key::Character('t'),
+            V::U => Key::Character('u'),
+            V::V => Key::Character('v'),
+            V::W => Key::Character('w'),
+            V::X => Key::Character('x'),
+            V::Y => Key::Character('y'),
+            V::Z => Key::Character('z'),
+            V::Minus | V::NumpadSubtract => Key::Character('-'),
+            V::Equals | V::NumpadEquals => Key::Character('='),
+            V::Plus | V::NumpadAdd => Key::Character('+'),
+            V::Asterisk | V::NumpadMultiply => Key::Character('*'),
+            V::Slash | V::NumpadDivide => Key::Character('/'),
+            V::Period | V::NumpadDecimal => Key::Character('.'),
+            V::Comma | V::NumpadComma => Key::Character(','),
+            V::Backslash => Key::Character('\\'),
+            V::LBracket => Key::Character('['),
+            V::RBracket => Key::Character(']'),
+            V::Semicolon => Key::Character(';'),
+            V::Colon => Key::Character(':'),
+            V::Apostrophe => Key::Character('\''),
+            V::Grave => Key::Character('`'),
+            V::At => Key::Character('@'),
+            V::Caret => Key::Character('^'),
+            V::Underline => Key::Character('_'),
+            V::Space => Key::Space,
+            V::Return | V::NumpadEnter => Key::Enter,
+            V::Tab => Key::Tab,
+            V::Back => Key::Backspace,
+            V::Delete => Key::Delete,
+            V::Insert => Key::Insert,
+            V::Escape => Key::Escape,
+            V::Home => Key::Home,
+            V::End => Key::End,
+            V::PageUp => Key::PageUp,
+            V::PageDown => Key::PageDown,
+            V::Left => Key::Left,
+            V::Right => Key::Right,
+            V::Up => Key::Up,
+            V::Down => Key::Down,
+            V::F1 => Key::F(1),
+            V::F2 => Key::F(2),
+            V::F3 => Key::F(3),
+            V::F4 => Key::F(4),
+            V::F5 => Key::F(5),
+            V::F6 => Key::F(6),
+            V::F7 => Key::F(7),
+            V::F8 => Key::F(8),
+            V::F9 => Key::F(9),
+            V::F10 => Key::F(10),
+            V::F11 => Key::F(11),
+            V::F12 => Key::F(12),
+            V::F13 => Key::F(13),
+            V::F14 => Key::F(14),
+            V::F15 => Key::F(15),
+            V::F16 => Key::F(16),
+            V::F17 => Key::F(17),
+            V::F18 => Key::F(18),
+            V::F19 => Key::F(19),
+            V::F20 => Key::F(20),
+            V::F21 => Key::F(21),
+            V::F22 => Key::F(22),
+            V::F23 => Key::F(23),
+            V::F24 => Key::F(24),
+            V::LShift | V::RShift => Key::Shift,
+            V::LControl | V::RControl => Key::Ctrl,
+            V::LAlt | V::RAlt => Key::Alt,
+            V::LWin | V::RWin => Key::Logo,
+            V::Capital => Key::CapsLock,
+            V::Numlock => Key::NumLock,
+            V::Scroll => Key::ScrollLock,
+            V::Snapshot | V::Sysrq => Key::PrintScreen,
+            V::Pause => Key::Pause,
+            V::Apps => Key::Menu,
+            _ => return None,
+        };
+        Some(key)
+    }
+
+    /// Map a scancode, which follows the physical position of the key rather than the layout. Keys are named
+    /// after what they type on a US keyboard, so `Character('w')` is always the key above `Character('s')`.
+    ///
+    /// Only the main block of a PC keyboard is known, everything else (and every key on macOS) is `Unknown`.
+    pub fn from_scancode(scancode: u32) -> Key{
+        if cfg!(target_os = "macos"){
+            return Key::Unknown(scancode);
+        }
+
+        const ROWS: [(u32, &str); 4] = [(0x02, "1234567890-="), (0x10, "qwertyuiop[]"), (0x1E, "asdfghjkl;'`"), (0x2B, "\\zxcvbnm,./")];
+        for (start, row) in ROWS.iter(){
+            if scancode >= *start && scancode < start + row.len() as u32{
+                return Key::Character(row.as_bytes()[(scancode - start) as usize] as char);
+            }
+        }
+
+        match scancode{
+            0x01 => Key::Escape,
+            0x0E => Key::Backspace,
+            0x0F => Key::Tab,
+            0x1C => Key::Enter,
+            0x1D => Key::Ctrl,
+            0x2A | 0x36 => Key::Shift,
+            0x38 => Key::Alt,
+            0x39 => Key::Space,
+            0x3A => Key::CapsLock,
+            0x3B..=0x44 => Key::F((scancode - 0x3B + 1) as u8),
+            0x57 => Key::F(11),
+            0x58 => Key::F(12),
+            _ => Key::Unknown(scancode),
+        }
+    }
+
+    /// True for keys that type something (characters and space), as opposed to control keys like enter or the arrows
+    pub fn is_text(&self) -> bool{
+        matches!(self, Key::Character(_) | Key::Space)
+    }
+
+    /// True for the modifier keys themselves
+    pub fn is_modifier(&self) -> bool{
+        matches!(self, Key::Shift | Key::Ctrl | Key::Alt | Key::Logo)
+    }
+}
+
+/// A key being pressed or released
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct KeyEvent{
+    /// The key, following the keyboard layout
+    pub key: Key,
+    /// The key, following its physical position (see `Key::from_scancode`). Handy for things like WASD movement.
+    pub physical: Key,
+    pub scancode: u32,
+    pub pressed: bool,
+    /// True if this press came from the key being held down
+    pub repeat: bool,
+    pub modifiers: Modifiers,
+}
+
+impl KeyEvent{
+    /// True if this key press will also type some text (which arrives separately, as `UiEvent::Text`).
+    ///
+    /// Text inputs should ignore these and insert the text instead. Holding ctrl or the logo key turns a text key
+    /// into a shortcut, except for ctrl+alt, which is how Windows reports AltGr.
+    pub fn is_text_input(&self) -> bool{
+        self.pressed && self.key.is_text() && (!(self.modifiers.ctrl || self.modifiers.logo) || (self.modifiers.ctrl && self.modifiers.alt))
+    }
+
+    /// True if this is a control key press, like a shortcut or an arrow key
+    pub fn is_control(&self) -> bool{
+        self.pressed && !self.is_text_input()
+    }
+}
@@ -0,0 +1,213 @@
+//! This module contains the `InputMap`, which binds named actions (like "confirm" or "navigate_up") to keys,
+//! mouse buttons and gamepad buttons. Components can then react to actions instead of specific inputs,
+//! so menus work the same with a keyboard, a mouse or a gamepad.
+
+use std::fmt;
+
+use winit::event::Event;
+
+use super::{InputState, Key, KeyEvent, MouseButton, MouseEvent, UiEvent};
+
+/// A gamepad button, named after its position so it's the same across controllers.
+///
+/// winit doesn't read gamepads, so these have to be passed in with `InputMap::handle_gamepad` from a gamepad library.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum GamepadButton{
+    /// A on an Xbox controller, cross on a PlayStation controller
+    South,
+    East,
+    West,
+    North,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+    LeftBumper,
+    RightBumper,
+    LeftTrigger,
+    RightTrigger,
+    Start,
+    Select,
+    LeftStick,
+    RightStick,
+}
+
+/// Something that can trigger an action
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Binding{
+    Key(Key),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+}
+
+/// Readable names for bindings, for showing them in menus (like a rebinding screen)
+impl fmt::Display for Binding{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        match self{
+            Binding::Key(Key::Character(character)) => write!(f, "{}", character.to_uppercase()),
+            Binding::Key(Key::F(number)) => write!(f, "F{}", number),
+            Binding::Key(Key::Unknown(scancode)) => write!(f, "Key {}", scancode),
+            Binding::Key(key) => write!(f, "{:?}", key),
+            Binding::Mouse(MouseButton::Other(button)) => write!(f, "Mouse {}", button),
+            Binding::Mouse(button) => write!(f, "{:?} Click", button),
+            Binding::Gamepad(button) => write!(f, "Gamepad {:?}", button),
+        }
+    }
+}
+
+/// What happened to an action, see `InputMap::handle_event`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ActionEvent{
+    Pressed(String),
+    Released(String),
+    /// An action got a new binding from `InputMap::start_rebind`
+    Rebound(String, Binding),
+}
+
+/// # InputMap
+///
+/// Named actions, each with any number of bindings. Feed it every event (and any gamepad input), and it reports
+/// actions being pressed and released.
+///
+/// For rebinding, call `start_rebind` with an action, and the next key, mouse or gamepad button pressed becomes its
+/// binding (escape cancels).
+pub struct InputMap{
+    actions: Vec<(String, Vec<Binding>)>,
+    held: Vec<Binding>,
+    rebinding: Option<String>,
+    input: InputState,
+}
+
+impl InputMap{
+    /// Create an empty input map
+    pub fn new() -> Self{
+        Self{
+            actions: Vec::new(),
+            held: Vec::new(),
+            rebinding: None,
+            input: InputState::new(),
+        }
+    }
+
+    /// Create an input map with the usual menu actions - "confirm", "cancel", "navigate_up", "navigate_down",
+    /// "navigate_left" and "navigate_right" - bound to the keyboard and gamepad
+    pub fn menu_defaults() -> Self{
+        let mut map = InputMap::new();
+        map.bind("confirm", Binding::Key(Key::Enter));
+        map.bind("confirm", Binding::Key(Key::Space));
+        map.bind("confirm", Binding::Gamepad(GamepadButton::South));
+        map.bind("cancel", Binding::Key(Key::Escape));
+        map.bind("cancel", Binding::Gamepad(GamepadButton::East));
+        map.bind("navigate_up", Binding::Key(Key::Up));
+        map.bind("navigate_up", Binding::Gamepad(GamepadButton::DPadUp));
+        map.bind("navigate_down", Binding::Key(Key::Down));
+        map.bind("navigate_down", Binding::Gamepad(GamepadButton::DPadDown));
+        map.bind("navigate_left", Binding::Key(Key::Left));
+        map.bind("navigate_left", Binding::Gamepad(GamepadButton::DPadLeft));
+        map.bind("navigate_right", Binding::Key(Key::Right));
+        map.bind("navigate_right", Binding::Gamepad(GamepadButton::DPadRight));
+        map
+    }
+
+    /// Add a binding to an action, creating the action if it doesn't exist yet
+    pub fn bind<S: Into<String>>(&mut self, action: S, binding: Binding){
+        let action = action.into();
+        match self.actions.iter_mut().find(|(name, _)| *name == action){
+            Some((_, bindings)) => {
+                if !bindings.contains(&binding){
+                    bindings.push(binding);
+                }
+            }
+            None => self.actions.push((action, vec![binding])),
+        }
+    }
+
+    /// Remove a binding from an action
+    pub fn unbind(&mut self, action: &str, binding: Binding){
+        if let Some((_, bindings)) = self.actions.iter_mut().find(|(name, _)| name == action){
+            bindings.retain(|bound| *bound != binding);
+        }
+    }
+
+    /// Remove every binding from an action, keeping the action itself
+    pub fn clear_bindings(&mut self, action: &str){
+        if let Some((_, bindings)) = self.actions.iter_mut().find(|(name, _)| name == action){
+            bindings.clear();
+        }
+    }
+
+    /// The bindings of an action, in the order they were added
+    pub fn get_bindings(&self, action: &str) -> &[Binding]{
+        match self.actions.iter().find(|(name, _)| name == action){
+            Some((_, bindings)) => bindings,
+            None => &[],
+        }
+    }
+
+    /// The names of every action, in the order they were added
+    pub fn get_actions(&self) -> Vec<&str>{
+        self.actions.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    /// Check if any binding of an action is held down
+    pub fn is_active(&self, action: &str) -> bool{
+        self.get_bindings(action).iter().any(|binding| self.held.contains(binding))
+    }
+
+    /// Rebind an action - the next button pressed replaces its bindings, and an `ActionEvent::Rebound` is sent
+    pub fn start_rebind<S: Into<String>>(&mut self, action: S){
+        self.rebinding = Some(action.into());
+    }
+
+    pub fn cancel_rebind(&mut self){
+        self.rebinding = None;
+    }
+
+    /// The action waiting for a new binding, if any
+    pub fn get_rebinding(&self) -> Option<&str>{
+        self.rebinding.as_deref()
+    }
+
+    /// Update the map from a winit event, returning what happened to any actions
+    pub fn handle_event(&mut self, event: &Event<()>, window: &winit::window::Window) -> Vec<ActionEvent>{
+        match self.input.handle_event(event, window){
+            Some(UiEvent::Key(KeyEvent{ repeat: false, key, pressed, .. })) => self.handle_binding(Binding::Key(key), pressed),
+            Some(UiEvent::MouseInput(MouseEvent{ button, pressed, .. })) => self.handle_binding(Binding::Mouse(button), pressed),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Pass in a gamepad button being pressed or released, returning what happened to any actions
+    pub fn handle_gamepad(&mut self, button: GamepadButton, pressed: bool) -> Vec<ActionEvent>{
+        self.handle_binding(Binding::Gamepad(button), pressed)
+    }
+
+    /// Handle a binding being pressed or released. This is what `handle_event` and `handle_gamepad` use,
+    /// and is handy for testing or for input coming from somewhere else.
+    pub fn handle_binding(&mut self, binding: Binding, pressed: bool) -> Vec<ActionEvent>{
+        if pressed{
+            if let Some(action) = self.rebinding.take(){
+                if binding == Binding::Key(Key::Escape){
+                    return Vec::new();
+                }
+                self.clear_bindings(&action);
+                self.bind(action.clone(), binding);
+                return vec![ActionEvent::Rebound(action, binding)];
+            }
+            if self.held.contains(&binding){
+                return Vec::new();
+            }
+            self.held.push(binding);
+        }else{
+            if !self.held.contains(&binding){
+                return Vec::new();
+            }
+            self.held.retain(|held| *held != binding);
+        }
+
+        self.actions.iter()
+            .filter(|(_, bindings)| bindings.contains(&binding))
+            .map(|(name, _)| if pressed { ActionEvent::Pressed(name.clone()) } else { ActionEvent::Released(name.clone()) })
+            .collect()
+    }
+}
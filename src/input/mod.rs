@@ -0,0 +1,19 @@
+//! This module turns raw winit input into something easier for components to work with.
+//! Components still get every winit event, but can run them through an `InputState` to get
+//! normalized events with the mouse button, key and keyboard modifiers already worked out.
+
+mod mouse;
+mod keys;
+mod state;
+mod map;
+mod dispatch;
+mod gesture;
+mod pointer;
+
+pub use mouse::{Modifiers, MouseButton, MouseEvent, HoverEvent};
+pub use keys::{Key, KeyEvent};
+pub use state::{InputState, UiEvent};
+pub use map::{InputMap, Binding, GamepadButton, ActionEvent};
+pub use dispatch::{EventPhase, EventResponse, InputCategory, InputRouting, InputPriority};
+pub use gesture::{GestureRecognizer, Gesture, SwipeDirection};
+pub use pointer::PointerLock;
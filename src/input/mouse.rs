@@ -0,0 +1,83 @@
+//! This module contains the mouse and modifier side of the input layer - normalized mouse buttons,
+//! and the set of held modifier keys that gets attached to every input event.
+
+use winit::event::ModifiersState;
+
+/// A mouse button
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum MouseButton{
+    Left,
+    Right,
+    Middle,
+    /// Any other button, like the back and forward buttons. The number depends on the platform.
+    Other(u16),
+}
+
+impl From<winit::event::MouseButton> for MouseButton{
+    fn from(button: winit::event::MouseButton) -> Self{
+        match button{
+            winit::event::MouseButton::Left => MouseButton::Left,
+            winit::event::MouseButton::Right => MouseButton::Right,
+            winit::event::MouseButton::Middle => MouseButton::Middle,
+            winit::event::MouseButton::Other(other) => MouseButton::Other(other),
+        }
+    }
+}
+
+/// # Modifiers
+///
+/// Which modifier keys are held down
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Modifiers{
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    /// The windows key, or command on macOS
+    pub logo: bool,
+}
+
+impl Modifiers{
+    /// No modifiers held
+    pub const NONE: Modifiers = Modifiers{ ctrl: false, shift: false, alt: false, logo: false };
+
+    /// The modifier used for shortcuts and multi-select on this platform - command on macOS, ctrl everywhere else
+    pub fn command(&self) -> bool{
+        if cfg!(target_os = "macos"){
+            self.logo
+        }else{
+            self.ctrl
+        }
+    }
+
+    pub fn is_empty(&self) -> bool{
+        *self == Modifiers::NONE
+    }
+}
+
+impl From<ModifiersState> for Modifiers{
+    fn from(state: ModifiersState) -> Self{
+        Self{
+            ctrl: state.ctrl(),
+            shift: state.shift(),
+            alt: state.alt(),
+            logo: state.logo(),
+        }
+    }
+}
+
+/// A mouse button being pressed or released
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MouseEvent{
+    pub button: MouseButton,
+    pub pressed: bool,
+    /// Where the cursor was, in the same space as components (pixels from the centre of the screen)
+    pub pos: [f32; 2],
+    pub modifiers: Modifiers,
+}
+
+/// Sent to a component when the cursor starts or stops hovering over it, see `EventGUIComponent::handle_hover`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HoverEvent{
+    Enter,
+    Exit,
+}
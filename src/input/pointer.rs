@@ -0,0 +1,79 @@
+//! This module contains `PointerLock`, which hides the cursor and keeps it in the window while something
+//! needs raw mouse movement, like dragging a slider past the edge of the screen or orbiting a 3D viewport.
+
+use winit::{dpi::PhysicalPosition, event::{DeviceEvent, Event, WindowEvent}};
+
+/// # PointerLock
+///
+/// Locks the cursor to the window and hides it, reporting the raw mouse movement instead. When unlocked, the cursor
+/// is put back where it was locked, so an infinite drag doesn't leave it somewhere unexpected.
+///
+/// The lock is let go automatically if the window loses focus, so the user never gets stuck.
+pub struct PointerLock{
+    locked: bool,
+    cursor_pos: PhysicalPosition<f64>,
+    restore_pos: Option<PhysicalPosition<f64>>,
+}
+
+impl PointerLock{
+    pub fn new() -> Self{
+        Self{
+            locked: false,
+            cursor_pos: PhysicalPosition::new(0.0, 0.0),
+            restore_pos: None,
+        }
+    }
+
+    /// Grab and hide the cursor. Fails if the platform doesn't support grabbing it.
+    pub fn lock(&mut self, window: &winit::window::Window) -> Result<(), &'static str>{
+        if self.locked{
+            return Ok(());
+        }
+        window.set_cursor_grab(true).map_err(|_| "Error, failed to grab the cursor!")?;
+        window.set_cursor_visible(false);
+        self.restore_pos = Some(self.cursor_pos);
+        self.locked = true;
+        Ok(())
+    }
+
+    /// Let go of the cursor, show it again, and put it back where it was locked
+    pub fn unlock(&mut self, window: &winit::window::Window){
+        if !self.locked{
+            return;
+        }
+        let _ = window.set_cursor_grab(false);
+        window.set_cursor_visible(true);
+        if let Some(pos) = self.restore_pos.take(){
+            let _ = window.set_cursor_position(pos);
+        }
+        self.locked = false;
+    }
+
+    pub fn is_locked(&self) -> bool{
+        self.locked
+    }
+
+    /// Pass in every event. While locked, this returns how far the mouse moved, in raw device units
+    /// (roughly pixels, before any pointer acceleration).
+    pub fn handle_event(&mut self, event: &Event<()>, window: &winit::window::Window) -> Option<[f32; 2]>{
+        match event{
+            Event::WindowEvent{
+                ref event,
+                window_id,
+            } if window_id == &window.id() => {
+                match event{
+                    WindowEvent::CursorMoved{ position, .. } if !self.locked => {
+                        self.cursor_pos = *position;
+                    }
+                    WindowEvent::Focused(false) => self.unlock(window),
+                    _ => {}
+                }
+                None
+            }
+            Event::DeviceEvent{ event: DeviceEvent::MouseMotion{ delta }, .. } if self.locked => {
+                Some([delta.0 as f32, delta.1 as f32])
+            }
+            _ => None,
+        }
+    }
+}
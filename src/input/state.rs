@@ -0,0 +1,150 @@
+//! This module contains `InputState`, which keeps track of the modifiers, cursor, mouse buttons and keys
+//! (which winit reports in separate events), and turns winit events into normalized `UiEvent`s.
+
+use winit::event::{DeviceEvent, ElementState, Event, MouseScrollDelta, WindowEvent};
+
+use crate::components::base_components::window_to_component_space;
+
+use super::{Key, KeyEvent, Modifiers, MouseButton, MouseEvent};
+
+// How many pixels one line of a (non pixel based) mouse wheel scrolls
+const LINE_HEIGHT: f32 = 40.0;
+
+/// A normalized input event, see `InputState::handle_event`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum UiEvent{
+    CursorMoved{ pos: [f32; 2], modifiers: Modifiers },
+    CursorLeft,
+    MouseInput(MouseEvent),
+    Key(KeyEvent),
+    /// Some text was typed. Control characters (like backspace) are left out, use the key events for those.
+    Text(char),
+    /// The mouse wheel (or touchpad) scrolled, in pixels. Positive y scrolls up.
+    Scroll{ delta: [f32; 2], pos: [f32; 2], modifiers: Modifiers },
+    /// The raw movement of the mouse, straight from the device. This keeps coming when the cursor hits the edge of
+    /// the screen or is locked in place (see `PointerLock`), so it's what infinite drags and 3D viewports want.
+    /// Only sent while the window has focus.
+    MouseMotion{ delta: [f32; 2] },
+}
+
+/// # InputState
+///
+/// Keeps track of the held modifiers, the cursor, and the pressed mouse buttons and keys. Components that care about
+/// them keep one of these around and pass it every event they get, instead of tracking everything themselves.
+#[derive(Debug, Clone, Default)]
+pub struct InputState{
+    modifiers: Modifiers,
+    cursor_pos: [f32; 2],
+    pressed: Vec<MouseButton>,
+    held_keys: Vec<u32>, // scancodes, so we can spot key repeats
+    unfocused: bool,
+}
+
+impl InputState{
+    pub fn new() -> Self{
+        Self::default()
+    }
+
+    /// Update the state from a winit event, returning the matching `UiEvent` if it was an input event
+    pub fn handle_event(&mut self, event: &Event<()>, window: &winit::window::Window) -> Option<UiEvent>{
+        let event = match event{
+            Event::WindowEvent{
+                ref event,
+                window_id,
+            } if window_id == &window.id() => event,
+            // Device events come in even when another window has focus, so ignore them then
+            Event::DeviceEvent{ event: DeviceEvent::MouseMotion{ delta }, .. } if !self.unfocused => {
+                return Some(UiEvent::MouseMotion{ delta: [delta.0 as f32, delta.1 as f32] });
+            }
+            _ => return None,
+        };
+        let size = window.inner_size();
+
+        match event{
+            WindowEvent::ModifiersChanged(state) => {
+                self.modifiers = Modifiers::from(*state);
+                None
+            }
+            WindowEvent::CursorMoved{ position, .. } => {
+                self.cursor_pos = window_to_component_space([position.x, position.y], (size.width, size.height));
+                Some(UiEvent::CursorMoved{ pos: self.cursor_pos, modifiers: self.modifiers })
+            }
+            WindowEvent::CursorLeft{ .. } => Some(UiEvent::CursorLeft),
+            WindowEvent::MouseInput{ state, button, .. } => {
+                let button = MouseButton::from(*button);
+                let pressed = state == &ElementState::Pressed;
+                if pressed{
+                    if !self.pressed.contains(&button){
+                        self.pressed.push(button);
+                    }
+                }else{
+                    self.pressed.retain(|held| *held != button);
+                }
+                Some(UiEvent::MouseInput(MouseEvent{
+                    button,
+                    pressed,
+                    pos: self.cursor_pos,
+                    modifiers: self.modifiers,
+                }))
+            }
+            WindowEvent::MouseWheel{ delta, .. } => {
+                let delta = match delta{
+                    MouseScrollDelta::LineDelta(x, y) => [x * LINE_HEIGHT, y * LINE_HEIGHT],
+                    MouseScrollDelta::PixelDelta(position) => [position.x as f32, position.y as f32],
+                };
+                Some(UiEvent::Scroll{ delta, pos: self.cursor_pos, modifiers: self.modifiers })
+            }
+            WindowEvent::KeyboardInput{ input, .. } => {
+                let pressed = input.state == ElementState::Pressed;
+                let repeat = pressed && self.held_keys.contains(&input.scancode);
+                if pressed{
+                    if !repeat{
+                        self.held_keys.push(input.scancode);
+                    }
+                }else{
+                    self.held_keys.retain(|held| *held != input.scancode);
+                }
+                Some(UiEvent::Key(KeyEvent{
+                    key: Key::from_input(input),
+                    physical: Key::from_scancode(input.scancode),
+                    scancode: input.scancode,
+                    pressed,
+                    repeat,
+                    modifiers: self.modifiers,
+                }))
+            }
+            WindowEvent::ReceivedCharacter(character) if !character.is_control() => Some(UiEvent::Text(*character)),
+            // We won't see the buttons being released, so forget about them
+            WindowEvent::Focused(true) => {
+                self.unfocused = false;
+                None
+            }
+            WindowEvent::Focused(false) => {
+                self.unfocused = true;
+                self.pressed.clear();
+                self.held_keys.clear();
+                self.modifiers = Modifiers::NONE;
+                None
+            }
+            _ => None,
+        }
+    }
+
+    pub fn get_modifiers(&self) -> Modifiers{
+        self.modifiers
+    }
+
+    /// The cursor position, in the same space as components
+    pub fn get_cursor_pos(&self) -> [f32; 2]{
+        self.cursor_pos
+    }
+
+    pub fn is_pressed(&self, button: MouseButton) -> bool{
+        self.pressed.contains(&button)
+    }
+
+    /// Check if a key is held down, by its physical position
+    pub fn is_key_held(&self, key: Key) -> bool{
+        self.held_keys.iter().any(|scancode| Key::from_scancode(*scancode) == key)
+    }
+}
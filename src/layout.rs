@@ -4,7 +4,131 @@
 //! with little to no delay.
 
 
-use crate::components::{EventGUIComponent, GUIComponent, TextGUIComponent};
+use std::{cell::RefCell, collections::{HashMap, HashSet}, rc::Rc};
+
+use wgpu_glyph::{HorizontalAlign, VerticalAlign};
+
+use crate::{components::{Badge, ContextMenu, EventGUIComponent, GUIComponent, Icon, Label, LoadingProgress, LoadingScreen, Rect, SplashScreen, TextGUIComponent, base_components::window_to_component_space, tooltip::HoverTooltip, toast::{Notifications, ToastLevel}}, hud::{Anchor, SafeArea, WorldAnchor, anchor_rect, world_anchor_pos}, input::{EventPhase, EventResponse, HoverEvent, InputCategory, InputPriority}, rendering::{Renderer, TextBrush}};
+
+/// A component that can be hovered, by where it is in the layout
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HoverTarget{
+    Event(usize),
+    Overlay(usize),
+}
+
+/// A component in a layout, by which list it's in and its ID in that list. Handles move along with the IDs when
+/// components before them are removed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ComponentHandle{
+    Component(usize),
+    Event(usize),
+    Text(usize),
+    Overlay(usize),
+}
+
+// A change to make to a layout once the current event has been dispatched
+pub(crate) enum LayoutCommand{
+    AddComponent(Box<dyn GUIComponent>),
+    AddEventComponent(Box<dyn EventGUIComponent>),
+    AddTextComponent(Box<dyn TextGUIComponent>),
+    AddOverlayComponent(Box<dyn EventGUIComponent>),
+    RemoveComponent(usize),
+    RemoveEventComponent(usize),
+    RemoveTextComponent(usize),
+    RemoveOverlayComponent(usize),
+    PushLayout(Layout),
+    PopLayout,
+    Notify(String, ToastLevel),
+    Run(Box<dyn FnOnce(&mut Layout)>),
+}
+
+/// # LayoutCommands
+///
+/// A queue of changes to make to a layout. Components can't change the layout they're in while it's handling an
+/// event (the layout is already borrowed), so they queue the change here instead, and the GUI applies it once the
+/// event has been dispatched - before the next event, update or frame.
+///
+/// Get one from `Layout::get_commands` and keep it around, it's cheap to clone. Commands run in the order they were
+/// queued. Adding components doesn't give you their IDs, so use `run` if you need them.
+#[derive(Clone)]
+pub struct LayoutCommands{
+    queue: Rc<RefCell<Vec<LayoutCommand>>>,
+}
+
+impl LayoutCommands{
+    fn new() -> Self{
+        Self{
+            queue: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    fn push(&self, command: LayoutCommand){
+        self.queue.borrow_mut().push(command);
+    }
+
+    pub fn add_component<T: GUIComponent + 'static>(&self, comp: Box<T>){
+        self.push(LayoutCommand::AddComponent(comp));
+    }
+
+    pub fn add_event_component<T: EventGUIComponent + 'static>(&self, comp: Box<T>){
+        self.push(LayoutCommand::AddEventComponent(comp));
+    }
+
+    pub fn add_text_component<T: TextGUIComponent + 'static>(&self, comp: Box<T>){
+        self.push(LayoutCommand::AddTextComponent(comp));
+    }
+
+    /// Add an overlay component, for opening popovers and the like
+    pub fn add_overlay_component<T: EventGUIComponent + 'static>(&self, comp: Box<T>){
+        self.push(LayoutCommand::AddOverlayComponent(comp));
+    }
+
+    pub fn remove_component_by_id(&self, id: usize){
+        self.push(LayoutCommand::RemoveComponent(id));
+    }
+
+    pub fn remove_event_component_by_id(&self, id: usize){
+        self.push(LayoutCommand::RemoveEventComponent(id));
+    }
+
+    pub fn remove_text_component_by_id(&self, id: usize){
+        self.push(LayoutCommand::RemoveTextComponent(id));
+    }
+
+    pub fn remove_overlay_component_by_id(&self, id: usize){
+        self.push(LayoutCommand::RemoveOverlayComponent(id));
+    }
+
+    /// Stack a layout on top of the others, see `Renderer::push_layout`
+    pub fn push_layout(&self, layout: Layout){
+        self.push(LayoutCommand::PushLayout(layout));
+    }
+
+    /// Remove the topmost stacked layout, see `Renderer::pop_layout`
+    pub fn pop_layout(&self){
+        self.push(LayoutCommand::PopLayout);
+    }
+
+    /// Show a toast, see `Layout::notify`
+    pub fn notify(&self, text: &str, level: ToastLevel){
+        self.push(LayoutCommand::Notify(text.to_string(), level));
+    }
+
+    /// Run a function on the layout, for anything the other commands don't cover
+    pub fn run<F: FnOnce(&mut Layout) + 'static>(&self, function: F){
+        self.push(LayoutCommand::Run(Box::new(function)));
+    }
+
+    /// How many commands are waiting to be applied
+    pub fn len(&self) -> usize{
+        self.queue.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.queue.borrow().is_empty()
+    }
+}
 
 /// # Layout
 ///
@@ -13,10 +137,52 @@ use crate::components::{EventGUIComponent, GUIComponent, TextGUIComponent};
 /// It stores one for regular image based GUI components,
 /// and one for rendering text based components like labels.
 /// It also stores event components, components which should check events.
+///
+/// Lastly, there is an overlay layer for things that float above everything else, like tooltips, popovers,
+/// drag ghosts and toasts. Overlay components are event components that always get rendered last (their text
+/// included) and get every event before the rest of the layout, no matter when they were added.
+///
+/// When layouts are stacked on top of each other (see `Renderer::push_layout`), the input priority decides
+/// which kinds of input get through to the layouts underneath.
+///
+/// Components can be given a line of text to show in a tooltip when hovered with `set_tooltip`, without
+/// adding a `Tooltip` of their own, and a menu to open when right-clicked with `set_context_menu`. Short messages
+/// can be shown in toasts that go away on their own with `notify`, and counts in a `Badge` on the corner of a
+/// component with `set_badge`.
+///
+/// Components can also be anchored to slots around the edge of the screen (see `set_anchor`), which keeps them
+/// there as the window resizes - `Layout::hud` sets a layout up for this, for things like health bars and minimaps.
+/// They can follow points in the game's 3D scene too (see `set_world_anchor`), for nameplates and objective markers.
 pub struct Layout{
     pub components: Vec<Box<dyn GUIComponent>>,
     pub event_components: Vec<Box<dyn EventGUIComponent>>,
     pub text_components: Vec<Box<dyn TextGUIComponent>>,
+    pub overlay_components: Vec<Box<dyn EventGUIComponent>>,
+    pub input_priority: InputPriority,
+    hovered: Option<HoverTarget>,
+    commands: LayoutCommands,
+    arranged_for: Option<(u32, u32)>, // the screen size the components were last arranged for, None if that's out of date
+    content_scale: f32,
+    ui_scale: f32, // the renderer's, on top of the content scale (see `Renderer::reconfigure`)
+    classes: HashMap<ComponentHandle, Vec<String>>,
+    hidden: HashSet<ComponentHandle>,
+    disabled: HashSet<ComponentHandle>, // drawn, but get no input (see `set_disabled`)
+    tooltips: HashMap<ComponentHandle, String>,
+    hover_tooltip: HoverTooltip,
+    badges: HashMap<ComponentHandle, Badge>,
+    context_menus: HashMap<Option<ComponentHandle>, ContextMenu>, // None is the whole layout's menu
+    cursor: [f32; 2], // where the last cursor event put the cursor, for opening context menus
+    notifications: Notifications,
+    anchors: Vec<(ComponentHandle, Anchor)>, // in the order they were anchored, which is the order they stack in
+    safe_area: SafeArea,
+    anchor_spacing: f32,
+    world_anchors: Vec<(ComponentHandle, WorldAnchor)>,
+    view_projection: Option<cgmath::Matrix4<f32>>,
+    behind_camera: HashSet<ComponentHandle>, // world anchored components hidden because their point is behind the camera
+    world_moved: bool, // the camera or a world anchor moved since the world anchored components were placed
+    batch_depth: usize,
+    batch_invalidated: bool, // something invalidated the layout during the current batch
+    loading: Option<LoadingProgress>, // layouts can't be switched with commands while this is on top and loading
 }
 
 
@@ -28,11 +194,193 @@ impl Layout{
             components: Vec::<Box<dyn GUIComponent>>::new(),
             event_components: Vec::<Box<dyn EventGUIComponent>>::new(),
             text_components: Vec::<Box<dyn TextGUIComponent>>::new(),
+            overlay_components: Vec::<Box<dyn EventGUIComponent>>::new(),
+            input_priority: InputPriority::default(),
+            hovered: None,
+            commands: LayoutCommands::new(),
+            arranged_for: None,
+            content_scale: 1.0,
+            ui_scale: 1.0,
+            classes: HashMap::new(),
+            hidden: HashSet::new(),
+            disabled: HashSet::new(),
+            tooltips: HashMap::new(),
+            hover_tooltip: HoverTooltip::new(),
+            badges: HashMap::new(),
+            context_menus: HashMap::new(),
+            cursor: [0.0, 0.0],
+            notifications: Notifications::new(),
+            anchors: Vec::new(),
+            safe_area: SafeArea::default(),
+            anchor_spacing: 8.0,
+            world_anchors: Vec::new(),
+            view_projection: None,
+            behind_camera: HashSet::new(),
+            world_moved: false,
+            batch_depth: 0,
+            batch_invalidated: false,
+            loading: None,
+        }
+    }
+
+    /// Create a layout for a game's HUD, to anchor things like health bars, minimaps and ammo counters to the
+    /// corners and edges of the screen. It's an ordinary layout with a 16 pixel safe area, which passes all input
+    /// through to the layouts under it:
+    ///
+    /// ```ignore
+    /// let mut hud = Layout::hud();
+    /// hud.add_anchored_component(Anchor::TopLeft, Box::new(health_bar));
+    /// hud.add_anchored_text_component(Anchor::BottomRight, Box::new(ammo_label));
+    /// renderer.push_layout(hud);
+    /// ```
+    pub fn hud() -> Self{
+        let mut layout = Self::new();
+        layout.set_safe_area(SafeArea::uniform(16.0));
+        layout.set_input_priority(InputPriority::PASS_THROUGH);
+        layout
+    }
+
+    /// Remove every component, along with the quads, buffers and bind groups they (and the layout's tooltips, context
+    /// menus, badges and notifications) hold, dropping them straight away in the order they were added. Settings like the
+    /// input priority, content scale and safe area are kept, as is the `LayoutCommands` handle, so the layout can be
+    /// filled again - with components made on a new device, after the graphics settings change.
+    pub fn clear_gpu_resources(&mut self){
+        let mut cleared = Layout::new();
+        cleared.input_priority = self.input_priority;
+        cleared.content_scale = self.content_scale;
+        cleared.ui_scale = self.ui_scale;
+        cleared.safe_area = self.safe_area;
+        cleared.anchor_spacing = self.anchor_spacing;
+        cleared.view_projection = self.view_projection;
+        cleared.commands = self.commands.clone();
+        drop(std::mem::replace(self, cleared));
+    }
+
+    /// Create a layout with a `LoadingScreen` filling the screen, showing `progress` and going through `tips`.
+    /// It blocks all input from the layouts under it, and while it's the topmost layout and the load isn't
+    /// finished, pushing and popping layouts through `LayoutCommands` is held back (see `set_loading`):
+    ///
+    /// ```ignore
+    /// let progress = LoadingProgress::new();
+    /// start_loading(progress.clone());
+    /// let loading = Layout::loading_screen(progress, &["Tip: save often"], &renderer);
+    /// loading.get_commands().pop_layout(); // goes once the load is finished
+    /// renderer.push_layout(loading);
+    /// ```
+    pub fn loading_screen(progress: LoadingProgress, tips: &[&str], renderer: &Renderer) -> Self{
+        let mut layout = Self::new();
+        layout.set_input_priority(InputPriority::MODAL);
+        layout.add_component(Box::new(LoadingScreen::new(Rect::new([0.0, 0.0], [0.0, 0.0]), progress.clone(), tips, renderer)));
+        layout.set_loading(Some(progress));
+        layout
+    }
+
+    /// Create a layout filling the screen with `splash`, blocking all input from the layouts under it. Once the
+    /// splash screen is finished the layout pops itself, and pushes `next` (like a main menu) in its place if
+    /// there is one.
+    pub fn splash_screen(mut splash: SplashScreen, next: Option<Layout>) -> Self{
+        let mut layout = Self::new();
+        layout.set_input_priority(InputPriority::MODAL);
+        splash.hand_over(layout.get_commands(), next);
+        layout.add_event_component(Box::new(splash));
+        layout
+    }
+
+    /// Mark the layout as showing a load. While it's the topmost layout and `progress` isn't finished, the
+    /// renderer holds on to layouts pushed and popped with `LayoutCommands`, and switches them once it is.
+    /// `Renderer::push_layout` and `Renderer::pop_layout` still work straight away.
+    pub fn set_loading(&mut self, progress: Option<LoadingProgress>){
+        self.loading = progress;
+    }
+
+    /// True if the layout is showing a load that isn't finished yet
+    pub fn is_loading(&self) -> bool{
+        self.loading.as_ref().is_some_and(|progress| !progress.is_finished())
+    }
+
+    /// Get a handle to queue changes to this layout from inside event handlers and callbacks
+    pub fn get_commands(&self) -> LayoutCommands{
+        self.commands.clone()
+    }
+
+    /// Apply the queued commands (see `LayoutCommands`). Pushing and popping layouts has to be done by the
+    /// renderer, so those commands are handed back.
+    pub(crate) fn apply_commands(&mut self) -> Vec<LayoutCommand>{
+        let mut stack_commands = Vec::new();
+        if !self.commands.is_empty(){
+            self.invalidate_layout();
+        }
+        // Commands can queue more commands (from `run`), so keep going until the queue is empty
+        loop{
+            let commands: Vec<LayoutCommand> = self.commands.queue.borrow_mut().drain(..).collect();
+            if commands.is_empty(){
+                return stack_commands;
+            }
+            for command in commands{
+                match command{
+                    LayoutCommand::AddComponent(comp) => self.components.push(comp),
+                    LayoutCommand::AddEventComponent(comp) => self.event_components.push(comp),
+                    LayoutCommand::AddTextComponent(comp) => self.text_components.push(comp),
+                    LayoutCommand::AddOverlayComponent(comp) => self.overlay_components.push(comp),
+                    LayoutCommand::RemoveComponent(id) if id < self.components.len() => self.remove_component_by_id(id),
+                    LayoutCommand::RemoveEventComponent(id) if id < self.event_components.len() => self.remove_event_component_by_id(id),
+                    LayoutCommand::RemoveTextComponent(id) if id < self.text_components.len() => self.remove_text_component_by_id(id),
+                    LayoutCommand::RemoveOverlayComponent(id) if id < self.overlay_components.len() => self.remove_overlay_component_by_id(id),
+                    LayoutCommand::Notify(text, level) => self.notify(&text, level),
+                    LayoutCommand::Run(function) => function(self),
+                    LayoutCommand::PushLayout(_) | LayoutCommand::PopLayout => stack_commands.push(command),
+                    // Removing something that's already gone
+                    _ => {}
+                }
+            }
         }
     }
     
+    /// Set how this layout shares input with the layouts under it, when it's stacked on top of them
+    pub fn set_input_priority(&mut self, input_priority: InputPriority){
+        self.input_priority = input_priority;
+    }
+
+    /// Scale everything in this layout - quads, text, padding and hitboxes - by some amount, for a "UI size"
+    /// setting. Components carry on working in unscaled pixels, and the layout is scaled around the centre of
+    /// the screen when it's drawn (and the cursor is scaled back before components see it).
+    ///
+    /// Components are positioned in physical pixels, so to scale for the monitor's DPI as well, multiply the
+    /// setting by `window.scale_factor()`.
+    pub fn set_content_scale(&mut self, scale: f32){
+        if scale > 0.0 && scale.is_finite(){
+            self.content_scale = scale;
+            self.invalidate_layout();
+        }
+    }
+
+    pub fn get_content_scale(&self) -> f32{
+        self.content_scale
+    }
+
+    // Set by the renderer, for its UI scale setting
+    pub(crate) fn set_ui_scale(&mut self, scale: f32){
+        if scale != self.ui_scale{
+            self.ui_scale = scale;
+            self.invalidate_layout();
+        }
+    }
+
+    /// The scale the layout is drawn at - its content scale, times the renderer's UI scale (see
+    /// `GraphicsSettings::set_ui_scale`)
+    pub fn get_total_scale(&self) -> f32{
+        self.content_scale * self.ui_scale
+    }
+
+    /// How big the screen is from the point of view of the components, once the scale is taken off
+    pub fn get_content_dim(&self, screen_dim: (u32, u32)) -> (u32, u32){
+        let scale = self.get_total_scale();
+        ((screen_dim.0 as f32 / scale).round() as u32, (screen_dim.1 as f32 / scale).round() as u32)
+    }
+
     /// Adds a new component, Only accepts a GUIComponent type, and returns the ID (location in vec) of the component
     pub fn add_component<T: GUIComponent + 'static>(&mut self, comp: Box<T>) -> usize{
+        self.invalidate_layout();
         self.components.push(comp);
 
         self.components.len() - 1
@@ -47,24 +395,933 @@ impl Layout{
 
     /// Adds a new event component, Only accepts a EventGUIComponent type, and returns the ID (location in vec) of the component
     pub fn add_event_component<T: EventGUIComponent + 'static>(&mut self, comp: Box<T>) -> usize{
+        self.invalidate_layout();
         self.event_components.push(comp);
 
         self.event_components.len() - 1
     }
 
+    /// Adds a new overlay component, which gets drawn on top of everything else. Returns the ID (location in vec) of the component
+    pub fn add_overlay_component<T: EventGUIComponent + 'static>(&mut self, comp: Box<T>) -> usize{
+        self.invalidate_layout();
+        self.overlay_components.push(comp);
+
+        self.overlay_components.len() - 1
+    }
+
     /// Remove a component from the vec using the ID of the component
     pub fn remove_component_by_id(&mut self, id: usize){
+        self.invalidate_layout();
         self.components.remove(id);
+        self.shift_handles(ComponentHandle::Component(id));
     }
 
     /// Remove a text component from the vec using the ID of the text component
     pub fn remove_text_component_by_id(&mut self, id: usize){
         self.text_components.remove(id);
+        self.shift_handles(ComponentHandle::Text(id));
     }
 
     /// Remove a event component from the vec using the ID of the component
     pub fn remove_event_component_by_id(&mut self, id: usize){
+        self.invalidate_layout();
         self.event_components.remove(id);
+        self.shift_handles(ComponentHandle::Event(id));
+        self.hovered = match self.hovered{
+            Some(HoverTarget::Event(hovered)) if hovered == id => None,
+            Some(HoverTarget::Event(hovered)) if hovered > id => Some(HoverTarget::Event(hovered - 1)),
+            hovered => hovered,
+        };
+    }
+
+    /// Remove an overlay component from the vec using the ID of the component
+    pub fn remove_overlay_component_by_id(&mut self, id: usize){
+        self.invalidate_layout();
+        self.overlay_components.remove(id);
+        self.shift_handles(ComponentHandle::Overlay(id));
+        self.hovered = match self.hovered{
+            Some(HoverTarget::Overlay(hovered)) if hovered == id => None,
+            Some(HoverTarget::Overlay(hovered)) if hovered > id => Some(HoverTarget::Overlay(hovered - 1)),
+            hovered => hovered,
+        };
+    }
+
+    /// Give a component a class, like `"danger-button"`, so it can be found with `query`. Components can have
+    /// any number of classes.
+    pub fn add_class(&mut self, handle: ComponentHandle, class: &str){
+        let classes = self.classes.entry(handle).or_default();
+        if !classes.iter().any(|c| c == class){
+            classes.push(class.to_string());
+        }
+    }
+
+    pub fn remove_class(&mut self, handle: ComponentHandle, class: &str){
+        if let Some(classes) = self.classes.get_mut(&handle){
+            classes.retain(|c| c != class);
+            if classes.is_empty(){
+                self.classes.remove(&handle);
+            }
+        }
+    }
+
+    pub fn has_class(&self, handle: ComponentHandle, class: &str) -> bool{
+        self.get_classes(handle).iter().any(|c| c == class)
+    }
+
+    /// The classes of a component, in the order they were added
+    pub fn get_classes(&self, handle: ComponentHandle) -> &[String]{
+        self.classes.get(&handle).map(|classes| classes.as_slice()).unwrap_or(&[])
+    }
+
+    /// Find the components matching a selector, in order. A selector is one or more classes, like `.form.required`
+    /// (components with every class), and several selectors can be separated by commas (components matching any).
+    pub fn query(&self, selector: &str) -> Vec<ComponentHandle>{
+        let selectors: Vec<Vec<&str>> = selector.split(',')
+            .map(|selector| selector.split('.').map(|class| class.trim()).filter(|class| !class.is_empty()).collect::<Vec<_>>())
+            .filter(|classes| !classes.is_empty())
+            .collect();
+
+        let mut handles: Vec<ComponentHandle> = self.classes.iter()
+            .filter(|(_, classes)| selectors.iter().any(|selector| selector.iter().all(|class| classes.iter().any(|c| c == class))))
+            .map(|(handle, _)| *handle)
+            .collect();
+        handles.sort();
+        handles
+    }
+
+    /// Run a function on every component of type `T` matching a selector (see `query`), for bulk changes like
+    /// disabling every field in a form. Components of other types are skipped.
+    pub fn for_each_in<T: 'static, F: FnMut(&mut T)>(&mut self, selector: &str, mut function: F){
+        for handle in self.query(selector){
+            let comp = match handle{
+                ComponentHandle::Component(id) => self.components.get_mut(id).map(|comp| comp.as_any_mut()),
+                ComponentHandle::Event(id) => self.event_components.get_mut(id).map(|comp| comp.as_any_mut()),
+                ComponentHandle::Text(id) => self.text_components.get_mut(id).map(|comp| comp.as_any_mut()),
+                ComponentHandle::Overlay(id) => self.overlay_components.get_mut(id).map(|comp| comp.as_any_mut()),
+            };
+            if let Some(comp) = comp.and_then(|comp| comp.downcast_mut::<T>()){
+                function(comp);
+            }
+        }
+    }
+
+    /// Hide a component, or show it again. Hidden components stay in the layout (keeping their IDs), but aren't
+    /// drawn, don't get events and can't be hovered. Their linked labels are hidden along with them.
+    pub fn set_hidden(&mut self, handle: ComponentHandle, hidden: bool){
+        let changed = if hidden { self.hidden.insert(handle) } else { self.hidden.remove(&handle) };
+        if hidden{
+            self.hover_tooltip.move_target(|target| Some(target).filter(|target| *target != handle));
+            if let Some(menu) = self.context_menus.get_mut(&Some(handle)){
+                menu.close();
+            }
+        }
+        if changed{
+            self.invalidate_layout();
+        }
+    }
+
+    pub fn is_hidden(&self, handle: ComponentHandle) -> bool{
+        self.hidden.contains(&handle)
+    }
+
+    /// Disable a component, or enable it again. Disabled components are still drawn, but don't get input (other
+    /// events, like resizes, still reach them), can't be hovered and can't capture input as a modal.
+    pub fn set_disabled(&mut self, handle: ComponentHandle, disabled: bool){
+        if disabled{
+            self.disabled.insert(handle);
+            self.hover_tooltip.move_target(|target| Some(target).filter(|target| *target != handle));
+        }else{
+            self.disabled.remove(&handle);
+        }
+    }
+
+    pub fn is_disabled(&self, handle: ComponentHandle) -> bool{
+        self.disabled.contains(&handle)
+    }
+
+    /// Show some text in a tooltip once the cursor has hovered over a component for a moment (see
+    /// `set_tooltip_delay`). The tooltip goes near the cursor, on top of everything in the layout, and hides
+    /// when a mouse button is pressed. Only components that return their bounds from `get_bounds` can be hovered.
+    pub fn set_tooltip(&mut self, handle: ComponentHandle, text: &str){
+        self.tooltips.insert(handle, text.to_string());
+        if self.hover_tooltip.get_target() == Some(handle){
+            self.hover_tooltip.set_text(text);
+        }
+    }
+
+    pub fn remove_tooltip(&mut self, handle: ComponentHandle){
+        self.tooltips.remove(&handle);
+        self.hover_tooltip.move_target(|target| Some(target).filter(|target| *target != handle));
+    }
+
+    pub fn get_tooltip(&self, handle: ComponentHandle) -> Option<&str>{
+        self.tooltips.get(&handle).map(|text| text.as_str())
+    }
+
+    /// Set how long (in seconds) the cursor has to hover before a tooltip shows up. Half a second by default.
+    pub fn set_tooltip_delay(&mut self, delay: f32){
+        self.hover_tooltip.set_delay(delay);
+    }
+
+    pub fn get_tooltip_delay(&self) -> f32{
+        self.hover_tooltip.get_delay()
+    }
+
+    /// True while a tooltip from `set_tooltip` is showing
+    pub fn is_tooltip_visible(&self) -> bool{
+        self.hover_tooltip.is_visible()
+    }
+
+    /// Attach a badge to a component, replacing the one it had before. The badge is drawn over the layout's
+    /// components (the overlay included), on the corner of the component's bounds, and goes when the component does.
+    pub fn set_badge(&mut self, handle: ComponentHandle, badge: Badge){
+        self.badges.insert(handle, badge);
+    }
+
+    pub fn remove_badge(&mut self, handle: ComponentHandle) -> Option<Badge>{
+        self.badges.remove(&handle)
+    }
+
+    pub fn get_badge(&self, handle: ComponentHandle) -> Option<&Badge>{
+        self.badges.get(&handle)
+    }
+
+    /// Borrow a component's badge, to change its count
+    pub fn borrow_badge_mut(&mut self, handle: ComponentHandle) -> Option<&mut Badge>{
+        self.badges.get_mut(&handle)
+    }
+
+    // Where a component is, if it's there to be drawn
+    fn visible_bounds(&self, handle: ComponentHandle) -> Option<Rect>{
+        if self.hidden.contains(&handle){
+            return None;
+        }
+        match handle{
+            ComponentHandle::Component(id) => self.components.get(id).filter(|comp| comp.is_enabled()).and_then(|comp| comp.get_bounds()),
+            ComponentHandle::Event(id) => self.event_components.get(id).filter(|comp| comp.is_enabled()).and_then(|comp| comp.get_bounds()),
+            ComponentHandle::Overlay(id) => self.overlay_components.get(id).filter(|comp| comp.is_enabled()).and_then(|comp| comp.get_bounds()),
+            ComponentHandle::Text(_) => None,
+        }
+    }
+
+    /// Open a menu at the cursor when a component is right-clicked, or with `None`, when anywhere in the layout
+    /// without a menu of its own is. Replaces the menu the target had before.
+    pub fn set_context_menu(&mut self, target: Option<ComponentHandle>, menu: ContextMenu){
+        self.context_menus.insert(target, menu);
+    }
+
+    pub fn remove_context_menu(&mut self, target: Option<ComponentHandle>) -> Option<ContextMenu>{
+        self.context_menus.remove(&target)
+    }
+
+    pub fn borrow_context_menu_mut(&mut self, target: Option<ComponentHandle>) -> Option<&mut ContextMenu>{
+        self.context_menus.get_mut(&target)
+    }
+
+    /// Open the context menu of a component (or the layout's, with `None`) at `pos`, closing any other. Returns
+    /// false if there's no menu to open.
+    pub fn open_context_menu(&mut self, target: Option<ComponentHandle>, pos: [f32; 2]) -> bool{
+        if !self.context_menus.contains_key(&target){
+            return false;
+        }
+        self.close_context_menus();
+        self.hover_tooltip.dismiss();
+        self.context_menus.get_mut(&target).unwrap().open_at(pos);
+        true
+    }
+
+    pub fn close_context_menus(&mut self){
+        for menu in self.context_menus.values_mut(){
+            menu.close();
+        }
+    }
+
+    pub fn is_context_menu_open(&self) -> bool{
+        self.context_menus.values().any(|menu| menu.is_open())
+    }
+
+    /// Show a toast in the corner of the screen, which goes away after a few seconds (see `Notifications`)
+    pub fn notify(&mut self, text: &str, level: ToastLevel){
+        self.notifications.show(text, level);
+    }
+
+    /// Borrow the layout's toasts, to change where they go and how long they stay
+    pub fn borrow_notifications_mut(&mut self) -> &mut Notifications{
+        &mut self.notifications
+    }
+
+    pub fn get_notifications(&self) -> &Notifications{
+        &self.notifications
+    }
+
+    // The menu a right-click at `cursor` opens - the clicked component's, or failing that, the layout's
+    fn context_menu_target(&self, cursor: [f32; 2]) -> Option<Option<ComponentHandle>>{
+        match self.component_at(cursor){
+            Some(handle) if self.context_menus.contains_key(&Some(handle)) => Some(Some(handle)),
+            _ if self.get_modal().is_none() && self.context_menus.contains_key(&None) => Some(None),
+            _ => None,
+        }
+    }
+
+    // A component was removed, so forget its classes (and whether it was hidden or disabled), and move the ones after
+    // it down
+    fn shift_handles(&mut self, removed: ComponentHandle){
+        let shift = |handle: ComponentHandle| match (handle, removed){
+            (ComponentHandle::Component(id), ComponentHandle::Component(gone)) if id > gone => ComponentHandle::Component(id - 1),
+            (ComponentHandle::Event(id), ComponentHandle::Event(gone)) if id > gone => ComponentHandle::Event(id - 1),
+            (ComponentHandle::Text(id), ComponentHandle::Text(gone)) if id > gone => ComponentHandle::Text(id - 1),
+            (ComponentHandle::Overlay(id), ComponentHandle::Overlay(gone)) if id > gone => ComponentHandle::Overlay(id - 1),
+            _ => handle,
+        };
+        self.classes = self.classes.drain()
+            .filter(|(handle, _)| *handle != removed)
+            .map(|(handle, classes)| (shift(handle), classes))
+            .collect();
+        self.hidden = self.hidden.drain()
+            .filter(|handle| *handle != removed)
+            .map(shift)
+            .collect();
+        self.disabled = self.disabled.drain()
+            .filter(|handle| *handle != removed)
+            .map(shift)
+            .collect();
+        self.tooltips = self.tooltips.drain()
+            .filter(|(handle, _)| *handle != removed)
+            .map(|(handle, text)| (shift(handle), text))
+            .collect();
+        self.badges = self.badges.drain()
+            .filter(|(handle, _)| *handle != removed)
+            .map(|(handle, badge)| (shift(handle), badge))
+            .collect();
+        self.hover_tooltip.move_target(|target| Some(target).filter(|target| *target != removed).map(shift));
+        self.context_menus = self.context_menus.drain()
+            .filter(|(target, _)| *target != Some(removed))
+            .map(|(target, menu)| (target.map(shift), menu))
+            .collect();
+        self.anchors = self.anchors.drain(..)
+            .filter(|(handle, _)| *handle != removed)
+            .map(|(handle, anchor)| (shift(handle), anchor))
+            .collect();
+        self.world_anchors = self.world_anchors.drain(..)
+            .filter(|(handle, _)| *handle != removed)
+            .map(|(handle, anchor)| (shift(handle), anchor))
+            .collect();
+        self.behind_camera = self.behind_camera.drain()
+            .filter(|handle| *handle != removed)
+            .map(shift)
+            .collect();
+    }
+
+    /// Pin a component to a slot on the screen, or unpin it with `None`. Anchored components are arranged in their
+    /// slot every time the layout is, inside the safe area, so they stay put as the window resizes. Components in
+    /// the same slot stack up away from the edge of the screen (down from the top and middle slots, up from the
+    /// bottom ones) in the order they were anchored.
+    ///
+    /// Components are sized with `measure` (or failing that, `get_bounds`) and placed with `arrange`, so only
+    /// components that can be arranged move. Labels get aligned to the slot instead, and icons are placed like a
+    /// square as big as their size.
+    pub fn set_anchor(&mut self, handle: ComponentHandle, anchor: Option<Anchor>){
+        match (self.anchors.iter_mut().find(|(anchored, _)| *anchored == handle), anchor){
+            (Some(slot), Some(anchor)) => slot.1 = anchor,
+            (None, Some(anchor)) => self.anchors.push((handle, anchor)),
+            (_, None) => self.anchors.retain(|(anchored, _)| *anchored != handle),
+        }
+        self.invalidate_layout();
+    }
+
+    pub fn get_anchor(&self, handle: ComponentHandle) -> Option<Anchor>{
+        self.anchors.iter().find(|(anchored, _)| *anchored == handle).map(|(_, anchor)| *anchor)
+    }
+
+    /// Add a component anchored to a slot, returning its ID
+    pub fn add_anchored_component<T: GUIComponent + 'static>(&mut self, anchor: Anchor, comp: Box<T>) -> usize{
+        let id = self.add_component(comp);
+        self.set_anchor(ComponentHandle::Component(id), Some(anchor));
+        id
+    }
+
+    /// Add an event component anchored to a slot, returning its ID
+    pub fn add_anchored_event_component<T: EventGUIComponent + 'static>(&mut self, anchor: Anchor, comp: Box<T>) -> usize{
+        let id = self.add_event_component(comp);
+        self.set_anchor(ComponentHandle::Event(id), Some(anchor));
+        id
+    }
+
+    /// Add a text component (like a label) anchored to a slot, returning its ID
+    pub fn add_anchored_text_component<T: TextGUIComponent + 'static>(&mut self, anchor: Anchor, comp: Box<T>) -> usize{
+        let id = self.add_text_component(comp);
+        self.set_anchor(ComponentHandle::Text(id), Some(anchor));
+        id
+    }
+
+    /// Set how far anchored components stay from the edges of the screen
+    pub fn set_safe_area(&mut self, safe_area: SafeArea){
+        self.safe_area = safe_area;
+        self.invalidate_layout();
+    }
+
+    pub fn get_safe_area(&self) -> SafeArea{
+        self.safe_area
+    }
+
+    /// Set the space between components stacked in the same slot. 8 pixels by default.
+    pub fn set_anchor_spacing(&mut self, spacing: f32){
+        self.anchor_spacing = spacing;
+        self.invalidate_layout();
+    }
+
+    // Put the anchored components in their slots
+    fn arrange_anchored(&mut self, screen_dim: (u32, u32)){
+        let available = [screen_dim.0 as f32, screen_dim.1 as f32];
+        let safe_area = self.safe_area;
+        let mut stacked: HashMap<Anchor, f32> = HashMap::new();
+        for (handle, anchor) in self.anchors.clone(){
+            if self.hidden.contains(&handle){
+                continue;
+            }
+            let offset = *stacked.get(&anchor).unwrap_or(&0.0);
+            let place = |size: [f32; 2]| anchor_rect(anchor, size, screen_dim, &safe_area, offset);
+            let height = match handle{
+                ComponentHandle::Text(id) => self.text_components.get_mut(id).and_then(|comp| {
+                    let comp = comp.as_any_mut();
+                    if let Some(icon) = comp.downcast_mut::<Icon>(){
+                        // Icons are drawn centred on their position, as big as their size both ways
+                        let size = icon.get_size();
+                        icon.set_pos(place([size, size]).pos, screen_dim);
+                        return Some(size);
+                    }
+                    comp.downcast_mut::<Label>().map(|label| {
+                        // Labels can't be measured here, but they can be aligned to the corner of the slot
+                        let [across, down] = anchor.alignment();
+                        label.align_horizontal([HorizontalAlign::Left, HorizontalAlign::Center, HorizontalAlign::Right][(across + 1) as usize]);
+                        label.align_vertical([VerticalAlign::Top, VerticalAlign::Center, VerticalAlign::Bottom][(down + 1) as usize]);
+                        label.set_pos(place([0.0, 0.0]).pos, screen_dim);
+                        label.estimated_height()
+                    })
+                }),
+                _ => self.measure_and_arrange(handle, available, place).map(|size| size[1]),
+            };
+            if let Some(height) = height{
+                stacked.insert(anchor, offset + height + self.anchor_spacing);
+            }
+        }
+    }
+
+    // Size a component with `measure` (or failing that, `get_bounds`) and arrange it where `place` puts something
+    // that size, returning the size. Text components can't be measured, so they're left alone.
+    fn measure_and_arrange<F: Fn([f32; 2]) -> Rect>(&mut self, handle: ComponentHandle, available: [f32; 2], place: F) -> Option<[f32; 2]>{
+        let arrange = |comp: &mut dyn EventGUIComponent| {
+            let size = comp.measure(available).or_else(|| comp.get_bounds().map(|bounds| bounds.size))?;
+            comp.arrange(place(size));
+            Some(size)
+        };
+        match handle{
+            ComponentHandle::Component(id) => self.components.get_mut(id).and_then(|comp| {
+                let size = comp.measure(available).or_else(|| comp.get_bounds().map(|bounds| bounds.size))?;
+                comp.arrange(place(size));
+                Some(size)
+            }),
+            ComponentHandle::Event(id) => self.event_components.get_mut(id).and_then(|comp| arrange(comp.as_mut())),
+            ComponentHandle::Overlay(id) => self.overlay_components.get_mut(id).and_then(|comp| arrange(comp.as_mut())),
+            ComponentHandle::Text(_) => None,
+        }
+    }
+
+    /// Pin a component to a point in the game's 3D scene, or unpin it with `None`. Each frame the point is
+    /// projected with the matrix from `set_view_projection`, and the component is arranged centred on where it
+    /// lands (labels and icons are moved there, labels keeping their alignment). Like with `set_anchor`, only
+    /// components that can be arranged move.
+    ///
+    /// While the point is behind the camera the component is hidden, unless the anchor is clamped to the screen.
+    /// Nothing moves until a view-projection matrix has been set.
+    pub fn set_world_anchor(&mut self, handle: ComponentHandle, anchor: Option<WorldAnchor>){
+        match (self.world_anchors.iter_mut().find(|(anchored, _)| *anchored == handle), anchor){
+            (Some(slot), Some(anchor)) => slot.1 = anchor,
+            (None, Some(anchor)) => self.world_anchors.push((handle, anchor)),
+            (_, None) => {
+                self.world_anchors.retain(|(anchored, _)| *anchored != handle);
+                if self.behind_camera.remove(&handle){
+                    self.set_hidden(handle, false);
+                }
+            }
+        }
+        self.world_moved = true;
+    }
+
+    pub fn get_world_anchor(&self, handle: ComponentHandle) -> Option<WorldAnchor>{
+        self.world_anchors.iter().find(|(anchored, _)| *anchored == handle).map(|(_, anchor)| *anchor)
+    }
+
+    /// Move a component's world anchor to a new point, like when the character it's over walks somewhere
+    pub fn move_world_anchor(&mut self, handle: ComponentHandle, point: [f32; 3]){
+        if let Some((_, anchor)) = self.world_anchors.iter_mut().find(|(anchored, _)| *anchored == handle){
+            anchor.point = point;
+            self.world_moved = true;
+        }
+    }
+
+    /// Set the camera's view-projection matrix, the same one the game renders its scene with (including the
+    /// `OPENGL_TO_WGPU_MATRIX` correction if it uses one), so world anchored components line up with it. Call
+    /// this every frame the camera moves. Plain arrays are column-major, like cgmath's.
+    pub fn set_view_projection<M: Into<cgmath::Matrix4<f32>>>(&mut self, view_projection: M){
+        self.view_projection = Some(view_projection.into());
+        self.world_moved = true;
+    }
+
+    /// True if a world anchored component is hidden because its point is behind the camera
+    pub fn is_behind_camera(&self, handle: ComponentHandle) -> bool{
+        self.behind_camera.contains(&handle)
+    }
+
+    // Move the world anchored components to where their points are on screen
+    fn arrange_world_anchored(&mut self, screen_dim: (u32, u32)){
+        let view_projection = match self.view_projection{
+            Some(view_projection) => view_projection,
+            None => return,
+        };
+        let available = [screen_dim.0 as f32, screen_dim.1 as f32];
+        let safe_area = self.safe_area;
+        for (handle, anchor) in self.world_anchors.clone(){
+            let pos = match world_anchor_pos(&anchor, view_projection, screen_dim, &safe_area){
+                Some(pos) => pos,
+                None => {
+                    if !self.hidden.contains(&handle){
+                        self.behind_camera.insert(handle);
+                        self.set_hidden(handle, true);
+                    }
+                    continue;
+                }
+            };
+            if self.behind_camera.remove(&handle){
+                self.set_hidden(handle, false);
+            }
+            if self.hidden.contains(&handle){
+                continue;
+            }
+            match handle{
+                ComponentHandle::Text(id) => {
+                    if let Some(comp) = self.text_components.get_mut(id).map(|comp| comp.as_any_mut()){
+                        if let Some(label) = comp.downcast_mut::<Label>(){
+                            label.set_pos(pos, screen_dim);
+                        }else if let Some(icon) = comp.downcast_mut::<Icon>(){
+                            icon.set_pos(pos, screen_dim);
+                        }
+                    }
+                }
+                _ => {
+                    self.measure_and_arrange(handle, available, |size| Rect::new(pos, size));
+                }
+            }
+        }
+    }
+
+    /// Measure and arrange the components again before the next frame. Adding and removing components and
+    /// resizing the window do this for you, but changing something that affects a component's size (like the
+    /// text of a label in a container) needs it calling.
+    pub fn invalidate_layout(&mut self){
+        if self.batch_depth > 0{
+            self.batch_invalidated = true;
+        }else{
+            self.arranged_for = None;
+        }
+    }
+
+    /// Make a lot of changes at once, like adding hundreds of rows to a list. Invalidating the layout is held
+    /// back until the function returns, and then happens once, and the layout isn't arranged while it runs.
+    /// Batches can be nested, in which case everything waits for the outermost one.
+    pub fn batch<R, F: FnOnce(&mut Layout) -> R>(&mut self, function: F) -> R{
+        self.batch_depth += 1;
+        let result = function(self);
+        self.batch_depth -= 1;
+        if self.batch_depth == 0 && self.batch_invalidated{
+            self.batch_invalidated = false;
+            self.invalidate_layout();
+        }
+        result
+    }
+
+    /// True while inside `batch`
+    pub fn is_batching(&self) -> bool{
+        self.batch_depth > 0
+    }
+
+    /// Run the measure and arrange passes over the components that take part in layout, unless nothing has
+    /// changed since last time. The renderer calls this before every frame.
+    ///
+    /// Components at the top of the layout get the whole screen to measure themselves in, and stay centred
+    /// where they are.
+    pub fn arrange(&mut self, screen_dim: (u32, u32)){
+        if self.batch_depth > 0{
+            return;
+        }
+        if self.arranged_for == Some(screen_dim){
+            // World anchored components follow the camera every frame, without arranging everything else again
+            if self.world_moved{
+                self.world_moved = false;
+                self.arrange_world_anchored(screen_dim);
+            }
+            return;
+        }
+        let available = [screen_dim.0 as f32, screen_dim.1 as f32];
+
+        for comp in self.components.iter_mut(){
+            if let Some(size) = comp.measure(available){
+                let pos = comp.get_pos();
+                comp.arrange(Rect::new(pos, size));
+            }
+        }
+        for comp in self.event_components.iter_mut().chain(self.overlay_components.iter_mut()){
+            if let Some(size) = comp.measure(available){
+                let pos = comp.get_bounds().map(|bounds| bounds.pos).unwrap_or_else(|| comp.get_pos());
+                comp.arrange(Rect::new(pos, size));
+            }
+        }
+        self.arrange_anchored(screen_dim);
+        self.world_moved = false;
+        self.arrange_world_anchored(screen_dim);
+        self.arranged_for = Some(screen_dim);
+    }
+
+    /// The component currently under the cursor, if any
+    pub fn get_hovered(&self) -> Option<HoverTarget>{
+        self.hovered
+    }
+
+    /// Find the topmost component under the cursor (in component space, or `None` if the cursor left the window),
+    /// and send hover events to the components it moved between. The GUI calls this once per frame when the cursor moved.
+    pub fn update_hover(&mut self, cursor: Option<[f32; 2]>){
+        let scale = self.get_total_scale();
+        let cursor = cursor.map(|cursor| [cursor[0] / scale, cursor[1] / scale]);
+        let (hidden, disabled) = (&self.hidden, &self.disabled);
+        let modal = self.get_modal();
+        let under_cursor = |comp: &dyn EventGUIComponent, handle: ComponentHandle| match (cursor, comp.get_bounds()){
+            (Some(cursor), Some(bounds)) => comp.is_enabled() && !hidden.contains(&handle) && !disabled.contains(&handle) && (modal.is_none() || modal == Some(handle)) && bounds.contains(cursor),
+            _ => false,
+        };
+        // Things added later are drawn on top, and the overlay is on top of everything
+        let hovered = (0..self.overlay_components.len()).rev()
+            .find(|id| under_cursor(self.overlay_components[*id].as_ref(), ComponentHandle::Overlay(*id))).map(HoverTarget::Overlay)
+            .or_else(|| (0..self.event_components.len()).rev()
+                .find(|id| under_cursor(self.event_components[*id].as_ref(), ComponentHandle::Event(*id))).map(HoverTarget::Event));
+
+        if hovered != self.hovered{
+            if let Some(old) = self.hovered{
+                self.send_hover(old, HoverEvent::Exit);
+            }
+            if let Some(new) = hovered{
+                self.send_hover(new, HoverEvent::Enter);
+            }
+            self.hovered = hovered;
+        }
+
+        let target = cursor.and_then(|cursor| self.tooltip_target(cursor));
+        let tooltips = &self.tooltips;
+        self.hover_tooltip.set_target(target.map(|handle| (handle, tooltips[&handle].as_str())), cursor.unwrap_or([0.0, 0.0]));
+    }
+
+    // The topmost component under the cursor, if it has a tooltip. No tooltips show while a context menu is open.
+    fn tooltip_target(&self, cursor: [f32; 2]) -> Option<ComponentHandle>{
+        if self.is_context_menu_open(){
+            return None;
+        }
+        self.component_at(cursor).filter(|handle| self.tooltips.contains_key(handle))
+    }
+
+    // The topmost component under the cursor. A hovered event component covers up everything under it.
+    fn component_at(&self, cursor: [f32; 2]) -> Option<ComponentHandle>{
+        match self.hovered{
+            Some(HoverTarget::Event(id)) => Some(ComponentHandle::Event(id)),
+            Some(HoverTarget::Overlay(id)) => Some(ComponentHandle::Overlay(id)),
+            None if self.get_modal().is_some() => None,
+            None => (0..self.components.len()).rev().find(|id| {
+                let comp = &self.components[*id];
+                let handle = ComponentHandle::Component(*id);
+                comp.is_enabled() && !self.hidden.contains(&handle) && !self.disabled.contains(&handle) && matches!(comp.get_bounds(), Some(bounds) if bounds.contains(cursor))
+            }).map(ComponentHandle::Component),
+        }
+    }
+
+    /// The topmost component capturing all input, if any (see `EventGUIComponent::is_modal`)
+    pub fn get_modal(&self) -> Option<ComponentHandle>{
+        self.event_handles().zip(self.event_components.iter().chain(self.overlay_components.iter()))
+            .filter(|(handle, comp)| comp.is_enabled() && comp.is_modal() && !self.hidden.contains(handle) && !self.disabled.contains(handle))
+            .last()
+            .map(|(handle, _)| handle)
+    }
+
+    /// True if a kind of input shouldn't reach the layouts under this one, either because of the input priority or
+    /// because a modal component or context menu is capturing everything
+    pub fn blocks_input(&self, category: InputCategory) -> bool{
+        self.input_priority.blocks(category) || self.get_modal().is_some() || self.is_context_menu_open()
+    }
+
+    /// Send an event through the layout, from the top of the overlay down to the bottom, giving each component the
+    /// capture phase and then the bubble phase before moving on (the layout isn't a container, so capturing only
+    /// means something inside components with children). Returns true if a component consumed the event.
+    ///
+    /// While a modal component is open, input only goes to it. Other events (like resizes) still reach everything.
+    /// An open context menu gets input before anything else, and a right-click opens the menu under the cursor.
+    pub fn dispatch_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window) -> bool{
+        use winit::event::{ElementState, Event, MouseButton, WindowEvent};
+
+        if let Event::WindowEvent{ event: WindowEvent::MouseInput{ state: ElementState::Pressed, .. }, .. } = event{
+            self.hover_tooltip.dismiss();
+        }
+        let scaled = self.scale_event(event, window);
+        let event = scaled.as_ref().unwrap_or(event);
+
+        if let Event::WindowEvent{ event: WindowEvent::CursorMoved{ position, .. }, .. } = event{
+            let size = window.inner_size();
+            self.cursor = window_to_component_space([position.x, position.y], (size.width, size.height));
+        }
+        if let Some(menu) = self.context_menus.values_mut().find(|menu| menu.is_open()){
+            if menu.handle_event(event, window){
+                return true;
+            }
+        }
+        if let Event::WindowEvent{ event: WindowEvent::MouseInput{ state: ElementState::Pressed, button: MouseButton::Right, .. }, .. } = event{
+            if let Some(target) = self.context_menu_target(self.cursor){
+                return self.open_context_menu(target, self.cursor);
+            }
+        }
+        // Input only goes to the modal component, if there is one
+        let input = InputCategory::of(event).is_some();
+        let only = self.get_modal().filter(|_| input);
+        let (hidden, disabled) = (&self.hidden, &self.disabled);
+        let events = self.event_components.iter_mut().enumerate().map(|(id, comp)| (ComponentHandle::Event(id), comp));
+        let overlays = self.overlay_components.iter_mut().enumerate().map(|(id, comp)| (ComponentHandle::Overlay(id), comp));
+        let mut comps: Vec<_> = events.chain(overlays)
+            .filter(|(handle, _)| !hidden.contains(handle) && !(input && disabled.contains(handle)) && (only.is_none() || only == Some(*handle)))
+            .map(|(_, comp)| comp)
+            .collect();
+
+        for comp in comps.iter_mut().rev(){
+            for phase in [EventPhase::Capture, EventPhase::Bubble]{
+                if comp.handle_event(event, window, phase) == EventResponse::Consumed{
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    // Components work out where the cursor is from the event and the window size, so move the cursor (and
+    // touches) to where it would be if the whole window was unscaled
+    fn scale_event(&self, event: &winit::event::Event<()>, window: &winit::window::Window) -> Option<winit::event::Event<'static, ()>>{
+        use winit::{dpi::PhysicalPosition, event::{Event, WindowEvent}};
+
+        if self.get_total_scale() == 1.0{
+            return None;
+        }
+        let size = window.inner_size();
+        let centre = [size.width as f64 / 2.0, size.height as f64 / 2.0];
+        let scale = self.get_total_scale() as f64;
+        let unscale = |position: PhysicalPosition<f64>| PhysicalPosition::new(
+            (position.x - centre[0]) / scale + centre[0],
+            (position.y - centre[1]) / scale + centre[1],
+        );
+
+        match event{
+            Event::WindowEvent{ window_id, event: WindowEvent::CursorMoved{ device_id, position, .. } } => {
+                #[allow(deprecated)]
+                let event = WindowEvent::CursorMoved{ device_id: *device_id, position: unscale(*position), modifiers: Default::default() };
+                Some(Event::WindowEvent{ window_id: *window_id, event })
+            }
+            Event::WindowEvent{ window_id, event: WindowEvent::Touch(touch) } => {
+                let mut touch = *touch;
+                touch.location = unscale(touch.location);
+                Some(Event::WindowEvent{ window_id: *window_id, event: WindowEvent::Touch(touch) })
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the layout ready to render - move linked text to its components, and let the components write
+    /// any changed values to the GPU
+    pub(crate) fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        let mut text_child_components = Vec::<(usize, bool, [f32; 2])>::new();
+        for (id, comp) in self.components.iter().enumerate(){
+            if let Some(text_id) = comp.get_text_id(){
+                text_child_components.push((text_id, comp.is_enabled() && !self.hidden.contains(&ComponentHandle::Component(id)), comp.get_pos()));
+            }
+        }
+        for (handle, comp) in self.event_handles().zip(self.event_components.iter().chain(self.overlay_components.iter())){
+            if let Some(text_id) = comp.get_text_id(){
+                text_child_components.push((text_id, comp.is_enabled() && !self.hidden.contains(&handle), comp.get_pos()));
+            }
+        }
+
+        for (id, enabled, pos) in text_child_components.iter(){
+            let text = self.borrow_text_component_as_type_mut::<Label>(*id).unwrap();
+            text.set_pos(*pos, screen_dim);
+            if *enabled{
+                text.enable();
+            }else{
+                text.disable();
+            }
+        }
+
+        for comp in self.components.iter_mut(){
+            comp.prepare(queue, screen_dim);
+        }
+        for comp in self.event_components.iter_mut(){
+            comp.prepare(queue, screen_dim);
+        }
+        for comp in self.overlay_components.iter_mut(){
+            comp.prepare(queue, screen_dim);
+        }
+        // Badges follow their components, wherever they were arranged
+        let bounds: Vec<_> = self.badges.keys().map(|handle| (*handle, self.visible_bounds(*handle))).collect();
+        for (handle, bounds) in bounds{
+            let badge = self.badges.get_mut(&handle).unwrap();
+            badge.place(bounds);
+            badge.prepare(queue, screen_dim);
+        }
+        self.notifications.prepare(queue, screen_dim);
+        self.hover_tooltip.prepare(queue, screen_dim);
+        for menu in self.context_menus.values_mut(){
+            menu.prepare(queue, screen_dim);
+        }
+    }
+
+    /// Let components create anything they need on the GPU, before the frame gets laid out
+    pub(crate) fn rebuild(&mut self, device: &wgpu::Device){
+        let mut changed = false;
+        for comp in self.components.iter_mut(){
+            changed |= comp.rebuild(device);
+        }
+        for comp in self.event_components.iter_mut().chain(self.overlay_components.iter_mut()){
+            changed |= comp.rebuild(device);
+        }
+        self.notifications.rebuild(device);
+        self.hover_tooltip.rebuild(device);
+        if changed{
+            self.invalidate_layout();
+        }
+    }
+
+    /// Let components measure their text, before they get prepared
+    pub(crate) fn layout_text(&mut self, brush: &mut TextBrush){
+        for comp in self.components.iter_mut(){
+            comp.layout_text(brush);
+        }
+        for comp in self.event_components.iter_mut().chain(self.overlay_components.iter_mut()){
+            comp.layout_text(brush);
+        }
+        for badge in self.badges.values_mut(){
+            badge.layout_text(brush);
+        }
+        self.notifications.layout_text(brush);
+        self.hover_tooltip.layout_text(brush);
+    }
+
+    /// Advance the components by a frame, returning true if any of them are animating
+    pub(crate) fn update(&mut self, dt: f32) -> bool{
+        let mut animating = false;
+        for comp in self.components.iter_mut(){
+            comp.update(dt);
+            animating |= comp.is_animating();
+        }
+        for comp in self.event_components.iter_mut(){
+            comp.update(dt);
+            animating |= comp.is_animating();
+        }
+        for comp in self.overlay_components.iter_mut(){
+            comp.update(dt);
+            animating |= comp.is_animating();
+        }
+        for comp in self.text_components.iter_mut(){
+            comp.update(dt);
+            animating |= comp.is_animating();
+        }
+        self.notifications.update(dt);
+        animating |= self.notifications.is_animating();
+        animating |= self.hover_tooltip.update(dt);
+        animating
+    }
+
+    /// Draw the components. The pipeline and bind groups are the defaults the renderer sets up before each component
+    /// (the camera, a tint and a texture), so components can swap them for their own.
+    pub(crate) fn render<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>, pipeline: &'a wgpu::RenderPipeline, defaults: [&'a wgpu::BindGroup; 3]){
+        for (id, comp) in self.components.iter().enumerate(){
+            if self.hidden.contains(&ComponentHandle::Component(id)){
+                continue;
+            }
+            render_pass.set_pipeline(pipeline);
+            render_pass.set_bind_group(0, defaults[0], &[]);
+            render_pass.set_bind_group(2, defaults[1], &[]);
+            render_pass.set_bind_group(3, defaults[2], &[]);
+            comp.render(render_pass);
+        }
+        // The overlay goes on top of everything else
+        for (handle, comp) in self.event_handles().zip(self.event_components.iter().chain(self.overlay_components.iter())){
+            if self.hidden.contains(&handle){
+                continue;
+            }
+            render_pass.set_pipeline(pipeline);
+            render_pass.set_bind_group(0, defaults[0], &[]);
+            render_pass.set_bind_group(2, defaults[1], &[]);
+            render_pass.set_bind_group(3, defaults[2], &[]);
+            comp.render(render_pass);
+        }
+        // Then badges, and toasts and tooltips from `set_tooltip` on top of them
+        render_pass.set_pipeline(pipeline);
+        render_pass.set_bind_group(0, defaults[0], &[]);
+        render_pass.set_bind_group(2, defaults[1], &[]);
+        for badge in self.badges.values(){
+            render_pass.set_bind_group(3, defaults[2], &[]);
+            badge.render(render_pass);
+        }
+        render_pass.set_bind_group(3, defaults[2], &[]);
+        self.notifications.render(render_pass);
+        self.hover_tooltip.render(render_pass);
+        // And context menus go on top of that
+        for menu in self.context_menus.values(){
+            menu.render(render_pass);
+        }
+    }
+
+    /// Queue all of the layout's text
+    pub(crate) fn render_text(&self, brush: &mut TextBrush){
+        for (id, text_comp) in self.text_components.iter().enumerate(){
+            if !self.hidden.contains(&ComponentHandle::Text(id)){
+                text_comp.render_text(brush);
+            }
+        }
+        // Text owned by the components themselves
+        for (id, comp) in self.components.iter().enumerate(){
+            if !self.hidden.contains(&ComponentHandle::Component(id)){
+                comp.render_text(brush);
+            }
+        }
+        for (handle, comp) in self.event_handles().zip(self.event_components.iter().chain(self.overlay_components.iter())){
+            if !self.hidden.contains(&handle){
+                comp.render_text(brush);
+            }
+        }
+        for badge in self.badges.values(){
+            badge.render_text(brush);
+        }
+        self.notifications.render_text(brush);
+        self.hover_tooltip.render_text(brush);
+        for menu in self.context_menus.values(){
+            menu.render_text(brush);
+        }
+    }
+
+    // The handles of the event components followed by the overlay, in the same order as iterating over them
+    fn event_handles(&self) -> impl Iterator<Item = ComponentHandle>{
+        (0..self.event_components.len()).map(ComponentHandle::Event).chain((0..self.overlay_components.len()).map(ComponentHandle::Overlay))
+    }
+
+    fn send_hover(&mut self, target: HoverTarget, event: HoverEvent){
+        let comp = match target{
+            HoverTarget::Event(id) => self.event_components.get_mut(id),
+            HoverTarget::Overlay(id) => self.overlay_components.get_mut(id),
+        };
+        if let Some(comp) = comp{
+            comp.handle_hover(event);
+        }
     }
 
     /// Borrow a component (non modifiable)
@@ -150,4 +1407,26 @@ impl Layout{
         }
         return Err("Error, failed to downcast!");
     }
+
+    /// Borrow an overlay component (non modifiable)
+    pub fn borrow_overlay_component(&self, id: usize) -> &dyn EventGUIComponent{
+        self.overlay_components[id].as_ref()
+    }
+
+    /// Borrow an overlay component as a type (non modifiable)
+    pub fn borrow_overlay_component_as_type<T: EventGUIComponent + 'static>(&self, id: usize) -> Result<&T, &'static str>{
+        let comp = self.overlay_components.get(id).ok_or("Error, no overlay component with that ID!")?;
+        comp.as_any().downcast_ref::<T>().ok_or("Error, failed to downcast!")
+    }
+
+    /// Borrow an overlay component mutably
+    pub fn borrow_overlay_component_mut(&mut self, id: usize) -> &mut Box<dyn EventGUIComponent>{
+        &mut self.overlay_components[id]
+    }
+
+    /// Borrow an overlay component as a type (modifiable)
+    pub fn borrow_overlay_component_as_type_mut<T: EventGUIComponent + 'static>(&mut self, id: usize) -> Result<&mut T, &'static str>{
+        let comp = self.overlay_components.get_mut(id).ok_or("Error, no overlay component with that ID!")?;
+        comp.as_any_mut().downcast_mut::<T>().ok_or("Error, failed to downcast!")
+    }
 }
\ No newline at end of file
@@ -1,4 +1,14 @@
+#[macro_use]
+pub mod profiling;
 pub mod rendering;
 pub mod gui;
 pub mod components;
-pub mod layout;
\ No newline at end of file
+pub mod layout;
+pub mod theme;
+pub mod hud;
+pub mod input;
+pub mod i18n;
+pub mod accessibility;
+mod fatal;
+
+pub use fatal::{catch_fatal_panic, install_panic_hook, panic_message, show_fatal_error};
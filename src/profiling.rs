@@ -0,0 +1,24 @@
+//! Profiling scopes for the expensive parts of a frame - layout, event dispatch, queuing glyphs and submitting
+//! to the GPU. They're sent to puffin with the `puffin` feature, and to Tracy with the `tracy-client` feature,
+//! and compile to nothing otherwise.
+//!
+//! With puffin, scopes only get recorded once `puffin::set_scopes_on(true)` has been called.
+
+// Time the rest of the enclosing block
+macro_rules! profile_scope{
+    ($name:expr) => {
+        #[cfg(feature = "puffin")]
+        puffin::profile_scope!($name);
+        #[cfg(feature = "tracy-client")]
+        let _tracy_span = tracy_client::Span::new($name, $name, file!(), line!(), 0);
+    };
+}
+
+/// Mark the end of a frame. The GUI calls this after every frame it draws, so only call it yourself if you're
+/// drawing with the renderer directly.
+pub fn finish_frame(){
+    #[cfg(feature = "puffin")]
+    puffin::GlobalProfiler::lock().new_frame();
+    #[cfg(feature = "tracy-client")]
+    tracy_client::finish_continuous_frame!();
+}
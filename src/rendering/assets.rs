@@ -0,0 +1,295 @@
+//! This module loads textures and fonts in the background. Loading an asset hands back a handle
+//! straight away, and the file gets read and decoded on another thread. Until it's ready, textures
+//! draw as a placeholder and fonts fall back to the default font.
+//!
+//! Loading the same path twice gives back the same handle. In debug builds, files that change on
+//! disk get reloaded automatically, which makes tweaking a theme much quicker.
+//!
+//! Files are read from the working directory by default. Use `set_source` to read them from
+//! somewhere else, like assets embedded in the binary.
+
+use std::{collections::HashMap, path::{Path, PathBuf}, sync::{Arc, mpsc::{channel, Receiver, Sender}}, time::SystemTime};
+#[cfg(debug_assertions)]
+use std::time::{Duration, Instant};
+
+use wgpu_glyph::FontId;
+
+use super::{AssetSource, FileSource, TextBrush, Texture, TextureData};
+
+/// A handle to a texture loaded through `Assets`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct TextureHandle(usize);
+
+/// A handle to a font loaded through `Assets`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct FontHandle(usize);
+
+/// Where an asset is in its loading
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum AssetState{
+    /// Not loaded yet, or unloaded to stay under a budget (see `TextureStreamer`)
+    Unloaded,
+    Loading,
+    Ready,
+    Failed(&'static str),
+}
+
+struct TextureAsset{
+    path: PathBuf,
+    texture: Option<Texture>,
+    state: AssetState,
+    modified: Option<SystemTime>,
+    in_flight: bool,
+}
+
+struct FontAsset{
+    path: PathBuf,
+    font: Option<FontId>,
+    state: AssetState,
+    modified: Option<SystemTime>,
+    in_flight: bool,
+}
+
+// What the loading threads send back. Decoding happens on the thread, uploading happens on the main thread.
+enum Loaded{
+    Texture(usize, Result<TextureData, &'static str>, Option<SystemTime>),
+    Font(usize, Result<Vec<u8>, &'static str>, Option<SystemTime>),
+}
+
+/// # Assets
+///
+/// Loads and owns textures and fonts. The renderer keeps one of these, and finishes off loaded
+/// assets every frame (see `Renderer::update_assets`).
+pub struct Assets{
+    textures: Vec<TextureAsset>,
+    fonts: Vec<FontAsset>,
+    texture_paths: HashMap<PathBuf, TextureHandle>,
+    font_paths: HashMap<PathBuf, FontHandle>,
+    source: Arc<dyn AssetSource>,
+
+    sender: Sender<Loaded>,
+    receiver: Receiver<Loaded>,
+    placeholder: Texture,
+
+    #[cfg(debug_assertions)]
+    last_reload_check: Instant,
+}
+
+impl Assets{
+    /// Create an empty asset store, along with the placeholder texture
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Self{
+        let (sender, receiver) = channel();
+        let placeholder = create_placeholder(device, queue);
+
+        Self{
+            textures: Vec::new(),
+            fonts: Vec::new(),
+            texture_paths: HashMap::new(),
+            font_paths: HashMap::new(),
+            source: Arc::new(FileSource::new("")),
+
+            sender,
+            receiver,
+            placeholder,
+
+            #[cfg(debug_assertions)]
+            last_reload_check: Instant::now(),
+        }
+    }
+
+    /// Set where assets are read from. Assets that are already loaded are kept.
+    pub fn set_source(&mut self, source: Arc<dyn AssetSource>){
+        self.source = source;
+        // The same path can mean a different file now
+        self.texture_paths.clear();
+        self.font_paths.clear();
+    }
+
+    pub fn get_source(&self) -> Arc<dyn AssetSource>{
+        self.source.clone()
+    }
+
+    /// Start loading a texture in the background. Loading a path that's already loaded (or loading)
+    /// returns the same handle.
+    pub fn load_texture<P: AsRef<Path>>(&mut self, path: P) -> TextureHandle{
+        let path = path.as_ref().to_path_buf();
+        let key = self.source.normalize(&path);
+        if let Some(handle) = self.texture_paths.get(&key){
+            return *handle;
+        }
+
+        let handle = TextureHandle(self.textures.len());
+        self.textures.push(TextureAsset{
+            path: path.clone(),
+            texture: None,
+            state: AssetState::Loading,
+            modified: None,
+            in_flight: true,
+        });
+        self.texture_paths.insert(key, handle);
+        spawn_texture_load(handle.0, path, self.source.clone(), self.sender.clone());
+        handle
+    }
+
+    /// Start loading a font in the background. Loading a path that's already loaded (or loading)
+    /// returns the same handle.
+    pub fn load_font<P: AsRef<Path>>(&mut self, path: P) -> FontHandle{
+        let path = path.as_ref().to_path_buf();
+        let key = self.source.normalize(&path);
+        if let Some(handle) = self.font_paths.get(&key){
+            return *handle;
+        }
+
+        let handle = FontHandle(self.fonts.len());
+        self.fonts.push(FontAsset{
+            path: path.clone(),
+            font: None,
+            state: AssetState::Loading,
+            modified: None,
+            in_flight: true,
+        });
+        self.font_paths.insert(key, handle);
+        spawn_font_load(handle.0, path, self.source.clone(), self.sender.clone());
+        handle
+    }
+
+    /// Get a texture. While it's loading (or if it failed to load) this is the placeholder texture.
+    pub fn get_texture(&self, handle: TextureHandle) -> &Texture{
+        self.textures[handle.0].texture.as_ref().unwrap_or(&self.placeholder)
+    }
+
+    /// Get the ID of a font. While it's loading (or if it failed to load) this is the default font, `FontId(0)`.
+    pub fn get_font(&self, handle: FontHandle) -> FontId{
+        self.fonts[handle.0].font.unwrap_or_default()
+    }
+
+    pub fn get_texture_state(&self, handle: TextureHandle) -> AssetState{
+        self.textures[handle.0].state
+    }
+
+    pub fn get_font_state(&self, handle: FontHandle) -> AssetState{
+        self.fonts[handle.0].state
+    }
+
+    /// The placeholder drawn for textures that aren't ready yet
+    pub fn get_placeholder(&self) -> &Texture{
+        &self.placeholder
+    }
+
+    /// True while any asset is still being read from disk
+    pub fn is_loading(&self) -> bool{
+        self.textures.iter().any(|asset| asset.in_flight) || self.fonts.iter().any(|asset| asset.in_flight)
+    }
+
+    /// Load every texture again on a new device, after the old one was lost. They draw as the placeholder until
+    /// they're ready, like the first time. Fonts aren't on the GPU, so they stay as they are.
+    pub(crate) fn reload(&mut self, device: &wgpu::Device, queue: &wgpu::Queue){
+        self.placeholder = create_placeholder(device, queue);
+        for (index, asset) in self.textures.iter_mut().enumerate(){
+            asset.texture = None;
+            // Loads that are already going get uploaded to the new device when they finish
+            if !asset.in_flight{
+                asset.state = AssetState::Loading;
+                asset.in_flight = true;
+                spawn_texture_load(index, asset.path.clone(), self.source.clone(), self.sender.clone());
+            }
+        }
+    }
+
+    /// Upload everything that finished loading since the last call, and (in debug builds) start
+    /// reloading files that changed. Returns true if any asset changed.
+    pub(crate) fn update(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, text_brush: &mut TextBrush) -> bool{
+        let mut changed = false;
+
+        while let Ok(loaded) = self.receiver.try_recv(){
+            changed = true;
+            match loaded{
+                Loaded::Texture(index, result, modified) => {
+                    let asset = &mut self.textures[index];
+                    asset.in_flight = false;
+                    asset.modified = modified;
+                    let label = asset.path.to_string_lossy();
+                    match result.and_then(|data| Texture::from_data(&data, &label, device, queue)){
+                        Ok(texture) => {
+                            asset.texture = Some(texture);
+                            asset.state = AssetState::Ready;
+                        }
+                        Err(e) => asset.state = AssetState::Failed(e),
+                    }
+                }
+                Loaded::Font(index, result, modified) => {
+                    let asset = &mut self.fonts[index];
+                    asset.in_flight = false;
+                    asset.modified = modified;
+                    match result.and_then(|data| wgpu_glyph::ab_glyph::FontArc::try_from_vec(data).map_err(|_| "Error, failed to load font!")){
+                        Ok(font) => {
+                            // Fonts can't be replaced, so a reloaded font gets a new ID
+                            asset.font = Some(text_brush.add_font(font));
+                            asset.state = AssetState::Ready;
+                        }
+                        Err(e) => asset.state = AssetState::Failed(e),
+                    }
+                }
+            }
+        }
+
+        #[cfg(debug_assertions)]
+        self.check_for_changes();
+
+        changed
+    }
+
+    // Reload any files that changed on disk. Only checked about once a second, as it touches the file system.
+    // Sources that can't tell when a file changed (like embedded ones) never reload.
+    #[cfg(debug_assertions)]
+    fn check_for_changes(&mut self){
+        if self.last_reload_check.elapsed() < Duration::from_secs(1){
+            return;
+        }
+        self.last_reload_check = Instant::now();
+
+        for (index, asset) in self.textures.iter_mut().enumerate(){
+            if !asset.in_flight && asset.modified.is_some() && self.source.modified(&asset.path) > asset.modified{
+                // Keep drawing the old texture until the new one is ready
+                asset.in_flight = true;
+                spawn_texture_load(index, asset.path.clone(), self.source.clone(), self.sender.clone());
+            }
+        }
+        for (index, asset) in self.fonts.iter_mut().enumerate(){
+            if !asset.in_flight && asset.modified.is_some() && self.source.modified(&asset.path) > asset.modified{
+                asset.in_flight = true;
+                spawn_font_load(index, asset.path.clone(), self.source.clone(), self.sender.clone());
+            }
+        }
+    }
+}
+
+// A small grey checkerboard, so missing textures are easy to spot
+pub(crate) fn create_placeholder(device: &wgpu::Device, queue: &wgpu::Queue) -> Texture{
+    Texture::from_rgba(&[
+        160, 160, 160, 255, 96, 96, 96, 255,
+        96, 96, 96, 255, 160, 160, 160, 255,
+    ], (2, 2), "Placeholder texture", device, queue)
+}
+
+// Read and decode a texture file. This is slow, so it's done on a loading thread.
+pub(crate) fn decode_texture(source: &dyn AssetSource, path: &Path) -> Result<TextureData, &'static str>{
+    TextureData::from_bytes(&source.read(path)?)
+}
+
+fn spawn_texture_load(index: usize, path: PathBuf, source: Arc<dyn AssetSource>, sender: Sender<Loaded>){
+    std::thread::spawn(move || {
+        let modified = source.modified(&path);
+        let result = decode_texture(source.as_ref(), &path);
+        // The receiver only goes away with the renderer, at which point nobody cares about the result
+        let _ = sender.send(Loaded::Texture(index, result, modified));
+    });
+}
+
+fn spawn_font_load(index: usize, path: PathBuf, source: Arc<dyn AssetSource>, sender: Sender<Loaded>){
+    std::thread::spawn(move || {
+        let modified = source.modified(&path);
+        let result = source.read(&path);
+        let _ = sender.send(Loaded::Font(index, result, modified));
+    });
+}
@@ -0,0 +1,184 @@
+//! This module lets the user choose how the renderer talks to the GPU - which graphics API (backend)
+//! to use, which GPU (adapter) to pick, and whether to prefer power saving or performance.
+//! This matters on laptops with more than one GPU, and when working around driver issues. It also has the
+//! `GraphicsSettings` that can be changed without making a new renderer.
+
+use super::TextQuality;
+
+/// Which adapter (GPU) the renderer should use
+#[derive(Debug, Clone, PartialEq)]
+pub enum AdapterSelection{
+    /// Let wgpu pick, based on the power preference
+    Auto,
+    /// The adapter at an index in `Renderer::list_adapters`
+    Index(usize),
+    /// The first adapter with a name containing this text (ignoring case), like "nvidia" or "intel"
+    Name(String),
+}
+
+/// # RendererConfig
+///
+/// Settings used when creating a renderer with `Renderer::with_config` (or `with_config_blocking`). The defaults match `Renderer::new` -
+/// the best backend for the platform, and the low power GPU.
+#[derive(Debug, Clone)]
+pub struct RendererConfig{
+    pub backends: wgpu::BackendBit,
+    pub power_preference: wgpu::PowerPreference,
+    pub adapter: AdapterSelection,
+}
+
+impl Default for RendererConfig{
+    fn default() -> RendererConfig{
+        Self{
+            backends: wgpu::BackendBit::PRIMARY,
+            power_preference: wgpu::PowerPreference::LowPower,
+            adapter: AdapterSelection::Auto,
+        }
+    }
+}
+
+impl RendererConfig{
+    /// Create a new config with default values
+    pub fn new() -> Self{
+        Self::default()
+    }
+
+    /// Set which backends can be used, for example `wgpu::BackendBit::VULKAN` or `wgpu::BackendBit::DX12`
+    pub fn set_backends(&mut self, backends: wgpu::BackendBit) -> &mut Self{
+        self.backends = backends;
+        self
+    }
+
+    /// Set the backends from a name ("vulkan", "dx12", "dx11", "metal", "gl" or "primary").
+    /// Handy for picking the backend from an environment variable or command line argument.
+    pub fn set_backend_name(&mut self, name: &str) -> Result<&mut Self, &'static str>{
+        self.backends = backends_from_name(name).ok_or("Error, unknown backend name!")?;
+        Ok(self)
+    }
+
+    /// Prefer the low power (integrated) or high performance (discrete) GPU
+    pub fn set_power_preference(&mut self, power_preference: wgpu::PowerPreference) -> &mut Self{
+        self.power_preference = power_preference;
+        self
+    }
+
+    /// Choose a specific adapter
+    pub fn set_adapter(&mut self, adapter: AdapterSelection) -> &mut Self{
+        self.adapter = adapter;
+        self
+    }
+}
+
+/// # GraphicsSettings
+///
+/// Settings that can be changed while the app is running, with `Renderer::reconfigure` - the kind that go on a
+/// graphics options screen. Unlike a `RendererConfig`, changing these keeps the window, device and layouts.
+/// Get the current ones with `Renderer::get_graphics_settings`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GraphicsSettings{
+    /// Samples per pixel for multisample anti-aliasing (MSAA) of shapes - 1 (off) or 4. Text is smoothed on its own,
+    /// either way. Every GPU can do 4, but some can't do 2 or 8, and wgpu can't tell us which, so they aren't allowed.
+    pub msaa_samples: u32,
+    pub present_mode: wgpu::PresentMode,
+    /// Scales every layout, on top of their own content scale (see `Layout::set_content_scale`)
+    pub ui_scale: f32,
+    pub text_quality: TextQuality,
+}
+
+impl Default for GraphicsSettings{
+    fn default() -> GraphicsSettings{
+        Self{
+            msaa_samples: 1,
+            present_mode: wgpu::PresentMode::Fifo,
+            ui_scale: 1.0,
+            text_quality: TextQuality::default(),
+        }
+    }
+}
+
+impl GraphicsSettings{
+    /// Create new settings with default values - no MSAA, vsync on and no UI scale
+    pub fn new() -> Self{
+        Self::default()
+    }
+
+    /// Set how many samples per pixel MSAA takes (1 or 4)
+    pub fn set_msaa_samples(&mut self, samples: u32) -> &mut Self{
+        self.msaa_samples = samples;
+        self
+    }
+
+    /// Set how frames are shown. `Fifo` is vsync, `Mailbox` shows frames as soon as they're ready.
+    pub fn set_present_mode(&mut self, present_mode: wgpu::PresentMode) -> &mut Self{
+        self.present_mode = present_mode;
+        self
+    }
+
+    /// Set how much bigger (or smaller) to draw every layout
+    pub fn set_ui_scale(&mut self, scale: f32) -> &mut Self{
+        self.ui_scale = scale;
+        self
+    }
+
+    /// Set how text in scaled layouts is drawn (see `TextQuality`)
+    pub fn set_text_quality(&mut self, quality: TextQuality) -> &mut Self{
+        self.text_quality = quality;
+        self
+    }
+
+    /// Check the settings can be used, which `Renderer::reconfigure` does before changing anything
+    pub fn validate(&self) -> Result<(), &'static str>{
+        // Other counts would fail when the pipelines are made, on GPUs that can't do them
+        if ![1, 4].contains(&self.msaa_samples){
+            return Err("Error, MSAA takes 1 or 4 samples!");
+        }
+        if !(self.ui_scale > 0.0 && self.ui_scale.is_finite()){
+            return Err("Error, the UI scale has to be above 0!");
+        }
+        Ok(())
+    }
+}
+
+/// Turn a backend name into the matching backend bits
+pub fn backends_from_name(name: &str) -> Option<wgpu::BackendBit>{
+    match name.trim().to_ascii_lowercase().as_str(){
+        "vulkan" | "vk" => Some(wgpu::BackendBit::VULKAN),
+        "dx12" | "d3d12" => Some(wgpu::BackendBit::DX12),
+        "dx11" | "d3d11" => Some(wgpu::BackendBit::DX11),
+        "metal" | "mtl" => Some(wgpu::BackendBit::METAL),
+        "gl" | "opengl" => Some(wgpu::BackendBit::GL),
+        "primary" => Some(wgpu::BackendBit::PRIMARY),
+        "secondary" => Some(wgpu::BackendBit::SECONDARY),
+        "all" => Some(wgpu::BackendBit::all()),
+        _ => None,
+    }
+}
+
+/// List the adapters available on some backends, in the order `AdapterSelection::Index` uses
+pub fn list_adapters(backends: wgpu::BackendBit) -> Vec<wgpu::AdapterInfo>{
+    let instance = wgpu::Instance::new(backends);
+    instance.enumerate_adapters(backends).map(|adapter| adapter.get_info()).collect()
+}
+
+// Pick an adapter following the config
+pub(crate) async fn select_adapter(instance: &wgpu::Instance, surface: &wgpu::Surface, config: &RendererConfig) -> Result<wgpu::Adapter, &'static str>{
+    match &config.adapter{
+        AdapterSelection::Auto => {
+            instance.request_adapter(
+                &wgpu::RequestAdapterOptions {
+                    power_preference: config.power_preference,
+                    compatible_surface: Some(surface),
+                },
+            ).await.ok_or("Error, no suitable GPU adapter found!")
+        }
+        AdapterSelection::Index(index) => {
+            instance.enumerate_adapters(config.backends).nth(*index).ok_or("Error, no GPU adapter at that index!")
+        }
+        AdapterSelection::Name(name) => {
+            let name = name.to_lowercase();
+            instance.enumerate_adapters(config.backends)
+                .find(|adapter| adapter.get_info().name.to_lowercase().contains(&name))
+                .ok_or("Error, no GPU adapter with that name!")
+        }
+    }
+}
@@ -0,0 +1,89 @@
+//! This module contains `CursorControl`, which keeps track of whether the cursor should be grabbed and visible.
+//! The GUI uses it to give the cursor back while the window doesn't have focus or a modal layout is open,
+//! and to put things back the way they were afterwards.
+
+use std::{cell::RefCell, rc::Rc};
+
+/// How the cursor is kept in the window
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CursorGrab{
+    /// The cursor can leave the window
+    None,
+    /// The cursor is kept inside the window
+    Confined,
+}
+
+struct CursorState{
+    grab: CursorGrab,
+    visible: bool,
+    released: bool, // given back to the user for now, like while a modal is open
+    applied: Option<(bool, bool)>, // the grab and visibility last set on the window
+}
+
+/// # CursorControl
+///
+/// A handle for changing the cursor while the GUI is running. Get one with `Window::get_cursor_control`, and keep
+/// it in your event handler or components. Changes are applied once per frame by the GUI.
+///
+/// Whatever it's set to, the cursor is shown and let go while the window doesn't have focus, or while a stacked layout
+/// that blocks the mouse is open (see `Renderer::push_layout`), and put back once that's over.
+#[derive(Clone)]
+pub struct CursorControl{
+    state: Rc<RefCell<CursorState>>,
+}
+
+impl CursorControl{
+    pub(crate) fn new() -> Self{
+        Self{
+            state: Rc::new(RefCell::new(CursorState{
+                grab: CursorGrab::None,
+                visible: true,
+                released: false,
+                applied: None,
+            })),
+        }
+    }
+
+    pub fn set_grab(&self, grab: CursorGrab){
+        self.state.borrow_mut().grab = grab;
+    }
+
+    pub fn get_grab(&self) -> CursorGrab{
+        self.state.borrow().grab
+    }
+
+    pub fn set_visible(&self, visible: bool){
+        self.state.borrow_mut().visible = visible;
+    }
+
+    pub fn is_visible(&self) -> bool{
+        self.state.borrow().visible
+    }
+
+    /// True while the cursor has been given back because the window lost focus or a modal is open
+    pub fn is_released(&self) -> bool{
+        self.state.borrow().released
+    }
+
+    pub(crate) fn set_released(&self, released: bool){
+        self.state.borrow_mut().released = released;
+    }
+
+    /// Set the cursor on the window, if anything changed since last time
+    pub(crate) fn apply(&self, window: &winit::window::Window) -> Result<(), &'static str>{
+        let mut state = self.state.borrow_mut();
+        let wanted = if state.released{
+            (false, true)
+        }else{
+            (state.grab == CursorGrab::Confined, state.visible)
+        };
+        if state.applied == Some(wanted){
+            return Ok(());
+        }
+
+        window.set_cursor_visible(wanted.1);
+        // Remember what we set even if grabbing fails, so we don't keep trying every frame
+        state.applied = Some(wanted);
+        window.set_cursor_grab(wanted.0).map_err(|_| "Error, failed to grab the cursor!")
+    }
+}
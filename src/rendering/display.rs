@@ -0,0 +1,152 @@
+//! This module contains the `DisplayModeSelector`, which lists the resolutions and refresh rates of a monitor
+//! for a settings menu, and switches the window (and the swapchain) over to the one picked.
+
+use winit::{dpi::{LogicalSize, PhysicalSize}, monitor::{MonitorHandle, VideoMode}, window::Fullscreen};
+
+use super::Renderer;
+
+// A resolution, and its video modes sorted from the fastest refresh rate down
+struct Resolution{
+    size: (u32, u32),
+    modes: Vec<VideoMode>,
+}
+
+/// # DisplayModeSelector
+///
+/// The fullscreen resolutions and refresh rates a monitor supports, ready to put in a pair of dropdowns.
+/// Use `get_resolution_labels` and `get_refresh_rate_labels` for the options, `select_resolution` and
+/// `select_refresh_rate` when the player picks one, then `apply` to switch to it.
+///
+/// Resolutions are in physical pixels. Monitors can have different scale factors, so `get_logical_size` tells you
+/// how big the selected resolution is in logical pixels (how much room the UI gets) on this monitor.
+pub struct DisplayModeSelector{
+    monitor: MonitorHandle,
+    resolutions: Vec<Resolution>,
+    resolution: usize,
+    refresh_rate: usize,
+}
+
+impl DisplayModeSelector{
+    /// List the modes of the monitor the window is on, with the window's current size selected if the monitor
+    /// supports it (otherwise the largest resolution)
+    pub fn new(window: &winit::window::Window) -> Result<Self, &'static str>{
+        let monitor = match window.current_monitor(){
+            Some(monitor) => monitor,
+            None => return Err("Error, couldn't find the monitor the window is on!"),
+        };
+        let mut selector = Self::with_monitor(monitor)?;
+
+        let size = window.inner_size();
+        if let Some(index) = selector.resolutions.iter().position(|resolution| resolution.size == (size.width, size.height)){
+            selector.select_resolution(index);
+        }
+        Ok(selector)
+    }
+
+    /// List the modes of a monitor (from `Window::available_monitors`, for example), with the largest resolution selected
+    pub fn with_monitor(monitor: MonitorHandle) -> Result<Self, &'static str>{
+        let mut resolutions: Vec<Resolution> = Vec::new();
+        for mode in monitor.video_modes(){
+            let size = (mode.size().width, mode.size().height);
+            match resolutions.iter_mut().find(|resolution| resolution.size == size){
+                Some(resolution) => resolution.modes.push(mode),
+                None => resolutions.push(Resolution{ size, modes: vec![mode] }),
+            }
+        }
+        if resolutions.is_empty(){
+            return Err("Error, the monitor doesn't have any video modes!");
+        }
+
+        // Biggest resolutions first, and the fastest refresh rate (at the best bit depth) first within each
+        resolutions.sort_by_key(|resolution| std::cmp::Reverse((resolution.size.0 * resolution.size.1, resolution.size.0)));
+        for resolution in resolutions.iter_mut(){
+            resolution.modes.sort_by_key(|mode| std::cmp::Reverse((mode.refresh_rate(), mode.bit_depth())));
+            resolution.modes.dedup_by_key(|mode| mode.refresh_rate());
+        }
+
+        Ok(Self{
+            monitor,
+            resolutions,
+            resolution: 0,
+            refresh_rate: 0,
+        })
+    }
+
+    /// The resolutions, like "1920 x 1080", biggest first
+    pub fn get_resolution_labels(&self) -> Vec<String>{
+        self.resolutions.iter().map(|resolution| format!("{} x {}", resolution.size.0, resolution.size.1)).collect()
+    }
+
+    /// The refresh rates of the selected resolution, like "144 Hz", fastest first
+    pub fn get_refresh_rate_labels(&self) -> Vec<String>{
+        self.resolutions[self.resolution].modes.iter().map(|mode| format!("{} Hz", mode.refresh_rate())).collect()
+    }
+
+    /// Select a resolution by its index in `get_resolution_labels`. The refresh rate closest to the
+    /// one selected before is kept.
+    pub fn select_resolution(&mut self, index: usize){
+        if index >= self.resolutions.len(){
+            return;
+        }
+        let refresh_rate = self.get_refresh_rate();
+        self.resolution = index;
+        self.refresh_rate = self.resolutions[index].modes.iter()
+            .enumerate()
+            .min_by_key(|(_, mode)| (mode.refresh_rate() as i32 - refresh_rate as i32).abs())
+            .map(|(index, _)| index)
+            .unwrap_or(0);
+    }
+
+    /// Select a refresh rate by its index in `get_refresh_rate_labels`
+    pub fn select_refresh_rate(&mut self, index: usize){
+        if index < self.resolutions[self.resolution].modes.len(){
+            self.refresh_rate = index;
+        }
+    }
+
+    pub fn get_selected_resolution(&self) -> usize{
+        self.resolution
+    }
+
+    pub fn get_selected_refresh_rate(&self) -> usize{
+        self.refresh_rate
+    }
+
+    /// The selected resolution in physical pixels
+    pub fn get_resolution(&self) -> PhysicalSize<u32>{
+        let size = self.resolutions[self.resolution].size;
+        PhysicalSize::new(size.0, size.1)
+    }
+
+    /// The selected refresh rate in Hz
+    pub fn get_refresh_rate(&self) -> u16{
+        self.resolutions[self.resolution].modes[self.refresh_rate].refresh_rate()
+    }
+
+    /// The monitor's scale factor, from its DPI
+    pub fn get_scale_factor(&self) -> f64{
+        self.monitor.scale_factor()
+    }
+
+    /// The selected resolution in logical pixels on this monitor
+    pub fn get_logical_size(&self) -> LogicalSize<f64>{
+        self.get_resolution().to_logical(self.get_scale_factor())
+    }
+
+    /// The video mode that's selected
+    pub fn get_video_mode(&self) -> VideoMode{
+        self.resolutions[self.resolution].modes[self.refresh_rate].clone()
+    }
+
+    /// Switch the window to the selected mode in exclusive fullscreen, and recreate the swapchain to match
+    pub fn apply(&self, window: &winit::window::Window, renderer: &mut Renderer){
+        window.set_fullscreen(Some(Fullscreen::Exclusive(self.get_video_mode())));
+        renderer.resize(self.get_resolution());
+    }
+
+    /// Switch the window to borderless fullscreen on this monitor, which always uses the monitor's own resolution
+    pub fn apply_borderless(&self, window: &winit::window::Window, renderer: &mut Renderer){
+        window.set_fullscreen(Some(Fullscreen::Borderless(Some(self.monitor.clone()))));
+        renderer.resize(self.monitor.size());
+    }
+}
@@ -0,0 +1,460 @@
+//! This module contains the pipeline text gets drawn with - the texture glyphs are cached in, and the instances
+//! (one quad per glyph) the glyph brush lays out into it. Drawing text ourselves, rather than through
+//! `wgpu_glyph`, lets the glyph shader bend coverage with a gamma curve (see `TextRasterOptions::gamma`).
+
+use std::num::NonZeroU64;
+
+use glyph_brush::{GlyphVertex, Rectangle, ab_glyph::Rect};
+use wgpu::util::{DeviceExt, StagingBelt};
+
+// The biggest the glyph cache grows to (in both directions) when a frame has more glyphs than fit
+const MAX_CACHE_SIZE: u32 = 8192;
+
+/// One glyph to draw - a quad in pixels, where its coverage is in the cache texture, and its colour
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub(crate) struct GlyphInstance{
+    left_top: [f32; 3], // and the depth
+    right_bottom: [f32; 2],
+    tex_left_top: [f32; 2],
+    tex_right_bottom: [f32; 2],
+    color: [f32; 4],
+}
+
+impl GlyphInstance{
+    /// Make the instance for a glyph laid out by the glyph brush
+    pub(crate) fn from_vertex(vertex: GlyphVertex) -> Self{
+        let GlyphVertex{ tex_coords, pixel_coords, bounds, extra } = vertex;
+        Self::new(pixel_coords, tex_coords, bounds, extra.color, extra.z)
+    }
+
+    /// Make the instance for a glyph covering `rect` (in pixels), cutting off whatever's outside `bounds` (and the
+    /// same share of its texture)
+    pub(crate) fn new(mut rect: Rect, mut tex_coords: Rect, bounds: Rect, color: [f32; 4], z: f32) -> Self{
+        if rect.max.x > bounds.max.x{
+            let old_width = rect.width();
+            rect.max.x = bounds.max.x;
+            tex_coords.max.x = tex_coords.min.x + tex_coords.width() * rect.width() / old_width;
+        }
+        if rect.min.x < bounds.min.x{
+            let old_width = rect.width();
+            rect.min.x = bounds.min.x;
+            tex_coords.min.x = tex_coords.max.x - tex_coords.width() * rect.width() / old_width;
+        }
+        if rect.max.y > bounds.max.y{
+            let old_height = rect.height();
+            rect.max.y = bounds.max.y;
+            tex_coords.max.y = tex_coords.min.y + tex_coords.height() * rect.height() / old_height;
+        }
+        if rect.min.y < bounds.min.y{
+            let old_height = rect.height();
+            rect.min.y = bounds.min.y;
+            tex_coords.min.y = tex_coords.max.y - tex_coords.height() * rect.height() / old_height;
+        }
+
+        Self{
+            left_top: [rect.min.x, rect.min.y, z],
+            right_bottom: [rect.max.x, rect.max.y],
+            tex_left_top: [tex_coords.min.x, tex_coords.min.y],
+            tex_right_bottom: [tex_coords.max.x, tex_coords.max.y],
+            color,
+        }
+    }
+
+    fn desc<'a>() -> wgpu::VertexBufferDescriptor<'a>{
+        use std::mem;
+        wgpu::VertexBufferDescriptor {
+            stride: mem::size_of::<GlyphInstance>() as wgpu::BufferAddress,
+            step_mode: wgpu::InputStepMode::Instance,
+            attributes: &[
+                wgpu::VertexAttributeDescriptor {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float3,
+                },
+                wgpu::VertexAttributeDescriptor {
+                    offset: mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float2,
+                },
+                wgpu::VertexAttributeDescriptor {
+                    offset: mem::size_of::<[f32; 5]>() as wgpu::BufferAddress,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Float2,
+                },
+                wgpu::VertexAttributeDescriptor {
+                    offset: mem::size_of::<[f32; 7]>() as wgpu::BufferAddress,
+                    shader_location: 3,
+                    format: wgpu::VertexFormat::Float2,
+                },
+                wgpu::VertexAttributeDescriptor {
+                    offset: mem::size_of::<[f32; 9]>() as wgpu::BufferAddress,
+                    shader_location: 4,
+                    format: wgpu::VertexFormat::Float4,
+                },
+            ]
+        }
+    }
+}
+
+// What the glyph shaders need for every draw, laid out like the uniform block in them
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct Globals{
+    transform: [f32; 16],
+    gamma: f32,
+    _padding: [f32; 3],
+}
+
+/// # GlyphPipeline
+///
+/// The texture glyphs are cached in, and the pipeline drawing them from it. The glyph brush (or the SDF atlas) says
+/// which parts of the cache to update and where the glyphs go, and this draws them with the transform and gamma of
+/// each draw. The fragment shader decides what the cache holds - coverage, or distances to the edges of glyphs.
+pub(crate) struct GlyphPipeline{
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    bind_group: wgpu::BindGroup,
+    globals: wgpu::Buffer,
+    sampler: wgpu::Sampler,
+    cache: wgpu::Texture,
+    cache_size: (u32, u32),
+    instances: wgpu::Buffer,
+    capacity: usize, // how many instances fit in the buffer
+    count: usize, // how many of them are drawn
+}
+
+impl GlyphPipeline{
+    pub(crate) fn new(device: &wgpu::Device, cache_size: (u32, u32), fragment_shader: wgpu::ShaderModuleSource, label: &str) -> Self{
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStage::VERTEX | wgpu::ShaderStage::FRAGMENT,
+                    ty: wgpu::BindingType::UniformBuffer {
+                        dynamic: false,
+                        min_binding_size: NonZeroU64::new(std::mem::size_of::<Globals>() as u64),
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStage::FRAGMENT,
+                    ty: wgpu::BindingType::SampledTexture {
+                        multisampled: false,
+                        dimension: wgpu::TextureViewDimension::D2,
+                        component_type: wgpu::TextureComponentType::Float,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStage::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler {
+                        comparison: false,
+                    },
+                    count: None,
+                },
+            ],
+            label: Some("Glyph bind group layout"),
+        });
+
+        let globals = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Glyph globals"),
+            size: std::mem::size_of::<Globals>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let sampler = device.create_sampler(
+            &wgpu::SamplerDescriptor {
+                address_mode_u: wgpu::AddressMode::ClampToEdge,
+                address_mode_v: wgpu::AddressMode::ClampToEdge,
+                address_mode_w: wgpu::AddressMode::ClampToEdge,
+                mag_filter: wgpu::FilterMode::Linear,
+                min_filter: wgpu::FilterMode::Linear,
+                mipmap_filter: wgpu::FilterMode::Nearest,
+                ..Default::default()
+            }
+        );
+        let cache = create_cache(device, cache_size, label);
+        let bind_group = create_bind_group(device, &bind_group_layout, &globals, &cache, &sampler);
+
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Glyph pipeline layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let vs_module = device.create_shader_module(wgpu::include_spirv!("../../shaders/glyph.vert.spv"));
+        let fs_module = device.create_shader_module(fragment_shader);
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(label),
+            layout: Some(&layout),
+            vertex_stage: wgpu::ProgrammableStageDescriptor {
+                module: &vs_module,
+                entry_point: "main",
+            },
+            fragment_stage: Some(wgpu::ProgrammableStageDescriptor {
+                module: &fs_module,
+                entry_point: "main",
+            }),
+            // Rotated text can face either way
+            rasterization_state: Some(
+                wgpu::RasterizationStateDescriptor {
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: wgpu::CullMode::None,
+                    ..Default::default()
+                }
+            ),
+            color_states: &[
+                wgpu::ColorStateDescriptor {
+                    format: wgpu::TextureFormat::Bgra8UnormSrgb,
+                    color_blend: wgpu::BlendDescriptor {
+                        src_factor: wgpu::BlendFactor::SrcAlpha,
+                        dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                        operation: wgpu::BlendOperation::Add
+                    },
+                    alpha_blend: wgpu::BlendDescriptor {
+                        src_factor: wgpu::BlendFactor::One,
+                        dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                        operation: wgpu::BlendOperation::Add
+                    },
+                    write_mask: wgpu::ColorWrite::ALL
+                }
+            ],
+            primitive_topology: wgpu::PrimitiveTopology::TriangleStrip,
+            depth_stencil_state: None,
+            vertex_state: wgpu::VertexStateDescriptor {
+                index_format: wgpu::IndexFormat::Uint16,
+                vertex_buffers: &[GlyphInstance::desc()],
+            },
+            sample_count: 1,
+            sample_mask: !0,
+            alpha_to_coverage_enabled: false,
+        });
+
+        let capacity = 256;
+        Self{
+            pipeline,
+            bind_group_layout,
+            bind_group,
+            globals,
+            sampler,
+            cache,
+            cache_size,
+            instances: create_instance_buffer(device, capacity),
+            capacity,
+            count: 0,
+        }
+    }
+
+    /// The size of the cache texture, in pixels
+    pub(crate) fn cache_size(&self) -> (u32, u32){
+        self.cache_size
+    }
+
+    /// The size to grow the cache to when the glyph brush suggests a bigger one, or `None` if it can't grow
+    pub(crate) fn grown_cache_size(&self, suggested: (u32, u32)) -> Option<(u32, u32)>{
+        grown_cache_size(self.cache_size, suggested)
+    }
+
+    /// Make a new (empty) cache texture. The glyph brush has to be resized too, so it caches every glyph again.
+    pub(crate) fn resize_cache(&mut self, device: &wgpu::Device, size: (u32, u32)){
+        self.cache = create_cache(device, size, "Glyph cache");
+        self.cache_size = size;
+        self.bind_group = create_bind_group(device, &self.bind_group_layout, &self.globals, &self.cache, &self.sampler);
+    }
+
+    /// Copy glyph data (one byte per pixel) into part of the cache, after whatever's already in the encoder
+    pub(crate) fn update_cache(&mut self, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder, rect: Rectangle<u32>, data: &[u8]){
+        let (width, height) = (rect.width(), rect.height());
+        if width == 0 || height == 0{
+            return;
+        }
+        // Buffer to texture copies need rows padded out to the alignment
+        let padded = padded_row(width);
+        let mut padded_data = vec![0; (padded * height) as usize];
+        for (row, line) in data.chunks(width as usize).enumerate(){
+            let start = row * padded as usize;
+            padded_data[start..start + line.len()].copy_from_slice(line);
+        }
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Glyph cache upload"),
+            contents: &padded_data,
+            usage: wgpu::BufferUsage::COPY_SRC,
+        });
+        encoder.copy_buffer_to_texture(
+            wgpu::BufferCopyView {
+                buffer: &buffer,
+                layout: wgpu::TextureDataLayout {
+                    offset: 0,
+                    bytes_per_row: padded,
+                    rows_per_image: height,
+                },
+            },
+            wgpu::TextureCopyView {
+                texture: &self.cache,
+                mip_level: 0,
+                origin: wgpu::Origin3d{ x: rect.min[0], y: rect.min[1], z: 0 },
+            },
+            wgpu::Extent3d{ width, height, depth: 1 },
+        );
+    }
+
+    /// Replace the glyphs to draw, growing the buffer if they don't fit
+    pub(crate) fn upload(&mut self, device: &wgpu::Device, staging_belt: &mut StagingBelt, encoder: &mut wgpu::CommandEncoder, instances: &[GlyphInstance]){
+        self.count = instances.len();
+        if instances.is_empty(){
+            return;
+        }
+        if instances.len() > self.capacity{
+            self.capacity = instances.len().next_power_of_two();
+            self.instances = create_instance_buffer(device, self.capacity);
+        }
+        let bytes: &[u8] = bytemuck::cast_slice(instances);
+        staging_belt.write_buffer(encoder, &self.instances, 0, NonZeroU64::new(bytes.len() as u64).unwrap(), device).copy_from_slice(bytes);
+    }
+
+    /// Draw the uploaded glyphs into the view, over what's there already
+    pub(crate) fn draw(&mut self, device: &wgpu::Device, staging_belt: &mut StagingBelt, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView, transform: [f32; 16], gamma: f32){
+        if self.count == 0{
+            return;
+        }
+        let globals = Globals{ transform, gamma, _padding: [0.0; 3] };
+        let bytes = bytemuck::bytes_of(&globals);
+        staging_belt.write_buffer(encoder, &self.globals, 0, NonZeroU64::new(bytes.len() as u64).unwrap(), device).copy_from_slice(bytes);
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[
+                wgpu::RenderPassColorAttachmentDescriptor {
+                    attachment: view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: true,
+                    }
+                },
+            ],
+            depth_stencil_attachment: None,
+        });
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.instances.slice(..));
+        render_pass.draw(0..4, 0..self.count as u32);
+    }
+}
+
+fn create_cache(device: &wgpu::Device, size: (u32, u32), label: &str) -> wgpu::Texture{
+    device.create_texture(
+        &wgpu::TextureDescriptor {
+            label: Some(label),
+            size: wgpu::Extent3d{ width: size.0, height: size.1, depth: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::R8Unorm,
+            usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
+        }
+    )
+}
+
+fn create_bind_group(device: &wgpu::Device, layout: &wgpu::BindGroupLayout, globals: &wgpu::Buffer, cache: &wgpu::Texture, sampler: &wgpu::Sampler) -> wgpu::BindGroup{
+    let view = cache.create_view(&wgpu::TextureViewDescriptor::default());
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer(globals.slice(..)),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::TextureView(&view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: wgpu::BindingResource::Sampler(sampler),
+            },
+        ],
+        label: Some("Glyph bind group"),
+    })
+}
+
+fn create_instance_buffer(device: &wgpu::Device, capacity: usize) -> wgpu::Buffer{
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Glyph instances"),
+        size: (capacity * std::mem::size_of::<GlyphInstance>()) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsage::VERTEX | wgpu::BufferUsage::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
+// How many bytes a row of `width` pixels takes up in an upload, padded to the copy alignment
+fn padded_row(width: u32) -> u32{
+    let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    width.div_ceil(align) * align
+}
+
+// The size to grow a cache of `current` pixels to, towards what the glyph brush `suggested` but no bigger than the
+// max, or `None` once it's as big as it gets
+fn grown_cache_size(current: (u32, u32), suggested: (u32, u32)) -> Option<(u32, u32)>{
+    let grown = (suggested.0.min(MAX_CACHE_SIZE), suggested.1.min(MAX_CACHE_SIZE));
+    if grown.0 <= current.0 && grown.1 <= current.1{
+        None
+    }else{
+        Some((grown.0.max(current.0), grown.1.max(current.1)))
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use glyph_brush::ab_glyph::point;
+
+    fn rect(min: [f32; 2], max: [f32; 2]) -> Rect{
+        Rect{ min: point(min[0], min[1]), max: point(max[0], max[1]) }
+    }
+
+    /// Test that glyphs poking out of their section's bounds get cut off, along with their texture
+    #[test]
+    fn from_vertex_test(){
+        let extra = glyph_brush::Extra{ color: [1.0, 0.0, 0.0, 1.0], z: 0.5 };
+        let inside = GlyphInstance::from_vertex(GlyphVertex{
+            tex_coords: rect([0.0, 0.0], [0.5, 0.5]),
+            pixel_coords: rect([10.0, 10.0], [20.0, 20.0]),
+            bounds: rect([0.0, 0.0], [100.0, 100.0]),
+            extra: &extra,
+        });
+        assert_eq!(inside.left_top, [10.0, 10.0, 0.5]);
+        assert_eq!(inside.right_bottom, [20.0, 20.0]);
+        assert_eq!(inside.tex_right_bottom, [0.5, 0.5]);
+        assert_eq!(inside.color, [1.0, 0.0, 0.0, 1.0]);
+
+        // Half of the glyph is past the right edge, and a quarter above the top
+        let clipped = GlyphInstance::from_vertex(GlyphVertex{
+            tex_coords: rect([0.0, 0.0], [0.5, 0.5]),
+            pixel_coords: rect([10.0, 10.0], [20.0, 20.0]),
+            bounds: rect([0.0, 12.5], [15.0, 100.0]),
+            extra: &extra,
+        });
+        assert_eq!(clipped.left_top, [10.0, 12.5, 0.5]);
+        assert_eq!(clipped.right_bottom, [15.0, 20.0]);
+        assert_eq!(clipped.tex_left_top, [0.0, 0.125]);
+        assert_eq!(clipped.tex_right_bottom, [0.25, 0.5]);
+    }
+
+    /// Test that the cache grows as suggested, up to the max, then stops
+    #[test]
+    fn grown_cache_size_test(){
+        assert_eq!(grown_cache_size((256, 256), (512, 512)), Some((512, 512)));
+        assert_eq!(grown_cache_size((4096, 4096), (8192, 16384)), Some((8192, 8192)));
+        assert_eq!(grown_cache_size((8192, 8192), (16384, 16384)), None);
+    }
+
+    /// Test that upload rows are padded to the copy alignment
+    #[test]
+    fn padded_row_test(){
+        assert_eq!(padded_row(1), 256);
+        assert_eq!(padded_row(256), 256);
+        assert_eq!(padded_row(300), 512);
+    }
+}
@@ -0,0 +1,154 @@
+//! This module contains the `Lines` struct, a set of one pixel wide line segments drawn with the renderer's line
+//! pipeline, for things like chart plots and axes that would take a lot of thin quads.
+
+use std::rc::Rc;
+
+use super::{Allocation, MemoryCategory, Pipelines, Renderer, Tint, Transform, render::Vertex};
+
+/// # Lines
+///
+/// Coloured line segments, positioned in pixels like a `Quad` - from the centre of the screen, with y going down.
+///
+/// The vertex buffer grows to fit the segments in `rebuild`, which components should call from their own `rebuild`
+/// (it needs the GPU device), and changes are only sent to the GPU when `prepare` is called.
+pub struct Lines{
+    segments: Vec<[[f32; 2]; 2]>,
+    transform: Transform,
+    tint: Tint,
+    vertex_buffer: Option<wgpu::Buffer>,
+    capacity: usize, // how many segments fit in the vertex buffer
+    pipelines: Pipelines,
+    pipeline: Rc<wgpu::RenderPipeline>,
+    default_pipeline: Rc<wgpu::RenderPipeline>,
+    screen_dim: (u32, u32),
+    dirty: bool,
+    _memory: Option<Allocation>,
+}
+
+impl Lines{
+    /// Create an empty set of lines
+    pub fn new(color: [f32; 4], renderer: &Renderer) -> Self{
+        Self{
+            segments: Vec::new(),
+            // The vertices are worked out in normalized device coordinates, so they don't need moving
+            transform: Transform::new(
+                cgmath::Vector3::<f32>::new(0.0, 0.0, 0.0),
+                cgmath::Quaternion::<f32>::new(1.0, 0.0, 0.0, 0.0),
+                cgmath::Vector3::<f32>::new(1.0, 1.0, 1.0),
+                &renderer.device,
+            ),
+            tint: Tint::new(color, &renderer.device),
+            vertex_buffer: None,
+            capacity: 0,
+            pipelines: renderer.get_pipelines(),
+            pipeline: renderer.get_line_pipeline(),
+            default_pipeline: renderer.get_default_pipeline(),
+            screen_dim: (0, 0),
+            dirty: true,
+            _memory: None,
+        }
+    }
+
+    /// Create an empty set of lines drawn with the same pipelines as these, for components that need more colours
+    /// once the renderer is no longer to hand (like in `rebuild`)
+    pub fn empty_like(&self, color: [f32; 4], device: &wgpu::Device) -> Self{
+        Self{
+            segments: Vec::new(),
+            transform: Transform::new(
+                cgmath::Vector3::<f32>::new(0.0, 0.0, 0.0),
+                cgmath::Quaternion::<f32>::new(1.0, 0.0, 0.0, 0.0),
+                cgmath::Vector3::<f32>::new(1.0, 1.0, 1.0),
+                device,
+            ),
+            tint: Tint::new(color, device),
+            vertex_buffer: None,
+            capacity: 0,
+            pipelines: self.pipelines.clone(),
+            pipeline: self.pipelines.get_line(),
+            default_pipeline: self.pipelines.get_default(),
+            screen_dim: (0, 0),
+            dirty: true,
+            _memory: None,
+        }
+    }
+
+    /// Replace the segments, each going between two points
+    pub fn set_segments(&mut self, segments: &[[[f32; 2]; 2]]){
+        self.segments = segments.to_vec();
+        self.dirty = true;
+    }
+
+    /// Replace the segments with a line joining up some points in order
+    pub fn set_strip(&mut self, points: &[[f32; 2]]){
+        self.segments = points.windows(2).map(|pair| [pair[0], pair[1]]).collect();
+        self.dirty = true;
+    }
+
+    pub fn get_segments(&self) -> &[[[f32; 2]; 2]]{
+        &self.segments
+    }
+
+    pub fn clear(&mut self){
+        self.set_segments(&[]);
+    }
+
+    pub fn set_color(&mut self, color: [f32; 4]){
+        self.tint.set(color);
+    }
+
+    pub fn get_color(&self) -> [f32; 4]{
+        self.tint.color
+    }
+
+    /// Make the vertex buffer bigger if the segments don't fit in it
+    pub fn rebuild(&mut self, device: &wgpu::Device){
+        if self.segments.len() <= self.capacity{
+            return;
+        }
+        self.capacity = self.segments.len().next_power_of_two();
+        let bytes = (self.capacity * 2 * std::mem::size_of::<Vertex>()) as u64;
+        self.vertex_buffer = Some(device.create_buffer(&wgpu::BufferDescriptor{
+            label: Some("Line Vertex Buffer"),
+            size: bytes,
+            usage: wgpu::BufferUsage::VERTEX | wgpu::BufferUsage::COPY_DST,
+            mapped_at_creation: false,
+        }));
+        self._memory = Some(Allocation::new(MemoryCategory::Buffer, bytes));
+        self.dirty = true;
+    }
+
+    /// Write any changes to the GPU. This has to run before rendering.
+    pub fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        // In case the MSAA level changed
+        self.pipeline = self.pipelines.get_line();
+        self.default_pipeline = self.pipelines.get_default();
+        self.tint.write_buffer(queue);
+        if !(self.dirty || self.screen_dim != screen_dim) || screen_dim.0 == 0 || screen_dim.1 == 0{
+            return;
+        }
+        if let Some(buffer) = self.vertex_buffer.as_ref().filter(|_| self.segments.len() <= self.capacity){
+            let half = [screen_dim.0 as f32 / 2.0, screen_dim.1 as f32 / 2.0];
+            let vertices: Vec<Vertex> = self.segments.iter().flatten().map(|point| Vertex{
+                position: [point[0] / half[0], -point[1] / half[1], 0.0],
+                tex_coords: [0.0, 0.0],
+            }).collect();
+            queue.write_buffer(buffer, 0, bytemuck::cast_slice(&vertices));
+            self.screen_dim = screen_dim;
+            self.dirty = false;
+        }
+    }
+
+    /// Draw the lines, going back to the default pipeline afterwards
+    pub fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>) where 'a: 'b{
+        let buffer = match &self.vertex_buffer{
+            Some(buffer) if !self.segments.is_empty() && self.segments.len() <= self.capacity => buffer,
+            _ => return,
+        };
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(1, &self.transform.bind_group, &[]);
+        render_pass.set_bind_group(2, &self.tint.bind_group, &[]);
+        render_pass.set_vertex_buffer(0, buffer.slice(..));
+        render_pass.draw(0..(self.segments.len() * 2) as u32, 0..1);
+        render_pass.set_pipeline(&self.default_pipeline);
+    }
+}
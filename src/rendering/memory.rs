@@ -0,0 +1,197 @@
+//! This module keeps count of the GPU memory the library allocates, for `Renderer::memory_report`, and contains
+//! the `LruBudget` the texture caches use to stay under a size limit. Sizes are worked out from what was asked for,
+//! so the driver may use a bit more for padding and alignment.
+
+use std::{collections::HashMap, hash::Hash, sync::atomic::{AtomicU64, Ordering}};
+
+static TEXTURE_BYTES: AtomicU64 = AtomicU64::new(0);
+static BUFFER_BYTES: AtomicU64 = AtomicU64::new(0);
+
+// What kind of memory an allocation is
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) enum MemoryCategory{
+    Texture,
+    Buffer,
+}
+
+impl MemoryCategory{
+    fn counter(self) -> &'static AtomicU64{
+        match self{
+            MemoryCategory::Texture => &TEXTURE_BYTES,
+            MemoryCategory::Buffer => &BUFFER_BYTES,
+        }
+    }
+}
+
+// Counts some GPU memory for as long as it's alive. Kept next to the texture or buffer it counts.
+#[derive(Debug)]
+pub(crate) struct Allocation{
+    category: MemoryCategory,
+    pub(crate) bytes: u64,
+}
+
+impl Allocation{
+    pub(crate) fn new(category: MemoryCategory, bytes: u64) -> Self{
+        category.counter().fetch_add(bytes, Ordering::Relaxed);
+        Self{
+            category,
+            bytes,
+        }
+    }
+}
+
+impl Allocation{
+    // The vertex buffer of a quad
+    pub(crate) fn quad_buffer() -> Self{
+        Self::new(MemoryCategory::Buffer, std::mem::size_of_val(super::QUAD) as u64)
+    }
+
+    // A uniform buffer holding a `T`
+    pub(crate) fn uniform_buffer<T>() -> Self{
+        Self::new(MemoryCategory::Buffer, std::mem::size_of::<T>() as u64)
+    }
+}
+
+impl Drop for Allocation{
+    fn drop(&mut self){
+        self.category.counter().fetch_sub(self.bytes, Ordering::Relaxed);
+    }
+}
+
+/// # MemoryReport
+///
+/// How much GPU memory the renderer is using, in bytes, split up by what it's used for. See `Renderer::memory_report`.
+///
+/// Textures and buffers are counted for everything the library creates, across every renderer. Buffers made
+/// directly with `UniformUtils` aren't counted.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct MemoryReport{
+    /// Every texture, including the ones in the texture pool and the assets
+    pub textures: u64,
+    /// The part of `textures` in the renderer's `TexturePool`
+    pub texture_pool: u64,
+    /// Vertex and uniform buffers, mostly from quads
+    pub buffers: u64,
+    /// The texture the glyph brush caches drawn glyphs in
+    pub glyph_cache: u64,
+    /// The images the window is drawn into
+    pub swap_chain: u64,
+}
+
+impl MemoryReport{
+    // The textures and buffers counted so far
+    pub(crate) fn counted() -> Self{
+        Self{
+            textures: TEXTURE_BYTES.load(Ordering::Relaxed),
+            buffers: BUFFER_BYTES.load(Ordering::Relaxed),
+            ..Default::default()
+        }
+    }
+
+    /// Everything added up. `texture_pool` is already part of `textures`, so it isn't counted twice.
+    pub fn total(&self) -> u64{
+        self.textures + self.buffers + self.glyph_cache + self.swap_chain
+    }
+}
+
+/// # LruBudget
+///
+/// Keeps track of the size of the things in a cache, and when each was last used, to decide what to throw away
+/// when the cache gets too big. The least recently used things go first.
+///
+/// The budget doesn't own the things it tracks - `evict` hands back the keys to drop, and the cache drops them.
+#[derive(Debug, Clone)]
+pub struct LruBudget<K: Eq + Hash + Clone>{
+    entries: HashMap<K, (u64, u64)>, // size, when it was last used
+    limit: Option<u64>,
+    used: u64,
+    clock: u64,
+}
+
+impl<K: Eq + Hash + Clone> LruBudget<K>{
+    /// Create a budget with no limit
+    pub fn new() -> Self{
+        Self{
+            entries: HashMap::new(),
+            limit: None,
+            used: 0,
+            clock: 0,
+        }
+    }
+
+    /// Set how many bytes the cache can use, or `None` for no limit
+    pub fn set_limit(&mut self, limit: Option<u64>){
+        self.limit = limit;
+    }
+
+    pub fn get_limit(&self) -> Option<u64>{
+        self.limit
+    }
+
+    /// How many bytes the tracked things add up to
+    pub fn get_used(&self) -> u64{
+        self.used
+    }
+
+    /// Start tracking something (or update its size), counting it as just used
+    pub fn insert(&mut self, key: K, bytes: u64){
+        self.clock += 1;
+        if let Some((old, _)) = self.entries.insert(key, (bytes, self.clock)){
+            self.used -= old;
+        }
+        self.used += bytes;
+    }
+
+    /// Mark something as just used
+    pub fn touch(&mut self, key: &K){
+        self.clock += 1;
+        if let Some(entry) = self.entries.get_mut(key){
+            entry.1 = self.clock;
+        }
+    }
+
+    /// Stop tracking something
+    pub fn remove(&mut self, key: &K){
+        if let Some((bytes, _)) = self.entries.remove(key){
+            self.used -= bytes;
+        }
+    }
+
+    pub fn contains(&self, key: &K) -> bool{
+        self.entries.contains_key(key)
+    }
+
+    /// Stop tracking the least recently used things until the rest fit in the limit, and return their keys,
+    /// oldest first. Something bigger than the whole limit is dropped too, once it's the oldest.
+    pub fn evict(&mut self) -> Vec<K>{
+        self.evict_where(|_| true)
+    }
+
+    /// Like `evict`, but only things `can_evict` returns true for get dropped - for example to keep
+    /// what's on screen. This can leave the cache over its limit.
+    pub fn evict_where<F: Fn(&K) -> bool>(&mut self, can_evict: F) -> Vec<K>{
+        let limit = match self.limit{
+            Some(limit) if self.used > limit => limit,
+            _ => return Vec::new(),
+        };
+
+        let mut by_age: Vec<(u64, K)> = self.entries.iter().map(|(key, (_, used))| (*used, key.clone())).collect();
+        by_age.sort_by_key(|(used, _)| *used);
+
+        let mut evicted = Vec::new();
+        for (_, key) in by_age.into_iter().filter(|(_, key)| can_evict(key)){
+            if self.used <= limit{
+                break;
+            }
+            self.remove(&key);
+            evicted.push(key);
+        }
+        evicted
+    }
+}
+
+impl<K: Eq + Hash + Clone> Default for LruBudget<K>{
+    fn default() -> Self{
+        Self::new()
+    }
+}
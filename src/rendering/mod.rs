@@ -1,9 +1,42 @@
 mod window;
 mod render;
+mod config;
 mod transform;
 mod uniform;
+mod tint;
+mod quad;
+mod lines;
+mod texture;
+mod assets;
+mod source;
+mod picker;
+mod cursor;
+mod display;
+mod memory;
+mod streaming;
+mod texture_data;
+mod text;
+mod glyphs;
+mod sdf;
+mod pipelines;
 
 pub use window::{Window, WindowBuilder, ScreenMode};
-pub use render::{Renderer, QUAD};
+pub use render::{Renderer, QUAD, measure_text};
+pub use config::{RendererConfig, AdapterSelection, GraphicsSettings, backends_from_name};
 pub use transform::{Transform, TransformUniform};
-pub use uniform::UniformUtils;
\ No newline at end of file
+pub use uniform::UniformUtils;
+pub use tint::{Tint, TintUniform};
+pub use quad::Quad;
+pub use lines::Lines;
+pub use texture::{Texture, TexturePool, TextureUtils};
+pub use texture_data::TextureData;
+pub use assets::{Assets, AssetState, TextureHandle, FontHandle};
+pub use source::{AssetSource, FileSource, EmbeddedSource};
+pub use picker::PixelPicker;
+pub use cursor::{CursorControl, CursorGrab};
+pub use display::DisplayModeSelector;
+pub use memory::{MemoryReport, LruBudget};
+pub use streaming::{TextureStreamer, StreamHandle};
+pub use text::{TextBrush, TextQuality, TextRasterOptions};
+pub use pipelines::Pipelines;
+pub(crate) use memory::{Allocation, MemoryCategory};
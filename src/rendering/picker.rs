@@ -0,0 +1,39 @@
+//! This module lets components ask the renderer for the colour of a pixel on screen. Components
+//! don't have access to the renderer, so they hold a `PixelPicker` instead, and the renderer
+//! answers the picks once it has drawn the next frame.
+
+use std::{cell::RefCell, rc::Rc};
+
+type PickCallback = Box<dyn FnOnce([f32; 4])>;
+type PickRequest = ([u32; 2], PickCallback);
+
+/// # PixelPicker
+///
+/// A handle for reading back pixels from the rendered frame. Get one with `Renderer::get_pixel_picker`.
+#[derive(Clone)]
+pub struct PixelPicker{
+    requests: Rc<RefCell<Vec<PickRequest>>>,
+}
+
+impl PixelPicker{
+    pub(crate) fn new() -> Self{
+        Self{
+            requests: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    /// Ask for the colour of a pixel (in window space). The callback runs with the colour (RGBA, 0 to 1)
+    /// after the next frame is drawn. Picks outside of the window are dropped.
+    pub fn pick<F: FnOnce([f32; 4]) + 'static>(&self, pos: [u32; 2], callback: F){
+        self.requests.borrow_mut().push((pos, Box::new(callback)));
+    }
+
+    /// True while there are picks waiting for the next frame
+    pub fn is_pending(&self) -> bool{
+        !self.requests.borrow().is_empty()
+    }
+
+    pub(crate) fn take_requests(&self) -> Vec<PickRequest>{
+        std::mem::take(&mut *self.requests.borrow_mut())
+    }
+}
@@ -0,0 +1,134 @@
+//! This module contains `Pipelines`, the render pipelines the renderer draws with, shared with the components that
+//! switch between them. They get made again when `Renderer::reconfigure` changes the MSAA level, and when the
+//! renderer moves to a new device (see `Renderer::recover_device`).
+
+use std::{borrow::Cow, cell::RefCell, collections::HashMap, rc::Rc};
+
+use super::Renderer;
+
+// A fragment shader, kept so its pipeline can be made again
+enum ShaderSource{
+    SpirV(Vec<u32>),
+    Wgsl(String),
+}
+
+impl ShaderSource{
+    fn new(source: wgpu::ShaderModuleSource) -> Self{
+        match source{
+            wgpu::ShaderModuleSource::SpirV(words) => ShaderSource::SpirV(words.into_owned()),
+            wgpu::ShaderModuleSource::Wgsl(text) => ShaderSource::Wgsl(text.into_owned()),
+        }
+    }
+
+    fn get(&self) -> wgpu::ShaderModuleSource<'_>{
+        match self{
+            ShaderSource::SpirV(words) => wgpu::ShaderModuleSource::SpirV(Cow::Borrowed(words)),
+            ShaderSource::Wgsl(text) => wgpu::ShaderModuleSource::Wgsl(Cow::Borrowed(text)),
+        }
+    }
+}
+
+struct PipelineSet{
+    sample_count: u32,
+    default: Rc<wgpu::RenderPipeline>,
+    line: Rc<wgpu::RenderPipeline>,
+    // Draws a layer of stacked layouts onto the window, when there's MSAA
+    composite: Rc<wgpu::RenderPipeline>,
+    named: HashMap<String, (Rc<wgpu::RenderPipeline>, ShaderSource)>,
+}
+
+/// # Pipelines
+///
+/// A handle to the renderer's pipelines, cheap to clone - get one with `Renderer::get_pipelines`.
+///
+/// The MSAA level is part of a pipeline, so changing it (see `Renderer::reconfigure`) replaces all of them.
+/// Components that set pipelines themselves (like `Lines`) keep this handle and fetch the pipelines again in
+/// `prepare`, rather than holding on to the ones from when they were made.
+#[derive(Clone)]
+pub struct Pipelines{
+    set: Rc<RefCell<PipelineSet>>,
+}
+
+impl Pipelines{
+    pub(crate) fn new(device: &wgpu::Device, sample_count: u32) -> Self{
+        let (default, line) = Pipelines::create_defaults(device, sample_count);
+        Self{
+            set: Rc::new(RefCell::new(PipelineSet{
+                sample_count,
+                default,
+                line,
+                composite: Rc::new(Renderer::create_composite_pipeline(device)),
+                named: HashMap::new(),
+            })),
+        }
+    }
+
+    fn create_defaults(device: &wgpu::Device, sample_count: u32) -> (Rc<wgpu::RenderPipeline>, Rc<wgpu::RenderPipeline>){
+        let fs_module = device.create_shader_module(wgpu::include_spirv!("../../shaders/shader.frag.spv"));
+        (
+            Rc::new(Renderer::create_pipeline(device, &fs_module, "Render Pipeline", wgpu::PrimitiveTopology::TriangleList, sample_count)),
+            Rc::new(Renderer::create_pipeline(device, &fs_module, "Line Pipeline", wgpu::PrimitiveTopology::LineList, sample_count)),
+        )
+    }
+
+    /// The pipeline everything is drawn with by default
+    pub fn get_default(&self) -> Rc<wgpu::RenderPipeline>{
+        self.set.borrow().default.clone()
+    }
+
+    /// The pipeline `Lines` are drawn with
+    pub fn get_line(&self) -> Rc<wgpu::RenderPipeline>{
+        self.set.borrow().line.clone()
+    }
+
+    /// A pipeline added with `Renderer::add_pipeline`
+    pub fn get(&self, name: &str) -> Option<Rc<wgpu::RenderPipeline>>{
+        self.set.borrow().named.get(name).map(|(pipeline, _)| pipeline.clone())
+    }
+
+    /// How many samples per pixel the pipelines draw with
+    pub fn get_sample_count(&self) -> u32{
+        self.set.borrow().sample_count
+    }
+
+    pub(crate) fn get_composite(&self) -> Rc<wgpu::RenderPipeline>{
+        self.set.borrow().composite.clone()
+    }
+
+    // Add a pipeline with another fragment shader, or get the one already added under the name
+    pub(crate) fn add(&self, device: &wgpu::Device, name: &str, fragment_shader: wgpu::ShaderModuleSource) -> Rc<wgpu::RenderPipeline>{
+        if let Some(pipeline) = self.get(name){
+            return pipeline;
+        }
+        let source = ShaderSource::new(fragment_shader);
+        let mut set = self.set.borrow_mut();
+        let pipeline = Rc::new(Pipelines::create_named(device, name, &source, set.sample_count));
+        set.named.insert(name.to_string(), (pipeline.clone(), source));
+        pipeline
+    }
+
+    fn create_named(device: &wgpu::Device, name: &str, source: &ShaderSource, sample_count: u32) -> wgpu::RenderPipeline{
+        let fs_module = device.create_shader_module(source.get());
+        Renderer::create_pipeline(device, &fs_module, name, wgpu::PrimitiveTopology::TriangleList, sample_count)
+    }
+
+    // Make every pipeline again for another sample count
+    pub(crate) fn rebuild(&self, device: &wgpu::Device, sample_count: u32){
+        if self.get_sample_count() != sample_count{
+            self.recreate(device, sample_count);
+        }
+    }
+
+    // Make every pipeline again on a new device, after the old one was lost
+    pub(crate) fn recreate(&self, device: &wgpu::Device, sample_count: u32){
+        let mut set = self.set.borrow_mut();
+        let (default, line) = Pipelines::create_defaults(device, sample_count);
+        set.composite = Rc::new(Renderer::create_composite_pipeline(device));
+        set.default = default;
+        set.line = line;
+        for (name, (pipeline, source)) in set.named.iter_mut(){
+            *pipeline = Rc::new(Pipelines::create_named(device, name, source, sample_count));
+        }
+        set.sample_count = sample_count;
+    }
+}
@@ -0,0 +1,178 @@
+//! This module contains the `Quad` struct, a coloured rectangle that most of the
+//! built in components are made out of. It keeps its position and size in pixels,
+//! and converts them into a `Transform` when the frame is prepared.
+
+use std::rc::Rc;
+
+use wgpu::Device;
+
+use super::{Allocation, QUAD, Texture, Tint, Transform, render::Vertex};
+
+/// # Quad
+///
+/// A coloured rectangle, positioned in pixels.
+///
+/// Like buttons, the position is the centre of the quad, measured from the centre of the screen
+/// (with y going down). The size is the full width and height of the quad in pixels.
+///
+/// Changes are only sent to the GPU when `prepare` is called, which components should do from
+/// their own `prepare` function.
+///
+/// A quad can be given a texture with `set_texture`, which is drawn multiplied by the colour - so a white
+/// shape in a texture can be drawn in any colour. `set_uv` draws part of the texture instead of all of it, like one
+/// frame of a spritesheet. `set_rotation` turns it around its centre, though hit testing with `contains` ignores that.
+pub struct Quad{
+    pos: [f32; 2],
+    size: [f32; 2],
+    transform: Transform,
+    tint: Tint,
+    texture: Option<Rc<Texture>>,
+    uv: ([f32; 2], [f32; 2]), // offset and size, in texture coordinates
+    rotation: f32, // radians, clockwise
+    vertex_buffer: wgpu::Buffer,
+    screen_dim: (u32, u32),
+    dirty: bool,
+    uv_dirty: bool,
+    _memory: Allocation,
+}
+
+impl Quad{
+    /// Create a new quad
+    pub fn new(pos: [f32; 2], size: [f32; 2], color: [f32; 4], device: &Device) -> Self{
+        Self{
+            pos,
+            size,
+            transform: Transform::new(
+                cgmath::Vector3::<f32>::new(0.0, 0.0, 0.0),
+                cgmath::Quaternion::<f32>::new(1.0, 0.0, 0.0, 0.0),
+                cgmath::Vector3::<f32>::new(0.0, 0.0, 0.0),
+                device,
+            ),
+            tint: Tint::new(color, device),
+            texture: None,
+            uv: ([0.0, 0.0], [1.0, 1.0]),
+            rotation: 0.0,
+            vertex_buffer: crate::components::base_components::create_buffers(device),
+            screen_dim: (0, 0),
+            dirty: true,
+            uv_dirty: false,
+            _memory: Allocation::quad_buffer(),
+        }
+    }
+
+    pub fn set_pos(&mut self, pos: [f32; 2]){
+        if self.pos != pos{
+            self.pos = pos;
+            self.dirty = true;
+        }
+    }
+
+    pub fn get_pos(&self) -> [f32; 2]{
+        self.pos
+    }
+
+    pub fn set_size(&mut self, size: [f32; 2]){
+        if self.size != size{
+            self.size = size;
+            self.dirty = true;
+        }
+    }
+
+    pub fn get_size(&self) -> [f32; 2]{
+        self.size
+    }
+
+    pub fn set_color(&mut self, color: [f32; 4]){
+        self.tint.set(color);
+    }
+
+    pub fn get_color(&self) -> [f32; 4]{
+        self.tint.color
+    }
+
+    /// Draw a texture over the quad, tinted with its colour, or go back to a plain colour with `None`. The texture
+    /// is shared, so lots of quads can draw the same one.
+    ///
+    /// The texture stays bound after the quad is drawn, so a component drawing plain quads as well as textured
+    /// ones should draw the plain ones first.
+    pub fn set_texture(&mut self, texture: Option<Rc<Texture>>){
+        self.texture = texture;
+    }
+
+    pub fn get_texture(&self) -> Option<&Rc<Texture>>{
+        self.texture.as_ref()
+    }
+
+    /// Draw part of the texture, starting at `offset` and `size` across, in texture coordinates (0 to 1, from the
+    /// top left). The whole texture is an offset of 0 and a size of 1.
+    pub fn set_uv(&mut self, offset: [f32; 2], size: [f32; 2]){
+        if self.uv != (offset, size){
+            self.uv = (offset, size);
+            self.uv_dirty = true;
+        }
+    }
+
+    pub fn get_uv(&self) -> ([f32; 2], [f32; 2]){
+        self.uv
+    }
+
+    /// Turn the quad clockwise around its centre, by an angle in radians
+    pub fn set_rotation(&mut self, rotation: f32){
+        if self.rotation != rotation{
+            self.rotation = rotation;
+            self.dirty = true;
+        }
+    }
+
+    pub fn get_rotation(&self) -> f32{
+        self.rotation
+    }
+
+    /// Check if a point (in window space, like a cursor position) is inside the quad
+    pub fn contains(&self, point: [f64; 2], screen_dim: (u32, u32)) -> bool{
+        let x = point[0] as f32 - (screen_dim.0 / 2) as f32;
+        let y = point[1] as f32 - (screen_dim.1 / 2) as f32;
+
+        (x - self.pos[0]).abs() <= self.size[0] / 2.0 && (y - self.pos[1]).abs() <= self.size[1] / 2.0
+    }
+
+    /// Write any changes to the GPU. This has to run before rendering.
+    pub fn prepare(&mut self, queue: &wgpu::Queue, screen_dim: (u32, u32)){
+        if (self.dirty || self.screen_dim != screen_dim) && screen_dim.0 > 0 && screen_dim.1 > 0{
+            let half_width = (screen_dim.0 / 2) as f32;
+            let half_height = (screen_dim.1 / 2) as f32;
+
+            // The quad goes from -1 to 1, so it's scaled by half its size in pixels, rotated, then scaled down to the
+            // screen. Screen space has y going up, so clockwise on screen is a negative angle.
+            self.transform.position = cgmath::Vector3::<f32>::new(self.pos[0] / half_width, -self.pos[1] / half_height, 0.0);
+            self.transform.scale = cgmath::Vector3::<f32>::new(self.size[0] / 2.0, self.size[1] / 2.0, 1.0);
+            self.transform.rotation = cgmath::Quaternion::from(cgmath::Euler::new(cgmath::Rad(0.0), cgmath::Rad(0.0), cgmath::Rad(-self.rotation)));
+            self.transform.screen_scale = cgmath::Vector3::<f32>::new(2.0 / screen_dim.0 as f32, 2.0 / screen_dim.1 as f32, 1.0);
+            self.transform.write_buffer(queue);
+
+            self.screen_dim = screen_dim;
+            self.dirty = false;
+        }
+        if self.uv_dirty{
+            let (offset, size) = self.uv;
+            let vertices: Vec<Vertex> = QUAD.iter().map(|vertex| Vertex{
+                position: vertex.position,
+                tex_coords: [offset[0] + vertex.tex_coords[0] * size[0], offset[1] + vertex.tex_coords[1] * size[1]],
+            }).collect();
+            queue.write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(&vertices));
+            self.uv_dirty = false;
+        }
+        self.tint.write_buffer(queue);
+    }
+
+    /// Draw the quad
+    pub fn render<'a, 'b>(&'a self, render_pass: &'b mut wgpu::RenderPass<'a>) where 'a: 'b{
+        render_pass.set_bind_group(1, &self.transform.bind_group, &[]);
+        render_pass.set_bind_group(2, &self.tint.bind_group, &[]);
+        if let Some(texture) = &self.texture{
+            render_pass.set_bind_group(3, &texture.bind_group, &[]);
+        }
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        render_pass.draw(0..6, 0..1);
+    }
+}
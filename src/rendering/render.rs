@@ -8,11 +8,23 @@
 
 
 
+use std::rc::Rc;
+
 use wgpu::{BindGroup, Device, ShaderStage, util::StagingBelt};
+use futures::executor::block_on;
+use wgpu_glyph::GlyphCruncher;
+
+use crate::{input::InputCategory, layout::{Layout, LayoutCommand}};
 
-use crate::{components::{Label}, layout::{Layout}};
+use super::{Allocation, Assets, GraphicsSettings, MemoryCategory, MemoryReport, Pipelines, PixelPicker, Quad, RendererConfig, TextBrush, TextQuality, TextRasterOptions, Texture, TexturePool, TextureStreamer, Tint, UniformUtils, config::{list_adapters, select_adapter}};
 
-use super::{UniformUtils};
+// How big the glyph cache starts out, unless it's changed with `Renderer::set_glyph_cache_size`
+const DEFAULT_GLYPH_CACHE_SIZE: (u32, u32) = (256, 256);
+// The swapchain is double buffered
+const SWAP_CHAIN_IMAGES: u64 = 2;
+// How many frames in a row the swapchain can be lost, even after recreating it, before the device is taken to be
+// gone. wgpu doesn't tell us when the device is lost, so this is the only sign of it.
+const LOST_FRAME_LIMIT: u32 = 3;
 
 /// # Renderer
 ///
@@ -24,53 +36,57 @@ pub struct Renderer{
     pub queue: wgpu::Queue,
     pub sc_desc: wgpu::SwapChainDescriptor,
     swap_chain: wgpu::SwapChain,
+    lost_frames: u32, // how many frames in a row the swapchain has been lost
+    config: RendererConfig, // to pick an adapter again if the device is lost
     pub size: winit::dpi::PhysicalSize<u32>,
 
-    render_pipeline: wgpu::RenderPipeline,
+    // Shared with the components that switch pipelines, so they can be replaced when the MSAA level changes
+    pipelines: Pipelines,
+    // Only there with MSAA on
+    msaa: Option<MsaaTargets>,
+    ui_scale: f32,
     staging_belt: StagingBelt,
 
-    glyph_brush: wgpu_glyph::GlyphBrush<()>,
+    text_brush: TextBrush,
+    glyph_cache_size: (u32, u32),
+    text_quality: TextQuality,
+    text_options: TextRasterOptions,
 
-    pub layout: Layout,    
+    pub layout: Layout,
+    // Layouts drawn on top of the main one, like a pause menu over a HUD
+    layout_stack: Vec<Layout>,
+    // Layout pushes and pops held back while a loading screen is on top
+    held_stack_commands: Vec<LayoutCommand>,
+    pub textures: TexturePool,
+    pub assets: Assets,
+    pub streamer: TextureStreamer,
 
     camera: Camera,
+    // Used by components that don't set their own colour
+    default_tint: Tint,
+    // Used by components that don't draw a texture - plain white, so the tint shows as it is
+    default_texture: Texture,
+    picker: PixelPicker,
 }
 
 
 impl Renderer{
     /// Create a new renderer, initializing all values
     pub async fn new(window: &winit::window::Window) -> Self{
-        // Set our size to the window size
-        let size = window.inner_size();
-
-
-        // Create a new instance with the best api (VULKAN, DX12/DX11 or METAL)
-        let instance = wgpu::Instance::new(wgpu::BackendBit::PRIMARY);
+        Renderer::with_config(window, &RendererConfig::default()).await.expect("Error creating renderer")
+    }
 
-        // Create a surface (like a link to the winit window)
-        
-            let surface = unsafe { instance.create_surface(window) };
+    /// Like `new`, but blocks the thread while the GPU is set up instead of needing to be awaited
+    pub fn new_blocking(window: &winit::window::Window) -> Self{
+        block_on(Renderer::new(window))
+    }
 
-        // Create our adapter. We can select things like the power preference
-        // and define the surface to draw to.
-        // We want low power as we're not drawing games and the like.
-        let adapter = instance.request_adapter(
-            &wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::LowPower,
-                compatible_surface: Some(&surface),
-            },
-        ).await.unwrap();
+    /// Create a new renderer, choosing the backend, adapter and power preference
+    pub async fn with_config(window: &winit::window::Window, config: &RendererConfig) -> Result<Self, &'static str>{
+        // Set our size to the window size
+        let size = window.inner_size();
 
-        // Request the device and queue. This can be thought of as a link to the GPU,
-        // and the queue is like a pipe to render down (eg, compute or graphics).
-        let (device, queue) = adapter.request_device(
-            &wgpu::DeviceDescriptor {
-                features: wgpu::Features::default(),
-                limits: wgpu::Limits::default(),
-                shader_validation: true,
-            },
-            None, // Trace path
-        ).await.unwrap();
+        let (surface, device, queue) = Renderer::create_device(window, config).await?;
 
         // We define what a swapchain should be - eg, its usage, format (RGB, BGR)
         // size, width and present mode - vsync on or off for example.
@@ -85,7 +101,7 @@ impl Renderer{
         // create a swapchain using the swapchain description and link it to the surface
         let swap_chain = device.create_swap_chain(&surface, &sc_desc);
 
-        let render_pipeline = Renderer::create_render_pipeline(&device);
+        let pipelines = Pipelines::new(&device, 1);
 
         let staging_belt = StagingBelt::new(512);
 
@@ -93,56 +109,195 @@ impl Renderer{
         let font = wgpu_glyph::ab_glyph::FontArc::try_from_slice(include_bytes!("../../fonts/FingerPaint-Regular.ttf"))
         .expect("Load font");
 
-        let glyph_brush = wgpu_glyph::GlyphBrushBuilder::using_font(font)
-            .build(&device, wgpu::TextureFormat::Bgra8UnormSrgb);
+        let text_brush = TextBrush::new(vec![font], DEFAULT_GLYPH_CACHE_SIZE, &TextRasterOptions::default(), &device);
 
         let layout = Layout::new();
+        let textures = TexturePool::new();
+        let assets = Assets::new(&device, &queue);
+        let streamer = TextureStreamer::new(&device, &queue);
 
         let camera = Camera::new(0.1, 750.0, &device, &sc_desc);
 
-        Self{
+        let default_tint = Tint::new([1.0, 0.0, 0.0, 1.0], &device);
+        let default_texture = Texture::from_rgba(&[255, 255, 255, 255], (1, 1), "Default texture", &device, &queue);
+
+        Ok(Self{
             surface,
             device,
             queue,
             sc_desc,
             swap_chain,
+            lost_frames: 0,
+            config: config.clone(),
             size,
 
-            render_pipeline,
+            pipelines,
+            msaa: None,
+            ui_scale: 1.0,
             staging_belt,
-            glyph_brush,
+            text_brush,
+            glyph_cache_size: DEFAULT_GLYPH_CACHE_SIZE,
+            text_quality: TextQuality::default(),
+            text_options: TextRasterOptions::default(),
             layout,
-            camera
+            layout_stack: Vec::new(),
+            held_stack_commands: Vec::new(),
+            textures,
+            assets,
+            streamer,
+            camera,
+            default_tint,
+            default_texture,
+            picker: PixelPicker::new(),
+        })
+    }
+
+    /// Like `with_config`, but blocks the thread while the GPU is set up instead of needing to be awaited
+    pub fn with_config_blocking(window: &winit::window::Window, config: &RendererConfig) -> Result<Self, &'static str>{
+        block_on(Renderer::with_config(window, config))
+    }
+
+    // Make a surface for the window, and a device on the adapter the config picks
+    async fn create_device(window: &winit::window::Window, config: &RendererConfig) -> Result<(wgpu::Surface, wgpu::Device, wgpu::Queue), &'static str>{
+        // Create a new instance with the chosen apis (by default the best one - VULKAN, DX12/DX11 or METAL)
+        let instance = wgpu::Instance::new(config.backends);
+
+        // Create a surface (like a link to the winit window)
+        let surface = unsafe { instance.create_surface(window) };
+
+        // Create our adapter. We can select things like the power preference
+        // and define the surface to draw to.
+        // By default we want low power as we're not drawing games and the like.
+        let adapter = select_adapter(&instance, &surface, config).await?;
+
+        // Request the device and queue. This can be thought of as a link to the GPU,
+        // and the queue is like a pipe to render down (eg, compute or graphics).
+        let (device, queue) = adapter.request_device(
+            &wgpu::DeviceDescriptor {
+                // Compressed textures are used when the GPU has them, and decoded otherwise
+                features: adapter.features() & wgpu::Features::TEXTURE_COMPRESSION_BC,
+                limits: wgpu::Limits::default(),
+                shader_validation: true,
+            },
+            None, // Trace path
+        ).await.map_err(|_| "Error, failed to create the GPU device!")?;
+        Ok((surface, device, queue))
+    }
+
+    /// Move the renderer to a new device after the old one was lost (see `render`), picking the adapter the same
+    /// way as when it was made. The window, settings, pipelines (including the ones from `add_pipeline`), fonts,
+    /// textures added with `add_texture`, and the textures in `assets` and `streamer` are all made again on it -
+    /// loaded textures draw as placeholders until they've loaded again, like the first time.
+    ///
+    /// The components in the layouts were made on the old device, so they can't be drawn any more. The layouts are
+    /// emptied (keeping their settings, see `Layout::clear_gpu_resources`) and the stacked ones removed, for the app
+    /// to fill again - the GUI does this with the handler from `GUI::set_rebuild_handler`.
+    ///
+    /// If there's no device to be had, the renderer is left as it was and the error is returned.
+    pub fn recover_device(&mut self, window: &winit::window::Window) -> Result<(), &'static str>{
+        let (surface, device, queue) = block_on(Renderer::create_device(window, &self.config))?;
+
+        for mut stacked in self.layout_stack.drain(..).rev(){
+            stacked.clear_gpu_resources();
         }
+        self.layout.clear_gpu_resources();
+        self.held_stack_commands.clear();
+
+        self.surface = surface;
+        self.device = device;
+        self.queue = queue;
+        self.recreate_swap_chain();
+        self.lost_frames = 0;
+
+        let sample_count = self.pipelines.get_sample_count();
+        self.pipelines.recreate(&self.device, sample_count);
+        self.msaa = MsaaTargets::new(sample_count, &self.sc_desc, &self.device, &self.queue);
+        self.staging_belt = StagingBelt::new(512);
+        self.rebuild_glyph_brush();
+        self.camera = Camera::new(0.1, 750.0, &self.device, &self.sc_desc);
+        self.default_tint = Tint::new([1.0, 0.0, 0.0, 1.0], &self.device);
+        self.default_texture = Texture::from_rgba(&[255, 255, 255, 255], (1, 1), "Default texture", &self.device, &self.queue);
+
+        self.textures.reload(&self.device, &self.queue);
+        self.assets.reload(&self.device, &self.queue);
+        self.streamer.reload(&self.device, &self.queue);
+        Ok(())
     }
 
-    /// Create a render pipeline from default values, taking in a reference to the device
+    /// List the GPU adapters available on some backends. See `AdapterSelection::Index`.
+    pub fn list_adapters(backends: wgpu::BackendBit) -> Vec<wgpu::AdapterInfo>{
+        list_adapters(backends)
+    }
+
+    /// Create a render pipeline from default values, taking in a reference to the device. Like the other pipelines
+    /// made with these functions, it draws without MSAA.
     pub fn create_render_pipeline(device: &wgpu::Device) -> wgpu::RenderPipeline{
+        let fs_module = device.create_shader_module(wgpu::include_spirv!("../../shaders/shader.frag.spv"));
+        Renderer::create_pipeline_with_fragment(device, &fs_module, "Render Pipeline")
+    }
+
+    /// Create a pipeline like the default one, except that it draws a line between each pair of vertices instead
+    /// of a triangle between each three. Lines are a pixel wide.
+    pub fn create_line_pipeline(device: &wgpu::Device) -> wgpu::RenderPipeline{
+        let fs_module = device.create_shader_module(wgpu::include_spirv!("../../shaders/shader.frag.spv"));
+        Renderer::create_pipeline(device, &fs_module, "Line Pipeline", wgpu::PrimitiveTopology::LineList, 1)
+    }
+
+    /// Create a render pipeline like the default one, but with another fragment shader. The shader gets the same
+    /// inputs as the default one - the texture coordinates at location 0, the tint at set 2 and the texture at set 3.
+    /// Use `add_pipeline` instead for a pipeline that keeps up with the MSAA level.
+    pub fn create_pipeline_with_fragment(device: &wgpu::Device, fs_module: &wgpu::ShaderModule, label: &str) -> wgpu::RenderPipeline{
+        Renderer::create_pipeline(device, fs_module, label, wgpu::PrimitiveTopology::TriangleList, 1)
+    }
+
+    pub(crate) fn create_pipeline(device: &wgpu::Device, fs_module: &wgpu::ShaderModule, label: &str, topology: wgpu::PrimitiveTopology, sample_count: u32) -> wgpu::RenderPipeline{
+        let color_blend = wgpu::BlendDescriptor {
+            src_factor: wgpu::BlendFactor::SrcAlpha,
+            dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+            operation: wgpu::BlendOperation::Add
+        };
+        Renderer::create_pipeline_with_blend(device, fs_module, label, topology, sample_count, color_blend)
+    }
+
+    // The pipeline stacked layouts get drawn onto the window with when there's MSAA. Their layer was drawn onto a
+    // transparent background, which leaves its colours already multiplied by their alpha.
+    pub(crate) fn create_composite_pipeline(device: &wgpu::Device) -> wgpu::RenderPipeline{
+        let fs_module = device.create_shader_module(wgpu::include_spirv!("../../shaders/shader.frag.spv"));
+        let color_blend = wgpu::BlendDescriptor {
+            src_factor: wgpu::BlendFactor::One,
+            dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+            operation: wgpu::BlendOperation::Add
+        };
+        Renderer::create_pipeline_with_blend(device, &fs_module, "Composite Pipeline", wgpu::PrimitiveTopology::TriangleList, 1, color_blend)
+    }
+
+    fn create_pipeline_with_blend(device: &wgpu::Device, fs_module: &wgpu::ShaderModule, label: &str, topology: wgpu::PrimitiveTopology, sample_count: u32, color_blend: wgpu::BlendDescriptor) -> wgpu::RenderPipeline{
         // Define our pipeline layout. This is where we define bind_group_layouts
         let render_pipeline_layout =
        device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
            label: Some("Render Pipeline Layout"),
            bind_group_layouts: &[
                &UniformUtils::create_bind_group_layout(device, 0, wgpu::ShaderStage::VERTEX, false, None, "Some bidn group layout"),
-               &UniformUtils::create_bind_group_layout(device, 0, wgpu::ShaderStage::VERTEX, false, None, "Some bidn group layout")
+               &UniformUtils::create_bind_group_layout(device, 0, wgpu::ShaderStage::VERTEX, false, None, "Some bidn group layout"),
+               &UniformUtils::create_bind_group_layout(device, 0, wgpu::ShaderStage::FRAGMENT, false, None, "Tint bind group layout"),
+               &Texture::create_bind_group_layout(device)
            ],
            push_constant_ranges: &[],
         });
 
         // Create our shader modules
         let vs_module = device.create_shader_module(wgpu::include_spirv!("../../shaders/shader.vert.spv"));
-        let fs_module = device.create_shader_module(wgpu::include_spirv!("../../shaders/shader.frag.spv"));
 
         // Create the pipeline. We define it - we're rendering a GUI, so it doesn't matter much
         device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Render Pipeline"),
+            label: Some(label),
             layout: Some(&render_pipeline_layout),
             vertex_stage: wgpu::ProgrammableStageDescriptor {
                 module: &vs_module,
                 entry_point: "main", // 1.
             },
             fragment_stage: Some(wgpu::ProgrammableStageDescriptor { // 2.
-                module: &fs_module,
+                module: fs_module,
                 entry_point: "main",
             }),
             rasterization_state: Some(
@@ -158,11 +313,7 @@ impl Renderer{
             color_states: &[
                 wgpu::ColorStateDescriptor {
                     format: wgpu::TextureFormat::Bgra8UnormSrgb,
-                    color_blend: wgpu::BlendDescriptor {
-                        src_factor: wgpu::BlendFactor::SrcAlpha,
-                        dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
-                        operation: wgpu::BlendOperation::Add
-                    },
+                    color_blend,
                     alpha_blend: wgpu::BlendDescriptor {
                         src_factor: wgpu::BlendFactor::One,
                         dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
@@ -174,7 +325,7 @@ impl Renderer{
                 }
             ],
 
-            primitive_topology: wgpu::PrimitiveTopology::TriangleList, // 1.
+            primitive_topology: topology, // 1.
 
             depth_stencil_state: None,
 
@@ -182,114 +333,661 @@ impl Renderer{
                 index_format: wgpu::IndexFormat::Uint32,
                 vertex_buffers: &[Vertex::desc()],
             },
-            sample_count: 1, // 5.
+            sample_count, // 5.
             sample_mask: !0, // 6.
-            alpha_to_coverage_enabled: true, // 7.
+            // Transparency is blended, where coverage from alpha would dither it once there's MSAA
+            alpha_to_coverage_enabled: false, // 7.
         })
     }
 
+    /// Add a pipeline with a custom fragment shader under a name (see `create_pipeline_with_fragment`), for components
+    /// that draw more than a textured quad, like the gradients in a `ColorPicker`. Components set the pipeline before
+    /// drawing, and the layout puts the default one back before the next component. Changing the MSAA level
+    /// replaces it, so components should fetch it again from `get_pipelines` in `prepare`.
+    ///
+    /// If there's already a pipeline with the name, it's kept and returned instead.
+    pub fn add_pipeline(&mut self, name: &str, fragment_shader: wgpu::ShaderModuleSource) -> Rc<wgpu::RenderPipeline>{
+        self.pipelines.add(&self.device, name, fragment_shader)
+    }
+
+    /// Get a pipeline added with `add_pipeline`
+    pub fn get_pipeline(&self, name: &str) -> Option<Rc<wgpu::RenderPipeline>>{
+        self.pipelines.get(name)
+    }
+
+    /// Get a handle to all of the pipelines, for components that set their own and need to keep up with the MSAA
+    /// level (see `Pipelines`)
+    pub fn get_pipelines(&self) -> Pipelines{
+        self.pipelines.clone()
+    }
+
+    /// Get the pipeline everything is drawn with by default, for components that need to go back to it after
+    /// drawing with their own
+    pub fn get_default_pipeline(&self) -> Rc<wgpu::RenderPipeline>{
+        self.pipelines.get_default()
+    }
+
+    /// Get the pipeline `Lines` are drawn with (see `create_line_pipeline`)
+    pub fn get_line_pipeline(&self) -> Rc<wgpu::RenderPipeline>{
+        self.pipelines.get_line()
+    }
+
+    /// Add a font (for example an icon font) to the text renderer, returning the ID to use it with.
+    ///
+    /// The default font always has the ID `FontId(0)`.
+    pub fn add_font(&mut self, font_data: Vec<u8>) -> Result<wgpu_glyph::FontId, &'static str>{
+        let font = wgpu_glyph::ab_glyph::FontArc::try_from_vec(font_data).map_err(|_| "Error, failed to load font!")?;
+        Ok(self.text_brush.add_font(font))
+    }
+
+    /// Measure how big some text is in pixels, wrapping it past `max_width` like a `Label` would
+    pub fn measure_text(&mut self, text: &str, size: f32, font: wgpu_glyph::FontId, max_width: Option<f32>) -> [f32; 2]{
+        measure_text(&mut self.text_brush, text, size, font, max_width)
+    }
+
+    /// Load a texture from the bytes of an image file, and add it to the texture pool under a name
+    pub fn add_texture<S: Into<String>>(&mut self, name: S, bytes: &[u8]) -> Result<(), &'static str>{
+        let name = name.into();
+        let texture = Texture::from_bytes(bytes, &name, &self.device, &self.queue)?;
+        // The file is kept, to load it again if the device is lost
+        self.textures.add_texture_with_source(name, texture, bytes.to_vec());
+        Ok(())
+    }
+
+    /// Set the size of the texture glyphs are cached in, in pixels. When it's full, the glyphs that haven't been
+    /// drawn recently make room for new ones, so this caps how much memory text uses. It only grows past this if
+    /// one frame has more glyphs than fit.
+    ///
+    /// This makes a new glyph brush (keeping the fonts and their IDs), so every glyph gets drawn again.
+    pub fn set_glyph_cache_size(&mut self, size: (u32, u32)){
+        if size == self.glyph_cache_size{
+            return;
+        }
+        self.glyph_cache_size = size;
+        self.rebuild_glyph_brush();
+    }
+
+    // Make a new glyph brush with the current settings, keeping the fonts and their IDs
+    fn rebuild_glyph_brush(&mut self){
+        self.text_brush = TextBrush::new(self.text_brush.fonts().to_vec(), self.glyph_cache_size, &self.text_options, &self.device);
+    }
+
+    pub fn get_glyph_cache_size(&self) -> (u32, u32){
+        self.glyph_cache_size
+    }
+
+    /// Choose how text in scaled layouts is drawn - stretched along with the layout, drawn again at its final size
+    /// so it stays sharp, or drawn from distance fields (see `TextQuality`)
+    pub fn set_text_quality(&mut self, quality: TextQuality){
+        self.text_quality = quality;
+    }
+
+    pub fn get_text_quality(&self) -> TextQuality{
+        self.text_quality
+    }
+
+    /// Set how glyphs are rasterized (see `TextRasterOptions`). Themes set these too, see `ThemeBundle::from_files`.
+    /// Changing subpixel positioning makes a new glyph brush, so every glyph gets drawn again.
+    pub fn set_text_options(&mut self, options: TextRasterOptions){
+        let rebuild = options.subpixel_positioning != self.text_options.subpixel_positioning;
+        self.text_options = options;
+        if rebuild{
+            self.rebuild_glyph_brush();
+        }else{
+            self.text_brush.set_options(&options);
+        }
+    }
+
+    pub fn get_text_options(&self) -> TextRasterOptions{
+        self.text_options
+    }
+
+    /// How much GPU memory is in use, split up by what it's used for. The texture pool can be kept under a size
+    /// with `TexturePool::set_limit`, and the glyph cache with `set_glyph_cache_size`.
+    pub fn memory_report(&self) -> MemoryReport{
+        MemoryReport{
+            texture_pool: self.textures.get_memory_size(),
+            glyph_cache: self.text_brush.memory_size(),
+            swap_chain: SWAP_CHAIN_IMAGES * 4 * self.sc_desc.width as u64 * self.sc_desc.height as u64,
+            ..MemoryReport::counted()
+        }
+    }
+
+    /// Finish off any assets that loaded in the background since the last frame, and stream textures in and out
+    /// (see `TextureStreamer`). The main loop calls this every frame, returning true if any asset changed.
+    pub fn update_assets(&mut self) -> bool{
+        let assets = self.assets.update(&self.device, &self.queue, &mut self.text_brush);
+        let streamed = self.streamer.update(&self.device, &self.queue, &self.assets.get_source());
+        assets || streamed
+    }
+
     /// This function gets called upon a resize, as we need to recreate the swapchain
     pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
         self.size = new_size;
         if new_size.width > 0 && new_size.height > 0{
             self.sc_desc.width = new_size.width;
             self.sc_desc.height = new_size.height;
-            self.swap_chain = self.device.create_swap_chain(&self.surface, &self.sc_desc);
+            self.recreate_swap_chain();
+            self.msaa = MsaaTargets::new(self.pipelines.get_sample_count(), &self.sc_desc, &self.device, &self.queue);
         }
     }
 
+    /// Change the graphics settings (see `GraphicsSettings`) without making a new renderer, keeping the window,
+    /// the layouts and everything loaded. Changing the MSAA level makes the pipelines again (see `Pipelines`), and
+    /// changing the present mode makes a new swapchain.
+    ///
+    /// Nothing changes if the settings aren't valid (see `GraphicsSettings::validate`).
+    pub fn reconfigure(&mut self, settings: &GraphicsSettings) -> Result<(), &'static str>{
+        settings.validate()?;
+        if settings.msaa_samples != self.pipelines.get_sample_count(){
+            // Nothing already submitted can still be using the old pipelines or targets
+            self.wait_for_gpu();
+            self.pipelines.rebuild(&self.device, settings.msaa_samples);
+            self.msaa = MsaaTargets::new(settings.msaa_samples, &self.sc_desc, &self.device, &self.queue);
+        }
+        if settings.present_mode != self.sc_desc.present_mode{
+            self.sc_desc.present_mode = settings.present_mode;
+            if self.size.width > 0 && self.size.height > 0{
+                self.recreate_swap_chain();
+            }
+        }
+        self.ui_scale = settings.ui_scale;
+        for layout in self.layouts_mut(){
+            layout.set_ui_scale(settings.ui_scale);
+        }
+        self.text_quality = settings.text_quality;
+        Ok(())
+    }
+
+    /// The graphics settings in use, to start a settings screen from
+    pub fn get_graphics_settings(&self) -> GraphicsSettings{
+        GraphicsSettings{
+            msaa_samples: self.pipelines.get_sample_count(),
+            present_mode: self.sc_desc.present_mode,
+            ui_scale: self.ui_scale,
+            text_quality: self.text_quality,
+        }
+    }
+
+    /// Turn vsync on or off. With vsync off, frames are shown as soon as they're ready (see `GUI::set_max_fps`
+    /// to keep the frame rate reasonable).
+    pub fn set_vsync(&mut self, vsync: bool){
+        let present_mode = if vsync { wgpu::PresentMode::Fifo } else { wgpu::PresentMode::Mailbox };
+        if self.sc_desc.present_mode != present_mode{
+            self.sc_desc.present_mode = present_mode;
+            if self.size.width > 0 && self.size.height > 0{
+                self.recreate_swap_chain();
+            }
+        }
+    }
+
+    pub fn get_vsync(&self) -> bool{
+        self.sc_desc.present_mode == wgpu::PresentMode::Fifo
+    }
+
+    /// Recreate the swapchain with the current size. This is needed when the swapchain gets lost or goes
+    /// out of date, for example when the GPU is reset or the display changes.
+    pub fn recreate_swap_chain(&mut self){
+        self.swap_chain = self.device.create_swap_chain(&self.surface, &self.sc_desc);
+    }
+
     /// This should run BEFORE we render. This lets us set up last minute values
     /// and update our layout before we render
     pub fn prepass(&mut self){
-        let mut text_child_components = Vec::<(usize, bool, [f32; 2])>::new();
-        let components = &self.layout.components;
-        for i in 0..components.len(){
-            let comp = &components[i];
-            if let Some(id) = comp.get_text_id(){
-                text_child_components.push((id, comp.is_enabled(), comp.get_pos()));
+        profile_scope!("rusty_gui::layout");
+        let screen_dim = (self.sc_desc.width, self.sc_desc.height);
+        for layout in std::iter::once(&mut self.layout).chain(self.layout_stack.iter_mut()){
+            // Layouts with a content scale get a smaller (or bigger) screen, which gets stretched to fit the window
+            let content_dim = layout.get_content_dim(screen_dim);
+            layout.rebuild(&self.device);
+            layout.layout_text(&mut self.text_brush);
+            layout.arrange(content_dim);
+            layout.prepare(&self.queue, content_dim);
+        }
+    }
+
+    /// Stack a layout on top of the current ones, for example a pause menu over a game's HUD. It gets drawn
+    /// over the layouts under it, and sees input first - use `Layout::set_input_priority` to stop input from
+    /// reaching the layouts underneath.
+    pub fn push_layout(&mut self, mut layout: Layout){
+        layout.set_ui_scale(self.ui_scale);
+        self.layout_stack.push(layout);
+    }
+
+    /// Remove the topmost stacked layout. The main layout (`Renderer::layout`) is never removed.
+    pub fn pop_layout(&mut self) -> Option<Layout>{
+        self.layout_stack.pop()
+    }
+
+    /// Apply the changes queued in every layout's `LayoutCommands`. The GUI calls this after each event is
+    /// dispatched and after updating.
+    ///
+    /// While the topmost layout is loading (see `Layout::set_loading`), pushed and popped layouts are held back,
+    /// and switched in the order they were queued once the load is finished.
+    pub fn apply_commands(&mut self){
+        loop{
+            let mut queued = Vec::new();
+            for layout in self.layouts_mut(){
+                queued.extend(layout.apply_commands());
+            }
+            self.held_stack_commands.extend(queued);
+            if self.held_stack_commands.is_empty() || self.is_loading(){
+                return;
+            }
+            // Pushed layouts can come with commands of their own, so go round again
+            let mut stack_commands = std::mem::take(&mut self.held_stack_commands).into_iter();
+            while let Some(command) = stack_commands.next(){
+                // A pushed loading screen holds back whatever comes after it
+                if self.is_loading(){
+                    self.held_stack_commands.push(command);
+                    self.held_stack_commands.extend(stack_commands);
+                    break;
+                }
+                match command{
+                    LayoutCommand::PushLayout(layout) => self.push_layout(layout),
+                    LayoutCommand::PopLayout => { self.pop_layout(); }
+                    _ => {}
+                }
             }
         }
-        let components = &self.layout.event_components;
-        for i in 0..components.len() {
-            let comp = &components[i];
-            if let Some(id) = comp.get_text_id(){
-                text_child_components.push((id, comp.is_enabled(), comp.get_pos()));
+    }
+
+    /// True if the topmost layout is showing a load that isn't finished, so layouts can't be switched with
+    /// `LayoutCommands` yet
+    pub fn is_loading(&self) -> bool{
+        self.layout_stack.last().unwrap_or(&self.layout).is_loading()
+    }
+
+    /// How many layouts are stacked on top of the main one
+    pub fn stacked_layout_count(&self) -> usize{
+        self.layout_stack.len()
+    }
+
+    /// True if a stacked layout blocks the mouse from reaching the layouts under it, like a pause menu, or a
+    /// modal component like a `Dialog` is open in any layout
+    pub fn is_modal_open(&self) -> bool{
+        self.layout_stack.iter().any(|layout| layout.input_priority.blocks(InputCategory::Mouse))
+            || std::iter::once(&self.layout).chain(self.layout_stack.iter()).any(|layout| layout.get_modal().is_some())
+    }
+
+    /// Borrow the topmost layout, which is the main layout if nothing is stacked on it
+    pub fn borrow_top_layout_mut(&mut self) -> &mut Layout{
+        match self.layout_stack.last_mut(){
+            Some(layout) => layout,
+            None => &mut self.layout,
+        }
+    }
+
+    /// Every active layout, from the bottom (the main layout) to the top
+    pub fn layouts_mut(&mut self) -> impl Iterator<Item = &mut Layout>{
+        std::iter::once(&mut self.layout).chain(self.layout_stack.iter_mut())
+    }
+
+    /// Send an event through the layouts, from the top down. Layouts stop kinds of input they block from
+    /// reaching the layouts under them. Returns true if a component consumed the event.
+    pub fn dispatch_event(&mut self, event: &winit::event::Event<()>, window: &winit::window::Window) -> bool{
+        profile_scope!("rusty_gui::dispatch_event");
+        let category = InputCategory::of(event);
+        for layout in self.layout_stack.iter_mut().rev().chain(std::iter::once(&mut self.layout)){
+            if layout.dispatch_event(event, window){
+                return true;
+            }
+            if let Some(category) = category{
+                if layout.blocks_input(category){
+                    break;
+                }
             }
         }
+        false
+    }
 
-        for (id, enabled, pos) in text_child_components.iter(){
-            let text = self.layout.borrow_text_component_as_type_mut::<Label>(*id).unwrap();
-            text.set_pos(*pos, (self.sc_desc.width, self.sc_desc.height));
-            if *enabled{
-                text.enable();
-            }else{
-                text.disable();
+    /// Update which components are hovered (see `Layout::update_hover`). Layouts under one that blocks the mouse
+    /// don't get hovered at all.
+    pub fn update_hover(&mut self, cursor: Option<[f32; 2]>){
+        let mut cursor = cursor;
+        for layout in self.layout_stack.iter_mut().rev().chain(std::iter::once(&mut self.layout)){
+            layout.update_hover(cursor);
+            if layout.blocks_input(InputCategory::Mouse){
+                cursor = None;
             }
         }
     }
 
     /// Render a single frame 
-    pub fn render(&mut self, clear_color: wgpu::Color){
-        let frame = self.swap_chain.get_current_frame().unwrap().output;
+    ///
+    /// If the swapchain was lost or is out of date, it gets recreated and the frame is skipped, so the next
+    /// frame draws as normal. The errors returned can't be recovered from by the renderer:
+    ///
+    /// * `OutOfMemory`, when there's no memory left for a frame
+    /// * `Lost`, when the swapchain stays lost for a few frames even after being recreated. That means the device
+    ///   itself is gone (the GPU was reset, or its driver was updated), along with every buffer and texture on it.
+    ///   `recover_device` moves the renderer to a new one, and the GUI does that by itself.
+    pub fn render(&mut self, clear_color: wgpu::Color) -> Result<(), wgpu::SwapChainError>{
+        // There's nothing to draw to while the window is minimized
+        if self.size.width == 0 || self.size.height == 0{
+            return Ok(());
+        }
+
+        let frame = match self.swap_chain.get_current_frame(){
+            Ok(frame) => frame,
+            Err(wgpu::SwapChainError::Lost) => {
+                self.lost_frames += 1;
+                if self.lost_frames >= LOST_FRAME_LIMIT{
+                    return Err(wgpu::SwapChainError::Lost);
+                }
+                self.recreate_swap_chain();
+                return Ok(());
+            }
+            Err(wgpu::SwapChainError::Outdated) => {
+                self.recreate_swap_chain();
+                return Ok(());
+            }
+            // The GPU took too long to hand over a frame, so just try again next frame
+            Err(wgpu::SwapChainError::Timeout) => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        self.lost_frames = 0;
+        let suboptimal = frame.suboptimal;
+        let frame = frame.output;
 
         let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
             label: Some("Render Encoder"),
         });   
 
-        self.camera.update(&self.sc_desc);
+        self.draw(&mut encoder, &frame.view, clear_color);
 
+        self.staging_belt.finish();
+        
+        // submit will accept anything that implements IntoIter
         {
-            // Pre pass
-            // Main pass - Render all our shaders and objects to the screen
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                color_attachments: &[
-                    wgpu::RenderPassColorAttachmentDescriptor {
-                        attachment: &frame.view,
-                        resolve_target: None,
-                        ops: wgpu::Operations {
-                            load: wgpu::LoadOp::Clear(clear_color),
-                            store: true,
-                        }
-                    },
-                ],
-                depth_stencil_attachment: None,
-            });
+            profile_scope!("rusty_gui::submit");
+            self.queue.submit(std::iter::once(encoder.finish()));
+        }
 
-            render_pass.set_pipeline(&self.render_pipeline);
+        // The frame has to be presented (dropped) before the swapchain can be recreated
+        drop(frame);
+        if suboptimal{
+            self.recreate_swap_chain();
+        }
 
+        // Answer any pixel picks (like from an eyedropper) now the frame is drawn
+        for (pos, callback) in self.picker.take_requests(){
+            if let Ok(color) = self.read_pixel(pos, clear_color){
+                callback(color);
+            }
+        }
+        Ok(())
+    }
 
+    /// Read back the colour of a pixel (in window space) as it appears on screen, by drawing the current
+    /// layout into an offscreen texture. The colour is returned as RGBA, from 0 to 1 (in sRGB).
+    ///
+    /// This waits for the GPU, so it's meant for one off reads like an eyedropper, not every frame.
+    pub fn read_pixel(&mut self, pos: [u32; 2], clear_color: wgpu::Color) -> Result<[f32; 4], &'static str>{
+        let (width, height) = (self.sc_desc.width, self.sc_desc.height);
+        if pos[0] >= width || pos[1] >= height{
+            return Err("Error, pixel is outside of the window!");
+        }
 
-            {   
-                let components = &self.layout.components;
-                for i in 0..components.len(){
-                    let comp = &components[i];
-                    render_pass.set_bind_group(0, &self.camera.bind_group, &[]);
-                    comp.render(&mut render_pass);
-                }
-            }
+        let size = wgpu::Extent3d {
+            width,
+            height,
+            depth: 1,
+        };
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Readback texture"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.sc_desc.format,
+            usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT | wgpu::TextureUsage::COPY_SRC,
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        // Rows of a texture copy have to be 256 byte aligned, even if we only want 4 bytes
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Readback buffer"),
+            size: wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as u64,
+            usage: wgpu::BufferUsage::COPY_DST | wgpu::BufferUsage::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Readback Encoder"),
+        });
+        self.draw(&mut encoder, &view, clear_color);
+        encoder.copy_texture_to_buffer(
+            wgpu::TextureCopyView {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d { x: pos[0], y: pos[1], z: 0 },
+            },
+            wgpu::BufferCopyView {
+                buffer: &buffer,
+                layout: wgpu::TextureDataLayout {
+                    offset: 0,
+                    bytes_per_row: wgpu::COPY_BYTES_PER_ROW_ALIGNMENT,
+                    rows_per_image: 1,
+                },
+            },
+            wgpu::Extent3d {
+                width: 1,
+                height: 1,
+                depth: 1,
+            },
+        );
+        self.staging_belt.finish();
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = buffer.slice(..);
+        let mapping = slice.map_async(wgpu::MapMode::Read);
+        self.device.poll(wgpu::Maintain::Wait);
+        block_on(mapping).map_err(|_| "Error, failed to read back the pixel!")?;
+
+        let data = slice.get_mapped_range();
+        // The swapchain format is BGRA
+        let color = [data[2] as f32 / 255.0, data[1] as f32 / 255.0, data[0] as f32 / 255.0, data[3] as f32 / 255.0];
+        drop(data);
+        buffer.unmap();
+        Ok(color)
+    }
+
+    /// Block until the GPU has finished everything it's been given
+    pub fn wait_for_gpu(&self){
+        self.device.poll(wgpu::Maintain::Wait);
+    }
+
+    /// Tear the renderer down, instead of leaving it to whatever order things get dropped in at exit. This waits for
+    /// the GPU to finish, then drops the layouts (top of the stack first), textures and assets, text and pipelines,
+    /// and the swapchain and surface, before the device itself.
+    ///
+    /// It's also how to switch graphics settings that need a new device, like the backend or adapter: shut the old
+    /// renderer down first, so two devices aren't holding on to GPU memory at once.
+    ///
+    /// ```ignore
+    /// if let Some(old) = renderer.take(){
+    ///     old.shutdown();
+    /// }
+    /// renderer = Some(Renderer::with_config_blocking(&window, &new_config)?);
+    /// ```
+    pub fn shutdown(self){
+        self.wait_for_gpu();
+        let Renderer{
+            device, swap_chain,
+            pipelines, msaa, staging_belt, text_brush,
+            layout, layout_stack, held_stack_commands, textures, assets, streamer,
+            camera, default_tint, default_texture, picker, ..
+        } = self;
+
+        for mut stacked in layout_stack.into_iter().rev(){
+            stacked.clear_gpu_resources();
+        }
+        let mut layout = layout;
+        layout.clear_gpu_resources();
+        drop(layout);
+        drop(held_stack_commands);
+        drop(picker);
+        drop(streamer);
+        drop(assets);
+        drop(textures);
+        drop(default_texture);
+        drop(default_tint);
+        drop(camera);
+        drop(text_brush);
+        drop(staging_belt);
+        // Components can hold on to pipelines, but they're all gone now
+        drop(pipelines);
+        drop(msaa);
+        drop(swap_chain);
+        // Let the device free everything that was dropped before it goes too
+        device.poll(wgpu::Maintain::Wait);
+        drop(device);
+        // The queue and surface go last, with the rest of the renderer
+    }
+
+    /// Get a handle components can use to ask for the colour of a pixel (see `Eyedropper`).
+    /// Picks get answered after the next frame is drawn.
+    pub fn get_pixel_picker(&self) -> PixelPicker{
+        self.picker.clone()
+    }
+
+    // Record drawing the layouts into a view
+    fn draw(&mut self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView, clear_color: wgpu::Color){
+        self.camera.update(&self.sc_desc);
+
+        let pipeline = self.pipelines.get_default();
+        let composite_pipeline = self.pipelines.get_composite();
+        let layouts = std::iter::once(&self.layout).chain(self.layout_stack.iter());
+        for (i, layout) in layouts.enumerate(){
             {
-                let components = &self.layout.event_components;
-                for i in 0..components.len() {
-                    let comp = &components[i];
-                    render_pass.set_bind_group(0, &self.camera.bind_group, &[]);
-                    comp.render(&mut render_pass);
-                }
+                // Main pass - Render all our shaders and objects to the screen. Only the first layout clears it,
+                // the rest get drawn on top.
+                //
+                // With MSAA, the first layout is resolved straight onto the screen. Resolving the rest there would
+                // cover the text of the layouts under them, so they're resolved onto a layer drawn over it instead.
+                let (attachment, resolve_target, load) = match &self.msaa{
+                    None => (view, None, if i == 0 { wgpu::LoadOp::Clear(clear_color) } else { wgpu::LoadOp::Load }),
+                    Some(msaa) if i == 0 => (&msaa.view, Some(view), wgpu::LoadOp::Clear(clear_color)),
+                    Some(msaa) => (&msaa.view, Some(msaa.layer_view()), wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT)),
+                };
+                let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    color_attachments: &[
+                        wgpu::RenderPassColorAttachmentDescriptor {
+                            attachment,
+                            resolve_target,
+                            ops: wgpu::Operations {
+                                load,
+                                store: true,
+                            }
+                        },
+                    ],
+                    depth_stencil_attachment: None,
+                });
+
+                render_pass.set_pipeline(&pipeline);
+                layout.render(&mut render_pass, &pipeline, [&self.camera.bind_group, &self.default_tint.bind_group, &self.default_texture.bind_group]);
             }
-            {
-                for text_comp in self.layout.text_components.iter(){
-                    text_comp.render_text(&mut self.glyph_brush);
-                }
+            if let (Some(msaa), true) = (&self.msaa, i > 0){
+                let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    color_attachments: &[
+                        wgpu::RenderPassColorAttachmentDescriptor {
+                            attachment: view,
+                            resolve_target: None,
+                            ops: wgpu::Operations {
+                                load: wgpu::LoadOp::Load,
+                                store: true,
+                            }
+                        },
+                    ],
+                    depth_stencil_attachment: None,
+                });
+                render_pass.set_pipeline(&composite_pipeline);
+                render_pass.set_bind_group(0, &self.camera.bind_group, &[]);
+                msaa.layer.render(&mut render_pass);
             }
+
+            // The text of each layout is drawn before the next layout, so stacked layouts cover it
+            profile_scope!("rusty_gui::queue_glyphs");
+            let scale = layout.get_total_scale();
+            let mut transform = wgpu_glyph::orthographic_projection(self.sc_desc.width, self.sc_desc.height);
+            let snapping = self.text_options.pixel_snapping;
+            if self.text_quality == TextQuality::Crisp{
+                // The text gets queued at its final size, so it doesn't need stretching
+                self.text_brush.set_raster(scale, snapping, self.text_quality);
+            }else{
+                // Distance fields are stretched like the rest, but stay sharp
+                self.text_brush.set_raster(1.0, snapping, self.text_quality);
+                transform[0] *= scale;
+                transform[5] *= scale;
+            }
+            layout.render_text(&mut self.text_brush);
+            self.text_brush.draw(&self.device, &mut self.staging_belt, encoder, view, transform);
         }
+    }
+}
 
-        {
-            self.glyph_brush.draw_queued(&self.device, &mut self.staging_belt, &mut encoder, &frame.view, self.sc_desc.width, self.sc_desc.height).unwrap();
+// What gets drawn into when there's MSAA, made for the size of the window
+struct MsaaTargets{
+    // The multisampled image layouts are drawn into, then resolved
+    view: wgpu::TextureView,
+    _texture: wgpu::Texture,
+    _memory: Allocation,
+    // Covers the window, drawing the layer stacked layouts are resolved onto
+    layer: Quad,
+}
+
+impl MsaaTargets{
+    // Nothing is needed without MSAA, or while the window is minimised
+    fn new(sample_count: u32, sc_desc: &wgpu::SwapChainDescriptor, device: &wgpu::Device, queue: &wgpu::Queue) -> Option<Self>{
+        if sample_count <= 1 || sc_desc.width == 0 || sc_desc.height == 0{
+            return None;
         }
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("MSAA texture"),
+            size: wgpu::Extent3d {
+                width: sc_desc.width,
+                height: sc_desc.height,
+                depth: 1,
+            },
+            mip_level_count: 1,
+            sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: sc_desc.format,
+            usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT,
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let memory = Allocation::new(MemoryCategory::Texture, 4 * sample_count as u64 * sc_desc.width as u64 * sc_desc.height as u64);
+
+        let screen_dim = (sc_desc.width, sc_desc.height);
+        let mut layer = Quad::new([0.0, 0.0], [screen_dim.0 as f32, screen_dim.1 as f32], [1.0, 1.0, 1.0, 1.0], device);
+        layer.set_texture(Some(Rc::new(Texture::render_target(screen_dim, sc_desc.format, "MSAA layer", device))));
+        layer.prepare(queue, screen_dim);
+
+        Some(Self{
+            view,
+            _texture: texture,
+            _memory: memory,
+            layer,
+        })
+    }
 
-        self.staging_belt.finish();
-        
-        // submit will accept anything that implements IntoIter
-        self.queue.submit(std::iter::once(encoder.finish()));
+    fn layer_view(&self) -> &wgpu::TextureView{
+        // The layer always has its texture
+        &self.layer.get_texture().expect("MSAA layer texture").view
+    }
+}
+
+/// Measure how big some text is in pixels with a glyph brush, for components measuring their text in `layout_text`
+pub fn measure_text(brush: &mut TextBrush, text: &str, size: f32, font: wgpu_glyph::FontId, max_width: Option<f32>) -> [f32; 2]{
+    let section = wgpu_glyph::Section {
+        bounds: (max_width.unwrap_or(f32::INFINITY), f32::INFINITY),
+        text: vec![wgpu_glyph::Text::new(text).with_scale(wgpu_glyph::ab_glyph::PxScale::from(size)).with_font_id(font)],
+        ..wgpu_glyph::Section::default()
+    };
+    match brush.glyph_bounds(section){
+        Some(bounds) => [bounds.max.x.max(0.0), bounds.max.y.max(0.0)],
+        None => [0.0, 0.0],
     }
 }
 
@@ -333,13 +1031,13 @@ impl Vertex {
 /// This is a helpful quad type to help you render sprites to the screen
 pub const QUAD: &[Vertex] = &[
     // Changed
-    Vertex { position: [-1.0, 1.0, 0.0], tex_coords: [1.0, 0.0], }, // A
-    Vertex { position: [-1.0, -1.0, 0.0], tex_coords: [1.0, 1.0], }, // A
-    Vertex { position: [1.0, -1.0, 0.0], tex_coords: [0.0, 1.0], }, // A
+    Vertex { position: [-1.0, 1.0, 0.0], tex_coords: [0.0, 0.0], }, // A
+    Vertex { position: [-1.0, -1.0, 0.0], tex_coords: [0.0, 1.0], }, // A
+    Vertex { position: [1.0, -1.0, 0.0], tex_coords: [1.0, 1.0], }, // A
     
-    Vertex { position: [-1.0, 1.0, 0.0], tex_coords: [1.0, 0.0], }, // A
-    Vertex { position: [1.0, -1.0, 0.0], tex_coords: [0.0, 1.0], }, // A
-    Vertex { position: [1.0, 1.0, 0.0], tex_coords: [0.0, 0.0], }, // A
+    Vertex { position: [-1.0, 1.0, 0.0], tex_coords: [0.0, 0.0], }, // A
+    Vertex { position: [1.0, -1.0, 0.0], tex_coords: [1.0, 1.0], }, // A
+    Vertex { position: [1.0, 1.0, 0.0], tex_coords: [1.0, 0.0], }, // A
     
 ]; 
 
@@ -357,6 +1055,7 @@ pub struct Camera {
     buffer: wgpu::Buffer,
 
     bind_group: BindGroup,
+    _memory: Allocation,
 }
 
 #[rustfmt::skip]
@@ -384,6 +1083,7 @@ impl Camera {
             camera_uniform,
             buffer,
             bind_group,
+            _memory: Allocation::uniform_buffer::<CameraUniform>(),
         }
     }
     pub fn build_view_projection_matrix(&mut self, sc_desc: &wgpu::SwapChainDescriptor) -> cgmath::Matrix4<f32>{
@@ -0,0 +1,245 @@
+//! This module contains the `SdfAtlas`, which draws text for `TextQuality::Sdf`. Every glyph is turned into a signed
+//! distance field once, at one size, and the glyph shader finds its edges from that at whatever size it ends up on
+//! screen, so scaled text stays sharp without drawing the glyphs again.
+
+use std::collections::HashMap;
+
+use glyph_brush::{Rectangle, ab_glyph::{Font, FontArc, Glyph, GlyphId, Rect, point}};
+use wgpu::util::StagingBelt;
+
+use super::glyphs::{GlyphInstance, GlyphPipeline};
+
+// The size (in pixels) glyphs are turned into distance fields at
+const SDF_SIZE: f32 = 32.0;
+// How far (in pixels, at `SDF_SIZE`) the distance field reaches either side of the edges of a glyph
+const SPREAD: usize = 4;
+// How many pixels across the atlas is
+const ATLAS_SIZE: u32 = 1024;
+
+/// A glyph laid out by the glyph brush, waiting to be drawn from the atlas
+pub(crate) struct SdfGlyph{
+    pub(crate) font_id: usize,
+    pub(crate) glyph: Glyph,
+    pub(crate) color: [f32; 4],
+    pub(crate) z: f32,
+    pub(crate) bounds: Rect, // of the section it's in
+}
+
+// Where a glyph's distance field is in the atlas, and where it goes around the glyph's origin at `SDF_SIZE`
+struct Entry{
+    offset: [f32; 2],
+    rect: Rectangle<u32>,
+}
+
+/// # SdfAtlas
+///
+/// The distance fields of every glyph drawn with `TextQuality::Sdf`, packed into one texture. Glyphs are added the
+/// first time they're drawn, and when the atlas is full it's cleared and filled again with just the glyphs being
+/// drawn.
+pub(crate) struct SdfAtlas{
+    pipeline: GlyphPipeline,
+    entries: HashMap<(usize, GlyphId), Option<Entry>>, // `None` for glyphs with nothing to draw, like spaces
+    packer: ShelfPacker,
+}
+
+impl SdfAtlas{
+    pub(crate) fn new(device: &wgpu::Device) -> Self{
+        Self{
+            pipeline: GlyphPipeline::new(device, (ATLAS_SIZE, ATLAS_SIZE), wgpu::include_spirv!("../../shaders/glyph_sdf.frag.spv"), "SDF glyph atlas"),
+            entries: HashMap::new(),
+            packer: ShelfPacker::new(ATLAS_SIZE),
+        }
+    }
+
+    /// How much memory the atlas takes up, in bytes
+    pub(crate) fn memory_size(&self) -> u64{
+        ATLAS_SIZE as u64 * ATLAS_SIZE as u64
+    }
+
+    /// Set the glyphs the next draw draws, adding any that aren't in the atlas yet
+    pub(crate) fn upload(&mut self, device: &wgpu::Device, staging_belt: &mut StagingBelt, encoder: &mut wgpu::CommandEncoder, fonts: &[FontArc], glyphs: &[SdfGlyph]){
+        let instances = match self.instances(device, encoder, fonts, glyphs){
+            Ok(instances) => instances,
+            // Anything drawn before this has already read the atlas, so it can be cleared
+            Err(_) => {
+                self.entries.clear();
+                self.packer = ShelfPacker::new(ATLAS_SIZE);
+                self.instances(device, encoder, fonts, glyphs).unwrap_or_else(|instances| instances)
+            }
+        };
+        self.pipeline.upload(device, staging_belt, encoder, &instances);
+    }
+
+    /// Draw the uploaded glyphs into the view
+    pub(crate) fn draw(&mut self, device: &wgpu::Device, staging_belt: &mut StagingBelt, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView, transform: [f32; 16], gamma: f32){
+        self.pipeline.draw(device, staging_belt, encoder, view, transform, gamma);
+    }
+
+    // The instances for the glyphs, or as many as fit as an error if the atlas filled up
+    fn instances(&mut self, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder, fonts: &[FontArc], glyphs: &[SdfGlyph]) -> Result<Vec<GlyphInstance>, Vec<GlyphInstance>>{
+        let mut instances = Vec::with_capacity(glyphs.len());
+        let mut full = false;
+        for queued in glyphs{
+            let key = (queued.font_id, queued.glyph.id);
+            if !self.entries.contains_key(&key){
+                let font = match fonts.get(queued.font_id){
+                    Some(font) => font,
+                    None => continue,
+                };
+                match self.add(device, encoder, font, queued.glyph.id){
+                    Ok(entry) => { self.entries.insert(key, entry); }
+                    Err(()) => {
+                        full = true;
+                        continue;
+                    }
+                }
+            }
+            if let Some(Some(entry)) = self.entries.get(&key){
+                instances.push(place(entry, queued));
+            }
+        }
+        if full { Err(instances) } else { Ok(instances) }
+    }
+
+    // Turn a glyph into a distance field and copy it into the atlas, or fail if there isn't room
+    fn add(&mut self, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder, font: &FontArc, id: GlyphId) -> Result<Option<Entry>, ()>{
+        let outlined = match font.outline_glyph(id.with_scale(SDF_SIZE)){
+            Some(outlined) => outlined,
+            None => return Ok(None),
+        };
+        let bounds = outlined.px_bounds();
+        let (width, height) = (bounds.width() as usize, bounds.height() as usize);
+        let mut coverage = vec![0.0; width * height];
+        outlined.draw(|x, y, c| {
+            if let Some(pixel) = coverage.get_mut(y as usize * width + x as usize){
+                *pixel = c;
+            }
+        });
+
+        let (padded_width, padded_height) = ((width + SPREAD * 2) as u32, (height + SPREAD * 2) as u32);
+        let [x, y] = self.packer.pack(padded_width, padded_height).ok_or(())?;
+        let rect = Rectangle{ min: [x, y], max: [x + padded_width, y + padded_height] };
+        self.pipeline.update_cache(device, encoder, rect, &distance_field(&coverage, width, height));
+        Ok(Some(Entry{
+            offset: [bounds.min.x - SPREAD as f32, bounds.min.y - SPREAD as f32],
+            rect,
+        }))
+    }
+}
+
+// The instance for a glyph whose distance field is at `entry`, stretched from `SDF_SIZE` to the glyph's size
+fn place(entry: &Entry, queued: &SdfGlyph) -> GlyphInstance{
+    let factor = [queued.glyph.scale.x / SDF_SIZE, queued.glyph.scale.y / SDF_SIZE];
+    let origin = queued.glyph.position;
+    let min = point(origin.x + entry.offset[0] * factor[0], origin.y + entry.offset[1] * factor[1]);
+    let size = [entry.rect.width() as f32 * factor[0], entry.rect.height() as f32 * factor[1]];
+    let texture = |value: u32| value as f32 / ATLAS_SIZE as f32;
+    GlyphInstance::new(
+        Rect{ min, max: point(min.x + size[0], min.y + size[1]) },
+        Rect{ min: point(texture(entry.rect.min[0]), texture(entry.rect.min[1])), max: point(texture(entry.rect.max[0]), texture(entry.rect.max[1])) },
+        queued.bounds,
+        queued.color,
+        queued.z,
+    )
+}
+
+// Turn a glyph's coverage (`width` by `height`) into a distance field with `SPREAD` pixels of padding all round.
+// Each pixel is how far it is to the edge, from 0 (`SPREAD` or more outside) through a half (on the edge) to 255
+// (`SPREAD` or more inside).
+fn distance_field(coverage: &[f32], width: usize, height: usize) -> Vec<u8>{
+    let spread = SPREAD as i32;
+    let inside = |x: i32, y: i32| {
+        let (x, y) = (x - spread, y - spread);
+        x >= 0 && y >= 0 && (x as usize) < width && (y as usize) < height && coverage[y as usize * width + x as usize] >= 0.5
+    };
+    let (padded_width, padded_height) = (width as i32 + spread * 2, height as i32 + spread * 2);
+    let mut field = Vec::with_capacity((padded_width * padded_height) as usize);
+    for y in 0..padded_height{
+        for x in 0..padded_width{
+            let here = inside(x, y);
+            // The nearest pixel on the other side of the edge, with the edge half way to it
+            let mut nearest = spread as f32 + 0.5;
+            for dy in -spread..=spread{
+                for dx in -spread..=spread{
+                    if inside(x + dx, y + dy) != here{
+                        nearest = nearest.min(((dx * dx + dy * dy) as f32).sqrt());
+                    }
+                }
+            }
+            let distance = if here { nearest - 0.5 } else { 0.5 - nearest };
+            field.push(((0.5 + distance / (spread as f32 * 2.0)).clamp(0.0, 1.0) * 255.0).round() as u8);
+        }
+    }
+    field
+}
+
+// Packs rectangles into a square row by row, each row as tall as the tallest rectangle in it
+struct ShelfPacker{
+    size: u32,
+    x: u32,
+    y: u32,
+    shelf_height: u32,
+}
+
+impl ShelfPacker{
+    fn new(size: u32) -> Self{
+        Self{
+            size,
+            x: 0,
+            y: 0,
+            shelf_height: 0,
+        }
+    }
+
+    // Where the top left of a rectangle goes, or `None` if it doesn't fit
+    fn pack(&mut self, width: u32, height: u32) -> Option<[u32; 2]>{
+        if width > self.size{
+            return None;
+        }
+        if self.x + width > self.size{
+            self.x = 0;
+            self.y += self.shelf_height;
+            self.shelf_height = 0;
+        }
+        if self.y + height > self.size{
+            return None;
+        }
+        let pos = [self.x, self.y];
+        self.x += width;
+        self.shelf_height = self.shelf_height.max(height);
+        Some(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// Test that rectangles fill rows left to right, then start a new row below the tallest one
+    #[test]
+    fn shelf_packer_test(){
+        let mut packer = ShelfPacker::new(100);
+        assert_eq!(packer.pack(40, 10), Some([0, 0]));
+        assert_eq!(packer.pack(40, 30), Some([40, 0]));
+        assert_eq!(packer.pack(40, 10), Some([0, 30]));
+        assert_eq!(packer.pack(200, 10), None);
+        assert_eq!(packer.pack(50, 80), None);
+        assert_eq!(packer.pack(50, 60), Some([40, 30]));
+    }
+
+    /// Test that distance fields are a half on the edge of a glyph, rising inside it and falling outside
+    #[test]
+    fn distance_field_test(){
+        // A solid 4 by 4 square
+        let field = distance_field(&[1.0; 16], 4, 4);
+        let width = 4 + SPREAD * 2;
+        assert_eq!(field.len(), width * width);
+        let at = |x: usize, y: usize| field[(y + SPREAD) * width + x + SPREAD];
+        // Just inside and just outside the left edge are half a pixel either side of it
+        assert_eq!(at(0, 1), (0.5625f32 * 255.0).round() as u8);
+        assert_eq!(field[(1 + SPREAD) * width + SPREAD - 1], (0.4375f32 * 255.0).round() as u8);
+        assert!(at(1, 1) > at(0, 1));
+        // The corners of the padding are as far outside as it goes
+        assert_eq!(field[0], 0);
+    }
+}
@@ -0,0 +1,143 @@
+//! This module abstracts over where asset data comes from. Assets can be read from the file system,
+//! or embedded in the binary with `include_bytes!` so an app can ship as a single executable.
+//! Both `Assets` and `ThemeBundle` load through an `AssetSource`.
+
+use std::{collections::HashMap, path::{Component, Path, PathBuf}, time::SystemTime};
+
+/// Something assets can be read from. Paths are relative to the root of the source, using `/`.
+pub trait AssetSource: Send + Sync{
+    /// Read the whole file at a path
+    fn read(&self, path: &Path) -> Result<Vec<u8>, &'static str>;
+
+    /// List every file under a directory (recursively). An empty path lists everything.
+    fn list(&self, dir: &Path) -> Vec<PathBuf>;
+
+    /// When a file was last changed, if the source can tell. Used for hot reloading.
+    fn modified(&self, _path: &Path) -> Option<SystemTime>{
+        None
+    }
+
+    /// Turn a path into the form used to tell whether two paths point at the same asset
+    fn normalize(&self, path: &Path) -> PathBuf{
+        clean_path(path)
+    }
+}
+
+/// # FileSource
+///
+/// Reads assets from a directory on disk
+pub struct FileSource{
+    root: PathBuf,
+}
+
+impl FileSource{
+    /// Create a source reading from a directory. An empty path reads relative to the working directory.
+    pub fn new<P: Into<PathBuf>>(root: P) -> Self{
+        Self{
+            root: root.into(),
+        }
+    }
+
+    pub fn get_root(&self) -> &Path{
+        &self.root
+    }
+}
+
+impl AssetSource for FileSource{
+    fn read(&self, path: &Path) -> Result<Vec<u8>, &'static str>{
+        std::fs::read(self.root.join(path)).map_err(|_| "Error, failed to read asset file!")
+    }
+
+    fn list(&self, dir: &Path) -> Vec<PathBuf>{
+        let mut files = Vec::new();
+        let mut dirs = vec![self.root.join(dir)];
+        while let Some(dir) = dirs.pop(){
+            let entries = match std::fs::read_dir(&dir){
+                Ok(entries) => entries,
+                Err(_) => continue,
+            };
+            for entry in entries.flatten(){
+                let path = entry.path();
+                if path.is_dir(){
+                    dirs.push(path);
+                }else if let Ok(relative) = path.strip_prefix(&self.root){
+                    files.push(clean_path(relative));
+                }
+            }
+        }
+        files
+    }
+
+    fn modified(&self, path: &Path) -> Option<SystemTime>{
+        std::fs::metadata(self.root.join(path)).and_then(|metadata| metadata.modified()).ok()
+    }
+
+    fn normalize(&self, path: &Path) -> PathBuf{
+        // Use the canonical path where we can, so different spellings of the same file match
+        std::fs::canonicalize(self.root.join(path)).unwrap_or_else(|_| clean_path(path))
+    }
+}
+
+/// # EmbeddedSource
+///
+/// Serves assets compiled into the binary. The `embed_assets!` macro builds one of these from a
+/// list of paths, relative to the crate's `Cargo.toml`:
+///
+/// ```ignore
+/// let source = rusty_gui::embed_assets!("fonts/Roboto.ttf", "textures/button.png", "theme.style");
+/// ```
+///
+/// Files embedded some other way (for example with `rust-embed`) can be added with `add`.
+pub struct EmbeddedSource{
+    files: HashMap<PathBuf, &'static [u8]>,
+}
+
+impl EmbeddedSource{
+    pub fn new() -> Self{
+        Self{
+            files: HashMap::new(),
+        }
+    }
+
+    /// Add a file under a path
+    pub fn add<P: AsRef<Path>>(&mut self, path: P, data: &'static [u8]){
+        self.files.insert(clean_path(path.as_ref()), data);
+    }
+
+    /// Add a file, returning the source (for chaining)
+    pub fn with<P: AsRef<Path>>(mut self, path: P, data: &'static [u8]) -> Self{
+        self.add(path, data);
+        self
+    }
+
+    /// Get the embedded data of a file without copying it
+    pub fn get<P: AsRef<Path>>(&self, path: P) -> Option<&'static [u8]>{
+        self.files.get(&clean_path(path.as_ref())).copied()
+    }
+}
+
+impl AssetSource for EmbeddedSource{
+    fn read(&self, path: &Path) -> Result<Vec<u8>, &'static str>{
+        self.get(path).map(|data| data.to_vec()).ok_or("Error, no embedded asset at that path!")
+    }
+
+    fn list(&self, dir: &Path) -> Vec<PathBuf>{
+        let dir = clean_path(dir);
+        self.files.keys().filter(|path| path.starts_with(&dir)).cloned().collect()
+    }
+}
+
+/// Build an `EmbeddedSource` from a list of files, embedding them with `include_bytes!`.
+/// Paths are relative to the `Cargo.toml` of the crate using the macro.
+#[macro_export]
+macro_rules! embed_assets{
+    ($($path:literal),* $(,)?) => {
+        $crate::rendering::EmbeddedSource::new()
+            $(.with($path, include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/", $path))))*
+    };
+}
+
+// Drop `.` components and leading slashes, so `./fonts/a.ttf` and `fonts/a.ttf` are the same asset
+fn clean_path(path: &Path) -> PathBuf{
+    path.components().filter(|component| matches!(component, Component::Normal(_) | Component::ParentDir)).collect()
+}
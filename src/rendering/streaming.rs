@@ -0,0 +1,199 @@
+//! This module streams textures in and out for UIs with lots of big images, like galleries. Textures only
+//! get loaded once something on screen asks for them, and the ones that haven't been seen for the longest
+//! get unloaded when the textures go over a memory budget.
+
+use std::{cell::RefCell, collections::HashMap, path::{Path, PathBuf}, rc::Rc, sync::{Arc, mpsc::{channel, Receiver, Sender}}};
+
+use super::{AssetSource, AssetState, LruBudget, Texture, TextureData, assets::{create_placeholder, decode_texture}};
+
+/// A handle to a texture streamed through a `TextureStreamer`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct StreamHandle(usize);
+
+// What a loading thread sends back - the index of the texture, and its pixels
+type Decoded = (usize, Result<TextureData, &'static str>);
+
+struct StreamedTexture{
+    path: PathBuf,
+    texture: Option<Rc<Texture>>,
+    state: AssetState,
+    last_seen: u64, // the frame it was last on screen
+}
+
+struct StreamState{
+    textures: Vec<StreamedTexture>,
+    paths: HashMap<PathBuf, StreamHandle>,
+    budget: LruBudget<StreamHandle>,
+    frame: u64,
+    wanted: Vec<StreamHandle>, // seen on screen, but not loaded yet
+
+    sender: Sender<Decoded>,
+    receiver: Receiver<Decoded>,
+    placeholder: Rc<Texture>,
+}
+
+/// # TextureStreamer
+///
+/// Loads textures when they first get shown, and unloads them when they haven't been shown for a while and the
+/// streamed textures are over budget. The renderer keeps one (`Renderer::streamer`), and cloning it gives
+/// another handle to the same textures, for components like `StreamedImage`.
+///
+/// Components call `mark_visible` every frame they're on screen. The streamer starts loading the textures that
+/// were asked for (reading them through the assets' `AssetSource`) and finishes off the ones that are ready when
+/// `Renderer::update_assets` runs. Textures seen in the last frame are never unloaded, even over budget.
+pub struct TextureStreamer{
+    state: Rc<RefCell<StreamState>>,
+}
+
+impl Clone for TextureStreamer{
+    fn clone(&self) -> Self{
+        Self{
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl TextureStreamer{
+    /// Create a streamer with no budget
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Self{
+        let (sender, receiver) = channel();
+        Self{
+            state: Rc::new(RefCell::new(StreamState{
+                textures: Vec::new(),
+                paths: HashMap::new(),
+                budget: LruBudget::new(),
+                frame: 0,
+                wanted: Vec::new(),
+
+                sender,
+                receiver,
+                placeholder: Rc::new(create_placeholder(device, queue)),
+            })),
+        }
+    }
+
+    /// Get a handle for a texture without loading it. Asking for the same path twice gives the same handle.
+    pub fn request<P: AsRef<Path>>(&self, path: P) -> StreamHandle{
+        let mut state = self.state.borrow_mut();
+        let path = path.as_ref().to_path_buf();
+        if let Some(handle) = state.paths.get(&path){
+            return *handle;
+        }
+
+        let handle = StreamHandle(state.textures.len());
+        state.textures.push(StreamedTexture{
+            path: path.clone(),
+            texture: None,
+            state: AssetState::Unloaded,
+            last_seen: 0,
+        });
+        state.paths.insert(path, handle);
+        handle
+    }
+
+    /// Say a texture is on screen this frame, so it gets loaded (if it isn't already) and kept
+    pub fn mark_visible(&self, handle: StreamHandle){
+        let mut state = self.state.borrow_mut();
+        let frame = state.frame;
+        let texture = &mut state.textures[handle.0];
+        texture.last_seen = frame;
+        if texture.state == AssetState::Unloaded{
+            texture.state = AssetState::Loading;
+            state.wanted.push(handle);
+        }
+        state.budget.touch(&handle);
+    }
+
+    /// Get a texture, if it's loaded
+    pub fn get_texture(&self, handle: StreamHandle) -> Option<Rc<Texture>>{
+        self.state.borrow().textures[handle.0].texture.clone()
+    }
+
+    /// The texture drawn while others are loading
+    pub fn get_placeholder(&self) -> Rc<Texture>{
+        self.state.borrow().placeholder.clone()
+    }
+
+    pub fn get_state(&self, handle: StreamHandle) -> AssetState{
+        self.state.borrow().textures[handle.0].state
+    }
+
+    /// Set how many bytes the loaded textures can take up, or `None` to keep everything loaded
+    pub fn set_budget(&self, budget: Option<u64>){
+        self.state.borrow_mut().budget.set_limit(budget);
+    }
+
+    pub fn get_budget(&self) -> Option<u64>{
+        self.state.borrow().budget.get_limit()
+    }
+
+    /// How many bytes the loaded textures take up
+    pub fn get_used(&self) -> u64{
+        self.state.borrow().budget.get_used()
+    }
+
+    /// True while any texture is being loaded
+    pub fn is_loading(&self) -> bool{
+        self.state.borrow().textures.iter().any(|texture| texture.state == AssetState::Loading)
+    }
+
+    /// Drop every texture after the device was lost, so they load again on the new one the next time they're on
+    /// screen
+    pub(crate) fn reload(&self, device: &wgpu::Device, queue: &wgpu::Queue){
+        let mut state = self.state.borrow_mut();
+        let state = &mut *state;
+        state.placeholder = Rc::new(create_placeholder(device, queue));
+        for (index, texture) in state.textures.iter_mut().enumerate(){
+            texture.texture = None;
+            state.budget.remove(&StreamHandle(index));
+            // Loads that are already going get uploaded to the new device when they finish
+            if texture.state != AssetState::Loading{
+                texture.state = AssetState::Unloaded;
+            }
+        }
+    }
+
+    /// Start loading the textures that were asked for, upload the ones that finished, and unload the ones that
+    /// haven't been seen for the longest if we're over budget. Returns true if any texture changed.
+    pub(crate) fn update(&self, device: &wgpu::Device, queue: &wgpu::Queue, source: &Arc<dyn AssetSource>) -> bool{
+        let mut state = self.state.borrow_mut();
+        let state = &mut *state;
+        let mut changed = false;
+
+        for handle in state.wanted.drain(..){
+            let path = state.textures[handle.0].path.clone();
+            let source = source.clone();
+            let sender = state.sender.clone();
+            std::thread::spawn(move || {
+                // The receiver only goes away with the streamer, at which point nobody cares about the result
+                let _ = sender.send((handle.0, decode_texture(source.as_ref(), &path)));
+            });
+        }
+
+        while let Ok((index, result)) = state.receiver.try_recv(){
+            changed = true;
+            let texture = &mut state.textures[index];
+            match result.and_then(|data| Texture::from_data(&data, &texture.path.to_string_lossy(), device, queue)){
+                Ok(uploaded) => {
+                    state.budget.insert(StreamHandle(index), uploaded.get_memory_size());
+                    texture.texture = Some(Rc::new(uploaded));
+                    texture.state = AssetState::Ready;
+                }
+                Err(e) => texture.state = AssetState::Failed(e),
+            }
+        }
+
+        // Anything seen last frame is still on screen, so it stays
+        let frame = state.frame;
+        let textures = &mut state.textures;
+        for handle in state.budget.evict_where(|handle| textures[handle.0].last_seen < frame){
+            let texture = &mut textures[handle.0];
+            texture.texture = None;
+            texture.state = AssetState::Unloaded;
+            changed = true;
+        }
+
+        state.frame += 1;
+        changed
+    }
+}
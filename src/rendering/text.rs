@@ -0,0 +1,411 @@
+//! This module picks how text in scaled layouts (see `Layout::set_content_scale`) gets drawn - stretched like the
+//! rest of the layout, rasterized again at the size it ends up on screen, or drawn from distance fields so it stays
+//! sharp at any size - and holds the
+//! rasterization options that help small text stay legible, and the `TextBrush` components queue their text with.
+
+use std::{borrow::Cow, hash::Hash};
+
+use cgmath::{Matrix4, Rad, Vector3};
+use glyph_brush::{BrushAction, BrushError, SectionGeometry};
+use wgpu::util::StagingBelt;
+use wgpu_glyph::{GlyphCruncher, GlyphPositioner, SectionGlyphIter, ab_glyph::{Font, FontArc}};
+
+use crate::theme::Stylesheet;
+
+use super::{glyphs::{GlyphInstance, GlyphPipeline}, sdf::{SdfAtlas, SdfGlyph}};
+
+/// # TextQuality
+///
+/// How the renderer draws text in layouts with a content scale. Set it with `Renderer::set_text_quality`.
+///
+/// `Stretched` draws glyphs at their unscaled size and stretches them along with the layout, which goes blurry
+/// when scaled up but never has to draw the glyphs again - good for animated zooms. `Crisp` draws the glyphs at
+/// the size they end up on screen, so they stay sharp at any scale, at the cost of caching every glyph again
+/// whenever the scale changes. Text in unscaled layouts looks the same either way with those two.
+///
+/// `Sdf` turns each glyph into a signed distance field once, and finds the edges of the glyphs from it at whatever
+/// size they end up, so text stays sharp through zooms without drawing glyphs again. Edges are a little softer and
+/// corners a little rounder than rasterized glyphs, especially on small text, and it doesn't use subpixel
+/// positioning.
+///
+/// They all lay text out with the same glyph brush, so the text API stays the same. Components queueing text
+/// themselves should queue it with `TextBrush::queue`, which scales it.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum TextQuality{
+    #[default]
+    Stretched,
+    Crisp,
+    Sdf,
+}
+
+/// # TextRasterOptions
+///
+/// How glyphs get rasterized, set with `Renderer::set_text_options` or from the `[text]` section of a theme's
+/// stylesheet (see `from_stylesheet`). The defaults suit most text, but small text (around 11 to 13 pixels) on
+/// standard DPI monitors can look blurry, which turning off subpixel positioning and turning on pixel snapping helps with.
+///
+/// Light text on dark backgrounds tends to look heavier than dark text on light ones, which `gamma` evens out.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TextRasterOptions{
+    /// Draw glyphs at fractions of a pixel, keeping the spacing between them even. Turned off, every glyph is drawn
+    /// at a whole pixel, which is sharper but can make the spacing uneven.
+    pub subpixel_positioning: bool,
+    /// Round the position and size of text to whole pixels, so the baseline and the stems of glyphs line up with
+    /// the pixels instead of getting smeared across two of them
+    pub pixel_snapping: bool,
+    /// The curve glyph coverage goes through in the glyph shader. Above 1 makes the edges of glyphs more solid, so
+    /// text looks heavier, and below 1 makes it lighter.
+    pub gamma: f32,
+    /// Light hinting, only up and down: stretch the text slightly so the height of its lowercase letters lands on
+    /// whole pixels, keeping their tops sharp. Widths don't change, so it doesn't move text around.
+    pub hinting: bool,
+}
+
+impl Default for TextRasterOptions{
+    fn default() -> TextRasterOptions{
+        Self{
+            subpixel_positioning: true,
+            pixel_snapping: false,
+            gamma: 1.0,
+            hinting: false,
+        }
+    }
+}
+
+impl TextRasterOptions{
+    /// Read the options from the `[text]` section of a stylesheet, using the defaults for anything missing:
+    ///
+    /// ```text
+    /// [text]
+    /// subpixel_positioning = false
+    /// pixel_snapping = true
+    /// gamma = 1.2
+    /// hinting = true
+    /// ```
+    pub fn from_stylesheet(stylesheet: &Stylesheet) -> Self{
+        let default = Self::default();
+        Self{
+            subpixel_positioning: stylesheet.get_bool("text", "subpixel_positioning").unwrap_or(default.subpixel_positioning),
+            pixel_snapping: stylesheet.get_bool("text", "pixel_snapping").unwrap_or(default.pixel_snapping),
+            gamma: stylesheet.get_f32("text", "gamma").unwrap_or(default.gamma),
+            hinting: stylesheet.get_bool("text", "hinting").unwrap_or(default.hinting),
+        }
+    }
+
+    // How far (in pixels) a glyph can move before it gets drawn again, rather than reusing the cached one
+    pub(crate) fn position_tolerance(&self) -> f32{
+        if self.subpixel_positioning { 0.1 } else { 1.0 }
+    }
+}
+
+/// # TextBrush
+///
+/// The glyph brush components queue their text with in `render_text`, and measure it with in `layout_text` (through
+/// `GlyphCruncher`, like any glyph brush). It also knows how the layout being drawn rasterizes its text, so `queue`
+/// scales each section to the size it ends up on screen with `TextQuality::Crisp`, and snaps it to whole pixels
+/// with `TextRasterOptions::pixel_snapping`. Text is always measured at its unscaled size.
+///
+/// It draws with its own pipeline, which is where the gamma of `TextRasterOptions` is applied.
+///
+/// Glyphs are drawn upright, unless a section is queued with `queue_rotated`. Every rotated section takes a draw
+/// call of its own (after the upright text of its layout), so they suit short text like labels.
+pub struct TextBrush{
+    glyphs: glyph_brush::GlyphBrush<GlyphInstance>,
+    pipeline: GlyphPipeline,
+    x_heights: Vec<Option<f32>>, // of each font, as a fraction of its height, for hinting
+    sdf: Option<SdfAtlas>, // made the first time there's text to draw with it
+    sdf_glyphs: Vec<SdfGlyph>, // laid out, waiting to be drawn
+    scale: f32, // what queued text gets scaled by, while a crisp layout's text is being queued
+    quality: TextQuality,
+    pixel_snapping: bool,
+    hinting: bool,
+    gamma: f32,
+    rotated: Vec<(wgpu_glyph::OwnedSection, f32, [f32; 2])>, // and the angle and pivot
+}
+
+impl TextBrush{
+    pub(crate) fn new(fonts: Vec<FontArc>, cache_size: (u32, u32), options: &TextRasterOptions, device: &wgpu::Device) -> Self{
+        Self{
+            x_heights: fonts.iter().map(x_height).collect(),
+            glyphs: glyph_brush::GlyphBrushBuilder::using_fonts(fonts)
+                .initial_cache_size(cache_size)
+                .draw_cache_position_tolerance(options.position_tolerance())
+                .build(),
+            pipeline: GlyphPipeline::new(device, cache_size, wgpu::include_spirv!("../../shaders/glyph.frag.spv"), "Glyph cache"),
+            sdf: None,
+            sdf_glyphs: Vec::new(),
+            scale: 1.0,
+            quality: TextQuality::default(),
+            pixel_snapping: false,
+            hinting: options.hinting,
+            gamma: options.gamma,
+            rotated: Vec::new(),
+        }
+    }
+
+    /// Queue a section of text to draw this frame, scaled, snapped and hinted for the layout it's in
+    pub fn queue(&mut self, section: wgpu_glyph::Section){
+        let section = self.raster_section(section);
+        if self.quality == TextQuality::Sdf{
+            let glyphs = self.lay_out_sdf(&section);
+            self.sdf_glyphs.extend(glyphs);
+        }else{
+            self.glyphs.queue(section);
+        }
+    }
+
+    /// Queue a section of text turned by an angle (in radians, clockwise) about a point, in the same space as its
+    /// position
+    pub fn queue_rotated(&mut self, section: wgpu_glyph::Section, angle: f32, pivot: [f32; 2]){
+        if angle == 0.0{
+            self.queue(section);
+            return;
+        }
+        let section = self.raster_section(section);
+        self.rotated.push((section.to_owned(), angle, [pivot[0] * self.scale, pivot[1] * self.scale]));
+    }
+
+    /// The fonts text can be drawn in, indexed by their `FontId`
+    pub fn fonts(&self) -> &[FontArc]{
+        self.glyphs.fonts()
+    }
+
+    /// The size of the texture glyphs are cached in, in pixels. It grows if a frame has more glyphs than fit.
+    pub fn cache_size(&self) -> (u32, u32){
+        self.pipeline.cache_size()
+    }
+
+    // The glyph brush can't replace fonts, only add them
+    pub(crate) fn add_font(&mut self, font: FontArc) -> wgpu_glyph::FontId{
+        self.x_heights.push(x_height(&font));
+        self.glyphs.add_font(font)
+    }
+
+    /// How much memory the glyph cache (and the SDF atlas, once there is one) takes up, in bytes
+    pub(crate) fn memory_size(&self) -> u64{
+        let (width, height) = self.cache_size();
+        width as u64 * height as u64 + self.sdf.as_ref().map_or(0, SdfAtlas::memory_size)
+    }
+
+    // Set how the next layout's text gets queued
+    pub(crate) fn set_raster(&mut self, scale: f32, pixel_snapping: bool, quality: TextQuality){
+        self.scale = scale;
+        self.pixel_snapping = pixel_snapping;
+        self.quality = quality;
+    }
+
+    // Pick up the options that don't need the glyph brush made again
+    pub(crate) fn set_options(&mut self, options: &TextRasterOptions){
+        self.hinting = options.hinting;
+        self.gamma = options.gamma;
+    }
+
+    // Scale and snap a section for the layout it's in, then hint it at the size it ends up
+    fn raster_section<'a>(&self, section: wgpu_glyph::Section<'a>) -> wgpu_glyph::Section<'a>{
+        let mut section = scale_section(section, self.scale, self.pixel_snapping);
+        if self.hinting{
+            for text in section.text.iter_mut(){
+                if let Some(Some(fraction)) = self.x_heights.get(text.font_id.0){
+                    text.scale.y = hinted_height(text.scale.y, *fraction);
+                }
+            }
+        }
+        section
+    }
+
+    // Lay a section out for drawing from the SDF atlas
+    fn lay_out_sdf(&mut self, section: &wgpu_glyph::Section) -> Vec<SdfGlyph>{
+        let bounds = section.layout.bounds_rect(&SectionGeometry{ screen_position: section.screen_position, bounds: section.bounds });
+        self.glyphs.glyphs(section).map(|glyph| {
+            let extra = &section.text[glyph.section_index].extra;
+            SdfGlyph{ font_id: glyph.font_id.0, glyph: glyph.glyph.clone(), color: extra.color, z: extra.z, bounds }
+        }).collect()
+    }
+
+    // Draw everything queued since the last draw into the view, then the rotated sections one at a time
+    pub(crate) fn draw(&mut self, device: &wgpu::Device, staging_belt: &mut StagingBelt, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView, transform: [f32; 16]){
+        if self.quality == TextQuality::Sdf{
+            let glyphs = std::mem::take(&mut self.sdf_glyphs);
+            self.draw_sdf(device, staging_belt, encoder, view, &glyphs, transform);
+            for (section, angle, pivot) in std::mem::take(&mut self.rotated){
+                let glyphs = self.lay_out_sdf(&section.to_borrowed());
+                self.draw_sdf(device, staging_belt, encoder, view, &glyphs, rotate_about(transform, angle, pivot));
+            }
+            return;
+        }
+        self.draw_queued(device, staging_belt, encoder, view, transform);
+        for (section, angle, pivot) in std::mem::take(&mut self.rotated){
+            self.glyphs.queue(section.to_borrowed());
+            self.draw_queued(device, staging_belt, encoder, view, rotate_about(transform, angle, pivot));
+        }
+    }
+
+    fn draw_sdf(&mut self, device: &wgpu::Device, staging_belt: &mut StagingBelt, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView, glyphs: &[SdfGlyph], transform: [f32; 16]){
+        if glyphs.is_empty(){
+            return;
+        }
+        let atlas = self.sdf.get_or_insert_with(|| SdfAtlas::new(device));
+        atlas.upload(device, staging_belt, encoder, self.glyphs.fonts(), glyphs);
+        atlas.draw(device, staging_belt, encoder, view, transform, self.gamma);
+    }
+
+    // Cache the queued glyphs (growing the cache if they don't fit) and draw them
+    fn draw_queued(&mut self, device: &wgpu::Device, staging_belt: &mut StagingBelt, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView, transform: [f32; 16]){
+        loop{
+            let pipeline = &mut self.pipeline;
+            let action = self.glyphs.process_queued(
+                |rect, data| pipeline.update_cache(device, encoder, rect, data),
+                GlyphInstance::from_vertex,
+            );
+            match action{
+                Ok(BrushAction::Draw(instances)) => {
+                    self.pipeline.upload(device, staging_belt, encoder, &instances);
+                    break;
+                }
+                // The last glyphs are still in the instance buffer
+                Ok(BrushAction::ReDraw) => break,
+                Err(BrushError::TextureTooSmall{ suggested }) => {
+                    let size = self.pipeline.grown_cache_size(suggested)
+                        .expect("The glyph cache is as big as it gets, and still too small for one frame of text");
+                    self.pipeline.resize_cache(device, size);
+                    self.glyphs.resize_texture(size.0, size.1);
+                }
+            }
+        }
+        self.pipeline.draw(device, staging_belt, encoder, view, transform, self.gamma);
+    }
+}
+
+impl GlyphCruncher for TextBrush{
+    fn glyphs_custom_layout<'a, 'b, S, L>(&'b mut self, section: S, custom_layout: &L) -> SectionGlyphIter<'b>
+    where
+        L: GlyphPositioner + Hash,
+        S: Into<Cow<'a, wgpu_glyph::Section<'a>>>,
+    {
+        self.glyphs.glyphs_custom_layout(section, custom_layout)
+    }
+
+    fn glyph_bounds_custom_layout<'a, S, L>(&mut self, section: S, custom_layout: &L) -> Option<wgpu_glyph::ab_glyph::Rect>
+    where
+        L: GlyphPositioner + Hash,
+        S: Into<Cow<'a, wgpu_glyph::Section<'a>>>,
+    {
+        self.glyphs.glyph_bounds_custom_layout(section, custom_layout)
+    }
+
+    fn fonts(&self) -> &[FontArc]{
+        self.glyphs.fonts()
+    }
+}
+
+// Turn a (column major) transform by `angle` radians about `pivot`, before it's applied
+fn rotate_about(transform: [f32; 16], angle: f32, pivot: [f32; 2]) -> [f32; 16]{
+    let columns = [0, 4, 8, 12].map(|i| [transform[i], transform[i + 1], transform[i + 2], transform[i + 3]]);
+    let pivot = Vector3::new(pivot[0], pivot[1], 0.0);
+    let rotated = Matrix4::from(columns) * Matrix4::from_translation(pivot) * Matrix4::from_angle_z(Rad(angle)) * Matrix4::from_translation(-pivot);
+    let columns: [[f32; 4]; 4] = rotated.into();
+    let mut transform = [0.0; 16];
+    for (i, column) in columns.iter().enumerate(){
+        transform[i * 4..i * 4 + 4].copy_from_slice(column);
+    }
+    transform
+}
+
+// How tall a font's lowercase letters are (going by the 'x'), as a fraction of the font's height, or `None` if it
+// doesn't have an 'x'
+fn x_height(font: &FontArc) -> Option<f32>{
+    let outline = font.outline(font.glyph_id('x'))?;
+    let height = font.height_unscaled();
+    if height > 0.0 { Some(outline.bounds.max.y / height) } else { None }
+}
+
+// The height to draw text `height` pixels high at, so its x-height (a `fraction` of its height) lands on whole
+// pixels, and never shrinks to nothing
+fn hinted_height(height: f32, fraction: f32) -> f32{
+    if fraction <= 0.0{
+        return height;
+    }
+    (height * fraction).round().max(1.0) / fraction
+}
+
+// Scale a section of text by `scale` (position, bounds and size), rounding the position and size to whole pixels
+// with `snap`
+fn scale_section(mut section: wgpu_glyph::Section, scale: f32, snap: bool) -> wgpu_glyph::Section{
+    if scale == 1.0 && !snap{
+        return section;
+    }
+    let round = |value: f32| if snap { value.round() } else { value };
+
+    section.screen_position = (round(section.screen_position.0 * scale), round(section.screen_position.1 * scale));
+    section.bounds = (section.bounds.0 * scale, section.bounds.1 * scale);
+    for text in section.text.iter_mut(){
+        // Never round down to nothing
+        let size = |value: f32| round(value * scale).max(1.0);
+        text.scale = wgpu_glyph::ab_glyph::PxScale{ x: size(text.scale.x), y: size(text.scale.y) };
+    }
+    section
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    fn section(position: (f32, f32), size: f32) -> wgpu_glyph::Section<'static>{
+        wgpu_glyph::Section {
+            screen_position: position,
+            bounds: (100.0, f32::INFINITY),
+            text: vec![wgpu_glyph::Text::new("text").with_scale(size)],
+            ..wgpu_glyph::Section::default()
+        }
+    }
+
+    /// Test that sections get scaled all over, and snapped to whole pixels only when asked
+    #[test]
+    fn scale_section_test(){
+        let scaled = scale_section(section((10.3, 20.0), 11.0), 1.5, false);
+        assert_eq!(scaled.screen_position, (10.3 * 1.5, 30.0));
+        assert_eq!(scaled.bounds, (150.0, f32::INFINITY));
+        assert_eq!(scaled.text[0].scale.y, 16.5);
+
+        let snapped = scale_section(section((10.3, 20.0), 11.0), 1.5, true);
+        assert_eq!(snapped.screen_position, (15.0, 30.0));
+        assert_eq!(snapped.text[0].scale.y, 17.0);
+
+        // Unscaled text is only snapped, and tiny text never disappears
+        let snapped = scale_section(section((10.6, 20.2), 0.2), 1.0, true);
+        assert_eq!(snapped.screen_position, (11.0, 20.0));
+        assert_eq!(snapped.text[0].scale.y, 1.0);
+        assert_eq!(scale_section(section((10.6, 20.2), 11.0), 1.0, false).screen_position, (10.6, 20.2));
+    }
+
+    /// Test that hinting puts the x-height on whole pixels, only moving the height a little
+    #[test]
+    fn hinted_height_test(){
+        // An x-height of half the height: 11px text has a 5.5px x-height, which goes up to 6
+        assert_eq!(hinted_height(11.0, 0.5), 12.0);
+        assert_eq!(hinted_height(10.0, 0.5), 10.0);
+        assert_eq!(hinted_height(10.6, 0.5), 10.0);
+        // Tiny text keeps a pixel of x-height, and fonts without one aren't hinted
+        assert_eq!(hinted_height(0.5, 0.5), 2.0);
+        assert_eq!(hinted_height(11.0, 0.0), 11.0);
+    }
+
+    /// Test that rotated text turns clockwise about its pivot (with y going down), before the transform
+    #[test]
+    fn rotate_about_test(){
+        let apply = |transform: [f32; 16], point: [f32; 2]| {
+            let x = transform[0] * point[0] + transform[4] * point[1] + transform[12];
+            let y = transform[1] * point[0] + transform[5] * point[1] + transform[13];
+            [(x * 1000.0).round() / 1000.0, (y * 1000.0).round() / 1000.0]
+        };
+        let identity = [1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0];
+        let quarter = rotate_about(identity, std::f32::consts::FRAC_PI_2, [10.0, 20.0]);
+        assert_eq!(apply(quarter, [10.0, 20.0]), [10.0, 20.0]);
+        assert_eq!(apply(quarter, [15.0, 20.0]), [10.0, 25.0]);
+
+        // The transform still applies afterwards
+        let doubled = [2.0, 0.0, 0.0, 0.0, 0.0, 2.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 0.0, 1.0];
+        let turned = rotate_about(doubled, std::f32::consts::PI, [0.0, 0.0]);
+        assert_eq!(apply(turned, [3.0, 4.0]), [-5.0, -8.0]);
+    }
+}
@@ -3,51 +3,159 @@
 //! and the various buffers/bind groups in a hashmap as a pool,
 //! to avoid reloading textures over and over.
 
-use std::{collections::HashMap, ops::Deref};
-use super::{Renderer, UniformUtils, render};
-use image::GenericImageView;
-use wgpu::BindGroup;
+use std::{cell::RefCell, collections::HashMap};
+use super::{Allocation, LruBudget, MemoryCategory, Renderer, TextureData, texture_data::{block_bytes, is_whole_blocks, level_size}};
 
 
+/// # TexturePool
+///
+/// Stores loaded textures by name, so components can share them.
+///
+/// The pool can be given a size limit with `set_limit`. Adding a texture that takes it over the limit removes
+/// the textures that were least recently added or fetched with `get_texture`, until it fits again. Textures
+/// put straight into `pool` aren't counted.
+///
+/// Textures added with `Renderer::add_texture` keep their image file, so they can be loaded again if the device is
+/// lost (see `Renderer::recover_device`). Other textures are dropped when that happens.
 pub struct TexturePool{
-    pub pool: HashMap<&'static str, wgpu::BindGroup>
+    pub pool: HashMap<String, Texture>,
+    budget: RefCell<LruBudget<String>>,
+    sources: HashMap<String, Vec<u8>>, // the image files of the textures added by the renderer
 }
 
 impl TexturePool{
     pub fn new() -> Self{
         Self{
-            pool: HashMap::<&'static str, wgpu::BindGroup>::new()
+            pool: HashMap::<String, Texture>::new(),
+            budget: RefCell::new(LruBudget::new()),
+            sources: HashMap::new(),
         }
     }
 
-    pub fn add_texture(&mut self, name: &'static str, texture: BindGroup){
+    pub fn add_texture<S: Into<String>>(&mut self, name: S, texture: Texture){
+        let name = name.into();
+        self.sources.remove(&name);
+        self.budget.get_mut().insert(name.clone(), texture.get_memory_size());
         self.pool.insert(name, texture);
+        self.evict();
     }
 
-    pub fn get_texture(&self, name: &'static str) -> Option<&BindGroup>{
+    // Add a texture along with the image file it was loaded from
+    pub(crate) fn add_texture_with_source(&mut self, name: String, texture: Texture, bytes: Vec<u8>){
+        self.add_texture(name.clone(), texture);
+        if self.pool.contains_key(&name){
+            self.sources.insert(name, bytes);
+        }
+    }
+
+    pub fn get_texture(&self, name: &str) -> Option<&Texture>{
+        self.budget.borrow_mut().touch(&name.to_string());
         self.pool.get(name)
     }
+
+    pub fn remove_texture(&mut self, name: &str) -> Option<Texture>{
+        self.budget.get_mut().remove(&name.to_string());
+        self.sources.remove(name);
+        self.pool.remove(name)
+    }
+
+    /// Set how many bytes of textures the pool can hold, or `None` for no limit
+    pub fn set_limit(&mut self, limit: Option<u64>){
+        self.budget.get_mut().set_limit(limit);
+        self.evict();
+    }
+
+    pub fn get_limit(&self) -> Option<u64>{
+        self.budget.borrow().get_limit()
+    }
+
+    /// How many bytes the textures in the pool take up
+    pub fn get_memory_size(&self) -> u64{
+        self.pool.values().map(Texture::get_memory_size).sum()
+    }
+
+    /// Load the textures again on a new device, after the old one was lost. The ones without an image file to
+    /// load them from are dropped.
+    pub(crate) fn reload(&mut self, device: &wgpu::Device, queue: &wgpu::Queue){
+        let names: Vec<String> = self.pool.keys().cloned().collect();
+        for name in names{
+            match self.sources.get(&name).and_then(|bytes| Texture::from_bytes(bytes, &name, device, queue).ok()){
+                Some(texture) => { self.pool.insert(name, texture); }
+                None => { self.remove_texture(&name); }
+            }
+        }
+    }
+
+    // Drop the least recently used textures until the pool fits in its limit
+    fn evict(&mut self){
+        for name in self.budget.get_mut().evict(){
+            self.pool.remove(&name);
+            self.sources.remove(&name);
+        }
+    }
 }
 
+/// # Texture
+///
+/// A texture on the GPU, along with the view, sampler and bind group needed to draw it.
 pub struct Texture{
-    texture: wgpu::Texture,
-    view: wgpu::TextureView,
-    sampler: wgpu::Sampler,
+    pub texture: wgpu::Texture,
+    pub view: wgpu::TextureView,
+    pub sampler: wgpu::Sampler,
+    pub size: (u32, u32),
+    pub format: wgpu::TextureFormat,
 
-    bind_group: wgpu::BindGroup,
-    bind_group_layout: wgpu::BindGroupLayout
+    pub bind_group: wgpu::BindGroup,
+    pub bind_group_layout: wgpu::BindGroupLayout,
+    memory: Allocation,
 }
 
 impl Texture{
-    pub fn from_path(path: &'static str, renderer: &Renderer) -> Self{
+    /// Load a texture from an image file
+    pub fn from_path(path: &str, renderer: &Renderer) -> Result<Self, &'static str>{
+        let bytes = std::fs::read(path).map_err(|_| "Error, failed to read image file!")?;
+        Texture::from_bytes(&bytes, path, &renderer.device, &renderer.queue)
+    }
 
-        let loaded_image = image::open(path).expect("image failed to load");
-        let rgba = loaded_image.as_rgba8().expect("Image failed to load/convert as RGBA8!");
-        let dimensions = loaded_image.dimensions();
+    /// Load a texture from the bytes of an image file - PNG, JPEG and the other formats the `image` crate supports,
+    /// or a KTX2 or DDS file (see `TextureData`)
+    pub fn from_bytes(bytes: &[u8], label: &str, device: &wgpu::Device, queue: &wgpu::Queue) -> Result<Self, &'static str>{
+        let data = TextureData::from_bytes(bytes)?;
+        Texture::from_data(&data, label, device, queue)
+    }
+
+    /// Create a texture from raw RGBA8 pixels
+    pub fn from_rgba(rgba: &[u8], dimensions: (u32, u32), label: &str, device: &wgpu::Device, queue: &wgpu::Queue) -> Self{
+        Texture::upload(wgpu::TextureFormat::Rgba8UnormSrgb, dimensions, &[rgba], label, device, queue)
+    }
 
-        let queue = renderer.queue;
-        let device = renderer.device;
+    /// Create a texture from `TextureData`. Compressed textures stay compressed on the GPU if it supports them
+    /// (the renderer asks for BC compression when the adapter has it). Otherwise they're decoded to RGBA, which
+    /// fails for the formats `TextureData::decode_rgba` can't decode.
+    ///
+    /// Compressed textures need a width and height that are multiples of 4 to stay compressed, and only the
+    /// mip levels at least 4 pixels across are kept.
+    pub fn from_data(data: &TextureData, label: &str, device: &wgpu::Device, queue: &wgpu::Queue) -> Result<Self, &'static str>{
+        if !data.is_compressed(){
+            let levels: Vec<&[u8]> = data.levels.iter().map(|level| level.as_slice()).collect();
+            return Ok(Texture::upload(data.format, data.size, &levels, label, device, queue));
+        }
+
+        let supported = device.features().contains(wgpu::Features::TEXTURE_COMPRESSION_BC);
+        if supported && is_whole_blocks(data.size){
+            // Copies have to be whole blocks, so the levels smaller than a block get left off
+            let levels: Vec<&[u8]> = data.levels.iter().enumerate()
+                .take_while(|(level, _)| is_whole_blocks(level_size(data.size, *level as u32)))
+                .map(|(_, level)| level.as_slice())
+                .collect();
+            Ok(Texture::upload(data.format, data.size, &levels, label, device, queue))
+        }else{
+            Ok(Texture::from_rgba(&data.decode_rgba()?, data.size, label, device, queue))
+        }
+    }
 
+    // Create a texture in some format, with its mip levels
+    fn upload(format: wgpu::TextureFormat, dimensions: (u32, u32), levels: &[&[u8]], label: &str, device: &wgpu::Device, queue: &wgpu::Queue) -> Self{
         let size = wgpu::Extent3d {
             width: dimensions.0,
             height: dimensions.1,
@@ -56,31 +164,68 @@ impl Texture{
 
         let texture = device.create_texture(
             &wgpu::TextureDescriptor {
-                label: Some("Image"),
+                label: Some(label),
                 size,
-                mip_level_count: 1,
+                mip_level_count: levels.len() as u32,
                 sample_count: 1,
                 dimension: wgpu::TextureDimension::D2,
-                format: wgpu::TextureFormat::Rgba8UnormSrgb,
+                format,
                 usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
             }
         );
 
-        queue.write_texture(
-            wgpu::TextureCopyView {
-                texture: &texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-            },
-            &rgba,
-            wgpu::TextureDataLayout {
-                offset: 0,
-                bytes_per_row: 4 * dimensions.0,
-                rows_per_image: dimensions.1,
-            },
-            size,
+        // Compressed rows are rows of 4x4 blocks
+        let block = block_bytes(format);
+        let block_width = if block == 4 { 1 } else { 4 };
+        for (level, data) in levels.iter().enumerate(){
+            let (width, height) = level_size(dimensions, level as u32);
+            queue.write_texture(
+                wgpu::TextureCopyView {
+                    texture: &texture,
+                    mip_level: level as u32,
+                    origin: wgpu::Origin3d::ZERO,
+                },
+                data,
+                wgpu::TextureDataLayout {
+                    offset: 0,
+                    bytes_per_row: block * (width / block_width),
+                    rows_per_image: height,
+                },
+                wgpu::Extent3d {
+                    width,
+                    height,
+                    depth: 1,
+                },
+            );
+        }
+        let memory_size = levels.iter().map(|level| level.len() as u64).sum();
+        Texture::wrap(texture, format, dimensions, memory_size, label, device)
+    }
+
+    /// Create a blank texture that can be drawn into as well as drawn with, like a layer that gets drawn onto the
+    /// screen afterwards
+    pub fn render_target(dimensions: (u32, u32), format: wgpu::TextureFormat, label: &str, device: &wgpu::Device) -> Self{
+        let texture = device.create_texture(
+            &wgpu::TextureDescriptor {
+                label: Some(label),
+                size: wgpu::Extent3d {
+                    width: dimensions.0,
+                    height: dimensions.1,
+                    depth: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::OUTPUT_ATTACHMENT,
+            }
         );
+        let memory_size = block_bytes(format) as u64 * dimensions.0 as u64 * dimensions.1 as u64;
+        Texture::wrap(texture, format, dimensions, memory_size, label, device)
+    }
 
+    // Make the view, sampler and bind group for a texture
+    fn wrap(texture: wgpu::Texture, format: wgpu::TextureFormat, dimensions: (u32, u32), memory_size: u64, label: &str, device: &wgpu::Device) -> Self{
         let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
         let sampler = device.create_sampler(
             &wgpu::SamplerDescriptor {
@@ -94,9 +239,96 @@ impl Texture{
             }
         );
 
-        todo!()
+        let bind_group_layout = Texture::create_bind_group_layout(device);
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                }
+            ],
+            label: Some(label),
+        });
+
+        Self{
+            texture,
+            view,
+            sampler,
+            size: dimensions,
+            format,
+            bind_group,
+            bind_group_layout,
+            memory: Allocation::new(MemoryCategory::Texture, memory_size),
+        }
+    }
+
+    /// How many bytes the texture takes up on the GPU
+    pub fn get_memory_size(&self) -> u64{
+        self.memory.bytes
+    }
+
+    /// Replace the pixels of the texture with new RGBA8 data, the same size as the texture
+    pub fn write_pixels(&self, rgba: &[u8], queue: &wgpu::Queue) -> Result<(), &'static str>{
+        if self.format != wgpu::TextureFormat::Rgba8UnormSrgb{
+            return Err("Error, only RGBA textures can have their pixels replaced!");
+        }
+        if rgba.len() != (4 * self.size.0 * self.size.1) as usize{
+            return Err("Error, pixel data doesn't match the size of the texture!");
+        }
+
+        queue.write_texture(
+            wgpu::TextureCopyView {
+                texture: &self.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+            },
+            rgba,
+            wgpu::TextureDataLayout {
+                offset: 0,
+                bytes_per_row: 4 * self.size.0,
+                rows_per_image: self.size.1,
+            },
+            wgpu::Extent3d {
+                width: self.size.0,
+                height: self.size.1,
+                depth: 1,
+            },
+        );
+        Ok(())
+    }
+
+    /// Create the layout textures are bound with - the texture at binding 0, and its sampler at binding 1
+    pub fn create_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout{
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStage::FRAGMENT,
+                    ty: wgpu::BindingType::SampledTexture {
+                        multisampled: false,
+                        dimension: wgpu::TextureViewDimension::D2,
+                        component_type: wgpu::TextureComponentType::Float,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStage::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler {
+                        comparison: false,
+                    },
+                    count: None,
+                },
+            ],
+            label: Some("Texture bind group layout"),
+        })
     }
 }
 
 /// This struct holds useful utilities to create textures.
-pub struct TextureUtils;
\ No newline at end of file
+pub struct TextureUtils;
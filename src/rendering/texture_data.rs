@@ -0,0 +1,359 @@
+//! This module reads texture files that are stored the way the GPU wants them - KTX2 and DDS files, usually
+//! block compressed with BC1 to BC7. Compressed textures take up a quarter (or less) of the memory of plain
+//! RGBA ones, which adds up quickly in UIs with lots of icons.
+//!
+//! When the GPU can't draw compressed textures, BC1 to BC5 get decoded to RGBA instead.
+
+use image::GenericImageView;
+
+const KTX2_MAGIC: [u8; 12] = [0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A];
+const DDS_MAGIC: [u8; 4] = *b"DDS ";
+
+/// # TextureData
+///
+/// The pixels of a texture, ready to upload - in a GPU format, with a mip chain if the file had one.
+/// Images loaded with the `image` crate become a single level of `Rgba8UnormSrgb` pixels.
+#[derive(Debug, Clone)]
+pub struct TextureData{
+    pub format: wgpu::TextureFormat,
+    pub size: (u32, u32),
+    /// The mip levels, from the full size image down
+    pub levels: Vec<Vec<u8>>,
+}
+
+impl TextureData{
+    /// Read a KTX2 or DDS file, or decode any other image file (PNG, JPEG and the other formats the `image`
+    /// crate supports) into RGBA pixels
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str>{
+        if bytes.starts_with(&KTX2_MAGIC){
+            TextureData::from_ktx2(bytes)
+        }else if bytes.starts_with(&DDS_MAGIC){
+            TextureData::from_dds(bytes)
+        }else{
+            let loaded_image = image::load_from_memory(bytes).map_err(|_| "Error, failed to load image!")?;
+            let size = loaded_image.dimensions();
+            Ok(TextureData::from_rgba(loaded_image.into_rgba8().into_raw(), size))
+        }
+    }
+
+    /// Wrap some RGBA8 pixels
+    pub fn from_rgba(rgba: Vec<u8>, size: (u32, u32)) -> Self{
+        Self{
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            size,
+            levels: vec![rgba],
+        }
+    }
+
+    /// Read a KTX2 file. Supercompressed files (Basis Universal, Zstandard) aren't supported.
+    pub fn from_ktx2(bytes: &[u8]) -> Result<Self, &'static str>{
+        if !bytes.starts_with(&KTX2_MAGIC){
+            return Err("Error, not a KTX2 file!");
+        }
+        let format = ktx2_format(read_u32(bytes, 12)?).ok_or("Error, unsupported KTX2 texture format!")?;
+        let size = (read_u32(bytes, 20)?, read_u32(bytes, 24)?);
+        if read_u32(bytes, 28)? > 1 || read_u32(bytes, 32)? > 1 || read_u32(bytes, 36)? != 1{
+            return Err("Error, only 2D KTX2 textures are supported!");
+        }
+        if read_u32(bytes, 44)? != 0{
+            return Err("Error, supercompressed KTX2 textures aren't supported!");
+        }
+
+        // The level index comes straight after the header, biggest level first
+        let level_count = read_u32(bytes, 40)?.max(1) as usize;
+        let mut levels = Vec::with_capacity(level_count);
+        for level in 0..level_count{
+            let entry = 80 + level * 24;
+            let offset = read_u64(bytes, entry)? as usize;
+            let length = read_u64(bytes, entry + 8)? as usize;
+            let data = bytes.get(offset..offset + length).ok_or("Error, KTX2 file is too short!")?;
+            levels.push(data.to_vec());
+        }
+        TextureData::checked(format, size, levels)
+    }
+
+    /// Read a DDS file, with either the old FourCC formats (DXT1 to DXT5, ATI1 and ATI2) or a DX10 header
+    pub fn from_dds(bytes: &[u8]) -> Result<Self, &'static str>{
+        if !bytes.starts_with(&DDS_MAGIC){
+            return Err("Error, not a DDS file!");
+        }
+        let size = (read_u32(bytes, 16)?, read_u32(bytes, 12)?);
+        let level_count = read_u32(bytes, 28)?.max(1) as usize;
+
+        let four_cc = bytes.get(84..88).ok_or("Error, DDS file is too short!")?;
+        let (format, mut offset) = match four_cc{
+            b"DX10" => (dxgi_format(read_u32(bytes, 128)?).ok_or("Error, unsupported DDS texture format!")?, 148),
+            four_cc => (four_cc_format(four_cc).ok_or("Error, unsupported DDS texture format!")?, 128),
+        };
+
+        // The levels are stored one after another, so their sizes have to be worked out
+        let mut levels = Vec::with_capacity(level_count);
+        for level in 0..level_count{
+            let length = level_bytes(format, level_size(size, level as u32)) as usize;
+            let data = bytes.get(offset..offset + length).ok_or("Error, DDS file is too short!")?;
+            levels.push(data.to_vec());
+            offset += length;
+        }
+        TextureData::checked(format, size, levels)
+    }
+
+    /// True if the pixels are block compressed
+    pub fn is_compressed(&self) -> bool{
+        block_bytes(self.format) != 4
+    }
+
+    /// How many bytes all the levels add up to
+    pub fn get_memory_size(&self) -> u64{
+        self.levels.iter().map(|level| level.len() as u64).sum()
+    }
+
+    /// Decode the full size level into RGBA8 pixels. BC6H and BC7, and the signed BC4 and BC5 formats,
+    /// can't be decoded.
+    pub fn decode_rgba(&self) -> Result<Vec<u8>, &'static str>{
+        use wgpu::TextureFormat::*;
+
+        let level = &self.levels[0];
+        if !self.is_compressed(){
+            return Ok(level.clone());
+        }
+        let decode_block: fn(&[u8]) -> [[u8; 4]; 16] = match self.format{
+            Bc1RgbaUnorm | Bc1RgbaUnormSrgb => |block| decode_bc1(block, true),
+            Bc2RgbaUnorm | Bc2RgbaUnormSrgb => decode_bc2,
+            Bc3RgbaUnorm | Bc3RgbaUnormSrgb => decode_bc3,
+            Bc4RUnorm => decode_bc4,
+            Bc5RgUnorm => decode_bc5,
+            _ => return Err("Error, this texture format can't be decoded, and the GPU doesn't support it!"),
+        };
+
+        let (width, height) = self.size;
+        let block_size = block_bytes(self.format) as usize;
+        let blocks_wide = width.div_ceil(4) as usize;
+        let mut rgba = vec![0u8; 4 * width as usize * height as usize];
+        for (i, block) in level.chunks_exact(block_size).enumerate(){
+            let (bx, by) = (i % blocks_wide * 4, i / blocks_wide * 4);
+            for (p, pixel) in decode_block(block).iter().enumerate(){
+                let (x, y) = (bx + p % 4, by + p / 4);
+                // Blocks on the right and bottom edges can hang off the image
+                if x < width as usize && y < height as usize{
+                    let at = 4 * (y * width as usize + x);
+                    rgba[at..at + 4].copy_from_slice(pixel);
+                }
+            }
+        }
+        Ok(rgba)
+    }
+
+    // Make sure the levels are as big as the format and size say they should be
+    fn checked(format: wgpu::TextureFormat, size: (u32, u32), levels: Vec<Vec<u8>>) -> Result<Self, &'static str>{
+        if size.0 == 0 || size.1 == 0{
+            return Err("Error, texture has no pixels!");
+        }
+        for (level, data) in levels.iter().enumerate(){
+            if data.len() as u64 != level_bytes(format, level_size(size, level as u32)){
+                return Err("Error, texture level is the wrong size!");
+            }
+        }
+        Ok(Self{
+            format,
+            size,
+            levels,
+        })
+    }
+}
+
+// How many bytes a 4x4 block (or, for uncompressed formats, a pixel) takes up
+pub(crate) fn block_bytes(format: wgpu::TextureFormat) -> u32{
+    use wgpu::TextureFormat::*;
+
+    match format{
+        Bc1RgbaUnorm | Bc1RgbaUnormSrgb | Bc4RUnorm | Bc4RSnorm => 8,
+        Bc2RgbaUnorm | Bc2RgbaUnormSrgb | Bc3RgbaUnorm | Bc3RgbaUnormSrgb | Bc5RgUnorm | Bc5RgSnorm
+        | Bc6hRgbUfloat | Bc6hRgbSfloat | Bc7RgbaUnorm | Bc7RgbaUnormSrgb => 16,
+        _ => 4,
+    }
+}
+
+// True if a size is made of whole 4x4 blocks
+pub(crate) fn is_whole_blocks(size: (u32, u32)) -> bool{
+    size.0 & 3 == 0 && size.1 & 3 == 0
+}
+
+// The size of a mip level in pixels
+pub(crate) fn level_size(size: (u32, u32), level: u32) -> (u32, u32){
+    ((size.0 >> level).max(1), (size.1 >> level).max(1))
+}
+
+// How many bytes a level of some size takes up
+fn level_bytes(format: wgpu::TextureFormat, size: (u32, u32)) -> u64{
+    let block = block_bytes(format) as u64;
+    if block == 4{
+        4 * size.0 as u64 * size.1 as u64
+    }else{
+        block * (size.0 as u64).div_ceil(4) * (size.1 as u64).div_ceil(4)
+    }
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> Result<u32, &'static str>{
+    let data = bytes.get(at..at + 4).ok_or("Error, texture file is too short!")?;
+    Ok(u32::from_le_bytes([data[0], data[1], data[2], data[3]]))
+}
+
+fn read_u64(bytes: &[u8], at: usize) -> Result<u64, &'static str>{
+    Ok(read_u32(bytes, at)? as u64 | (read_u32(bytes, at + 4)? as u64) << 32)
+}
+
+// Vulkan format numbers, used by KTX2
+fn ktx2_format(vk_format: u32) -> Option<wgpu::TextureFormat>{
+    use wgpu::TextureFormat::*;
+
+    Some(match vk_format{
+        37 => Rgba8Unorm,
+        43 => Rgba8UnormSrgb,
+        131 | 133 => Bc1RgbaUnorm,
+        132 | 134 => Bc1RgbaUnormSrgb,
+        135 => Bc2RgbaUnorm,
+        136 => Bc2RgbaUnormSrgb,
+        137 => Bc3RgbaUnorm,
+        138 => Bc3RgbaUnormSrgb,
+        139 => Bc4RUnorm,
+        140 => Bc4RSnorm,
+        141 => Bc5RgUnorm,
+        142 => Bc5RgSnorm,
+        143 => Bc6hRgbUfloat,
+        144 => Bc6hRgbSfloat,
+        145 => Bc7RgbaUnorm,
+        146 => Bc7RgbaUnormSrgb,
+        _ => return None,
+    })
+}
+
+// DXGI format numbers, used by DDS files with a DX10 header
+fn dxgi_format(dxgi_format: u32) -> Option<wgpu::TextureFormat>{
+    use wgpu::TextureFormat::*;
+
+    Some(match dxgi_format{
+        28 => Rgba8Unorm,
+        29 => Rgba8UnormSrgb,
+        71 => Bc1RgbaUnorm,
+        72 => Bc1RgbaUnormSrgb,
+        74 => Bc2RgbaUnorm,
+        75 => Bc2RgbaUnormSrgb,
+        77 => Bc3RgbaUnorm,
+        78 => Bc3RgbaUnormSrgb,
+        80 => Bc4RUnorm,
+        81 => Bc4RSnorm,
+        83 => Bc5RgUnorm,
+        84 => Bc5RgSnorm,
+        95 => Bc6hRgbUfloat,
+        96 => Bc6hRgbSfloat,
+        98 => Bc7RgbaUnorm,
+        99 => Bc7RgbaUnormSrgb,
+        _ => return None,
+    })
+}
+
+// The formats older DDS files name with four letters
+fn four_cc_format(four_cc: &[u8]) -> Option<wgpu::TextureFormat>{
+    use wgpu::TextureFormat::*;
+
+    Some(match four_cc{
+        b"DXT1" => Bc1RgbaUnorm,
+        b"DXT2" | b"DXT3" => Bc2RgbaUnorm,
+        b"DXT4" | b"DXT5" => Bc3RgbaUnorm,
+        b"ATI1" | b"BC4U" => Bc4RUnorm,
+        b"BC4S" => Bc4RSnorm,
+        b"ATI2" | b"BC5U" => Bc5RgUnorm,
+        b"BC5S" => Bc5RgSnorm,
+        _ => return None,
+    })
+}
+
+// Turn a 5:6:5 colour into 8 bits per channel
+fn unpack_565(color: u16) -> [u8; 3]{
+    let (r, g, b) = ((color >> 11) & 31, (color >> 5) & 63, color & 31);
+    [(r * 255 / 31) as u8, (g * 255 / 63) as u8, (b * 255 / 31) as u8]
+}
+
+// The colour part of a block, shared by BC1 to BC3. BC2 and BC3 always use four colours.
+fn decode_bc1(block: &[u8], allow_transparent: bool) -> [[u8; 4]; 16]{
+    let c0 = u16::from_le_bytes([block[0], block[1]]);
+    let c1 = u16::from_le_bytes([block[2], block[3]]);
+    let (a, b) = (unpack_565(c0), unpack_565(c1));
+    let mix = |wa: u16, wb: u16, total: u16| {
+        let channel = |i: usize| ((a[i] as u16 * wa + b[i] as u16 * wb) / total) as u8;
+        [channel(0), channel(1), channel(2), 255]
+    };
+
+    let palette = if c0 > c1 || !allow_transparent{
+        [[a[0], a[1], a[2], 255], [b[0], b[1], b[2], 255], mix(2, 1, 3), mix(1, 2, 3)]
+    }else{
+        [[a[0], a[1], a[2], 255], [b[0], b[1], b[2], 255], mix(1, 1, 2), [0, 0, 0, 0]]
+    };
+
+    let indices = u32::from_le_bytes([block[4], block[5], block[6], block[7]]);
+    let mut pixels = [[0u8; 4]; 16];
+    for (i, pixel) in pixels.iter_mut().enumerate(){
+        *pixel = palette[(indices >> (2 * i) & 3) as usize];
+    }
+    pixels
+}
+
+// Explicit 4 bit alpha, then a BC1 colour block
+fn decode_bc2(block: &[u8]) -> [[u8; 4]; 16]{
+    let mut pixels = decode_bc1(&block[8..], false);
+    for (i, pixel) in pixels.iter_mut().enumerate(){
+        let alpha = (block[i / 2] >> (4 * (i % 2))) & 15;
+        pixel[3] = alpha * 17;
+    }
+    pixels
+}
+
+// Interpolated alpha, then a BC1 colour block
+fn decode_bc3(block: &[u8]) -> [[u8; 4]; 16]{
+    let mut pixels = decode_bc1(&block[8..], false);
+    for (pixel, alpha) in pixels.iter_mut().zip(decode_channel(&block[..8]).iter()){
+        pixel[3] = *alpha;
+    }
+    pixels
+}
+
+fn decode_bc4(block: &[u8]) -> [[u8; 4]; 16]{
+    let mut pixels = [[0, 0, 0, 255]; 16];
+    for (pixel, red) in pixels.iter_mut().zip(decode_channel(block).iter()){
+        pixel[0] = *red;
+    }
+    pixels
+}
+
+fn decode_bc5(block: &[u8]) -> [[u8; 4]; 16]{
+    let mut pixels = decode_bc4(&block[..8]);
+    for (pixel, green) in pixels.iter_mut().zip(decode_channel(&block[8..]).iter()){
+        pixel[1] = *green;
+    }
+    pixels
+}
+
+// One interpolated 8 bit channel - the alpha of BC3, and the channels of BC4 and BC5
+fn decode_channel(block: &[u8]) -> [u8; 16]{
+    let (e0, e1) = (block[0] as u16, block[1] as u16);
+    let mut palette = [e0 as u8, e1 as u8, 0, 0, 0, 0, 0, 255];
+    if e0 > e1{
+        for i in 1..7{
+            palette[i + 1] = (((7 - i as u16) * e0 + i as u16 * e1) / 7) as u8;
+        }
+    }else{
+        for i in 1..5{
+            palette[i + 1] = (((5 - i as u16) * e0 + i as u16 * e1) / 5) as u8;
+        }
+    }
+
+    let mut indices = 0u64;
+    for (i, byte) in block[2..8].iter().enumerate(){
+        indices |= (*byte as u64) << (8 * i);
+    }
+    let mut values = [0u8; 16];
+    for (i, value) in values.iter_mut().enumerate(){
+        *value = palette[(indices >> (3 * i) & 7) as usize];
+    }
+    values
+}
@@ -0,0 +1,57 @@
+//! This module contains the `Tint` struct, which defines the colour a quad gets drawn with.
+//! It works just like a `Transform`, but is bound to the fragment shader instead.
+
+use wgpu::{BindGroup, Device, ShaderStage};
+
+use super::{Allocation, UniformUtils};
+
+/// # Tint
+///
+/// Stores the colour of a quad, as well as the uniform buffer and bind group used to draw it.
+///
+/// After changing the colour, call `write_buffer` so the GPU gets the new value.
+pub struct Tint{
+    pub color: [f32; 4],
+    uniform: TintUniform,
+    buffer: wgpu::Buffer,
+    pub bind_group: BindGroup,
+    _memory: Allocation,
+}
+
+impl Tint{
+    /// Create a new tint from an RGBA colour
+    pub fn new(color: [f32; 4], device: &Device) -> Self{
+        let uniform = TintUniform{ color };
+        let (buffer, bind_group, _) = UniformUtils::create(device, ShaderStage::FRAGMENT, 0, &uniform, "Tint");
+
+        Self{
+            color,
+            uniform,
+            buffer,
+            bind_group,
+            _memory: Allocation::uniform_buffer::<TintUniform>(),
+        }
+    }
+
+    /// Change the colour. This won't show until `write_buffer` is called.
+    pub fn set(&mut self, color: [f32; 4]){
+        self.color = color;
+    }
+
+    /// Write the colour into the uniform buffer, if it has changed
+    pub fn write_buffer(&mut self, queue: &wgpu::Queue){
+        if self.uniform.color != self.color{
+            self.uniform.color = self.color;
+            queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(&[self.uniform]));
+        }
+    }
+}
+
+
+// We need this for Rust to store our data correctly for the shaders
+#[repr(C)]
+// This is so we can store this in a buffer
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct TintUniform{
+    color: [f32; 4],
+}
@@ -5,7 +5,7 @@ use wgpu::{BindGroup, Device, ShaderStage};
 
 use cgmath::SquareMatrix;
 
-use super::UniformUtils;
+use super::{Allocation, UniformUtils};
 
 
 #[rustfmt::skip]
@@ -16,19 +16,28 @@ pub const OPENGL_TO_WGPU_MATRIX: cgmath::Matrix4<f32> = cgmath::Matrix4::new(
     0.0, 0.0, 0.5, 1.0,
 );
 
+/// # Transform
+///
+/// A position, rotation and scale, combined into the matrix things are drawn with. The scale is applied first, then
+/// the rotation, then the position. `screen_scale` goes between the rotation and the position, and is 1 unless set -
+/// with the scale in pixels and `screen_scale` turning pixels into screen space, rotated things keep their shape in
+/// windows that aren't square.
 pub struct Transform{
     pub position: cgmath::Vector3::<f32>,
     pub rotation: cgmath::Quaternion::<f32>,
     pub scale: cgmath::Vector3::<f32>,
+    pub screen_scale: cgmath::Vector3::<f32>,
     value: cgmath::Matrix4::<f32>,
     uniform: TransformUniform,
     buffer: wgpu::Buffer,
     pub bind_group: BindGroup,
+    _memory: Allocation,
 }
 impl Transform{
     /// Create a new transform. Takes in the position, rotation and scale values.
     pub fn new(position: cgmath::Vector3::<f32>, rotation: cgmath::Quaternion::<f32>, scale: cgmath::Vector3::<f32>, device: &Device) -> Self{
-        let value: cgmath::Matrix4<f32> = cgmath::Matrix4::from_translation(position) * cgmath::Matrix4::from(rotation) * cgmath::Matrix4::from_nonuniform_scale(scale.x, scale.y, scale.z);
+        let screen_scale = cgmath::Vector3::<f32>::new(1.0, 1.0, 1.0);
+        let value = Transform::matrix(position, rotation, scale, screen_scale);
         let mut uniform = TransformUniform::new();
         uniform.update(value);
 
@@ -38,19 +47,28 @@ impl Transform{
             position,
             rotation,
             scale,
+            screen_scale,
             value,
             uniform,
             buffer,
-            bind_group
+            bind_group,
+            _memory: Allocation::uniform_buffer::<TransformUniform>(),
         }
     }
 
     /// Force an update to the transformation matrix. This is implicitly called when rendering, so is only necessary if you need to use it before a frame is drawn.
     pub fn update(&mut self){
-        self.value = cgmath::Matrix4::from_translation(self.position) * cgmath::Matrix4::from(self.rotation) * cgmath::Matrix4::from_nonuniform_scale(self.scale.x, self.scale.y, self.scale.z);
+        self.value = Transform::matrix(self.position, self.rotation, self.scale, self.screen_scale);
         self.uniform.update(self.value);
     }
 
+    fn matrix(position: cgmath::Vector3::<f32>, rotation: cgmath::Quaternion::<f32>, scale: cgmath::Vector3::<f32>, screen_scale: cgmath::Vector3::<f32>) -> cgmath::Matrix4<f32>{
+        cgmath::Matrix4::from_translation(position)
+            * cgmath::Matrix4::from_nonuniform_scale(screen_scale.x, screen_scale.y, screen_scale.z)
+            * cgmath::Matrix4::from(rotation)
+            * cgmath::Matrix4::from_nonuniform_scale(scale.x, scale.y, scale.z)
+    }
+
     pub fn get_buffer(&mut self, device: &Device) -> &wgpu::Buffer{
         let value: [[f32; 4]; 4] = self.value.into();
 
@@ -63,6 +81,14 @@ impl Transform{
 
         &self.buffer
     }
+
+    /// Update the transformation matrix and write it into the existing uniform buffer.
+    ///
+    /// Unlike `get_buffer`, this doesn't recreate the buffer, so the bind group stays valid.
+    pub fn write_buffer(&mut self, queue: &wgpu::Queue){
+        self.update();
+        queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(&[self.uniform]));
+    }
 }
 
 
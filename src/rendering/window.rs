@@ -16,6 +16,8 @@ use winit::platform::windows::EventLoopExtWindows;
 
 use winit::event::Event;
 
+use super::{CursorControl, CursorGrab};
+
 /// # Window
 ///
 /// This struct contains information for the window used in a GUI application
@@ -32,6 +34,9 @@ pub struct Window{
     pub window: window::Window,
     pub event_loop: Option<event_loop::EventLoop<()>>,
     pub event_callback_handler: Option<Box<dyn Fn(&Event<()>, &mut window::Window, &mut crate::rendering::Renderer) -> ()>>,
+    /// Whether the renderer should wait for the display before showing a frame. The GUI passes this on to the renderer.
+    pub vsync: bool,
+    cursor: CursorControl,
 }
 
 
@@ -49,6 +54,25 @@ impl Window{
     pub fn set_event_handler(&mut self, event_handler: Box<dyn Fn(&Event<()>, &mut window::Window, &mut crate::rendering::Renderer) -> ()>){
         self.event_callback_handler = Some(event_handler);
     }
+
+    /// Keep the cursor inside the window (or let it go). This gets undone while the window doesn't have focus or a
+    /// modal layout is open, and put back afterwards.
+    pub fn set_cursor_grab(&mut self, grab: CursorGrab) -> Result<(), &'static str>{
+        self.cursor.set_grab(grab);
+        self.cursor.apply(&self.window)
+    }
+
+    /// Show or hide the cursor over the window. Like grabbing, the cursor is shown again while the window
+    /// doesn't have focus or a modal layout is open.
+    pub fn set_cursor_visible(&mut self, visible: bool){
+        self.cursor.set_visible(visible);
+        let _ = self.cursor.apply(&self.window);
+    }
+
+    /// Get a handle to change the cursor while the GUI is running
+    pub fn get_cursor_control(&self) -> CursorControl{
+        self.cursor.clone()
+    }
 }
 
 /// # WindowBuilder
@@ -143,16 +167,6 @@ impl WindowBuilder{
         let mut video_modes: Vec<monitor::VideoMode> = monitor.video_modes().filter(|_| if x == 0 { x += 1; true }else{ false }).collect();
         let video_modes = video_modes.swap_remove(0);
 
-        // Vsync mode - refresh rate
-        let _vsync_mode = match self.vsync{
-            true => {
-                wgpu::PresentMode::Fifo
-            }
-            false => {
-                wgpu::PresentMode::Mailbox
-            }
-        };
-
         // Check if we're running fullscreen and/or set resolutions
         let winit_builder = match self.screen_mode{
             ScreenMode::Fullscreen => {
@@ -172,6 +186,8 @@ impl WindowBuilder{
             window: winit_builder.with_resizable(self.resizeable).with_decorations(self.decorations).with_title(&self.title).build(&mut event_loop).expect("Failed to build window!"),
             event_loop: Some(event_loop),
             event_callback_handler: Some(Box::new(Window::default_event_callback)),
+            vsync: self.vsync,
+            cursor: CursorControl::new(),
         })
         
     }
@@ -192,16 +208,6 @@ impl WindowBuilder{
         let mut video_modes: Vec<monitor::VideoMode> = monitor.video_modes().filter(|_| if x == 0 { x += 1; true }else{ false }).collect();
         let video_modes = video_modes.swap_remove(0);
 
-        // Vsync mode - refresh rate
-        let _vsync_mode = match self.vsync{
-            true => {
-                wgpu::PresentMode::Fifo
-            }
-            false => {
-                wgpu::PresentMode::Mailbox
-            }
-        };
-
         // Check if we're running fullscreen and/or set resolutions
         let winit_builder = match self.screen_mode{
             ScreenMode::Fullscreen => {
@@ -221,6 +227,8 @@ impl WindowBuilder{
             window: winit_builder.with_resizable(self.resizeable).with_decorations(self.decorations).with_title(&self.title).build(&mut event_loop).expect("Failed to build window!"),
             event_loop: Some(event_loop),
             event_callback_handler: Some(Box::new(Window::default_event_callback)),
+            vsync: self.vsync,
+            cursor: CursorControl::new(),
         })
         
     }
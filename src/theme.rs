@@ -0,0 +1,266 @@
+//! Themes let an application be reskinned without changing any code. A theme bundle is a
+//! directory (or a zip archive, with the `zip` feature) holding a stylesheet, fonts and textures,
+//! which all get registered with the renderer in one call.
+//!
+//! A bundle is laid out like this:
+//!
+//! ```text
+//! my_theme/
+//!     theme.style        <- the stylesheet
+//!     fonts/*.ttf        <- fonts, named by their file name (without the extension)
+//!     textures/*.png     <- textures, named by their path inside textures/ (without the extension)
+//! ```
+//!
+//! Bundles can also be embedded in the binary, see `ThemeBundle::from_source`.
+
+use std::{collections::HashMap, path::Path};
+
+use crate::rendering::{AssetSource, FileSource, Renderer, TextRasterOptions};
+
+/// # Stylesheet
+///
+/// A simple stylesheet made of sections and properties:
+///
+/// ```text
+/// # Comments start with a hash
+/// [button]
+/// color = #3366ccff
+/// text_size = 24
+///
+/// [textures.button]
+/// nine_slice = 8, 8, 8, 8
+/// ```
+///
+/// Properties before the first section go in the section named `""`.
+pub struct Stylesheet{
+    sections: HashMap<String, HashMap<String, String>>,
+}
+
+impl Stylesheet{
+    /// Create an empty stylesheet
+    pub fn new() -> Self{
+        Self{
+            sections: HashMap::new(),
+        }
+    }
+
+    /// Parse a stylesheet from text
+    pub fn parse(text: &str) -> Result<Self, &'static str>{
+        let mut sections = HashMap::<String, HashMap<String, String>>::new();
+        let mut section = String::new();
+
+        for line in text.lines(){
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#'){
+                continue;
+            }
+
+            if line.starts_with('['){
+                if !line.ends_with(']'){
+                    return Err("Error, unclosed section in stylesheet!");
+                }
+                section = line[1..line.len() - 1].trim().to_string();
+                continue;
+            }
+
+            let mut parts = line.splitn(2, '=');
+            let key = parts.next().unwrap().trim();
+            let value = parts.next().ok_or("Error, expected `key = value` in stylesheet!")?.trim();
+            if key.is_empty(){
+                return Err("Error, empty property name in stylesheet!");
+            }
+            sections.entry(section.clone()).or_default().insert(key.to_string(), value.to_string());
+        }
+
+        Ok(Self{
+            sections,
+        })
+    }
+
+    /// Get a property as text
+    pub fn get(&self, section: &str, key: &str) -> Option<&str>{
+        self.sections.get(section)?.get(key).map(|value| value.as_str())
+    }
+
+    /// Get a property as a number
+    pub fn get_f32(&self, section: &str, key: &str) -> Option<f32>{
+        self.get(section, key)?.parse().ok()
+    }
+
+    /// Get a property as a flag, written as `true`/`false`, `yes`/`no`, `on`/`off` or `1`/`0`
+    pub fn get_bool(&self, section: &str, key: &str) -> Option<bool>{
+        match self.get(section, key)?.to_ascii_lowercase().as_str(){
+            "true" | "yes" | "on" | "1" => Some(true),
+            "false" | "no" | "off" | "0" => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Get a list of numbers, separated by commas
+    pub fn get_f32_list(&self, section: &str, key: &str) -> Option<Vec<f32>>{
+        self.get(section, key)?.split(',').map(|value| value.trim().parse().ok()).collect()
+    }
+
+    /// Get a colour. Colours can be written as hex (`#rrggbb` or `#rrggbbaa`) or as 3 or 4 numbers
+    /// from 0 to 1, separated by commas.
+    pub fn get_color(&self, section: &str, key: &str) -> Option<[f32; 4]>{
+        parse_color(self.get(section, key)?)
+    }
+
+    /// Get a property for a component with some classes (see `Layout::add_class`), from the sections named
+    /// after them (like `[.danger-button]`). Later classes win when more than one has the property.
+    pub fn get_for_classes<S: AsRef<str>>(&self, classes: &[S], key: &str) -> Option<&str>{
+        classes.iter().rev().find_map(|class| self.get(&format!(".{}", class.as_ref()), key))
+    }
+
+    /// Set a property, replacing any old value
+    pub fn set<S: Into<String>>(&mut self, section: &str, key: &str, value: S){
+        self.sections.entry(section.to_string()).or_default().insert(key.to_string(), value.into());
+    }
+
+    /// Add all the properties from another stylesheet, overwriting ours where both have a value
+    pub fn merge(&mut self, other: &Stylesheet){
+        for (section, properties) in other.sections.iter(){
+            let ours = self.sections.entry(section.clone()).or_default();
+            for (key, value) in properties.iter(){
+                ours.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// Parse a colour written as `#rrggbb`, `#rrggbbaa` or `r, g, b[, a]` (from 0 to 1)
+pub fn parse_color(value: &str) -> Option<[f32; 4]>{
+    let value = value.trim();
+    if let Some(hex) = value.strip_prefix('#'){
+        if (hex.len() != 6 && hex.len() != 8) || !hex.is_ascii(){
+            return None;
+        }
+        let mut color = [1.0; 4];
+        for i in 0..hex.len() / 2{
+            color[i] = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()? as f32 / 255.0;
+        }
+        return Some(color);
+    }
+
+    let values: Vec<f32> = value.split(',').map(|value| value.trim().parse().ok()).collect::<Option<_>>()?;
+    match values.len(){
+        3 => Some([values[0], values[1], values[2], 1.0]),
+        4 => Some([values[0], values[1], values[2], values[3]]),
+        _ => None,
+    }
+}
+
+/// # ThemeBundle
+///
+/// A loaded theme. Fonts and textures get registered with the renderer while loading, and the bundle
+/// keeps track of the IDs and names they were registered with.
+pub struct ThemeBundle{
+    pub stylesheet: Stylesheet,
+    pub fonts: HashMap<String, wgpu_glyph::FontId>,
+    pub textures: Vec<String>,
+}
+
+impl ThemeBundle{
+    /// Load a theme from a directory, or from a `.zip` archive (with the `zip` feature),
+    /// registering its fonts and textures with the renderer
+    pub fn load<P: AsRef<Path>>(path: P, renderer: &mut Renderer) -> Result<Self, &'static str>{
+        let path = path.as_ref();
+        if path.is_dir(){
+            ThemeBundle::from_source(&FileSource::new(path), renderer)
+        }else if path.extension().map_or(false, |extension| extension.eq_ignore_ascii_case("zip")){
+            ThemeBundle::from_files(read_zip_files(path)?, renderer)
+        }else{
+            Err("Error, a theme bundle has to be a directory or a zip archive!")
+        }
+    }
+
+    /// Load a theme from an asset source, for example one made with `embed_assets!`
+    pub fn from_source(source: &dyn AssetSource, renderer: &mut Renderer) -> Result<Self, &'static str>{
+        let mut files = Vec::new();
+        for path in source.list(Path::new("")){
+            let data = source.read(&path)?;
+            let name = path.components().map(|component| component.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/");
+            files.push((name, data));
+        }
+        ThemeBundle::from_files(files, renderer)
+    }
+
+    /// Create a theme from a list of files (paths relative to the root of the bundle, using `/`) and their contents.
+    /// The text rasterization options in the stylesheet are applied to the renderer, with the defaults for any it leaves out.
+    pub fn from_files(files: Vec<(String, Vec<u8>)>, renderer: &mut Renderer) -> Result<Self, &'static str>{
+        let mut bundle = Self{
+            stylesheet: Stylesheet::new(),
+            fonts: HashMap::new(),
+            textures: Vec::new(),
+        };
+
+        for (path, data) in files{
+            let (stem, extension) = match path.rfind('.'){
+                Some(i) => (&path[..i], path[i + 1..].to_ascii_lowercase()),
+                None => continue,
+            };
+
+            if path == "theme.style"{
+                let text = String::from_utf8(data).map_err(|_| "Error, stylesheet isn't valid UTF-8!")?;
+                bundle.stylesheet.merge(&Stylesheet::parse(&text)?);
+            }else if let Some(name) = stem.strip_prefix("fonts/"){
+                if extension == "ttf" || extension == "otf"{
+                    let id = renderer.add_font(data)?;
+                    bundle.fonts.insert(name.to_string(), id);
+                }
+            }else if let Some(name) = stem.strip_prefix("textures/"){
+                if ["png", "jpg", "jpeg", "bmp", "tga"].contains(&extension.as_str()){
+                    renderer.add_texture(name, &data)?;
+                    bundle.textures.push(name.to_string());
+                }
+            }
+        }
+
+        // Themes choose how small text is drawn too, see `TextRasterOptions::from_stylesheet`
+        renderer.set_text_options(TextRasterOptions::from_stylesheet(&bundle.stylesheet));
+        Ok(bundle)
+    }
+
+    /// Get the ID of a font from the theme
+    pub fn get_font(&self, name: &str) -> Option<wgpu_glyph::FontId>{
+        self.fonts.get(name).copied()
+    }
+
+    /// Get the nine-slice insets (left, top, right, bottom in pixels) of a texture, set in the stylesheet
+    /// with `nine_slice = l, t, r, b` under `[textures.<name>]`
+    pub fn get_nine_slice(&self, texture: &str) -> Option<[f32; 4]>{
+        let insets = self.stylesheet.get_f32_list(&format!("textures.{}", texture), "nine_slice")?;
+        match insets.len(){
+            1 => Some([insets[0]; 4]),
+            4 => Some([insets[0], insets[1], insets[2], insets[3]]),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "zip")]
+fn read_zip_files(path: &Path) -> Result<Vec<(String, Vec<u8>)>, &'static str>{
+    use std::io::Read;
+
+    let file = std::fs::File::open(path).map_err(|_| "Error, failed to open theme archive!")?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|_| "Error, failed to read theme archive!")?;
+
+    let mut files = Vec::new();
+    for i in 0..archive.len(){
+        let mut file = archive.by_index(i).map_err(|_| "Error, failed to read theme archive!")?;
+        if file.is_dir(){
+            continue;
+        }
+        let name = file.name().to_string();
+        let mut data = Vec::new();
+        file.read_to_end(&mut data).map_err(|_| "Error, failed to read theme archive!")?;
+        files.push((name, data));
+    }
+    Ok(files)
+}
+
+#[cfg(not(feature = "zip"))]
+fn read_zip_files(_path: &Path) -> Result<Vec<(String, Vec<u8>)>, &'static str>{
+    Err("Error, loading themes from zip archives needs the `zip` feature!")
+}
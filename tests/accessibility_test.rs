@@ -0,0 +1,36 @@
+use std::sync::{Arc, Mutex};
+
+use rusty_gui::accessibility::{self, Announcer, Politeness};
+
+struct Recorder{
+    messages: Arc<Mutex<Vec<(String, Politeness)>>>,
+}
+
+impl Announcer for Recorder{
+    fn announce(&mut self, message: &str, politeness: Politeness){
+        self.messages.lock().unwrap().push((message.to_string(), politeness));
+    }
+}
+
+/// Test that announcements reach the announcer, from any thread
+#[test]
+fn announce_test(){
+    // Nothing set yet, so this goes nowhere
+    accessibility::announce("Dropped");
+
+    let messages = Arc::new(Mutex::new(Vec::new()));
+    accessibility::set_announcer(Some(Box::new(Recorder{ messages: messages.clone() })));
+
+    accessibility::announce("File saved");
+    std::thread::spawn(|| accessibility::announce_assertive("Connection lost")).join().unwrap();
+    accessibility::announce("");
+
+    assert_eq!(*messages.lock().unwrap(), vec![
+        (String::from("File saved"), Politeness::Polite),
+        (String::from("Connection lost"), Politeness::Assertive),
+    ]);
+
+    accessibility::set_announcer(None);
+    accessibility::announce("Dropped");
+    assert_eq!(messages.lock().unwrap().len(), 2);
+}
@@ -0,0 +1,28 @@
+use image::{Delay, Frame, RgbaImage, codecs::gif::GifEncoder};
+use rusty_gui::components::SpriteSheet;
+
+/// Test that a GIF's frames get packed into a grid, keeping their delays
+#[test]
+fn sprite_sheet_from_gif_test(){
+    let colors = [[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255]];
+    let mut bytes = Vec::new();
+    {
+        let mut encoder = GifEncoder::new(&mut bytes);
+        let frames = colors.iter().map(|color| Frame::from_parts(RgbaImage::from_pixel(2, 2, image::Rgba(*color)), 0, 0, Delay::from_numer_denom_ms(200, 1)));
+        encoder.encode_frames(frames).unwrap();
+    }
+
+    let sheet = SpriteSheet::from_gif(&bytes).unwrap();
+    assert_eq!(sheet.get_frame_count(), 3);
+    assert_eq!(sheet.grid, (2, 2));
+    assert_eq!(sheet.size, (4, 4));
+    assert_eq!(sheet.durations, vec![0.2, 0.2, 0.2]);
+    // The third frame starts the second row, and the last cell is left empty
+    let pixel = |x: usize, y: usize| &sheet.pixels[(y * 4 + x) * 4..(y * 4 + x) * 4 + 4];
+    assert_eq!(pixel(0, 0), &colors[0]);
+    assert_eq!(pixel(3, 1), &colors[1]);
+    assert_eq!(pixel(1, 3), &colors[2]);
+    assert_eq!(pixel(3, 3), &[0, 0, 0, 0]);
+
+    assert!(SpriteSheet::from_gif(b"not a gif").is_err());
+}
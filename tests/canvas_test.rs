@@ -0,0 +1,29 @@
+use rusty_gui::components::{PaintShape, Painter};
+
+/// Test that a painter keeps what was painted in order, with outlines turned into lines
+#[test]
+fn painter_test(){
+    let black = [0.0, 0.0, 0.0, 1.0];
+    let mut painter = Painter::new([100.0, 50.0], 1.5);
+    assert_eq!(painter.get_size(), [100.0, 50.0]);
+    assert_eq!(painter.get_time(), 1.5);
+
+    painter.fill_rect([0.0, 0.0], [10.0, 10.0], black);
+    painter.stroke_rect([5.0, 5.0], [20.0, 10.0], black);
+    painter.fill_circle([50.0, 25.0], -3.0, black);
+    painter.text("Hi", [1.0, 2.0], 16.0, black);
+
+    let shapes = painter.get_shapes();
+    assert_eq!(shapes.len(), 7);
+    assert_eq!(shapes[0], PaintShape::Rect{ pos: [0.0, 0.0], size: [10.0, 10.0], color: black });
+    assert_eq!(shapes[1], PaintShape::Line{ from: [5.0, 5.0], to: [25.0, 5.0], color: black });
+    assert_eq!(shapes[4], PaintShape::Line{ from: [5.0, 15.0], to: [5.0, 5.0], color: black });
+    assert_eq!(shapes[5], PaintShape::Circle{ center: [50.0, 25.0], radius: 0.0, color: black });
+    assert_eq!(shapes[6], PaintShape::Text{ text: "Hi".to_string(), pos: [1.0, 2.0], size: 16.0, color: black });
+
+    // Circle outlines are made of lines too
+    let mut painter = Painter::new([100.0, 50.0], 0.0);
+    painter.stroke_circle([0.0, 0.0], 10.0, black);
+    assert!(painter.get_shapes().len() >= 12);
+    assert!(painter.get_shapes().iter().all(|shape| matches!(shape, PaintShape::Line{ .. })));
+}
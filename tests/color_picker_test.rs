@@ -0,0 +1,25 @@
+use rusty_gui::components::{hsv_to_rgb, rgb_to_hsv};
+
+fn close(a: [f32; 3], b: [f32; 3]) -> bool{
+    a.iter().zip(b.iter()).all(|(a, b)| (a - b).abs() < 1e-4)
+}
+
+/// Test the primary colours and greys convert between RGB and HSV
+#[test]
+fn hsv_conversion_test(){
+    assert!(close(hsv_to_rgb([0.0, 1.0, 1.0]), [1.0, 0.0, 0.0]));
+    assert!(close(hsv_to_rgb([1.0 / 3.0, 1.0, 1.0]), [0.0, 1.0, 0.0]));
+    assert!(close(hsv_to_rgb([2.0 / 3.0, 1.0, 0.5]), [0.0, 0.0, 0.5]));
+    assert!(close(hsv_to_rgb([0.5, 0.0, 0.25]), [0.25, 0.25, 0.25]));
+
+    assert!(close(rgb_to_hsv([0.0, 0.0, 0.5]), [2.0 / 3.0, 1.0, 0.5]));
+    assert!(close(rgb_to_hsv([0.25, 0.25, 0.25]), [0.0, 0.0, 0.25]));
+}
+
+/// Test that colours survive going to HSV and back
+#[test]
+fn hsv_round_trip_test(){
+    for rgb in [[0.2, 0.6, 0.9], [1.0, 0.5, 0.0], [0.3, 0.1, 0.4], [0.9, 0.0, 0.1]].iter(){
+        assert!(close(hsv_to_rgb(rgb_to_hsv(*rgb)), *rgb), "{:?}", rgb);
+    }
+}
@@ -0,0 +1,26 @@
+use std::panic;
+
+use rusty_gui::{catch_fatal_panic, panic_message};
+
+/// Test that the message comes out of both kinds of string payload, and that other payloads don't have one
+#[test]
+fn panic_message_test(){
+    let payload = panic::catch_unwind(|| panic!("out of sprites")).unwrap_err();
+    assert_eq!(panic_message(payload.as_ref()), Some("out of sprites"));
+
+    let index = 7;
+    let payload = panic::catch_unwind(|| panic!("no sprite {}", index)).unwrap_err();
+    assert_eq!(panic_message(payload.as_ref()), Some("no sprite 7"));
+
+    let payload = panic::catch_unwind(|| panic::panic_any(7)).unwrap_err();
+    assert_eq!(panic_message(payload.as_ref()), None);
+}
+
+/// Test that panics go through `catch_fatal_panic` untouched when there's no panic hook to show them
+#[test]
+fn catch_fatal_panic_test(){
+    assert_eq!(catch_fatal_panic(|| 7), 7);
+
+    let payload = panic::catch_unwind(|| catch_fatal_panic(|| panic!("out of sprites"))).unwrap_err();
+    assert_eq!(panic_message(payload.as_ref()), Some("out of sprites"));
+}
@@ -0,0 +1,69 @@
+use std::ops::Range;
+
+use rusty_gui::components::{Searchable, TextSearch, find::find_matches};
+
+/// Bare bones text component, so we can search without a window
+struct Text{
+    text: String,
+    highlights: Vec<Range<usize>>,
+    current: Option<usize>,
+}
+
+impl Searchable for Text{
+    fn get_text(&self) -> &str{
+        &self.text
+    }
+
+    fn set_text(&mut self, text: String){
+        self.text = text;
+    }
+
+    fn set_highlights(&mut self, matches: &[Range<usize>], current: Option<usize>){
+        self.highlights = matches.to_vec();
+        self.current = current;
+    }
+}
+
+/// Test that matches are found, cycled through and replaced
+#[test]
+fn find_and_replace_test(){
+    let mut text = Text{ text: String::from("One fish, two Fish, red fish"), highlights: Vec::new(), current: None };
+    let mut search = TextSearch::new();
+
+    search.set_query("fish", &mut text);
+    assert_eq!(text.highlights, vec![4..8, 14..18, 24..28]);
+    assert_eq!(text.current, Some(0));
+
+    assert_eq!(search.previous(&mut text), Some(24..28));
+    assert_eq!(search.next(&mut text), Some(4..8));
+
+    search.set_case_sensitive(true, &mut text);
+    assert_eq!(search.get_matches().len(), 2);
+
+    assert!(search.replace("cat", &mut text));
+    assert_eq!(text.text, "One cat, two Fish, red fish");
+    assert_eq!(search.get_current_match(), Some(23..27));
+
+    search.set_case_sensitive(false, &mut text);
+    assert_eq!(search.replace_all("dog", &mut text), 2);
+    assert_eq!(text.text, "One cat, two dog, red dog");
+    assert!(search.get_matches().is_empty());
+}
+
+/// Test that ignoring case works past ASCII, with the matches given as ranges of the original text
+#[test]
+fn unicode_case_test(){
+    let mut text = Text{ text: String::from("\u{c9}cole, \u{e9}COLE and ecole"), highlights: Vec::new(), current: None };
+    let mut search = TextSearch::new();
+
+    search.set_query("\u{e9}cole", &mut text);
+    assert_eq!(text.highlights, vec![0..6, 8..14]);
+    search.set_case_sensitive(true, &mut text);
+    assert!(search.get_matches().is_empty());
+
+    // The Kelvin sign is three bytes, but lowercases to a one byte k
+    assert_eq!(find_matches("5 \u{212a}elvin", "kelvin", false), vec![2..10]);
+    assert_eq!(find_matches("\u{3a3}\u{39f}\u{3a3}", "\u{3c3}\u{3bf}\u{3c2}", false), vec![0..6]);
+    // Characters that fold to more than one don't match them
+    assert!(find_matches("stra\u{df}e", "strasse", false).is_empty());
+}
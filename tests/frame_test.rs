@@ -0,0 +1,24 @@
+use std::time::{Duration, Instant};
+
+use rusty_gui::gui::FrameLimiter;
+
+/// Test that the frame limiter spaces frames out to the target frame rate
+#[test]
+fn frame_limiter_test(){
+    let mut limiter = FrameLimiter::new(Some(100.0));
+    assert_eq!(limiter.get_frame_time(), Some(Duration::from_millis(10)));
+
+    limiter.wait();
+    let start = Instant::now();
+    for _ in 0..5{
+        limiter.wait();
+    }
+    assert!(start.elapsed() >= Duration::from_millis(45));
+
+    // No limit doesn't wait at all
+    limiter.set_max_fps(None);
+    assert_eq!(limiter.get_max_fps(), None);
+    let start = Instant::now();
+    limiter.wait();
+    assert!(start.elapsed() < Duration::from_millis(5));
+}
@@ -0,0 +1,64 @@
+use rusty_gui::i18n::{Catalog, Date, Locale, PluralCategory, PluralRule};
+
+#[test]
+fn numbers_follow_the_locale(){
+    let us = Locale::default();
+    assert_eq!(us.format_integer(1234567), "1,234,567");
+    assert_eq!(us.format_integer(-1000), "-1,000");
+    assert_eq!(us.format_integer(999), "999");
+    assert_eq!(us.format_decimal(1234.5, 2), "1,234.50");
+    assert_eq!(us.format_decimal(-0.001, 2), "0.00");
+
+    let german = Locale::from_tag("de-DE").unwrap();
+    assert_eq!(german.format_decimal(1234.5, 1), "1.234,5");
+
+    let french = Locale::from_tag("fr_FR").unwrap();
+    assert_eq!(french.format_decimal(1234.5, 1), "1\u{202F}234,5");
+
+    assert!(Locale::from_tag("xx").is_err());
+}
+
+#[test]
+fn dates_follow_the_locale(){
+    let date = Date::new(2026, 10, 6);
+    assert_eq!(Locale::from_tag("en-US").unwrap().format_date(date), "10/06/2026");
+    assert_eq!(Locale::from_tag("en-GB").unwrap().format_date(date), "06/10/2026");
+    assert_eq!(Locale::from_tag("de").unwrap().format_date(date), "06.10.2026");
+    assert_eq!(Locale::from_tag("ja-JP").unwrap().format_date(date), "2026/10/06");
+}
+
+#[test]
+fn plural_rules(){
+    assert_eq!(PluralRule::OneOther.select(1), PluralCategory::One);
+    assert_eq!(PluralRule::OneOther.select(0), PluralCategory::Other);
+    assert_eq!(PluralRule::ZeroOneOther.select(0), PluralCategory::One);
+
+    let russian = PluralRule::EastSlavic;
+    assert_eq!(russian.select(21), PluralCategory::One);
+    assert_eq!(russian.select(11), PluralCategory::Many);
+    assert_eq!(russian.select(23), PluralCategory::Few);
+    assert_eq!(russian.select(13), PluralCategory::Many);
+
+    assert_eq!(PluralRule::Polish.select(21), PluralCategory::Many);
+    assert_eq!(PluralRule::Polish.select(22), PluralCategory::Few);
+    assert_eq!(PluralRule::None.select(1), PluralCategory::Other);
+}
+
+#[test]
+fn catalog_picks_the_plural_form(){
+    let mut catalog = Catalog::new(Locale::default());
+    catalog.add_plural("items", &[(PluralCategory::One, "{} item"), (PluralCategory::Other, "{} items")]);
+    catalog.add("title", "Inventory");
+
+    assert_eq!(catalog.get_plural("items", 1), "1 item");
+    assert_eq!(catalog.get_plural("items", 3), "3 items");
+    assert_eq!(catalog.get_plural("items", 1500), "1,500 items");
+    assert_eq!(catalog.get("title"), "Inventory");
+    assert_eq!(catalog.get("missing"), "missing");
+
+    catalog.set_locale(Locale::from_tag("ru-RU").unwrap());
+    catalog.add_plural("items", &[(PluralCategory::One, "{} предмет"), (PluralCategory::Few, "{} предмета"), (PluralCategory::Other, "{} предметов")]);
+    assert_eq!(catalog.get_plural("items", 3), "3 предмета");
+    // Many isn't given, so other is used
+    assert_eq!(catalog.get_plural("items", 5), "5 предметов");
+}
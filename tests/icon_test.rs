@@ -0,0 +1,45 @@
+use rusty_gui::{components::{Icon, icon::{generate_icon_enum, parse_icon_metadata}}, hud::Anchor, layout::Layout};
+
+const METADATA: &str = "
+# Some Material style icons
+home e88a
+arrow_back e5c4
+3d_rotation 0xe84d
+not_an_icon zzzz
+";
+
+/// Test that icon metadata is parsed, skipping bad lines
+#[test]
+fn icon_metadata_test(){
+    let icons = parse_icon_metadata(METADATA);
+    assert_eq!(icons, vec![
+        (String::from("home"), '\u{e88a}'),
+        (String::from("arrow_back"), '\u{e5c4}'),
+        (String::from("3d_rotation"), '\u{e84d}'),
+    ]);
+}
+
+/// Test that the generated enum has valid variant names
+#[test]
+fn icon_enum_test(){
+    let source = generate_icon_enum("MaterialIcon", METADATA);
+    assert!(source.contains("pub enum MaterialIcon{"));
+    assert!(source.contains("    ArrowBack,\n"));
+    assert!(source.contains("    Icon3dRotation,\n"));
+    assert!(source.contains("MaterialIcon::Home => '\\u{e88a}',"));
+    assert!(source.contains("MaterialIcon::ArrowBack => \"arrow_back\","));
+}
+
+/// Test that anchored icons go in their slot like a square as big as they are, stacking down from the top
+#[test]
+fn icon_anchor_test(){
+    let icon = || Box::new(Icon::from_codepoint(0xe88a, wgpu_glyph::FontId::default(), 24.0, [0.0, 0.0]).unwrap());
+    let mut layout = Layout::hud();
+    let first = layout.add_anchored_text_component(Anchor::TopLeft, icon());
+    let second = layout.add_anchored_text_component(Anchor::TopLeft, icon());
+    layout.arrange((800, 600));
+
+    // Icons are drawn centred on their position, which is in window pixels
+    assert_eq!(layout.borrow_text_component_as_type::<Icon>(first).unwrap().get_pos(), [28.0, 28.0]);
+    assert_eq!(layout.borrow_text_component_as_type::<Icon>(second).unwrap().get_pos(), [28.0, 60.0]);
+}
@@ -0,0 +1,127 @@
+use rusty_gui::input::{Modifiers, MouseButton};
+use winit::event::ModifiersState;
+
+/// Test that winit input gets normalized as expected
+#[test]
+fn modifiers_test(){
+    let modifiers = Modifiers::from(ModifiersState::CTRL | ModifiersState::SHIFT);
+    assert!(modifiers.ctrl && modifiers.shift);
+    assert!(!modifiers.alt && !modifiers.logo);
+    assert!(!modifiers.is_empty());
+    assert!(Modifiers::from(ModifiersState::empty()).is_empty());
+
+    assert_eq!(MouseButton::from(winit::event::MouseButton::Middle), MouseButton::Middle);
+    assert_eq!(MouseButton::from(winit::event::MouseButton::Other(4)), MouseButton::Other(4));
+}
+
+/// Test that keys map the same from keycodes and scancodes, and that shortcuts aren't treated as text
+#[test]
+fn key_test(){
+    use rusty_gui::input::{Key, KeyEvent};
+    use winit::event::VirtualKeyCode;
+
+    assert_eq!(Key::from_virtual_keycode(VirtualKeyCode::A), Some(Key::Character('a')));
+    assert_eq!(Key::from_virtual_keycode(VirtualKeyCode::Numpad5), Some(Key::Character('5')));
+    assert_eq!(Key::from_virtual_keycode(VirtualKeyCode::NumpadEnter), Some(Key::Enter));
+    if !cfg!(target_os = "macos"){
+        assert_eq!(Key::from_scancode(0x11), Key::Character('w'));
+        assert_eq!(Key::from_scancode(0x1C), Key::Enter);
+        assert_eq!(Key::from_scancode(0x3C), Key::F(2));
+    }
+
+    let mut event = KeyEvent{
+        key: Key::Character('s'),
+        physical: Key::Character('s'),
+        scancode: 0x1F,
+        pressed: true,
+        repeat: false,
+        modifiers: Modifiers::NONE,
+    };
+    assert!(event.is_text_input());
+    event.modifiers.ctrl = true;
+    assert!(event.is_control());
+    // AltGr
+    event.modifiers.alt = true;
+    assert!(event.is_text_input());
+
+    event.key = Key::Left;
+    event.modifiers = Modifiers::NONE;
+    assert!(event.is_control());
+}
+
+/// Test that actions fire from any of their bindings, and can be rebound
+#[test]
+fn input_map_test(){
+    use rusty_gui::input::{ActionEvent, Binding, GamepadButton, InputMap, Key};
+
+    let mut map = InputMap::menu_defaults();
+    assert_eq!(map.handle_binding(Binding::Key(Key::Enter), true), vec![ActionEvent::Pressed("confirm".into())]);
+    assert!(map.is_active("confirm"));
+    assert_eq!(map.handle_binding(Binding::Key(Key::Enter), false), vec![ActionEvent::Released("confirm".into())]);
+    assert_eq!(map.handle_gamepad(GamepadButton::DPadUp, true), vec![ActionEvent::Pressed("navigate_up".into())]);
+
+    // Rebind confirm to F, then enter shouldn't do anything
+    map.start_rebind("confirm");
+    let f = Binding::Key(Key::Character('f'));
+    assert_eq!(map.handle_binding(f, true), vec![ActionEvent::Rebound("confirm".into(), f)]);
+    assert_eq!(map.get_bindings("confirm"), &[f]);
+    assert!(map.handle_binding(Binding::Key(Key::Enter), true).is_empty());
+    assert_eq!(f.to_string(), "F");
+}
+
+/// Test that input priorities block the right kinds of input
+#[test]
+fn input_priority_test(){
+    use rusty_gui::input::{InputCategory, InputPriority, InputRouting};
+    use winit::event::{Event, WindowEvent};
+
+    let window_id = unsafe { winit::window::WindowId::dummy() };
+    let resized = Event::WindowEvent{ window_id, event: WindowEvent::Resized(winit::dpi::PhysicalSize::new(1, 1)) };
+    let typed = Event::WindowEvent{ window_id, event: WindowEvent::ReceivedCharacter('a') };
+    assert_eq!(InputCategory::of(&resized), None);
+    assert_eq!(InputCategory::of(&typed), Some(InputCategory::Keyboard));
+
+    let hud = InputPriority{ keyboard: InputRouting::PassThrough, mouse: InputRouting::Block };
+    assert!(hud.blocks(InputCategory::Mouse));
+    assert!(!hud.blocks(InputCategory::Keyboard));
+    assert!(InputPriority::MODAL.blocks(InputCategory::Keyboard));
+    assert!(!InputPriority::default().blocks(InputCategory::Mouse));
+}
+
+/// Test that taps, swipes, long presses and pinches get recognized
+#[test]
+fn gesture_test(){
+    use rusty_gui::input::{Gesture, GestureRecognizer, SwipeDirection};
+
+    let mut gestures = GestureRecognizer::new();
+    gestures.pointer_down(0, [0.0, 0.0]);
+    assert_eq!(gestures.pointer_up(0), vec![Gesture::Tap{ pos: [0.0, 0.0] }]);
+
+    // A quick flick to the left
+    gestures.pointer_down(0, [0.0, 0.0]);
+    assert_eq!(gestures.pointer_move(0, [-20.0, 0.0]), vec![Gesture::Pan{ pos: [-20.0, 0.0], delta: [-20.0, 0.0] }]);
+    gestures.update(1.0 / 60.0);
+    gestures.pointer_move(0, [-40.0, 0.0]);
+    gestures.update(1.0 / 60.0);
+    let released = gestures.pointer_up(0);
+    assert!(matches!(released[1], Gesture::Swipe{ direction: SwipeDirection::Left, .. }));
+
+    // Held still
+    gestures.pointer_down(0, [0.0, 0.0]);
+    assert!(gestures.is_animating());
+    for _ in 0..60{
+        if let Some(gesture) = gestures.update(1.0 / 60.0).pop(){
+            assert_eq!(gesture, Gesture::LongPress{ pos: [0.0, 0.0] });
+        }
+    }
+    assert!(!gestures.is_animating());
+    gestures.pointer_up(0);
+
+    // Two fingers moving apart to twice the distance
+    gestures.pointer_down(0, [-10.0, 0.0]);
+    gestures.pointer_down(1, [10.0, 0.0]);
+    gestures.pointer_move(1, [30.0, 0.0]);
+    assert_eq!(gestures.pointer_move(0, [-30.0, 0.0]), vec![Gesture::Pinch{ centre: [0.0, 0.0], scale: 3.0 }]);
+    assert_eq!(gestures.pointer_up(0), vec![Gesture::PinchEnd{ scale: 3.0 }]);
+    assert!(gestures.pointer_up(1).is_empty());
+}
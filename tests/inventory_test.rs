@@ -0,0 +1,52 @@
+use rusty_gui::components::{Inventory, InventoryItem};
+
+fn arrows(count: u32) -> InventoryItem{
+    InventoryItem::new("arrow", "Arrow", 'a', count, 64)
+}
+
+/// Test that putting items in a slot stacks them up to the limit, and gives back what doesn't fit
+#[test]
+fn stacking_test(){
+    let mut inventory = Inventory::new(4);
+    assert_eq!(inventory.put(0, arrows(40)), None);
+    assert_eq!(inventory.put(0, arrows(40)), Some(arrows(16)));
+    assert_eq!(inventory.get(0).map(|item| item.count), Some(64));
+
+    // Different items don't stack
+    let sword = InventoryItem::new("sword", "Sword", 's', 1, 1);
+    assert_eq!(inventory.put(0, sword.clone()), Some(sword.clone()));
+    assert_eq!(inventory.put(1, sword.clone()), None);
+
+    // Adding tops up existing stacks before using empty slots
+    inventory.split(0, 10);
+    assert_eq!(inventory.add(arrows(80)), None);
+    assert_eq!(inventory.get(0).map(|item| item.count), Some(64));
+    assert_eq!(inventory.get(2).map(|item| item.count), Some(64));
+    assert_eq!(inventory.get(3).map(|item| item.count), Some(6));
+    assert_eq!(inventory.add(arrows(100)), Some(arrows(42)));
+    assert_eq!(inventory.count("arrow"), 192);
+}
+
+/// Test splitting stacks and taking items out by id
+#[test]
+fn split_test(){
+    let mut inventory = Inventory::new(3);
+    inventory.set(0, Some(arrows(9)));
+    assert_eq!(inventory.split(0, 4), Some(arrows(4)));
+    assert_eq!(inventory.get(0), Some(&arrows(5)));
+    assert_eq!(inventory.split(0, 10), Some(arrows(5)));
+    assert_eq!(inventory.get(0), None);
+    assert_eq!(inventory.split(0, 1), None);
+
+    inventory.set(0, Some(arrows(3)));
+    inventory.set(2, Some(arrows(3)));
+    assert_eq!(inventory.remove("arrow", 4), 4);
+    assert_eq!(inventory.get(0), Some(&arrows(2)));
+    assert_eq!(inventory.get(2), None);
+    assert_eq!(inventory.remove("arrow", 10), 2);
+
+    inventory.set(1, Some(arrows(1)));
+    inventory.swap(1, 2);
+    assert_eq!(inventory.get(2), Some(&arrows(1)));
+    assert_eq!(inventory.take(2), Some(arrows(1)));
+}
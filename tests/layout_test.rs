@@ -0,0 +1,377 @@
+use std::{any::Any, cell::RefCell, rc::Rc};
+
+use rusty_gui::{components::{EventGUIComponent, Orientation, Rect, StackPanel, ToastCorner, ToastLevel}, hud::{Anchor, SafeArea, WorldAnchor, project_point}, input::{HoverEvent, InputCategory}, layout::{ComponentHandle, HoverTarget, Layout}};
+
+// A component that only records the hover events it gets
+struct HoverBox{
+    bounds: Rect,
+    events: Rc<RefCell<Vec<(usize, HoverEvent)>>>,
+    id: usize,
+}
+
+impl EventGUIComponent for HoverBox{
+    fn render<'a, 'b>(&'a self, _render_pass: &'b mut wgpu::RenderPass<'a>) where 'a: 'b {}
+    fn handle_event_callback(&mut self, _event: &winit::event::Event<()>, _window: &winit::window::Window){}
+    fn as_any(&self) -> &dyn Any{ self }
+    fn as_any_mut(&mut self) -> &mut dyn Any{ self }
+    fn get_text_id(&self) -> Option<usize>{ None }
+    fn is_enabled(&self) -> bool{ true }
+    fn get_pos(&self) -> [f32; 2]{ self.bounds.pos }
+    fn get_bounds(&self) -> Option<Rect>{ Some(self.bounds) }
+    fn handle_hover(&mut self, event: HoverEvent){
+        self.events.borrow_mut().push((self.id, event));
+    }
+}
+
+/// Test that only the topmost component under the cursor gets hovered, and only changes are sent
+#[test]
+fn hover_test(){
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let mut layout = Layout::new();
+    layout.add_event_component(Box::new(HoverBox{ bounds: Rect::new([0.0, 0.0], [100.0, 100.0]), events: events.clone(), id: 0 }));
+    layout.add_event_component(Box::new(HoverBox{ bounds: Rect::new([40.0, 0.0], [20.0, 20.0]), events: events.clone(), id: 1 }));
+
+    layout.update_hover(Some([0.0, 0.0]));
+    layout.update_hover(Some([1.0, 1.0]));
+    assert_eq!(layout.get_hovered(), Some(HoverTarget::Event(0)));
+    assert_eq!(*events.borrow(), vec![(0, HoverEvent::Enter)]);
+
+    // The second box is on top of the first
+    layout.update_hover(Some([40.0, 0.0]));
+    layout.update_hover(None);
+    assert_eq!(*events.borrow(), vec![(0, HoverEvent::Enter), (0, HoverEvent::Exit), (1, HoverEvent::Enter), (1, HoverEvent::Exit)]);
+    assert_eq!(layout.get_hovered(), None);
+}
+
+// A component that wants a fixed size, and records where it gets arranged
+struct SizedBox{
+    size: [f32; 2],
+    arranged: Rc<RefCell<Vec<Rect>>>,
+    measured: Rc<RefCell<usize>>,
+}
+
+impl EventGUIComponent for SizedBox{
+    fn render<'a, 'b>(&'a self, _render_pass: &'b mut wgpu::RenderPass<'a>) where 'a: 'b {}
+    fn handle_event_callback(&mut self, _event: &winit::event::Event<()>, _window: &winit::window::Window){}
+    fn as_any(&self) -> &dyn Any{ self }
+    fn as_any_mut(&mut self) -> &mut dyn Any{ self }
+    fn get_text_id(&self) -> Option<usize>{ None }
+    fn is_enabled(&self) -> bool{ true }
+    fn get_pos(&self) -> [f32; 2]{ [0.0, 0.0] }
+    fn measure(&mut self, _available: [f32; 2]) -> Option<[f32; 2]>{
+        *self.measured.borrow_mut() += 1;
+        Some(self.size)
+    }
+    fn arrange(&mut self, rect: Rect){
+        self.arranged.borrow_mut().push(rect);
+    }
+}
+
+/// Test that a stack sizes itself around its children and places them in order, and that the layout
+/// only measures again once it's been invalidated
+#[test]
+fn stack_layout_test(){
+    let arranged = Rc::new(RefCell::new(Vec::new()));
+    let measured = Rc::new(RefCell::new(0));
+    let mut stack = StackPanel::new([0.0, 0.0], Orientation::Vertical, 10.0);
+    stack.set_padding([5.0, 5.0]);
+    stack.push(Box::new(SizedBox{ size: [40.0, 20.0], arranged: arranged.clone(), measured: measured.clone() }));
+    stack.push(Box::new(SizedBox{ size: [60.0, 30.0], arranged: arranged.clone(), measured: measured.clone() }));
+
+    let mut layout = Layout::new();
+    let id = layout.add_event_component(Box::new(stack));
+    layout.arrange((800, 600));
+    layout.arrange((800, 600));
+    assert_eq!(*measured.borrow(), 2);
+
+    // 20 + 10 + 30 tall and 60 wide, plus the padding, centred on the origin
+    let stack = layout.event_components[id].as_any().downcast_ref::<StackPanel>().unwrap();
+    assert_eq!(stack.get_rect(), Rect::new([0.0, 0.0], [70.0, 70.0]));
+    assert_eq!(*arranged.borrow(), vec![Rect::new([0.0, -20.0], [60.0, 20.0]), Rect::new([0.0, 15.0], [60.0, 30.0])]);
+
+    layout.invalidate_layout();
+    layout.arrange((800, 600));
+    assert_eq!(*measured.borrow(), 4);
+}
+
+/// Test that the content scale shrinks the screen the components see, and scales the cursor to match
+#[test]
+fn content_scale_test(){
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let mut layout = Layout::new();
+    layout.add_event_component(Box::new(HoverBox{ bounds: Rect::new([40.0, 0.0], [20.0, 20.0]), events: events.clone(), id: 0 }));
+
+    layout.set_content_scale(2.0);
+    layout.set_content_scale(0.0);
+    assert_eq!(layout.get_content_scale(), 2.0);
+    assert_eq!(layout.get_content_dim((800, 600)), (400, 300));
+
+    // The box is drawn twice as far from the centre, so that's where the cursor has to be
+    layout.update_hover(Some([40.0, 0.0]));
+    assert_eq!(layout.get_hovered(), None);
+    layout.update_hover(Some([80.0, 0.0]));
+    assert_eq!(layout.get_hovered(), Some(HoverTarget::Event(0)));
+}
+
+/// Test that selectors find components by class, and that classes follow components when ones before them are removed
+#[test]
+fn class_query_test(){
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let mut layout = Layout::new();
+    for id in 0..3{
+        layout.add_event_component(Box::new(HoverBox{ bounds: Rect::new([0.0, 0.0], [10.0, 10.0]), events: events.clone(), id }));
+    }
+    layout.add_class(ComponentHandle::Event(0), "form");
+    layout.add_class(ComponentHandle::Event(1), "form");
+    layout.add_class(ComponentHandle::Event(1), "danger");
+    layout.add_class(ComponentHandle::Event(2), "danger");
+
+    assert_eq!(layout.query(".form"), vec![ComponentHandle::Event(0), ComponentHandle::Event(1)]);
+    assert_eq!(layout.query(".form.danger"), vec![ComponentHandle::Event(1)]);
+    assert_eq!(layout.query(".form, .danger").len(), 3);
+
+    layout.remove_event_component_by_id(0);
+    assert_eq!(layout.query(".form"), vec![ComponentHandle::Event(0)]);
+    assert!(layout.has_class(ComponentHandle::Event(1), "danger"));
+
+    let mut ids = Vec::new();
+    layout.for_each_in::<HoverBox, _>(".danger", |comp| ids.push(comp.id));
+    assert_eq!(ids, vec![1, 2]);
+}
+
+/// Test that a batch of changes only gets measured once, after it's finished
+#[test]
+fn batch_test(){
+    let arranged = Rc::new(RefCell::new(Vec::new()));
+    let measured = Rc::new(RefCell::new(0));
+    let mut layout = Layout::new();
+    layout.arrange((800, 600));
+
+    let added = layout.batch(|layout| {
+        for _ in 0..100{
+            layout.add_event_component(Box::new(SizedBox{ size: [10.0, 10.0], arranged: arranged.clone(), measured: measured.clone() }));
+            layout.arrange((800, 600));
+        }
+        assert!(layout.is_batching());
+        layout.event_components.len()
+    });
+    assert_eq!(added, 100);
+    assert_eq!(*measured.borrow(), 0);
+
+    layout.arrange((800, 600));
+    assert_eq!(*measured.borrow(), 100);
+    assert!(!layout.is_batching());
+}
+
+/// Test that hidden components can't be hovered, and stay hidden when the ones before them are removed
+#[test]
+fn hidden_test(){
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let mut layout = Layout::new();
+    for id in 0..3{
+        layout.add_event_component(Box::new(HoverBox{ bounds: Rect::new([0.0, 0.0], [10.0, 10.0]), events: events.clone(), id }));
+    }
+
+    layout.set_hidden(ComponentHandle::Event(2), true);
+    assert!(layout.is_hidden(ComponentHandle::Event(2)));
+
+    // The top box is hidden, so the one under it gets hovered
+    layout.update_hover(Some([0.0, 0.0]));
+    assert_eq!(layout.get_hovered(), Some(HoverTarget::Event(1)));
+
+    layout.remove_event_component_by_id(0);
+    assert!(layout.is_hidden(ComponentHandle::Event(1)));
+    assert!(!layout.is_hidden(ComponentHandle::Event(2)));
+
+    layout.set_hidden(ComponentHandle::Event(1), false);
+    layout.update_hover(Some([1.0, 1.0]));
+    assert_eq!(layout.get_hovered(), Some(HoverTarget::Event(1)));
+}
+
+/// Test that tooltips follow their components when the ones before them are removed, and go with them
+#[test]
+fn tooltip_test(){
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let mut layout = Layout::new();
+    for id in 0..3{
+        layout.add_event_component(Box::new(HoverBox{ bounds: Rect::new([0.0, 0.0], [10.0, 10.0]), events: events.clone(), id }));
+    }
+
+    layout.set_tooltip(ComponentHandle::Event(0), "First");
+    layout.set_tooltip(ComponentHandle::Event(2), "Last");
+    layout.update_hover(Some([0.0, 0.0]));
+    assert!(!layout.is_tooltip_visible());
+
+    layout.remove_event_component_by_id(0);
+    assert_eq!(layout.get_tooltip(ComponentHandle::Event(0)), None);
+    assert_eq!(layout.get_tooltip(ComponentHandle::Event(1)), Some("Last"));
+
+    layout.remove_tooltip(ComponentHandle::Event(1));
+    assert_eq!(layout.get_tooltip(ComponentHandle::Event(1)), None);
+
+    layout.set_tooltip_delay(-1.0);
+    assert_eq!(layout.get_tooltip_delay(), 0.0);
+}
+
+// A component that captures all input while it's open, like a dialog
+struct ModalBox{
+    bounds: Rect,
+    open: bool,
+}
+
+impl EventGUIComponent for ModalBox{
+    fn render<'a, 'b>(&'a self, _render_pass: &'b mut wgpu::RenderPass<'a>) where 'a: 'b {}
+    fn handle_event_callback(&mut self, _event: &winit::event::Event<()>, _window: &winit::window::Window){}
+    fn as_any(&self) -> &dyn Any{ self }
+    fn as_any_mut(&mut self) -> &mut dyn Any{ self }
+    fn get_text_id(&self) -> Option<usize>{ None }
+    fn is_enabled(&self) -> bool{ true }
+    fn get_pos(&self) -> [f32; 2]{ self.bounds.pos }
+    fn get_bounds(&self) -> Option<Rect>{ Some(self.bounds) }
+    fn is_modal(&self) -> bool{ self.open }
+}
+
+/// Test that nothing but an open modal component can be hovered, and that it blocks the layouts underneath
+#[test]
+fn modal_test(){
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let mut layout = Layout::new();
+    layout.add_event_component(Box::new(HoverBox{ bounds: Rect::new([0.0, 0.0], [100.0, 100.0]), events: events.clone(), id: 0 }));
+    layout.add_overlay_component(Box::new(ModalBox{ bounds: Rect::new([0.0, 0.0], [20.0, 20.0]), open: true }));
+    assert_eq!(layout.get_modal(), Some(ComponentHandle::Overlay(0)));
+    assert!(layout.blocks_input(InputCategory::Keyboard));
+
+    // Outside the modal box, but inside the one behind it
+    layout.update_hover(Some([40.0, 40.0]));
+    assert_eq!(layout.get_hovered(), None);
+    layout.update_hover(Some([0.0, 0.0]));
+    assert_eq!(layout.get_hovered(), Some(HoverTarget::Overlay(0)));
+
+    layout.borrow_overlay_component_as_type_mut::<ModalBox>(0).unwrap().open = false;
+    assert_eq!(layout.get_modal(), None);
+    assert!(!layout.blocks_input(InputCategory::Mouse));
+    layout.update_hover(Some([40.0, 40.0]));
+    assert_eq!(layout.get_hovered(), Some(HoverTarget::Event(0)));
+    assert_eq!(*events.borrow(), vec![(0, HoverEvent::Enter)]);
+}
+
+/// Test that toasts pile up to the limit, with the oldest leaving to make room
+#[test]
+fn toast_test(){
+    let mut layout = Layout::new();
+    layout.borrow_notifications_mut().set_max_visible(2);
+    layout.borrow_notifications_mut().set_corner(ToastCorner::TopRight);
+    assert!(layout.get_notifications().is_empty());
+
+    layout.notify("Saved", ToastLevel::Info);
+    layout.notify("Disk almost full", ToastLevel::Warning);
+    assert_eq!(layout.get_notifications().len(), 2);
+    layout.notify("Couldn't save", ToastLevel::Error);
+    assert_eq!(layout.get_notifications().len(), 2);
+
+    layout.borrow_notifications_mut().dismiss_all();
+    assert!(layout.get_notifications().is_empty());
+    assert_eq!(layout.get_notifications().get_corner(), ToastCorner::TopRight);
+}
+
+/// Test that anchored components go in their slots inside the safe area, stacking away from the edge
+#[test]
+fn hud_anchor_test(){
+    let measured = Rc::new(RefCell::new(0));
+    let boxes: Vec<_> = (0..3).map(|_| Rc::new(RefCell::new(Vec::new()))).collect();
+    let sized = |size: [f32; 2], arranged: &Rc<RefCell<Vec<Rect>>>| Box::new(SizedBox{ size, arranged: arranged.clone(), measured: measured.clone() });
+
+    let mut layout = Layout::hud();
+    assert_eq!(layout.get_safe_area(), SafeArea::uniform(16.0));
+    layout.add_anchored_event_component(Anchor::TopLeft, sized([40.0, 20.0], &boxes[0]));
+    layout.add_anchored_event_component(Anchor::TopLeft, sized([60.0, 30.0], &boxes[1]));
+    layout.add_anchored_event_component(Anchor::BottomRight, sized([40.0, 20.0], &boxes[2]));
+    layout.arrange((800, 600));
+
+    assert_eq!(boxes[0].borrow().last(), Some(&Rect::new([-364.0, -274.0], [40.0, 20.0])));
+    // Below the first, with the spacing between them
+    assert_eq!(boxes[1].borrow().last(), Some(&Rect::new([-354.0, -241.0], [60.0, 30.0])));
+    assert_eq!(boxes[2].borrow().last(), Some(&Rect::new([364.0, 274.0], [40.0, 20.0])));
+
+    // Anchors follow their components when the ones before them are removed
+    layout.remove_event_component_by_id(0);
+    assert_eq!(layout.get_anchor(ComponentHandle::Event(0)), Some(Anchor::TopLeft));
+    assert_eq!(layout.get_anchor(ComponentHandle::Event(1)), Some(Anchor::BottomRight));
+    layout.set_anchor(ComponentHandle::Event(1), None);
+    assert_eq!(layout.get_anchor(ComponentHandle::Event(1)), None);
+}
+
+/// Test that world anchored components follow their point around the screen, and hide behind the camera
+#[test]
+fn world_anchor_test(){
+    let camera = cgmath::perspective(cgmath::Deg(90.0), 1.0, 0.1, 100.0);
+    assert_eq!(project_point(camera, [1.0, 1.0, -2.0], (800, 600)), Some([200.0, -150.0]));
+    assert_eq!(project_point(camera, [0.0, 0.0, 2.0], (800, 600)), None);
+
+    let measured = Rc::new(RefCell::new(0));
+    let boxes: Vec<_> = (0..2).map(|_| Rc::new(RefCell::new(Vec::new()))).collect();
+    let sized = |arranged: &Rc<RefCell<Vec<Rect>>>| Box::new(SizedBox{ size: [40.0, 20.0], arranged: arranged.clone(), measured: measured.clone() });
+
+    let mut layout = Layout::hud();
+    let nameplate = ComponentHandle::Event(layout.add_event_component(sized(&boxes[0])));
+    let marker = ComponentHandle::Event(layout.add_event_component(sized(&boxes[1])));
+    layout.set_world_anchor(nameplate, Some(WorldAnchor::new([1.0, 1.0, -2.0]).with_offset([0.0, -10.0])));
+    layout.set_world_anchor(marker, Some(WorldAnchor::new([8.0, 0.0, -2.0]).clamped()));
+    layout.set_view_projection(camera);
+    layout.arrange((800, 600));
+    assert_eq!(boxes[0].borrow().last(), Some(&Rect::new([200.0, -160.0], [40.0, 20.0])));
+    // Off the right of the screen, so pulled in to the edge of the safe area
+    assert_eq!(boxes[1].borrow().last(), Some(&Rect::new([384.0, 0.0], [40.0, 20.0])));
+
+    // Turning the camera around puts the nameplate's point behind it
+    layout.set_view_projection(camera * cgmath::Matrix4::from_angle_y(cgmath::Deg(180.0)));
+    layout.arrange((800, 600));
+    assert!(layout.is_behind_camera(nameplate) && layout.is_hidden(nameplate));
+    assert!(!layout.is_hidden(marker));
+    layout.set_view_projection(camera);
+    layout.arrange((800, 600));
+    assert!(!layout.is_behind_camera(nameplate) && !layout.is_hidden(nameplate));
+}
+
+/// Test that disabled components can't be hovered, and stay disabled as the components before them are removed
+#[test]
+fn disabled_test(){
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let mut layout = Layout::new();
+    layout.add_event_component(Box::new(HoverBox{ bounds: Rect::new([0.0, 0.0], [100.0, 100.0]), events: events.clone(), id: 0 }));
+    layout.add_event_component(Box::new(HoverBox{ bounds: Rect::new([0.0, 0.0], [20.0, 20.0]), events: events.clone(), id: 1 }));
+
+    // The cursor goes through the disabled box to the one under it
+    layout.set_disabled(ComponentHandle::Event(1), true);
+    layout.update_hover(Some([0.0, 0.0]));
+    assert_eq!(layout.get_hovered(), Some(HoverTarget::Event(0)));
+    assert!(!layout.is_hidden(ComponentHandle::Event(1)));
+
+    layout.remove_event_component_by_id(0);
+    assert!(layout.is_disabled(ComponentHandle::Event(0)));
+    assert!(!layout.is_disabled(ComponentHandle::Event(1)));
+    layout.set_disabled(ComponentHandle::Event(0), false);
+    layout.update_hover(Some([1.0, 1.0]));
+    assert_eq!(layout.get_hovered(), Some(HoverTarget::Event(0)));
+}
+
+/// Test that clearing the GPU resources empties the layout, but keeps its settings
+#[test]
+fn clear_gpu_resources_test(){
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let mut layout = Layout::new();
+    layout.add_event_component(Box::new(HoverBox{ bounds: Rect::new([0.0, 0.0], [100.0, 100.0]), events: events.clone(), id: 0 }));
+    layout.add_overlay_component(Box::new(HoverBox{ bounds: Rect::new([0.0, 0.0], [20.0, 20.0]), events: events.clone(), id: 1 }));
+    layout.set_content_scale(2.0);
+    layout.set_safe_area(SafeArea::uniform(16.0));
+    layout.update_hover(Some([0.0, 0.0]));
+
+    layout.clear_gpu_resources();
+    assert!(layout.event_components.is_empty());
+    assert!(layout.overlay_components.is_empty());
+    assert_eq!(layout.get_hovered(), None);
+    assert_eq!(layout.get_content_scale(), 2.0);
+    assert_eq!(layout.get_safe_area(), SafeArea::uniform(16.0));
+    // Nothing is left holding on to the components
+    assert_eq!(Rc::strong_count(&events), 1);
+}
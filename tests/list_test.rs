@@ -0,0 +1,26 @@
+use rusty_gui::components::{ListChange, ListData};
+
+/// Test that changes to list data are recorded in order, and that replacing everything drops the older changes
+#[test]
+fn list_data_test(){
+    let data = ListData::from_vec(vec!["a", "b"]);
+    assert_eq!(data.take_changes(), vec![ListChange::Reset]);
+
+    let handle = data.clone();
+    handle.push("c");
+    handle.insert(0, "z");
+    handle.set(1, "A");
+    handle.update(3, |item| *item = "C");
+    assert_eq!(handle.remove(2), "b");
+    assert_eq!(data.take_changes(), vec![ListChange::Inserted(2), ListChange::Inserted(0), ListChange::Updated(1), ListChange::Updated(3), ListChange::Removed(2)]);
+    assert!(data.take_changes().is_empty());
+
+    assert_eq!(data.len(), 3);
+    assert_eq!(data.with(2, |item| *item), Some("C"));
+    assert_eq!(data.with(3, |item| *item), None);
+
+    data.push("d");
+    data.clear();
+    assert_eq!(data.take_changes(), vec![ListChange::Reset]);
+    assert!(data.is_empty());
+}
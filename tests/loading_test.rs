@@ -0,0 +1,39 @@
+use rusty_gui::{components::LoadingProgress, layout::Layout};
+
+/// Test that progress reported from another thread shows up in every handle
+#[test]
+fn loading_progress_test(){
+    let progress = LoadingProgress::new();
+    let reporter = progress.clone();
+    std::thread::spawn(move || {
+        reporter.set_message("Loading textures");
+        reporter.set_progress(1.5);
+    }).join().unwrap();
+
+    assert_eq!(progress.get_message(), "Loading textures");
+    // Progress is kept between 0 and 1
+    assert_eq!(progress.get_progress(), 1.0);
+    assert!(!progress.is_finished());
+
+    progress.set_progress(0.25);
+    progress.finish();
+    assert!(progress.is_finished());
+    assert_eq!(progress.get_progress(), 1.0);
+}
+
+/// Test that a layout is only loading while its progress isn't finished
+#[test]
+fn layout_loading_test(){
+    let mut layout = Layout::new();
+    assert!(!layout.is_loading());
+
+    let progress = LoadingProgress::new();
+    layout.set_loading(Some(progress.clone()));
+    assert!(layout.is_loading());
+    progress.finish();
+    assert!(!layout.is_loading());
+
+    layout.set_loading(Some(LoadingProgress::new()));
+    layout.set_loading(None);
+    assert!(!layout.is_loading());
+}
@@ -0,0 +1,48 @@
+use rusty_gui::rendering::{LruBudget, MemoryReport};
+
+/// Test that going over the limit drops the least recently used things first
+#[test]
+fn lru_budget_test(){
+    let mut budget = LruBudget::new();
+    budget.insert("a", 40);
+    budget.insert("b", 40);
+    budget.insert("c", 40);
+    assert_eq!(budget.get_used(), 120);
+    assert!(budget.evict().is_empty());
+
+    // Using "a" makes "b" the oldest
+    budget.touch(&"a");
+    budget.set_limit(Some(80));
+    assert_eq!(budget.evict(), vec!["b"]);
+    assert_eq!(budget.get_used(), 80);
+
+    // Replacing something changes its size instead of adding it again
+    budget.insert("c", 70);
+    assert_eq!(budget.get_used(), 110);
+    assert_eq!(budget.evict(), vec!["a"]);
+    assert!(budget.contains(&"c"));
+
+    budget.remove(&"c");
+    assert_eq!(budget.get_used(), 0);
+}
+
+/// Test that the total doesn't count the texture pool twice
+#[test]
+fn memory_report_total_test(){
+    let report = MemoryReport{ textures: 100, texture_pool: 60, buffers: 10, glyph_cache: 5, swap_chain: 1 };
+    assert_eq!(report.total(), 116);
+}
+
+/// Test that things kept by `evict_where` stay, even if that leaves the budget over its limit
+#[test]
+fn lru_budget_keep_test(){
+    let mut budget = LruBudget::new();
+    budget.insert(1, 50);
+    budget.insert(2, 50);
+    budget.insert(3, 50);
+    budget.set_limit(Some(60));
+
+    assert_eq!(budget.evict_where(|key| *key != 1), vec![2, 3]);
+    assert!(budget.contains(&1));
+    assert_eq!(budget.get_used(), 50);
+}
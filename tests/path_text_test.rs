@@ -0,0 +1,45 @@
+use std::f32::consts::PI;
+
+use rusty_gui::components::BezierPath;
+
+fn close(a: f32, b: f32, tolerance: f32) -> bool{
+    (a - b).abs() < tolerance
+}
+
+/// Test the length of a path and points along it, through straight lines and round corners
+#[test]
+fn path_sample_test(){
+    let path = BezierPath::new([0.0, 0.0]).line_to([100.0, 0.0]).line_to([100.0, 50.0]);
+    assert!(close(path.length(), 150.0, 1e-3));
+
+    let (pos, angle) = path.sample(40.0).unwrap();
+    assert!(close(pos[0], 40.0, 1e-3) && close(pos[1], 0.0, 1e-3));
+    assert!(close(angle, 0.0, 1e-3));
+
+    // Heading down the screen, after the corner
+    let (pos, angle) = path.sample(125.0).unwrap();
+    assert!(close(pos[0], 100.0, 1e-3) && close(pos[1], 25.0, 1e-3));
+    assert!(close(angle, PI / 2.0, 1e-3));
+
+    assert_eq!(path.sample(-1.0), None);
+    assert_eq!(path.sample(151.0), None);
+}
+
+/// Test that arcs stay on their circle, and that circles start on the left and go over the top
+#[test]
+fn path_arc_test(){
+    let circle = BezierPath::circle([10.0, 20.0], 50.0);
+    assert!(close(circle.length(), 2.0 * PI * 50.0, 0.5));
+    assert!(close(circle.get_start()[0], -40.0, 1e-3));
+
+    for step in 0..16{
+        let (pos, _) = circle.sample(circle.length() * step as f32 / 16.0).unwrap();
+        let radius = ((pos[0] - 10.0).powi(2) + (pos[1] - 20.0).powi(2)).sqrt();
+        assert!(close(radius, 50.0, 0.1), "{:?}", pos);
+    }
+
+    // A quarter of the way round is the top, heading right
+    let (pos, angle) = circle.sample(circle.length() / 4.0).unwrap();
+    assert!(close(pos[0], 10.0, 0.5) && close(pos[1], -30.0, 0.5));
+    assert!(close(angle, 0.0, 0.1));
+}
@@ -1,5 +1,5 @@
 use futures::executor::block_on;
-use rusty_gui::rendering::{Renderer, WindowBuilder};
+use rusty_gui::rendering::{GraphicsSettings, Renderer, WindowBuilder};
 
 
 /// Test that renderer contruction works as expected
@@ -7,4 +7,26 @@ use rusty_gui::rendering::{Renderer, WindowBuilder};
 fn test_renderer(){
     let window = unsafe { WindowBuilder::new().build_unsafe().unwrap() };
     let _renderer = block_on(Renderer::new(&window.window));
-}
\ No newline at end of file
+}
+
+/// Test that graphics settings are checked before the renderer uses them
+#[test]
+fn graphics_settings_test(){
+    let mut settings = GraphicsSettings::new();
+    assert_eq!(settings.msaa_samples, 1);
+    assert_eq!(settings.ui_scale, 1.0);
+    assert!(settings.validate().is_ok());
+
+    settings.set_msaa_samples(4).set_ui_scale(1.5);
+    assert!(settings.validate().is_ok());
+
+    // Not every GPU can do 2 or 8 samples
+    for samples in [2, 3, 8]{
+        settings.set_msaa_samples(samples);
+        assert!(settings.validate().is_err());
+    }
+    settings.set_msaa_samples(4).set_ui_scale(0.0);
+    assert!(settings.validate().is_err());
+    settings.set_ui_scale(f32::NAN);
+    assert!(settings.validate().is_err());
+}
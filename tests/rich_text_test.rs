@@ -0,0 +1,16 @@
+use rusty_gui::components::RichText;
+
+/// Test that links are told apart from plain spans, and that nothing gets hit before the text is laid out
+#[test]
+fn link_span_test(){
+    let text = RichText::new(20.0, [0.0, 0.0])
+        .with_text("See ")
+        .with_link("the docs", [0.2, 0.4, 1.0, 1.0], Box::new(|| {}))
+        .with_text(".");
+    assert!(!text.is_link(0));
+    assert!(text.is_link(1));
+    assert!(!text.is_link(3));
+    assert_eq!(text.glyph_at([5.0, 5.0]), None);
+    assert!(text.span_rects(1).is_empty());
+    assert_eq!(text.get_hovered_link(), None);
+}
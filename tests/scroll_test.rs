@@ -0,0 +1,163 @@
+use std::{cell::RefCell, rc::Rc};
+
+use rusty_gui::components::{KineticScroll, LazyLoader, LoadHandle, OverscrollEffect, PullToRefresh, StickyHeaders, StuckHeader};
+
+/// Test that a released drag keeps scrolling, and a bounce settles back on the edge
+#[test]
+fn kinetic_scroll_test(){
+    let mut scroll = KineticScroll::new();
+    scroll.set_max_offset(1000.0);
+
+    // Drag upwards 50px over 5 frames, then let go
+    scroll.begin_drag(500.0);
+    for i in 1..=5{
+        scroll.drag_to(500.0 - i as f32 * 10.0);
+        scroll.update(1.0 / 60.0);
+    }
+    scroll.end_drag();
+    assert_eq!(scroll.get_offset(), 50.0);
+
+    scroll.update(1.0 / 60.0);
+    assert!(scroll.get_offset() > 50.0);
+    assert!(scroll.is_animating());
+
+    for _ in 0..600{
+        scroll.update(1.0 / 60.0);
+    }
+    assert!(!scroll.is_animating());
+
+    // Fling past the start, it should come back to rest at 0
+    scroll.set_offset(10.0);
+    scroll.begin_drag(0.0);
+    scroll.drag_to(100.0);
+    scroll.update(1.0 / 60.0);
+    scroll.end_drag();
+    assert!(scroll.get_overscroll() < 0.0);
+
+    for _ in 0..600{
+        scroll.update(1.0 / 60.0);
+    }
+    assert_eq!(scroll.get_offset(), 0.0);
+    assert!(!scroll.is_animating());
+
+    // Clamped scrolling never leaves the bounds
+    scroll.set_overscroll_effect(OverscrollEffect::Clamp);
+    scroll.scroll_by(-50.0);
+    assert_eq!(scroll.get_offset(), 0.0);
+}
+
+/// Test jumping, animating and bringing spans into view
+#[test]
+fn programmatic_scroll_test(){
+    let mut scroll = KineticScroll::new();
+    scroll.set_content(1000.0, 200.0);
+    assert_eq!(scroll.get_max_offset(), 800.0);
+
+    scroll.scroll_to(5000.0, false);
+    assert_eq!(scroll.get_offset(), 800.0);
+
+    scroll.scroll_to(100.0, true);
+    assert_eq!(scroll.get_target(), Some(100.0));
+    for _ in 0..120{
+        scroll.update(1.0 / 60.0);
+    }
+    assert_eq!(scroll.get_offset(), 100.0);
+    assert!(!scroll.is_animating());
+
+    // Already visible, so nothing changes
+    scroll.ensure_visible(150.0, 50.0, false);
+    assert_eq!(scroll.get_offset(), 100.0);
+
+    // Below the view, so it gets scrolled up to the bottom edge
+    scroll.ensure_visible(400.0, 50.0, false);
+    assert_eq!(scroll.get_offset(), 250.0);
+
+    // Above the view, so it gets scrolled down to the top edge
+    scroll.ensure_visible(20.0, 50.0, false);
+    assert_eq!(scroll.get_offset(), 20.0);
+    assert!(scroll.is_visible(0.0, 30.0));
+    assert!(!scroll.is_visible(300.0, 30.0));
+}
+
+/// Test that headers stick, then get pushed out by the next one
+#[test]
+fn sticky_headers_test(){
+    let mut headers = StickyHeaders::new();
+    headers.add_section(0.0, 20.0);
+    headers.add_section(100.0, 20.0);
+    headers.add_section(300.0, 30.0);
+
+    assert_eq!(headers.stuck_header(50.0), Some(StuckHeader{ index: 0, y: 0.0 }));
+    // The second header is 10px from the top, so it pushes the first one 10px up
+    assert_eq!(headers.stuck_header(90.0), Some(StuckHeader{ index: 0, y: -10.0 }));
+    assert_eq!(headers.stuck_header(150.0), Some(StuckHeader{ index: 1, y: 0.0 }));
+    assert_eq!(headers.stuck_header(1000.0), Some(StuckHeader{ index: 2, y: 0.0 }));
+
+    headers.clear();
+    headers.add_section(40.0, 20.0);
+    assert_eq!(headers.stuck_header(10.0), None);
+}
+
+/// Test that the loader fires near the end, and only once per fetch
+#[test]
+fn lazy_loader_test(){
+    let handles = Rc::new(RefCell::new(Vec::<LoadHandle>::new()));
+    let callback_handles = handles.clone();
+    let mut loader = LazyLoader::new(100.0, Some(Box::new(move |handle| callback_handles.borrow_mut().push(handle))));
+
+    assert!(!loader.check(0.0, 500.0));
+    assert!(loader.check(450.0, 500.0));
+    assert!(loader.is_loading());
+    assert!(!loader.check(480.0, 500.0));
+
+    handles.borrow()[0].finish();
+    assert!(!loader.is_loading());
+    assert!(loader.check(480.0, 500.0));
+    assert_eq!(handles.borrow().len(), 2);
+
+    handles.borrow()[1].finish();
+    loader.set_exhausted(true);
+    assert!(!loader.check(500.0, 500.0));
+}
+
+/// Test that pulling far enough past the top refreshes, and holds the content open until done
+#[test]
+fn pull_to_refresh_test(){
+    let handles = Rc::new(RefCell::new(Vec::<LoadHandle>::new()));
+    let callback_handles = handles.clone();
+    let mut refresh = PullToRefresh::new(60.0, Some(Box::new(move |handle| callback_handles.borrow_mut().push(handle))));
+    let mut scroll = KineticScroll::new();
+    scroll.set_content(1000.0, 200.0);
+
+    // Not far enough (the drag is halved past the edge)
+    scroll.begin_drag(0.0);
+    scroll.drag_to(100.0);
+    refresh.update(&scroll);
+    assert!(refresh.get_progress() < 1.0);
+    scroll.end_drag();
+    assert!(!refresh.update(&scroll));
+
+    for _ in 0..600{
+        scroll.update(1.0 / 60.0);
+    }
+
+    scroll.begin_drag(0.0);
+    scroll.drag_to(200.0);
+    refresh.update(&scroll);
+    assert_eq!(refresh.get_progress(), 1.0);
+    scroll.end_drag();
+    assert!(refresh.update(&scroll));
+    assert!(refresh.is_refreshing());
+
+    // The scroll springs back, but the content is held open
+    for _ in 0..600{
+        scroll.update(1.0 / 60.0);
+        refresh.update(&scroll);
+    }
+    assert_eq!(refresh.get_offset(), -45.0);
+
+    handles.borrow()[0].finish();
+    refresh.update(&scroll);
+    assert!(!refresh.is_refreshing());
+    assert_eq!(refresh.get_offset(), 0.0);
+}
@@ -0,0 +1,13 @@
+use rusty_gui::components::filter_suggestions;
+
+/// Test that suggestions match the query anywhere, ignoring case, with the ones starting with it first
+#[test]
+fn filter_suggestions_test(){
+    let items = ["France", "Germany", "Finland", "Greece", "Malta"];
+    assert_eq!(filter_suggestions(&items, "f"), vec!["France", "Finland"]);
+    assert_eq!(filter_suggestions(&items, "AN"), vec!["France", "Germany", "Finland"]);
+    assert_eq!(filter_suggestions(&items, "e"), vec!["France", "Germany", "Greece"]);
+    assert_eq!(filter_suggestions(&items, " gr "), vec!["Greece"]);
+    assert!(filter_suggestions(&items, "xyz").is_empty());
+    assert!(filter_suggestions(&items, "  ").is_empty());
+}
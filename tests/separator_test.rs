@@ -0,0 +1,19 @@
+use rusty_gui::{components::{Orientation, Rect, Spacer, StackPanel}, layout::Layout};
+
+
+/// Test that spacers push the children after them along a stack, without stretching
+#[test]
+fn spacer_test(){
+    let mut stack = StackPanel::new([0.0, 0.0], Orientation::Vertical, 0.0);
+    stack.push(Box::new(Spacer::new([40.0, 20.0])));
+    stack.push(Box::new(Spacer::new([0.0, 30.0])));
+
+    let mut layout = Layout::new();
+    let id = layout.add_event_component(Box::new(stack));
+    layout.arrange((800, 600));
+
+    let stack = layout.event_components[id].as_any_mut().downcast_mut::<StackPanel>().unwrap();
+    assert_eq!(stack.get_rect(), Rect::new([0.0, 0.0], [40.0, 50.0]));
+    let second = stack.borrow_child_mut(1).unwrap().as_any().downcast_ref::<Spacer>().unwrap();
+    assert_eq!(second.get_rect(), Rect::new([0.0, 10.0], [0.0, 30.0]));
+}
@@ -0,0 +1,67 @@
+use rusty_gui::components::{Label, TextEffect, TextEffects, TextGUIComponent};
+
+/// Test that a typewriter reveals characters over time, and can be skipped
+#[test]
+fn typewriter_test(){
+    let mut effects = TextEffects::new();
+    effects.add(TextEffect::Typewriter{ chars_per_second: 10.0 });
+    effects.restart(5);
+    assert!(effects.is_typing());
+    assert_eq!(effects.get_revealed(), 0);
+    assert_eq!(effects.color(0, [0.0, 0.0, 0.0, 1.0])[3], 0.0);
+
+    effects.update(0.25);
+    assert_eq!(effects.get_revealed(), 2);
+    assert!(effects.is_revealed(1));
+    assert!(!effects.is_revealed(2));
+
+    effects.update(10.0);
+    assert_eq!(effects.get_revealed(), 5);
+    assert!(!effects.is_typing());
+    // Nothing else is playing, so there's no need to keep redrawing
+    assert!(!effects.is_animating());
+
+    effects.retype(3);
+    assert!(effects.is_typing());
+    effects.finish_typing();
+    assert_eq!(effects.get_revealed(), 3);
+}
+
+/// Test that waves move characters, rainbows colour them, and plain text is left alone
+#[test]
+fn effect_style_test(){
+    let mut effects = TextEffects::new();
+    effects.restart(4);
+    assert_eq!(effects.offset(2), [0.0, 0.0]);
+    assert_eq!(effects.color(2, [0.1, 0.2, 0.3, 0.5]), [0.1, 0.2, 0.3, 0.5]);
+
+    effects.add(TextEffect::Wave{ amplitude: 4.0, speed: 1.0 });
+    effects.add(TextEffect::Rainbow{ speed: 1.0 });
+    assert!(effects.moves_glyphs());
+    assert!(effects.is_animating());
+    effects.update(0.3);
+    let offset = effects.offset(0);
+    assert_eq!(offset[0], 0.0);
+    assert!(offset[1].abs() <= 4.0 && offset[1] != 0.0);
+    // Neighbouring characters are at different points of the wave
+    assert!(effects.offset(1) != offset);
+    // Rainbows keep the text's alpha
+    assert_eq!(effects.color(0, [0.0, 0.0, 0.0, 0.5])[3], 0.5);
+}
+
+/// Test that labels type out new text, and only when it changes
+#[test]
+fn label_typewriter_test(){
+    let mut label = Label::new("Hello", 20.0, [0.0, 0.0]);
+    label.add_effect(TextEffect::Typewriter{ chars_per_second: 100.0 });
+    assert!(label.is_typing());
+    assert!(TextGUIComponent::is_animating(&label));
+    TextGUIComponent::update(&mut label, 1.0);
+    assert!(!label.is_typing());
+
+    label.set_text("Hello");
+    assert!(!label.is_typing());
+    label.set_text("Goodbye");
+    assert!(label.is_typing());
+    assert_eq!(label.get_effects().get_revealed(), 0);
+}
@@ -0,0 +1,39 @@
+use rusty_gui::{components::{EditResult, TextBuffer}, input::{Key, KeyEvent, Modifiers}};
+
+fn press(key: Key) -> KeyEvent{
+    KeyEvent{ key, physical: key, scancode: 0, pressed: true, repeat: false, modifiers: Modifiers::NONE }
+}
+
+/// Test typing and moving around, including characters longer than a byte
+#[test]
+fn text_buffer_test(){
+    let mut buffer = TextBuffer::new();
+    for character in "héllo".chars(){
+        buffer.insert(character);
+    }
+    assert_eq!(buffer.get_text(), "héllo");
+    assert_eq!(buffer.get_cursor(), "héllo".len());
+
+    // Move back over the "llo" and the "é", and delete the "h"
+    for _ in 0..4{
+        assert_eq!(buffer.handle_key(&press(Key::Left)), EditResult::Moved);
+    }
+    assert_eq!(buffer.get_cursor(), 1);
+    assert_eq!(buffer.handle_key(&press(Key::Backspace)), EditResult::Changed);
+    assert_eq!(buffer.get_text(), "éllo");
+    assert_eq!(buffer.handle_key(&press(Key::Backspace)), EditResult::Ignored);
+
+    assert_eq!(buffer.handle_key(&press(Key::Delete)), EditResult::Changed);
+    assert_eq!(buffer.get_text(), "llo");
+
+    buffer.handle_key(&press(Key::End));
+    buffer.insert('!');
+    assert_eq!(buffer.get_text(), "llo!");
+    assert_eq!(buffer.handle_key(&press(Key::Delete)), EditResult::Ignored);
+    assert_eq!(buffer.handle_key(&press(Key::Character('a'))), EditResult::Ignored);
+
+    // Cursors in the middle of a character get rounded down
+    buffer.set_text("é");
+    buffer.set_cursor(1);
+    assert_eq!(buffer.get_cursor(), 0);
+}
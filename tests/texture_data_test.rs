@@ -0,0 +1,70 @@
+use rusty_gui::rendering::TextureData;
+
+// A DDS file holding one 4x4 level in some FourCC format
+fn dds(four_cc: &[u8; 4], data: &[u8]) -> Vec<u8>{
+    let mut bytes = vec![0u8; 128];
+    bytes[..4].copy_from_slice(b"DDS ");
+    bytes[4..8].copy_from_slice(&124u32.to_le_bytes());
+    bytes[12..16].copy_from_slice(&4u32.to_le_bytes()); // height
+    bytes[16..20].copy_from_slice(&4u32.to_le_bytes()); // width
+    bytes[28..32].copy_from_slice(&1u32.to_le_bytes()); // mip levels
+    bytes[84..88].copy_from_slice(four_cc);
+    bytes.extend_from_slice(data);
+    bytes
+}
+
+/// Test that a BC1 block decodes to its end colours, and to transparent black in three colour mode
+#[test]
+fn bc1_decode_test(){
+    // Red and blue, with red picked for the first row, blue for the second and two thirds red for the rest
+    let block = [0x00, 0xF8, 0x1F, 0x00, 0x00, 0x55, 0xAA, 0xAA];
+    let data = TextureData::from_bytes(&dds(b"DXT1", &block)).unwrap();
+    assert!(data.is_compressed());
+    assert_eq!(data.size, (4, 4));
+    assert_eq!(data.get_memory_size(), 8);
+
+    let rgba = data.decode_rgba().unwrap();
+    assert_eq!(&rgba[0..4], &[255, 0, 0, 255]);
+    assert_eq!(&rgba[16..20], &[0, 0, 255, 255]);
+    assert_eq!(&rgba[32..36], &[170, 0, 85, 255]);
+
+    // With the first colour smaller, index 3 is transparent
+    let block = [0x1F, 0x00, 0x00, 0xF8, 0xFF, 0xFF, 0xFF, 0xFF];
+    let rgba = TextureData::from_bytes(&dds(b"DXT1", &block)).unwrap().decode_rgba().unwrap();
+    assert_eq!(&rgba[0..4], &[0, 0, 0, 0]);
+}
+
+/// Test that BC3 alpha gets interpolated between its end points
+#[test]
+fn bc3_alpha_test(){
+    let mut block = vec![255, 0, 0, 0, 0, 0, 0, 0];
+    // Every pixel uses alpha index 1, the second end point
+    block[2..8].copy_from_slice(&[0x49, 0x92, 0x24, 0x49, 0x92, 0x24]);
+    block.extend_from_slice(&[0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0]);
+    let rgba = TextureData::from_bytes(&dds(b"DXT5", &block)).unwrap().decode_rgba().unwrap();
+    assert!(rgba.chunks(4).all(|pixel| pixel == [255, 255, 255, 0]));
+}
+
+/// Test that KTX2 files are read, and bad files are turned away
+#[test]
+fn ktx2_test(){
+    let mut bytes = vec![0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A];
+    bytes.resize(104, 0);
+    bytes[12..16].copy_from_slice(&43u32.to_le_bytes()); // R8G8B8A8_SRGB
+    bytes[20..24].copy_from_slice(&1u32.to_le_bytes());
+    bytes[24..28].copy_from_slice(&1u32.to_le_bytes());
+    bytes[36..40].copy_from_slice(&1u32.to_le_bytes()); // faces
+    bytes[40..44].copy_from_slice(&1u32.to_le_bytes()); // levels
+    bytes[80..88].copy_from_slice(&104u64.to_le_bytes());
+    bytes[88..96].copy_from_slice(&4u64.to_le_bytes());
+    bytes.extend_from_slice(&[1, 2, 3, 4]);
+
+    let data = TextureData::from_bytes(&bytes).unwrap();
+    assert!(!data.is_compressed());
+    assert_eq!(data.format, wgpu::TextureFormat::Rgba8UnormSrgb);
+    assert_eq!(data.decode_rgba().unwrap(), vec![1, 2, 3, 4]);
+
+    bytes.truncate(106);
+    assert!(TextureData::from_bytes(&bytes).is_err());
+    assert!(TextureData::from_bytes(&dds(b"DXT1", &[0; 4])).is_err());
+}
@@ -0,0 +1,83 @@
+use rusty_gui::{rendering::TextRasterOptions, theme::{parse_color, Stylesheet}};
+
+const STYLE: &str = "
+# A small theme
+accent = #ff000080
+
+[button]
+color = 0.2, 0.4, 0.8
+text_size = 24
+
+[textures.button]
+nine_slice = 8, 8, 8, 8
+";
+
+/// Test that stylesheets are parsed into sections and properties
+#[test]
+fn stylesheet_test(){
+    let style = Stylesheet::parse(STYLE).unwrap();
+    assert_eq!(style.get("", "accent"), Some("#ff000080"));
+    assert_eq!(style.get_f32("button", "text_size"), Some(24.0));
+    assert_eq!(style.get_color("button", "color"), Some([0.2, 0.4, 0.8, 1.0]));
+    assert_eq!(style.get_f32_list("textures.button", "nine_slice"), Some(vec![8.0; 4]));
+    assert_eq!(style.get("button", "missing"), None);
+
+    assert!(Stylesheet::parse("[button").is_err());
+    assert!(Stylesheet::parse("no value").is_err());
+}
+
+/// Test that class sections are looked up with the last class winning
+#[test]
+fn class_style_test(){
+    let style = Stylesheet::parse("[.button]\ncolor = #0000ff\ntext_size = 20\n[.danger]\ncolor = #ff0000").unwrap();
+    assert_eq!(style.get_for_classes(&["button", "danger"], "color"), Some("#ff0000"));
+    assert_eq!(style.get_for_classes(&["button", "danger"], "text_size"), Some("20"));
+    assert_eq!(style.get_for_classes::<&str>(&[], "color"), None);
+}
+
+/// Test that colours parse from hex and from lists of numbers
+#[test]
+fn color_test(){
+    assert_eq!(parse_color("#ff0000"), Some([1.0, 0.0, 0.0, 1.0]));
+    assert_eq!(parse_color("#00ff0000"), Some([0.0, 1.0, 0.0, 0.0]));
+    assert_eq!(parse_color("0, 0, 1, 0.5"), Some([0.0, 0.0, 1.0, 0.5]));
+    assert_eq!(parse_color("#ff00"), None);
+    assert_eq!(parse_color("1, 2"), None);
+}
+
+/// Test that themes can set the text rasterization options, leaving out the ones they don't care about
+#[test]
+fn text_options_test(){
+    let style = Stylesheet::parse("[text]\nsubpixel_positioning = off\npixel_snapping = maybe\ngamma = 1.4").unwrap();
+    assert_eq!(style.get_bool("text", "subpixel_positioning"), Some(false));
+    assert_eq!(style.get_bool("text", "pixel_snapping"), None);
+
+    let options = TextRasterOptions::from_stylesheet(&style);
+    assert!(!options.subpixel_positioning);
+    assert_eq!(options.pixel_snapping, TextRasterOptions::default().pixel_snapping);
+    assert_eq!(options.gamma, 1.4);
+    assert_eq!(options.hinting, TextRasterOptions::default().hinting);
+}
+
+/// Test that embedded sources list and read their files
+#[test]
+fn embedded_source_test(){
+    use rusty_gui::rendering::{AssetSource, EmbeddedSource};
+    use std::path::Path;
+
+    let source = EmbeddedSource::new()
+        .with("theme.style", b"[button]\ncolor = #ffffff")
+        .with("./fonts/icons.ttf", b"font");
+
+    assert_eq!(source.read(Path::new("fonts/icons.ttf")).unwrap(), b"font".to_vec());
+    assert!(source.read(Path::new("fonts/missing.ttf")).is_err());
+    assert_eq!(source.list(Path::new("fonts")), vec![Path::new("fonts/icons.ttf").to_path_buf()]);
+    assert_eq!(source.list(Path::new("")).len(), 2);
+}
+
+/// Test that the embed macro includes files relative to the manifest
+#[test]
+fn embed_assets_test(){
+    let source = rusty_gui::embed_assets!("Cargo.toml");
+    assert!(!source.get("Cargo.toml").unwrap().is_empty());
+}